@@ -0,0 +1,283 @@
+//! Baseline numbers for the loader, the software renderer's passes and
+//! the sim's XY movement, all on E1M1.
+//!
+//! `cargo bench --features bench`; without `assets/doom.wad` every group
+//! is skipped.  Setup (reading the WAD, loading the map, spawning the
+//! actors) happens once per group; the timed loops don't allocate beyond
+//! what the code under test does itself.
+//!
+//! The `filtering` group times the wall and plane passes under each
+//! [`Filtering`] mode; `nearest` should match `wall_pass` + `plane_pass`
+//! from before bilinear filtering existed.
+//!
+//! The loader group also prints how many allocations a load makes, into
+//! a fresh bank and into one that already holds the map's textures; a
+//! counting global allocator keeps the tally for every group.
+//!
+//! Follow-ups noticed while writing these, not done here:
+//! - `load_level` decodes every sprite in the WAD up front; a second map
+//!   reuses them, the first pays for all of them.
+//! - `xy_movement_system` gathers its movers into a `SmallVec<[_; 16]>`,
+//!   which spills to the heap every tic once more than 16 things move.
+//! - `draw_level` times its own passes with `Instant::now()` three times a
+//!   frame; cheap, but it shows up at tiny resolutions.
+//! - `render_to_buffer` builds a fresh `Software` per call, so it can't
+//!   be used for steady-state numbers; the benches keep one renderer.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use criterion::{BenchmarkId, Criterion, black_box};
+use glam::Vec2;
+use yadoom_rs::{
+    defs,
+    renderer::{Filtering, Renderer, Software},
+    sim::{TicRunner, Velocity},
+    wad::{Wad, load_level},
+    world::{Camera, Level, SubsectorId, TextureBank},
+};
+
+/// Output sizes the renderer is timed at.
+const SIZES: [(usize, usize); 2] = [(640, 400), (1920, 1080)];
+
+/// Actors moving in the XY-movement bench.
+const ACTORS: usize = 500;
+
+/// Allocations made so far, by every thread.
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static COUNTING: CountingAlloc = CountingAlloc;
+
+/// Allocations `f` makes.
+fn count_allocs<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCS.load(Ordering::Relaxed) - before
+}
+
+fn doom_wad() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join("doom.wad")
+}
+
+/// E1M1 with its things spawned, ready to render.
+struct Map {
+    level: Level,
+    bank: TextureBank,
+    sim: TicRunner,
+}
+
+fn load_e1m1(wad: &Wad) -> Map {
+    let mut bank = TextureBank::default_with_checker();
+    let mut level = load_level(wad, wad.level_indices()[0], &mut bank).unwrap();
+    level.finalise_bsp();
+    let mut sim = TicRunner::new(&level);
+    for thing in &level.things {
+        if let Some(info) = defs::by_doomednum(thing.type_id) {
+            sim.spawn_mobj(
+                &level,
+                info,
+                thing.pos.x,
+                thing.pos.y,
+                thing.angle,
+                thing.sub_sector,
+            );
+        }
+    }
+    Map { level, bank, sim }
+}
+
+/// The player start looking east, north and west.
+fn cameras(level: &Level) -> [(&'static str, Camera); 3] {
+    let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+    let eye = start.pos.extend(level.floor_height_at(start.pos) + 41.0);
+    let at = |yaw: f32| Camera::new(eye, yaw.to_radians(), 90_f32.to_radians());
+    [
+        ("start_east", at(0.0)),
+        ("start_north", at(90.0)),
+        ("start_west", at(180.0)),
+    ]
+}
+
+fn bench_loader(c: &mut Criterion, wad: &Wad) {
+    let marker = wad.level_indices()[0];
+    let mut bank = TextureBank::default_with_checker();
+    let cold = count_allocs(|| load_level(wad, marker, &mut bank).unwrap());
+    let warm = count_allocs(|| load_level(wad, marker, &mut bank).unwrap());
+    eprintln!("load_e1m1: {cold} allocations into a fresh bank, {warm} into a warm one");
+
+    c.bench_function("load_e1m1", |b| {
+        b.iter(|| {
+            let mut bank = TextureBank::default_with_checker();
+            black_box(load_level(wad, marker, &mut bank).unwrap())
+        })
+    });
+}
+
+fn bench_bsp_walk(c: &mut Criterion, map: &Map) {
+    let mut group = c.benchmark_group("fill_active_subsectors");
+    let mut active: Vec<SubsectorId> = Vec::with_capacity(map.level.subsectors.len());
+    for (name, camera) in cameras(&map.level) {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                map.level.fill_active_subsectors(&camera, &mut active);
+                black_box(active.len())
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A whole `draw_level` per iteration, plus each pass on its own as
+/// `draw_level` times it – the plane pass is `flush_planes` alone.
+fn bench_draw_level(c: &mut Criterion, map: &Map) {
+    type Pass = fn(&yadoom_rs::renderer::RenderStats) -> Duration;
+    let passes: [(&str, Pass); 4] = [
+        ("draw_level", |s| s.wall_time + s.plane_time + s.sprite_time),
+        ("wall_pass", |s| s.wall_time),
+        ("plane_pass", |s| s.plane_time),
+        ("sprite_pass", |s| s.sprite_time),
+    ];
+    let mut active: Vec<SubsectorId> = Vec::with_capacity(map.level.subsectors.len());
+    let mut sw = Software::default();
+
+    for (pass, time) in passes {
+        let mut group = c.benchmark_group(pass);
+        for (w, h) in SIZES {
+            for (name, camera) in cameras(&map.level) {
+                map.level.fill_active_subsectors(&camera, &mut active);
+                // one untimed frame grows every buffer to size
+                sw.begin_frame(w, h);
+                sw.draw_level(&active, &map.level, &map.sim, &camera, &map.bank);
+
+                let id = BenchmarkId::new(name, format!("{w}x{h}"));
+                group.bench_function(id, |b| {
+                    b.iter_custom(|iters| {
+                        let mut total = Duration::ZERO;
+                        for _ in 0..iters {
+                            sw.begin_frame(w, h);
+                            sw.draw_level(&active, &map.level, &map.sim, &camera, &map.bank);
+                            total += time(sw.stats());
+                        }
+                        total
+                    })
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+/// Walls and flats (the passes filtering touches) at 640×400, looking
+/// down the start room, nearest against bilinear.
+fn bench_filtering(c: &mut Criterion, map: &Map) {
+    let (w, h) = SIZES[0];
+    let (_, camera) = &cameras(&map.level)[1];
+    let mut active: Vec<SubsectorId> = Vec::with_capacity(map.level.subsectors.len());
+    map.level.fill_active_subsectors(camera, &mut active);
+    let mut sw = Software::default();
+
+    let mut group = c.benchmark_group("filtering");
+    for (name, filtering) in [
+        ("nearest", Filtering::Nearest),
+        ("bilinear", Filtering::Bilinear),
+    ] {
+        sw.set_filtering(filtering);
+        sw.begin_frame(w, h);
+        sw.draw_level(&active, &map.level, &map.sim, camera, &map.bank);
+        group.bench_function(name, |b| {
+            b.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    sw.begin_frame(w, h);
+                    sw.draw_level(&active, &map.level, &map.sim, camera, &map.bank);
+                    total += sw.stats().wall_time + sw.stats().plane_time;
+                }
+                total
+            })
+        });
+    }
+    group.finish();
+}
+
+/// `ACTORS` imps scattered around E1M1's things, each shoved in a fixed
+/// pseudo-random direction before every timed tic.
+fn bench_xy_movement(c: &mut Criterion, wad: &Wad) {
+    let mut bank = TextureBank::default_with_checker();
+    let mut level = load_level(wad, wad.level_indices()[0], &mut bank).unwrap();
+    level.finalise_bsp();
+    let mut sim = TicRunner::new(&level);
+    let imp = defs::by_id("TROOP").unwrap();
+
+    let mut seed = 0x2545_f491_u32;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as f32 / u32::MAX as f32
+    };
+    let mut actors = Vec::with_capacity(ACTORS);
+    for i in 0..ACTORS {
+        let spot = level.things[i % level.things.len()].pos;
+        let pos = spot + Vec2::new(next() - 0.5, next() - 0.5) * 48.0;
+        let ss = level.locate_subsector(pos);
+        let ent = sim.spawn_mobj(&level, imp, pos.x, pos.y, 0.0, ss);
+        let push = Vec2::from_angle(next() * std::f32::consts::TAU) * imp.speed as f32;
+        actors.push((ent, push.extend(0.0)));
+    }
+
+    c.bench_function("xy_movement_500", |b| {
+        b.iter_custom(|iters| {
+            let mut total = Duration::ZERO;
+            for _ in 0..iters {
+                for &(ent, push) in &actors {
+                    if let Ok(mut vel) = sim.world_mut().get::<&mut Velocity>(ent) {
+                        vel.0 = push;
+                    }
+                }
+                let t = Instant::now();
+                sim.run_xy_movement(&mut level);
+                total += t.elapsed();
+                sim.drain_events().for_each(drop);
+            }
+            total
+        })
+    });
+}
+
+fn main() {
+    let path = doom_wad();
+    let Ok(bytes) = std::fs::read(&path) else {
+        eprintln!("skipping benches: {} not found", path.display());
+        return;
+    };
+    let wad = Wad::from_bytes(bytes).unwrap();
+    let map = load_e1m1(&wad);
+
+    let mut c = Criterion::default().configure_from_args();
+    bench_loader(&mut c, &wad);
+    bench_bsp_walk(&mut c, &map);
+    bench_draw_level(&mut c, &map);
+    bench_filtering(&mut c, &map);
+    bench_xy_movement(&mut c, &wad);
+    c.final_summary();
+}
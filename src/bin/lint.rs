@@ -0,0 +1,89 @@
+//! Load every map in a WAD the way the game does and report what's in it
+//! and what's wrong with it.
+//!
+//! ```text
+//! cargo run --bin yadoom-lint -- doom.wad
+//! ```
+//!
+//! Maps load forgivingly: repaired defects, things of unknown type and
+//! texture names the WAD lacks are reported, not fatal.  The exit code is
+//! non-zero only if some map doesn't load at all.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+
+use yadoom_rs::{
+    wad::{LoadOptions, NodeFormat, Wad, load_level_report},
+    world::TextureBank,
+};
+
+/// CLI options handled via `clap` derive.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Opts {
+    /// Path to an IWAD or PWAD
+    wad: PathBuf,
+}
+
+fn main() -> ExitCode {
+    let opts = Opts::parse();
+    let wad = match Wad::from_file(&opts.wad) {
+        Ok(wad) => wad,
+        Err(e) => {
+            eprintln!("error: can't open {}: {e}", opts.wad.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut bank = TextureBank::default_with_checker();
+    let mut failed = 0;
+    let markers = wad.level_indices();
+    for &marker in &markers {
+        let name = Wad::lump_name_str(&wad.lumps()[marker].name);
+        let (level, report) =
+            match load_level_report(&wad, marker, &mut bank, LoadOptions::default()) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    println!("{name}: FAILED: {e}");
+                    failed += 1;
+                    continue;
+                }
+            };
+
+        let nodes = match report.node_format {
+            NodeFormat::Vanilla => "vanilla nodes",
+            NodeFormat::DeePBsp => "DeePBSP nodes",
+            NodeFormat::Extended => "extended (XNOD) nodes",
+            NodeFormat::Missing => "no nodes (built on load)",
+        };
+        println!(
+            "{name}: {} things, {} linedefs, {} sidedefs, {} sectors, {} segs; {nodes}",
+            level.things.len(),
+            level.linedefs.len(),
+            level.sidedefs.len(),
+            level.sectors.len(),
+            level.segs.len(),
+        );
+        for defect in &report.defects {
+            println!("  {defect}");
+        }
+        for &i in &report.unknown_things {
+            println!("  thing {i}: unknown type {}", level.things[i].type_id);
+        }
+        for tex in &report.missing_textures {
+            println!("  missing texture {tex}");
+        }
+    }
+
+    println!(
+        "{} maps, {} loaded, {failed} failed",
+        markers.len(),
+        markers.len() - failed
+    );
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
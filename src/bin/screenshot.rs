@@ -0,0 +1,129 @@
+//! Render a single frame without opening a window and save it as PNG.
+//!
+//! ```text
+//! cargo run --features screenshot --bin yadoom-screenshot -- \
+//!     doom.wad 0 --pos 1056 -3616 41 --yaw 90 -o e1m1.png
+//! ```
+
+use clap::Parser;
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use yadoom_rs::{
+    game::VIEW_HEIGHT,
+    renderer::render_to_buffer,
+    sim::TicRunner,
+    wad::{Wad, load_level},
+    world::{Angle, Camera, TextureBank},
+};
+
+/// CLI options handled via `clap` derive.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Opts {
+    /// Path to an IWAD
+    wad: PathBuf,
+
+    /// Index of the map inside the WAD
+    #[arg(default_value_t = 0)]
+    map: usize,
+
+    /// Camera position (defaults to the player start at eye height)
+    #[arg(long, num_args = 3, value_names = ["X", "Y", "Z"], allow_negative_numbers = true)]
+    pos: Option<Vec<f32>>,
+
+    /// Camera heading in degrees (0 = east, counter-clockwise; defaults to
+    /// the player start's, or east without one)
+    #[arg(long, allow_negative_numbers = true)]
+    yaw: Option<f32>,
+
+    /// Horizontal field of view in degrees
+    #[arg(long, default_value_t = 90.0)]
+    fov: f32,
+
+    #[arg(long, default_value_t = 640)]
+    width: usize,
+
+    #[arg(long, default_value_t = 400)]
+    height: usize,
+
+    /// Output PNG
+    #[arg(short, long, value_name = "FILE", default_value = "screenshot.png")]
+    out: PathBuf,
+}
+
+fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+    let wad = Wad::from_file(&opts.wad)?;
+
+    let mut bank = TextureBank::default_with_checker();
+    let levels = wad.level_indices();
+    let &marker = levels.get(opts.map).ok_or_else(|| {
+        anyhow::anyhow!(
+            "no map {} in {}: it has {}",
+            opts.map,
+            opts.wad.display(),
+            levels.len()
+        )
+    })?;
+    let mut level = load_level(&wad, marker, &mut bank)?;
+    level.finalise_bsp();
+
+    let mut sim = TicRunner::new(&level);
+    for thing in &level.things {
+        if let Some(info) = yadoom_rs::defs::by_doomednum(thing.type_id) {
+            sim.spawn_mobj(
+                &level,
+                info,
+                thing.pos.x,
+                thing.pos.y,
+                thing.angle,
+                thing.sub_sector,
+            );
+        }
+    }
+
+    // only needed for what `--pos` / `--yaw` leave out
+    let start = level.things.iter().find(|t| t.type_id == 1);
+
+    let pos = match opts.pos.as_deref() {
+        Some(&[x, y, z]) => glam::vec3(x, y, z),
+        _ => {
+            let start =
+                start.ok_or_else(|| anyhow::anyhow!("no player start in map; pass --pos"))?;
+            let ss = level.locate_subsector(start.pos);
+            let sector = &level.sectors[level.subsectors[ss as usize].sector as usize];
+            start.pos.extend(sector.floor_h + VIEW_HEIGHT)
+        }
+    };
+    let yaw = match opts.yaw {
+        Some(degrees) => Angle::from_degrees(degrees),
+        None => start.map_or(Angle::ZERO, |s| s.angle),
+    };
+    let camera = Camera::new(pos, yaw, opts.fov.to_radians());
+
+    let fb = render_to_buffer(&level, &camera, &sim, &bank, opts.width, opts.height);
+
+    let rgb: Vec<u8> = fb
+        .iter()
+        .flat_map(|&p| [(p >> 16) as u8, (p >> 8) as u8, p as u8])
+        .collect();
+
+    let file = BufWriter::new(File::create(&opts.out)?);
+    let mut enc = png::Encoder::new(file, opts.width as u32, opts.height as u32);
+    enc.set_color(png::ColorType::Rgb);
+    enc.set_depth(png::BitDepth::Eight);
+    enc.write_header()?.write_image_data(&rgb)?;
+
+    println!(
+        "{}: {}×{} from ({:.0}, {:.0}, {:.0}) yaw {:.0}° → {}",
+        level.name,
+        opts.width,
+        opts.height,
+        pos.x,
+        pos.y,
+        pos.z,
+        yaw.degrees(),
+        opts.out.display()
+    );
+    Ok(())
+}
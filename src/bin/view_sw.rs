@@ -1,176 +1,955 @@
-use minifb::{Key, KeyRepeat, Window, WindowOptions};
-use std::time::{Duration, Instant};
+use clap::{Parser, ValueEnum};
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window, WindowOptions};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use yadoom_rs::{
-    renderer::{Renderer, Software},
-    sim::player_input,
-    sim::{Angle, InputCmd, Position, TicRunner},
-    wad::{Wad, load_level},
-    world::{Camera, SubsectorId, TextureBank},
+    Game, GameConfig,
+    console::{Console, HUD_FONT, WHITE, text_scale},
+    engine::{GameLoop, run_timedemo},
+    game::{GameState, Phase},
+    input::{Action, Bindings, Control, InputCollector, RENDER_SCALES, Settings},
+    menu::{Menu, MenuEvent, MenuKey, darken},
+    renderer::{
+        LightMode, RenderStats, Renderer, Rgba, SCREENBLOCKS_MAX, SCREENBLOCKS_MIN, Software,
+        ViewWindow,
+        capture::{FrameInfo, FrameWriter},
+        overlay::Canvas,
+        presentation::{Presenter, VideoMode},
+        wipe::Wipe,
+    },
+    screens::{ViewBorder, endoom_text},
+    sim::demo::{DemoHeader, DemoPlayer, DemoRecorder},
+    sim::{GameRules, InputCmd, InputSource, PhysicsConfig, Rng, Skill, TicRunner},
+    wad::preload_all_textures,
+    world::{
+        Camera, CameraMode, FontRef, LineSet, PaletteEffect, SubsectorId, Texture, TextureBank,
+    },
 };
 
 const W: usize = 1280;
 const H: usize = 800;
-const PLAYER_HEIGHT: f32 = 41.0;
+const QUICKSAVE: &str = "yadoom.sav";
+/// Bindings and menu settings, unless `--bindings` names another file.
+const CONFIG: &str = "yadoom.ini";
+/// The menu runs at the sim's rate while the sim is paused under it.
+const MENU_TIC: Duration = Duration::from_nanos(1_000_000_000 / 35);
+/// F12 screenshots go to the working directory as `yadoom000000.png`, ….
+const SCREENSHOT_PREFIX: &str = "yadoom";
 
-fn main() -> anyhow::Result<()> {
-    let mut args = std::env::args().skip(1);
-    let wad_path = args.next().expect("usage: view_sw <doom.wad>");
-    let map_idx: usize = args.next().unwrap_or_else(|| "0".into()).parse()?;
-    let wad = Wad::from_file(&wad_path)?;
-
-    let mut texture_bank = TextureBank::default_with_checker();
-    let mut level = load_level(&wad, wad.level_indices()[map_idx], &mut texture_bank)?;
-    level.finalise_bsp();
-
-    let mut sim = TicRunner::new(&level);
-
-    for thing in &level.things {
-        if let Some(info) = yadoom_rs::defs::by_doomednum(thing.type_id) {
-            sim.spawn_mobj(
-                &level,
-                info,
-                thing.pos.x,
-                thing.pos.y,
-                thing.angle,
-                thing.sub_sector,
-            );
+/// CLI options handled via `clap` derive.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Opts {
+    /// Path to an IWAD
+    wad: PathBuf,
+
+    /// Map to start (`E1M1`, `MAP07`…); the title screens come first
+    /// (and start the first map) if unset
+    map: Option<String>,
+
+    /// PWADs to load on top of the IWAD, later ones winning
+    #[arg(long, value_name = "PWAD", num_args = 1..)]
+    file: Vec<PathBuf>,
+
+    /// DeHackEd patches to apply, after any `DEHACKED` lump in the WADs
+    #[arg(long, value_name = "FILE", num_args = 1..)]
+    deh: Vec<PathBuf>,
+
+    /// Skill level 1‥5
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(1..=5))]
+    skill: u8,
+
+    /// Monsters fast as on Nightmare, whatever the skill (demos don't
+    /// store it, so not while recording or playing one)
+    #[arg(long, conflicts_with_all = ["record", "playdemo", "timedemo"])]
+    fast: bool,
+
+    /// Dead monsters come back as on Nightmare, whatever the skill
+    #[arg(long, conflicts_with_all = ["record", "playdemo", "timedemo"])]
+    respawn: bool,
+
+    /// Record every tic's input and save it here on exit
+    #[arg(long, value_name = "FILE", conflicts_with = "playdemo")]
+    record: Option<PathBuf>,
+
+    /// Play back a demo recorded with `--record`
+    #[arg(long, value_name = "FILE")]
+    playdemo: Option<PathBuf>,
+
+    /// Play a demo back flat out without a window, drawing every tic
+    /// once at the `--video-mode` frame size, and print how long it took
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["record", "playdemo"])]
+    timedemo: Option<PathBuf>,
+
+    /// With `--timedemo`, print the report as JSON
+    #[arg(long, requires = "timedemo")]
+    json: bool,
+
+    /// Compose every texture up front instead of on first use
+    #[arg(long)]
+    preload_all: bool,
+
+    /// Blend things between tics instead of drawing them where the last
+    /// tic left them
+    #[arg(long, alias = "uncapped")]
+    interpolate: bool,
+
+    /// Key bindings file (`action = key, …`), which also keeps the menu
+    /// settings; `yadoom.ini` if unset, the vanilla layout if that's missing
+    #[arg(long, value_name = "FILE")]
+    bindings: Option<PathBuf>,
+
+    /// Physics file (`key = value`); vanilla's constants if unset
+    #[arg(long, value_name = "FILE")]
+    physics: Option<PathBuf>,
+
+    /// Rendering backend
+    #[arg(long, value_enum, default_value_t = Backend::Software)]
+    renderer: Backend,
+
+    /// Two players sharing the screen, top and bottom; player 2 plays on
+    /// the left side of the keyboard (WASD)
+    #[arg(long, conflicts_with_all = ["record", "playdemo"])]
+    split: bool,
+
+    /// How frames fill the window: `crispy` (320×200 blown up to 4:3,
+    /// black bars round it), `native` or `scaled:<percent>`; the menu's
+    /// render scale if unset
+    #[arg(long, value_name = "MODE")]
+    video_mode: Option<VideoMode>,
+
+    /// Save frames as numbered PNGs in this directory, exactly as the
+    /// window shows them; needs the `png` cargo feature
+    #[arg(long, value_name = "DIR")]
+    dump_frames: Option<PathBuf>,
+
+    /// With `--dump-frames`, save only every Nth frame
+    #[arg(long, value_name = "N", default_value_t = 1, requires = "dump_frames",
+          value_parser = clap::value_parser!(u32).range(1..))]
+    dump_every: u32,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Backend {
+    Software,
+    /// Needs the `wgpu` cargo feature
+    Wgpu,
+}
+
+/// The selected renderer; `Renderer` isn't object-safe, so dispatch by hand.
+enum AnyRenderer {
+    Software(Box<Software>),
+    #[cfg(feature = "wgpu")]
+    Wgpu(Box<yadoom_rs::renderer::wgpu::WgpuRenderer>),
+}
+
+impl AnyRenderer {
+    fn new(backend: Backend, bank: &TextureBank) -> anyhow::Result<Self> {
+        match backend {
+            Backend::Software => Ok(Self::Software(Box::default())),
+            #[cfg(feature = "wgpu")]
+            Backend::Wgpu => {
+                let mut r = yadoom_rs::renderer::wgpu::WgpuRenderer::new()?;
+                r.upload_textures(bank)?;
+                Ok(Self::Wgpu(Box::new(r)))
+            }
+            #[cfg(not(feature = "wgpu"))]
+            Backend::Wgpu => {
+                let _ = bank;
+                anyhow::bail!("built without the `wgpu` feature")
+            }
+        }
+    }
+
+    /// Only the software renderer tints the screen so far.
+    fn set_palette_effect(&mut self, effect: PaletteEffect) {
+        match self {
+            Self::Software(sw) => sw.set_palette_effect(effect),
+            #[cfg(feature = "wgpu")]
+            Self::Wgpu(_) => {}
         }
     }
 
-    println!("Doom level: {}", level.name);
+    /// Lighting overrides are a software renderer debug aid.
+    fn set_light_mode(&mut self, mode: LightMode) {
+        match self {
+            Self::Software(sw) => sw.set_light_mode(mode),
+            #[cfg(feature = "wgpu")]
+            Self::Wgpu(_) => {}
+        }
+    }
 
-    let player_thing = level
-        .things
-        .iter()
-        .find(|t| t.type_id == 1)
-        .expect("no player start in map");
+    /// Step to the next of `RENDER_SCALES`; the GPU always draws at
+    /// window size.
+    fn cycle_render_scale(&mut self) {
+        match self {
+            Self::Software(sw) => {
+                let next = RENDER_SCALES
+                    .iter()
+                    .position(|&s| s == sw.render_scale())
+                    .map_or(1.0, |i| RENDER_SCALES[(i + 1) % RENDER_SCALES.len()]);
+                sw.set_render_scale(next);
+            }
+            #[cfg(feature = "wgpu")]
+            Self::Wgpu(_) => {}
+        }
+    }
 
-    let player_ent = sim.spawn_mobj(
-        &level,
-        yadoom_rs::defs::by_id("PLAYER").unwrap(),
-        player_thing.pos.x,
-        player_thing.pos.y,
-        player_thing.angle,
-        player_thing.sub_sector,
-    );
+    fn set_render_scale(&mut self, scale: f32) {
+        match self {
+            Self::Software(sw) => sw.set_render_scale(scale),
+            #[cfg(feature = "wgpu")]
+            Self::Wgpu(_) => {}
+        }
+    }
+
+    fn render_scale(&self) -> f32 {
+        match self {
+            Self::Software(sw) => sw.render_scale(),
+            #[cfg(feature = "wgpu")]
+            Self::Wgpu(_) => 1.0,
+        }
+    }
+}
+
+macro_rules! dispatch {
+    ($self:ident, $r:ident => $e:expr) => {
+        match $self {
+            AnyRenderer::Software($r) => $e,
+            #[cfg(feature = "wgpu")]
+            AnyRenderer::Wgpu($r) => $e,
+        }
+    };
+}
+
+impl Renderer for AnyRenderer {
+    fn begin_view(&mut self, w: usize, h: usize, view: ViewWindow) {
+        dispatch!(self, r => r.begin_view(w, h, view))
+    }
+
+    fn draw_border(&mut self, border: &ViewBorder) {
+        dispatch!(self, r => r.draw_border(border))
+    }
+
+    fn draw_level(
+        &mut self,
+        subsectors: &[SubsectorId],
+        level: &yadoom_rs::world::Level,
+        sim: &TicRunner,
+        camera: &Camera,
+        texture_bank: &TextureBank,
+    ) {
+        dispatch!(self, r => r.draw_level(subsectors, level, sim, camera, texture_bank))
+    }
+
+    fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, col: u32) {
+        dispatch!(self, r => r.draw_line(x0, y0, x1, y1, col))
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, col: Rgba) {
+        dispatch!(self, r => r.fill_rect(x, y, w, h, col))
+    }
+
+    fn blit_patch(&mut self, patch: &Texture, x: i32, y: i32, shade: u8, bank: &TextureBank) {
+        dispatch!(self, r => r.blit_patch(patch, x, y, shade, bank))
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        dispatch!(self, r => r.draw_text(text, x, y, font, col))
+    }
+
+    fn mark_seen(&self, level: &yadoom_rs::world::Level, seen: &mut LineSet) {
+        dispatch!(self, r => r.mark_seen(level, seen))
+    }
+
+    fn stats(&self) -> &RenderStats {
+        dispatch!(self, r => r.stats())
+    }
+
+    fn end_frame<F>(&mut self, submit: F)
+    where
+        F: FnOnce(&[Rgba], usize, usize),
+    {
+        dispatch!(self, r => r.end_frame(submit))
+    }
+}
+
+/// Saves what goes on the window: the next frame after F12, and every
+/// `every`th frame with `--dump-frames`.
+#[derive(Default)]
+struct Capture {
+    dump: Option<FrameWriter>,
+    every: u32,
+    frames: u32,
+    screenshot: bool,
+    shots: Option<FrameWriter>,
+    /// The map and tic the frames show.
+    map: String,
+    gametic: u64,
+}
+
+impl Capture {
+    fn frame(&mut self, fb: &[Rgba], w: usize, h: usize) {
+        let info = FrameInfo {
+            map: &self.map,
+            gametic: self.gametic,
+        };
+        if std::mem::take(&mut self.screenshot) {
+            let shots = match self.shots.take() {
+                Some(shots) => Ok(shots),
+                None => FrameWriter::new(".", SCREENSHOT_PREFIX),
+            };
+            match shots.and_then(|mut shots| Ok((shots.write(fb, w, h, info)?, shots))) {
+                Ok((path, shots)) => {
+                    println!("screenshot {}", path.display());
+                    self.shots = Some(shots);
+                }
+                Err(e) => eprintln!("warning: screenshot failed: {e}"),
+            }
+        }
+        if let Some(dump) = &mut self.dump {
+            if self.frames.is_multiple_of(self.every) {
+                if let Err(e) = dump.write(fb, w, h, info) {
+                    // one bad frame would only be followed by more
+                    eprintln!("warning: frame dump stopped: {e}");
+                    self.dump = None;
+                }
+            }
+            self.frames = self.frames.wrapping_add(1);
+        }
+    }
+
+    /// Wait for every queued frame to be written.
+    fn finish(&mut self) {
+        for writer in [self.dump.take(), self.shots.take()].into_iter().flatten() {
+            if let Err(e) = writer.finish() {
+                eprintln!("warning: {e}");
+            }
+        }
+    }
+}
+
+/// Puts frames on the window, melting the last one into the next scene
+/// while a wipe runs.  Frames smaller than the window are scaled up to it.
+#[derive(Default)]
+struct Screen {
+    presenter: Presenter,
+    capture: Capture,
+    /// The frame on the window, kept to melt from.
+    shown: Vec<Rgba>,
+    size: (usize, usize),
+    wipe: Option<(Wipe, Instant)>,
+    melted: Vec<Rgba>,
+}
+
+impl Screen {
+    /// Melt whatever is on the window into the frames that follow, the
+    /// columns' start delays seeded from `rng`.
+    fn start_wipe(&mut self, rng: Rng) {
+        let (w, h) = self.size;
+        if !self.shown.is_empty() {
+            self.wipe = Some((Wipe::new(self.shown.clone(), w, h, rng), Instant::now()));
+        }
+    }
 
-    let mut camera = Camera::new(
-        player_thing.pos.extend(41.0),
-        player_thing.angle,
-        90_f32.to_radians(),
+    /// A melt is running; the sim waits for it like vanilla's.
+    fn wiping(&self) -> bool {
+        self.wipe.is_some()
+    }
+
+    fn present(&mut self, win: &mut Window, fb: &[Rgba], w: usize, h: usize) -> minifb::Result<()> {
+        if let Some((wipe, clock)) = &mut self.wipe {
+            let mut tics = 0;
+            while clock.elapsed() >= MENU_TIC {
+                *clock += MENU_TIC;
+                tics += 1;
+            }
+            if (w, h) == self.size && !wipe.advance(tics) {
+                wipe.compose(fb, &mut self.melted);
+                let out = self.presenter.present(&self.melted, w, h, W, H);
+                self.capture.frame(out, W, H);
+                return win.update_with_buffer(out, W, H);
+            }
+            self.wipe = None;
+        }
+        self.shown.clear();
+        self.shown.extend_from_slice(fb);
+        self.size = (w, h);
+        let out = self.presenter.present(fb, w, h, W, H);
+        self.capture.frame(out, W, H);
+        win.update_with_buffer(out, W, H)
+    }
+}
+
+/// Character a key types into the console, if any.
+fn console_char(key: Key, shift: bool) -> Option<char> {
+    const LETTERS: [Key; 26] = [
+        Key::A,
+        Key::B,
+        Key::C,
+        Key::D,
+        Key::E,
+        Key::F,
+        Key::G,
+        Key::H,
+        Key::I,
+        Key::J,
+        Key::K,
+        Key::L,
+        Key::M,
+        Key::N,
+        Key::O,
+        Key::P,
+        Key::Q,
+        Key::R,
+        Key::S,
+        Key::T,
+        Key::U,
+        Key::V,
+        Key::W,
+        Key::X,
+        Key::Y,
+        Key::Z,
+    ];
+    const DIGITS: [Key; 10] = [
+        Key::Key0,
+        Key::Key1,
+        Key::Key2,
+        Key::Key3,
+        Key::Key4,
+        Key::Key5,
+        Key::Key6,
+        Key::Key7,
+        Key::Key8,
+        Key::Key9,
+    ];
+    if let Some(i) = LETTERS.iter().position(|&k| k == key) {
+        return Some((b'a' + i as u8) as char);
+    }
+    if let Some(i) = DIGITS.iter().position(|&k| k == key) {
+        return Some((b'0' + i as u8) as char);
+    }
+    match key {
+        Key::Space => Some(' '),
+        Key::Minus if shift => Some('_'),
+        Key::Minus => Some('-'),
+        Key::Period => Some('.'),
+        _ => None,
+    }
+}
+
+/// What a key does in the menu, if anything.
+fn menu_key(key: Key) -> Option<MenuKey> {
+    match key {
+        Key::Up => Some(MenuKey::Up),
+        Key::Down => Some(MenuKey::Down),
+        Key::Left => Some(MenuKey::Left),
+        Key::Right => Some(MenuKey::Right),
+        Key::Enter => Some(MenuKey::Enter),
+        Key::Escape | Key::Backspace => Some(MenuKey::Back),
+        _ => console_char(key, false).map(MenuKey::Char),
+    }
+}
+
+/// Is `c` held: one of `keys` (by their `Key` names) or a mouse button
+/// that's down?
+fn control_down(win: &Window, keys: &[String], c: &Control) -> bool {
+    match c {
+        Control::Key(_) => keys.iter().any(|k| c.is_key(k)),
+        Control::Mouse(n) => win.get_mouse_down(match n {
+            1 => MouseButton::Left,
+            2 => MouseButton::Right,
+            _ => MouseButton::Middle,
+        }),
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+
+    let mut demo = (opts.playdemo.as_ref())
+        .or(opts.timedemo.as_ref())
+        .map(DemoPlayer::from_file)
+        .transpose()?;
+
+    /* a demo pins the map, skill and RNG seed it was recorded with */
+    let (map, seed, skill) = match &demo {
+        Some(d) => (
+            Some(d.header().map.clone()),
+            d.header().seed,
+            d.header().skill,
+        ),
+        None => (opts.map.clone(), 0, opts.skill - 1),
+    };
+    let skill = Skill::from_index(skill).unwrap_or_default();
+
+    let mut game = Game::new(GameConfig {
+        wad_path: opts.wad.clone(),
+        pwads: opts.file.clone(),
+        skill,
+        rules: GameRules {
+            fast_monsters: opts.fast,
+            respawn_monsters: opts.respawn,
+            ..GameRules::VANILLA
+        },
+        map,
+        seed,
+        players: if opts.split { 2 } else { 1 },
+        deh: opts.deh.clone(),
+    })?;
+    if opts.preload_all {
+        let GameState { wad, bank, .. } = game.state_mut();
+        preload_all_textures(wad, bank)?;
+    }
+    if let (Some(_), Some(player)) = (&opts.timedemo, &mut demo) {
+        // stdout is the report's alone; `--video-mode` sets the frame size
+        let video = opts.video_mode.unwrap_or_default();
+        let (fw, fh) = video.frame_size(W, H);
+        let mut renderer = AnyRenderer::new(opts.renderer, game.textures())?;
+        renderer.set_render_scale(video.render_scale());
+        let report = run_timedemo(&mut game, player, &mut renderer, fw, fh);
+        if opts.json {
+            println!("{}", report.to_json());
+        } else {
+            println!("{report}");
+        }
+        return Ok(());
+    }
+    println!(
+        "textures: {} ({} KiB)",
+        game.textures().len(),
+        game.textures().memory_usage() / 1024
     );
 
-    let mut renderer = Software::default();
+    let mut recorder = opts
+        .record
+        .as_ref()
+        .map(|_| DemoRecorder::new(DemoHeader::new(&game.level().name, skill.index(), seed)));
+
+    if opts.map.is_none() && demo.is_none() {
+        game.state_mut().show_title();
+    } else {
+        println!("Doom level: {}", game.state().level_info().name);
+    }
+
+    let mut console = Console::new();
+    let config = opts.bindings.clone().unwrap_or_else(|| CONFIG.into());
+    let mut settings = Settings::default();
+    if opts.bindings.is_some() || config.exists() {
+        game.state_mut().bindings = Bindings::load(&config)?;
+        settings = Settings::load(&config)?;
+    }
+    if let Some(path) = &opts.physics {
+        let state = game.state_mut();
+        state.physics = PhysicsConfig::load(path)?;
+        state.sim.set_physics(state.physics);
+    }
+    // split screen: player 2's keys are taken from player 1
+    let bindings2 = Bindings::player_two();
+    if opts.split {
+        let state = game.state_mut();
+        state.bindings = std::mem::take(&mut state.bindings).without(&bindings2);
+    }
+    let mut input = InputCollector::default();
+    input.apply_settings(&settings);
+    let mut input2 = InputCollector::default();
+    let mut last_mouse = None;
+    // whether a mouse button bound to use or fire was down last frame,
+    // so holding one skips only one intermission stage
+    let mut clicking = false;
+
+    if let Some(mode) = opts.video_mode {
+        settings.render_scale = mode.render_scale();
+    }
+    let video = opts.video_mode.unwrap_or_default();
+    // the whole frame, HUD and menus too; `screen` scales it to the window
+    let (fw, fh) = video.frame_size(W, H);
+
+    let mut renderer = AnyRenderer::new(opts.renderer, game.textures())?;
+    renderer.set_render_scale(settings.render_scale);
+    game.state_mut().screenblocks = settings.screenblocks;
+    if demo.is_none() && recorder.is_none() {
+        // demos don't store it: they are recorded and played back aiming
+        let state = game.state_mut();
+        state.autoaim = settings.autoaim;
+        state.sim.set_autoaim(state.autoaim);
+    }
+    let mut menu = Menu::new(game.wad());
+    let mut menu_clock = Instant::now();
+    let mut quit = false;
 
     let mut win = Window::new("Rust Doom Software Render", W, H, WindowOptions::default())?;
-    win.set_target_fps(35);
+    // frames come as fast as the display takes them; `game_loop` paces the sim
+    win.set_target_fps(0);
+    // Pause freezes the sim (F8 steps it, [ and ] slow and speed it), and
+    // so does the open console
+    let mut game_loop = GameLoop::new();
 
     // ────────────────── benchmarking state ──────────────────────────────
     let mut acc_time = Duration::ZERO; // cumulated render time
     let mut acc_frames = 0usize; // frames in the current window
     let mut last_print = Instant::now(); // when we printed last
+    let mut stats_line = String::new(); // last report, for the overlay
 
-    let mut active_subsectors: Vec<SubsectorId> = Vec::new();
+    let mut overlay: Vec<Rgba> = Vec::new();
+    let mut screen = Screen {
+        presenter: Presenter::new(video),
+        ..Screen::default()
+    };
+    if let Some(dir) = &opts.dump_frames {
+        screen.capture.dump = Some(FrameWriter::new(dir, "frame")?);
+        screen.capture.every = opts.dump_every;
+    }
 
-    while win.is_open() && !win.is_key_down(Key::Escape) {
+    while win.is_open() && !quit {
         let t0 = Instant::now(); // ┌─ frame timer start
 
-        /* --------------- build one InputCmd per tic ----------------------- */
-        let mut cmd = InputCmd::default();
+        /* --------------- developer console -------------------------------- */
+        if win.is_key_pressed(Key::Backquote, KeyRepeat::No) {
+            console.toggle();
+        } else if console.is_open() {
+            let shift = win.is_key_down(Key::LeftShift) || win.is_key_down(Key::RightShift);
+            for key in win.get_keys_pressed(KeyRepeat::Yes) {
+                match key {
+                    Key::Enter => console.submit(game.state_mut()),
+                    Key::Backspace => console.backspace(),
+                    _ => {
+                        if let Some(c) = console_char(key, shift) {
+                            console.type_char(c);
+                        }
+                    }
+                }
+            }
+        }
 
-        /* movement --------------------------------------------------------- */
-        if win.is_key_down(Key::Up) || win.is_key_down(Key::W) {
-            cmd.forward += 1.0;
+        /* --------------- menu: Escape opens it, then it takes the keys ---- */
+        let was_active = menu.is_active();
+        if console.is_open() {
+            // the console has the keyboard
+        } else if menu.is_active() {
+            for key in win.get_keys_pressed(KeyRepeat::Yes) {
+                let Some(key) = menu_key(key) else {
+                    continue;
+                };
+                match menu.responder(key, &mut settings) {
+                    Some(MenuEvent::NewGame { map, skill }) => {
+                        game.state_mut().skill = skill;
+                        match game.change_map(&map) {
+                            Ok(()) => {
+                                game_loop.reset();
+                                println!(
+                                    "Doom level: {} ({skill:?})",
+                                    game.state().level_info().name
+                                );
+                            }
+                            Err(e) => eprintln!("can't start {map}: {e}"),
+                        }
+                    }
+                    Some(MenuEvent::Settings) => {
+                        input.apply_settings(&settings);
+                        renderer.set_render_scale(settings.render_scale);
+                        game.state_mut().screenblocks = settings.screenblocks;
+                        if let Err(e) = settings.save(&config) {
+                            eprintln!("can't save settings to {}: {e}", config.display());
+                        }
+                    }
+                    Some(MenuEvent::Quit) => quit = true,
+                    None => {}
+                }
+            }
+        } else if win.is_key_pressed(Key::Escape, KeyRepeat::No) {
+            menu.open();
         }
-        if win.is_key_down(Key::Down) || win.is_key_down(Key::S) {
-            cmd.forward -= 1.0;
+        while menu_clock.elapsed() >= MENU_TIC {
+            menu_clock += MENU_TIC;
+            menu.ticker();
         }
 
-        let alt = win.is_key_down(Key::LeftAlt) || win.is_key_down(Key::RightAlt);
-        if alt {
-            /* Alt + ←/→  = strafe */
-            if win.is_key_down(Key::Left) {
-                cmd.strafe -= 1.0;
+        if win.is_key_pressed(Key::Pause, KeyRepeat::No) {
+            game_loop.control.toggle_pause();
+        }
+        if win.is_key_pressed(Key::F12, KeyRepeat::No) {
+            screen.capture.screenshot = true;
+        }
+        screen.capture.map.clone_from(&game.level().name);
+        screen.capture.gametic = game_loop.gametic();
+        if std::mem::take(&mut game.state_mut().start_wipe) {
+            screen.start_wipe(*game.state().sim.rng());
+        }
+        game_loop.paused = console.is_open() || menu.is_active() || screen.wiping();
+        let frame = game_loop.frame();
+
+        /* --------------- title loop: any key brings up the menu ----------- */
+        let state = game.state_mut();
+        if let Phase::Title(title) = &mut state.phase {
+            for _ in 0..frame.tics {
+                title.ticker();
             }
-            if win.is_key_down(Key::Right) {
-                cmd.strafe += 1.0;
+            overlay.resize(fw * fh, 0);
+            title.draw(&mut overlay, fw, fh);
+            let open = !console.is_open()
+                && !was_active
+                && win
+                    .get_keys_pressed(KeyRepeat::No)
+                    .iter()
+                    .any(|&k| k != Key::Backquote);
+            if open && !menu.is_active() {
+                menu.open();
             }
-        } else {
-            /* plain ←/→   = turn   */
-            if win.is_key_down(Key::Left) {
-                cmd.turn += 1.0;
+            menu.draw(&settings, &mut overlay, fw, fh, &state.bank);
+            console.draw(state, &mut Canvas::new(&mut overlay, fw, fh), fw, fh);
+            screen.present(&mut win, &overlay, fw, fh)?;
+            continue;
+        }
+
+        /* --------------- intermission: use or fire skips ahead ------------ */
+        let skip_controls = || {
+            [Action::Use, Action::Fire]
+                .into_iter()
+                .flat_map(|a| state.bindings.get(a))
+        };
+        let was_clicking = clicking;
+        clicking = skip_controls()
+            .filter(|c| matches!(c, Control::Mouse(_)))
+            .any(|c| control_down(&win, &[], c));
+        if let Phase::Intermission(im) = &mut state.phase {
+            let pressed: Vec<String> = (win.get_keys_pressed(KeyRepeat::No).iter())
+                .map(|k| format!("{k:?}"))
+                .collect();
+            let skip = win.is_key_pressed(Key::Space, KeyRepeat::No)
+                || win.is_key_pressed(Key::Enter, KeyRepeat::No)
+                || (clicking && !was_clicking)
+                || skip_controls().any(|c| pressed.iter().any(|k| c.is_key(k)));
+            if skip && !console.is_open() && !menu.is_active() && !was_active {
+                im.accelerate();
+            }
+            // no audio backend yet: the count's sounds go unheard
+            for _ in 0..frame.tics {
+                im.ticker();
+            }
+            if im.is_done() {
+                match state.advance() {
+                    Ok(()) => {
+                        game_loop.reset();
+                        println!("Doom level: {}", state.level_info().name);
+                    }
+                    Err(e) => eprintln!("can't start the next map: {e}"),
+                }
             }
-            if win.is_key_down(Key::Right) {
-                cmd.turn -= 1.0;
+            if let Phase::Intermission(im) = &state.phase {
+                overlay.resize(fw * fh, 0);
+                im.draw(&mut overlay, fw, fh, &state.bank);
+                if menu.is_active() {
+                    darken(&mut overlay);
+                    menu.draw(&settings, &mut overlay, fw, fh, &state.bank);
+                }
+                console.draw(state, &mut Canvas::new(&mut overlay, fw, fh), fw, fh);
+                screen.present(&mut win, &overlay, fw, fh)?;
+                continue;
             }
         }
 
-        /* WASD strafing mirrors arrow-key strafing */
-        if win.is_key_down(Key::A) {
-            cmd.strafe -= 1.0;
-        }
-        if win.is_key_down(Key::D) {
-            cmd.strafe += 1.0;
-        }
+        /* --------------- sample the controls; the sim pulls one cmd a tic -- */
+        let mouse = win.get_mouse_pos(MouseMode::Pass);
+        let mouse_dx = match (mouse, last_mouse) {
+            (Some((x, _)), Some((lx, _))) => x - lx,
+            _ => 0.0,
+        };
+        last_mouse = mouse;
+
+        // the console and the menu swallow the keyboard: the player just
+        // stands there
+        if console.is_open() || menu.is_active() {
+            input.clear();
+            input2.clear();
+        } else {
+            let keys: Vec<String> = win.get_keys().iter().map(|k| format!("{k:?}")).collect();
+            let down = |c: &Control| control_down(&win, &keys, c);
+            input.update(&game.state().bindings, down, mouse_dx);
+            if opts.split {
+                input2.update(&bindings2, down, 0.0);
+            }
 
-        /* modifiers & actions --------------------------------------------- */
-        cmd.run = win.is_key_down(Key::LeftShift) || win.is_key_down(Key::RightShift);
-        cmd.fire = win.is_key_down(Key::LeftCtrl) || win.is_key_down(Key::RightCtrl);
-        cmd.use_act = win.is_key_pressed(Key::Space, KeyRepeat::No); // edge-trigger
+            if win.is_key_pressed(Key::F5, KeyRepeat::No) {
+                renderer.cycle_render_scale();
+                settings.render_scale = renderer.render_scale();
+                println!("render scale {:.0}%", settings.render_scale * 100.0);
+            }
 
-        const NUMBER_KEYS: [Key; 7] = [
-            Key::Key1,
-            Key::Key2,
-            Key::Key3,
-            Key::Key4,
-            Key::Key5,
-            Key::Key6,
-            Key::Key7,
-        ];
+            /* - / = shrink and grow the view, like vanilla ----------------- */
+            let resize = [(Key::Minus, -1), (Key::Equal, 1)]
+                .into_iter()
+                .find(|&(key, _)| win.is_key_pressed(key, KeyRepeat::No));
+            if let Some((_, step)) = resize {
+                let blocks = settings.screenblocks.saturating_add_signed(step);
+                settings.screenblocks = blocks.clamp(SCREENBLOCKS_MIN, SCREENBLOCKS_MAX);
+                game.state_mut().screenblocks = settings.screenblocks;
+            }
 
-        for (i, &key) in NUMBER_KEYS.iter().enumerate() {
-            if win.is_key_pressed(key, KeyRepeat::No) {
-                cmd.weapon = Some((i + 1) as u8);
-                break;
+            if win.is_key_pressed(Key::F8, KeyRepeat::No) {
+                game_loop.control.step();
+            }
+            if win.is_key_pressed(Key::LeftBracket, KeyRepeat::Yes) {
+                game_loop.control.slower();
+                println!("sim {}", game_loop.control);
+            }
+            if win.is_key_pressed(Key::RightBracket, KeyRepeat::Yes) {
+                game_loop.control.faster();
+                println!("sim {}", game_loop.control);
             }
-        }
 
-        /* send to ECS ------------------------------------------------------ */
-        player_input(sim.world_mut(), player_ent, cmd);
+            if win.is_key_pressed(Key::F7, KeyRepeat::No) {
+                let mode = game.camera_mode().next();
+                game.set_camera_mode(mode);
+                println!("camera {mode}");
+            }
 
-        sim.pump(&level);
+            if win.is_key_pressed(Key::F11, KeyRepeat::No) {
+                let state = game.state_mut();
+                state.light_mode = state.light_mode.next();
+                println!("light {}", state.light_mode);
+            }
 
-        if let Ok(mut q) = sim.world().query_one::<(&Position, &Angle)>(player_ent) {
-            if let Some((pos, ang)) = q.get() {
-                camera.pos.x = pos.0.x;
-                camera.pos.y = pos.0.y;
-                camera.pos.z = pos.1 + PLAYER_HEIGHT;
-                camera.yaw = ang.0;
+            /* quicksave / quickload ---------------------------------------- */
+            if win.is_key_pressed(Key::F6, KeyRepeat::No) {
+                match game.save_game(QUICKSAVE) {
+                    Ok(()) => println!("game saved to {QUICKSAVE}"),
+                    Err(e) => eprintln!("save failed: {e}"),
+                }
+            }
+            // a load would swap the world under a demo being played or
+            // recorded, which then desyncs
+            if demo.is_none() && recorder.is_none() && win.is_key_pressed(Key::F9, KeyRepeat::No) {
+                match game.load_game(QUICKSAVE) {
+                    Ok(()) => {
+                        game_loop.reset();
+                        println!("game loaded from {QUICKSAVE}");
+                    }
+                    Err(e) => eprintln!("load failed: {e}"),
+                }
             }
         }
 
-        // dbg!(camera);
+        /* send to ECS ------------------------------------------------------ */
+        // the free camera takes the controls; the player stands still.  It
+        // flies in real time, so it looks around a paused sim too
+        let mut idle = InputCmd::default();
+        let live: &mut dyn InputSource = if game.camera_mode() == CameraMode::Free {
+            game.fly_camera(&mut input, frame.real_tics);
+            &mut idle
+        } else {
+            &mut input
+        };
+        match (&mut demo, &mut recorder) {
+            (Some(player), _) => {
+                if player.is_finished() {
+                    break;
+                }
+                game.run_tics(player, frame.tics);
+            }
+            (None, Some(rec)) => {
+                game.run_tics(&mut rec.tee(live), frame.tics);
+            }
+            (None, None) if opts.split => {
+                game.run_player_tics(&mut [live, &mut input2], frame.tics);
+            }
+            (None, None) => {
+                game.run_tics(live, frame.tics);
+            }
+        }
+
+        // no audio backend yet – drop sound events so the queue stays empty
+        game.drain_events().for_each(drop);
+        if !matches!(game.phase(), Phase::Level) {
+            println!("{} finished", game.level().name);
+            continue;
+        }
 
         /* draw */
-        renderer.begin_frame(W, H);
-        level.fill_active_subsectors(&camera, &mut active_subsectors);
-        renderer.draw_level(&active_subsectors, &level, &sim, &camera, &texture_bank);
-        renderer.end_frame(|fb, w, h| {
+        if std::mem::take(&mut game.state_mut().start_wipe) {
+            screen.start_wipe(*game.state().sim.rng());
+        }
+        if opts.interpolate {
+            game.state_mut().sim.set_frame_alpha(frame.alpha);
+        }
+        renderer.set_palette_effect(game.palette_effect());
+        renderer.set_light_mode(game.state().light_mode);
+        if opts.split {
+            game.draw_split_frame(&mut renderer, fw, fh);
+        } else {
+            game.draw_frame(&mut renderer, fw, fh);
+        }
+        let bank = game.textures();
+        if game.state().show_stats {
+            if let Some(font) = bank.font(HUD_FONT) {
+                // bottom-right corner, one counter group per row
+                let font = font.scaled(text_scale(fh));
+                let line_h = font.line_height();
+                let lines = renderer.stats().lines();
+                let sim_line = format!("sim {}", game_loop.control);
+                let rows = [&sim_line, &stats_line].into_iter().chain(&lines);
+                let top = fh.saturating_sub((lines.len() + 3) * line_h);
+                for (i, line) in rows.enumerate() {
+                    let x = fw.saturating_sub(font.text_width(line) + 2 * font.scale);
+                    let y = top + i * line_h;
+                    renderer.draw_text(line, x as i32, y as i32, &font, WHITE);
+                }
+            }
+        }
+        // the menu goes on after the frame, and the console over that
+        let overlaid = menu.is_active();
+        if !overlaid {
+            console.draw(game.state(), &mut renderer, fw, fh);
+        }
+        let mut size = (fw, fh);
+        renderer.end_frame(|fb: &[Rgba], w, h| {
             // ─────────── accumulate & report every ~3 s ────────────────────
             acc_time += t0.elapsed();
             acc_frames += 1;
-            win.update_with_buffer(fb, w, h).unwrap()
+            if !overlaid {
+                return screen.present(&mut win, fb, w, h).unwrap();
+            }
+            overlay.clear();
+            overlay.extend_from_slice(fb);
+            size = (w, h);
         });
+        if win.is_key_pressed(Key::F10, KeyRepeat::No) {
+            println!("{}", renderer.stats());
+        }
+        if overlaid {
+            let (w, h) = size;
+            darken(&mut overlay);
+            menu.draw(&settings, &mut overlay, w, h, game.textures());
+            console.draw(game.state(), &mut Canvas::new(&mut overlay, w, h), w, h);
+            screen.present(&mut win, &overlay, w, h)?;
+        }
 
         if last_print.elapsed() >= Duration::from_secs(3) {
             let avg_ms = acc_time.as_secs_f64() * 1000.0 / acc_frames as f64;
-            let fps = 1000.0 / avg_ms;
-            println!("avg render: {:.2} ms  ({:.1} FPS)", avg_ms, fps);
+            stats_line = format!(
+                "avg render: {:.2} ms  {:.1} FPS  tic {}  scale {:.0}%",
+                avg_ms,
+                game_loop.fps(),
+                game_loop.gametic(),
+                renderer.render_scale() * 100.0
+            );
+            println!("{stats_line}");
             acc_time = Duration::ZERO;
             acc_frames = 0;
             last_print = Instant::now();
         }
     }
+
+    screen.capture.finish();
+    if let (Some(rec), Some(path)) = (&recorder, &opts.record) {
+        rec.save(path)?;
+        println!("recorded {} tics to {}", rec.len(), path.display());
+    }
+    if let Some(text) = endoom_text(game.wad()) {
+        print!("{text}");
+    }
     Ok(())
 }
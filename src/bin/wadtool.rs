@@ -0,0 +1,287 @@
+//! Dump WAD assets to files for debugging: lumps, textures, flats,
+//! sprites, palettes and whole maps.
+//!
+//! ```text
+//! cargo run --features png --bin yadoom-wadtool -- doom.wad list
+//! cargo run --features png --bin yadoom-wadtool -- doom.wad export-texture STARTAN3 startan3.png
+//! cargo run --features png --bin yadoom-wadtool -- doom.wad export-map E1M1 e1m1.svg
+//! ```
+//!
+//! Textures are composed by the same `WadTextures` source the engine
+//! uses, so a wrong picture here is a wrong picture in game.
+
+use anyhow::{Context, bail};
+use clap::{Parser, Subcommand};
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use yadoom_rs::{
+    defs,
+    wad::{Wad, WadTextures, load_flat, load_level, load_palettes, load_patch, patch_offsets},
+    world::{Level, LinedefFlags, Palette, Texture, TextureBank},
+};
+
+/// Pixels per palette entry in `export-palette`.
+const SWATCH: usize = 16;
+/// Margin around the map in `export-map`, in map units.
+const MAP_MARGIN: f32 = 64.0;
+
+/// CLI options handled via `clap` derive.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+struct Opts {
+    /// Path to an IWAD or PWAD
+    wad: PathBuf,
+
+    #[command(subcommand)]
+    cmd: Cmd,
+}
+
+#[derive(Subcommand, Debug)]
+enum Cmd {
+    /// Every lump with its size and what it looks like
+    List,
+    /// A wall texture from TEXTURE1/2, composed from its patches
+    ExportTexture { name: String, out: PathBuf },
+    /// A 64×64 flat
+    ExportFlat { name: String, out: PathBuf },
+    /// A sprite frame (e.g. TROOA1), plus a `.json` sidecar with its
+    /// offsets
+    ExportSprite { name: String, out: PathBuf },
+    /// One PLAYPAL palette as a 16×16 grid of swatches
+    ExportPalette {
+        out: PathBuf,
+        /// 0 is the normal palette, 1‥13 the damage/pickup/suit tints
+        #[arg(long, default_value_t = 0)]
+        index: usize,
+    },
+    /// A map's lines and things as SVG
+    ExportMap { map: String, out: PathBuf },
+}
+
+fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+    let wad =
+        Wad::from_file(&opts.wad).with_context(|| format!("can't open {}", opts.wad.display()))?;
+
+    match opts.cmd {
+        Cmd::List => list(&wad),
+        Cmd::ExportTexture { name, out } => {
+            let name = name.to_ascii_uppercase();
+            let tex = WadTextures::new(&wad)?
+                .compose_texture(&name)?
+                .with_context(|| format!("no texture {name}"))?;
+            write_png(&out, &tex, &palette(&wad, 0)?)?;
+        }
+        Cmd::ExportFlat { name, out } => {
+            let name = name.to_ascii_uppercase();
+            let flat = load_flat(&wad, &name).with_context(|| format!("no flat {name}"))?;
+            write_png(&out, &flat, &palette(&wad, 0)?)?;
+        }
+        Cmd::ExportSprite { name, out } => {
+            let name = name.to_ascii_uppercase();
+            let pic = load_patch(&wad, &name).with_context(|| format!("no sprite {name}"))?;
+            let (left, top) = patch_offsets(&wad, &name).unwrap_or_default();
+            write_png(&out, &pic, &palette(&wad, 0)?)?;
+            let sidecar = out.with_extension("json");
+            fs::write(
+                &sidecar,
+                format!(
+                    "{{\"name\": \"{name}\", \"width\": {}, \"height\": {}, \
+                     \"left_offset\": {left}, \"top_offset\": {top}}}\n",
+                    pic.w, pic.h
+                ),
+            )?;
+            println!("{}", sidecar.display());
+        }
+        Cmd::ExportPalette { out, index } => {
+            let pal = palette(&wad, index)?;
+            let pixels = (0..=255).collect();
+            let grid = Texture {
+                name: "PLAYPAL".into(),
+                w: 16,
+                h: 16,
+                pixels,
+                mask: None,
+            };
+            write_png_scaled(&out, &grid, &pal, SWATCH)?;
+        }
+        Cmd::ExportMap { map, out } => {
+            let name = map.to_ascii_uppercase();
+            let marker = wad
+                .level_indices()
+                .into_iter()
+                .find(|&i| Wad::lump_name_str(&wad.lumps()[i].name) == name)
+                .with_context(|| format!("no map {name}"))?;
+            let mut bank = TextureBank::default_with_checker();
+            let level = load_level(&wad, marker, &mut bank)?;
+            fs::write(&out, map_svg(&level))?;
+            println!("{}", out.display());
+        }
+    }
+    Ok(())
+}
+
+/// `list`: index, name, size and kind of every lump.
+fn list(wad: &Wad) {
+    let maps = wad.level_indices();
+    let mut section = "";
+    for (i, lump) in wad.lumps().iter().enumerate() {
+        let name = Wad::lump_name_str(&lump.name);
+        let kind = match name {
+            "F_START" | "FF_START" => {
+                section = "flat";
+                "marker"
+            }
+            "S_START" | "SS_START" => {
+                section = "sprite";
+                "marker"
+            }
+            "P_START" | "PP_START" => {
+                section = "patch";
+                "marker"
+            }
+            _ if name.ends_with("_END") => {
+                section = "";
+                "marker"
+            }
+            _ if maps.contains(&i) => "map",
+            "THINGS" | "LINEDEFS" | "SIDEDEFS" | "VERTEXES" | "SEGS" | "SSECTORS" | "NODES"
+            | "SECTORS" | "REJECT" | "BLOCKMAP" => "map data",
+            "PLAYPAL" => "palettes",
+            "COLORMAP" => "colormaps",
+            "PNAMES" => "patch names",
+            "TEXTURE1" | "TEXTURE2" => "textures",
+            "ENDOOM" => "text screen",
+            _ if name.starts_with("DEMO") => "demo",
+            _ if name.starts_with("DS") || name.starts_with("DP") => "sound",
+            _ if name.starts_with("D_") => "music",
+            _ if !section.is_empty() => section,
+            _ if lump.size == 0 => "marker",
+            _ => "lump",
+        };
+        println!("{i:5}  {name:<8}  {:>8}  {kind}", lump.size);
+    }
+}
+
+fn palette(wad: &Wad, index: usize) -> anyhow::Result<Palette> {
+    let mut palettes = load_palettes(wad).context("no PLAYPAL")?;
+    if index >= palettes.len() {
+        bail!("PLAYPAL has {} palettes", palettes.len());
+    }
+    Ok(palettes.swap_remove(index))
+}
+
+fn write_png(out: &Path, tex: &Texture, palette: &Palette) -> anyhow::Result<()> {
+    write_png_scaled(out, tex, palette, 1)
+}
+
+/// `tex` as RGBA, each texel `scale`×`scale` pixels; transparent texels
+/// stay transparent.
+fn write_png_scaled(
+    out: &Path,
+    tex: &Texture,
+    palette: &Palette,
+    scale: usize,
+) -> anyhow::Result<()> {
+    let (w, h) = (tex.w * scale, tex.h * scale);
+    let mut rgba = Vec::with_capacity(w * h * 4);
+    for y in 0..h {
+        for x in 0..w {
+            let i = y / scale * tex.w + x / scale;
+            let c = palette[tex.pixels[i] as usize];
+            let a = if tex.is_opaque(i) { 0xFF } else { 0 };
+            rgba.extend([(c >> 16) as u8, (c >> 8) as u8, c as u8, a]);
+        }
+    }
+
+    let file = BufWriter::new(File::create(out)?);
+    let mut enc = png::Encoder::new(file, w as u32, h as u32);
+    enc.set_color(png::ColorType::Rgba);
+    enc.set_depth(png::BitDepth::Eight);
+    enc.write_header()?.write_image_data(&rgba)?;
+    println!("{}: {}×{} → {}", tex.name, tex.w, tex.h, out.display());
+    Ok(())
+}
+
+/// The map in automap colours: one-sided walls red, floor steps brown,
+/// ceiling steps yellow, other two-sided lines grey, things as circles
+/// of their radius.  North is up.
+fn map_svg(level: &Level) -> String {
+    let (mut min, mut max) = (glam::Vec2::splat(f32::MAX), glam::Vec2::splat(f32::MIN));
+    for v in &level.vertices {
+        min = min.min(v.pos);
+        max = max.max(v.pos);
+    }
+    if level.vertices.is_empty() {
+        (min, max) = (glam::Vec2::ZERO, glam::Vec2::ZERO);
+    }
+    let (min, max) = (min - MAP_MARGIN, max + MAP_MARGIN);
+    let size = max - min;
+    // SVG's y runs down, the map's up
+    let at = |p: glam::Vec2| (p.x - min.x, max.y - p.y);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" \
+         width=\"{}\" height=\"{}\">",
+        size.x, size.y, size.x, size.y
+    );
+    let _ = writeln!(
+        svg,
+        "<title>{}</title>\n<rect width=\"100%\" height=\"100%\" fill=\"black\"/>",
+        level.name
+    );
+
+    let sector = |side: Option<u16>| {
+        side.and_then(|sd| level.sidedefs.get(sd as usize))
+            .and_then(|sd| level.sectors.get(sd.sector as usize))
+    };
+    for line in &level.linedefs {
+        let (Some(a), Some(b)) = (
+            level.vertices.get(line.v1 as usize),
+            level.vertices.get(line.v2 as usize),
+        ) else {
+            continue;
+        };
+        let colour = match (sector(line.right_sidedef), sector(line.left_sidedef)) {
+            _ if line.flags.contains(LinedefFlags::SECRET) => "#fc0000",
+            (Some(front), Some(back)) if front.floor_h != back.floor_h => "#bc7844",
+            (Some(front), Some(back)) if front.ceil_h != back.ceil_h => "#fcfc00",
+            (Some(_), Some(_)) => "#6c6c6c",
+            _ => "#fc0000",
+        };
+        let ((x1, y1), (x2, y2)) = (at(a.pos), at(b.pos));
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" stroke=\"{colour}\" \
+             stroke-width=\"2\"><title>linedef {} special {} tag {}</title></line>",
+            line.id, line.special, line.tag
+        );
+    }
+
+    for thing in &level.things {
+        let info = defs::by_doomednum(thing.type_id);
+        let radius = info.map_or(16, |i| i.radius);
+        let name = info.map_or("?", |i| i.id);
+        let colour = if (1..=4).contains(&thing.type_id) {
+            "#00fc00"
+        } else {
+            "#74fc6c"
+        };
+        let (x, y) = at(thing.pos);
+        let _ = writeln!(
+            svg,
+            "<circle cx=\"{x}\" cy=\"{y}\" r=\"{radius}\" fill=\"none\" stroke=\"{colour}\">\
+             <title>{} {name}</title></circle>",
+            thing.type_id
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
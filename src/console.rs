@@ -0,0 +1,422 @@
+//! Developer console: a line editor, a command registry and a text
+//! overlay drawn with the WAD's HUD font.
+//!
+//! Frontends feed it keys while it is open (and stop building game
+//! `InputCmd`s), then call [`Console::draw`] with the frame's renderer
+//! before `end_frame`, or with a `Canvas` over a frame drawn without one.
+
+use std::collections::HashMap;
+
+use crate::game::GameState;
+use crate::input::{Action, Control};
+use crate::renderer::{LightMode, Rgba, overlay::TextTarget};
+use crate::sim::{Angle, CheatFlags, Position};
+
+/// A console command: the game plus the words after the command name.
+pub type Command = fn(&mut GameState, &[&str]);
+
+/// Output lines shown above the prompt.
+pub const CONSOLE_LINES: usize = 10;
+
+/*──────────────────────────── HUD font ─────────────────────────────*/
+
+/// What the HUD font (`STCFN033`‥`STCFN095`: upper-case ASCII from `!`
+/// to `_`) is registered as in the `TextureBank`.
+pub const HUD_FONT: &str = "STCFN";
+pub const FONT_FIRST: u8 = b'!';
+pub const FONT_LAST: u8 = b'_';
+
+/// Text tint that leaves the font's own colours alone.
+pub const WHITE: Rgba = 0xFF_FF_FF;
+
+/// Font scale for a `h`-pixel-high frame: one texel per 200-line pixel.
+pub fn text_scale(h: usize) -> usize {
+    (h / 200).max(1)
+}
+
+/*──────────────────────────── Console ──────────────────────────────*/
+
+pub struct Console {
+    open: bool,
+    line: String,
+    commands: HashMap<&'static str, Command>,
+}
+
+impl Console {
+    /// A closed console with the built-in commands registered.
+    pub fn new() -> Self {
+        let mut con = Self {
+            open: false,
+            line: String::new(),
+            commands: HashMap::new(),
+        };
+        con.register("noclip", cmd_noclip);
+        con.register("fly", cmd_fly);
+        con.register("god", cmd_god);
+        con.register("warp", cmd_warp);
+        con.register("summon", cmd_summon);
+        con.register("stats", cmd_stats);
+        con.register("bind", cmd_bind);
+        con.register("light", cmd_light);
+        con.register("autoaim", cmd_autoaim);
+        con
+    }
+
+    /// Add (or replace) the command `name`.
+    pub fn register(&mut self, name: &'static str, cmd: Command) {
+        self.commands.insert(name, cmd);
+    }
+
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    /// Append a typed character to the input line.
+    pub fn type_char(&mut self, c: char) {
+        self.line.push(c);
+    }
+
+    pub fn backspace(&mut self) {
+        self.line.pop();
+    }
+
+    /// Echo and run the input line, then clear it.
+    pub fn submit(&mut self, game: &mut GameState) {
+        let line = std::mem::take(&mut self.line);
+        if line.trim().is_empty() {
+            return;
+        }
+        game.print(format!("] {line}"));
+        self.execute(game, &line);
+    }
+
+    /// Run one command line.  `help` lists the registry; unknown commands
+    /// only print a message.
+    pub fn execute(&self, game: &mut GameState, line: &str) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((name, args)) = words.split_first() else {
+            return;
+        };
+        let name = name.to_ascii_lowercase();
+        if name == "help" {
+            let mut names: Vec<_> = self.commands.keys().copied().collect();
+            names.sort_unstable();
+            game.print(names.join(" "));
+            return;
+        }
+        match self.commands.get(name.as_str()) {
+            Some(cmd) => cmd(game, args),
+            None => game.print(format!("unknown command {name}")),
+        }
+    }
+
+    /// Darken the top of the `w`×`h` frame and draw the last output
+    /// lines plus the prompt over it, in the game's HUD font.  Does
+    /// nothing while closed.
+    pub fn draw(&self, game: &GameState, out: &mut impl TextTarget, w: usize, h: usize) {
+        if !self.open {
+            return;
+        }
+        let scale = text_scale(h);
+        let font = game.bank.font(HUD_FONT).map(|f| f.scaled(scale));
+        let line_h = font.map_or(8 * scale, |f| f.line_height());
+        let rows = ((CONSOLE_LINES + 1) * line_h + 2 * scale).min(h);
+        out.dim_rect(0, 0, w as i32, rows as i32);
+        let Some(font) = font else {
+            return;
+        };
+
+        let x = 2 * scale as i32;
+        let y = |row: usize| (scale + row * line_h) as i32;
+        let first = game.messages.len().saturating_sub(CONSOLE_LINES);
+        for (i, msg) in game.messages[first..].iter().enumerate() {
+            out.text(msg, x, y(i), &font, WHITE);
+        }
+        let prompt = format!("] {}_", self.line);
+        out.text(&prompt, x, y(CONSOLE_LINES), &font, WHITE);
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/*─────────────────────────── Built-ins ─────────────────────────────*/
+
+fn toggle(game: &mut GameState, cheat: CheatFlags, on: &str, off: &str) {
+    match game.sim.toggle_cheat(cheat) {
+        Some(true) => game.print(on),
+        Some(false) => game.print(off),
+        None => game.print("no player"),
+    }
+}
+
+fn cmd_noclip(game: &mut GameState, _: &[&str]) {
+    toggle(
+        game,
+        CheatFlags::NOCLIP,
+        "No Clipping Mode ON",
+        "No Clipping Mode OFF",
+    );
+}
+
+fn cmd_fly(game: &mut GameState, _: &[&str]) {
+    toggle(game, CheatFlags::FLY, "Fly Mode ON", "Fly Mode OFF");
+}
+
+fn cmd_god(game: &mut GameState, _: &[&str]) {
+    toggle(
+        game,
+        CheatFlags::GOD,
+        "Degreelessness Mode On",
+        "Degreelessness Mode Off",
+    );
+}
+
+fn cmd_warp(game: &mut GameState, args: &[&str]) {
+    let [map] = args else {
+        game.print("usage: warp <map>");
+        return;
+    };
+    match game.warp(map) {
+        Ok(()) => game.print(format!("warped to {}", game.level.name)),
+        Err(e) => game.print(e.to_string()),
+    }
+}
+
+/// Distance in front of the player `summon` places things at.
+const SUMMON_DIST: f32 = 64.0;
+
+fn cmd_summon(game: &mut GameState, args: &[&str]) {
+    let [id] = args else {
+        game.print("usage: summon <mobj id>");
+        return;
+    };
+    let Some(&info) = game.sim.definitions().by_id(&id.to_ascii_uppercase()) else {
+        game.print(format!("unknown thing {id}"));
+        return;
+    };
+    let Some(player) = game.sim.player() else {
+        game.print("no player");
+        return;
+    };
+    let (pos, ang) = {
+        let world = game.sim.world();
+        let (Ok(pos), Ok(ang)) = (world.get::<&Position>(player), world.get::<&Angle>(player))
+        else {
+            return;
+        };
+        (pos.0, *ang)
+    };
+    let at = pos + ang.unit() * SUMMON_DIST;
+    let ss = game.level.locate_subsector(at);
+    game.sim.spawn_mobj(&game.level, &info, at.x, at.y, ang, ss);
+    game.print(format!("summoned {}", info.id));
+}
+
+fn cmd_stats(game: &mut GameState, _: &[&str]) {
+    game.show_stats = !game.show_stats;
+}
+
+/// `light` shows the lighting mode; `light vanilla|full|distance|<row>`
+/// sets it.
+fn cmd_light(game: &mut GameState, args: &[&str]) {
+    if let Some(arg) = args.first() {
+        match LightMode::parse(arg) {
+            Some(mode) => game.light_mode = mode,
+            None => return game.print("usage: light [vanilla|full|distance|0-31]"),
+        }
+    }
+    let line = format!("light {}", game.light_mode);
+    game.print(line);
+}
+
+/// `autoaim` toggles the players' vertical autoaim, this map and the
+/// next.
+fn cmd_autoaim(game: &mut GameState, _: &[&str]) {
+    game.autoaim = !game.autoaim;
+    game.sim.set_autoaim(game.autoaim);
+    let line = format!("autoaim {}", if game.autoaim { "ON" } else { "OFF" });
+    game.print(line);
+}
+
+/// `bind` lists every binding, `bind <action>` shows one and
+/// `bind <action> <control>…` replaces it.
+fn cmd_bind(game: &mut GameState, args: &[&str]) {
+    let Some((name, controls)) = args.split_first() else {
+        for action in Action::ALL {
+            let line = game.bindings.describe(action);
+            game.print(line);
+        }
+        return;
+    };
+    let Some(action) = Action::from_name(name) else {
+        game.print(format!("unknown action {name}"));
+        return;
+    };
+    if !controls.is_empty() {
+        let Some(controls) = controls.iter().map(|c| Control::parse(c)).collect() else {
+            game.print("usage: bind <action> [key|mouse1-3]...");
+            return;
+        };
+        game.bindings.set(action, controls);
+    }
+    let line = game.bindings.describe(action);
+    game.print(line);
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs;
+    use crate::renderer::overlay::Canvas;
+    use crate::sim::TicRunner;
+    use crate::wad::Wad;
+    use crate::world::{TextureBank, testmap};
+
+    fn game() -> GameState {
+        let mut header = b"IWAD".to_vec();
+        header.extend(0u32.to_le_bytes());
+        header.extend(12u32.to_le_bytes());
+
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        let wad = Wad::from_bytes(header).unwrap();
+        GameState {
+            view_border: crate::screens::ViewBorder::new(&wad),
+            wad,
+            bank: TextureBank::default_with_checker(),
+            level,
+            sim,
+            seed: 0,
+            skill: Default::default(),
+            rules: Default::default(),
+            defs: crate::defs::Definitions::shared_builtin(),
+            players: 1,
+            show_stats: false,
+            light_mode: Default::default(),
+            start_wipe: false,
+            messages: Vec::new(),
+            phase: crate::game::Phase::Level,
+            bindings: crate::input::Bindings::vanilla(),
+            physics: Default::default(),
+            autoaim: true,
+            screenblocks: crate::renderer::SCREENBLOCKS_MAX,
+            level_infos: Default::default(),
+            session: Default::default(),
+        }
+    }
+
+    fn last(game: &GameState) -> &str {
+        game.messages.last().map_or("", String::as_str)
+    }
+
+    #[test]
+    fn cheats_toggle_and_report() {
+        let con = Console::new();
+        let mut game = game();
+
+        con.execute(&mut game, "noclip");
+        assert_eq!(last(&game), "No Clipping Mode ON");
+        assert!(game.sim.cheats().contains(CheatFlags::NOCLIP));
+
+        con.execute(&mut game, "  NoClip ");
+        assert_eq!(last(&game), "No Clipping Mode OFF");
+        con.execute(&mut game, "god");
+        con.execute(&mut game, "fly");
+        assert_eq!(game.sim.cheats(), CheatFlags::GOD | CheatFlags::FLY);
+
+        con.execute(&mut game, "stats");
+        assert!(game.show_stats);
+
+        con.execute(&mut game, "autoaim");
+        assert_eq!(last(&game), "autoaim OFF");
+        assert!(!game.sim.autoaim());
+    }
+
+    #[test]
+    fn light_sets_the_mode() {
+        let con = Console::new();
+        let mut game = game();
+        con.execute(&mut game, "light");
+        assert_eq!(last(&game), "light vanilla");
+        con.execute(&mut game, "light 12");
+        assert_eq!(game.light_mode, LightMode::Flat(12));
+        assert_eq!(last(&game), "light flat 12");
+        con.execute(&mut game, "light dark");
+        assert_eq!(last(&game), "usage: light [vanilla|full|distance|0-31]");
+        assert_eq!(game.light_mode, LightMode::Flat(12));
+    }
+
+    #[test]
+    fn bind_shows_and_replaces() {
+        let con = Console::new();
+        let mut game = game();
+
+        con.execute(&mut game, "bind use");
+        assert_eq!(last(&game), "use = Space");
+        con.execute(&mut game, "bind USE Enter mouse3");
+        assert_eq!(last(&game), "use = Enter, mouse3");
+        assert_eq!(
+            game.bindings.get(Action::Use),
+            [Control::Key("Enter".into()), Control::Mouse(3)]
+        );
+        con.execute(&mut game, "bind jump Space");
+        assert_eq!(last(&game), "unknown action jump");
+    }
+
+    #[test]
+    fn summon_warp_and_unknown() {
+        let mut con = Console::new();
+        let mut game = game();
+        let before = game.sim.world().len();
+
+        con.execute(&mut game, "summon troop");
+        assert_eq!(last(&game), "summoned TROOP");
+        assert_eq!(game.sim.world().len(), before + 1);
+        con.execute(&mut game, "summon nothing");
+        assert_eq!(last(&game), "unknown thing nothing");
+
+        con.execute(&mut game, "warp e1m9");
+        assert_eq!(last(&game), "no map E1M9 in the WAD");
+
+        con.execute(&mut game, "frobnicate 3");
+        assert_eq!(last(&game), "unknown command frobnicate");
+
+        con.register("frobnicate", |g, args| g.print(args.join("+")));
+        for c in "frobnicate 1 2".chars() {
+            con.type_char(c);
+        }
+        con.submit(&mut game);
+        assert_eq!(game.messages[game.messages.len() - 2], "] frobnicate 1 2");
+        assert_eq!(last(&game), "1+2");
+    }
+
+    #[test]
+    fn overlay_dims_only_the_console_rows() {
+        let mut con = Console::new();
+        let game = game();
+        let (w, h) = (320, 200);
+        let mut fb = vec![0xFFFF_FFFF; w * h];
+
+        con.draw(&game, &mut Canvas::new(&mut fb, w, h), w, h);
+        assert!(fb.iter().all(|&p| p == 0xFFFF_FFFF));
+
+        con.toggle();
+        con.draw(&game, &mut Canvas::new(&mut fb, w, h), w, h);
+        assert_eq!(fb[0], 0xFF7F_7F7F);
+        assert_eq!(fb[w * h - 1], 0xFFFF_FFFF);
+    }
+}
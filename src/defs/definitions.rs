@@ -0,0 +1,125 @@
+//! The info tables the game actually runs on.
+//!
+//! `MOBJINFO` and `STATES` are compiled in; DeHackEd patches need to
+//! change them, so the sim reads a [`Definitions`] copy instead, shared
+//! behind an `Arc` between the game and each map's sim.  Without a patch
+//! that is [`Definitions::shared_builtin`], built once.
+
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, sync::Arc};
+
+use super::{MOBJINFO, MobjInfo, STATES, State, StateInfo};
+
+/// Ammo types, in vanilla `ammotype_t` order.
+pub const NUM_AMMO_TYPES: usize = 4;
+
+/// The tunables vanilla hard-codes in `p_inter.c` and friends, which
+/// DeHackEd's `Misc` section exposes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Misc {
+    pub initial_health: i32,
+    pub initial_bullets: i32,
+    /// Cap for health bonuses.
+    pub max_health: i32,
+    /// Cap for armor bonuses.
+    pub max_armor: i32,
+    pub green_armor_class: i32,
+    pub blue_armor_class: i32,
+    /// Cap for soulspheres.
+    pub max_soulsphere: i32,
+    pub soulsphere_health: i32,
+    pub megasphere_health: i32,
+    pub bfg_cells_per_shot: i32,
+}
+
+impl Default for Misc {
+    fn default() -> Self {
+        Self {
+            initial_health: 100,
+            initial_bullets: 50,
+            max_health: 200,
+            max_armor: 200,
+            green_armor_class: 1,
+            blue_armor_class: 2,
+            max_soulsphere: 200,
+            soulsphere_health: 100,
+            megasphere_health: 200,
+            bfg_cells_per_shot: 40,
+        }
+    }
+}
+
+/// A runtime copy of the info tables.
+#[derive(Clone, Debug)]
+pub struct Definitions {
+    pub mobjinfo: Vec<MobjInfo>,
+    pub states: Vec<StateInfo>,
+    /// Per ammo type (vanilla `maxammo`).
+    pub max_ammo: [i32; NUM_AMMO_TYPES],
+    /// Rounds in a clip-sized pickup (vanilla `clipammo`).
+    pub clip_ammo: [i32; NUM_AMMO_TYPES],
+    pub misc: Misc,
+    by_doomednum: HashMap<u16, usize>,
+    by_id: HashMap<&'static str, usize>,
+}
+
+static BUILTIN: Lazy<Arc<Definitions>> = Lazy::new(|| Arc::new(Definitions::builtin()));
+
+impl Definitions {
+    /// A copy of the compiled-in tables.
+    pub fn builtin() -> Self {
+        let mut defs = Self {
+            mobjinfo: MOBJINFO.to_vec(),
+            states: STATES.to_vec(),
+            max_ammo: [200, 50, 300, 50],
+            clip_ammo: [10, 4, 20, 1],
+            misc: Misc::default(),
+            by_doomednum: HashMap::new(),
+            by_id: HashMap::new(),
+        };
+        defs.reindex();
+        defs
+    }
+
+    /// Rebuild the lookups after `mobjinfo` changed (a patch can renumber
+    /// a thing's `doomednum`).
+    pub fn reindex(&mut self) {
+        self.by_doomednum.clear();
+        for (i, info) in self.mobjinfo.iter().enumerate() {
+            if info.doomednum >= 0 {
+                self.by_doomednum.insert(info.doomednum as u16, i);
+            }
+        }
+        self.by_id = self
+            .mobjinfo
+            .iter()
+            .enumerate()
+            .map(|(i, info)| (info.id, i))
+            .collect();
+    }
+
+    pub fn by_doomednum(&self, num: u16) -> Option<&MobjInfo> {
+        self.by_doomednum.get(&num).map(|&i| &self.mobjinfo[i])
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<&MobjInfo> {
+        self.by_id.get(id).map(|&i| &self.mobjinfo[i])
+    }
+
+    /// The compiled-in tables, built on first use and shared from then on.
+    pub fn shared_builtin() -> Arc<Self> {
+        BUILTIN.clone()
+    }
+
+    /// The compiled-in tables, for good: what [`super::by_id`] and
+    /// friends look things up in.
+    pub(super) fn builtin_static() -> &'static Self {
+        &BUILTIN
+    }
+
+    /// What these tables say `state` is.
+    #[inline]
+    pub fn state(&self, state: State) -> &StateInfo {
+        &self.states[state as usize]
+    }
+}
@@ -0,0 +1,391 @@
+//! DeHackEd patches – the text `.deh` format mods use to change things,
+//! frames and a few tunables.
+//!
+//! `Thing`, `Frame`, `Pointer`, `Ammo` and `Misc` sections are applied to
+//! a [`Definitions`]; everything else (weapons, sounds, text, BEX
+//! extensions) is skipped with a warning.  Things are numbered from 1 in
+//! `MOBJINFO` order, frames from 0 in `STATES` order, as in DeHackEd.
+
+use std::{fs, io, path::Path};
+
+use thiserror::Error;
+
+use super::{Definitions, MobjFlags, SOUNDS, STATES, Sound, State};
+
+/// Vanilla `sprnames`, which `Sprite number` indexes.
+pub const SPRITE_NAMES: [&str; 138] = [
+    "TROO", "SHTG", "PUNG", "PISG", "PISF", "SHTF", "SHT2", "CHGG", "CHGF", "MISG", "MISF", "SAWG",
+    "PLSG", "PLSF", "BFGG", "BFGF", "BLUD", "PUFF", "BAL1", "BAL2", "PLSS", "PLSE", "MISL", "BFS1",
+    "BFE1", "BFE2", "TFOG", "IFOG", "PLAY", "POSS", "SPOS", "VILE", "FIRE", "FATB", "FBXP", "SKEL",
+    "MANF", "FATT", "CPOS", "SARG", "HEAD", "BAL7", "BOSS", "BOS2", "SKUL", "SPID", "BSPI", "APLS",
+    "APBX", "CYBR", "PAIN", "SSWV", "KEEN", "BBRN", "BOSF", "ARM1", "ARM2", "BAR1", "BEXP", "FCAN",
+    "BON1", "BON2", "BKEY", "RKEY", "YKEY", "BSKU", "RSKU", "YSKU", "STIM", "MEDI", "SOUL", "PINV",
+    "PSTR", "PINS", "MEGA", "SUIT", "PMAP", "PVIS", "CLIP", "AMMO", "ROCK", "BROK", "CELL", "CELP",
+    "SHEL", "SBOX", "BPAK", "BFUG", "MGUN", "CSAW", "LAUN", "PLAS", "SHOT", "SGN2", "COLU", "SMT2",
+    "GOR1", "POL2", "POL5", "POL4", "POL3", "POL1", "POL6", "GOR2", "GOR3", "GOR4", "GOR5", "SMIT",
+    "COL1", "COL2", "COL3", "COL4", "CAND", "CBRA", "COL6", "TRE1", "TRE2", "ELEC", "CEYE", "FSKU",
+    "COL5", "TBLU", "TGRN", "TRED", "SMBT", "SMGT", "SMRT", "HDB1", "HDB2", "HDB3", "HDB4", "HDB5",
+    "HDB6", "POB1", "POB2", "BRS1", "TLMP", "TLP2",
+];
+
+/// 1.0 in the 16.16 fixed point patches give sizes in.
+const FRACUNIT: i32 = 1 << 16;
+
+/// Frame numbers carry the full-bright bit above these.
+const FF_FRAMEMASK: i32 = 0x7fff;
+/// The full-bright bit.
+const FF_FULLBRIGHT: i32 = 0x8000;
+
+#[derive(Error, Debug)]
+pub enum DehError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("line {line}: expected `key = value`")]
+    Syntax { line: usize },
+
+    #[error("line {line}: no {section} {index}")]
+    BadIndex {
+        line: usize,
+        section: &'static str,
+        index: i64,
+    },
+
+    #[error("line {line}: bad value for {name:?}")]
+    BadValue { line: usize, name: String },
+}
+
+/// The section the lines being read belong to.
+#[derive(Clone, Copy, Debug)]
+enum Section {
+    /// The header before the first section.
+    Header,
+    /// A `MOBJINFO` index.
+    Thing(usize),
+    /// A `STATES` index.
+    Frame(usize),
+    /// The frame whose codepointer is being replaced.
+    Pointer(usize),
+    Ammo(usize),
+    Misc,
+    /// A section this doesn't support.
+    Skip,
+}
+
+/// Read the patch at `path` into `defs`.
+pub fn load<P: AsRef<Path>>(defs: &mut Definitions, path: P) -> Result<(), DehError> {
+    let bytes = fs::read(path)?;
+    apply(defs, &String::from_utf8_lossy(&bytes))
+}
+
+/// Apply the patch `text` to `defs`.
+pub fn apply(defs: &mut Definitions, text: &str) -> Result<(), DehError> {
+    let mut section = Section::Header;
+    let mut lines = text.lines().enumerate();
+    while let Some((i, raw)) = lines.next() {
+        let line = i + 1;
+        let s = raw.trim();
+        if s.is_empty() || s.starts_with('#') {
+            continue;
+        }
+
+        let Some((name, value)) = s.split_once('=') else {
+            if s.starts_with("Text ") {
+                eprintln!("warning: line {line}: skipping unsupported section {s}");
+                skip_text(s, &mut lines);
+                section = Section::Skip;
+                continue;
+            }
+            section = match start_section(defs, line, s)? {
+                Some(next) => next,
+                None if matches!(section, Section::Header | Section::Skip) => section,
+                None => return Err(DehError::Syntax { line }),
+            };
+            continue;
+        };
+        let (name, value) = (name.trim().to_ascii_lowercase(), value.trim());
+        let bad = || DehError::BadValue {
+            line,
+            name: name.clone(),
+        };
+        let num = || value.parse::<i32>().map_err(|_| bad());
+        let index = || usize::try_from(num()?).map_err(|_| bad());
+        let state =
+            || -> Result<State, DehError> { Ok(STATES.get(index()?).ok_or_else(bad)?.state) };
+        let sound =
+            || -> Result<Sound, DehError> { Ok(SOUNDS.get(index()?).ok_or_else(bad)?.sound) };
+
+        match section {
+            Section::Header | Section::Skip => {}
+            Section::Thing(t) => {
+                let info = &mut defs.mobjinfo[t];
+                match name.as_str() {
+                    "id #" => info.doomednum = num()?,
+                    "hit points" => info.spawnhealth = num()?,
+                    "reaction time" => info.reactiontime = num()?,
+                    "pain chance" => info.painchance = num()?,
+                    "mass" => info.mass = num()?,
+                    "missile damage" => info.damage = num()?,
+                    // missiles' speeds are fixed point, monsters' whole units
+                    "speed" => {
+                        let v = num()?;
+                        info.speed = if v.abs() >= FRACUNIT { v / FRACUNIT } else { v };
+                    }
+                    "width" => info.radius = num()? / FRACUNIT,
+                    "height" => info.height = num()? / FRACUNIT,
+                    "bits" => info.flags = parse_bits(value).ok_or_else(bad)?,
+                    "initial frame" => info.spawnstate = state()?,
+                    "first moving frame" => info.seestate = state()?,
+                    "injury frame" => info.painstate = state()?,
+                    "close attack frame" => info.meleestate = state()?,
+                    "far attack frame" => info.missilestate = state()?,
+                    "death frame" => info.deathstate = state()?,
+                    "exploding frame" => info.xdeathstate = state()?,
+                    "respawn frame" => info.raisestate = state()?,
+                    "alert sound" => info.seesound = sound()?,
+                    "attack sound" => info.attacksound = sound()?,
+                    "pain sound" => info.painsound = sound()?,
+                    "death sound" => info.deathsound = sound()?,
+                    "action sound" => info.activesound = sound()?,
+                    _ => unsupported(line, &name),
+                }
+            }
+            Section::Frame(f) => {
+                let st = &mut defs.states[f];
+                match name.as_str() {
+                    "sprite number" => st.sprite = SPRITE_NAMES.get(index()?).ok_or_else(bad)?,
+                    "sprite subnumber" => {
+                        let n = num()?;
+                        st.frame = (n & FF_FRAMEMASK) as u8;
+                        st.bright = n & FF_FULLBRIGHT != 0;
+                    }
+                    "duration" => st.tics = num()?,
+                    "next frame" => st.next_state = state()?,
+                    "unknown 1" => st.misc1 = num()?,
+                    "unknown 2" => st.misc2 = num()?,
+                    _ => unsupported(line, &name),
+                }
+            }
+            Section::Pointer(f) => match name.as_str() {
+                // the action the compiled-in frame `value` had
+                "codep frame" => {
+                    defs.states[f].action = STATES.get(index()?).ok_or_else(bad)?.action
+                }
+                _ => unsupported(line, &name),
+            },
+            Section::Ammo(a) => match name.as_str() {
+                "max ammo" => defs.max_ammo[a] = num()?,
+                "per ammo" => defs.clip_ammo[a] = num()?,
+                _ => unsupported(line, &name),
+            },
+            Section::Misc => {
+                let misc = &mut defs.misc;
+                match name.as_str() {
+                    "initial health" => misc.initial_health = num()?,
+                    "initial bullets" => misc.initial_bullets = num()?,
+                    "max health" => misc.max_health = num()?,
+                    "max armor" => misc.max_armor = num()?,
+                    "green armor class" => misc.green_armor_class = num()?,
+                    "blue armor class" => misc.blue_armor_class = num()?,
+                    "max soulsphere" => misc.max_soulsphere = num()?,
+                    "soulsphere health" => misc.soulsphere_health = num()?,
+                    "megasphere health" => misc.megasphere_health = num()?,
+                    "bfg cells/shot" => misc.bfg_cells_per_shot = num()?,
+                    _ => unsupported(line, &name),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The section `header` starts, `None` if it doesn't start one.
+fn start_section(
+    defs: &Definitions,
+    line: usize,
+    header: &str,
+) -> Result<Option<Section>, DehError> {
+    if header.starts_with('[') {
+        eprintln!("warning: line {line}: skipping unsupported BEX section {header}");
+        return Ok(Some(Section::Skip));
+    }
+    let mut words = header.split_whitespace();
+    let kind = words.next().unwrap_or("");
+    let index = |section: &'static str, n: Option<&str>, len: usize, base: i64| {
+        let n: i64 = n
+            .and_then(|n| n.trim_matches(['(', ')']).parse().ok())
+            .ok_or(DehError::Syntax { line })?;
+        usize::try_from(n - base)
+            .ok()
+            .filter(|&i| i < len)
+            .ok_or(DehError::BadIndex {
+                line,
+                section,
+                index: n,
+            })
+    };
+    let section = match kind {
+        "Thing" => Section::Thing(index("thing", words.next(), defs.mobjinfo.len(), 1)?),
+        "Frame" => Section::Frame(index("frame", words.next(), defs.states.len(), 0)?),
+        // `Pointer N (Frame M)`: N counts codepointers, M is what matters
+        "Pointer" => {
+            let frame = words.nth(2);
+            Section::Pointer(index("frame", frame, defs.states.len(), 0)?)
+        }
+        "Ammo" => Section::Ammo(index("ammo", words.next(), defs.max_ammo.len(), 0)?),
+        "Misc" => Section::Misc,
+        "Weapon" | "Sound" | "Sprite" | "Cheat" => {
+            eprintln!("warning: line {line}: skipping unsupported section {header}");
+            Section::Skip
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(section))
+}
+
+/// `Text OLD NEW` is followed by OLD + NEW characters of raw text, which
+/// can look like anything; skip past them.
+fn skip_text<'a>(header: &str, lines: &mut impl Iterator<Item = (usize, &'a str)>) {
+    let len: usize = header
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|n| n.parse::<usize>().ok())
+        .sum();
+    let mut seen = 0;
+    while seen < len {
+        let Some((_, l)) = lines.next() else {
+            break;
+        };
+        // the newline counts as one
+        seen += l.trim_end_matches('\r').len() + 1;
+    }
+}
+
+/// `Bits` as a number or as `SOLID+SHOOTABLE`-style flag names.
+fn parse_bits(value: &str) -> Option<MobjFlags> {
+    if let Ok(n) = value.parse::<u32>() {
+        return Some(MobjFlags::from_bits_retain(n));
+    }
+    value
+        .split(['+', '|', ',', ' '])
+        .filter(|s| !s.is_empty())
+        .try_fold(MobjFlags::empty(), |acc, name| {
+            Some(acc | MobjFlags::from_name(&name.to_ascii_uppercase())?)
+        })
+}
+
+fn unsupported(line: usize, name: &str) {
+    eprintln!("warning: line {line}: unsupported DeHackEd field {name:?}");
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::{self, Action},
+        sim::{Health, TicRunner},
+        world::testmap,
+    };
+    use std::sync::Arc;
+
+    const PATCH: &str = "\
+Patch File for DeHackEd v3.0
+# doubled-up zombies
+Doom version = 19
+Patch format = 6
+
+Thing 2 (Zombieman)
+Hit points = 40
+Speed = 16
+Width = 1310720
+Bits = SOLID+SHOOTABLE+COUNTKILL
+
+Thing 34 (Rocket)
+Speed = 1310720
+
+Frame 174
+Duration = 2
+Sprite subnumber = 32769
+Next frame = 175
+
+Pointer 60 (Frame 174)
+Codep Frame = 176
+
+Text 5 6
+Hello
+World!
+
+Ammo 0
+Max ammo = 400
+
+Misc 0
+Initial Bullets = 25
+BFG Cells/Shot = 30
+";
+
+    #[test]
+    fn sections_patch_their_tables() {
+        let mut d = Definitions::builtin();
+        apply(&mut d, PATCH).unwrap();
+
+        let zombie = d.by_id("POSSESSED").unwrap();
+        assert_eq!(zombie.spawnhealth, 40);
+        assert_eq!((zombie.speed, zombie.radius), (16, 20));
+        assert_eq!(
+            zombie.flags.bits(),
+            (MobjFlags::SOLID | MobjFlags::SHOOTABLE | MobjFlags::COUNTKILL).bits()
+        );
+        assert_eq!(d.by_id("ROCKET").unwrap().speed, 20);
+
+        let st = d.states[174];
+        assert_eq!((st.tics, st.frame, st.bright), (2, 1, true));
+        assert_eq!(st.next_state, STATES[175].state);
+        assert!(matches!(st.action, Action::Chase));
+
+        assert_eq!(d.max_ammo[0], 400);
+        assert_eq!(d.misc.initial_bullets, 25);
+        assert_eq!(d.misc.bfg_cells_per_shot, 30);
+
+        let err = apply(&mut d, "Thing 500\nHit points = 1").unwrap_err();
+        assert!(matches!(err, DehError::BadIndex { line: 1, .. }));
+        let err = apply(&mut d, "Frame 3\nNext frame = 99999").unwrap_err();
+        assert!(matches!(err, DehError::BadValue { line: 2, .. }));
+        let err = apply(&mut d, "Misc 0\ngarbage").unwrap_err();
+        assert!(matches!(err, DehError::Syntax { line: 2 }));
+    }
+
+    #[test]
+    fn sprite_numbers_follow_the_state_table() {
+        // the table introduces the sprites in `sprnames` order
+        let mut seen = Vec::new();
+        for st in STATES {
+            if !seen.contains(&st.sprite) {
+                seen.push(st.sprite);
+            }
+        }
+        assert_eq!(seen, SPRITE_NAMES);
+    }
+
+    #[test]
+    fn patched_zombiemen_spawn_with_double_health() {
+        assert_eq!(defs::by_id("POSSESSED").unwrap().spawnhealth, 20);
+        let mut d = Definitions::builtin();
+        apply(&mut d, "Thing 2 (Zombieman)\nHit points = 40\n").unwrap();
+
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        sim.set_definitions(Arc::new(d));
+        let info = *sim.definitions().by_id("POSSESSED").unwrap();
+        let zombie = sim.spawn_mobj(&level, &info, 40.0, 64.0, 0.0, 0);
+        assert_eq!(sim.world().get::<&Health>(zombie).unwrap().0, 40);
+
+        // the compiled-in tables are untouched
+        assert_eq!(defs::by_id("POSSESSED").unwrap().spawnhealth, 20);
+    }
+}
@@ -3,7 +3,7 @@
 use crate::defs::flags::MobjFlags as MF;
 use crate::defs::{sound::Sound, state::State};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct MobjInfo {
     pub id: &'static str,
     pub doomednum: i32,
@@ -222,7 +222,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::TRACEEXP1,
         xdeathstate: State::NULL,
         deathsound: Sound::barexp,
-        speed: 0,
+        speed: 10,
         radius: 11,
         height: 8,
         mass: 100,
@@ -304,7 +304,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::FATSHOTX1,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 20,
         radius: 6,
         height: 8,
         mass: 100,
@@ -504,7 +504,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::BRBALLX1,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 15,
         radius: 6,
         height: 8,
         mass: 100,
@@ -842,7 +842,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::NULL,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 10,
         radius: 6,
         height: 32,
         mass: 100,
@@ -926,7 +926,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::TBALLX1,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 10,
         radius: 6,
         height: 8,
         mass: 100,
@@ -954,7 +954,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::RBALLX1,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 10,
         radius: 6,
         height: 8,
         mass: 100,
@@ -982,7 +982,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::EXPLODE1,
         xdeathstate: State::NULL,
         deathsound: Sound::barexp,
-        speed: 0,
+        speed: 20,
         radius: 11,
         height: 8,
         mass: 100,
@@ -1010,7 +1010,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::PLASEXP,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 25,
         radius: 13,
         height: 8,
         mass: 100,
@@ -1038,7 +1038,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::BFGLAND,
         xdeathstate: State::NULL,
         deathsound: Sound::rxplod,
-        speed: 0,
+        speed: 25,
         radius: 13,
         height: 8,
         mass: 100,
@@ -1066,7 +1066,7 @@ pub const MOBJINFO: &[MobjInfo] = &[
         deathstate: State::ARACH_PLEX,
         xdeathstate: State::NULL,
         deathsound: Sound::firxpl,
-        speed: 0,
+        speed: 25,
         radius: 13,
         height: 8,
         mass: 100,
@@ -1,4 +1,6 @@
 pub mod action;
+pub mod definitions;
+pub mod deh;
 pub mod flags;
 pub mod mobjinfo;
 pub mod sound;
@@ -7,33 +9,22 @@ pub mod states;
 
 pub use self::{
     action::Action,
+    definitions::Definitions,
     flags::MobjFlags,
     mobjinfo::{MOBJINFO, MobjInfo},
-    sound::Sound,
+    sound::{SOUNDS, Sound, SoundInfo},
     state::State,
     states::{STATES, StateInfo},
 };
 
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
-
-static BY_DOOMEDNUM: Lazy<HashMap<u16, &'static MobjInfo>> = Lazy::new(|| {
-    let mut map = HashMap::with_capacity(MOBJINFO.len());
-    for info in MOBJINFO {
-        if info.doomednum >= 0 {
-            map.insert(info.doomednum as u16, info);
-        }
-    }
-    map
-});
-
+/// The mobj type placed by editor number `num`, in the compiled-in
+/// tables; the sim looks things up in its own `Definitions`.
 pub fn by_doomednum(num: u16) -> Option<&'static MobjInfo> {
-    BY_DOOMEDNUM.get(&num).copied()
+    Definitions::builtin_static().by_doomednum(num)
 }
 
-static BY_ID: Lazy<HashMap<&'static str, &'static MobjInfo>> =
-    Lazy::new(|| MOBJINFO.iter().map(|info| (info.id, info)).collect());
-
+/// The mobj type called `id` (`"POSSESSED"`…), in the compiled-in
+/// tables.
 pub fn by_id(id: &str) -> Option<&'static MobjInfo> {
-    BY_ID.get(id).copied()
+    Definitions::builtin_static().by_id(id)
 }
@@ -1,66 +1,1127 @@
 // AUTO-GENERATED - see tools/gen_mobjinfo
 
-#[derive(Debug, Copy, Clone)]
+#[repr(usize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum Sound {
-    None,
-    barexp,
-    bgact,
-    bgdth1,
-    bgsit1,
-    bosdth,
-    bospit,
-    bospn,
-    brsdth,
-    brssit,
-    bspact,
-    bspdth,
-    bspsit,
-    cacdth,
-    cacsit,
-    cybdth,
-    cybsit,
-    dmact,
-    dmpain,
-    firsht,
-    firxpl,
-    keendt,
-    keenpn,
-    kntdth,
-    kntsit,
-    mandth,
-    mansit,
-    mnpain,
-    pedth,
-    pepain,
-    pesit,
-    pistol,
-    plasma,
-    pldeth,
-    plpain,
-    podth1,
-    podth2,
-    popain,
-    posact,
-    posit1,
-    posit2,
-    rlaunc,
-    rxplod,
-    sgtatk,
-    sgtdth,
-    sgtsit,
-    shotgn,
-    skeact,
-    skeatk,
-    skedth,
-    skesit,
-    sklatk,
-    spidth,
-    spisit,
-    ssdth,
-    sssit,
-    vilact,
-    vildth,
-    vilsit,
-    vipain,
+    None = 0,
+    pistol = 1,
+    shotgn = 2,
+    sgcock = 3,
+    dshtgn = 4,
+    dbopn = 5,
+    dbcls = 6,
+    dbload = 7,
+    plasma = 8,
+    bfg = 9,
+    sawup = 10,
+    sawidl = 11,
+    sawful = 12,
+    sawhit = 13,
+    rlaunc = 14,
+    rxplod = 15,
+    firsht = 16,
+    firxpl = 17,
+    pstart = 18,
+    pstop = 19,
+    doropn = 20,
+    dorcls = 21,
+    stnmov = 22,
+    swtchn = 23,
+    swtchx = 24,
+    plpain = 25,
+    dmpain = 26,
+    popain = 27,
+    vipain = 28,
+    mnpain = 29,
+    pepain = 30,
+    slop = 31,
+    itemup = 32,
+    wpnup = 33,
+    oof = 34,
+    telept = 35,
+    posit1 = 36,
+    posit2 = 37,
+    posit3 = 38,
+    bgsit1 = 39,
+    bgsit2 = 40,
+    sgtsit = 41,
+    cacsit = 42,
+    brssit = 43,
+    cybsit = 44,
+    spisit = 45,
+    bspsit = 46,
+    kntsit = 47,
+    vilsit = 48,
+    mansit = 49,
+    pesit = 50,
+    sklatk = 51,
+    sgtatk = 52,
+    skepch = 53,
+    vilatk = 54,
+    claw = 55,
+    skeswg = 56,
+    pldeth = 57,
+    pdiehi = 58,
+    podth1 = 59,
+    podth2 = 60,
+    podth3 = 61,
+    bgdth1 = 62,
+    bgdth2 = 63,
+    sgtdth = 64,
+    cacdth = 65,
+    skldth = 66,
+    brsdth = 67,
+    cybdth = 68,
+    spidth = 69,
+    bspdth = 70,
+    vildth = 71,
+    kntdth = 72,
+    pedth = 73,
+    skedth = 74,
+    posact = 75,
+    bgact = 76,
+    dmact = 77,
+    bspact = 78,
+    bspwlk = 79,
+    vilact = 80,
+    noway = 81,
+    barexp = 82,
+    punch = 83,
+    hoof = 84,
+    metal = 85,
+    chgun = 86,
+    tink = 87,
+    bdopn = 88,
+    bdcls = 89,
+    itmbk = 90,
+    flame = 91,
+    flamst = 92,
+    getpow = 93,
+    bospit = 94,
+    boscub = 95,
+    bossit = 96,
+    bospn = 97,
+    bosdth = 98,
+    manatk = 99,
+    mandth = 100,
+    sssit = 101,
+    ssdth = 102,
+    keenpn = 103,
+    keendt = 104,
+    skeact = 105,
+    skesit = 106,
+    skeatk = 107,
+    radio = 108,
+}
+
+/// One `sfxinfo_t`.
+#[derive(Debug, Copy, Clone)]
+pub struct SoundInfo {
+    pub sound: Sound,
+    /// `DS*` lump with the samples.
+    pub lump: &'static str,
+    /// Never plays twice at once.
+    pub singularity: bool,
+    /// Lower wins when channels run out.
+    pub priority: i32,
+    /// Plays this sound's samples instead (`chgun` uses `pistol`).
+    pub link: Option<Sound>,
+    pub pitch: i32,
+    pub volume: i32,
+}
+
+pub const SOUNDS: &[SoundInfo] = &[
+    SoundInfo {
+        sound: Sound::None,
+        lump: "DSNONE",
+        singularity: false,
+        priority: 0,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pistol,
+        lump: "DSPISTOL",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::shotgn,
+        lump: "DSSHOTGN",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sgcock,
+        lump: "DSSGCOCK",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dshtgn,
+        lump: "DSDSHTGN",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dbopn,
+        lump: "DSDBOPN",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dbcls,
+        lump: "DSDBCLS",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dbload,
+        lump: "DSDBLOAD",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::plasma,
+        lump: "DSPLASMA",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bfg,
+        lump: "DSBFG",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sawup,
+        lump: "DSSAWUP",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sawidl,
+        lump: "DSSAWIDL",
+        singularity: false,
+        priority: 118,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sawful,
+        lump: "DSSAWFUL",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sawhit,
+        lump: "DSSAWHIT",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::rlaunc,
+        lump: "DSRLAUNC",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::rxplod,
+        lump: "DSRXPLOD",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::firsht,
+        lump: "DSFIRSHT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::firxpl,
+        lump: "DSFIRXPL",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pstart,
+        lump: "DSPSTART",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pstop,
+        lump: "DSPSTOP",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::doropn,
+        lump: "DSDOROPN",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dorcls,
+        lump: "DSDORCLS",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::stnmov,
+        lump: "DSSTNMOV",
+        singularity: false,
+        priority: 119,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::swtchn,
+        lump: "DSSWTCHN",
+        singularity: false,
+        priority: 78,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::swtchx,
+        lump: "DSSWTCHX",
+        singularity: false,
+        priority: 78,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::plpain,
+        lump: "DSPLPAIN",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dmpain,
+        lump: "DSDMPAIN",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::popain,
+        lump: "DSPOPAIN",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::vipain,
+        lump: "DSVIPAIN",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::mnpain,
+        lump: "DSMNPAIN",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pepain,
+        lump: "DSPEPAIN",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::slop,
+        lump: "DSSLOP",
+        singularity: false,
+        priority: 78,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::itemup,
+        lump: "DSITEMUP",
+        singularity: true,
+        priority: 78,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::wpnup,
+        lump: "DSWPNUP",
+        singularity: true,
+        priority: 78,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::oof,
+        lump: "DSOOF",
+        singularity: false,
+        priority: 96,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::telept,
+        lump: "DSTELEPT",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::posit1,
+        lump: "DSPOSIT1",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::posit2,
+        lump: "DSPOSIT2",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::posit3,
+        lump: "DSPOSIT3",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bgsit1,
+        lump: "DSBGSIT1",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bgsit2,
+        lump: "DSBGSIT2",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sgtsit,
+        lump: "DSSGTSIT",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::cacsit,
+        lump: "DSCACSIT",
+        singularity: true,
+        priority: 98,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::brssit,
+        lump: "DSBRSSIT",
+        singularity: true,
+        priority: 94,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::cybsit,
+        lump: "DSCYBSIT",
+        singularity: true,
+        priority: 92,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::spisit,
+        lump: "DSSPISIT",
+        singularity: true,
+        priority: 90,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bspsit,
+        lump: "DSBSPSIT",
+        singularity: true,
+        priority: 90,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::kntsit,
+        lump: "DSKNTSIT",
+        singularity: true,
+        priority: 90,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::vilsit,
+        lump: "DSVILSIT",
+        singularity: true,
+        priority: 90,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::mansit,
+        lump: "DSMANSIT",
+        singularity: true,
+        priority: 90,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pesit,
+        lump: "DSPESIT",
+        singularity: true,
+        priority: 90,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sklatk,
+        lump: "DSSKLATK",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sgtatk,
+        lump: "DSSGTATK",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skepch,
+        lump: "DSSKEPCH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::vilatk,
+        lump: "DSVILATK",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::claw,
+        lump: "DSCLAW",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skeswg,
+        lump: "DSSKESWG",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pldeth,
+        lump: "DSPLDETH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pdiehi,
+        lump: "DSPDIEHI",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::podth1,
+        lump: "DSPODTH1",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::podth2,
+        lump: "DSPODTH2",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::podth3,
+        lump: "DSPODTH3",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bgdth1,
+        lump: "DSBGDTH1",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bgdth2,
+        lump: "DSBGDTH2",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sgtdth,
+        lump: "DSSGTDTH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::cacdth,
+        lump: "DSCACDTH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skldth,
+        lump: "DSSKLDTH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::brsdth,
+        lump: "DSBRSDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::cybdth,
+        lump: "DSCYBDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::spidth,
+        lump: "DSSPIDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bspdth,
+        lump: "DSBSPDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::vildth,
+        lump: "DSVILDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::kntdth,
+        lump: "DSKNTDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::pedth,
+        lump: "DSPEDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skedth,
+        lump: "DSSKEDTH",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::posact,
+        lump: "DSPOSACT",
+        singularity: true,
+        priority: 120,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bgact,
+        lump: "DSBGACT",
+        singularity: true,
+        priority: 120,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::dmact,
+        lump: "DSDMACT",
+        singularity: true,
+        priority: 120,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bspact,
+        lump: "DSBSPACT",
+        singularity: true,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bspwlk,
+        lump: "DSBSPWLK",
+        singularity: true,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::vilact,
+        lump: "DSVILACT",
+        singularity: true,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::noway,
+        lump: "DSNOWAY",
+        singularity: false,
+        priority: 78,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::barexp,
+        lump: "DSBAREXP",
+        singularity: false,
+        priority: 60,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::punch,
+        lump: "DSPUNCH",
+        singularity: false,
+        priority: 64,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::hoof,
+        lump: "DSHOOF",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::metal,
+        lump: "DSMETAL",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::chgun,
+        lump: "DSCHGUN",
+        singularity: false,
+        priority: 64,
+        link: Some(Sound::pistol),
+        pitch: 150,
+        volume: 0,
+    },
+    SoundInfo {
+        sound: Sound::tink,
+        lump: "DSTINK",
+        singularity: false,
+        priority: 60,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bdopn,
+        lump: "DSBDOPN",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bdcls,
+        lump: "DSBDCLS",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::itmbk,
+        lump: "DSITMBK",
+        singularity: false,
+        priority: 100,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::flame,
+        lump: "DSFLAME",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::flamst,
+        lump: "DSFLAMST",
+        singularity: false,
+        priority: 32,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::getpow,
+        lump: "DSGETPOW",
+        singularity: false,
+        priority: 60,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bospit,
+        lump: "DSBOSPIT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::boscub,
+        lump: "DSBOSCUB",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bossit,
+        lump: "DSBOSSIT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bospn,
+        lump: "DSBOSPN",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::bosdth,
+        lump: "DSBOSDTH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::manatk,
+        lump: "DSMANATK",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::mandth,
+        lump: "DSMANDTH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::sssit,
+        lump: "DSSSSIT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::ssdth,
+        lump: "DSSSDTH",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::keenpn,
+        lump: "DSKEENPN",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::keendt,
+        lump: "DSKEENDT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skeact,
+        lump: "DSSKEACT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skesit,
+        lump: "DSSKESIT",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::skeatk,
+        lump: "DSSKEATK",
+        singularity: false,
+        priority: 70,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+    SoundInfo {
+        sound: Sound::radio,
+        lump: "DSRADIO",
+        singularity: false,
+        priority: 60,
+        link: None,
+        pitch: -1,
+        volume: -1,
+    },
+];
+
+impl Sound {
+    #[inline(always)]
+    pub fn info(self) -> &'static SoundInfo {
+        &SOUNDS[self as usize]
+    }
+    /// The `DS*` lump to play, following `link`.
+    pub fn lump_name(self) -> &'static str {
+        self.info().link.unwrap_or(self).info().lump
+    }
 }
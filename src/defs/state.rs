@@ -973,6 +973,9 @@ pub enum State {
     TECH2LAMP4 = 966,
 }
 
+/// Lookups in the compiled-in `STATES`; the sim goes through its own
+/// [`Definitions::state`](super::Definitions::state), which a DeHackEd
+/// patch may have changed.
 impl State {
     #[inline(always)]
     pub fn info(self) -> &'static super::states::StateInfo {
@@ -8,6 +8,8 @@ pub struct StateInfo {
     pub state: State,
     pub sprite: &'static str,
     pub frame: u8,
+    /// Drawn at full brightness (vanilla `FF_FULLBRIGHT`).
+    pub bright: bool,
     pub tics: i32,
     pub action: Action,
     pub next_state: State,
@@ -20,6 +22,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::NULL,
         sprite: "TROO",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -30,6 +33,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::LIGHTDONE,
         sprite: "SHTG",
         frame: 4,
+        bright: false,
         tics: 0,
         action: Action::Light0,
         next_state: State::NULL,
@@ -40,6 +44,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCH,
         sprite: "PUNG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::PUNCH,
@@ -50,6 +55,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCHDOWN,
         sprite: "PUNG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::PUNCHDOWN,
@@ -60,6 +66,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCHUP,
         sprite: "PUNG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::PUNCHUP,
@@ -70,6 +77,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCH1,
         sprite: "PUNG",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PUNCH2,
@@ -80,6 +88,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCH2,
         sprite: "PUNG",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::Punch,
         next_state: State::PUNCH3,
@@ -90,6 +99,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCH3,
         sprite: "PUNG",
         frame: 3,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PUNCH4,
@@ -100,6 +110,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCH4,
         sprite: "PUNG",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PUNCH5,
@@ -110,6 +121,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUNCH5,
         sprite: "PUNG",
         frame: 1,
+        bright: false,
         tics: 5,
         action: Action::ReFire,
         next_state: State::PUNCH,
@@ -120,6 +132,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOL,
         sprite: "PISG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::PISTOL,
@@ -130,6 +143,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOLDOWN,
         sprite: "PISG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::PISTOLDOWN,
@@ -140,6 +154,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOLUP,
         sprite: "PISG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::PISTOLUP,
@@ -150,6 +165,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOL1,
         sprite: "PISG",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PISTOL2,
@@ -160,6 +176,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOL2,
         sprite: "PISG",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::FirePistol,
         next_state: State::PISTOL3,
@@ -170,6 +187,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOL3,
         sprite: "PISG",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PISTOL4,
@@ -180,6 +198,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOL4,
         sprite: "PISG",
         frame: 1,
+        bright: false,
         tics: 5,
         action: Action::ReFire,
         next_state: State::PISTOL,
@@ -190,6 +209,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PISTOLFLASH,
         sprite: "PISF",
         frame: 0,
+        bright: false,
         tics: 7,
         action: Action::Light1,
         next_state: State::LIGHTDONE,
@@ -200,6 +220,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::SGUN,
@@ -210,6 +231,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUNDOWN,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::SGUNDOWN,
@@ -220,6 +242,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUNUP,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::SGUNUP,
@@ -230,6 +253,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN1,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::SGUN2,
@@ -240,6 +264,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN2,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 7,
         action: Action::FireShotgun,
         next_state: State::SGUN3,
@@ -250,6 +275,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN3,
         sprite: "SHTG",
         frame: 1,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SGUN4,
@@ -260,6 +286,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN4,
         sprite: "SHTG",
         frame: 2,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SGUN5,
@@ -270,6 +297,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN5,
         sprite: "SHTG",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SGUN6,
@@ -280,6 +308,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN6,
         sprite: "SHTG",
         frame: 2,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SGUN7,
@@ -290,6 +319,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN7,
         sprite: "SHTG",
         frame: 1,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SGUN8,
@@ -300,6 +330,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN8,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::SGUN9,
@@ -310,6 +341,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUN9,
         sprite: "SHTG",
         frame: 0,
+        bright: false,
         tics: 7,
         action: Action::ReFire,
         next_state: State::SGUN,
@@ -320,6 +352,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUNFLASH1,
         sprite: "SHTF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Light1,
         next_state: State::SGUNFLASH2,
@@ -330,6 +363,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SGUNFLASH2,
         sprite: "SHTF",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Light2,
         next_state: State::LIGHTDONE,
@@ -340,6 +374,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::DSGUN,
@@ -350,6 +385,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUNDOWN,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::DSGUNDOWN,
@@ -360,6 +396,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUNUP,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::DSGUNUP,
@@ -370,6 +407,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN1,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::DSGUN2,
@@ -380,6 +418,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN2,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 7,
         action: Action::FireShotgun2,
         next_state: State::DSGUN3,
@@ -390,6 +429,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN3,
         sprite: "SHT2",
         frame: 1,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::DSGUN4,
@@ -400,6 +440,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN4,
         sprite: "SHT2",
         frame: 2,
+        bright: false,
         tics: 7,
         action: Action::CheckReload,
         next_state: State::DSGUN5,
@@ -410,6 +451,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN5,
         sprite: "SHT2",
         frame: 3,
+        bright: false,
         tics: 7,
         action: Action::OpenShotgun2,
         next_state: State::DSGUN6,
@@ -420,6 +462,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN6,
         sprite: "SHT2",
         frame: 4,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::DSGUN7,
@@ -430,6 +473,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN7,
         sprite: "SHT2",
         frame: 5,
+        bright: false,
         tics: 7,
         action: Action::LoadShotgun2,
         next_state: State::DSGUN8,
@@ -440,6 +484,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN8,
         sprite: "SHT2",
         frame: 6,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::DSGUN9,
@@ -450,6 +495,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN9,
         sprite: "SHT2",
         frame: 7,
+        bright: false,
         tics: 6,
         action: Action::CloseShotgun2,
         next_state: State::DSGUN10,
@@ -460,6 +506,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUN10,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::ReFire,
         next_state: State::DSGUN,
@@ -470,6 +517,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSNR1,
         sprite: "SHT2",
         frame: 1,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::DSNR2,
@@ -480,6 +528,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSNR2,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::DSGUNDOWN,
@@ -490,6 +539,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUNFLASH1,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::Light1,
         next_state: State::DSGUNFLASH2,
@@ -500,6 +550,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DSGUNFLASH2,
         sprite: "SHT2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Light2,
         next_state: State::LIGHTDONE,
@@ -510,6 +561,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAIN,
         sprite: "CHGG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::CHAIN,
@@ -520,6 +572,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAINDOWN,
         sprite: "CHGG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::CHAINDOWN,
@@ -530,6 +583,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAINUP,
         sprite: "CHGG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::CHAINUP,
@@ -540,6 +594,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAIN1,
         sprite: "CHGG",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::FireCGun,
         next_state: State::CHAIN2,
@@ -550,6 +605,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAIN2,
         sprite: "CHGG",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::FireCGun,
         next_state: State::CHAIN3,
@@ -560,6 +616,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAIN3,
         sprite: "CHGG",
         frame: 1,
+        bright: false,
         tics: 0,
         action: Action::ReFire,
         next_state: State::CHAIN,
@@ -570,6 +627,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAINFLASH1,
         sprite: "CHGF",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::Light1,
         next_state: State::LIGHTDONE,
@@ -580,6 +638,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CHAINFLASH2,
         sprite: "CHGF",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::Light2,
         next_state: State::LIGHTDONE,
@@ -590,6 +649,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILE,
         sprite: "MISG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::MISSILE,
@@ -600,6 +660,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILEDOWN,
         sprite: "MISG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::MISSILEDOWN,
@@ -610,6 +671,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILEUP,
         sprite: "MISG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::MISSILEUP,
@@ -620,6 +682,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILE1,
         sprite: "MISG",
         frame: 1,
+        bright: false,
         tics: 8,
         action: Action::GunFlash,
         next_state: State::MISSILE2,
@@ -630,6 +693,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILE2,
         sprite: "MISG",
         frame: 1,
+        bright: false,
         tics: 12,
         action: Action::FireMissile,
         next_state: State::MISSILE3,
@@ -640,6 +704,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILE3,
         sprite: "MISG",
         frame: 1,
+        bright: false,
         tics: 0,
         action: Action::ReFire,
         next_state: State::MISSILE,
@@ -650,6 +715,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILEFLASH1,
         sprite: "MISF",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Light1,
         next_state: State::MISSILEFLASH2,
@@ -660,6 +726,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILEFLASH2,
         sprite: "MISF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::MISSILEFLASH3,
@@ -670,6 +737,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILEFLASH3,
         sprite: "MISF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Light2,
         next_state: State::MISSILEFLASH4,
@@ -680,6 +748,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MISSILEFLASH4,
         sprite: "MISF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Light2,
         next_state: State::LIGHTDONE,
@@ -690,6 +759,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAW,
         sprite: "SAWG",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::WeaponReady,
         next_state: State::SAWB,
@@ -700,6 +770,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAWB,
         sprite: "SAWG",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::WeaponReady,
         next_state: State::SAW,
@@ -710,6 +781,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAWDOWN,
         sprite: "SAWG",
         frame: 2,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::SAWDOWN,
@@ -720,6 +792,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAWUP,
         sprite: "SAWG",
         frame: 2,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::SAWUP,
@@ -730,6 +803,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAW1,
         sprite: "SAWG",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Saw,
         next_state: State::SAW2,
@@ -740,6 +814,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAW2,
         sprite: "SAWG",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::Saw,
         next_state: State::SAW3,
@@ -750,6 +825,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SAW3,
         sprite: "SAWG",
         frame: 1,
+        bright: false,
         tics: 0,
         action: Action::ReFire,
         next_state: State::SAW,
@@ -760,6 +836,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMA,
         sprite: "PLSG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::PLASMA,
@@ -770,6 +847,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMADOWN,
         sprite: "PLSG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::PLASMADOWN,
@@ -780,6 +858,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMAUP,
         sprite: "PLSG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::PLASMAUP,
@@ -790,6 +869,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMA1,
         sprite: "PLSG",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::FirePlasma,
         next_state: State::PLASMA2,
@@ -800,6 +880,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMA2,
         sprite: "PLSG",
         frame: 1,
+        bright: false,
         tics: 20,
         action: Action::ReFire,
         next_state: State::PLASMA,
@@ -810,6 +891,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMAFLASH1,
         sprite: "PLSF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Light1,
         next_state: State::LIGHTDONE,
@@ -820,6 +902,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASMAFLASH2,
         sprite: "PLSF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Light1,
         next_state: State::LIGHTDONE,
@@ -830,6 +913,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFG,
         sprite: "BFGG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::WeaponReady,
         next_state: State::BFG,
@@ -840,6 +924,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGDOWN,
         sprite: "BFGG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Lower,
         next_state: State::BFGDOWN,
@@ -850,6 +935,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGUP,
         sprite: "BFGG",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::Raise,
         next_state: State::BFGUP,
@@ -860,6 +946,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFG1,
         sprite: "BFGG",
         frame: 0,
+        bright: false,
         tics: 20,
         action: Action::BFGsound,
         next_state: State::BFG2,
@@ -870,6 +957,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFG2,
         sprite: "BFGG",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::GunFlash,
         next_state: State::BFG3,
@@ -880,6 +968,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFG3,
         sprite: "BFGG",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::FireBFG,
         next_state: State::BFG4,
@@ -890,6 +979,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFG4,
         sprite: "BFGG",
         frame: 1,
+        bright: false,
         tics: 20,
         action: Action::ReFire,
         next_state: State::BFG,
@@ -900,6 +990,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGFLASH1,
         sprite: "BFGF",
         frame: 0,
+        bright: false,
         tics: 11,
         action: Action::Light1,
         next_state: State::BFGFLASH2,
@@ -910,6 +1001,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGFLASH2,
         sprite: "BFGF",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::Light2,
         next_state: State::LIGHTDONE,
@@ -920,6 +1012,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOOD1,
         sprite: "BLUD",
         frame: 2,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BLOOD2,
@@ -930,6 +1023,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOOD2,
         sprite: "BLUD",
         frame: 1,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BLOOD3,
@@ -940,6 +1034,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOOD3,
         sprite: "BLUD",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::NULL,
@@ -950,6 +1045,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUFF1,
         sprite: "PUFF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PUFF2,
@@ -960,6 +1056,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUFF2,
         sprite: "PUFF",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PUFF3,
@@ -970,6 +1067,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUFF3,
         sprite: "PUFF",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PUFF4,
@@ -980,6 +1078,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PUFF4,
         sprite: "PUFF",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::NULL,
@@ -990,6 +1089,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TBALL1,
         sprite: "BAL1",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TBALL2,
@@ -1000,6 +1100,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TBALL2,
         sprite: "BAL1",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TBALL1,
@@ -1010,6 +1111,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TBALLX1,
         sprite: "BAL1",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TBALLX2,
@@ -1020,6 +1122,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TBALLX2,
         sprite: "BAL1",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TBALLX3,
@@ -1030,6 +1133,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TBALLX3,
         sprite: "BAL1",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::NULL,
@@ -1040,6 +1144,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RBALL1,
         sprite: "BAL2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::RBALL2,
@@ -1050,6 +1155,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RBALL2,
         sprite: "BAL2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::RBALL1,
@@ -1060,6 +1166,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RBALLX1,
         sprite: "BAL2",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::RBALLX2,
@@ -1070,6 +1177,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RBALLX2,
         sprite: "BAL2",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::RBALLX3,
@@ -1080,6 +1188,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RBALLX3,
         sprite: "BAL2",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::NULL,
@@ -1090,6 +1199,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASBALL,
         sprite: "PLSS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PLASBALL2,
@@ -1100,6 +1210,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASBALL2,
         sprite: "PLSS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PLASBALL,
@@ -1110,6 +1221,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASEXP,
         sprite: "PLSE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLASEXP2,
@@ -1120,6 +1232,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASEXP2,
         sprite: "PLSE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLASEXP3,
@@ -1130,6 +1243,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASEXP3,
         sprite: "PLSE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLASEXP4,
@@ -1140,6 +1254,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASEXP4,
         sprite: "PLSE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLASEXP5,
@@ -1150,6 +1265,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLASEXP5,
         sprite: "PLSE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::NULL,
@@ -1160,6 +1276,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ROCKET,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::None,
         next_state: State::ROCKET,
@@ -1170,6 +1287,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGSHOT,
         sprite: "BFS1",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BFGSHOT2,
@@ -1180,6 +1298,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGSHOT2,
         sprite: "BFS1",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BFGSHOT,
@@ -1190,6 +1309,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGLAND,
         sprite: "BFE1",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGLAND2,
@@ -1200,6 +1320,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGLAND2,
         sprite: "BFE1",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGLAND3,
@@ -1210,6 +1331,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGLAND3,
         sprite: "BFE1",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::BFGSpray,
         next_state: State::BFGLAND4,
@@ -1220,6 +1342,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGLAND4,
         sprite: "BFE1",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGLAND5,
@@ -1230,6 +1353,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGLAND5,
         sprite: "BFE1",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGLAND6,
@@ -1240,6 +1364,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGLAND6,
         sprite: "BFE1",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::NULL,
@@ -1250,6 +1375,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGEXP,
         sprite: "BFE2",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGEXP2,
@@ -1260,6 +1386,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGEXP2,
         sprite: "BFE2",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGEXP3,
@@ -1270,6 +1397,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGEXP3,
         sprite: "BFE2",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BFGEXP4,
@@ -1280,6 +1408,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFGEXP4,
         sprite: "BFE2",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::NULL,
@@ -1290,6 +1419,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EXPLODE1,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::Explode,
         next_state: State::EXPLODE2,
@@ -1300,6 +1430,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EXPLODE2,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::EXPLODE3,
@@ -1310,6 +1441,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EXPLODE3,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::NULL,
@@ -1320,6 +1452,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG01,
@@ -1330,6 +1463,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG01,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG02,
@@ -1340,6 +1474,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG02,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG2,
@@ -1350,6 +1485,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG2,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG3,
@@ -1360,6 +1496,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG3,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG4,
@@ -1370,6 +1507,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG4,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG5,
@@ -1380,6 +1518,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG5,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG6,
@@ -1390,6 +1529,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG6,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG7,
@@ -1400,6 +1540,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG7,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG8,
@@ -1410,6 +1551,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG8,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG9,
@@ -1420,6 +1562,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG9,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TFOG10,
@@ -1430,6 +1573,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TFOG10,
         sprite: "TFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::NULL,
@@ -1440,6 +1584,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::IFOG01,
@@ -1450,6 +1595,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG01,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::IFOG02,
@@ -1460,6 +1606,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG02,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::IFOG2,
@@ -1470,6 +1617,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG2,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::IFOG3,
@@ -1480,6 +1628,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG3,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::IFOG4,
@@ -1490,6 +1639,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG4,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::IFOG5,
@@ -1500,6 +1650,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::IFOG5,
         sprite: "IFOG",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::NULL,
@@ -1510,6 +1661,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY,
         sprite: "PLAY",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -1520,6 +1672,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_RUN1,
         sprite: "PLAY",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLAY_RUN2,
@@ -1530,6 +1683,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_RUN2,
         sprite: "PLAY",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLAY_RUN3,
@@ -1540,6 +1694,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_RUN3,
         sprite: "PLAY",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLAY_RUN4,
@@ -1550,6 +1705,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_RUN4,
         sprite: "PLAY",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLAY_RUN1,
@@ -1560,6 +1716,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_ATK1,
         sprite: "PLAY",
         frame: 4,
+        bright: false,
         tics: 12,
         action: Action::None,
         next_state: State::PLAY,
@@ -1570,6 +1727,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_ATK2,
         sprite: "PLAY",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PLAY_ATK1,
@@ -1580,6 +1738,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_PAIN,
         sprite: "PLAY",
         frame: 6,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::PLAY_PAIN2,
@@ -1590,6 +1749,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_PAIN2,
         sprite: "PLAY",
         frame: 6,
+        bright: false,
         tics: 4,
         action: Action::Pain,
         next_state: State::PLAY,
@@ -1600,6 +1760,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE1,
         sprite: "PLAY",
         frame: 7,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::PLAY_DIE2,
@@ -1610,6 +1771,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE2,
         sprite: "PLAY",
         frame: 8,
+        bright: false,
         tics: 10,
         action: Action::PlayerScream,
         next_state: State::PLAY_DIE3,
@@ -1620,6 +1782,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE3,
         sprite: "PLAY",
         frame: 9,
+        bright: false,
         tics: 10,
         action: Action::Fall,
         next_state: State::PLAY_DIE4,
@@ -1630,6 +1793,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE4,
         sprite: "PLAY",
         frame: 10,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::PLAY_DIE5,
@@ -1640,6 +1804,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE5,
         sprite: "PLAY",
         frame: 11,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::PLAY_DIE6,
@@ -1650,6 +1815,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE6,
         sprite: "PLAY",
         frame: 12,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::PLAY_DIE7,
@@ -1660,6 +1826,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_DIE7,
         sprite: "PLAY",
         frame: 13,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -1670,6 +1837,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE1,
         sprite: "PLAY",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PLAY_XDIE2,
@@ -1680,6 +1848,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE2,
         sprite: "PLAY",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::XScream,
         next_state: State::PLAY_XDIE3,
@@ -1690,6 +1859,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE3,
         sprite: "PLAY",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::PLAY_XDIE4,
@@ -1700,6 +1870,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE4,
         sprite: "PLAY",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PLAY_XDIE5,
@@ -1710,6 +1881,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE5,
         sprite: "PLAY",
         frame: 18,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PLAY_XDIE6,
@@ -1720,6 +1892,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE6,
         sprite: "PLAY",
         frame: 19,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PLAY_XDIE7,
@@ -1730,6 +1903,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE7,
         sprite: "PLAY",
         frame: 20,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PLAY_XDIE8,
@@ -1740,6 +1914,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE8,
         sprite: "PLAY",
         frame: 21,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::PLAY_XDIE9,
@@ -1750,6 +1925,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAY_XDIE9,
         sprite: "PLAY",
         frame: 22,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -1760,6 +1936,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_STND,
         sprite: "POSS",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::POSS_STND2,
@@ -1770,6 +1947,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_STND2,
         sprite: "POSS",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::POSS_STND,
@@ -1780,6 +1958,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN1,
         sprite: "POSS",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN2,
@@ -1790,6 +1969,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN2,
         sprite: "POSS",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN3,
@@ -1800,6 +1980,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN3,
         sprite: "POSS",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN4,
@@ -1810,6 +1991,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN4,
         sprite: "POSS",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN5,
@@ -1820,6 +2002,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN5,
         sprite: "POSS",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN6,
@@ -1830,6 +2013,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN6,
         sprite: "POSS",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN7,
@@ -1840,6 +2024,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN7,
         sprite: "POSS",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN8,
@@ -1850,6 +2035,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RUN8,
         sprite: "POSS",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::POSS_RUN1,
@@ -1860,6 +2046,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_ATK1,
         sprite: "POSS",
         frame: 4,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::POSS_ATK2,
@@ -1870,6 +2057,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_ATK2,
         sprite: "POSS",
         frame: 5,
+        bright: false,
         tics: 8,
         action: Action::PosAttack,
         next_state: State::POSS_ATK3,
@@ -1880,6 +2068,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_ATK3,
         sprite: "POSS",
         frame: 4,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::POSS_RUN1,
@@ -1890,6 +2079,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_PAIN,
         sprite: "POSS",
         frame: 6,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::POSS_PAIN2,
@@ -1900,6 +2090,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_PAIN2,
         sprite: "POSS",
         frame: 6,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::POSS_RUN1,
@@ -1910,6 +2101,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_DIE1,
         sprite: "POSS",
         frame: 7,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_DIE2,
@@ -1920,6 +2112,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_DIE2,
         sprite: "POSS",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::Scream,
         next_state: State::POSS_DIE3,
@@ -1930,6 +2123,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_DIE3,
         sprite: "POSS",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::POSS_DIE4,
@@ -1940,6 +2134,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_DIE4,
         sprite: "POSS",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_DIE5,
@@ -1950,6 +2145,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_DIE5,
         sprite: "POSS",
         frame: 11,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -1960,6 +2156,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE1,
         sprite: "POSS",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_XDIE2,
@@ -1970,6 +2167,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE2,
         sprite: "POSS",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::XScream,
         next_state: State::POSS_XDIE3,
@@ -1980,6 +2178,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE3,
         sprite: "POSS",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::POSS_XDIE4,
@@ -1990,6 +2189,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE4,
         sprite: "POSS",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_XDIE5,
@@ -2000,6 +2200,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE5,
         sprite: "POSS",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_XDIE6,
@@ -2010,6 +2211,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE6,
         sprite: "POSS",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_XDIE7,
@@ -2020,6 +2222,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE7,
         sprite: "POSS",
         frame: 18,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_XDIE8,
@@ -2030,6 +2233,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE8,
         sprite: "POSS",
         frame: 19,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_XDIE9,
@@ -2040,6 +2244,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_XDIE9,
         sprite: "POSS",
         frame: 20,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -2050,6 +2255,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RAISE1,
         sprite: "POSS",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_RAISE2,
@@ -2060,6 +2266,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RAISE2,
         sprite: "POSS",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_RAISE3,
@@ -2070,6 +2277,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RAISE3,
         sprite: "POSS",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_RAISE4,
@@ -2080,6 +2288,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::POSS_RAISE4,
         sprite: "POSS",
         frame: 7,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::POSS_RUN1,
@@ -2090,6 +2299,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_STND,
         sprite: "SPOS",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SPOS_STND2,
@@ -2100,6 +2310,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_STND2,
         sprite: "SPOS",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SPOS_STND,
@@ -2110,6 +2321,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN1,
         sprite: "SPOS",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN2,
@@ -2120,6 +2332,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN2,
         sprite: "SPOS",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN3,
@@ -2130,6 +2343,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN3,
         sprite: "SPOS",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN4,
@@ -2140,6 +2354,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN4,
         sprite: "SPOS",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN5,
@@ -2150,6 +2365,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN5,
         sprite: "SPOS",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN6,
@@ -2160,6 +2376,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN6,
         sprite: "SPOS",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN7,
@@ -2170,6 +2387,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN7,
         sprite: "SPOS",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN8,
@@ -2180,6 +2398,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RUN8,
         sprite: "SPOS",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPOS_RUN1,
@@ -2190,6 +2409,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_ATK1,
         sprite: "SPOS",
         frame: 4,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::SPOS_ATK2,
@@ -2200,6 +2420,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_ATK2,
         sprite: "SPOS",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::SPosAttack,
         next_state: State::SPOS_ATK3,
@@ -2210,6 +2431,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_ATK3,
         sprite: "SPOS",
         frame: 4,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPOS_RUN1,
@@ -2220,6 +2442,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_PAIN,
         sprite: "SPOS",
         frame: 6,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::SPOS_PAIN2,
@@ -2230,6 +2453,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_PAIN2,
         sprite: "SPOS",
         frame: 6,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::SPOS_RUN1,
@@ -2240,6 +2464,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_DIE1,
         sprite: "SPOS",
         frame: 7,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_DIE2,
@@ -2250,6 +2475,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_DIE2,
         sprite: "SPOS",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::Scream,
         next_state: State::SPOS_DIE3,
@@ -2260,6 +2486,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_DIE3,
         sprite: "SPOS",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::SPOS_DIE4,
@@ -2270,6 +2497,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_DIE4,
         sprite: "SPOS",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_DIE5,
@@ -2280,6 +2508,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_DIE5,
         sprite: "SPOS",
         frame: 11,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -2290,6 +2519,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE1,
         sprite: "SPOS",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_XDIE2,
@@ -2300,6 +2530,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE2,
         sprite: "SPOS",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::XScream,
         next_state: State::SPOS_XDIE3,
@@ -2310,6 +2541,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE3,
         sprite: "SPOS",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::SPOS_XDIE4,
@@ -2320,6 +2552,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE4,
         sprite: "SPOS",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_XDIE5,
@@ -2330,6 +2563,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE5,
         sprite: "SPOS",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_XDIE6,
@@ -2340,6 +2574,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE6,
         sprite: "SPOS",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_XDIE7,
@@ -2350,6 +2585,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE7,
         sprite: "SPOS",
         frame: 18,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_XDIE8,
@@ -2360,6 +2596,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE8,
         sprite: "SPOS",
         frame: 19,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_XDIE9,
@@ -2370,6 +2607,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_XDIE9,
         sprite: "SPOS",
         frame: 20,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -2380,6 +2618,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RAISE1,
         sprite: "SPOS",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_RAISE2,
@@ -2390,6 +2629,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RAISE2,
         sprite: "SPOS",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_RAISE3,
@@ -2400,6 +2640,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RAISE3,
         sprite: "SPOS",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_RAISE4,
@@ -2410,6 +2651,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RAISE4,
         sprite: "SPOS",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_RAISE5,
@@ -2420,6 +2662,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPOS_RAISE5,
         sprite: "SPOS",
         frame: 7,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SPOS_RUN1,
@@ -2430,6 +2673,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_STND,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::VILE_STND2,
@@ -2440,6 +2684,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_STND2,
         sprite: "VILE",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::VILE_STND,
@@ -2450,6 +2695,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN1,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN2,
@@ -2460,6 +2706,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN2,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN3,
@@ -2470,6 +2717,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN3,
         sprite: "VILE",
         frame: 1,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN4,
@@ -2480,6 +2728,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN4,
         sprite: "VILE",
         frame: 1,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN5,
@@ -2490,6 +2739,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN5,
         sprite: "VILE",
         frame: 2,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN6,
@@ -2500,6 +2750,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN6,
         sprite: "VILE",
         frame: 2,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN7,
@@ -2510,6 +2761,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN7,
         sprite: "VILE",
         frame: 3,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN8,
@@ -2520,6 +2772,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN8,
         sprite: "VILE",
         frame: 3,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN9,
@@ -2530,6 +2783,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN9,
         sprite: "VILE",
         frame: 4,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN10,
@@ -2540,6 +2794,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN10,
         sprite: "VILE",
         frame: 4,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN11,
@@ -2550,6 +2805,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN11,
         sprite: "VILE",
         frame: 5,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN12,
@@ -2560,6 +2816,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_RUN12,
         sprite: "VILE",
         frame: 5,
+        bright: false,
         tics: 2,
         action: Action::VileChase,
         next_state: State::VILE_RUN1,
@@ -2570,6 +2827,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK1,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 0,
         action: Action::VileStart,
         next_state: State::VILE_ATK2,
@@ -2580,6 +2838,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK2,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK3,
@@ -2590,6 +2849,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK3,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::VileTarget,
         next_state: State::VILE_ATK4,
@@ -2600,6 +2860,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK4,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK5,
@@ -2610,6 +2871,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK5,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK6,
@@ -2620,6 +2882,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK6,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK7,
@@ -2630,6 +2893,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK7,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK8,
@@ -2640,6 +2904,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK8,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK9,
@@ -2650,6 +2915,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK9,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::VILE_ATK10,
@@ -2660,6 +2926,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK10,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::VileAttack,
         next_state: State::VILE_ATK11,
@@ -2670,6 +2937,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_ATK11,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 20,
         action: Action::None,
         next_state: State::VILE_RUN1,
@@ -2680,6 +2948,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_HEAL1,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::VILE_HEAL2,
@@ -2690,6 +2959,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_HEAL2,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::VILE_HEAL3,
@@ -2700,6 +2970,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_HEAL3,
         sprite: "VILE",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::VILE_RUN1,
@@ -2710,6 +2981,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_PAIN,
         sprite: "VILE",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::VILE_PAIN2,
@@ -2720,6 +2992,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_PAIN2,
         sprite: "VILE",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::Pain,
         next_state: State::VILE_RUN1,
@@ -2730,6 +3003,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE1,
         sprite: "VILE",
         frame: 16,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::VILE_DIE2,
@@ -2740,6 +3014,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE2,
         sprite: "VILE",
         frame: 17,
+        bright: false,
         tics: 7,
         action: Action::Scream,
         next_state: State::VILE_DIE3,
@@ -2750,6 +3025,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE3,
         sprite: "VILE",
         frame: 18,
+        bright: false,
         tics: 7,
         action: Action::Fall,
         next_state: State::VILE_DIE4,
@@ -2760,6 +3036,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE4,
         sprite: "VILE",
         frame: 19,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::VILE_DIE5,
@@ -2770,6 +3047,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE5,
         sprite: "VILE",
         frame: 20,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::VILE_DIE6,
@@ -2780,6 +3058,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE6,
         sprite: "VILE",
         frame: 21,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::VILE_DIE7,
@@ -2790,6 +3069,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE7,
         sprite: "VILE",
         frame: 22,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::VILE_DIE8,
@@ -2800,6 +3080,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE8,
         sprite: "VILE",
         frame: 23,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::VILE_DIE9,
@@ -2810,6 +3091,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE9,
         sprite: "VILE",
         frame: 24,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::VILE_DIE10,
@@ -2820,6 +3102,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::VILE_DIE10,
         sprite: "VILE",
         frame: 25,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -2830,6 +3113,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE1,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::StartFire,
         next_state: State::FIRE2,
@@ -2840,6 +3124,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE2,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE3,
@@ -2850,6 +3135,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE3,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE4,
@@ -2860,6 +3146,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE4,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE5,
@@ -2870,6 +3157,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE5,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::FireCrackle,
         next_state: State::FIRE6,
@@ -2880,6 +3168,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE6,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE7,
@@ -2890,6 +3179,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE7,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE8,
@@ -2900,6 +3190,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE8,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE9,
@@ -2910,6 +3201,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE9,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE10,
@@ -2920,6 +3212,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE10,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE11,
@@ -2930,6 +3223,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE11,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE12,
@@ -2940,6 +3234,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE12,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE13,
@@ -2950,6 +3245,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE13,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE14,
@@ -2960,6 +3256,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE14,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE15,
@@ -2970,6 +3267,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE15,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE16,
@@ -2980,6 +3278,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE16,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE17,
@@ -2990,6 +3289,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE17,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE18,
@@ -3000,6 +3300,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE18,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE19,
@@ -3010,6 +3311,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE19,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::FireCrackle,
         next_state: State::FIRE20,
@@ -3020,6 +3322,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE20,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE21,
@@ -3030,6 +3333,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE21,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE22,
@@ -3040,6 +3344,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE22,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE23,
@@ -3050,6 +3355,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE23,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE24,
@@ -3060,6 +3366,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE24,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE25,
@@ -3070,6 +3377,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE25,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE26,
@@ -3080,6 +3388,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE26,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE27,
@@ -3090,6 +3399,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE27,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE28,
@@ -3100,6 +3410,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE28,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE29,
@@ -3110,6 +3421,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE29,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::FIRE30,
@@ -3120,6 +3432,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FIRE30,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Fire,
         next_state: State::NULL,
@@ -3130,6 +3443,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SMOKE1,
         sprite: "PUFF",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SMOKE2,
@@ -3140,6 +3454,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SMOKE2,
         sprite: "PUFF",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SMOKE3,
@@ -3150,6 +3465,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SMOKE3,
         sprite: "PUFF",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SMOKE4,
@@ -3160,6 +3476,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SMOKE4,
         sprite: "PUFF",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SMOKE5,
@@ -3170,6 +3487,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SMOKE5,
         sprite: "PUFF",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::NULL,
@@ -3180,6 +3498,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TRACER,
         sprite: "FATB",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Tracer,
         next_state: State::TRACER2,
@@ -3190,6 +3509,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TRACER2,
         sprite: "FATB",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Tracer,
         next_state: State::TRACER,
@@ -3200,6 +3520,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TRACEEXP1,
         sprite: "FBXP",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::TRACEEXP2,
@@ -3210,6 +3531,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TRACEEXP2,
         sprite: "FBXP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TRACEEXP3,
@@ -3220,6 +3542,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TRACEEXP3,
         sprite: "FBXP",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::NULL,
@@ -3230,6 +3553,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_STND,
         sprite: "SKEL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SKEL_STND2,
@@ -3240,6 +3564,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_STND2,
         sprite: "SKEL",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SKEL_STND,
@@ -3250,6 +3575,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN1,
         sprite: "SKEL",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN2,
@@ -3260,6 +3586,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN2,
         sprite: "SKEL",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN3,
@@ -3270,6 +3597,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN3,
         sprite: "SKEL",
         frame: 1,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN4,
@@ -3280,6 +3608,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN4,
         sprite: "SKEL",
         frame: 1,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN5,
@@ -3290,6 +3619,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN5,
         sprite: "SKEL",
         frame: 2,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN6,
@@ -3300,6 +3630,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN6,
         sprite: "SKEL",
         frame: 2,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN7,
@@ -3310,6 +3641,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN7,
         sprite: "SKEL",
         frame: 3,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN8,
@@ -3320,6 +3652,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN8,
         sprite: "SKEL",
         frame: 3,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN9,
@@ -3330,6 +3663,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN9,
         sprite: "SKEL",
         frame: 4,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN10,
@@ -3340,6 +3674,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN10,
         sprite: "SKEL",
         frame: 4,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN11,
@@ -3350,6 +3685,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN11,
         sprite: "SKEL",
         frame: 5,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN12,
@@ -3360,6 +3696,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RUN12,
         sprite: "SKEL",
         frame: 5,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SKEL_RUN1,
@@ -3370,6 +3707,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_FIST1,
         sprite: "SKEL",
         frame: 6,
+        bright: false,
         tics: 0,
         action: Action::FaceTarget,
         next_state: State::SKEL_FIST2,
@@ -3380,6 +3718,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_FIST2,
         sprite: "SKEL",
         frame: 6,
+        bright: false,
         tics: 6,
         action: Action::SkelWhoosh,
         next_state: State::SKEL_FIST3,
@@ -3390,6 +3729,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_FIST3,
         sprite: "SKEL",
         frame: 7,
+        bright: false,
         tics: 6,
         action: Action::FaceTarget,
         next_state: State::SKEL_FIST4,
@@ -3400,6 +3740,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_FIST4,
         sprite: "SKEL",
         frame: 8,
+        bright: false,
         tics: 6,
         action: Action::SkelFist,
         next_state: State::SKEL_RUN1,
@@ -3410,6 +3751,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_MISS1,
         sprite: "SKEL",
         frame: 0,
+        bright: false,
         tics: 0,
         action: Action::FaceTarget,
         next_state: State::SKEL_MISS2,
@@ -3420,6 +3762,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_MISS2,
         sprite: "SKEL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::SKEL_MISS3,
@@ -3430,6 +3773,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_MISS3,
         sprite: "SKEL",
         frame: 10,
+        bright: false,
         tics: 10,
         action: Action::SkelMissile,
         next_state: State::SKEL_MISS4,
@@ -3440,6 +3784,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_MISS4,
         sprite: "SKEL",
         frame: 10,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::SKEL_RUN1,
@@ -3450,6 +3795,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_PAIN,
         sprite: "SKEL",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_PAIN2,
@@ -3460,6 +3806,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_PAIN2,
         sprite: "SKEL",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::Pain,
         next_state: State::SKEL_RUN1,
@@ -3470,6 +3817,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_DIE1,
         sprite: "SKEL",
         frame: 11,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::SKEL_DIE2,
@@ -3480,6 +3828,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_DIE2,
         sprite: "SKEL",
         frame: 12,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::SKEL_DIE3,
@@ -3490,6 +3839,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_DIE3,
         sprite: "SKEL",
         frame: 13,
+        bright: false,
         tics: 7,
         action: Action::Scream,
         next_state: State::SKEL_DIE4,
@@ -3500,6 +3850,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_DIE4,
         sprite: "SKEL",
         frame: 14,
+        bright: false,
         tics: 7,
         action: Action::Fall,
         next_state: State::SKEL_DIE5,
@@ -3510,6 +3861,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_DIE5,
         sprite: "SKEL",
         frame: 15,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::SKEL_DIE6,
@@ -3520,6 +3872,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_DIE6,
         sprite: "SKEL",
         frame: 16,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -3530,6 +3883,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RAISE1,
         sprite: "SKEL",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_RAISE2,
@@ -3540,6 +3894,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RAISE2,
         sprite: "SKEL",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_RAISE3,
@@ -3550,6 +3905,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RAISE3,
         sprite: "SKEL",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_RAISE4,
@@ -3560,6 +3916,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RAISE4,
         sprite: "SKEL",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_RAISE5,
@@ -3570,6 +3927,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RAISE5,
         sprite: "SKEL",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_RAISE6,
@@ -3580,6 +3938,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKEL_RAISE6,
         sprite: "SKEL",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SKEL_RUN1,
@@ -3590,6 +3949,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATSHOT1,
         sprite: "MANF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::FATSHOT2,
@@ -3600,6 +3960,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATSHOT2,
         sprite: "MANF",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::FATSHOT1,
@@ -3610,6 +3971,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATSHOTX1,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::FATSHOTX2,
@@ -3620,6 +3982,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATSHOTX2,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATSHOTX3,
@@ -3630,6 +3993,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATSHOTX3,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::NULL,
@@ -3640,6 +4004,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_STND,
         sprite: "FATT",
         frame: 0,
+        bright: false,
         tics: 15,
         action: Action::Look,
         next_state: State::FATT_STND2,
@@ -3650,6 +4015,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_STND2,
         sprite: "FATT",
         frame: 1,
+        bright: false,
         tics: 15,
         action: Action::Look,
         next_state: State::FATT_STND,
@@ -3660,6 +4026,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN1,
         sprite: "FATT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN2,
@@ -3670,6 +4037,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN2,
         sprite: "FATT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN3,
@@ -3680,6 +4048,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN3,
         sprite: "FATT",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN4,
@@ -3690,6 +4059,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN4,
         sprite: "FATT",
         frame: 1,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN5,
@@ -3700,6 +4070,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN5,
         sprite: "FATT",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN6,
@@ -3710,6 +4081,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN6,
         sprite: "FATT",
         frame: 2,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN7,
@@ -3720,6 +4092,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN7,
         sprite: "FATT",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN8,
@@ -3730,6 +4103,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN8,
         sprite: "FATT",
         frame: 3,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN9,
@@ -3740,6 +4114,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN9,
         sprite: "FATT",
         frame: 4,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN10,
@@ -3750,6 +4125,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN10,
         sprite: "FATT",
         frame: 4,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN11,
@@ -3760,6 +4136,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN11,
         sprite: "FATT",
         frame: 5,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN12,
@@ -3770,6 +4147,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RUN12,
         sprite: "FATT",
         frame: 5,
+        bright: false,
         tics: 4,
         action: Action::Chase,
         next_state: State::FATT_RUN1,
@@ -3780,6 +4158,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK1,
         sprite: "FATT",
         frame: 6,
+        bright: false,
         tics: 20,
         action: Action::FatRaise,
         next_state: State::FATT_ATK2,
@@ -3790,6 +4169,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK2,
         sprite: "FATT",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::FatAttack1,
         next_state: State::FATT_ATK3,
@@ -3800,6 +4180,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK3,
         sprite: "FATT",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::FATT_ATK4,
@@ -3810,6 +4191,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK4,
         sprite: "FATT",
         frame: 6,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::FATT_ATK5,
@@ -3820,6 +4202,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK5,
         sprite: "FATT",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::FatAttack2,
         next_state: State::FATT_ATK6,
@@ -3830,6 +4213,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK6,
         sprite: "FATT",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::FATT_ATK7,
@@ -3840,6 +4224,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK7,
         sprite: "FATT",
         frame: 6,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::FATT_ATK8,
@@ -3850,6 +4235,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK8,
         sprite: "FATT",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::FatAttack3,
         next_state: State::FATT_ATK9,
@@ -3860,6 +4246,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK9,
         sprite: "FATT",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::FATT_ATK10,
@@ -3870,6 +4257,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_ATK10,
         sprite: "FATT",
         frame: 6,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::FATT_RUN1,
@@ -3880,6 +4268,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_PAIN,
         sprite: "FATT",
         frame: 9,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::FATT_PAIN2,
@@ -3890,6 +4279,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_PAIN2,
         sprite: "FATT",
         frame: 9,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::FATT_RUN1,
@@ -3900,6 +4290,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE1,
         sprite: "FATT",
         frame: 10,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE2,
@@ -3910,6 +4301,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE2,
         sprite: "FATT",
         frame: 11,
+        bright: false,
         tics: 6,
         action: Action::Scream,
         next_state: State::FATT_DIE3,
@@ -3920,6 +4312,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE3,
         sprite: "FATT",
         frame: 12,
+        bright: false,
         tics: 6,
         action: Action::Fall,
         next_state: State::FATT_DIE4,
@@ -3930,6 +4323,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE4,
         sprite: "FATT",
         frame: 13,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE5,
@@ -3940,6 +4334,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE5,
         sprite: "FATT",
         frame: 14,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE6,
@@ -3950,6 +4345,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE6,
         sprite: "FATT",
         frame: 15,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE7,
@@ -3960,6 +4356,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE7,
         sprite: "FATT",
         frame: 16,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE8,
@@ -3970,6 +4367,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE8,
         sprite: "FATT",
         frame: 17,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE9,
@@ -3980,6 +4378,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE9,
         sprite: "FATT",
         frame: 18,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FATT_DIE10,
@@ -3990,6 +4389,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_DIE10,
         sprite: "FATT",
         frame: 19,
+        bright: false,
         tics: -1,
         action: Action::BossDeath,
         next_state: State::NULL,
@@ -4000,6 +4400,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE1,
         sprite: "FATT",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE2,
@@ -4010,6 +4411,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE2,
         sprite: "FATT",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE3,
@@ -4020,6 +4422,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE3,
         sprite: "FATT",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE4,
@@ -4030,6 +4433,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE4,
         sprite: "FATT",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE5,
@@ -4040,6 +4444,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE5,
         sprite: "FATT",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE6,
@@ -4050,6 +4455,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE6,
         sprite: "FATT",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE7,
@@ -4060,6 +4466,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE7,
         sprite: "FATT",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RAISE8,
@@ -4070,6 +4477,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FATT_RAISE8,
         sprite: "FATT",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::FATT_RUN1,
@@ -4080,6 +4488,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_STND,
         sprite: "CPOS",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::CPOS_STND2,
@@ -4090,6 +4499,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_STND2,
         sprite: "CPOS",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::CPOS_STND,
@@ -4100,6 +4510,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN1,
         sprite: "CPOS",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN2,
@@ -4110,6 +4521,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN2,
         sprite: "CPOS",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN3,
@@ -4120,6 +4532,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN3,
         sprite: "CPOS",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN4,
@@ -4130,6 +4543,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN4,
         sprite: "CPOS",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN5,
@@ -4140,6 +4554,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN5,
         sprite: "CPOS",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN6,
@@ -4150,6 +4565,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN6,
         sprite: "CPOS",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN7,
@@ -4160,6 +4576,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN7,
         sprite: "CPOS",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN8,
@@ -4170,6 +4587,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RUN8,
         sprite: "CPOS",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CPOS_RUN1,
@@ -4180,6 +4598,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_ATK1,
         sprite: "CPOS",
         frame: 4,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::CPOS_ATK2,
@@ -4190,6 +4609,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_ATK2,
         sprite: "CPOS",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::CPosAttack,
         next_state: State::CPOS_ATK3,
@@ -4200,6 +4620,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_ATK3,
         sprite: "CPOS",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::CPosAttack,
         next_state: State::CPOS_ATK4,
@@ -4210,6 +4631,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_ATK4,
         sprite: "CPOS",
         frame: 5,
+        bright: false,
         tics: 1,
         action: Action::CPosRefire,
         next_state: State::CPOS_ATK2,
@@ -4220,6 +4642,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_PAIN,
         sprite: "CPOS",
         frame: 6,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::CPOS_PAIN2,
@@ -4230,6 +4653,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_PAIN2,
         sprite: "CPOS",
         frame: 6,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::CPOS_RUN1,
@@ -4240,6 +4664,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE1,
         sprite: "CPOS",
         frame: 7,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_DIE2,
@@ -4250,6 +4675,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE2,
         sprite: "CPOS",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::Scream,
         next_state: State::CPOS_DIE3,
@@ -4260,6 +4686,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE3,
         sprite: "CPOS",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::CPOS_DIE4,
@@ -4270,6 +4697,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE4,
         sprite: "CPOS",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_DIE5,
@@ -4280,6 +4708,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE5,
         sprite: "CPOS",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_DIE6,
@@ -4290,6 +4719,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE6,
         sprite: "CPOS",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_DIE7,
@@ -4300,6 +4730,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_DIE7,
         sprite: "CPOS",
         frame: 13,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -4310,6 +4741,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_XDIE1,
         sprite: "CPOS",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_XDIE2,
@@ -4320,6 +4752,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_XDIE2,
         sprite: "CPOS",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::XScream,
         next_state: State::CPOS_XDIE3,
@@ -4330,6 +4763,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_XDIE3,
         sprite: "CPOS",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::CPOS_XDIE4,
@@ -4340,6 +4774,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_XDIE4,
         sprite: "CPOS",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_XDIE5,
@@ -4350,6 +4785,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_XDIE5,
         sprite: "CPOS",
         frame: 18,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_XDIE6,
@@ -4360,6 +4796,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_XDIE6,
         sprite: "CPOS",
         frame: 19,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -4370,6 +4807,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE1,
         sprite: "CPOS",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RAISE2,
@@ -4380,6 +4818,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE2,
         sprite: "CPOS",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RAISE3,
@@ -4390,6 +4829,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE3,
         sprite: "CPOS",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RAISE4,
@@ -4400,6 +4840,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE4,
         sprite: "CPOS",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RAISE5,
@@ -4410,6 +4851,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE5,
         sprite: "CPOS",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RAISE6,
@@ -4420,6 +4862,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE6,
         sprite: "CPOS",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RAISE7,
@@ -4430,6 +4873,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CPOS_RAISE7,
         sprite: "CPOS",
         frame: 7,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::CPOS_RUN1,
@@ -4440,6 +4884,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_STND,
         sprite: "TROO",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::TROO_STND2,
@@ -4450,6 +4895,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_STND2,
         sprite: "TROO",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::TROO_STND,
@@ -4460,6 +4906,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN1,
         sprite: "TROO",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN2,
@@ -4470,6 +4917,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN2,
         sprite: "TROO",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN3,
@@ -4480,6 +4928,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN3,
         sprite: "TROO",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN4,
@@ -4490,6 +4939,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN4,
         sprite: "TROO",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN5,
@@ -4500,6 +4950,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN5,
         sprite: "TROO",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN6,
@@ -4510,6 +4961,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN6,
         sprite: "TROO",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN7,
@@ -4520,6 +4972,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN7,
         sprite: "TROO",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN8,
@@ -4530,6 +4983,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RUN8,
         sprite: "TROO",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::TROO_RUN1,
@@ -4540,6 +4994,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_ATK1,
         sprite: "TROO",
         frame: 4,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::TROO_ATK2,
@@ -4550,6 +5005,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_ATK2,
         sprite: "TROO",
         frame: 5,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::TROO_ATK3,
@@ -4560,6 +5016,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_ATK3,
         sprite: "TROO",
         frame: 6,
+        bright: false,
         tics: 6,
         action: Action::TroopAttack,
         next_state: State::TROO_RUN1,
@@ -4570,6 +5027,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_PAIN,
         sprite: "TROO",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::None,
         next_state: State::TROO_PAIN2,
@@ -4580,6 +5038,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_PAIN2,
         sprite: "TROO",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::Pain,
         next_state: State::TROO_RUN1,
@@ -4590,6 +5049,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_DIE1,
         sprite: "TROO",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::TROO_DIE2,
@@ -4600,6 +5060,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_DIE2,
         sprite: "TROO",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::Scream,
         next_state: State::TROO_DIE3,
@@ -4610,6 +5071,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_DIE3,
         sprite: "TROO",
         frame: 10,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TROO_DIE4,
@@ -4620,6 +5082,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_DIE4,
         sprite: "TROO",
         frame: 11,
+        bright: false,
         tics: 6,
         action: Action::Fall,
         next_state: State::TROO_DIE5,
@@ -4630,6 +5093,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_DIE5,
         sprite: "TROO",
         frame: 12,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -4640,6 +5104,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE1,
         sprite: "TROO",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::TROO_XDIE2,
@@ -4650,6 +5115,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE2,
         sprite: "TROO",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::XScream,
         next_state: State::TROO_XDIE3,
@@ -4660,6 +5126,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE3,
         sprite: "TROO",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::TROO_XDIE4,
@@ -4670,6 +5137,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE4,
         sprite: "TROO",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::TROO_XDIE5,
@@ -4680,6 +5148,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE5,
         sprite: "TROO",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::TROO_XDIE6,
@@ -4690,6 +5159,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE6,
         sprite: "TROO",
         frame: 18,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::TROO_XDIE7,
@@ -4700,6 +5170,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE7,
         sprite: "TROO",
         frame: 19,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::TROO_XDIE8,
@@ -4710,6 +5181,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_XDIE8,
         sprite: "TROO",
         frame: 20,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -4720,6 +5192,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RAISE1,
         sprite: "TROO",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::TROO_RAISE2,
@@ -4730,6 +5203,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RAISE2,
         sprite: "TROO",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::TROO_RAISE3,
@@ -4740,6 +5214,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RAISE3,
         sprite: "TROO",
         frame: 10,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TROO_RAISE4,
@@ -4750,6 +5225,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RAISE4,
         sprite: "TROO",
         frame: 9,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TROO_RAISE5,
@@ -4760,6 +5236,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TROO_RAISE5,
         sprite: "TROO",
         frame: 8,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::TROO_RUN1,
@@ -4770,6 +5247,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_STND,
         sprite: "SARG",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SARG_STND2,
@@ -4780,6 +5258,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_STND2,
         sprite: "SARG",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SARG_STND,
@@ -4790,6 +5269,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN1,
         sprite: "SARG",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN2,
@@ -4800,6 +5280,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN2,
         sprite: "SARG",
         frame: 0,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN3,
@@ -4810,6 +5291,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN3,
         sprite: "SARG",
         frame: 1,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN4,
@@ -4820,6 +5302,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN4,
         sprite: "SARG",
         frame: 1,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN5,
@@ -4830,6 +5313,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN5,
         sprite: "SARG",
         frame: 2,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN6,
@@ -4840,6 +5324,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN6,
         sprite: "SARG",
         frame: 2,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN7,
@@ -4850,6 +5335,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN7,
         sprite: "SARG",
         frame: 3,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN8,
@@ -4860,6 +5346,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RUN8,
         sprite: "SARG",
         frame: 3,
+        bright: false,
         tics: 2,
         action: Action::Chase,
         next_state: State::SARG_RUN1,
@@ -4870,6 +5357,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_ATK1,
         sprite: "SARG",
         frame: 4,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::SARG_ATK2,
@@ -4880,6 +5368,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_ATK2,
         sprite: "SARG",
         frame: 5,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::SARG_ATK3,
@@ -4890,6 +5379,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_ATK3,
         sprite: "SARG",
         frame: 6,
+        bright: false,
         tics: 8,
         action: Action::SargAttack,
         next_state: State::SARG_RUN1,
@@ -4900,6 +5390,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_PAIN,
         sprite: "SARG",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::None,
         next_state: State::SARG_PAIN2,
@@ -4910,6 +5401,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_PAIN2,
         sprite: "SARG",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::Pain,
         next_state: State::SARG_RUN1,
@@ -4920,6 +5412,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_DIE1,
         sprite: "SARG",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::SARG_DIE2,
@@ -4930,6 +5423,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_DIE2,
         sprite: "SARG",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::Scream,
         next_state: State::SARG_DIE3,
@@ -4940,6 +5434,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_DIE3,
         sprite: "SARG",
         frame: 10,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SARG_DIE4,
@@ -4950,6 +5445,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_DIE4,
         sprite: "SARG",
         frame: 11,
+        bright: false,
         tics: 4,
         action: Action::Fall,
         next_state: State::SARG_DIE5,
@@ -4960,6 +5456,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_DIE5,
         sprite: "SARG",
         frame: 12,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SARG_DIE6,
@@ -4970,6 +5467,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_DIE6,
         sprite: "SARG",
         frame: 13,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -4980,6 +5478,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RAISE1,
         sprite: "SARG",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SARG_RAISE2,
@@ -4990,6 +5489,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RAISE2,
         sprite: "SARG",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SARG_RAISE3,
@@ -5000,6 +5500,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RAISE3,
         sprite: "SARG",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SARG_RAISE4,
@@ -5010,6 +5511,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RAISE4,
         sprite: "SARG",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SARG_RAISE5,
@@ -5020,6 +5522,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RAISE5,
         sprite: "SARG",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SARG_RAISE6,
@@ -5030,6 +5533,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SARG_RAISE6,
         sprite: "SARG",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SARG_RUN1,
@@ -5040,6 +5544,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_STND,
         sprite: "HEAD",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::HEAD_STND,
@@ -5050,6 +5555,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RUN1,
         sprite: "HEAD",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::HEAD_RUN1,
@@ -5060,6 +5566,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_ATK1,
         sprite: "HEAD",
         frame: 1,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::HEAD_ATK2,
@@ -5070,6 +5577,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_ATK2,
         sprite: "HEAD",
         frame: 2,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::HEAD_ATK3,
@@ -5080,6 +5588,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_ATK3,
         sprite: "HEAD",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::HeadAttack,
         next_state: State::HEAD_RUN1,
@@ -5090,6 +5599,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_PAIN,
         sprite: "HEAD",
         frame: 4,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::HEAD_PAIN2,
@@ -5100,6 +5610,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_PAIN2,
         sprite: "HEAD",
         frame: 4,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::HEAD_PAIN3,
@@ -5110,6 +5621,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_PAIN3,
         sprite: "HEAD",
         frame: 5,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::HEAD_RUN1,
@@ -5120,6 +5632,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_DIE1,
         sprite: "HEAD",
         frame: 6,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_DIE2,
@@ -5130,6 +5643,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_DIE2,
         sprite: "HEAD",
         frame: 7,
+        bright: false,
         tics: 8,
         action: Action::Scream,
         next_state: State::HEAD_DIE3,
@@ -5140,6 +5654,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_DIE3,
         sprite: "HEAD",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_DIE4,
@@ -5150,6 +5665,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_DIE4,
         sprite: "HEAD",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_DIE5,
@@ -5160,6 +5676,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_DIE5,
         sprite: "HEAD",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::Fall,
         next_state: State::HEAD_DIE6,
@@ -5170,6 +5687,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_DIE6,
         sprite: "HEAD",
         frame: 11,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -5180,6 +5698,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RAISE1,
         sprite: "HEAD",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_RAISE2,
@@ -5190,6 +5709,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RAISE2,
         sprite: "HEAD",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_RAISE3,
@@ -5200,6 +5720,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RAISE3,
         sprite: "HEAD",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_RAISE4,
@@ -5210,6 +5731,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RAISE4,
         sprite: "HEAD",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_RAISE5,
@@ -5220,6 +5742,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RAISE5,
         sprite: "HEAD",
         frame: 7,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_RAISE6,
@@ -5230,6 +5753,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEAD_RAISE6,
         sprite: "HEAD",
         frame: 6,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::HEAD_RUN1,
@@ -5240,6 +5764,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRBALL1,
         sprite: "BAL7",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BRBALL2,
@@ -5250,6 +5775,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRBALL2,
         sprite: "BAL7",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BRBALL1,
@@ -5260,6 +5786,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRBALLX1,
         sprite: "BAL7",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BRBALLX2,
@@ -5270,6 +5797,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRBALLX2,
         sprite: "BAL7",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BRBALLX3,
@@ -5280,6 +5808,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRBALLX3,
         sprite: "BAL7",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::NULL,
@@ -5290,6 +5819,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_STND,
         sprite: "BOSS",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BOSS_STND2,
@@ -5300,6 +5830,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_STND2,
         sprite: "BOSS",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BOSS_STND,
@@ -5310,6 +5841,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN1,
         sprite: "BOSS",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN2,
@@ -5320,6 +5852,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN2,
         sprite: "BOSS",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN3,
@@ -5330,6 +5863,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN3,
         sprite: "BOSS",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN4,
@@ -5340,6 +5874,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN4,
         sprite: "BOSS",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN5,
@@ -5350,6 +5885,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN5,
         sprite: "BOSS",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN6,
@@ -5360,6 +5896,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN6,
         sprite: "BOSS",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN7,
@@ -5370,6 +5907,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN7,
         sprite: "BOSS",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN8,
@@ -5380,6 +5918,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RUN8,
         sprite: "BOSS",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOSS_RUN1,
@@ -5390,6 +5929,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_ATK1,
         sprite: "BOSS",
         frame: 4,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::BOSS_ATK2,
@@ -5400,6 +5940,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_ATK2,
         sprite: "BOSS",
         frame: 5,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::BOSS_ATK3,
@@ -5410,6 +5951,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_ATK3,
         sprite: "BOSS",
         frame: 6,
+        bright: false,
         tics: 8,
         action: Action::BruisAttack,
         next_state: State::BOSS_RUN1,
@@ -5420,6 +5962,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_PAIN,
         sprite: "BOSS",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::None,
         next_state: State::BOSS_PAIN2,
@@ -5430,6 +5973,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_PAIN2,
         sprite: "BOSS",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::Pain,
         next_state: State::BOSS_RUN1,
@@ -5440,6 +5984,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE1,
         sprite: "BOSS",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_DIE2,
@@ -5450,6 +5995,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE2,
         sprite: "BOSS",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::Scream,
         next_state: State::BOSS_DIE3,
@@ -5460,6 +6006,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE3,
         sprite: "BOSS",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_DIE4,
@@ -5470,6 +6017,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE4,
         sprite: "BOSS",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::Fall,
         next_state: State::BOSS_DIE5,
@@ -5480,6 +6028,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE5,
         sprite: "BOSS",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_DIE6,
@@ -5490,6 +6039,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE6,
         sprite: "BOSS",
         frame: 13,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_DIE7,
@@ -5500,6 +6050,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_DIE7,
         sprite: "BOSS",
         frame: 14,
+        bright: false,
         tics: -1,
         action: Action::BossDeath,
         next_state: State::NULL,
@@ -5510,6 +6061,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE1,
         sprite: "BOSS",
         frame: 14,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RAISE2,
@@ -5520,6 +6072,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE2,
         sprite: "BOSS",
         frame: 13,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RAISE3,
@@ -5530,6 +6083,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE3,
         sprite: "BOSS",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RAISE4,
@@ -5540,6 +6094,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE4,
         sprite: "BOSS",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RAISE5,
@@ -5550,6 +6105,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE5,
         sprite: "BOSS",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RAISE6,
@@ -5560,6 +6116,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE6,
         sprite: "BOSS",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RAISE7,
@@ -5570,6 +6127,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOSS_RAISE7,
         sprite: "BOSS",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOSS_RUN1,
@@ -5580,6 +6138,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_STND,
         sprite: "BOS2",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BOS2_STND2,
@@ -5590,6 +6149,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_STND2,
         sprite: "BOS2",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BOS2_STND,
@@ -5600,6 +6160,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN1,
         sprite: "BOS2",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN2,
@@ -5610,6 +6171,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN2,
         sprite: "BOS2",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN3,
@@ -5620,6 +6182,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN3,
         sprite: "BOS2",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN4,
@@ -5630,6 +6193,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN4,
         sprite: "BOS2",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN5,
@@ -5640,6 +6204,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN5,
         sprite: "BOS2",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN6,
@@ -5650,6 +6215,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN6,
         sprite: "BOS2",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN7,
@@ -5660,6 +6226,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN7,
         sprite: "BOS2",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN8,
@@ -5670,6 +6237,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RUN8,
         sprite: "BOS2",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BOS2_RUN1,
@@ -5680,6 +6248,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_ATK1,
         sprite: "BOS2",
         frame: 4,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::BOS2_ATK2,
@@ -5690,6 +6259,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_ATK2,
         sprite: "BOS2",
         frame: 5,
+        bright: false,
         tics: 8,
         action: Action::FaceTarget,
         next_state: State::BOS2_ATK3,
@@ -5700,6 +6270,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_ATK3,
         sprite: "BOS2",
         frame: 6,
+        bright: false,
         tics: 8,
         action: Action::BruisAttack,
         next_state: State::BOS2_RUN1,
@@ -5710,6 +6281,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_PAIN,
         sprite: "BOS2",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::None,
         next_state: State::BOS2_PAIN2,
@@ -5720,6 +6292,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_PAIN2,
         sprite: "BOS2",
         frame: 7,
+        bright: false,
         tics: 2,
         action: Action::Pain,
         next_state: State::BOS2_RUN1,
@@ -5730,6 +6303,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE1,
         sprite: "BOS2",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_DIE2,
@@ -5740,6 +6314,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE2,
         sprite: "BOS2",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::Scream,
         next_state: State::BOS2_DIE3,
@@ -5750,6 +6325,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE3,
         sprite: "BOS2",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_DIE4,
@@ -5760,6 +6336,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE4,
         sprite: "BOS2",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::Fall,
         next_state: State::BOS2_DIE5,
@@ -5770,6 +6347,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE5,
         sprite: "BOS2",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_DIE6,
@@ -5780,6 +6358,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE6,
         sprite: "BOS2",
         frame: 13,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_DIE7,
@@ -5790,6 +6369,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_DIE7,
         sprite: "BOS2",
         frame: 14,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -5800,6 +6380,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE1,
         sprite: "BOS2",
         frame: 14,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RAISE2,
@@ -5810,6 +6391,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE2,
         sprite: "BOS2",
         frame: 13,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RAISE3,
@@ -5820,6 +6402,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE3,
         sprite: "BOS2",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RAISE4,
@@ -5830,6 +6413,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE4,
         sprite: "BOS2",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RAISE5,
@@ -5840,6 +6424,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE5,
         sprite: "BOS2",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RAISE6,
@@ -5850,6 +6435,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE6,
         sprite: "BOS2",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RAISE7,
@@ -5860,6 +6446,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BOS2_RAISE7,
         sprite: "BOS2",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BOS2_RUN1,
@@ -5870,6 +6457,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_STND,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SKULL_STND2,
@@ -5880,6 +6468,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_STND2,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SKULL_STND,
@@ -5890,6 +6479,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_RUN1,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::Chase,
         next_state: State::SKULL_RUN2,
@@ -5900,6 +6490,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_RUN2,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::Chase,
         next_state: State::SKULL_RUN1,
@@ -5910,6 +6501,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_ATK1,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::SKULL_ATK2,
@@ -5920,6 +6512,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_ATK2,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::SkullAttack,
         next_state: State::SKULL_ATK3,
@@ -5930,6 +6523,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_ATK3,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SKULL_ATK4,
@@ -5940,6 +6534,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_ATK4,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::SKULL_ATK3,
@@ -5950,6 +6545,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_PAIN,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::SKULL_PAIN2,
@@ -5960,6 +6556,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_PAIN2,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::SKULL_RUN1,
@@ -5970,6 +6567,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_DIE1,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SKULL_DIE2,
@@ -5980,6 +6578,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_DIE2,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::Scream,
         next_state: State::SKULL_DIE3,
@@ -5990,6 +6589,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_DIE3,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SKULL_DIE4,
@@ -6000,6 +6600,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_DIE4,
         sprite: "SKUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::Fall,
         next_state: State::SKULL_DIE5,
@@ -6010,6 +6611,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_DIE5,
         sprite: "SKUL",
         frame: 9,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SKULL_DIE6,
@@ -6020,6 +6622,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULL_DIE6,
         sprite: "SKUL",
         frame: 10,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::NULL,
@@ -6030,6 +6633,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_STND,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SPID_STND2,
@@ -6040,6 +6644,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_STND2,
         sprite: "SPID",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SPID_STND,
@@ -6050,6 +6655,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN1,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Metal,
         next_state: State::SPID_RUN2,
@@ -6060,6 +6666,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN2,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN3,
@@ -6070,6 +6677,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN3,
         sprite: "SPID",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN4,
@@ -6080,6 +6688,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN4,
         sprite: "SPID",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN5,
@@ -6090,6 +6699,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN5,
         sprite: "SPID",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Metal,
         next_state: State::SPID_RUN6,
@@ -6100,6 +6710,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN6,
         sprite: "SPID",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN7,
@@ -6110,6 +6721,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN7,
         sprite: "SPID",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN8,
@@ -6120,6 +6732,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN8,
         sprite: "SPID",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN9,
@@ -6130,6 +6743,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN9,
         sprite: "SPID",
         frame: 4,
+        bright: false,
         tics: 3,
         action: Action::Metal,
         next_state: State::SPID_RUN10,
@@ -6140,6 +6754,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN10,
         sprite: "SPID",
         frame: 4,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN11,
@@ -6150,6 +6765,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN11,
         sprite: "SPID",
         frame: 5,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN12,
@@ -6160,6 +6776,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_RUN12,
         sprite: "SPID",
         frame: 5,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SPID_RUN1,
@@ -6170,6 +6787,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_ATK1,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 20,
         action: Action::FaceTarget,
         next_state: State::SPID_ATK2,
@@ -6180,6 +6798,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_ATK2,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::SPosAttack,
         next_state: State::SPID_ATK3,
@@ -6190,6 +6809,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_ATK3,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::SPosAttack,
         next_state: State::SPID_ATK4,
@@ -6200,6 +6820,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_ATK4,
         sprite: "SPID",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::SpidRefire,
         next_state: State::SPID_ATK2,
@@ -6210,6 +6831,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_PAIN,
         sprite: "SPID",
         frame: 8,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::SPID_PAIN2,
@@ -6220,6 +6842,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_PAIN2,
         sprite: "SPID",
         frame: 8,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::SPID_RUN1,
@@ -6230,6 +6853,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE1,
         sprite: "SPID",
         frame: 9,
+        bright: false,
         tics: 20,
         action: Action::Scream,
         next_state: State::SPID_DIE2,
@@ -6240,6 +6864,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE2,
         sprite: "SPID",
         frame: 10,
+        bright: false,
         tics: 10,
         action: Action::Fall,
         next_state: State::SPID_DIE3,
@@ -6250,6 +6875,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE3,
         sprite: "SPID",
         frame: 11,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE4,
@@ -6260,6 +6886,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE4,
         sprite: "SPID",
         frame: 12,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE5,
@@ -6270,6 +6897,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE5,
         sprite: "SPID",
         frame: 13,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE6,
@@ -6280,6 +6908,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE6,
         sprite: "SPID",
         frame: 14,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE7,
@@ -6290,6 +6919,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE7,
         sprite: "SPID",
         frame: 15,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE8,
@@ -6300,6 +6930,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE8,
         sprite: "SPID",
         frame: 16,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE9,
@@ -6310,6 +6941,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE9,
         sprite: "SPID",
         frame: 17,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::SPID_DIE10,
@@ -6320,6 +6952,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE10,
         sprite: "SPID",
         frame: 18,
+        bright: false,
         tics: 30,
         action: Action::None,
         next_state: State::SPID_DIE11,
@@ -6330,6 +6963,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPID_DIE11,
         sprite: "SPID",
         frame: 18,
+        bright: false,
         tics: -1,
         action: Action::BossDeath,
         next_state: State::NULL,
@@ -6340,6 +6974,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_STND,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BSPI_STND2,
@@ -6350,6 +6985,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_STND2,
         sprite: "BSPI",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BSPI_STND,
@@ -6360,6 +6996,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_SIGHT,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 20,
         action: Action::None,
         next_state: State::BSPI_RUN1,
@@ -6370,6 +7007,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN1,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::BabyMetal,
         next_state: State::BSPI_RUN2,
@@ -6380,6 +7018,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN2,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN3,
@@ -6390,6 +7029,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN3,
         sprite: "BSPI",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN4,
@@ -6400,6 +7040,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN4,
         sprite: "BSPI",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN5,
@@ -6410,6 +7051,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN5,
         sprite: "BSPI",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN6,
@@ -6420,6 +7062,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN6,
         sprite: "BSPI",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN7,
@@ -6430,6 +7073,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN7,
         sprite: "BSPI",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::BabyMetal,
         next_state: State::BSPI_RUN8,
@@ -6440,6 +7084,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN8,
         sprite: "BSPI",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN9,
@@ -6450,6 +7095,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN9,
         sprite: "BSPI",
         frame: 4,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN10,
@@ -6460,6 +7106,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN10,
         sprite: "BSPI",
         frame: 4,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN11,
@@ -6470,6 +7117,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN11,
         sprite: "BSPI",
         frame: 5,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN12,
@@ -6480,6 +7128,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RUN12,
         sprite: "BSPI",
         frame: 5,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::BSPI_RUN1,
@@ -6490,6 +7139,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_ATK1,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 20,
         action: Action::FaceTarget,
         next_state: State::BSPI_ATK2,
@@ -6500,6 +7150,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_ATK2,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::BspiAttack,
         next_state: State::BSPI_ATK3,
@@ -6510,6 +7161,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_ATK3,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BSPI_ATK4,
@@ -6520,6 +7172,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_ATK4,
         sprite: "BSPI",
         frame: 0,
+        bright: false,
         tics: 1,
         action: Action::SpidRefire,
         next_state: State::BSPI_ATK2,
@@ -6530,6 +7183,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_PAIN,
         sprite: "BSPI",
         frame: 8,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::BSPI_PAIN2,
@@ -6540,6 +7194,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_PAIN2,
         sprite: "BSPI",
         frame: 8,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::BSPI_RUN1,
@@ -6550,6 +7205,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE1,
         sprite: "BSPI",
         frame: 9,
+        bright: false,
         tics: 20,
         action: Action::Scream,
         next_state: State::BSPI_DIE2,
@@ -6560,6 +7216,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE2,
         sprite: "BSPI",
         frame: 10,
+        bright: false,
         tics: 7,
         action: Action::Fall,
         next_state: State::BSPI_DIE3,
@@ -6570,6 +7227,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE3,
         sprite: "BSPI",
         frame: 11,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::BSPI_DIE4,
@@ -6580,6 +7238,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE4,
         sprite: "BSPI",
         frame: 12,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::BSPI_DIE5,
@@ -6590,6 +7249,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE5,
         sprite: "BSPI",
         frame: 13,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::BSPI_DIE6,
@@ -6600,6 +7260,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE6,
         sprite: "BSPI",
         frame: 14,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::BSPI_DIE7,
@@ -6610,6 +7271,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_DIE7,
         sprite: "BSPI",
         frame: 15,
+        bright: false,
         tics: -1,
         action: Action::BossDeath,
         next_state: State::NULL,
@@ -6620,6 +7282,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE1,
         sprite: "BSPI",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RAISE2,
@@ -6630,6 +7293,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE2,
         sprite: "BSPI",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RAISE3,
@@ -6640,6 +7304,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE3,
         sprite: "BSPI",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RAISE4,
@@ -6650,6 +7315,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE4,
         sprite: "BSPI",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RAISE5,
@@ -6660,6 +7326,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE5,
         sprite: "BSPI",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RAISE6,
@@ -6670,6 +7337,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE6,
         sprite: "BSPI",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RAISE7,
@@ -6680,6 +7348,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSPI_RAISE7,
         sprite: "BSPI",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BSPI_RUN1,
@@ -6690,6 +7359,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLAZ,
         sprite: "APLS",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::ARACH_PLAZ2,
@@ -6700,6 +7370,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLAZ2,
         sprite: "APLS",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::ARACH_PLAZ,
@@ -6710,6 +7381,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLEX,
         sprite: "APBX",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::ARACH_PLEX2,
@@ -6720,6 +7392,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLEX2,
         sprite: "APBX",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::ARACH_PLEX3,
@@ -6730,6 +7403,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLEX3,
         sprite: "APBX",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::ARACH_PLEX4,
@@ -6740,6 +7414,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLEX4,
         sprite: "APBX",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::ARACH_PLEX5,
@@ -6750,6 +7425,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARACH_PLEX5,
         sprite: "APBX",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::NULL,
@@ -6760,6 +7436,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_STND,
         sprite: "CYBR",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::CYBER_STND2,
@@ -6770,6 +7447,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_STND2,
         sprite: "CYBR",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::CYBER_STND,
@@ -6780,6 +7458,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN1,
         sprite: "CYBR",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Hoof,
         next_state: State::CYBER_RUN2,
@@ -6790,6 +7469,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN2,
         sprite: "CYBR",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CYBER_RUN3,
@@ -6800,6 +7480,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN3,
         sprite: "CYBR",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CYBER_RUN4,
@@ -6810,6 +7491,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN4,
         sprite: "CYBR",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CYBER_RUN5,
@@ -6820,6 +7502,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN5,
         sprite: "CYBR",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CYBER_RUN6,
@@ -6830,6 +7513,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN6,
         sprite: "CYBR",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CYBER_RUN7,
@@ -6840,6 +7524,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN7,
         sprite: "CYBR",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Metal,
         next_state: State::CYBER_RUN8,
@@ -6850,6 +7535,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_RUN8,
         sprite: "CYBR",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::CYBER_RUN1,
@@ -6860,6 +7546,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_ATK1,
         sprite: "CYBR",
         frame: 4,
+        bright: false,
         tics: 6,
         action: Action::FaceTarget,
         next_state: State::CYBER_ATK2,
@@ -6870,6 +7557,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_ATK2,
         sprite: "CYBR",
         frame: 5,
+        bright: false,
         tics: 12,
         action: Action::CyberAttack,
         next_state: State::CYBER_ATK3,
@@ -6880,6 +7568,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_ATK3,
         sprite: "CYBR",
         frame: 4,
+        bright: false,
         tics: 12,
         action: Action::FaceTarget,
         next_state: State::CYBER_ATK4,
@@ -6890,6 +7579,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_ATK4,
         sprite: "CYBR",
         frame: 5,
+        bright: false,
         tics: 12,
         action: Action::CyberAttack,
         next_state: State::CYBER_ATK5,
@@ -6900,6 +7590,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_ATK5,
         sprite: "CYBR",
         frame: 4,
+        bright: false,
         tics: 12,
         action: Action::FaceTarget,
         next_state: State::CYBER_ATK6,
@@ -6910,6 +7601,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_ATK6,
         sprite: "CYBR",
         frame: 5,
+        bright: false,
         tics: 12,
         action: Action::CyberAttack,
         next_state: State::CYBER_RUN1,
@@ -6920,6 +7612,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_PAIN,
         sprite: "CYBR",
         frame: 6,
+        bright: false,
         tics: 10,
         action: Action::Pain,
         next_state: State::CYBER_RUN1,
@@ -6930,6 +7623,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE1,
         sprite: "CYBR",
         frame: 7,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::CYBER_DIE2,
@@ -6940,6 +7634,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE2,
         sprite: "CYBR",
         frame: 8,
+        bright: false,
         tics: 10,
         action: Action::Scream,
         next_state: State::CYBER_DIE3,
@@ -6950,6 +7645,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE3,
         sprite: "CYBR",
         frame: 9,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::CYBER_DIE4,
@@ -6960,6 +7656,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE4,
         sprite: "CYBR",
         frame: 10,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::CYBER_DIE5,
@@ -6970,6 +7667,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE5,
         sprite: "CYBR",
         frame: 11,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::CYBER_DIE6,
@@ -6980,6 +7678,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE6,
         sprite: "CYBR",
         frame: 12,
+        bright: false,
         tics: 10,
         action: Action::Fall,
         next_state: State::CYBER_DIE7,
@@ -6990,6 +7689,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE7,
         sprite: "CYBR",
         frame: 13,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::CYBER_DIE8,
@@ -7000,6 +7700,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE8,
         sprite: "CYBR",
         frame: 14,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::CYBER_DIE9,
@@ -7010,6 +7711,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE9,
         sprite: "CYBR",
         frame: 15,
+        bright: false,
         tics: 30,
         action: Action::None,
         next_state: State::CYBER_DIE10,
@@ -7020,6 +7722,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CYBER_DIE10,
         sprite: "CYBR",
         frame: 15,
+        bright: false,
         tics: -1,
         action: Action::BossDeath,
         next_state: State::NULL,
@@ -7030,6 +7733,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_STND,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::PAIN_STND,
@@ -7040,6 +7744,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RUN1,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::PAIN_RUN2,
@@ -7050,6 +7755,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RUN2,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::PAIN_RUN3,
@@ -7060,6 +7766,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RUN3,
         sprite: "PAIN",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::PAIN_RUN4,
@@ -7070,6 +7777,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RUN4,
         sprite: "PAIN",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::PAIN_RUN5,
@@ -7080,6 +7788,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RUN5,
         sprite: "PAIN",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::PAIN_RUN6,
@@ -7090,6 +7799,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RUN6,
         sprite: "PAIN",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::PAIN_RUN1,
@@ -7100,6 +7810,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_ATK1,
         sprite: "PAIN",
         frame: 3,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::PAIN_ATK2,
@@ -7110,6 +7821,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_ATK2,
         sprite: "PAIN",
         frame: 4,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::PAIN_ATK3,
@@ -7120,6 +7832,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_ATK3,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::FaceTarget,
         next_state: State::PAIN_ATK4,
@@ -7130,6 +7843,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_ATK4,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 0,
         action: Action::PainAttack,
         next_state: State::PAIN_RUN1,
@@ -7140,6 +7854,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_PAIN,
         sprite: "PAIN",
         frame: 6,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PAIN_PAIN2,
@@ -7150,6 +7865,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_PAIN2,
         sprite: "PAIN",
         frame: 6,
+        bright: false,
         tics: 6,
         action: Action::Pain,
         next_state: State::PAIN_RUN1,
@@ -7160,6 +7876,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_DIE1,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_DIE2,
@@ -7170,6 +7887,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_DIE2,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::Scream,
         next_state: State::PAIN_DIE3,
@@ -7180,6 +7898,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_DIE3,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_DIE4,
@@ -7190,6 +7909,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_DIE4,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_DIE5,
@@ -7200,6 +7920,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_DIE5,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::PainDie,
         next_state: State::PAIN_DIE6,
@@ -7210,6 +7931,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_DIE6,
         sprite: "PAIN",
         frame: 0,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::NULL,
@@ -7220,6 +7942,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RAISE1,
         sprite: "PAIN",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_RAISE2,
@@ -7230,6 +7953,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RAISE2,
         sprite: "PAIN",
         frame: 11,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_RAISE3,
@@ -7240,6 +7964,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RAISE3,
         sprite: "PAIN",
         frame: 10,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_RAISE4,
@@ -7250,6 +7975,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RAISE4,
         sprite: "PAIN",
         frame: 9,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_RAISE5,
@@ -7260,6 +7986,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RAISE5,
         sprite: "PAIN",
         frame: 8,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_RAISE6,
@@ -7270,6 +7997,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PAIN_RAISE6,
         sprite: "PAIN",
         frame: 7,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::PAIN_RUN1,
@@ -7280,6 +8008,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_STND,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SSWV_STND2,
@@ -7290,6 +8019,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_STND2,
         sprite: "SSWV",
         frame: 1,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::SSWV_STND,
@@ -7300,6 +8030,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN1,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN2,
@@ -7310,6 +8041,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN2,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN3,
@@ -7320,6 +8052,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN3,
         sprite: "SSWV",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN4,
@@ -7330,6 +8063,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN4,
         sprite: "SSWV",
         frame: 1,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN5,
@@ -7340,6 +8074,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN5,
         sprite: "SSWV",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN6,
@@ -7350,6 +8085,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN6,
         sprite: "SSWV",
         frame: 2,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN7,
@@ -7360,6 +8096,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN7,
         sprite: "SSWV",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN8,
@@ -7370,6 +8107,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RUN8,
         sprite: "SSWV",
         frame: 3,
+        bright: false,
         tics: 3,
         action: Action::Chase,
         next_state: State::SSWV_RUN1,
@@ -7380,6 +8118,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_ATK1,
         sprite: "SSWV",
         frame: 4,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::SSWV_ATK2,
@@ -7390,6 +8129,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_ATK2,
         sprite: "SSWV",
         frame: 5,
+        bright: false,
         tics: 10,
         action: Action::FaceTarget,
         next_state: State::SSWV_ATK3,
@@ -7400,6 +8140,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_ATK3,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::CPosAttack,
         next_state: State::SSWV_ATK4,
@@ -7410,6 +8151,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_ATK4,
         sprite: "SSWV",
         frame: 5,
+        bright: false,
         tics: 6,
         action: Action::FaceTarget,
         next_state: State::SSWV_ATK5,
@@ -7420,6 +8162,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_ATK5,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::CPosAttack,
         next_state: State::SSWV_ATK6,
@@ -7430,6 +8173,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_ATK6,
         sprite: "SSWV",
         frame: 5,
+        bright: false,
         tics: 1,
         action: Action::CPosRefire,
         next_state: State::SSWV_ATK2,
@@ -7440,6 +8184,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_PAIN,
         sprite: "SSWV",
         frame: 7,
+        bright: false,
         tics: 3,
         action: Action::None,
         next_state: State::SSWV_PAIN2,
@@ -7450,6 +8195,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_PAIN2,
         sprite: "SSWV",
         frame: 7,
+        bright: false,
         tics: 3,
         action: Action::Pain,
         next_state: State::SSWV_RUN1,
@@ -7460,6 +8206,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_DIE1,
         sprite: "SSWV",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_DIE2,
@@ -7470,6 +8217,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_DIE2,
         sprite: "SSWV",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::Scream,
         next_state: State::SSWV_DIE3,
@@ -7480,6 +8228,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_DIE3,
         sprite: "SSWV",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::SSWV_DIE4,
@@ -7490,6 +8239,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_DIE4,
         sprite: "SSWV",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_DIE5,
@@ -7500,6 +8250,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_DIE5,
         sprite: "SSWV",
         frame: 12,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -7510,6 +8261,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE1,
         sprite: "SSWV",
         frame: 13,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_XDIE2,
@@ -7520,6 +8272,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE2,
         sprite: "SSWV",
         frame: 14,
+        bright: false,
         tics: 5,
         action: Action::XScream,
         next_state: State::SSWV_XDIE3,
@@ -7530,6 +8283,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE3,
         sprite: "SSWV",
         frame: 15,
+        bright: false,
         tics: 5,
         action: Action::Fall,
         next_state: State::SSWV_XDIE4,
@@ -7540,6 +8294,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE4,
         sprite: "SSWV",
         frame: 16,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_XDIE5,
@@ -7550,6 +8305,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE5,
         sprite: "SSWV",
         frame: 17,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_XDIE6,
@@ -7560,6 +8316,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE6,
         sprite: "SSWV",
         frame: 18,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_XDIE7,
@@ -7570,6 +8327,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE7,
         sprite: "SSWV",
         frame: 19,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_XDIE8,
@@ -7580,6 +8338,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE8,
         sprite: "SSWV",
         frame: 20,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_XDIE9,
@@ -7590,6 +8349,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_XDIE9,
         sprite: "SSWV",
         frame: 21,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -7600,6 +8360,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RAISE1,
         sprite: "SSWV",
         frame: 12,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_RAISE2,
@@ -7610,6 +8371,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RAISE2,
         sprite: "SSWV",
         frame: 11,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_RAISE3,
@@ -7620,6 +8382,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RAISE3,
         sprite: "SSWV",
         frame: 10,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_RAISE4,
@@ -7630,6 +8393,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RAISE4,
         sprite: "SSWV",
         frame: 9,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_RAISE5,
@@ -7640,6 +8404,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SSWV_RAISE5,
         sprite: "SSWV",
         frame: 8,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::SSWV_RUN1,
@@ -7650,6 +8415,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::KEENSTND,
         sprite: "KEEN",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::KEENSTND,
@@ -7660,6 +8426,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN,
         sprite: "KEEN",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN2,
@@ -7670,6 +8437,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN2,
         sprite: "KEEN",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN3,
@@ -7680,6 +8448,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN3,
         sprite: "KEEN",
         frame: 2,
+        bright: false,
         tics: 6,
         action: Action::Scream,
         next_state: State::COMMKEEN4,
@@ -7690,6 +8459,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN4,
         sprite: "KEEN",
         frame: 3,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN5,
@@ -7700,6 +8470,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN5,
         sprite: "KEEN",
         frame: 4,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN6,
@@ -7710,6 +8481,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN6,
         sprite: "KEEN",
         frame: 5,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN7,
@@ -7720,6 +8492,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN7,
         sprite: "KEEN",
         frame: 6,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN8,
@@ -7730,6 +8503,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN8,
         sprite: "KEEN",
         frame: 7,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN9,
@@ -7740,6 +8514,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN9,
         sprite: "KEEN",
         frame: 8,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN10,
@@ -7750,6 +8525,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN10,
         sprite: "KEEN",
         frame: 9,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::COMMKEEN11,
@@ -7760,6 +8536,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN11,
         sprite: "KEEN",
         frame: 10,
+        bright: false,
         tics: 6,
         action: Action::KeenDie,
         next_state: State::COMMKEEN12,
@@ -7770,6 +8547,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COMMKEEN12,
         sprite: "KEEN",
         frame: 11,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -7780,6 +8558,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::KEENPAIN,
         sprite: "KEEN",
         frame: 12,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::KEENPAIN2,
@@ -7790,6 +8569,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::KEENPAIN2,
         sprite: "KEEN",
         frame: 12,
+        bright: false,
         tics: 8,
         action: Action::Pain,
         next_state: State::KEENSTND,
@@ -7800,6 +8580,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAIN,
         sprite: "BBRN",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -7810,6 +8591,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAIN_PAIN,
         sprite: "BBRN",
         frame: 1,
+        bright: false,
         tics: 36,
         action: Action::BrainPain,
         next_state: State::BRAIN,
@@ -7820,6 +8602,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAIN_DIE1,
         sprite: "BBRN",
         frame: 0,
+        bright: false,
         tics: 100,
         action: Action::BrainScream,
         next_state: State::BRAIN_DIE2,
@@ -7830,6 +8613,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAIN_DIE2,
         sprite: "BBRN",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BRAIN_DIE3,
@@ -7840,6 +8624,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAIN_DIE3,
         sprite: "BBRN",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BRAIN_DIE4,
@@ -7850,6 +8635,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAIN_DIE4,
         sprite: "BBRN",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::BrainDie,
         next_state: State::NULL,
@@ -7860,6 +8646,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINEYE,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Look,
         next_state: State::BRAINEYE,
@@ -7870,6 +8657,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINEYESEE,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 181,
         action: Action::BrainAwake,
         next_state: State::BRAINEYE1,
@@ -7880,6 +8668,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINEYE1,
         sprite: "SSWV",
         frame: 0,
+        bright: false,
         tics: 150,
         action: Action::BrainSpit,
         next_state: State::BRAINEYE1,
@@ -7890,6 +8679,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWN1,
         sprite: "BOSF",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::SpawnSound,
         next_state: State::SPAWN2,
@@ -7900,6 +8690,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWN2,
         sprite: "BOSF",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::SpawnFly,
         next_state: State::SPAWN3,
@@ -7910,6 +8701,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWN3,
         sprite: "BOSF",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::SpawnFly,
         next_state: State::SPAWN4,
@@ -7920,6 +8712,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWN4,
         sprite: "BOSF",
         frame: 0,
+        bright: false,
         tics: 3,
         action: Action::SpawnFly,
         next_state: State::SPAWN1,
@@ -7930,6 +8723,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE1,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE2,
@@ -7940,6 +8734,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE2,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE3,
@@ -7950,6 +8745,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE3,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE4,
@@ -7960,6 +8756,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE4,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE5,
@@ -7970,6 +8767,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE5,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE6,
@@ -7980,6 +8778,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE6,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE7,
@@ -7990,6 +8789,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE7,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::SPAWNFIRE8,
@@ -8000,6 +8800,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SPAWNFIRE8,
         sprite: "FIRE",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::Fire,
         next_state: State::NULL,
@@ -8010,6 +8811,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINEXPLODE1,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BRAINEXPLODE2,
@@ -8020,6 +8822,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINEXPLODE2,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BRAINEXPLODE3,
@@ -8030,6 +8833,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINEXPLODE3,
         sprite: "MISL",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::BrainExplode,
         next_state: State::NULL,
@@ -8040,6 +8844,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARM1,
         sprite: "ARM1",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::ARM1A,
@@ -8050,6 +8855,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARM1A,
         sprite: "ARM1",
         frame: 0,
+        bright: false,
         tics: 7,
         action: Action::None,
         next_state: State::ARM1,
@@ -8060,6 +8866,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARM2,
         sprite: "ARM2",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::ARM2A,
@@ -8070,6 +8877,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ARM2A,
         sprite: "ARM2",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::ARM2,
@@ -8080,6 +8888,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BAR1,
         sprite: "BAR1",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BAR2,
@@ -8090,6 +8899,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BAR2,
         sprite: "BAR1",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BAR1,
@@ -8100,6 +8910,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BEXP,
         sprite: "BEXP",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BEXP2,
@@ -8110,6 +8921,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BEXP2,
         sprite: "BEXP",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::Scream,
         next_state: State::BEXP3,
@@ -8120,6 +8932,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BEXP3,
         sprite: "BEXP",
         frame: 0,
+        bright: false,
         tics: 5,
         action: Action::None,
         next_state: State::BEXP4,
@@ -8130,6 +8943,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BEXP4,
         sprite: "BEXP",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::Explode,
         next_state: State::BEXP5,
@@ -8140,6 +8954,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BEXP5,
         sprite: "BEXP",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::NULL,
@@ -8150,6 +8965,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BBAR1,
         sprite: "FCAN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BBAR2,
@@ -8160,6 +8976,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BBAR2,
         sprite: "FCAN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BBAR3,
@@ -8170,6 +8987,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BBAR3,
         sprite: "FCAN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BBAR1,
@@ -8180,6 +8998,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON1,
         sprite: "BON1",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON1A,
@@ -8190,6 +9009,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON1A,
         sprite: "BON1",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON1B,
@@ -8200,6 +9020,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON1B,
         sprite: "BON1",
         frame: 2,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON1C,
@@ -8210,6 +9031,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON1C,
         sprite: "BON1",
         frame: 3,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON1D,
@@ -8220,6 +9042,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON1D,
         sprite: "BON1",
         frame: 2,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON1E,
@@ -8230,6 +9053,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON1E,
         sprite: "BON1",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON1,
@@ -8240,6 +9064,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON2,
         sprite: "BON2",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON2A,
@@ -8250,6 +9075,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON2A,
         sprite: "BON2",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON2B,
@@ -8260,6 +9086,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON2B,
         sprite: "BON2",
         frame: 2,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON2C,
@@ -8270,6 +9097,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON2C,
         sprite: "BON2",
         frame: 3,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON2D,
@@ -8280,6 +9108,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON2D,
         sprite: "BON2",
         frame: 2,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON2E,
@@ -8290,6 +9119,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BON2E,
         sprite: "BON2",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BON2,
@@ -8300,6 +9130,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BKEY,
         sprite: "BKEY",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BKEY2,
@@ -8310,6 +9141,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BKEY2,
         sprite: "BKEY",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BKEY,
@@ -8320,6 +9152,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RKEY,
         sprite: "RKEY",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::RKEY2,
@@ -8330,6 +9163,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RKEY2,
         sprite: "RKEY",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::RKEY,
@@ -8340,6 +9174,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::YKEY,
         sprite: "YKEY",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::YKEY2,
@@ -8350,6 +9185,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::YKEY2,
         sprite: "YKEY",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::YKEY,
@@ -8360,6 +9196,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSKULL,
         sprite: "BSKU",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BSKULL2,
@@ -8370,6 +9207,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BSKULL2,
         sprite: "BSKU",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BSKULL,
@@ -8380,6 +9218,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RSKULL,
         sprite: "RSKU",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::RSKULL2,
@@ -8390,6 +9229,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RSKULL2,
         sprite: "RSKU",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::RSKULL,
@@ -8400,6 +9240,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::YSKULL,
         sprite: "YSKU",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::YSKULL2,
@@ -8410,6 +9251,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::YSKULL2,
         sprite: "YSKU",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::YSKULL,
@@ -8420,6 +9262,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::STIM,
         sprite: "STIM",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8430,6 +9273,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEDI,
         sprite: "MEDI",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8440,6 +9284,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SOUL,
         sprite: "SOUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SOUL2,
@@ -8450,6 +9295,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SOUL2,
         sprite: "SOUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SOUL3,
@@ -8460,6 +9306,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SOUL3,
         sprite: "SOUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SOUL4,
@@ -8470,6 +9317,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SOUL4,
         sprite: "SOUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SOUL5,
@@ -8480,6 +9328,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SOUL5,
         sprite: "SOUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SOUL6,
@@ -8490,6 +9339,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SOUL6,
         sprite: "SOUL",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::SOUL,
@@ -8500,6 +9350,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINV,
         sprite: "PINV",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINV2,
@@ -8510,6 +9361,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINV2,
         sprite: "PINV",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINV3,
@@ -8520,6 +9372,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINV3,
         sprite: "PINV",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINV4,
@@ -8530,6 +9383,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINV4,
         sprite: "PINV",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINV,
@@ -8540,6 +9394,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PSTR,
         sprite: "PSTR",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8550,6 +9405,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINS,
         sprite: "PINS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINS2,
@@ -8560,6 +9416,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINS2,
         sprite: "PINS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINS3,
@@ -8570,6 +9427,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINS3,
         sprite: "PINS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINS4,
@@ -8580,6 +9438,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PINS4,
         sprite: "PINS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PINS,
@@ -8590,6 +9449,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEGA,
         sprite: "MEGA",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::MEGA2,
@@ -8600,6 +9460,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEGA2,
         sprite: "MEGA",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::MEGA3,
@@ -8610,6 +9471,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEGA3,
         sprite: "MEGA",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::MEGA4,
@@ -8620,6 +9482,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEGA4,
         sprite: "MEGA",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::MEGA,
@@ -8630,6 +9493,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SUIT,
         sprite: "SUIT",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8640,6 +9504,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PMAP,
         sprite: "PMAP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PMAP2,
@@ -8650,6 +9515,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PMAP2,
         sprite: "PMAP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PMAP3,
@@ -8660,6 +9526,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PMAP3,
         sprite: "PMAP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PMAP4,
@@ -8670,6 +9537,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PMAP4,
         sprite: "PMAP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PMAP5,
@@ -8680,6 +9548,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PMAP5,
         sprite: "PMAP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PMAP6,
@@ -8690,6 +9559,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PMAP6,
         sprite: "PMAP",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PMAP,
@@ -8700,6 +9570,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PVIS,
         sprite: "PVIS",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PVIS2,
@@ -8710,6 +9581,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PVIS2,
         sprite: "PVIS",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::PVIS,
@@ -8720,6 +9592,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CLIP,
         sprite: "CLIP",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8730,6 +9603,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::AMMO,
         sprite: "AMMO",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8740,6 +9614,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::ROCK,
         sprite: "ROCK",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8750,6 +9625,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BROK,
         sprite: "BROK",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8760,6 +9636,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CELL,
         sprite: "CELL",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8770,6 +9647,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CELP,
         sprite: "CELP",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8780,6 +9658,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SHEL,
         sprite: "SHEL",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8790,6 +9669,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SBOX,
         sprite: "SBOX",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8800,6 +9680,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BPAK,
         sprite: "BPAK",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8810,6 +9691,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BFUG,
         sprite: "BFUG",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8820,6 +9702,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MGUN,
         sprite: "MGUN",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8830,6 +9713,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CSAW,
         sprite: "CSAW",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8840,6 +9724,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::LAUN,
         sprite: "LAUN",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8850,6 +9735,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::PLAS,
         sprite: "PLAS",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8860,6 +9746,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SHOT,
         sprite: "SHOT",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8870,6 +9757,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SHOT2,
         sprite: "SGN2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8880,6 +9768,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COLU,
         sprite: "COLU",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8890,6 +9779,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::STALAG,
         sprite: "SMT2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8900,6 +9790,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOODYTWITCH,
         sprite: "GOR1",
         frame: 0,
+        bright: false,
         tics: 10,
         action: Action::None,
         next_state: State::BLOODYTWITCH2,
@@ -8910,6 +9801,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOODYTWITCH2,
         sprite: "GOR1",
         frame: 1,
+        bright: false,
         tics: 15,
         action: Action::None,
         next_state: State::BLOODYTWITCH3,
@@ -8920,6 +9812,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOODYTWITCH3,
         sprite: "GOR1",
         frame: 2,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::BLOODYTWITCH4,
@@ -8930,6 +9823,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLOODYTWITCH4,
         sprite: "GOR1",
         frame: 1,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::BLOODYTWITCH,
@@ -8940,6 +9834,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DEADTORSO,
         sprite: "PLAY",
         frame: 13,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8950,6 +9845,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DEADBOTTOM,
         sprite: "PLAY",
         frame: 18,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8960,6 +9856,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEADSONSTICK,
         sprite: "POL2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8970,6 +9867,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GIBS,
         sprite: "POL5",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8980,6 +9878,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEADONASTICK,
         sprite: "POL4",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -8990,6 +9889,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEADCANDLES,
         sprite: "POL3",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::HEADCANDLES2,
@@ -9000,6 +9900,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEADCANDLES2,
         sprite: "POL3",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::HEADCANDLES,
@@ -9010,6 +9911,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::DEADSTICK,
         sprite: "POL1",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9020,6 +9922,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::LIVESTICK,
         sprite: "POL6",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::LIVESTICK2,
@@ -9030,6 +9933,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::LIVESTICK2,
         sprite: "POL6",
         frame: 1,
+        bright: false,
         tics: 8,
         action: Action::None,
         next_state: State::LIVESTICK,
@@ -9040,6 +9944,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEAT2,
         sprite: "GOR2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9050,6 +9955,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEAT3,
         sprite: "GOR3",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9060,6 +9966,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEAT4,
         sprite: "GOR4",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9070,6 +9977,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::MEAT5,
         sprite: "GOR5",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9080,6 +9988,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::STALAGTITE,
         sprite: "SMIT",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9090,6 +9999,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TALLGRNCOL,
         sprite: "COL1",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9100,6 +10010,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SHRTGRNCOL,
         sprite: "COL2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9110,6 +10021,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TALLREDCOL,
         sprite: "COL3",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9120,6 +10032,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SHRTREDCOL,
         sprite: "COL4",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9130,6 +10043,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CANDLESTIK,
         sprite: "CAND",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9140,6 +10054,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::CANDELABRA,
         sprite: "CBRA",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9150,6 +10065,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SKULLCOL,
         sprite: "COL6",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9160,6 +10076,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TORCHTREE,
         sprite: "TRE1",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9170,6 +10087,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BIGTREE,
         sprite: "TRE2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9180,6 +10098,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECHPILLAR,
         sprite: "ELEC",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9190,6 +10109,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EVILEYE,
         sprite: "CEYE",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::EVILEYE2,
@@ -9200,6 +10120,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EVILEYE2,
         sprite: "CEYE",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::EVILEYE3,
@@ -9210,6 +10131,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EVILEYE3,
         sprite: "CEYE",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::EVILEYE4,
@@ -9220,6 +10142,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::EVILEYE4,
         sprite: "CEYE",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::EVILEYE,
@@ -9230,6 +10153,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FLOATSKULL,
         sprite: "FSKU",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FLOATSKULL2,
@@ -9240,6 +10164,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FLOATSKULL2,
         sprite: "FSKU",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FLOATSKULL3,
@@ -9250,6 +10175,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::FLOATSKULL3,
         sprite: "FSKU",
         frame: 0,
+        bright: false,
         tics: 6,
         action: Action::None,
         next_state: State::FLOATSKULL,
@@ -9260,6 +10186,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEARTCOL,
         sprite: "COL5",
         frame: 0,
+        bright: false,
         tics: 14,
         action: Action::None,
         next_state: State::HEARTCOL2,
@@ -9270,6 +10197,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HEARTCOL2,
         sprite: "COL5",
         frame: 1,
+        bright: false,
         tics: 14,
         action: Action::None,
         next_state: State::HEARTCOL,
@@ -9280,6 +10208,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLUETORCH,
         sprite: "TBLU",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BLUETORCH2,
@@ -9290,6 +10219,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLUETORCH2,
         sprite: "TBLU",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BLUETORCH3,
@@ -9300,6 +10230,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLUETORCH3,
         sprite: "TBLU",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BLUETORCH4,
@@ -9310,6 +10241,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BLUETORCH4,
         sprite: "TBLU",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BLUETORCH,
@@ -9320,6 +10252,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GREENTORCH,
         sprite: "TGRN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GREENTORCH2,
@@ -9330,6 +10263,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GREENTORCH2,
         sprite: "TGRN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GREENTORCH3,
@@ -9340,6 +10274,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GREENTORCH3,
         sprite: "TGRN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GREENTORCH4,
@@ -9350,6 +10285,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GREENTORCH4,
         sprite: "TGRN",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GREENTORCH,
@@ -9360,6 +10296,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::REDTORCH,
         sprite: "TRED",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::REDTORCH2,
@@ -9370,6 +10307,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::REDTORCH2,
         sprite: "TRED",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::REDTORCH3,
@@ -9380,6 +10318,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::REDTORCH3,
         sprite: "TRED",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::REDTORCH4,
@@ -9390,6 +10329,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::REDTORCH4,
         sprite: "TRED",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::REDTORCH,
@@ -9400,6 +10340,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BTORCHSHRT,
         sprite: "SMBT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BTORCHSHRT2,
@@ -9410,6 +10351,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BTORCHSHRT2,
         sprite: "SMBT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BTORCHSHRT3,
@@ -9420,6 +10362,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BTORCHSHRT3,
         sprite: "SMBT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BTORCHSHRT4,
@@ -9430,6 +10373,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BTORCHSHRT4,
         sprite: "SMBT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::BTORCHSHRT,
@@ -9440,6 +10384,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GTORCHSHRT,
         sprite: "SMGT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GTORCHSHRT2,
@@ -9450,6 +10395,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GTORCHSHRT2,
         sprite: "SMGT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GTORCHSHRT3,
@@ -9460,6 +10406,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GTORCHSHRT3,
         sprite: "SMGT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GTORCHSHRT4,
@@ -9470,6 +10417,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::GTORCHSHRT4,
         sprite: "SMGT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::GTORCHSHRT,
@@ -9480,6 +10428,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RTORCHSHRT,
         sprite: "SMRT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::RTORCHSHRT2,
@@ -9490,6 +10439,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RTORCHSHRT2,
         sprite: "SMRT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::RTORCHSHRT3,
@@ -9500,6 +10450,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RTORCHSHRT3,
         sprite: "SMRT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::RTORCHSHRT4,
@@ -9510,6 +10461,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::RTORCHSHRT4,
         sprite: "SMRT",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::RTORCHSHRT,
@@ -9520,6 +10472,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HANGNOGUTS,
         sprite: "HDB1",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9530,6 +10483,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HANGBNOBRAIN,
         sprite: "HDB2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9540,6 +10494,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HANGTLOOKDN,
         sprite: "HDB3",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9550,6 +10505,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HANGTSKULL,
         sprite: "HDB4",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9560,6 +10516,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HANGTLOOKUP,
         sprite: "HDB5",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9570,6 +10527,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::HANGTNOBRAIN,
         sprite: "HDB6",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9580,6 +10538,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::COLONGIBS,
         sprite: "POB1",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9590,6 +10549,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::SMALLPOOL,
         sprite: "POB2",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9600,6 +10560,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::BRAINSTEM,
         sprite: "BRS1",
         frame: 0,
+        bright: false,
         tics: -1,
         action: Action::None,
         next_state: State::NULL,
@@ -9610,6 +10571,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECHLAMP,
         sprite: "TLMP",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECHLAMP2,
@@ -9620,6 +10582,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECHLAMP2,
         sprite: "TLMP",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECHLAMP3,
@@ -9630,6 +10593,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECHLAMP3,
         sprite: "TLMP",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECHLAMP4,
@@ -9640,6 +10604,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECHLAMP4,
         sprite: "TLMP",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECHLAMP,
@@ -9650,6 +10615,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECH2LAMP,
         sprite: "TLP2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECH2LAMP2,
@@ -9660,6 +10626,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECH2LAMP2,
         sprite: "TLP2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECH2LAMP3,
@@ -9670,6 +10637,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECH2LAMP3,
         sprite: "TLP2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECH2LAMP4,
@@ -9680,6 +10648,7 @@ pub const STATES: &[StateInfo] = &[
         state: State::TECH2LAMP4,
         sprite: "TLP2",
         frame: 0,
+        bright: false,
         tics: 4,
         action: Action::None,
         next_state: State::TECH2LAMP,
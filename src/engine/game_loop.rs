@@ -0,0 +1,360 @@
+//! Fixed-timestep main loop timing.
+//!
+//! The sim runs at exactly [`SIM_FPS`] tics a second while frames come
+//! as fast as the display takes them.  Every frame asks [`GameLoop`] how
+//! many tics are due; wall-clock time piles up in an accumulator and is
+//! paid out one whole tic at a time.  What's left over is the blend
+//! factor for drawing between the last two tics.
+//!
+//! [`SimControl`] bends the sim's clock for debugging: freeze it, step
+//! it one tic at a time, or run it slower or faster than real time.  The
+//! free camera is paced by its own, unbent, clock, so it can look around
+//! a frozen world.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::sim::SIM_FPS;
+
+/// Most tics one frame runs; after a longer stall the rest is dropped
+/// instead of fast-forwarding the game.
+pub const MAX_CATCHUP_TICS: u32 = 4;
+
+/// Wall-clock length of one tic.
+pub const TIC: Duration = Duration::from_micros(1_000_000 / SIM_FPS as u64);
+
+/// The sim speeds `[` and `]` step through, slowest first.
+pub const TIME_SCALES: [f32; 7] = [0.1, 0.25, 0.5, 1.0, 1.25, 1.5, 2.0];
+
+/// How often the FPS counter is refreshed.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// What one frame has to do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoopFrame {
+    /// Tics to run before drawing.
+    pub tics: u32,
+    /// How far (0‥1) the wall clock is past the last of them; pass to
+    /// `TicRunner::set_frame_alpha` to draw between tics.
+    pub alpha: f32,
+    /// Tics of real time, for what keeps moving when the sim doesn't
+    /// (the free camera): neither paused nor scaled.
+    pub real_tics: u32,
+}
+
+/// Debugging controls over how the sim's clock runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimControl {
+    /// Run no tics at all.
+    pub paused: bool,
+    /// Run exactly one tic next frame, then pause.
+    pub single_step: bool,
+    /// Sim time per wall-clock time, one of [`TIME_SCALES`].
+    pub time_scale: f32,
+}
+
+impl Default for SimControl {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            single_step: false,
+            time_scale: 1.0,
+        }
+    }
+}
+
+impl SimControl {
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.single_step = false;
+    }
+
+    /// Advance one tic and stay paused.
+    pub fn step(&mut self) {
+        self.single_step = true;
+    }
+
+    /// The next of [`TIME_SCALES`] down; the slowest stays.
+    pub fn slower(&mut self) {
+        if let Some(&s) = TIME_SCALES.iter().rev().find(|&&s| s < self.time_scale) {
+            self.time_scale = s;
+        }
+    }
+
+    /// The next of [`TIME_SCALES`] up; the fastest stays.
+    pub fn faster(&mut self) {
+        if let Some(&s) = TIME_SCALES.iter().find(|&&s| s > self.time_scale) {
+            self.time_scale = s;
+        }
+    }
+}
+
+/// `paused`, or the speed: `×0.50`.
+impl fmt::Display for SimControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.paused {
+            f.write_str("paused")
+        } else {
+            write!(f, "×{:.2}", self.time_scale)
+        }
+    }
+}
+
+pub struct GameLoop {
+    /// Start of the previous frame; `None` before the first.
+    last: Option<Instant>,
+    /// Wall-clock time not yet paid out as tics.
+    accumulator: Duration,
+    /// Freeze the sim (menus, console); frames keep coming.
+    pub paused: bool,
+    /// Pause, step and speed set by the player.
+    pub control: SimControl,
+    /// Wall-clock time not yet paid out as [`LoopFrame::real_tics`].
+    real_accumulator: Duration,
+    /// Tics handed out since the loop started.
+    gametic: u64,
+    fps: f32,
+    fps_window: Option<(Instant, u32)>,
+}
+
+impl Default for GameLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameLoop {
+    pub fn new() -> Self {
+        Self {
+            last: None,
+            accumulator: Duration::ZERO,
+            paused: false,
+            control: SimControl::default(),
+            real_accumulator: Duration::ZERO,
+            gametic: 0,
+            fps: 0.0,
+            fps_window: None,
+        }
+    }
+
+    /// Start a frame now.
+    pub fn frame(&mut self) -> LoopFrame {
+        self.frame_at(Instant::now())
+    }
+
+    /// Start a frame at `now`.  Time spent paused is never made up.
+    pub fn frame_at(&mut self, now: Instant) -> LoopFrame {
+        let dt = self
+            .last
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last = Some(now);
+        self.count_frame(now);
+        self.real_accumulator += dt;
+        let real_tics = pay_out(&mut self.real_accumulator);
+
+        let tics = if self.paused {
+            0
+        } else if self.control.single_step {
+            // the stepped tic is drawn as it is, not blended
+            self.control.single_step = false;
+            self.control.paused = true;
+            self.accumulator = Duration::ZERO;
+            1
+        } else if self.control.paused {
+            0
+        } else {
+            // scaling the time paid in scales the tics paid out
+            self.accumulator += dt.mul_f32(self.control.time_scale);
+            pay_out(&mut self.accumulator)
+        };
+        self.gametic += u64::from(tics);
+        LoopFrame {
+            tics,
+            alpha: self.alpha(),
+            real_tics,
+        }
+    }
+
+    /// Forget the time piled up so far, e.g. after loading a map.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.accumulator = Duration::ZERO;
+        self.real_accumulator = Duration::ZERO;
+    }
+
+    /// Tics handed out since the loop started.
+    #[inline]
+    pub fn gametic(&self) -> u64 {
+        self.gametic
+    }
+
+    /// Frames per wall-clock second, refreshed every second.
+    #[inline]
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f32() / TIC.as_secs_f32()).min(1.0)
+    }
+
+    fn count_frame(&mut self, now: Instant) {
+        let (start, frames) = self.fps_window.get_or_insert((now, 0));
+        *frames += 1;
+        let elapsed = now.saturating_duration_since(*start);
+        if elapsed >= FPS_WINDOW {
+            self.fps = (*frames - 1) as f32 / elapsed.as_secs_f32();
+            self.fps_window = Some((now, 1));
+        }
+    }
+}
+
+/// Whole tics out of `accumulator`, at most [`MAX_CATCHUP_TICS`].
+fn pay_out(accumulator: &mut Duration) -> u32 {
+    let mut tics = 0;
+    while *accumulator >= TIC && tics < MAX_CATCHUP_TICS {
+        *accumulator -= TIC;
+        tics += 1;
+    }
+    if *accumulator >= TIC {
+        // stalled: keep the phase, drop the backlog
+        let rest = accumulator.as_nanos() % TIC.as_nanos();
+        *accumulator = Duration::from_nanos(rest as u64);
+    }
+    tics
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_sim_fps_tics_a_second_at_any_frame_rate() {
+        for frame_ms in [4, 10, 16, 33, 50] {
+            let mut lp = GameLoop::new();
+            let t0 = Instant::now();
+            let mut tics = 0;
+            let mut ms = 0;
+            while ms <= 2000 {
+                tics += lp.frame_at(t0 + Duration::from_millis(ms)).tics;
+                ms += frame_ms;
+            }
+            assert!(
+                (69..=70).contains(&tics),
+                "{frame_ms} ms frames: {tics} tics"
+            );
+            assert_eq!(lp.gametic(), u64::from(tics));
+        }
+    }
+
+    #[test]
+    fn alpha_is_the_leftover_fraction() {
+        let mut lp = GameLoop::new();
+        let t0 = Instant::now();
+        lp.frame_at(t0);
+        let f = lp.frame_at(t0 + TIC + TIC / 2);
+        assert_eq!(f.tics, 1);
+        assert!((f.alpha - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn stall_catches_up_at_most_a_few_tics() {
+        let mut lp = GameLoop::new();
+        let t0 = Instant::now();
+        lp.frame_at(t0);
+        let f = lp.frame_at(t0 + Duration::from_secs(2) + TIC / 4);
+        assert_eq!(f.tics, MAX_CATCHUP_TICS);
+        assert!(f.alpha < 1.0);
+        // and doesn't keep paying the backlog off afterwards
+        let f = lp.frame_at(t0 + Duration::from_secs(2) + TIC / 2);
+        assert_eq!(f.tics, 0);
+    }
+
+    #[test]
+    fn pause_freezes_the_sim_but_not_the_frames() {
+        let mut lp = GameLoop::new();
+        let t0 = Instant::now();
+        lp.frame_at(t0);
+        let before = lp.frame_at(t0 + TIC / 3).alpha;
+
+        lp.paused = true;
+        for i in 1..=100 {
+            let f = lp.frame_at(t0 + TIC / 3 + Duration::from_millis(10 * i));
+            assert_eq!((f.tics, f.alpha), (0, before));
+        }
+        lp.paused = false;
+        let f = lp.frame_at(t0 + TIC / 3 + Duration::from_millis(1010));
+        assert_eq!(f.tics, 0, "paused time was made up");
+        assert!(lp.fps() > 90.0 && lp.fps() < 110.0, "fps {}", lp.fps());
+    }
+
+    #[test]
+    fn sim_control_pauses_steps_and_scales() {
+        // 2 s of 10 ms frames from `t` on: (sim tics, real tics)
+        let next = |lp: &mut GameLoop, t: &mut Instant| {
+            let (mut tics, mut real) = (0, 0);
+            for _ in 0..200 {
+                *t += Duration::from_millis(10);
+                let f = lp.frame_at(*t);
+                tics += f.tics;
+                real += f.real_tics;
+            }
+            (tics, real)
+        };
+        let mut lp = GameLoop::new();
+        let mut t = Instant::now();
+        lp.frame_at(t);
+
+        let (tics, real) = next(&mut lp, &mut t);
+        assert!((69..=70).contains(&tics), "{tics}");
+        assert!((69..=70).contains(&real), "{real}");
+
+        // paused: nothing runs, but real time still counts for the camera
+        lp.control.toggle_pause();
+        let (tics, real) = next(&mut lp, &mut t);
+        assert_eq!(tics, 0);
+        assert!((69..=71).contains(&real), "{real}");
+
+        // a step is one tic on the next frame, then paused again
+        for _ in 0..3 {
+            lp.control.step();
+            assert_eq!(lp.frame_at(t).tics, 1);
+            assert_eq!(lp.frame_at(t).tics, 0);
+            assert!(lp.control.paused);
+        }
+        assert_eq!(next(&mut lp, &mut t).0, 0);
+
+        // half speed, then double
+        lp.control.toggle_pause();
+        lp.control.slower();
+        assert_eq!(lp.control.time_scale, 0.5);
+        let (tics, real) = next(&mut lp, &mut t);
+        assert!((34..=36).contains(&tics), "{tics}");
+        assert!((69..=71).contains(&real), "{real}");
+        for _ in 0..10 {
+            lp.control.faster();
+        }
+        assert_eq!(lp.control.time_scale, 2.0);
+        let tics = next(&mut lp, &mut t).0;
+        assert!((139..=141).contains(&tics), "{tics}");
+        for _ in 0..10 {
+            lp.control.slower();
+        }
+        assert_eq!(lp.control.to_string(), "×0.10");
+        let tics = next(&mut lp, &mut t).0;
+        assert!((6..=8).contains(&tics), "{tics}");
+
+        // the menu's pause holds a step back until it closes
+        lp.paused = true;
+        lp.control.step();
+        assert_eq!(lp.frame_at(t).tics, 0);
+        lp.paused = false;
+        assert_eq!(lp.frame_at(t).tics, 1);
+        assert!(lp.control.paused);
+    }
+}
@@ -0,0 +1,7 @@
+//! Frontend plumbing shared by the binaries.
+
+pub mod game_loop;
+pub mod timedemo;
+
+pub use game_loop::{GameLoop, LoopFrame, MAX_CATCHUP_TICS, SimControl};
+pub use timedemo::{TimedemoReport, run_timedemo};
@@ -0,0 +1,207 @@
+//! Timedemo: play a demo back as fast as the machine goes and time it.
+//!
+//! Like vanilla `-timedemo` every tic is run and drawn exactly once, with
+//! no fixed-timestep accumulator, no sleeping and no sound.  The report
+//! says how long the whole demo took, how the frames were spread and,
+//! per frame, how long the sim and each pass of the renderer took.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    Game,
+    game::Phase,
+    renderer::{RenderStats, Renderer},
+    sim::{SIM_FPS, demo::DemoPlayer},
+};
+
+/// What a timedemo measured.  A frame is one tic run and drawn.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TimedemoReport {
+    /// Gametics played, one frame each.
+    pub tics: u32,
+    /// Wall time for the lot.
+    pub total: Duration,
+    pub frame_min: Duration,
+    pub frame_max: Duration,
+    /// Summed over every frame.
+    pub sim: Duration,
+    pub walls: Duration,
+    pub planes: Duration,
+    pub sprites: Duration,
+}
+
+impl TimedemoReport {
+    /// Count one frame that took `frame`, `sim` of it in the sim, which
+    /// the renderer broke down in `stats`.
+    pub fn add_frame(&mut self, frame: Duration, sim: Duration, stats: &RenderStats) {
+        if self.tics == 0 {
+            (self.frame_min, self.frame_max) = (frame, frame);
+        }
+        self.tics += 1;
+        self.frame_min = self.frame_min.min(frame);
+        self.frame_max = self.frame_max.max(frame);
+        self.sim += sim;
+        self.walls += stats.wall_time;
+        self.planes += stats.plane_time;
+        self.sprites += stats.sprite_time;
+    }
+
+    /// The wall time in vanilla's 35 Hz realtics.
+    pub fn realtics(&self) -> u32 {
+        (self.total.as_secs_f64() * SIM_FPS as f64).round() as u32
+    }
+
+    pub fn fps(&self) -> f64 {
+        let secs = self.total.as_secs_f64();
+        if secs > 0.0 {
+            self.tics as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    pub fn frame_avg(&self) -> Duration {
+        self.total.checked_div(self.tics).unwrap_or_default()
+    }
+
+    /// Per frame, milliseconds: the sim, walls, planes, sprites and
+    /// whatever's left (the border, the frame handed over, the loop).
+    fn phases_ms(&self) -> [(&'static str, f64); 5] {
+        let per_frame = |d: Duration| match self.tics {
+            0 => 0.0,
+            n => d.as_secs_f64() * 1000.0 / n as f64,
+        };
+        let accounted = self.sim + self.walls + self.planes + self.sprites;
+        [
+            ("sim", per_frame(self.sim)),
+            ("walls", per_frame(self.walls)),
+            ("planes", per_frame(self.planes)),
+            ("sprites", per_frame(self.sprites)),
+            ("other", per_frame(self.total.saturating_sub(accounted))),
+        ]
+    }
+
+    /// The report as one line of JSON, for CI to keep.
+    pub fn to_json(&self) -> String {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let phases: Vec<String> = (self.phases_ms().iter())
+            .map(|(name, v)| format!("\"{name}\":{v:.3}"))
+            .collect();
+        format!(
+            "{{\"tics\":{},\"realtics\":{},\"seconds\":{:.4},\"fps\":{:.2},\
+             \"frame_ms\":{{\"avg\":{:.3},\"min\":{:.3},\"max\":{:.3}}},\
+             \"phase_ms\":{{{}}}}}",
+            self.tics,
+            self.realtics(),
+            self.total.as_secs_f64(),
+            self.fps(),
+            ms(self.frame_avg()),
+            ms(self.frame_min),
+            ms(self.frame_max),
+            phases.join(",")
+        )
+    }
+}
+
+impl fmt::Display for TimedemoReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        writeln!(
+            f,
+            "timed {} gametics in {} realtics ({:.1} fps)",
+            self.tics,
+            self.realtics(),
+            self.fps()
+        )?;
+        writeln!(
+            f,
+            "frame avg {:.2} ms  min {:.2}  max {:.2}",
+            ms(self.frame_avg()),
+            ms(self.frame_min),
+            ms(self.frame_max)
+        )?;
+        f.write_str("per frame:")?;
+        for (name, v) in self.phases_ms() {
+            write!(f, "  {name} {v:.2}")?;
+        }
+        f.write_str(" ms")
+    }
+}
+
+/// Play `demo` from where `game` stands, each tic run then drawn as a
+/// `w × h` frame that goes nowhere.  Stops at the demo's end or when the
+/// map is left.  Sound events are dropped.
+pub fn run_timedemo<R: Renderer>(
+    game: &mut Game,
+    demo: &mut DemoPlayer,
+    renderer: &mut R,
+    w: usize,
+    h: usize,
+) -> TimedemoReport {
+    let mut report = TimedemoReport::default();
+    let start = Instant::now();
+    while !demo.is_finished() && matches!(game.phase(), Phase::Level) {
+        let t0 = Instant::now();
+        game.run_tics(demo, 1);
+        game.drain_events().for_each(drop);
+        let sim = t0.elapsed();
+        game.render(renderer, w, h, |_, _, _| {});
+        report.add_frame(t0.elapsed(), sim, renderer.stats());
+    }
+    report.total = start.elapsed();
+    report
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_spreads_and_averages_frames() {
+        let ms = Duration::from_millis;
+        let stats = RenderStats {
+            wall_time: ms(2),
+            plane_time: ms(1),
+            ..RenderStats::ZERO
+        };
+        let mut report = TimedemoReport::default();
+        for frame in [ms(5), ms(3), ms(7)] {
+            report.add_frame(frame, ms(1), &stats);
+        }
+        report.total = ms(15) + ms(1);
+
+        assert_eq!((report.frame_min, report.frame_max), (ms(3), ms(7)));
+        assert!((report.fps() - 3.0 / 0.016).abs() < 1e-6);
+        // 16 ms is 0.56 of a realtic
+        assert_eq!(report.realtics(), 1);
+        let phases = report.phases_ms().map(|(_, v)| v);
+        for (got, want) in phases
+            .into_iter()
+            .zip([1.0, 2.0, 1.0, 0.0, 16.0 / 3.0 - 4.0])
+        {
+            assert!((got - want).abs() < 1e-9, "{phases:?}");
+        }
+
+        let json = report.to_json();
+        assert!(json.starts_with("{\"tics\":3,\"realtics\":1,"), "{json}");
+        assert!(json.contains("\"frame_ms\":{\"avg\":5.333,\"min\":3.000,\"max\":7.000}"));
+        assert!(
+            report
+                .to_string()
+                .starts_with("timed 3 gametics in 1 realtics")
+        );
+    }
+
+    #[test]
+    fn empty_report_divides_by_nothing() {
+        let report = TimedemoReport::default();
+        assert_eq!((report.fps(), report.frame_avg()), (0.0, Duration::ZERO));
+        assert!(!report.to_json().contains("NaN"));
+    }
+}
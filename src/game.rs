@@ -0,0 +1,852 @@
+//! The running game: the loaded WAD, the current map and its simulation.
+//!
+//! Frontends own one `GameState`; console commands get it by `&mut` so
+//! they can poke at any part of it.  The game is on the title screens,
+//! playing a map or showing the intermission after one; frontends only
+//! run tics while `phase` is `Phase::Level`, call
+//! [`GameState::check_exit`] and [`GameState::check_reborn`] after them
+//! and [`GameState::advance`] when the player is done reading the stats.
+//!
+//! [`Game`] wraps a `GameState` behind the handful of calls an embedder
+//! needs: load, tick, render, change map.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use glam::{Vec2, Vec3};
+use hecs::Entity;
+use thiserror::Error;
+
+use crate::console::{FONT_FIRST, FONT_LAST, HUD_FONT};
+use crate::defs::{
+    Definitions,
+    deh::{self, DehError},
+};
+use crate::input::Bindings;
+use crate::intermission::Intermission;
+use crate::level_info::{LevelInfo, LevelInfoMap};
+use crate::renderer::{LightMode, Renderer, Rgba, SCREENBLOCKS_MAX, ViewWindow};
+use crate::screens::{TitleLoop, ViewBorder};
+use crate::sim::{
+    GameRules, GameSession, InputCmd, InputSource, LevelExit, MAX_PLAYERS, PhysicsConfig,
+    PlayerStatus, Rng, SIM_FPS, SimEvent, Skill, TicRunner, hitscan,
+    saveload::{self, SaveError},
+    switches::SwitchList,
+};
+use crate::wad::{LoadError, Wad, WadError, load_font, load_level};
+use crate::world::{
+    Angle, BlockmapQuery, Camera, CameraMode, Level, PaletteEffect, SubsectorId, TextureBank,
+};
+
+/// Eye height above the floor the camera follows the player at, bob and
+/// landings aside.
+pub use crate::sim::VIEW_HEIGHT;
+
+/// How far the chase camera keeps off the wall behind the player.
+const CHASE_WALL_GAP: f32 = 8.0;
+
+/// Free camera handling, per tic: speed gained at full stick, speed
+/// kept, and the turn at full stick (the player's 180° a second).
+const FREE_CAM_ACCEL: f32 = 4.0;
+const FREE_CAM_FRICTION: f32 = 0.8;
+const FREE_CAM_TURN: f32 = std::f32::consts::PI / SIM_FPS as f32;
+
+#[derive(Error, Debug)]
+pub enum GameError {
+    #[error(transparent)]
+    Wad(#[from] WadError),
+
+    #[error(transparent)]
+    Load(#[from] LoadError),
+
+    #[error("no map {0} in the WAD")]
+    NoSuchMap(String),
+
+    #[error("the WAD has no maps")]
+    NoMaps,
+
+    #[error("map {0} has no player 1 start")]
+    NoPlayerStart(String),
+
+    #[error("{name}: {source}")]
+    Deh {
+        name: String,
+        #[source]
+        source: DehError,
+    },
+}
+
+/// Where the game loop is.
+pub enum Phase {
+    /// Cycling the title pages until a key starts the game.
+    Title(Box<TitleLoop>),
+    /// Playing `GameState::level`.
+    Level,
+    /// The level is over; showing its stats until the player moves on.
+    Intermission(Box<Intermission>),
+}
+
+pub struct GameState {
+    pub wad: Wad,
+    pub bank: TextureBank,
+    pub level: Level,
+    pub sim: TicRunner,
+    /// RNG seed every map starts from (demos pin it).
+    pub seed: u8,
+    /// Skill every map's things are spawned for; the menu picks it.
+    pub skill: Skill,
+    /// `-fast` / `-respawn` on top of what the skill plays by.
+    pub rules: GameRules,
+    /// Thing, frame and ammo tables every map's sim runs on, DeHackEd
+    /// patches applied.
+    pub defs: Arc<Definitions>,
+    /// Local players every map spawns, 1‥4.
+    pub players: u8,
+    /// Draw the render statistics overlay.
+    pub show_stats: bool,
+    /// Lighting override the frontend hands the software renderer; the
+    /// `light` command sets it.
+    pub light_mode: LightMode,
+    /// The scene just changed (new map, intermission, title); the frontend
+    /// melts its last frame into the next one and clears the flag.
+    pub start_wipe: bool,
+    /// Lines printed by console commands, oldest first.
+    pub messages: Vec<String>,
+    pub phase: Phase,
+    /// Controls → actions; the `bind` command edits them.
+    pub bindings: Bindings,
+    /// Movement constants every map's sim gets.
+    pub physics: PhysicsConfig,
+    /// Whether every map's players autoaim; see [`TicRunner::set_autoaim`].
+    pub autoaim: bool,
+    /// Vanilla `screenblocks`: how much of the frame the view gets,
+    /// 3‥11; see [`GameState::view_window`].
+    pub screenblocks: u8,
+    /// Drawn around the view when it doesn't fill the frame.
+    pub view_border: ViewBorder,
+    /// Map titles and par times.
+    pub level_infos: LevelInfoMap,
+    /// Inventory, automaps and tallies carried from map to map.
+    pub session: GameSession,
+}
+
+impl GameState {
+    /// Load the map whose marker lump is `marker` and spawn its things
+    /// as `defs` define them.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        wad: Wad,
+        mut bank: TextureBank,
+        marker: usize,
+        seed: u8,
+        skill: Skill,
+        rules: GameRules,
+        players: u8,
+        defs: Arc<Definitions>,
+    ) -> Result<Self, GameError> {
+        let (level, sim) = start_map(&wad, &mut bank, marker, seed, skill, rules, players, &defs)?;
+        load_font(&wad, &mut bank, HUD_FONT, FONT_FIRST, FONT_LAST);
+        Ok(Self {
+            bank,
+            level,
+            sim,
+            seed,
+            skill,
+            rules,
+            defs,
+            players,
+            show_stats: false,
+            light_mode: LightMode::Vanilla,
+            start_wipe: false,
+            messages: Vec::new(),
+            phase: Phase::Level,
+            bindings: Bindings::vanilla(),
+            physics: PhysicsConfig::default(),
+            autoaim: true,
+            screenblocks: SCREENBLOCKS_MAX,
+            view_border: ViewBorder::new(&wad),
+            level_infos: LevelInfoMap::default(),
+            session: GameSession::default(),
+            wad,
+        })
+    }
+
+    /// Title and par time of the current map.
+    pub fn level_info(&self) -> LevelInfo {
+        self.level_infos.lookup(&self.level.name)
+    }
+
+    /// The part of a `w × h` frame the 3-D view takes at the current
+    /// `screenblocks`.
+    pub fn view_window(&self, w: usize, h: usize) -> ViewWindow {
+        ViewWindow::for_screenblocks(self.screenblocks, w, h)
+    }
+
+    /// Replace the current map with the one at `marker`.
+    pub fn load_map(&mut self, marker: usize) -> Result<(), GameError> {
+        let (level, sim) = start_map(
+            &self.wad,
+            &mut self.bank,
+            marker,
+            self.seed,
+            self.skill,
+            self.rules,
+            self.players,
+            &self.defs,
+        )?;
+        self.session.leave_level(&self.level.name, &self.sim);
+        self.level = level;
+        self.sim = sim;
+        self.sim.set_physics(self.physics);
+        self.sim.set_autoaim(self.autoaim);
+        self.phase = Phase::Level;
+        self.start_wipe = true;
+        Ok(())
+    }
+
+    /// Leave whatever is going on for the title loop.
+    pub fn show_title(&mut self) {
+        self.phase = Phase::Title(Box::new(TitleLoop::new(&self.wad)));
+        self.start_wipe = true;
+    }
+
+    /// Change map by name (`E1M3`, `MAP07`…).
+    pub fn warp(&mut self, map: &str) -> Result<(), GameError> {
+        let marker = map_marker(&self.wad, map)?;
+        self.load_map(marker)
+    }
+
+    /// End the level if the sim hit an exit: tally its stats and switch to
+    /// the intermission.  Returns `true` when that happened.
+    pub fn check_exit(&mut self) -> bool {
+        let (Phase::Level, Some(exit)) = (&self.phase, self.sim.exit()) else {
+            return false;
+        };
+        let status = self.player_status().unwrap_or_default();
+        let next = self.next_marker(exit);
+        let mut im = Intermission::new(
+            self.level_info(),
+            next.map(|i| {
+                let name = Wad::lump_name_str(&self.wad.lumps()[i].name);
+                self.level_infos.lookup(name)
+            }),
+            &status,
+            self.sim.totals(),
+            self.sim.gametic(),
+        );
+        // vanilla keeps `didsecret` for the rest of the episode
+        im.did_secret = exit == LevelExit::Secret
+            || (self.session.levels()).any(|(map, _)| secret_map(map, &self.level.name));
+        im.load_pics(&self.wad);
+        self.phase = Phase::Intermission(Box::new(im));
+        self.start_wipe = true;
+        true
+    }
+
+    /// Start the map over if a dead player pressed use: everything back
+    /// as it was loaded and a fresh player with a pistol and 50 bullets,
+    /// which is vanilla `G_DoReborn` in single player.  Split screen does
+    /// the same for everyone.  Returns `true` when that happened.
+    pub fn check_reborn(&mut self) -> bool {
+        if !matches!(self.phase, Phase::Level) || !self.sim.reborn() {
+            return false;
+        }
+        let map = self.level.name.clone();
+        match self.warp(&map) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("warning: can't restart {map}: {e}");
+                false
+            }
+        }
+    }
+
+    /// Leave the intermission for the next map, keeping the player's
+    /// health, armor, ammo and weapons.  After the last map of an episode the
+    /// finished map starts over.
+    pub fn advance(&mut self) -> Result<(), GameError> {
+        let Phase::Intermission(im) = &self.phase else {
+            return Ok(());
+        };
+        let name = im.next.as_ref().unwrap_or(&im.finished).map.clone();
+        self.session.carry_inventory(&self.sim);
+        self.warp(&name)?;
+        self.session.enter_level(&mut self.sim);
+        Ok(())
+    }
+
+    fn player_status(&self) -> Option<PlayerStatus> {
+        let player = self.sim.player()?;
+        self.sim
+            .world()
+            .get::<&PlayerStatus>(player)
+            .ok()
+            .map(|s| *s)
+    }
+
+    /// Marker of the map `exit` leads to, if the WAD has it.
+    fn next_marker(&self, exit: LevelExit) -> Option<usize> {
+        let maps = self.wad.level_indices();
+        let name_of = |i: usize| Wad::lump_name_str(&self.wad.lumps()[i].name);
+        match next_map(&self.level.name, exit) {
+            Next::Map(name) => maps.into_iter().find(|&i| name_of(i) == name),
+            Next::EpisodeEnd => None,
+            // not a vanilla name: whatever follows in the WAD
+            Next::Unknown => {
+                let here = maps.iter().position(|&i| name_of(i) == self.level.name)?;
+                maps.get(here + 1).copied()
+            }
+        }
+    }
+
+    /// Append a line to the console output.
+    pub fn print(&mut self, msg: impl Into<String>) {
+        self.messages.push(msg.into());
+    }
+}
+
+/// Build the definitions from the compiled-in tables, the WAD's
+/// `DEHACKED` lump and the `deh` patches.
+fn load_definitions(wad: &Wad, deh: &[PathBuf]) -> Result<Arc<Definitions>, GameError> {
+    let lump = wad.find_lump("DEHACKED");
+    if lump.is_none() && deh.is_empty() {
+        return Ok(Definitions::shared_builtin());
+    }
+    let mut defs = Definitions::builtin();
+    if let Some(lump) = lump {
+        let text = String::from_utf8_lossy(wad.lump_bytes(lump)?);
+        deh::apply(&mut defs, &text).map_err(|source| GameError::Deh {
+            name: "DEHACKED".into(),
+            source,
+        })?;
+    }
+    for path in deh {
+        deh::load(&mut defs, path).map_err(|source| GameError::Deh {
+            name: path.display().to_string(),
+            source,
+        })?;
+    }
+    defs.reindex();
+    Ok(Arc::new(defs))
+}
+
+/// What [`Game::new`] loads.
+#[derive(Clone, Debug, Default)]
+pub struct GameConfig {
+    /// The IWAD.
+    pub wad_path: PathBuf,
+    /// PWADs loaded on top of it, in order; later ones win.
+    pub pwads: Vec<PathBuf>,
+    pub skill: Skill,
+    /// Rules on top of the skill's own, as `-fast` and `-respawn` give.
+    pub rules: GameRules,
+    /// Map to start on (`E1M1`, `MAP01`…); the WAD's first if unset.
+    pub map: Option<String>,
+    /// RNG seed every map starts from (demos pin it).
+    pub seed: u8,
+    /// Local players, 1‥4; 0 counts as 1.
+    pub players: u8,
+    /// DeHackEd patches, applied in order after the WAD's own `DEHACKED`
+    /// lump.
+    pub deh: Vec<PathBuf>,
+}
+
+/// The engine behind one call per frame: everything a frontend or an
+/// embedding program needs to load a WAD, run the sim and draw it.
+///
+/// ```no_run
+/// use yadoom_rs::{Game, GameConfig, renderer::Software, sim::InputCmd};
+///
+/// let mut game = Game::new(GameConfig {
+///     wad_path: "doom.wad".into(),
+///     map: Some("E1M1".into()),
+///     ..GameConfig::default()
+/// })?;
+/// let forward = InputCmd { forward: 1.0, ..InputCmd::default() };
+/// for _ in 0..35 {
+///     game.tick(forward);
+/// }
+///
+/// let mut renderer = Software::default();
+/// let mut pixels = Vec::new();
+/// game.render(&mut renderer, 320, 200, |fb, _, _| pixels.extend_from_slice(fb));
+/// # Ok::<(), yadoom_rs::game::GameError>(())
+/// ```
+///
+/// Frontends with a console, menus or the title loop reach the rest of
+/// the game through [`Game::state_mut`].
+pub struct Game {
+    state: GameState,
+    camera: Camera,
+    camera_mode: CameraMode,
+    /// How fast the free camera drifts, in units per tic.
+    free_vel: Vec3,
+    active: Vec<SubsectorId>,
+}
+
+impl Game {
+    /// Load the WAD and PWADs, apply DeHackEd patches, then `config.map`
+    /// with its things spawned for `config.skill`.
+    pub fn new(config: GameConfig) -> Result<Self, GameError> {
+        let mut wad = Wad::from_file(&config.wad_path)?;
+        for pwad in &config.pwads {
+            wad.merge_file(pwad)?;
+        }
+        let defs = load_definitions(&wad, &config.deh)?;
+        let marker = match &config.map {
+            Some(name) => map_marker(&wad, name)?,
+            None => *wad.level_indices().first().ok_or(GameError::NoMaps)?,
+        };
+        let bank = TextureBank::default_with_checker();
+        let players = config.players.max(1);
+        let state = GameState::new(
+            wad,
+            bank,
+            marker,
+            config.seed,
+            config.skill,
+            config.rules,
+            players,
+            defs,
+        )?;
+        Ok(Self::from_state(state))
+    }
+
+    /// Drive an already set-up `GameState`.
+    pub fn from_state(state: GameState) -> Self {
+        Self {
+            state,
+            camera: Camera::new(Vec3::ZERO, 0.0, 90_f32.to_radians()),
+            camera_mode: CameraMode::FirstPerson,
+            free_vel: Vec3::ZERO,
+            active: Vec::new(),
+        }
+    }
+
+    /// Run one tic with `cmd`, then end the level if it was exited.
+    /// Nothing happens outside `Phase::Level`.
+    pub fn tick(&mut self, cmd: InputCmd) {
+        self.tick_players(&[cmd]);
+    }
+
+    /// [`Game::tick`] with a command per player, player 1 first.
+    pub fn tick_players(&mut self, cmds: &[InputCmd]) {
+        if !matches!(self.state.phase, Phase::Level) {
+            return;
+        }
+        let GameState { level, sim, .. } = &mut self.state;
+        sim.run_tic_cmds(level, cmds);
+        if !self.state.check_exit() {
+            self.state.check_reborn();
+        }
+    }
+
+    /// [`Game::run_tics`] with an input per player, player 1 first; stops
+    /// when any of them runs dry.
+    pub fn run_player_tics(&mut self, inputs: &mut [&mut dyn InputSource], tics: u32) -> u32 {
+        let mut cmds = Vec::with_capacity(inputs.len());
+        for ran in 0..tics {
+            if !matches!(self.state.phase, Phase::Level) {
+                return ran;
+            }
+            cmds.clear();
+            for input in inputs.iter_mut() {
+                match input.next_cmd() {
+                    Some(cmd) => cmds.push(cmd),
+                    None => return ran,
+                }
+            }
+            self.tick_players(&cmds);
+        }
+        tics
+    }
+
+    /// Run up to `tics` tics pulling commands from `input` (a demo, the
+    /// live controls…); returns how many ran.
+    pub fn run_tics(&mut self, input: &mut dyn InputSource, tics: u32) -> u32 {
+        if !matches!(self.state.phase, Phase::Level) {
+            return 0;
+        }
+        let GameState { level, sim, .. } = &mut self.state;
+        let ran = sim.run_tics(level, input, tics);
+        if !self.state.check_exit() {
+            self.state.check_reborn();
+        }
+        ran
+    }
+
+    /// Sounds and other events of the tics run so far.  They pile up
+    /// until drained.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = SimEvent> + '_ {
+        self.state.sim.drain_events()
+    }
+
+    /// Draw the player's view as a `w × h` frame and hand the pixels to
+    /// `submit`.  Below full screen size the view is framed by the
+    /// border.  Between tics pass the blend factor to
+    /// `TicRunner::set_frame_alpha` first to draw things in between.
+    pub fn render<R, F>(&mut self, renderer: &mut R, w: usize, h: usize, submit: F)
+    where
+        R: Renderer,
+        F: FnOnce(&[Rgba], usize, usize),
+    {
+        self.draw_frame(renderer, w, h);
+        renderer.end_frame(submit);
+    }
+
+    /// [`Game::render`] up to `end_frame`, so the caller can make its own
+    /// 2-D calls – the HUD, the console – over the view first.
+    pub fn draw_frame<R: Renderer>(&mut self, renderer: &mut R, w: usize, h: usize) {
+        let mut camera = self.camera;
+        if let Some(player) = self.state.sim.player() {
+            self.follow(player, self.camera_mode, &mut camera);
+        }
+        self.camera = camera;
+        renderer.begin_view(w, h, self.state.view_window(w, h));
+        renderer.draw_border(&self.state.view_border);
+        self.draw_view(renderer, &camera);
+    }
+
+    /// Split-screen [`Game::render`]: the frame is cut into one band per
+    /// player, top to bottom, each drawn from its player's eyes.  The
+    /// free camera is a single-player thing, so it falls back to first
+    /// person here; the border and `screenblocks` are ignored.
+    pub fn render_split<R, F>(&mut self, renderer: &mut R, w: usize, h: usize, submit: F)
+    where
+        R: Renderer,
+        F: FnOnce(&[Rgba], usize, usize),
+    {
+        self.draw_split_frame(renderer, w, h);
+        renderer.end_frame(submit);
+    }
+
+    /// [`Game::render_split`] up to `end_frame`; see [`Game::draw_frame`].
+    pub fn draw_split_frame<R: Renderer>(&mut self, renderer: &mut R, w: usize, h: usize) {
+        let players = self.state.sim.players().to_vec();
+        let mode = match self.camera_mode {
+            CameraMode::Free => CameraMode::FirstPerson,
+            mode => mode,
+        };
+        let bands = players.len().max(1);
+        for (i, &player) in players.iter().enumerate() {
+            let mut camera = self.camera;
+            self.follow(player, mode, &mut camera);
+            let (top, bottom) = (i * h / bands, (i + 1) * h / bands);
+            renderer.begin_view(
+                w,
+                h,
+                ViewWindow {
+                    x: 0,
+                    y: top,
+                    w,
+                    h: bottom - top,
+                },
+            );
+            self.draw_view(renderer, &camera);
+            if i == 0 {
+                self.camera = camera;
+            }
+        }
+    }
+
+    /// Put `camera` where `mode` has it watch `player` from, as of the
+    /// blend factor the sim was given.
+    fn follow(&self, player: Entity, mode: CameraMode, camera: &mut Camera) {
+        let Some((pos, ang)) = self.state.sim.interpolated(player) else {
+            return;
+        };
+        let view = self.state.sim.view_height(player).unwrap_or(VIEW_HEIGHT);
+        let eye = pos.0.extend(pos.1 + view);
+        match mode {
+            CameraMode::FirstPerson => camera.pos = eye,
+            CameraMode::Chase { distance, height } => {
+                let query = self.state.sim.line_query();
+                camera.pos = chase_view(&self.state.level, query, eye, ang, distance, height);
+            }
+            CameraMode::Free => return,
+        }
+        camera.yaw = ang;
+    }
+
+    /// Draw the level from `camera` into the view `renderer` was last
+    /// given.
+    fn draw_view<R: Renderer>(&mut self, renderer: &mut R, camera: &Camera) {
+        self.state
+            .level
+            .fill_active_subsectors(camera, &mut self.active);
+        let state = &mut self.state;
+        state.sim.blend_sectors(&mut state.level);
+        renderer.draw_level(&self.active, &state.level, &state.sim, camera, &state.bank);
+        state.sim.unblend_sectors(&mut state.level);
+        renderer.mark_seen(&state.level, state.session.seen_mut(&state.level.name));
+    }
+
+    pub fn camera_mode(&self) -> CameraMode {
+        self.camera_mode
+    }
+
+    /// Switch how the view is placed.  The free camera starts wherever
+    /// the last frame was seen from.
+    pub fn set_camera_mode(&mut self, mode: CameraMode) {
+        self.camera_mode = mode;
+        self.free_vel = Vec3::ZERO;
+    }
+
+    /// Fly the `CameraMode::Free` camera with up to `tics` commands from
+    /// `input`: walking moves it along its heading, `fly` up and down,
+    /// turning turns it.  Nothing happens in the other modes.
+    pub fn fly_camera(&mut self, input: &mut dyn InputSource, tics: u32) {
+        if self.camera_mode != CameraMode::Free {
+            return;
+        }
+        for _ in 0..tics {
+            let Some(cmd) = input.next_cmd() else {
+                break;
+            };
+            self.camera.turn(cmd.turn * FREE_CAM_TURN);
+            let fwd = self.camera.forward();
+            let right = Vec2::new(fwd.y, -fwd.x);
+            let accel = if cmd.run { 2.0 } else { 1.0 } * FREE_CAM_ACCEL;
+            let wish = (fwd * cmd.forward + right * cmd.strafe).extend(cmd.fly);
+            self.free_vel = self.free_vel * FREE_CAM_FRICTION + wish * accel;
+            self.camera.pos += self.free_vel;
+        }
+    }
+
+    /// Start the map `name` (`E1M3`, `MAP07`…) from scratch.
+    pub fn change_map(&mut self, name: &str) -> Result<(), GameError> {
+        self.state.warp(name)
+    }
+
+    /// Write the current map, sim and session to `path`.
+    pub fn save_game<P: AsRef<Path>>(&self, path: P) -> Result<(), SaveError> {
+        let state = &self.state;
+        saveload::save_game(path, &state.sim, &state.level, &state.session)
+    }
+
+    /// Restore a game saved on the current map.
+    pub fn load_game<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SaveError> {
+        let state = &mut self.state;
+        let switches = SwitchList::new(&state.bank);
+        let defs = state.defs.clone();
+        (state.sim, state.session) = saveload::load_game(path, &mut state.level, &switches, defs)?;
+        state.sim.set_physics(state.physics);
+        state.sim.set_autoaim(state.autoaim);
+        state.start_wipe = true;
+        Ok(())
+    }
+
+    /// How the screen is tinted for the player's pain, pickups and
+    /// powerups.
+    pub fn palette_effect(&self) -> PaletteEffect {
+        self.state
+            .player_status()
+            .map_or_else(PaletteEffect::default, |st| st.palette_effect())
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.state.level
+    }
+
+    pub fn sim(&self) -> &TicRunner {
+        &self.state.sim
+    }
+
+    pub fn wad(&self) -> &Wad {
+        &self.state.wad
+    }
+
+    pub fn textures(&self) -> &TextureBank {
+        &self.state.bank
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.state.phase
+    }
+
+    /// Where the last `render` looked from.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut GameState {
+        &mut self.state
+    }
+}
+
+/// Where `CameraMode::Chase` looks from: `distance` back from `eye`
+/// against `yaw` and `height` up, short of the first wall in the way,
+/// and inside the floor and ceiling it ends up over.
+fn chase_view(
+    level: &Level,
+    query: &BlockmapQuery,
+    eye: Vec3,
+    yaw: Angle,
+    distance: f32,
+    height: f32,
+) -> Vec3 {
+    let z = eye.z + height;
+    let back = -yaw.unit() * distance;
+    let mut frac = hitscan::trace_to_wall(level, query, eye.truncate(), z, back);
+    if frac < 1.0 {
+        frac = (frac - CHASE_WALL_GAP / distance).max(0.0);
+    }
+    let at = eye.truncate() + back * frac;
+    let sector = &level.sectors[level.sector_of_subsector(level.locate_subsector(at)) as usize];
+    let top = (sector.ceil_h - 4.0).max(sector.floor_h + 4.0);
+    at.extend(z.clamp(sector.floor_h + 4.0, top))
+}
+
+/// Marker of the map called `name`, any case.
+fn map_marker(wad: &Wad, name: &str) -> Result<usize, GameError> {
+    let name = name.to_ascii_uppercase();
+    wad.level_indices()
+        .into_iter()
+        .find(|&i| Wad::lump_name_str(&wad.lumps()[i].name) == name)
+        .ok_or(GameError::NoSuchMap(name))
+}
+
+/// Whether `visited` is the secret map of `map`'s episode (vanilla sets
+/// `didsecret` on the way there and on leaving it).
+fn secret_map(visited: &str, map: &str) -> bool {
+    let is_secret = |m: &str| m.len() == 4 && m.starts_with('E') && m.ends_with("M9");
+    is_secret(visited) && visited.get(..2) == map.get(..2) || is_secret(map)
+}
+
+/// Where a vanilla map's exits lead.
+#[derive(Debug, PartialEq, Eq)]
+enum Next {
+    Map(String),
+    /// The last map of an episode (or of Doom II): the finale would run.
+    EpisodeEnd,
+    /// Neither `ExMy` nor `MAPxx`.
+    Unknown,
+}
+
+/// Vanilla `G_DoCompleted` / `G_WorldDone` map order, including the secret
+/// levels and the way back from them.
+fn next_map(map: &str, exit: LevelExit) -> Next {
+    let secret = exit == LevelExit::Secret;
+    let b = map.as_bytes();
+    if let [b'E', e @ b'1'..=b'9', b'M', m @ b'1'..=b'9'] = *b {
+        let (e, m) = (e - b'0', m - b'0');
+        let m = match (secret, m) {
+            (true, _) => 9,
+            // back from the secret level
+            (false, 9) => match e {
+                1 => 4,
+                2 => 6,
+                3 => 7,
+                _ => 3,
+            },
+            (false, 8) => return Next::EpisodeEnd,
+            (false, m) => m + 1,
+        };
+        return Next::Map(format!("E{e}M{m}"));
+    }
+    if let Some(n) = map.strip_prefix("MAP").and_then(|n| n.parse::<u32>().ok()) {
+        let n = match (secret, n) {
+            (true, 15) => 31,
+            (true, 31) => 32,
+            (_, 31 | 32) => 16,
+            (_, 30) => return Next::EpisodeEnd,
+            (_, n) => n + 1,
+        };
+        return Next::Map(format!("MAP{n:02}"));
+    }
+    Next::Unknown
+}
+
+/// Load a map, set up a fresh `TicRunner` and spawn the map's things for
+/// `skill` with `players` players at the player 1‥4 starts.  Players
+/// past the map's starts are left out.  The sim plays by the skill's
+/// rules plus `rules`, and runs on `defs`.
+#[allow(clippy::too_many_arguments)]
+fn start_map(
+    wad: &Wad,
+    bank: &mut TextureBank,
+    marker: usize,
+    seed: u8,
+    skill: Skill,
+    rules: GameRules,
+    players: u8,
+    defs: &Arc<Definitions>,
+) -> Result<(Level, TicRunner), GameError> {
+    let mut level = load_level(wad, marker, bank)?;
+    level.finalise_bsp();
+
+    let mut sim = TicRunner::new(&level);
+    sim.set_definitions(defs.clone());
+    sim.set_rng(Rng::new(seed));
+    sim.set_switch_list(SwitchList::new(bank));
+    sim.set_skill(skill);
+    sim.set_rules(GameRules::for_skill(skill).union(rules));
+    sim.set_netgame(players > 1);
+
+    for thing in &level.things {
+        sim.spawn_map_thing(&level, thing);
+    }
+
+    if sim.spawn_player(&level).is_none() {
+        return Err(GameError::NoPlayerStart(level.name.clone()));
+    }
+    for n in 2..=players.clamp(1, MAX_PLAYERS as u8) {
+        if sim.spawn_player(&level).is_none() {
+            eprintln!("warning: {} has no player {n} start", level.name);
+            break;
+        }
+    }
+    sim.spawn_specials(&mut level);
+
+    Ok((level, sim))
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(name: &str) -> Next {
+        Next::Map(name.into())
+    }
+
+    #[test]
+    fn vanilla_map_order() {
+        use LevelExit::{Normal, Secret};
+        assert_eq!(next_map("E1M1", Normal), map("E1M2"));
+        assert_eq!(next_map("E1M3", Secret), map("E1M9"));
+        assert_eq!(next_map("E1M9", Normal), map("E1M4"));
+        assert_eq!(next_map("E3M9", Normal), map("E3M7"));
+        assert_eq!(next_map("E2M8", Normal), Next::EpisodeEnd);
+
+        assert_eq!(next_map("MAP01", Normal), map("MAP02"));
+        assert_eq!(next_map("MAP15", Secret), map("MAP31"));
+        assert_eq!(next_map("MAP31", Secret), map("MAP32"));
+        assert_eq!(next_map("MAP32", Normal), map("MAP16"));
+        assert_eq!(next_map("MAP30", Normal), Next::EpisodeEnd);
+        assert_eq!(next_map("START", Normal), Next::Unknown);
+    }
+
+    #[test]
+    fn chase_camera_stops_short_of_walls() {
+        let level = crate::world::testmap::three_rooms(crate::world::testmap::Middle::Wall);
+        let query = BlockmapQuery::default();
+        // facing east in the middle of room A: 64 back fits
+        let eye = Vec3::new(100.0, 64.0, 41.0);
+        let at = chase_view(&level, &query, eye, Angle::ZERO, 64.0, 16.0);
+        assert!((at - Vec3::new(36.0, 64.0, 57.0)).length() < 1e-3, "{at}");
+        // facing west near the middle wall: the view backs into it
+        let eye = Vec3::new(80.0, 64.0, 41.0);
+        let at = chase_view(&level, &query, eye, Angle::ANG180, 96.0, 16.0);
+        assert!(at.x > 80.0 && at.x <= 128.0 - CHASE_WALL_GAP + 1e-3, "{at}");
+        // and never into the ceiling
+        let at = chase_view(&level, &query, eye, Angle::ZERO, 32.0, 500.0);
+        assert!(at.z < level.sectors[0].ceil_h, "{at}");
+    }
+}
@@ -0,0 +1,309 @@
+//! Action ↔ control bindings and their INI-style text format.
+
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use thiserror::Error;
+
+use super::settings::{is_settings, section_of};
+
+/// The bindings shipped with the game.
+pub const DEFAULT_BINDINGS: &str = include_str!("default.ini");
+/// Split screen's second player.
+pub const PLAYER2_BINDINGS: &str = include_str!("player2.ini");
+
+/// Something the player can do with a key or button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Forward,
+    Back,
+    Left,
+    Right,
+    StrafeLeft,
+    StrafeRight,
+    /// Modifier: `Left` / `Right` strafe instead of turning.
+    Strafe,
+    /// Modifier: move and turn faster.
+    Run,
+    Fire,
+    Use,
+    FlyUp,
+    FlyDown,
+    Weapon1,
+    Weapon2,
+    Weapon3,
+    Weapon4,
+    Weapon5,
+    Weapon6,
+    Weapon7,
+}
+
+impl Action {
+    pub const ALL: [Action; 19] = [
+        Action::Forward,
+        Action::Back,
+        Action::Left,
+        Action::Right,
+        Action::StrafeLeft,
+        Action::StrafeRight,
+        Action::Strafe,
+        Action::Run,
+        Action::Fire,
+        Action::Use,
+        Action::FlyUp,
+        Action::FlyDown,
+        Action::Weapon1,
+        Action::Weapon2,
+        Action::Weapon3,
+        Action::Weapon4,
+        Action::Weapon5,
+        Action::Weapon6,
+        Action::Weapon7,
+    ];
+
+    /// Name used in binding files and by the `bind` command.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Forward => "forward",
+            Action::Back => "back",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::StrafeLeft => "strafeleft",
+            Action::StrafeRight => "straferight",
+            Action::Strafe => "strafe",
+            Action::Run => "run",
+            Action::Fire => "fire",
+            Action::Use => "use",
+            Action::FlyUp => "flyup",
+            Action::FlyDown => "flydown",
+            Action::Weapon1 => "weapon1",
+            Action::Weapon2 => "weapon2",
+            Action::Weapon3 => "weapon3",
+            Action::Weapon4 => "weapon4",
+            Action::Weapon5 => "weapon5",
+            Action::Weapon6 => "weapon6",
+            Action::Weapon7 => "weapon7",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Weapon slot 1‥7 this action selects.
+    pub fn weapon(self) -> Option<u8> {
+        Some(match self {
+            Action::Weapon1 => 1,
+            Action::Weapon2 => 2,
+            Action::Weapon3 => 3,
+            Action::Weapon4 => 4,
+            Action::Weapon5 => 5,
+            Action::Weapon6 => 6,
+            Action::Weapon7 => 7,
+            _ => return None,
+        })
+    }
+}
+
+/// A physical input.  Key names belong to whatever window library the
+/// frontend uses; they are only ever compared, case-insensitively.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Control {
+    Key(String),
+    /// 1 = left, 2 = right, 3 = middle.
+    Mouse(u8),
+}
+
+impl Control {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() || s.contains(char::is_whitespace) {
+            return None;
+        }
+        let lower = s.to_ascii_lowercase();
+        if let Some(n) = lower.strip_prefix("mouse") {
+            return match n.parse() {
+                Ok(n @ 1..=3) => Some(Control::Mouse(n)),
+                _ => None,
+            };
+        }
+        Some(Control::Key(s.to_owned()))
+    }
+
+    /// Does this control name `key`?
+    pub fn is_key(&self, key: &str) -> bool {
+        matches!(self, Control::Key(k) if k.eq_ignore_ascii_case(key))
+    }
+}
+
+impl fmt::Display for Control {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Control::Key(k) => f.write_str(k),
+            Control::Mouse(n) => write!(f, "mouse{n}"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum BindError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("line {line}: expected `action = control, …`")]
+    Syntax { line: usize },
+
+    #[error("line {line}: unknown action {name:?}")]
+    UnknownAction { line: usize, name: String },
+
+    #[error("line {line}: bad control {name:?}")]
+    BadControl { line: usize, name: String },
+
+    #[error("line {line}: bad setting {name:?}")]
+    BadSetting { line: usize, name: String },
+}
+
+/// Which controls trigger each action.  Actions missing from a file are
+/// simply unbound.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bindings {
+    map: HashMap<Action, Vec<Control>>,
+}
+
+impl Bindings {
+    /// Parse `action = control, control` lines; `#` / `;` start comments
+    /// and `[section]` headers are ignored, as is the `[settings]`
+    /// section [`Settings`](super::Settings) reads.
+    pub fn parse(text: &str) -> Result<Self, BindError> {
+        let mut out = Self::default();
+        let mut section = None;
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            if let Some(name) = section_of(raw) {
+                section = Some(name);
+                continue;
+            }
+            let s = raw.split(['#', ';']).next().unwrap_or("").trim();
+            if s.is_empty() || is_settings(&section) {
+                continue;
+            }
+            let (name, controls) = s.split_once('=').ok_or(BindError::Syntax { line })?;
+            let name = name.trim();
+            let action = Action::from_name(name).ok_or_else(|| BindError::UnknownAction {
+                line,
+                name: name.to_owned(),
+            })?;
+            let controls = controls
+                .split(',')
+                .filter(|c| !c.trim().is_empty())
+                .map(|c| {
+                    Control::parse(c).ok_or_else(|| BindError::BadControl {
+                        line,
+                        name: c.trim().to_owned(),
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            out.set(action, controls);
+        }
+        Ok(out)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BindError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// The shipped layout.
+    pub fn vanilla() -> Self {
+        Self::parse(DEFAULT_BINDINGS).expect("default bindings parse")
+    }
+
+    /// The second player's layout in split screen.
+    pub fn player_two() -> Self {
+        Self::parse(PLAYER2_BINDINGS).expect("player 2 bindings parse")
+    }
+
+    /// Unbind every control `other` uses, so two players can share a
+    /// keyboard.
+    pub fn without(mut self, other: &Bindings) -> Self {
+        let taken: Vec<&Control> = other.map.values().flatten().collect();
+        for controls in self.map.values_mut() {
+            controls.retain(|c| !taken.contains(&c));
+        }
+        self
+    }
+
+    /// Controls bound to `action`.
+    pub fn get(&self, action: Action) -> &[Control] {
+        self.map.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replace what `action` is bound to.
+    pub fn set(&mut self, action: Action, controls: Vec<Control>) {
+        self.map.insert(action, controls);
+    }
+
+    /// `action = control, …` for one action.
+    pub fn describe(&self, action: Action) -> String {
+        let controls: Vec<_> = self.get(action).iter().map(ToString::to_string).collect();
+        format!("{} = {}", action.name(), controls.join(", "))
+    }
+}
+
+/// Writes the same format [`Bindings::parse`] reads.
+impl fmt::Display for Bindings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for action in Action::ALL {
+            writeln!(f, "{}", self.describe(action))?;
+        }
+        Ok(())
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_file_binds_every_action() {
+        let b = Bindings::vanilla();
+        for action in Action::ALL {
+            assert!(!b.get(action).is_empty(), "{action:?} unbound");
+        }
+        assert!(b.get(Action::Fire).contains(&Control::Mouse(1)));
+        assert!(b.get(Action::Forward).iter().any(|c| c.is_key("up")));
+        assert_eq!(Bindings::parse(&b.to_string()).unwrap(), b);
+        assert_eq!(Action::Weapon3.weapon(), Some(3));
+        assert_eq!(Action::Use.weapon(), None);
+    }
+
+    #[test]
+    fn split_screen_layouts_share_no_keys() {
+        let two = Bindings::player_two();
+        let one = Bindings::vanilla().without(&two);
+        let keys_two: Vec<&Control> = Action::ALL.iter().flat_map(|&a| two.get(a)).collect();
+        for action in Action::ALL {
+            assert!(
+                one.get(action).iter().all(|c| !keys_two.contains(&c)),
+                "{action:?}"
+            );
+        }
+        assert!(!one.get(Action::Forward).iter().any(|c| c.is_key("w")));
+        assert!(one.get(Action::Forward).iter().any(|c| c.is_key("up")));
+        assert!(two.get(Action::Forward).iter().any(|c| c.is_key("w")));
+        assert!(!one.get(Action::Fire).is_empty());
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let err = Bindings::parse("# hi\nforward = W\njump = Space").unwrap_err();
+        assert!(matches!(err, BindError::UnknownAction { line: 3, .. }));
+        let err = Bindings::parse("[keys]\nfire LeftCtrl").unwrap_err();
+        assert!(matches!(err, BindError::Syntax { line: 2 }));
+        let err = Bindings::parse("fire = mouse9").unwrap_err();
+        assert!(matches!(err, BindError::BadControl { line: 1, .. }));
+    }
+}
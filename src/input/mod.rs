@@ -0,0 +1,187 @@
+//! Player input independent of the window library.
+//!
+//! The frontend samples its controls once per frame into an
+//! [`InputCollector`], which the sim then pulls exactly one `InputCmd` per
+//! tic from (it is an `InputSource`).  Mouse turning and button presses
+//! accumulate between tics, so nothing is lost when frames outpace the
+//! 35 Hz sim or fall behind it.
+
+mod bindings;
+mod settings;
+
+pub use bindings::{Action, BindError, Bindings, Control, DEFAULT_BINDINGS, PLAYER2_BINDINGS};
+pub use settings::{RENDER_SCALES, Settings};
+
+use crate::sim::{InputCmd, InputSource};
+
+/// Mouse travel, in pixels, worth one tic of keyboard turning.
+const DEFAULT_MOUSE_PIXELS_PER_TURN: f32 = 20.0;
+
+/// Set of held actions, one bit per `Action::ALL` entry.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ActionSet(u32);
+
+impl ActionSet {
+    fn bit(action: Action) -> u32 {
+        let i = Action::ALL.iter().position(|&a| a == action).unwrap_or(0);
+        1 << i
+    }
+
+    fn contains(self, action: Action) -> bool {
+        self.0 & Self::bit(action) != 0
+    }
+
+    fn insert(&mut self, action: Action) {
+        self.0 |= Self::bit(action);
+    }
+}
+
+pub struct InputCollector {
+    /// Actions held at the last sample.
+    held: ActionSet,
+    /// Actions that went down since the last tic.
+    pressed: ActionSet,
+    /// Mouse turn not yet handed to a tic, in keyboard-tic units.
+    mouse_turn: f32,
+    /// Pixels of horizontal mouse travel per tic of keyboard turning.
+    pub mouse_pixels_per_turn: f32,
+    /// Act as if `run` were always held.
+    pub always_run: bool,
+}
+
+impl Default for InputCollector {
+    fn default() -> Self {
+        Self {
+            held: ActionSet::default(),
+            pressed: ActionSet::default(),
+            mouse_turn: 0.0,
+            mouse_pixels_per_turn: DEFAULT_MOUSE_PIXELS_PER_TURN,
+            always_run: false,
+        }
+    }
+}
+
+impl InputCollector {
+    /// Sample one frame.  `down` says whether a control is held right now;
+    /// `mouse_dx` is the horizontal mouse travel since the last frame.
+    pub fn update(&mut self, bindings: &Bindings, down: impl Fn(&Control) -> bool, mouse_dx: f32) {
+        let mut held = ActionSet::default();
+        for action in Action::ALL {
+            if bindings.get(action).iter().any(&down) {
+                held.insert(action);
+            }
+        }
+        self.pressed.0 |= held.0 & !self.held.0;
+        self.held = held;
+        // mouse right turns right, which is a negative turn
+        self.mouse_turn -= mouse_dx / self.mouse_pixels_per_turn.max(f32::EPSILON);
+    }
+
+    /// Take the mouse sensitivity from the menu's settings.
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.mouse_pixels_per_turn = settings.mouse_pixels_per_turn(DEFAULT_MOUSE_PIXELS_PER_TURN);
+    }
+
+    /// Forget everything held or pending (the console took the keyboard).
+    pub fn clear(&mut self) {
+        self.held = ActionSet::default();
+        self.pressed = ActionSet::default();
+        self.mouse_turn = 0.0;
+    }
+
+    /// The command for one tic from the current state.
+    fn build(&self) -> InputCmd {
+        let held = self.held;
+        let axis = |pos: Action, neg: Action| {
+            (held.contains(pos) as i32 - held.contains(neg) as i32) as f32
+        };
+
+        let mut cmd = InputCmd {
+            forward: axis(Action::Forward, Action::Back),
+            strafe: axis(Action::StrafeRight, Action::StrafeLeft),
+            run: self.always_run || held.contains(Action::Run),
+            fire: held.contains(Action::Fire),
+            use_act: self.pressed.contains(Action::Use),
+            fly: axis(Action::FlyUp, Action::FlyDown),
+            ..InputCmd::default()
+        };
+
+        let turn = axis(Action::Left, Action::Right);
+        if held.contains(Action::Strafe) {
+            cmd.strafe = (cmd.strafe - turn).clamp(-1.0, 1.0);
+        } else {
+            cmd.turn = turn;
+        }
+        cmd.turn += self.mouse_turn;
+
+        cmd.weapon = Action::ALL
+            .into_iter()
+            .filter(|&a| self.pressed.contains(a))
+            .find_map(Action::weapon);
+        cmd
+    }
+}
+
+/// One command per tic; the first tic after a frame gets the mouse turn
+/// and the button presses, later ones in the same frame only what's held.
+impl InputSource for InputCollector {
+    fn next_cmd(&mut self) -> Option<InputCmd> {
+        let cmd = self.build();
+        self.pressed = ActionSet::default();
+        self.mouse_turn = 0.0;
+        Some(cmd)
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(names: &'static [&'static str]) -> impl Fn(&Control) -> bool {
+        move |c| names.iter().any(|n| c.is_key(n))
+    }
+
+    #[test]
+    fn builds_one_command_per_tic() {
+        let bindings = Bindings::vanilla();
+        let mut input = InputCollector::default();
+
+        input.update(&bindings, keys(&["W", "LeftShift", "Space", "Key3"]), 0.0);
+        let cmd = input.next_cmd().unwrap();
+        assert_eq!((cmd.forward, cmd.run, cmd.use_act), (1.0, true, true));
+        assert_eq!(cmd.weapon, Some(3));
+
+        // a second tic in the same frame: still walking, no new presses
+        let cmd = input.next_cmd().unwrap();
+        assert_eq!(cmd.forward, 1.0);
+        assert!(!cmd.use_act && cmd.weapon.is_none());
+
+        // still held next frame: not pressed again
+        input.update(&bindings, keys(&["W", "Space"]), 0.0);
+        assert!(!input.next_cmd().unwrap().use_act);
+    }
+
+    #[test]
+    fn mouse_turn_accumulates_between_tics() {
+        let bindings = Bindings::vanilla();
+        let mut input = InputCollector::default();
+        for _ in 0..3 {
+            input.update(&bindings, keys(&[]), 10.0);
+        }
+        let cmd = input.next_cmd().unwrap();
+        assert_eq!(cmd.turn, -30.0 / DEFAULT_MOUSE_PIXELS_PER_TURN);
+        assert_eq!(input.next_cmd().unwrap().turn, 0.0);
+    }
+
+    #[test]
+    fn strafe_modifier_turns_arrows_into_sidesteps() {
+        let bindings = Bindings::vanilla();
+        let mut input = InputCollector::default();
+        input.update(&bindings, keys(&["LeftAlt", "Right"]), 0.0);
+        let cmd = input.next_cmd().unwrap();
+        assert_eq!((cmd.turn, cmd.strafe), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,196 @@
+//! Options the menu changes, kept in a `[settings]` section of the
+//! bindings file so one file holds a player's whole setup.
+
+use std::{fmt, fs, io, path::Path};
+
+use super::{BindError, DEFAULT_BINDINGS};
+use crate::renderer::{SCREENBLOCKS_MAX, SCREENBLOCKS_MIN};
+
+/// Section header the settings live under.
+const SECTION: &str = "settings";
+
+/// Render scales the detail slider and F5 step through, like vanilla's
+/// detail toggle.
+pub const RENDER_SCALES: [f32; 3] = [0.5, 0.75, 1.0];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    /// 0‥[`Self::MAX_MOUSE_SENSITIVITY`], like vanilla's
+    /// `mouse_sensitivity`.
+    pub mouse_sensitivity: u8,
+    /// Software renderer scale, one of [`RENDER_SCALES`].
+    pub render_scale: f32,
+    /// View size, vanilla `screenblocks` (3‥11).  Full screen by
+    /// default; vanilla's 9 leaves room for a status bar.
+    pub screenblocks: u8,
+    /// Shots aim up and down at what's in line, as in vanilla.
+    pub autoaim: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mouse_sensitivity: 5,
+            render_scale: 1.0,
+            screenblocks: SCREENBLOCKS_MAX,
+            autoaim: true,
+        }
+    }
+}
+
+/// Name of the `[section]` a line opens, if it is a header.
+pub(super) fn section_of(line: &str) -> Option<String> {
+    let s = line.split(['#', ';']).next().unwrap_or("").trim();
+    let name = s.strip_prefix('[')?.strip_suffix(']')?;
+    Some(name.trim().to_ascii_lowercase())
+}
+
+/// Whether `section_of` a header names the settings section.
+pub(super) fn is_settings(section: &Option<String>) -> bool {
+    section.as_deref() == Some(SECTION)
+}
+
+impl Settings {
+    pub const MAX_MOUSE_SENSITIVITY: u8 = 9;
+
+    /// Read the `[settings]` section of a bindings file; the rest of the
+    /// file and any setting it lacks are left alone.
+    pub fn parse(text: &str) -> Result<Self, BindError> {
+        let mut out = Self::default();
+        let mut section = None;
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            if let Some(name) = section_of(raw) {
+                section = Some(name);
+                continue;
+            }
+            let s = raw.split(['#', ';']).next().unwrap_or("").trim();
+            if !is_settings(&section) || s.is_empty() {
+                continue;
+            }
+            let (name, value) = s.split_once('=').ok_or(BindError::Syntax { line })?;
+            let (name, value) = (name.trim(), value.trim());
+            let bad = || BindError::BadSetting {
+                line,
+                name: name.to_owned(),
+            };
+            match name {
+                "mouse_sensitivity" => {
+                    out.mouse_sensitivity = value
+                        .parse()
+                        .ok()
+                        .filter(|&v| v <= Self::MAX_MOUSE_SENSITIVITY)
+                        .ok_or_else(bad)?
+                }
+                "render_scale" => {
+                    out.render_scale = value
+                        .parse()
+                        .ok()
+                        .filter(|v| RENDER_SCALES.contains(v))
+                        .ok_or_else(bad)?
+                }
+                "screenblocks" => {
+                    out.screenblocks = value
+                        .parse()
+                        .ok()
+                        .filter(|v| (SCREENBLOCKS_MIN..=SCREENBLOCKS_MAX).contains(v))
+                        .ok_or_else(bad)?
+                }
+                "autoaim" => out.autoaim = value.parse().map_err(|_| bad())?,
+                _ => return Err(bad()),
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BindError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// `text` with its `[settings]` section replaced by these values, at
+    /// the end; every other line is kept as it was.
+    pub fn store(&self, text: &str) -> String {
+        let mut out = String::new();
+        let mut section = None;
+        for raw in text.lines() {
+            if let Some(name) = section_of(raw) {
+                section = Some(name);
+            }
+            if !is_settings(&section) {
+                out.push_str(raw);
+                out.push('\n');
+            }
+        }
+        let mut out = out.trim_end().to_owned();
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        out.push_str(&self.to_string());
+        out
+    }
+
+    /// Write into the bindings file at `path`, keeping its bindings; a
+    /// new file starts out with the default ones.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => DEFAULT_BINDINGS.to_owned(),
+            Err(e) => return Err(e),
+        };
+        fs::write(path, self.store(&text))
+    }
+
+    /// Pixels of mouse travel per tic of keyboard turning; the default
+    /// sensitivity gives `base`, each step up turns faster.
+    pub fn mouse_pixels_per_turn(&self, base: f32) -> f32 {
+        base * 6.0 / (f32::from(self.mouse_sensitivity) + 1.0)
+    }
+}
+
+/// The `[settings]` section [`Settings::parse`] reads.
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[{SECTION}]")?;
+        writeln!(f, "mouse_sensitivity = {}", self.mouse_sensitivity)?;
+        writeln!(f, "render_scale = {}", self.render_scale)?;
+        writeln!(f, "screenblocks = {}", self.screenblocks)?;
+        writeln!(f, "autoaim = {}", self.autoaim)
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{Action, Bindings};
+
+    #[test]
+    fn settings_share_the_bindings_file() {
+        let s = Settings {
+            mouse_sensitivity: 8,
+            render_scale: 0.75,
+            screenblocks: 7,
+            autoaim: false,
+        };
+        let text = s.store(DEFAULT_BINDINGS);
+        assert_eq!(Settings::parse(&text).unwrap(), s);
+        // the bindings read past the section untouched
+        assert_eq!(Bindings::parse(&text).unwrap(), Bindings::vanilla());
+
+        // storing again replaces the section instead of adding one
+        let again = Settings::default().store(&text);
+        assert_eq!(again.matches("[settings]").count(), 1);
+        assert_eq!(Settings::parse(&again).unwrap(), Settings::default());
+        assert!(Bindings::parse(&again).unwrap().get(Action::Use).len() == 1);
+    }
+
+    #[test]
+    fn bad_settings_name_the_line() {
+        let err = Settings::parse("fire = mouse1\n[settings]\nfov = 90").unwrap_err();
+        assert!(matches!(err, BindError::BadSetting { line: 3, .. }));
+        let err = Settings::parse("[Settings]\nmouse_sensitivity = 12").unwrap_err();
+        assert!(matches!(err, BindError::BadSetting { line: 2, .. }));
+    }
+}
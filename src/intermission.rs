@@ -0,0 +1,1043 @@
+//! The stats screen between two maps (vanilla `wi_stuff.c`, single
+//! player): the episode map with its animations, the kill / item /
+//! secret percentages counting up, the level time and par in the `WINUM`
+//! digits, then the "entering" screen with the "you are here" pointer.
+//! All of it is laid out on vanilla's 320×200 screen ([`Screen320`]).
+//!
+//! A WAD without the `WI*` graphics gets the stats as text in the HUD
+//! font instead, with the map titles as `WILVxx` / `CWILVxx` patches
+//! when it has them.
+
+use crate::console::{HUD_FONT, WHITE, text_scale};
+use crate::defs::Sound;
+use crate::level_info::LevelInfo;
+use crate::renderer::{Rgba, overlay::Canvas};
+use crate::screens::Screen320;
+use crate::sim::{LevelTotals, PlayerStatus, Rng, SIM_FPS};
+use crate::wad::{Wad, load_palettes, load_patch, patch_offsets};
+use crate::world::{Palette, Texture, TextureBank};
+
+/// Rows of [`Intermission::lines`] that hold the finished and next map's
+/// titles.
+const FINISHED_ROW: usize = 0;
+const NEXT_ROW: usize = 10;
+
+/// Top of the map titles (vanilla `WI_TITLEY`).
+const TITLE_Y: i32 = 2;
+/// Where the stats go (`SP_STATSX`, `SP_STATSY`, `SP_TIMEX`, `SP_TIMEY`).
+const STATS_X: i32 = 50;
+const STATS_Y: i32 = 50;
+const TIME_X: i32 = 16;
+const TIME_Y: i32 = 200 - 32;
+
+/// Tics between two counts (vanilla `cnt_pause`).
+const PAUSE_TICS: i32 = SIM_FPS as i32;
+/// How long the "you are here" screen stays up (`SHOWNEXTLOCDELAY`).
+const SHOW_NEXT_LOC_TICS: i32 = 4 * SIM_FPS as i32;
+/// Tics of it still shown once it's over (`WI_initNoState`).
+const NO_STATE_TICS: i32 = 10;
+/// Longest time `WI_drawTime` writes out; past it the level took `WISUCKS`.
+const MAX_CLOCK: i32 = 61 * 59;
+
+/// When an episode map animation moves on (vanilla `animenum_t`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AnimKind {
+    /// Loops forever, starting at a random tic.
+    Always,
+    /// Plays once when the next map is this one (0-based).
+    Level(usize),
+}
+
+/// One animation on an episode map (vanilla `anim_t`).
+#[derive(Clone, Copy, Debug)]
+struct AnimInfo {
+    kind: AnimKind,
+    /// Tics per frame.
+    period: i32,
+    frames: usize,
+    at: (i32, i32),
+}
+
+const fn always(period: i32, x: i32, y: i32) -> AnimInfo {
+    AnimInfo {
+        kind: AnimKind::Always,
+        period,
+        frames: 3,
+        at: (x, y),
+    }
+}
+
+const fn level(map: usize, frames: usize, x: i32, y: i32) -> AnimInfo {
+    AnimInfo {
+        kind: AnimKind::Level(map),
+        period: SIM_FPS as i32 / 5,
+        frames,
+        at: (x, y),
+    }
+}
+
+/// Vanilla `epsd0animinfo`‥`epsd2animinfo`; frame `f` of animation `a`
+/// on episode `e`'s map is `WIAeaaff`.
+const EPISODE_ANIMS: [&[AnimInfo]; 3] = [
+    &[
+        always(11, 224, 104),
+        always(11, 184, 160),
+        always(11, 112, 136),
+        always(11, 72, 112),
+        always(11, 88, 96),
+        always(11, 64, 48),
+        always(11, 192, 40),
+        always(11, 136, 16),
+        always(11, 80, 16),
+        always(11, 64, 24),
+    ],
+    &[
+        level(1, 1, 128, 136),
+        level(2, 1, 128, 136),
+        level(3, 1, 128, 136),
+        level(4, 1, 128, 136),
+        level(5, 1, 128, 136),
+        level(6, 1, 128, 136),
+        level(7, 1, 128, 136),
+        level(8, 3, 192, 144),
+        level(8, 1, 128, 136),
+    ],
+    &[
+        always(11, 104, 168),
+        always(11, 40, 136),
+        always(11, 160, 96),
+        always(11, 104, 80),
+        always(11, 120, 32),
+        always(8, 40, 0),
+    ],
+];
+
+/// Where each map of episodes 1‥3 sits on its episode map (`lnodes`).
+const LNODES: [[(i32, i32); 9]; 3] = [
+    [
+        (185, 164),
+        (148, 143),
+        (69, 122),
+        (209, 102),
+        (116, 89),
+        (166, 55),
+        (71, 56),
+        (135, 29),
+        (71, 24),
+    ],
+    [
+        (254, 25),
+        (97, 50),
+        (188, 64),
+        (128, 78),
+        (214, 92),
+        (133, 130),
+        (208, 136),
+        (148, 140),
+        (235, 158),
+    ],
+    [
+        (156, 168),
+        (48, 154),
+        (174, 95),
+        (265, 75),
+        (130, 48),
+        (279, 23),
+        (198, 48),
+        (140, 25),
+        (281, 136),
+    ],
+];
+
+/// Episode and map, both from 0, of an `ExMy` name.
+fn episode_map(map: &str) -> Option<(usize, usize)> {
+    match *map.as_bytes() {
+        [b'E', e @ b'1'..=b'9', b'M', m @ b'1'..=b'9'] => {
+            Some(((e - b'1') as usize, (m - b'1') as usize))
+        }
+        _ => None,
+    }
+}
+
+/// A `WI*` patch and the offsets `V_DrawPatch` places it by.
+#[derive(Clone, Debug, PartialEq)]
+struct Pic {
+    tex: Texture,
+    left: i32,
+    top: i32,
+}
+
+impl Pic {
+    fn load(wad: &Wad, name: &str) -> Option<Self> {
+        let (left, top) = patch_offsets(wad, name)?;
+        Some(Self {
+            tex: load_patch(wad, name)?,
+            left: left.into(),
+            top: top.into(),
+        })
+    }
+
+    /// Title patch without offsets, as `LevelInfo::title_pic` loads it.
+    fn title(tex: &Texture) -> Self {
+        Self {
+            tex: tex.clone(),
+            left: 0,
+            top: 0,
+        }
+    }
+
+    fn w(&self) -> i32 {
+        self.tex.w as i32
+    }
+
+    fn h(&self) -> i32 {
+        self.tex.h as i32
+    }
+}
+
+/// The intermission graphics (vanilla `WI_loadData`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct WiPics {
+    /// `WIMAP0`‥`WIMAP2`, or `INTERPIC` for Doom II and episode 4.
+    background: Pic,
+    /// Frames of the episode map's animations, in [`EPISODE_ANIMS`]
+    /// order; empty where the WAD lacks one.
+    anims: Vec<Vec<Pic>>,
+    /// "You are here", pointing left or right (`WIURH0`, `WIURH1`).
+    yah: [Option<Pic>; 2],
+    /// Blood splat on maps already done.
+    splat: Option<Pic>,
+    /// `WINUM0`‥`WINUM9`.
+    num: Vec<Pic>,
+    percent: Pic,
+    colon: Pic,
+    sucks: Option<Pic>,
+    kills: Pic,
+    items: Pic,
+    secret: Pic,
+    time: Pic,
+    par: Pic,
+    finished: Pic,
+    entering: Pic,
+    palette: Palette,
+}
+
+impl WiPics {
+    /// Everything the screen after a map of `episode` (from 0; `None`
+    /// for Doom II) needs; `None` if the WAD is missing any of the
+    /// background, digits or labels.
+    pub fn load(wad: &Wad, episode: Option<usize>) -> Option<Self> {
+        let background = match episode {
+            Some(e) if e < 3 => format!("WIMAP{e}"),
+            _ => "INTERPIC".to_owned(),
+        };
+        let anims = match episode.and_then(|e| Some((e, EPISODE_ANIMS.get(e)?))) {
+            Some((e, infos)) => (infos.iter().enumerate())
+                .map(|(a, info)| {
+                    // vanilla's hack: the last episode 2 animation shares
+                    // the fifth one's frames
+                    let a = if (e, a) == (1, 8) { 4 } else { a };
+                    (0..info.frames)
+                        .map(|f| Pic::load(wad, &format!("WIA{e}{a:02}{f:02}")))
+                        .collect::<Option<Vec<_>>>()
+                        .unwrap_or_default()
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        Some(Self {
+            background: Pic::load(wad, &background)?,
+            anims,
+            yah: [Pic::load(wad, "WIURH0"), Pic::load(wad, "WIURH1")],
+            splat: Pic::load(wad, "WISPLAT"),
+            num: (0..10)
+                .map(|n| Pic::load(wad, &format!("WINUM{n}")))
+                .collect::<Option<_>>()?,
+            percent: Pic::load(wad, "WIPCT")?,
+            colon: Pic::load(wad, "WICOLON")?,
+            sucks: Pic::load(wad, "WISUCKS"),
+            kills: Pic::load(wad, "WIOSTK")?,
+            items: Pic::load(wad, "WIOSTI")?,
+            secret: Pic::load(wad, "WISCRT2")?,
+            time: Pic::load(wad, "WITIME")?,
+            par: Pic::load(wad, "WIPAR")?,
+            finished: Pic::load(wad, "WIF")?,
+            entering: Pic::load(wad, "WIENTER")?,
+            palette: load_palettes(wad)?.into_iter().next()?,
+        })
+    }
+}
+
+/// Where the screen is (vanilla `state`, and `sp_state` within the
+/// stats).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    /// Counting up.  Odd steps pause a second; 2, 4, 6 and 8 count the
+    /// kills, items, secrets and the times; 10 waits for use.
+    Stats(u8),
+    /// The episode map pointing at the next map (`ShowNextLoc`).
+    ShowNextLoc,
+    /// The last few tics of it (`NoState`).
+    NoState,
+    /// Over: time for the next map.
+    Done,
+}
+
+/// The numbers shown after `finished`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Intermission {
+    pub finished: LevelInfo,
+    /// Map the player goes on to; `None` after the last map of an episode.
+    pub next: Option<LevelInfo>,
+    /// Title patches drawn instead of the names, see
+    /// [`Self::load_pics`].
+    pub finished_pic: Option<Texture>,
+    pub next_pic: Option<Texture>,
+    pub kills: i32,
+    pub items: i32,
+    pub secrets: i32,
+    /// Percentages of the level totals.
+    pub kill_pct: i32,
+    pub item_pct: i32,
+    pub secret_pct: i32,
+    /// Level time in whole seconds.
+    pub seconds: u32,
+    /// The episode's secret map was reached, so it gets a splat too
+    /// (vanilla `didsecret`).
+    pub did_secret: bool,
+    /// The `WI*` graphics; text in the HUD font without them.
+    pics: Option<Box<WiPics>>,
+    stage: Stage,
+    /// Tics since the screen came up (vanilla `bcnt`).
+    bcnt: i32,
+    /// Tics left of a pause or of the stage (`cnt_pause`, `cnt`).
+    cnt: i32,
+    /// Use or fire was pressed (`acceleratestage`).
+    accelerate: bool,
+    /// What the counts have got to; -1 until they start.
+    cnt_kills: i32,
+    cnt_items: i32,
+    cnt_secret: i32,
+    cnt_time: i32,
+    cnt_par: i32,
+    /// Frame on screen (-1 for none) and tic of the next one, of every
+    /// episode map animation (`anim_t` `ctr`, `nexttic`).
+    anims: Vec<(i32, i32)>,
+    /// The "you are here" pointer is lit (`snl_pointeron`).
+    pointer_on: bool,
+    /// Vanilla's `M_Random`, for when the looping animations start.
+    rng: Rng,
+}
+
+/// `seconds` as `m:ss`.
+fn clock(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// `n` of `total` as a percentage; an empty total counts as 1 (vanilla
+/// `WI_initVariables`), so a level without secrets shows 0%.
+fn percent(n: i32, total: i32) -> i32 {
+    n * 100 / total.max(1)
+}
+
+impl Intermission {
+    pub fn new(
+        finished: LevelInfo,
+        next: Option<LevelInfo>,
+        status: &PlayerStatus,
+        totals: LevelTotals,
+        gametic: u32,
+    ) -> Self {
+        let mut im = Self {
+            finished,
+            next,
+            finished_pic: None,
+            next_pic: None,
+            kills: status.kill_count,
+            items: status.item_count,
+            secrets: status.secret_count,
+            kill_pct: percent(status.kill_count, totals.kills),
+            item_pct: percent(status.item_count, totals.items),
+            secret_pct: percent(status.secret_count, totals.secrets),
+            seconds: gametic / SIM_FPS,
+            did_secret: false,
+            pics: None,
+            stage: Stage::Stats(1),
+            bcnt: 0,
+            cnt: PAUSE_TICS,
+            accelerate: false,
+            cnt_kills: -1,
+            cnt_items: -1,
+            cnt_secret: -1,
+            cnt_time: -1,
+            cnt_par: -1,
+            anims: Vec::new(),
+            pointer_on: false,
+            rng: Rng::default(),
+        };
+        im.init_animated_back();
+        im
+    }
+
+    /// Pick up both maps' title patches and the `WI*` graphics from
+    /// `wad`.
+    pub fn load_pics(&mut self, wad: &Wad) {
+        self.finished_pic = self.finished.title_pic(wad);
+        self.next_pic = self.next.as_ref().and_then(|next| next.title_pic(wad));
+        self.pics = WiPics::load(wad, self.episode()).map(Box::new);
+    }
+
+    /// Whether the `WI*` graphics were found.
+    pub fn has_pics(&self) -> bool {
+        self.pics.is_some()
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    /// The next map should start.
+    pub fn is_done(&self) -> bool {
+        self.stage == Stage::Done
+    }
+
+    /// Use or fire was pressed: finish the counts, or move on once
+    /// they're done.  The text screen has nothing to count and ends.
+    pub fn accelerate(&mut self) {
+        match self.pics {
+            Some(_) => self.accelerate = true,
+            None => self.stage = Stage::Done,
+        }
+    }
+
+    /// Vanilla `WI_Ticker`: run one tic and return the sound it starts.
+    pub fn ticker(&mut self) -> Option<Sound> {
+        self.bcnt += 1;
+        let accelerate = std::mem::take(&mut self.accelerate);
+        self.update_animated_back();
+        match self.stage {
+            Stage::Stats(sp_state) => self.update_stats(sp_state, accelerate),
+            Stage::ShowNextLoc => {
+                self.cnt -= 1;
+                if self.cnt == 0 || accelerate {
+                    self.init_no_state();
+                } else {
+                    self.pointer_on = (self.cnt & 31) < 20;
+                }
+                None
+            }
+            Stage::NoState => {
+                self.cnt -= 1;
+                if self.cnt == 0 {
+                    self.stage = Stage::Done;
+                }
+                None
+            }
+            Stage::Done => None,
+        }
+    }
+
+    /// Vanilla `WI_updateStats`: 2 % a tic with a pistol shot every 4,
+    /// the times 3 seconds a tic, an explosion as each count ends.
+    fn update_stats(&mut self, sp_state: u8, accelerate: bool) -> Option<Sound> {
+        let par = self.par_seconds();
+        if accelerate && sp_state != 10 {
+            self.cnt_kills = self.kill_pct;
+            self.cnt_items = self.item_pct;
+            self.cnt_secret = self.secret_pct;
+            self.cnt_time = self.seconds as i32;
+            self.cnt_par = par;
+            self.stage = Stage::Stats(10);
+            return Some(Sound::barexp);
+        }
+        let tick = (self.bcnt & 3 == 0).then_some(Sound::pistol);
+        match sp_state {
+            2 | 4 | 6 => {
+                let (cnt, to) = match sp_state {
+                    2 => (&mut self.cnt_kills, self.kill_pct),
+                    4 => (&mut self.cnt_items, self.item_pct),
+                    _ => (&mut self.cnt_secret, self.secret_pct),
+                };
+                *cnt += 2;
+                if *cnt < to {
+                    return tick;
+                }
+                *cnt = to;
+                self.stage = Stage::Stats(sp_state + 1);
+                Some(Sound::barexp)
+            }
+            8 => {
+                let time = self.seconds as i32;
+                self.cnt_time = (self.cnt_time + 3).min(time);
+                self.cnt_par = (self.cnt_par + 3).min(par);
+                if self.cnt_par < par || self.cnt_time < time {
+                    return tick;
+                }
+                self.stage = Stage::Stats(9);
+                Some(Sound::barexp)
+            }
+            10 => {
+                if !accelerate {
+                    return None;
+                }
+                // Doom II and the last map of an episode have no map
+                // to point at
+                if self.episode().is_some() && self.next.is_some() {
+                    self.init_show_next_loc();
+                } else {
+                    self.init_no_state();
+                }
+                Some(Sound::sgcock)
+            }
+            _ => {
+                self.cnt -= 1;
+                if self.cnt == 0 {
+                    self.stage = Stage::Stats(sp_state + 1);
+                    self.cnt = PAUSE_TICS;
+                }
+                None
+            }
+        }
+    }
+
+    fn init_show_next_loc(&mut self) {
+        self.stage = Stage::ShowNextLoc;
+        self.cnt = SHOW_NEXT_LOC_TICS;
+        self.init_animated_back();
+    }
+
+    fn init_no_state(&mut self) {
+        self.stage = Stage::NoState;
+        self.cnt = NO_STATE_TICS;
+    }
+
+    /// Episode of the finished map, from 0, if it's an `ExMy`.
+    fn episode(&self) -> Option<usize> {
+        episode_map(&self.finished.map).map(|(e, _)| e)
+    }
+
+    /// Map indices (from 0) of the finished and next map on the
+    /// episode map, if it has one.
+    fn episode_maps(&self) -> Option<(usize, usize, Option<usize>)> {
+        let (e, last) = episode_map(&self.finished.map).filter(|&(e, _)| e < 3)?;
+        let next = (self.next.as_ref())
+            .and_then(|n| episode_map(&n.map))
+            .map(|(_, m)| m);
+        Some((e, last, next))
+    }
+
+    /// Par in seconds; 0 for maps without one.
+    fn par_seconds(&self) -> i32 {
+        self.finished.par.map_or(0, |p| p as i32)
+    }
+
+    /// Vanilla `WI_initAnimatedBack`.
+    fn init_animated_back(&mut self) {
+        let Some((e, _, _)) = self.episode_maps() else {
+            return;
+        };
+        self.anims = (EPISODE_ANIMS[e].iter())
+            .map(|a| {
+                let next_tic = match a.kind {
+                    AnimKind::Always => self.bcnt + 1 + self.rng.p_random() % a.period,
+                    AnimKind::Level(_) => self.bcnt + 1,
+                };
+                (-1, next_tic)
+            })
+            .collect();
+    }
+
+    /// Vanilla `WI_updateAnimatedBack`.
+    fn update_animated_back(&mut self) {
+        let Some((e, _, next)) = self.episode_maps() else {
+            return;
+        };
+        let counting = matches!(self.stage, Stage::Stats(_));
+        for (i, (a, (ctr, next_tic))) in EPISODE_ANIMS[e].iter().zip(&mut self.anims).enumerate() {
+            if self.bcnt != *next_tic {
+                continue;
+            }
+            match a.kind {
+                AnimKind::Always => {
+                    *ctr = (*ctr + 1) % a.frames as i32;
+                    *next_tic = self.bcnt + a.period;
+                }
+                // vanilla's "gawd-awful hack": the eighth waits for
+                // the counts
+                AnimKind::Level(map) if !(counting && i == 7) && next == Some(map) => {
+                    *ctr = (*ctr + 1).min(a.frames as i32 - 1);
+                    *next_tic = self.bcnt + a.period;
+                }
+                AnimKind::Level(_) => {}
+            }
+        }
+    }
+
+    /// The screen's text, top to bottom.
+    pub fn lines(&self) -> Vec<String> {
+        let par = match self.finished.par {
+            Some(par) => format!("par     {}", clock(par)),
+            None => String::new(),
+        };
+        let (entering, next) = match &self.next {
+            Some(next) => ("entering".to_owned(), next.name.clone()),
+            None => ("episode complete".to_owned(), String::new()),
+        };
+        vec![
+            self.finished.name.clone(),
+            "finished".to_owned(),
+            String::new(),
+            format!("kills   {}%", self.kill_pct),
+            format!("items   {}%", self.item_pct),
+            format!("secret  {}%", self.secret_pct),
+            format!("time    {}", clock(self.seconds)),
+            par,
+            String::new(),
+            entering,
+            next,
+            "press use to continue".to_owned(),
+        ]
+    }
+
+    /// Draw the screen into the `w`×`h` frame: vanilla's graphics if the
+    /// WAD has them, else [`Self::lines`] in `bank`'s HUD font.
+    pub fn draw(&self, fb: &mut [Rgba], w: usize, h: usize, bank: &TextureBank) {
+        fb.fill(0xFF00_0000);
+        let Some(pics) = &self.pics else {
+            return self.draw_text(fb, w, h, bank);
+        };
+        let mut page = Page {
+            screen: Screen320::new(w, h),
+            pics,
+            fb,
+        };
+        page.put(&pics.background, 0, 0);
+        if let Some((e, _, _)) = self.episode_maps() {
+            for ((a, &(ctr, _)), frames) in
+                EPISODE_ANIMS[e].iter().zip(&self.anims).zip(&pics.anims)
+            {
+                if let Some(pic) = usize::try_from(ctr).ok().and_then(|f| frames.get(f)) {
+                    page.put(pic, a.at.0, a.at.1);
+                }
+            }
+        }
+        match self.stage {
+            Stage::Stats(_) => self.draw_stats(&mut page),
+            Stage::ShowNextLoc => self.draw_show_next_loc(&mut page, self.pointer_on),
+            Stage::NoState | Stage::Done => self.draw_show_next_loc(&mut page, true),
+        }
+    }
+
+    /// Vanilla `WI_drawStats`, under the finished map's title.
+    fn draw_stats(&self, page: &mut Page) {
+        let pics = page.pics;
+        let mut y = TITLE_Y;
+        if let Some(title) = &self.finished_pic {
+            let title = Pic::title(title);
+            page.centred(&title, y);
+            y += 5 * title.h() / 4;
+        }
+        page.centred(&pics.finished, y);
+
+        let lh = 3 * pics.num[0].h() / 2;
+        let rows = [
+            (&pics.kills, self.cnt_kills),
+            (&pics.items, self.cnt_items),
+            (&pics.secret, self.cnt_secret),
+        ];
+        for (row, (label, pct)) in rows.into_iter().enumerate() {
+            let y = STATS_Y + row as i32 * lh;
+            page.put(label, STATS_X, y);
+            page.percent(320 - STATS_X, y, pct);
+        }
+        page.put(&pics.time, TIME_X, TIME_Y);
+        page.time(160 - TIME_X, TIME_Y, self.cnt_time);
+        if self.finished.par.is_some() {
+            page.put(&pics.par, 160 + TIME_X, TIME_Y);
+            page.time(320 - TIME_X, TIME_Y, self.cnt_par);
+        }
+    }
+
+    /// Vanilla `WI_drawShowNextLoc`: splats on the maps done, the
+    /// pointer on the next one, and "entering" over its title.
+    fn draw_show_next_loc(&self, page: &mut Page, pointer_on: bool) {
+        let pics = page.pics;
+        if let Some((e, last, next)) = self.episode_maps() {
+            // back from the secret map: the maps up to where it left off
+            let last = match (last, next) {
+                (8, Some(next)) => next.saturating_sub(1),
+                _ => last,
+            };
+            let splat = [pics.splat.as_ref(), None];
+            for &at in &LNODES[e][..=last] {
+                page.on_lnode(at, splat);
+            }
+            if self.did_secret {
+                page.on_lnode(LNODES[e][8], splat);
+            }
+            if let Some(next) = next.filter(|_| pointer_on) {
+                page.on_lnode(
+                    LNODES[e][next],
+                    [pics.yah[0].as_ref(), pics.yah[1].as_ref()],
+                );
+            }
+        }
+        // no "entering" for Doom II's first secret map
+        if self.next.as_ref().is_some_and(|n| n.map != "MAP31") {
+            let mut y = TITLE_Y;
+            page.centred(&pics.entering, y);
+            if let Some(title) = &self.next_pic {
+                let title = Pic::title(title);
+                y += 5 * title.h() / 4;
+                page.centred(&title, y);
+            }
+        }
+    }
+
+    /// Black out the frame and print [`Self::lines`] centred on it, with
+    /// the title patches standing in for the map names.
+    fn draw_text(&self, fb: &mut [Rgba], w: usize, h: usize, bank: &TextureBank) {
+        let scale = text_scale(h) * 2;
+        let font = bank.font(HUD_FONT).map(|f| f.scaled(scale));
+        let line_h = font.map_or(8 * scale, |f| f.line_height());
+        let pic = |row| match row {
+            FINISHED_ROW => self.finished_pic.as_ref(),
+            NEXT_ROW => self.next_pic.as_ref(),
+            _ => None,
+        };
+        let row_h = |row| pic(row).map_or(line_h, |p| (p.h + 1) * scale);
+
+        let lines = self.lines();
+        let total: usize = (0..lines.len()).map(row_h).sum();
+        let mut y = h.saturating_sub(total) / 2;
+        let mut canvas = Canvas::new(fb, w, h);
+        for (row, line) in lines.iter().enumerate() {
+            match (pic(row), font) {
+                (Some(pic), _) => {
+                    let x = w.saturating_sub(pic.w * scale) / 2;
+                    canvas.blit_patch(pic, x as i32, y as i32, scale, |t| bank.get_color(0, t));
+                }
+                (None, Some(font)) => {
+                    let x = w.saturating_sub(font.text_width(line)) / 2;
+                    canvas.text(line, x as i32, y as i32, &font, WHITE);
+                }
+                (None, None) => {}
+            }
+            y += row_h(row);
+        }
+    }
+}
+
+/// A frame the `WI*` graphics draw into at 320×200 coordinates.
+struct Page<'a> {
+    screen: Screen320,
+    pics: &'a WiPics,
+    fb: &'a mut [Rgba],
+}
+
+impl Page<'_> {
+    /// `pic` with its origin at `(x, y)`, like `V_DrawPatch`.
+    fn put(&mut self, pic: &Pic, x: i32, y: i32) {
+        let (x, y) = (x - pic.left, y - pic.top);
+        (self.screen).draw_patch(&pic.tex, x, y, &self.pics.palette, self.fb);
+    }
+
+    /// `pic` across the middle of the screen at `y`.
+    fn centred(&mut self, pic: &Pic, y: i32) {
+        self.put(pic, (320 - pic.w()) / 2, y);
+    }
+
+    /// Vanilla `WI_drawNum`: `n` right-aligned to end at `x`, in
+    /// `digits` digits or as many as it takes.  Returns its left edge.
+    fn num(&mut self, x: i32, y: i32, n: i32, digits: Option<usize>) -> i32 {
+        let digits = digits.unwrap_or_else(|| n.max(1).ilog10() as usize + 1);
+        let width = self.pics.num[0].w();
+        let (mut x, mut n) = (x, n);
+        for _ in 0..digits {
+            x -= width;
+            let digit = &self.pics.num[(n % 10) as usize];
+            self.put(digit, x, y);
+            n /= 10;
+        }
+        x
+    }
+
+    /// Vanilla `WI_drawPercent`; nothing until the count starts.
+    fn percent(&mut self, x: i32, y: i32, p: i32) {
+        if p < 0 {
+            return;
+        }
+        self.put(&self.pics.percent, x, y);
+        self.num(x, y, p, None);
+    }
+
+    /// Vanilla `WI_drawTime`: `t` seconds as `m:ss` (or `h:mm:ss`)
+    /// ending at `x`, or `WISUCKS` past an hour.
+    fn time(&mut self, x: i32, y: i32, t: i32) {
+        if t < 0 {
+            return;
+        }
+        if t > MAX_CLOCK {
+            if let Some(sucks) = &self.pics.sucks {
+                self.put(sucks, x - sucks.w(), y);
+            }
+            return;
+        }
+        let colon = &self.pics.colon;
+        let (mut x, mut div) = (x, 1);
+        loop {
+            x = self.num(x, y, (t / div) % 60, Some(2)) - colon.w();
+            div *= 60;
+            if div == 60 || t / div != 0 {
+                self.put(colon, x, y);
+            }
+            if t / div == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Vanilla `WI_drawOnLnode`: the first of `pics` that fits on the
+    /// screen at the map spot `at`.
+    fn on_lnode(&mut self, at: (i32, i32), pics: [Option<&Pic>; 2]) {
+        let fits = |p: &&Pic| {
+            let (left, top) = (at.0 - p.left, at.1 - p.top);
+            left >= 0 && top >= 0 && left + p.w() < 320 && top + p.h() < 200
+        };
+        if let Some(pic) = pics.into_iter().flatten().find(fits) {
+            self.put(pic, at.0, at.1);
+        }
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// E1M1 done in 1:23 with half the kills, all the items and no
+    /// secrets to find, on to E1M2.
+    fn e1m1() -> Intermission {
+        let status = PlayerStatus {
+            kill_count: 3,
+            item_count: 4,
+            secret_count: 0,
+            ..PlayerStatus::default()
+        };
+        let totals = LevelTotals {
+            kills: 6,
+            items: 4,
+            secrets: 0,
+        };
+        Intermission::new(
+            LevelInfo::for_name("E1M1"),
+            Some(LevelInfo::for_name("E1M2")),
+            &status,
+            totals,
+            83 * SIM_FPS,
+        )
+    }
+
+    /// `w`×`h` patch of colour `c` with its origin at (`left`, `top`).
+    fn solid_patch(w: usize, h: usize, c: u8, left: i16, top: i16) -> Vec<u8> {
+        crate::wad::encode_patch(w, h, (left, top), |_, _| Some(c))
+    }
+
+    /// The `WI*` graphics as small solid patches: colour 10 + n for
+    /// digit n, and the rest as [`colour`] says.  Only the first
+    /// episode 1 animation is there.
+    fn wi_wad() -> Wad {
+        let mut playpal = vec![0u8; 768];
+        for i in 0..256 {
+            playpal[i * 3] = i as u8;
+        }
+        let mut lumps = vec![("PLAYPAL".to_owned(), playpal)];
+        let mut add = |name: &str, p: Vec<u8>| lumps.push((name.to_owned(), p));
+        add("WIMAP0", solid_patch(1, 1, 1, 0, 0));
+        for n in 0..10 {
+            add(&format!("WINUM{n}"), solid_patch(2, 2, 10 + n, 0, 0));
+        }
+        add("WIPCT", solid_patch(2, 2, 30, 0, 0));
+        add("WICOLON", solid_patch(2, 2, 31, 0, 0));
+        for label in [
+            "WIOSTK", "WIOSTI", "WISCRT2", "WITIME", "WIPAR", "WIF", "WIENTER",
+        ] {
+            add(label, solid_patch(4, 2, 40, 0, 0));
+        }
+        add("WIURH0", solid_patch(3, 3, 50, 1, 1));
+        add("WISPLAT", solid_patch(1, 1, 51, 0, 0));
+        for f in 0..3 {
+            add(&format!("WIA000{f:02}"), solid_patch(1, 1, 60 + f, 0, 0));
+        }
+        let lumps: Vec<_> = lumps.iter().map(|(n, p)| (n.as_str(), p.clone())).collect();
+        Wad::from_lumps(&lumps)
+    }
+
+    const BLACK: Rgba = 0xFF00_0000;
+
+    /// What [`wi_wad`] paints patch colour `c` as.
+    fn colour(c: u8) -> Rgba {
+        0xFF00_0000 | (c as u32) << 16
+    }
+
+    /// Tic until `done` says so; the sounds started on the way.
+    fn run(im: &mut Intermission, done: impl Fn(&Intermission) -> bool) -> Vec<Sound> {
+        let mut sounds = Vec::new();
+        for _ in 0..10_000 {
+            if done(im) {
+                return sounds;
+            }
+            sounds.extend(im.ticker());
+        }
+        panic!("stuck at {:?}", im.stage());
+    }
+
+    #[test]
+    fn counts_up_at_vanilla_speed() {
+        let mut im = e1m1();
+        im.load_pics(&wi_wad());
+        assert!(im.has_pics());
+
+        // a second's pause, then 2 % a tic from -1 with a shot every 4
+        let sounds = run(&mut im, |im| im.stage() == Stage::Stats(2));
+        assert!(sounds.is_empty());
+        assert_eq!(im.bcnt, PAUSE_TICS);
+        let sounds = run(&mut im, |im| im.stage() == Stage::Stats(3));
+        assert_eq!(im.bcnt, PAUSE_TICS + 26);
+        assert_eq!(im.cnt_kills, 50);
+        assert_eq!(sounds.last(), Some(&Sound::barexp));
+        assert_eq!(sounds.iter().filter(|&&s| s == Sound::pistol).count(), 7);
+
+        // the times count 3 s a tic until both are there
+        run(&mut im, |im| im.stage() == Stage::Stats(9));
+        assert_eq!((im.cnt_items, im.cnt_secret), (100, 0));
+        assert_eq!((im.cnt_time, im.cnt_par), (83, 30));
+
+        // then it waits for use
+        run(&mut im, |im| im.stage() == Stage::Stats(10));
+        for _ in 0..100 {
+            assert_eq!(im.ticker(), None);
+        }
+        im.accelerate();
+        assert_eq!(im.ticker(), Some(Sound::sgcock));
+        assert_eq!(im.stage(), Stage::ShowNextLoc);
+        let at = im.bcnt;
+        run(&mut im, |im| im.stage() == Stage::NoState);
+        assert_eq!(im.bcnt - at, SHOW_NEXT_LOC_TICS);
+        run(&mut im, Intermission::is_done);
+        assert_eq!(im.bcnt - at, SHOW_NEXT_LOC_TICS + NO_STATE_TICS);
+    }
+
+    #[test]
+    fn use_finishes_the_count_then_moves_on() {
+        let mut im = e1m1();
+        im.load_pics(&wi_wad());
+        run(&mut im, |im| im.cnt_kills > 10);
+        im.accelerate();
+        assert_eq!(im.ticker(), Some(Sound::barexp));
+        assert_eq!(im.stage(), Stage::Stats(10));
+        assert_eq!(
+            (
+                im.cnt_kills,
+                im.cnt_items,
+                im.cnt_secret,
+                im.cnt_time,
+                im.cnt_par
+            ),
+            (50, 100, 0, 83, 30)
+        );
+        im.accelerate();
+        im.ticker();
+        im.accelerate();
+        im.ticker();
+        assert_eq!(im.stage(), Stage::NoState);
+
+        // the text screen just ends
+        let mut text = e1m1();
+        text.accelerate();
+        assert!(text.is_done());
+    }
+
+    #[test]
+    fn stats_sit_where_vanilla_puts_them() {
+        let mut im = e1m1();
+        im.load_pics(&wi_wad());
+        im.accelerate();
+        im.ticker();
+        let (w, h) = (320, 200);
+        let mut fb = vec![0; w * h];
+        im.draw(&mut fb, w, h, &TextureBank::default_with_checker());
+        let at = |x: usize, y: usize| fb[y * w + x];
+
+        assert_eq!(at(0, 0), colour(1), "background");
+        assert_eq!(at(158, TITLE_Y as usize), colour(40), "finished");
+        // kills 50% ending at x 270, then 100% and 0% a row and a half
+        // of digits lower each
+        assert_eq!(at(270, 50), colour(30));
+        assert_eq!((at(268, 50), at(266, 50)), (colour(10), colour(15)));
+        assert_eq!(at(264, 50), BLACK);
+        assert_eq!((at(264, 53), at(268, 56)), (colour(11), colour(10)));
+        assert_eq!(at(50, 56), colour(40));
+        // 1:23 ending at 144 and the par of 0:30 at 304
+        let time: Vec<_> = (134..144).step_by(2).map(|x| at(x, 168)).collect();
+        let par: Vec<_> = (294..304).step_by(2).map(|x| at(x, 168)).collect();
+        assert_eq!(time, [10, 11, 31, 12, 13].map(colour));
+        // vanilla's `WI_drawTime` leaves off a 0 minute
+        assert_eq!(par, [BLACK, BLACK, colour(31), colour(13), colour(10)]);
+    }
+
+    #[test]
+    fn next_map_gets_the_pointer_and_done_ones_a_splat() {
+        let mut im = e1m1();
+        im.load_pics(&wi_wad());
+        im.accelerate();
+        im.ticker();
+        im.accelerate();
+        im.ticker();
+        // long enough for the animations to start
+        for _ in 0..12 {
+            im.ticker();
+        }
+        run(&mut im, |im| im.pointer_on);
+        let (w, h) = (320, 200);
+        let mut fb = vec![0; w * h];
+        im.draw(&mut fb, w, h, &TextureBank::default_with_checker());
+        let at = |(x, y): (i32, i32)| fb[y as usize * w + x as usize];
+        let (done, next) = (LNODES[0][0], LNODES[0][1]);
+        assert_eq!(at(done), colour(51));
+        // hung from its offsets
+        assert_eq!(at((next.0 - 1, next.1 - 1)), colour(50));
+        assert_eq!(at(LNODES[0][2]), BLACK);
+        assert_eq!(at((158, TITLE_Y)), colour(40), "entering");
+        // the first animation is running, the ones the WAD lacks aren't
+        let anim = at(EPISODE_ANIMS[0][0].at);
+        assert!((60..63).map(colour).any(|c| c == anim), "{anim:x}");
+
+        // pointer blinks off for 12 of every 32 tics
+        run(&mut im, |im| !im.pointer_on);
+        fb.fill(0);
+        im.draw(&mut fb, w, h, &TextureBank::default_with_checker());
+        assert_eq!(fb[next.1 as usize * w + next.0 as usize], BLACK);
+    }
+
+    #[test]
+    fn percentages_and_time() {
+        let im = e1m1();
+        assert_eq!((im.kill_pct, im.item_pct, im.secret_pct), (50, 100, 0));
+        let lines = im.lines();
+        assert_eq!(lines[FINISHED_ROW], "E1M1: Hangar");
+        assert_eq!(lines[6], "time    1:23");
+        assert_eq!(lines[7], "par     0:30");
+        assert_eq!(lines[9..=NEXT_ROW], ["entering", "E1M2: Nuclear Plant"]);
+    }
+
+    #[test]
+    fn animation_periods_match_vanilla() {
+        // TICRATE/3 and TICRATE/4 on episodes 1 and 3, TICRATE/5 on 2
+        assert_eq!(EPISODE_ANIMS[0][0].period, 11);
+        assert_eq!(EPISODE_ANIMS[2][5].period, 8);
+        assert!(EPISODE_ANIMS[1].iter().all(|a| a.period == 7));
+    }
+}
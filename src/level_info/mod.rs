@@ -0,0 +1,159 @@
+//! What a map is called and how fast it should be finished.
+//!
+//! A [`LevelInfoMap`] asks its [`LevelInfoSource`] layers in turn, the
+//! last one pushed first; [`Vanilla`] sits at the bottom.  A MAPINFO or
+//! UMAPINFO lump would become another layer on top.  A map none of the
+//! layers knows is named after its marker lump and has no par time.
+
+mod vanilla;
+
+use crate::wad::{Wad, load_patch};
+use crate::world::Texture;
+
+/// One map's title and par time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LevelInfo {
+    /// The marker lump, `E1M1` or `MAP01`.
+    pub map: String,
+    /// What the player is shown, `E1M1: Hangar`.
+    pub name: String,
+    /// Par time in seconds.
+    pub par: Option<u32>,
+    /// Patch lump with the title drawn in the intermission font
+    /// (`WILV00`, `CWILV00`); the WAD may not have it.
+    pub title_patch: Option<String>,
+}
+
+impl LevelInfo {
+    /// `map` as the vanilla table has it, or named after itself.
+    pub fn for_name(map: &str) -> Self {
+        LevelInfoMap::default().lookup(map)
+    }
+
+    /// A map no layer knows.
+    fn bare(map: &str) -> Self {
+        Self {
+            map: map.to_owned(),
+            name: map.to_owned(),
+            par: None,
+            title_patch: None,
+        }
+    }
+
+    /// The title patch out of `wad`, if it has one.
+    pub fn title_pic(&self, wad: &Wad) -> Option<Texture> {
+        load_patch(wad, self.title_patch.as_deref()?)
+    }
+}
+
+/// Somewhere map titles and par times come from.
+pub trait LevelInfoSource {
+    /// What this source knows about `map`, if anything.
+    fn level_info(&self, map: &str) -> Option<LevelInfo>;
+}
+
+/// The titles and par times vanilla compiles in.
+pub struct Vanilla;
+
+impl LevelInfoSource for Vanilla {
+    fn level_info(&self, map: &str) -> Option<LevelInfo> {
+        let b = map.as_bytes();
+        if let [b'E', e @ b'1'..=b'4', b'M', m @ b'1'..=b'9'] = *b {
+            let (e, m) = ((e - b'1') as usize, (m - b'1') as usize);
+            return Some(LevelInfo {
+                map: map.to_owned(),
+                name: vanilla::EPISODE_TITLES[e][m].to_owned(),
+                par: vanilla::EPISODE_PARS.get(e).map(|pars| pars[m]),
+                title_patch: Some(format!("WILV{e}{m}")),
+            });
+        }
+        let n = map.strip_prefix("MAP")?.parse::<usize>().ok()?;
+        let i = n
+            .checked_sub(1)
+            .filter(|&i| i < vanilla::MAP_TITLES.len())?;
+        Some(LevelInfo {
+            map: map.to_owned(),
+            name: vanilla::MAP_TITLES[i].to_owned(),
+            par: Some(vanilla::MAP_PARS[i]),
+            title_patch: Some(format!("CWILV{i:02}")),
+        })
+    }
+}
+
+/// Level info sources, consulted from the top.
+pub struct LevelInfoMap {
+    layers: Vec<Box<dyn LevelInfoSource>>,
+}
+
+impl Default for LevelInfoMap {
+    fn default() -> Self {
+        Self {
+            layers: vec![Box::new(Vanilla)],
+        }
+    }
+}
+
+impl LevelInfoMap {
+    /// Put `source` on top; it's asked before everything already there.
+    pub fn push_layer(&mut self, source: Box<dyn LevelInfoSource>) {
+        self.layers.push(source);
+    }
+
+    /// `map` from the topmost layer that knows it.
+    pub fn lookup(&self, map: &str) -> LevelInfo {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|layer| layer.level_info(map))
+            .unwrap_or_else(|| LevelInfo::bare(map))
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vanilla_names_and_pars() {
+        let e1m1 = LevelInfo::for_name("E1M1");
+        assert_eq!(e1m1.name, "E1M1: Hangar");
+        assert_eq!(e1m1.par, Some(30));
+        assert_eq!(e1m1.title_patch.as_deref(), Some("WILV00"));
+
+        let e3m9 = LevelInfo::for_name("E3M9");
+        assert_eq!((e3m9.name.as_str(), e3m9.par), ("E3M9: Warrens", Some(135)));
+        assert_eq!(LevelInfo::for_name("E4M2").par, None);
+
+        let map32 = LevelInfo::for_name("MAP32");
+        assert_eq!(map32.name, "level 32: grosse");
+        assert_eq!(map32.par, Some(30));
+        assert_eq!(map32.title_patch.as_deref(), Some("CWILV31"));
+    }
+
+    #[test]
+    fn unknown_maps_fall_back_to_the_lump_name() {
+        for map in ["MAP33", "MAP00", "E5M1", "SLIME01"] {
+            assert_eq!(LevelInfo::for_name(map), LevelInfo::bare(map));
+        }
+    }
+
+    #[test]
+    fn upper_layers_win() {
+        struct Pwad;
+        impl LevelInfoSource for Pwad {
+            fn level_info(&self, map: &str) -> Option<LevelInfo> {
+                (map == "MAP01").then(|| LevelInfo {
+                    name: "The Way In".to_owned(),
+                    ..LevelInfo::bare(map)
+                })
+            }
+        }
+        let mut infos = LevelInfoMap::default();
+        infos.push_layer(Box::new(Pwad));
+        assert_eq!(infos.lookup("MAP01").name, "The Way In");
+        assert_eq!(infos.lookup("MAP02").name, "level 2: underhalls");
+    }
+}
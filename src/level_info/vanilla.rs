@@ -0,0 +1,104 @@
+//! Vanilla's map titles (`d_englsh.h` `HUSTR_*`) and par times
+//! (`wi_stuff.c` `pars` / `cpars`), in seconds.
+//!
+//! Doom II's titles are the ones in `doom2.wad`; Final Doom's IWADs reuse
+//! the `MAPxx` names with titles of their own that aren't here.
+
+/// `ExMy` titles, per episode.
+pub const EPISODE_TITLES: [[&str; 9]; 4] = [
+    [
+        "E1M1: Hangar",
+        "E1M2: Nuclear Plant",
+        "E1M3: Toxin Refinery",
+        "E1M4: Command Control",
+        "E1M5: Phobos Lab",
+        "E1M6: Central Processing",
+        "E1M7: Computer Station",
+        "E1M8: Phobos Anomaly",
+        "E1M9: Military Base",
+    ],
+    [
+        "E2M1: Deimos Anomaly",
+        "E2M2: Containment Area",
+        "E2M3: Refinery",
+        "E2M4: Deimos Lab",
+        "E2M5: Command Center",
+        "E2M6: Halls of the Damned",
+        "E2M7: Spawning Vats",
+        "E2M8: Tower of Babel",
+        "E2M9: Fortress of Mystery",
+    ],
+    [
+        "E3M1: Hell Keep",
+        "E3M2: Slough of Despair",
+        "E3M3: Pandemonium",
+        "E3M4: House of Pain",
+        "E3M5: Unholy Cathedral",
+        "E3M6: Mt. Erebus",
+        "E3M7: Limbo",
+        "E3M8: Dis",
+        "E3M9: Warrens",
+    ],
+    [
+        "E4M1: Hell Beneath",
+        "E4M2: Perfect Hatred",
+        "E4M3: Sever The Wicked",
+        "E4M4: Unruly Evil",
+        "E4M5: They Will Repent",
+        "E4M6: Against Thee Wickedly",
+        "E4M7: And Hell Followed",
+        "E4M8: Unto The Cruel",
+        "E4M9: Fear",
+    ],
+];
+
+/// `ExMy` par times; vanilla has none for episode 4.
+pub const EPISODE_PARS: [[u32; 9]; 3] = [
+    [30, 75, 120, 90, 165, 180, 180, 30, 165],
+    [90, 90, 90, 120, 90, 360, 240, 30, 170],
+    [90, 45, 90, 150, 90, 90, 165, 30, 135],
+];
+
+/// `MAP01`‥`MAP32` titles.
+pub const MAP_TITLES: [&str; 32] = [
+    "level 1: entryway",
+    "level 2: underhalls",
+    "level 3: the gantlet",
+    "level 4: the focus",
+    "level 5: the waste tunnels",
+    "level 6: the crusher",
+    "level 7: dead simple",
+    "level 8: tricks and traps",
+    "level 9: the pit",
+    "level 10: refueling base",
+    "level 11: 'o' of destruction!",
+    "level 12: the factory",
+    "level 13: downtown",
+    "level 14: the inmost dens",
+    "level 15: industrial zone",
+    "level 16: suburbs",
+    "level 17: tenements",
+    "level 18: the courtyard",
+    "level 19: the citadel",
+    "level 20: gotcha!",
+    "level 21: nirvana",
+    "level 22: the catacombs",
+    "level 23: barrels o' fun",
+    "level 24: the chasm",
+    "level 25: bloodfalls",
+    "level 26: the abandoned mines",
+    "level 27: monster condo",
+    "level 28: the spirit world",
+    "level 29: the living end",
+    "level 30: icon of sin",
+    "level 31: wolfenstein",
+    "level 32: grosse",
+];
+
+/// `MAP01`‥`MAP32` par times.
+pub const MAP_PARS: [u32; 32] = [
+    30, 90, 120, 120, 90, 150, 120, 120, 270, 90, //
+    210, 150, 150, 150, 210, 150, 420, 150, 210, 150, //
+    240, 150, 180, 150, 150, 300, 330, 420, 300, 180, //
+    120, 30,
+];
@@ -1,5 +1,21 @@
+//! A Doom engine: WAD loading, the tic-based simulation and software (or
+//! `wgpu`) rendering.
+//!
+//! [`Game`] is the entry point for programs embedding the engine; the
+//! modules below it are what the bundled frontends are built from.
+
+pub mod console;
 pub mod defs;
+pub mod engine;
+pub mod game;
+pub mod input;
+pub mod intermission;
+pub mod level_info;
+pub mod menu;
 pub mod renderer;
+pub mod screens;
 pub mod sim;
 pub mod wad;
 pub mod world;
+
+pub use game::{Game, GameConfig};
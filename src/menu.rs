@@ -0,0 +1,545 @@
+//! The menu (vanilla `m_menu.c`): new game → episode → skill, the
+//! options sliders and the quit prompt, drawn with the WAD's `M_*`
+//! patches.
+//!
+//! Frontends open it on Escape and, while [`Menu::is_active`], pause the
+//! sim, hand it [`MenuKey`]s instead of building `InputCmd`s and act on
+//! the [`MenuEvent`]s that come back.  [`darken`] the game view first
+//! to draw the menu over it.
+
+use std::collections::HashMap;
+
+use crate::console::{HUD_FONT, WHITE, text_scale};
+use crate::input::{RENDER_SCALES, Settings};
+use crate::renderer::{Rgba, overlay};
+use crate::screens::Screen320;
+use crate::sim::Skill;
+use crate::wad::{Wad, load_palettes, load_patch};
+use crate::world::{FontRef, Palette, Texture, TextureBank};
+
+/// Rows between menu items (vanilla `LINEHEIGHT`).
+const LINE_HEIGHT: i32 = 16;
+/// Skull cursor position relative to the item (`SKULLXOFF`).
+const SKULL_X: i32 = -32;
+const SKULL_Y: i32 = -5;
+/// Tics each skull frame stays up.
+const SKULL_TICS: u32 = 8;
+/// Notches on the mouse sensitivity slider.
+const SENSITIVITY_STEPS: usize = Settings::MAX_MOUSE_SENSITIVITY as usize + 1;
+
+const QUIT_PROMPT: [&str; 4] = [
+    "are you sure you want to",
+    "quit this great game?",
+    "",
+    "(press y to quit.)",
+];
+
+/// The keys the menu understands, whatever window library sent them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuKey {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    /// Escape or backspace: one page up.
+    Back,
+    Char(char),
+}
+
+/// What the frontend has to do after a key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MenuEvent {
+    /// Start `map` (the episode's first) on `skill`; the menu has closed.
+    NewGame { map: String, skill: Skill },
+    /// A slider moved: apply the settings and save them.
+    Settings,
+    /// The player confirmed quitting.
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Page {
+    Main,
+    Episode,
+    Skill,
+    Options,
+    Quit,
+}
+
+/// Vanilla item `status`: 1 selectable, 2 slider, -1 a blank row the
+/// slider above draws into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Go,
+    Slider,
+    Blank,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Item {
+    patch: &'static str,
+    /// Drawn in the HUD font when the WAD lacks `patch`.
+    label: &'static str,
+    kind: Kind,
+}
+
+const fn go(patch: &'static str, label: &'static str) -> Item {
+    Item {
+        patch,
+        label,
+        kind: Kind::Go,
+    }
+}
+
+const BLANK: Item = Item {
+    patch: "",
+    label: "",
+    kind: Kind::Blank,
+};
+
+const MAIN: [Item; 3] = [
+    go("M_NGAME", "new game"),
+    go("M_OPTION", "options"),
+    go("M_QUITG", "quit game"),
+];
+const EPISODES: [Item; 4] = [
+    go("M_EPI1", "knee-deep in the dead"),
+    go("M_EPI2", "the shores of hell"),
+    go("M_EPI3", "inferno"),
+    go("M_EPI4", "thy flesh consumed"),
+];
+const SKILLS: [Item; 5] = [
+    go("M_JKILL", "i'm too young to die."),
+    go("M_ROUGH", "hey, not too rough."),
+    go("M_HURT", "hurt me plenty."),
+    go("M_ULTRA", "ultra-violence."),
+    go("M_NMARE", "nightmare!"),
+];
+const OPTIONS: [Item; 4] = [
+    Item {
+        patch: "M_MSENS",
+        label: "mouse sensitivity",
+        kind: Kind::Slider,
+    },
+    BLANK,
+    Item {
+        patch: "M_DETAIL",
+        label: "graphic detail",
+        kind: Kind::Slider,
+    },
+    BLANK,
+];
+
+/// Headings: patch, label and where they go.
+type Title = (&'static str, &'static str, i32, i32);
+
+/// Every patch the menu draws.
+const PATCHES: [&str; 25] = [
+    "M_DOOM", "M_NGAME", "M_OPTION", "M_QUITG", "M_EPISOD", "M_EPI1", "M_EPI2", "M_EPI3", "M_EPI4",
+    "M_NEWG", "M_SKILL", "M_JKILL", "M_ROUGH", "M_HURT", "M_ULTRA", "M_NMARE", "M_OPTTTL",
+    "M_MSENS", "M_DETAIL", "M_THERML", "M_THERMM", "M_THERMR", "M_THERMO", "M_SKULL1", "M_SKULL2",
+];
+
+pub struct Menu {
+    patches: HashMap<&'static str, Texture>,
+    palette: Palette,
+    /// Open pages, innermost last, each with its cursor; empty when the
+    /// menu is closed.
+    stack: Vec<(Page, usize)>,
+    /// Episode items the WAD has maps for, with the map each starts on.
+    /// Doom II has a single one and no episode page.
+    episodes: Vec<(Item, String)>,
+    /// Episode picked on the episode page.
+    episode: usize,
+    skull_tics: u32,
+    which_skull: usize,
+}
+
+impl Menu {
+    pub fn new(wad: &Wad) -> Self {
+        let patches = PATCHES
+            .into_iter()
+            .filter_map(|name| Some((name, load_patch(wad, name)?)))
+            .collect();
+        let palette = load_palettes(wad)
+            .and_then(|p| p.into_iter().next())
+            .unwrap_or_default();
+        let mut episodes: Vec<_> = EPISODES
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| (item, format!("E{}M1", i + 1)))
+            .filter(|(_, map)| wad.find_lump(map).is_some())
+            .collect();
+        if episodes.is_empty() {
+            // Doom II or a PWAD: whatever map comes first
+            let first = wad
+                .level_indices()
+                .first()
+                .map(|&i| Wad::lump_name_str(&wad.lumps()[i].name).to_owned());
+            episodes.extend(first.map(|map| (BLANK, map)));
+        }
+        Self {
+            patches,
+            palette,
+            stack: Vec::new(),
+            episodes,
+            episode: 0,
+            skull_tics: SKULL_TICS,
+            which_skull: 0,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    /// Show the main menu.
+    pub fn open(&mut self) {
+        self.stack = vec![(Page::Main, 0)];
+    }
+
+    pub fn close(&mut self) {
+        self.stack.clear();
+    }
+
+    /// The page on screen and its cursor.
+    pub fn current(&self) -> Option<(Page, usize)> {
+        self.stack.last().copied()
+    }
+
+    /// Run one 35 Hz tic: blink the skull.
+    pub fn ticker(&mut self) {
+        self.skull_tics -= 1;
+        if self.skull_tics == 0 {
+            self.which_skull ^= 1;
+            self.skull_tics = SKULL_TICS;
+        }
+    }
+
+    fn items(&self, page: Page) -> Vec<Item> {
+        match page {
+            Page::Main => MAIN.to_vec(),
+            Page::Episode => self.episodes.iter().map(|e| e.0).collect(),
+            Page::Skill => SKILLS.to_vec(),
+            Page::Options => OPTIONS.to_vec(),
+            Page::Quit => Vec::new(),
+        }
+    }
+
+    /// Where a page's items start, and its headings.
+    fn layout(page: Page) -> ((i32, i32), &'static [Title]) {
+        match page {
+            Page::Main => ((97, 64), &[("M_DOOM", "doom", 94, 2)]),
+            Page::Episode => ((48, 63), &[("M_EPISOD", "which episode?", 54, 38)]),
+            Page::Skill => (
+                (48, 63),
+                &[
+                    ("M_NEWG", "new game", 96, 14),
+                    ("M_SKILL", "choose skill level:", 54, 38),
+                ],
+            ),
+            Page::Options => ((60, 37), &[("M_OPTTTL", "options", 108, 15)]),
+            Page::Quit => ((0, 0), &[]),
+        }
+    }
+
+    /// Handle one key press.  Returns what the frontend must do about it.
+    pub fn responder(&mut self, key: MenuKey, settings: &mut Settings) -> Option<MenuEvent> {
+        let (page, cursor) = self.current()?;
+
+        if page == Page::Quit {
+            if matches!(key, MenuKey::Char('y' | 'Y')) {
+                self.close();
+                return Some(MenuEvent::Quit);
+            }
+            self.stack.pop();
+            return None;
+        }
+
+        let items = self.items(page);
+        let step = |dir: isize| {
+            let n = items.len() as isize;
+            let mut i = cursor as isize;
+            loop {
+                i = (i + dir).rem_euclid(n);
+                if items[i as usize].kind != Kind::Blank {
+                    return i as usize;
+                }
+            }
+        };
+
+        match key {
+            MenuKey::Up | MenuKey::Down if !items.is_empty() => {
+                let dir = if key == MenuKey::Up { -1 } else { 1 };
+                self.stack.last_mut()?.1 = step(dir);
+            }
+            MenuKey::Left | MenuKey::Right if items.get(cursor)?.kind == Kind::Slider => {
+                let right = key == MenuKey::Right;
+                return slide(page, cursor, right, settings).then_some(MenuEvent::Settings);
+            }
+            MenuKey::Back => {
+                self.stack.pop();
+            }
+            MenuKey::Enter => return self.choose(page, cursor),
+            _ => {}
+        }
+        None
+    }
+
+    /// Enter on item `cursor` of `page`.
+    fn choose(&mut self, page: Page, cursor: usize) -> Option<MenuEvent> {
+        let medium = Skill::Medium.index() as usize;
+        match (page, cursor) {
+            (Page::Main, 0) if self.episodes.len() > 1 => self.stack.push((Page::Episode, 0)),
+            (Page::Main, 0) => {
+                self.episode = 0;
+                self.stack.push((Page::Skill, medium));
+            }
+            (Page::Main, 1) => self.stack.push((Page::Options, 0)),
+            (Page::Main, 2) => self.stack.push((Page::Quit, 0)),
+            (Page::Episode, i) => {
+                self.episode = i;
+                self.stack.push((Page::Skill, medium));
+            }
+            (Page::Skill, i) => {
+                let map = self.episodes.get(self.episode)?.1.clone();
+                let skill = Skill::from_index(i as u8)?;
+                self.close();
+                return Some(MenuEvent::NewGame { map, skill });
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Draw the open page over `fb`, in 320×200 coordinates scaled to the
+    /// frame.  Text is in `bank`'s HUD font.
+    pub fn draw(
+        &self,
+        settings: &Settings,
+        fb: &mut [Rgba],
+        w: usize,
+        h: usize,
+        bank: &TextureBank,
+    ) {
+        let Some((page, cursor)) = self.current() else {
+            return;
+        };
+        let mut canvas = Canvas {
+            screen: Screen320::new(w, h),
+            font: bank.font(HUD_FONT),
+            fb,
+            w,
+            h,
+        };
+
+        if page == Page::Quit {
+            let Some(font) = canvas.font else {
+                return;
+            };
+            let font = font.scaled(text_scale(h));
+            let line_h = font.line_height();
+            let top = h.saturating_sub(QUIT_PROMPT.len() * line_h) / 2;
+            let mut out = overlay::Canvas::new(canvas.fb, w, h);
+            for (i, line) in QUIT_PROMPT.iter().enumerate() {
+                let x = w.saturating_sub(font.text_width(line)) / 2;
+                out.text(line, x as i32, (top + i * line_h) as i32, &font, WHITE);
+            }
+            return;
+        }
+
+        let ((x, y), titles) = Self::layout(page);
+        for &(patch, label, tx, ty) in titles {
+            self.draw_item(&mut canvas, patch, label, tx, ty);
+        }
+        for (i, item) in self.items(page).iter().enumerate() {
+            let iy = y + i as i32 * LINE_HEIGHT;
+            self.draw_item(&mut canvas, item.patch, item.label, x, iy);
+            if item.kind == Kind::Slider {
+                let (notches, dot) = slider(page, i, settings);
+                self.draw_thermo(&mut canvas, x, iy + LINE_HEIGHT, notches, dot);
+            }
+        }
+        let skull = ["M_SKULL1", "M_SKULL2"][self.which_skull];
+        if let Some(pic) = self.patches.get(skull) {
+            let sy = y + cursor as i32 * LINE_HEIGHT + SKULL_Y;
+            canvas
+                .screen
+                .draw_patch(pic, x + SKULL_X, sy, &self.palette, canvas.fb);
+        }
+    }
+
+    /// A patch, or `label` in the HUD font where the WAD lacks it.
+    fn draw_item(&self, canvas: &mut Canvas, patch: &str, label: &str, x: i32, y: i32) {
+        if let Some(pic) = self.patches.get(patch) {
+            canvas
+                .screen
+                .draw_patch(pic, x, y, &self.palette, canvas.fb);
+            return;
+        }
+        let Some(font) = canvas.font else {
+            return;
+        };
+        let (fx, fy) = canvas.screen.to_frame(x, y);
+        let font = font.scaled(canvas.screen.scale() as usize);
+        overlay::Canvas::new(canvas.fb, canvas.w, canvas.h).text(label, fx, fy, &font, WHITE);
+    }
+
+    /// Vanilla `M_DrawThermo`: ends, `notches` middles and the dot.
+    fn draw_thermo(&self, canvas: &mut Canvas, x: i32, y: i32, notches: usize, dot: usize) {
+        let mut draw = |name: &str, px: i32| {
+            if let Some(pic) = self.patches.get(name) {
+                canvas
+                    .screen
+                    .draw_patch(pic, px, y, &self.palette, canvas.fb);
+            }
+        };
+        draw("M_THERML", x);
+        for i in 0..notches {
+            draw("M_THERMM", x + 8 + i as i32 * 8);
+        }
+        draw("M_THERMR", x + 8 + notches as i32 * 8);
+        draw("M_THERMO", x + 8 + dot as i32 * 8);
+    }
+}
+
+/// The frame a draw goes to and what it draws with.
+struct Canvas<'a> {
+    screen: Screen320,
+    font: Option<FontRef<'a>>,
+    fb: &'a mut [Rgba],
+    w: usize,
+    h: usize,
+}
+
+/// Notches and dot position of slider `item` on `page`.
+fn slider(page: Page, item: usize, settings: &Settings) -> (usize, usize) {
+    match (page, item) {
+        (Page::Options, 0) => (SENSITIVITY_STEPS, settings.mouse_sensitivity as usize),
+        _ => (
+            RENDER_SCALES.len(),
+            RENDER_SCALES
+                .iter()
+                .position(|&s| s == settings.render_scale)
+                .unwrap_or(RENDER_SCALES.len() - 1),
+        ),
+    }
+}
+
+/// Move slider `item` of `page` a notch; `false` at either end.
+fn slide(page: Page, item: usize, right: bool, settings: &mut Settings) -> bool {
+    let (notches, dot) = slider(page, item, settings);
+    let next = match right {
+        true if dot + 1 < notches => dot + 1,
+        false if dot > 0 => dot - 1,
+        _ => return false,
+    };
+    match (page, item) {
+        (Page::Options, 0) => settings.mouse_sensitivity = next as u8,
+        _ => settings.render_scale = RENDER_SCALES[next],
+    }
+    true
+}
+
+/// Halve the frame's brightness so the menu stands out over the game.
+pub fn darken(fb: &mut [Rgba]) {
+    for p in fb {
+        *p = 0xFF00_0000 | (*p >> 1) & 0x007F_7F7F;
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::encode_patch;
+
+    fn two_episodes() -> Wad {
+        let mut playpal = vec![0u8; 768];
+        playpal[3..6].copy_from_slice(&[0xFF, 0, 0]);
+        Wad::from_lumps(&[
+            ("PLAYPAL", playpal),
+            ("E1M1", Vec::new()),
+            ("E2M1", Vec::new()),
+            ("M_SKULL1", encode_patch(1, 1, (0, 0), |_, _| Some(1))),
+        ])
+    }
+
+    #[test]
+    fn new_game_walks_episode_then_skill() {
+        let mut menu = Menu::new(&two_episodes());
+        let mut settings = Settings::default();
+        assert!(menu.responder(MenuKey::Enter, &mut settings).is_none());
+        menu.open();
+        menu.responder(MenuKey::Enter, &mut settings);
+        assert_eq!(menu.current(), Some((Page::Episode, 0)));
+        // two episodes in the WAD: down wraps back round after the second
+        menu.responder(MenuKey::Down, &mut settings);
+        menu.responder(MenuKey::Enter, &mut settings);
+        assert_eq!(menu.current(), Some((Page::Skill, 2)));
+        menu.responder(MenuKey::Down, &mut settings);
+        let event = menu.responder(MenuKey::Enter, &mut settings);
+        assert_eq!(
+            event,
+            Some(MenuEvent::NewGame {
+                map: "E2M1".into(),
+                skill: Skill::Hard
+            })
+        );
+        assert!(!menu.is_active());
+    }
+
+    #[test]
+    fn sliders_skip_blanks_and_stop_at_the_ends() {
+        let mut menu = Menu::new(&two_episodes());
+        let mut settings = Settings::default();
+        menu.open();
+        menu.responder(MenuKey::Down, &mut settings);
+        menu.responder(MenuKey::Enter, &mut settings);
+        let event = menu.responder(MenuKey::Right, &mut settings);
+        assert_eq!(event, Some(MenuEvent::Settings));
+        assert_eq!(settings.mouse_sensitivity, 6);
+
+        // the detail slider is two rows down, past the thermometer's blank
+        menu.responder(MenuKey::Down, &mut settings);
+        assert_eq!(menu.current(), Some((Page::Options, 2)));
+        assert!(menu.responder(MenuKey::Right, &mut settings).is_none());
+        menu.responder(MenuKey::Left, &mut settings);
+        assert_eq!(settings.render_scale, 0.75);
+
+        menu.responder(MenuKey::Back, &mut settings);
+        menu.responder(MenuKey::Back, &mut settings);
+        assert!(!menu.is_active());
+    }
+
+    #[test]
+    fn quit_needs_a_yes() {
+        let mut menu = Menu::new(&two_episodes());
+        let mut settings = Settings::default();
+        menu.open();
+        menu.responder(MenuKey::Up, &mut settings);
+        menu.responder(MenuKey::Enter, &mut settings);
+        assert_eq!(menu.current(), Some((Page::Quit, 0)));
+        assert!(menu.responder(MenuKey::Char('n'), &mut settings).is_none());
+        assert_eq!(menu.current(), Some((Page::Main, 2)));
+        menu.responder(MenuKey::Enter, &mut settings);
+        let event = menu.responder(MenuKey::Char('y'), &mut settings);
+        assert_eq!(event, Some(MenuEvent::Quit));
+    }
+
+    #[test]
+    fn skull_marks_the_cursor_over_a_darkened_frame() {
+        let mut menu = Menu::new(&two_episodes());
+        menu.open();
+        let mut fb = vec![0xFF80_4020; 320 * 200];
+        darken(&mut fb);
+        assert_eq!(fb[0], 0xFF40_2010);
+        let bank = TextureBank::default_with_checker();
+        menu.draw(&Settings::default(), &mut fb, 320, 200, &bank);
+        let (x, y) = (97 + SKULL_X, 64 + SKULL_Y);
+        assert_eq!(fb[y as usize * 320 + x as usize], 0xFFFF_0000);
+    }
+}
@@ -0,0 +1,263 @@
+//! Saving what's on the window as PNGs: a screenshot at a time, or every
+//! frame for comparison videos.
+//!
+//! A [`FrameWriter`] copies each frame it's given and hands it to a
+//! writer thread over a bounded channel, so encoding doesn't hold up the
+//! game.  When the thread falls [`QUEUE_FRAMES`] frames behind the game
+//! waits for it instead of dropping frames; a dump with gaps is no use
+//! for a video.  Each PNG names the map and gametic it shows in `tEXt`
+//! chunks.  Writing needs the `png` cargo feature.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, SyncSender, TryRecvError},
+    thread::JoinHandle,
+};
+
+use thiserror::Error;
+
+use super::Rgba;
+
+/// Frames waiting for the writer thread before the game waits for it.
+pub const QUEUE_FRAMES: usize = 8;
+
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    #[error("can't write to {}: {source}", .dir.display())]
+    Dir { dir: PathBuf, source: io::Error },
+
+    #[error("{}: {source}", .path.display())]
+    Write { path: PathBuf, source: io::Error },
+
+    #[error("saving PNGs needs the `png` cargo feature")]
+    NoPng,
+
+    #[error("the frame writer has stopped")]
+    Stopped,
+}
+
+/// Where a frame comes from, written into its PNG.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameInfo<'a> {
+    pub map: &'a str,
+    pub gametic: u64,
+}
+
+/// One frame on its way to disk, already as RGB bytes.
+#[cfg_attr(not(feature = "png"), allow(dead_code))]
+struct Job {
+    path: PathBuf,
+    rgb: Vec<u8>,
+    w: usize,
+    h: usize,
+    map: String,
+    gametic: u64,
+}
+
+/// Numbered PNGs into one directory, written in the background.
+pub struct FrameWriter {
+    dir: PathBuf,
+    prefix: String,
+    next: u32,
+    jobs: Option<SyncSender<Job>>,
+    errors: Receiver<CaptureError>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FrameWriter {
+    /// Write `<prefix>000000.png`, `<prefix>000001.png`, … into `dir`,
+    /// which is made if missing.  Numbering starts past any such files
+    /// already there, so nothing is overwritten.
+    pub fn new(dir: impl Into<PathBuf>, prefix: &str) -> Result<Self, CaptureError> {
+        let dir = dir.into();
+        check_writable(&dir).map_err(|source| CaptureError::Dir {
+            dir: dir.clone(),
+            source,
+        })?;
+        if !cfg!(feature = "png") {
+            return Err(CaptureError::NoPng);
+        }
+        let mut next = 0;
+        while dir.join(file_name(prefix, next)).exists() {
+            next += 1;
+        }
+
+        let (jobs, queue) = mpsc::sync_channel::<Job>(QUEUE_FRAMES);
+        let (report, errors) = mpsc::channel();
+        let thread = std::thread::spawn(move || {
+            for job in queue {
+                if let Err(e) = write_png(&job) {
+                    // the game hears of it on its next frame
+                    let _ = report.send(e);
+                    return;
+                }
+            }
+        });
+        Ok(Self {
+            dir,
+            prefix: prefix.to_owned(),
+            next,
+            jobs: Some(jobs),
+            errors,
+            thread: Some(thread),
+        })
+    }
+
+    /// Queue `fb`, a `w × h` frame, to be written; the path it will have.
+    /// Fails if an earlier frame couldn't be written, after which the
+    /// writer takes no more.
+    pub fn write(
+        &mut self,
+        fb: &[Rgba],
+        w: usize,
+        h: usize,
+        info: FrameInfo,
+    ) -> Result<PathBuf, CaptureError> {
+        match self.errors.try_recv() {
+            Ok(e) => {
+                self.jobs = None;
+                return Err(e);
+            }
+            Err(TryRecvError::Disconnected) if self.thread.is_none() => {
+                return Err(CaptureError::Stopped);
+            }
+            Err(_) => {}
+        }
+        let path = self.dir.join(file_name(&self.prefix, self.next));
+        self.next += 1;
+        let job = Job {
+            path: path.clone(),
+            rgb: fb[..w * h]
+                .iter()
+                .flat_map(|&p| [(p >> 16) as u8, (p >> 8) as u8, p as u8])
+                .collect(),
+            w,
+            h,
+            map: info.map.to_owned(),
+            gametic: info.gametic,
+        };
+        let jobs = self.jobs.as_ref().ok_or(CaptureError::Stopped)?;
+        jobs.send(job).map_err(|_| CaptureError::Stopped)?;
+        Ok(path)
+    }
+
+    /// Wait for every queued frame to be on disk.
+    pub fn finish(mut self) -> Result<(), CaptureError> {
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            thread.join().map_err(|_| CaptureError::Stopped)?;
+        }
+        match self.errors.try_recv() {
+            Ok(e) => Err(e),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Frames still queued are written before the writer goes.
+impl Drop for FrameWriter {
+    fn drop(&mut self) {
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn file_name(prefix: &str, n: u32) -> String {
+    format!("{prefix}{n:06}.png")
+}
+
+/// Make `dir` and prove a file can be created in it, so a bad directory
+/// is reported up front rather than on the first frame.
+fn check_writable(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(".yadoom-capture");
+    fs::write(&probe, b"")?;
+    fs::remove_file(probe)
+}
+
+#[cfg(feature = "png")]
+fn write_png(job: &Job) -> Result<(), CaptureError> {
+    let fail = |e: png::EncodingError| CaptureError::Write {
+        path: job.path.clone(),
+        source: e.into(),
+    };
+    let file = fs::File::create(&job.path).map_err(|source| CaptureError::Write {
+        path: job.path.clone(),
+        source,
+    })?;
+    let mut enc = png::Encoder::new(io::BufWriter::new(file), job.w as u32, job.h as u32);
+    enc.set_color(png::ColorType::Rgb);
+    enc.set_depth(png::BitDepth::Eight);
+    enc.add_text_chunk("Map".into(), job.map.clone())
+        .map_err(fail)?;
+    enc.add_text_chunk("Gametic".into(), job.gametic.to_string())
+        .map_err(fail)?;
+    enc.write_header()
+        .and_then(|mut w| w.write_image_data(&job.rgb))
+        .map_err(fail)
+}
+
+#[cfg(not(feature = "png"))]
+fn write_png(_: &Job) -> Result<(), CaptureError> {
+    Err(CaptureError::NoPng)
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yadoom-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn unwritable_directories_are_refused_up_front() {
+        let dir = scratch("capture-file");
+        fs::create_dir_all(&dir).unwrap();
+        // a directory can't be made under a plain file
+        fs::write(dir.join("file"), b"").unwrap();
+        let err = FrameWriter::new(dir.join("file").join("shots"), "shot").err();
+        assert!(matches!(err, Some(CaptureError::Dir { .. })), "{err:?}");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn frames_come_back_with_their_map_and_tic() {
+        let dir = scratch("capture-png");
+        fs::create_dir_all(&dir).unwrap();
+        // numbering skips what's already there
+        fs::write(dir.join("shot000000.png"), b"").unwrap();
+
+        let mut writer = FrameWriter::new(&dir, "shot").unwrap();
+        let fb: Vec<Rgba> = vec![0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0x0012_3456];
+        let info = FrameInfo {
+            map: "E1M1",
+            gametic: 35,
+        };
+        let first = writer.write(&fb, 2, 2, info).unwrap();
+        let second = writer.write(&fb, 2, 2, info).unwrap();
+        writer.finish().unwrap();
+        assert_eq!(first, dir.join("shot000001.png"));
+        assert_eq!(second, dir.join("shot000002.png"));
+
+        let decoder = png::Decoder::new(fs::File::open(&first).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+        let mut rgb = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut rgb).unwrap();
+        assert_eq!(rgb, [255, 0, 0, 0, 255, 0, 0, 0, 255, 0x12, 0x34, 0x56]);
+        let text: Vec<_> = (reader.info().uncompressed_latin1_text.iter())
+            .map(|t| (t.keyword.as_str(), t.text.as_str()))
+            .collect();
+        assert_eq!(text, [("Map", "E1M1"), ("Gametic", "35")]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
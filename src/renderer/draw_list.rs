@@ -0,0 +1,71 @@
+//! Backend-neutral draw list.
+//!
+//! The software renderer can record what it would rasterise instead of
+//! writing pixels: one command per wall/sprite column or plane row.  A
+//! hardware backend replays the list in order, so BSP traversal, clipping
+//! and sprite sorting stay in one place.
+
+use bitflags::bitflags;
+use glam::Vec2;
+use std::ops::RangeInclusive;
+
+use crate::world::TextureId;
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct DrawFlags: u8 {
+        /// Skip transparent texels (sprites, masked mid-textures).
+        const MASKED  = 0x01;
+        /// Clamp V to the texture instead of wrapping it.
+        const CLAMP_V = 0x02;
+    }
+}
+
+/// One textured screen rectangle with affine texel coordinates.
+///
+/// The texel sampled at pixel `(x, y)` is
+/// `uv + duv_dx * (x - x0) + duv_dy * (y - y0)`, truncated towards zero and
+/// wrapped (or clamped, see [`DrawFlags::CLAMP_V`]) to the texture size –
+/// exactly what the software inner loops do.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DrawCmd {
+    /// Inclusive screen rectangle.
+    pub x0: u16,
+    pub y0: u16,
+    pub x1: u16,
+    pub y1: u16,
+    pub tex: TextureId,
+    /// Colormap row (0 = full bright, 31 = darkest).
+    pub shade: u8,
+    pub flags: DrawFlags,
+    pub uv: Vec2,
+    pub duv_dx: Vec2,
+    pub duv_dy: Vec2,
+}
+
+impl DrawCmd {
+    /// A single column `x` over `rows`, with fixed `uv.x` and V stepping by
+    /// `dv` per row.
+    #[inline]
+    pub fn column(
+        x: usize,
+        rows: RangeInclusive<i32>,
+        tex: TextureId,
+        shade: u8,
+        uv: Vec2,
+        dv: f32,
+    ) -> Self {
+        Self {
+            x0: x as u16,
+            y0: *rows.start() as u16,
+            x1: x as u16,
+            y1: *rows.end() as u16,
+            tex,
+            shade,
+            flags: DrawFlags::empty(),
+            uv,
+            duv_dx: Vec2::ZERO,
+            duv_dy: Vec2::new(0.0, dv),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! Window-less rendering: one frame straight into a `Vec<Rgba>`.
+//!
+//! Used by the screenshot tool, benchmarks and the golden-image tests.
+
+use super::{Renderer, Rgba, Software};
+use crate::{
+    sim::TicRunner,
+    world::{Camera, Level, SubsectorId, TextureBank},
+};
+
+/// Render one `w × h` frame of `level` from `camera` with a fresh
+/// software renderer and return the pixels (row-major, 0x00RRGGBB).
+pub fn render_to_buffer(
+    level: &Level,
+    camera: &Camera,
+    sim: &TicRunner,
+    bank: &TextureBank,
+    w: usize,
+    h: usize,
+) -> Vec<Rgba> {
+    let mut renderer = Software::default();
+    let mut active: Vec<SubsectorId> = Vec::new();
+    let mut out = Vec::new();
+
+    renderer.begin_frame(w, h);
+    level.fill_active_subsectors(camera, &mut active);
+    renderer.draw_level(&active, level, sim, camera, bank);
+    renderer.end_frame(|fb, _, _| out.extend_from_slice(fb));
+    out
+}
+
+/// Coarse luminance signature: the frame averaged down to `cols × rows`
+/// cells, one byte each.  Robust against single-pixel rounding changes but
+/// still catches missing walls, wrong textures or broken clipping.
+pub fn frame_signature(fb: &[Rgba], w: usize, h: usize, cols: usize, rows: usize) -> Vec<u8> {
+    let mut sums = vec![0u64; cols * rows];
+    let mut counts = vec![0u64; cols * rows];
+
+    for y in 0..h {
+        let cy = y * rows / h;
+        for x in 0..w {
+            let cx = x * cols / w;
+            let p = fb[y * w + x];
+            let (r, g, b) = ((p >> 16) & 0xFF, (p >> 8) & 0xFF, p & 0xFF);
+            let lum = (r * 77 + g * 150 + b * 29) >> 8;
+            sums[cy * cols + cx] += lum as u64;
+            counts[cy * cols + cx] += 1;
+        }
+    }
+
+    sums.iter()
+        .zip(&counts)
+        .map(|(&s, &n)| (s / n.max(1)) as u8)
+        .collect()
+}
+
+/*====================================================================*/
+/*                        Golden-image tests                          */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        renderer::Filtering,
+        wad::{Wad, load_level},
+        world::{Colormap, Palette, Texture, testmap},
+    };
+    use glam::{Vec2, Vec3};
+    use std::{fs, path::PathBuf};
+
+    const W: usize = 640;
+    const H: usize = 400;
+    const SIG_COLS: usize = 32;
+    const SIG_ROWS: usize = 20;
+    /// Largest per-cell luminance drift still considered "the same frame".
+    const TOLERANCE: u8 = 4;
+
+    fn doom_wad() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join("doom.wad")
+    }
+
+    fn golden_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("renderer")
+            .join("golden")
+    }
+
+    /// Render E1M1 from the player start, looking along `yaw_deg`; `None`
+    /// (and a note) unless `assets/doom.wad` is present.
+    fn render_e1m1(yaw_deg: f32) -> Option<Vec<Rgba>> {
+        if !doom_wad().exists() {
+            eprintln!("skipping: {} not found", doom_wad().display());
+            return None;
+        }
+        let (level, sim, bank, eye) = e1m1_start();
+        let camera = Camera::new(eye, yaw_deg.to_radians(), 90_f32.to_radians());
+        Some(render_to_buffer(&level, &camera, &sim, &bank, W, H))
+    }
+
+    /// E1M1 with its things spawned, and the eye at the player start.
+    fn e1m1_start() -> (Level, TicRunner, TextureBank, Vec3) {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = TextureBank::default_with_checker();
+        let mut level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        level.finalise_bsp();
+
+        let mut sim = TicRunner::new(&level);
+        for thing in &level.things {
+            if let Some(info) = defs::by_doomednum(thing.type_id) {
+                sim.spawn_mobj(
+                    &level,
+                    info,
+                    thing.pos.x,
+                    thing.pos.y,
+                    thing.angle,
+                    thing.sub_sector,
+                );
+            }
+        }
+
+        let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+        let ss = level.locate_subsector(start.pos);
+        let floor = level.sectors[level.subsectors[ss as usize].sector as usize].floor_h;
+        let eye = start.pos.extend(floor + 41.0);
+        (level, sim, bank, eye)
+    }
+
+    /// Turn a full circle at `eye` in `step_deg` steps, at each of `sizes`;
+    /// any column or row drawn off the frame panics.
+    fn sweep_yaw(
+        level: &Level,
+        sim: &TicRunner,
+        bank: &TextureBank,
+        eye: Vec3,
+        step_deg: f32,
+        sizes: &[(usize, usize)],
+    ) {
+        let mut renderer = Software::default();
+        let mut active = Vec::new();
+        for &(w, h) in sizes {
+            let steps = (360.0 / step_deg) as u32;
+            for i in 0..steps {
+                let yaw = (i as f32 * step_deg).to_radians();
+                let camera = Camera::new(eye, yaw, 90_f32.to_radians());
+                renderer.begin_frame(w, h);
+                level.fill_active_subsectors(&camera, &mut active);
+                renderer.draw_level(&active, level, sim, &camera, bank);
+                renderer.end_frame(|fb, fw, fh| assert_eq!((fb.len(), fw, fh), (w * h, w, h)));
+            }
+        }
+    }
+
+    /// The test map with checkerboard walls and flats, from room A's west
+    /// end looking east through the open strip.
+    fn render_rooms(filtering: Filtering) -> Vec<Rgba> {
+        let mut bank = TextureBank::default_with_checker();
+        // greys, the checker's two indices at a quarter and half, darkening
+        // row by row
+        let mut palette = Palette::default();
+        for (i, c) in palette.0.iter_mut().enumerate() {
+            *c = (i as u32 * 8).min(255) * 0x01_01_01;
+        }
+        bank.set_palette(palette);
+        let mut colormap = Colormap::default();
+        for (row, shades) in colormap.0.iter_mut().enumerate() {
+            for (i, c) in shades.iter_mut().enumerate() {
+                *c = (i * (34 - row) / 34) as u8;
+            }
+        }
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+        let checker = bank.insert("CHECKER", Texture::default()).unwrap();
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.lower, sd.middle) = (checker, checker, checker);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (checker, checker);
+        }
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(16.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+
+        let mut renderer = Software::default();
+        renderer.set_filtering(filtering);
+        let mut active = Vec::new();
+        let mut out = Vec::new();
+        renderer.begin_frame(W, H);
+        level.fill_active_subsectors(&camera, &mut active);
+        renderer.draw_level(&active, &level, &sim, &camera, &bank);
+        renderer.end_frame(|fb, _, _| out.extend_from_slice(fb));
+        out
+    }
+
+    /// Compare against `golden/<name>.sig`.  Set `YADOOM_BLESS=1` to
+    /// (re)write the reference; without it a missing one is a failure.
+    fn check_golden(name: &str, fb: &[Rgba]) {
+        let sig = frame_signature(fb, W, H, SIG_COLS, SIG_ROWS);
+        let hex: String = sig.iter().map(|b| format!("{b:02x}")).collect();
+        let path = golden_dir().join(format!("{name}.sig"));
+
+        if std::env::var_os("YADOOM_BLESS").is_some() {
+            fs::create_dir_all(golden_dir()).unwrap();
+            fs::write(&path, &hex).unwrap();
+            eprintln!("blessed {}", path.display());
+            return;
+        }
+
+        let Ok(want) = fs::read_to_string(&path) else {
+            panic!(
+                "{name}: no reference at {}; run with YADOOM_BLESS=1 to write it",
+                path.display()
+            );
+        };
+        let want: Vec<u8> = (0..want.trim().len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&want[i..i + 2], 16).unwrap())
+            .collect();
+        assert_eq!(want.len(), sig.len(), "{name}: signature size changed");
+
+        let worst = sig
+            .iter()
+            .zip(&want)
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0);
+        assert!(
+            worst <= TOLERANCE,
+            "{name}: frame differs from golden (max cell delta {worst})"
+        );
+    }
+
+    #[test]
+    fn signature_of_flat_frame() {
+        let fb = vec![0x00_80_80_80; 64 * 40];
+        let sig = frame_signature(&fb, 64, 40, 8, 5);
+        assert_eq!(sig.len(), 40);
+        assert!(sig.iter().all(|&l| l == 0x80));
+    }
+
+    #[test]
+    fn golden_e1m1_start() {
+        if let Some(fb) = render_e1m1(90.0) {
+            check_golden("e1m1_start", &fb);
+        }
+    }
+
+    #[test]
+    fn golden_e1m1_east() {
+        if let Some(fb) = render_e1m1(0.0) {
+            check_golden("e1m1_east", &fb);
+        }
+    }
+
+    #[test]
+    fn golden_e1m1_back() {
+        if let Some(fb) = render_e1m1(225.0) {
+            check_golden("e1m1_back", &fb);
+        }
+    }
+
+    /// Across the start room at its alcoves, where the bands used to stop a
+    /// row past each upper and lower wall and leave a trail of last frame
+    /// along the opening's edges.  Needs the WAD for its reference as well;
+    /// `portal_edges_leave_no_unpainted_rows` in the software renderer
+    /// covers the same bands without it.
+    #[test]
+    fn golden_e1m1_alcoves() {
+        if let Some(fb) = render_e1m1(45.0) {
+            check_golden("e1m1_alcoves", &fb);
+        }
+    }
+
+    #[test]
+    fn golden_rooms_nearest() {
+        check_golden("rooms_nearest", &render_rooms(Filtering::Nearest));
+    }
+
+    /// Blurred, but no lighter or darker overall than the nearest frame.
+    #[test]
+    fn golden_rooms_bilinear() {
+        let fb = render_rooms(Filtering::Bilinear);
+        check_golden("rooms_bilinear", &fb);
+        assert_ne!(fb, render_rooms(Filtering::Nearest));
+    }
+
+    /// Every yaw at the E1M1 start, at even and odd view sizes; skipped
+    /// unless `assets/doom.wad` is present.
+    #[test]
+    fn e1m1_start_yaw_sweep_stays_on_screen() {
+        if !doom_wad().exists() {
+            eprintln!("skipping: {} not found", doom_wad().display());
+            return;
+        }
+        let (level, sim, bank, eye) = e1m1_start();
+        sweep_yaw(&level, &sim, &bank, eye, 0.5, &[(640, 400), (641, 399)]);
+    }
+
+    /// The same sweep with the eye all but touching a wall corner and a
+    /// sprite on top of it, where projections run furthest off the frame.
+    #[test]
+    fn yaw_sweep_against_a_corner_stays_on_screen() {
+        let mut bank = TextureBank::default_with_checker();
+        let head = bank.insert("HEADA0", Texture::default()).unwrap();
+        bank.register_sprite_lump("HEADA0", head);
+        bank.build_shade_table();
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        let info = defs::by_id("HEAD").unwrap();
+        for pos in [Vec2::new(0.5, 0.5), Vec2::new(127.9, 64.0)] {
+            let ss = level.locate_subsector(pos);
+            sim.spawn_mobj(&level, info, pos.x, pos.y, 0.0, ss);
+        }
+        for eye in [Vec3::new(0.01, 0.01, 41.0), Vec3::new(127.99, 64.0, 41.0)] {
+            sweep_yaw(&level, &sim, &bank, eye, 0.5, &[(640, 400), (641, 399)]);
+        }
+    }
+
+    /// Doom 2 smoke test; skipped unless `assets/doom2.wad` is present.
+    #[test]
+    fn doom2_map01_renders() {
+        let path = doom_wad().with_file_name("doom2.wad");
+        if !path.exists() {
+            eprintln!("skipping: {} not found", path.display());
+            return;
+        }
+        let wad = Wad::from_file(path).unwrap();
+        let marker = wad.find_lump("MAP01").expect("MAP01 marker");
+        assert!(wad.level_indices().contains(&marker));
+
+        let mut bank = TextureBank::default_with_checker();
+        let mut level = load_level(&wad, marker, &mut bank).unwrap();
+        level.finalise_bsp();
+        assert_eq!(bank.id("SKY1"), Some(level.sky_texture));
+
+        let mut sim = TicRunner::new(&level);
+        let spawned = level
+            .things
+            .iter()
+            .filter_map(|t| sim.spawn_map_thing(&level, t))
+            .count();
+        assert!(spawned > 0);
+
+        let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+        let ss = level.locate_subsector(start.pos);
+        let floor = level.sectors[level.subsectors[ss as usize].sector as usize].floor_h;
+        let camera = Camera::new(
+            start.pos.extend(floor + 41.0),
+            start.angle,
+            90_f32.to_radians(),
+        );
+        let fb = render_to_buffer(&level, &camera, &sim, &bank, W, H);
+        assert_eq!(fb.len(), W * H);
+    }
+}
@@ -1,14 +1,36 @@
 //! Rendering abstraction layer.
+pub use stats::RenderStats;
+
 use crate::{
+    screens::ViewBorder,
     sim::TicRunner,
-    world::{Camera, Level, SubsectorId, TextureBank},
+    world::{Camera, FontRef, Level, LineSet, SubsectorId, Texture, TextureBank},
 };
 
 /// Pixel format of the software frame-buffer (0x00RRGGBB).
 pub type Rgba = u32;
 
+/// A frame is `begin_view`, then any of the 3-D calls (`draw_border`,
+/// `draw_level`) and the 2-D ones (`draw_line`, `fill_rect`, `dim_rect`,
+/// `blit_patch`, `draw_text`), then `end_frame`.  The 2-D calls are in window
+/// coordinates, clipped to the window, and always land over the level
+/// and its sprites, in the order they were made, whether they came
+/// before or after `draw_level`.
 pub trait Renderer {
-    fn begin_frame(&mut self, w: usize, h: usize);
+    /// Start a `w × h` frame whose 3-D view fills `view` of it.
+    fn begin_view(&mut self, w: usize, h: usize, view: ViewWindow);
+
+    /// Start a `w × h` frame that is all view.
+    fn begin_frame(&mut self, w: usize, h: usize) {
+        self.begin_view(w, h, ViewWindow::full(w, h));
+    }
+
+    /// Fill the frame around the view with `border`, if the view or the
+    /// frame changed size since it last was.  Renderers whose view is
+    /// always the whole frame leave this alone.
+    fn draw_border(&mut self, border: &ViewBorder) {
+        let _ = border;
+    }
 
     fn draw_level(
         &mut self,
@@ -21,10 +43,58 @@ pub trait Renderer {
 
     fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, col: u32);
 
+    /// Fill `w × h` pixels from (`x`, `y`) with `col`.
+    fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, col: Rgba) {
+        let _ = (x, y, w, h, col);
+    }
+
+    /// Halve the brightness of `w × h` pixels from (`x`, `y`), for a
+    /// translucent backdrop.
+    fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        let _ = (x, y, w, h);
+    }
+
+    /// A Doom-format graphic with its top-left corner at (`x`, `y`), lit
+    /// at colormap row `shade` of `bank`'s palette; see-through texels
+    /// are skipped.  The patch's own offsets are the caller's business.
+    fn blit_patch(&mut self, patch: &Texture, x: i32, y: i32, shade: u8, bank: &TextureBank) {
+        let _ = (patch, x, y, shade, bank);
+    }
+
+    /// `text` in `font` with its top-left corner at (`x`, `y`), each
+    /// pixel tinted by `col`; white draws the font's own colours.
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        let _ = (text, x, y, font, col);
+    }
+
+    /// Add the lines whose walls the last `draw_level` drew to `seen`.
+    /// Renderers that don't keep track leave it alone.
+    fn mark_seen(&self, level: &Level, seen: &mut LineSet) {
+        let _ = (level, seen);
+    }
+
+    /// Counters for the frame drawn since the last `begin_frame`.
+    fn stats(&self) -> &RenderStats {
+        &RenderStats::ZERO
+    }
+
     fn end_frame<F>(&mut self, submit: F)
     where
         F: FnOnce(&[Rgba], usize, usize);
 }
 
+pub mod capture;
+mod draw_list;
+mod headless;
+pub mod overlay;
+pub mod presentation;
 mod software;
-pub use software::Software;
+mod stats;
+mod view_window;
+#[cfg(feature = "wgpu")]
+pub mod wgpu;
+pub mod wipe;
+pub use draw_list::{DrawCmd, DrawFlags};
+pub use headless::{frame_signature, render_to_buffer};
+pub use software::{CompatOptions, DIST_PER_SHADE, Filtering, LightMode, Software};
+pub use view_window::{SCREENBLOCKS_MAX, SCREENBLOCKS_MIN, SCREENBLOCKS_STATUS_BAR, ViewWindow};
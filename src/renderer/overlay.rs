@@ -0,0 +1,439 @@
+//! The 2-D pass: lines, filled rectangles, patches and text over the 3-D
+//! frame, for the HUD, the console and debug drawing.
+//!
+//! A [`Canvas`] draws straight into a frame, clipped to it.  An
+//! [`Overlay`] keeps the 2-D calls a renderer gets during a frame and
+//! plays them onto the finished frame in `end_frame`, so they land over
+//! the level and its sprites whenever they were made, in the order they
+//! were made.
+
+use std::ops::Range;
+
+use super::{Renderer, Rgba};
+use crate::world::{FONT_SPACE_W, FontRef, Texture};
+
+/// `c` with each channel scaled by `tint`'s; white leaves it alone.
+fn tint(c: Rgba, tint: Rgba) -> Rgba {
+    let ch = |shift: u32| ((c >> shift & 0xFF) * (tint >> shift & 0xFF) / 0xFF) << shift;
+    (c & 0xFF00_0000) | ch(16) | ch(8) | ch(0)
+}
+
+/// The part of `start..start + len` inside `0..limit`.
+fn clip(start: i32, len: usize, limit: usize) -> Range<i32> {
+    let limit = limit.min(i32::MAX as usize) as i32;
+    let end = start.saturating_add(len.min(i32::MAX as usize) as i32);
+    start.clamp(0, limit)..end.clamp(0, limit)
+}
+
+/// Each glyph of `text` in `font` and the x it goes at, starting from `x`.
+fn glyphs<'a>(
+    text: &'a str,
+    x: i32,
+    font: &'a FontRef,
+) -> impl Iterator<Item = (&'a Texture, i32)> + 'a {
+    let mut x = x;
+    text.chars().filter_map(move |c| {
+        let glyph = font.glyph(c);
+        let at = x;
+        let w = glyph.map_or(FONT_SPACE_W, |g| g.w) * font.scale;
+        x = x.saturating_add(w as i32);
+        Some((glyph?, at))
+    })
+}
+
+/// A `w × h` frame to draw into.  Whatever falls outside it is dropped.
+pub struct Canvas<'a> {
+    fb: &'a mut [Rgba],
+    w: usize,
+    h: usize,
+}
+
+impl<'a> Canvas<'a> {
+    /// The first `w × h` pixels of `fb`, row by row.  Panics if `fb` is
+    /// smaller than that.
+    pub fn new(fb: &'a mut [Rgba], w: usize, h: usize) -> Self {
+        assert!(fb.len() >= w * h, "{} pixels for a {w}×{h} frame", fb.len());
+        Self {
+            fb: &mut fb[..w * h],
+            w,
+            h,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.w
+    }
+
+    pub fn height(&self) -> usize {
+        self.h
+    }
+
+    /// Bresenham line from (`x0`, `y0`) to (`x1`, `y1`), both ends drawn.
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, col: Rgba) {
+        let (mut x, mut y) = (x0, y0);
+        let dx = x1.abs_diff(x0).min(i32::MAX as u32) as i32;
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let dy = -(y1.abs_diff(y0).min(i32::MAX as u32) as i32);
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx.saturating_add(dy);
+        loop {
+            if (0..self.w as i32).contains(&x) && (0..self.h as i32).contains(&y) {
+                self.fb[y as usize * self.w + x as usize] = col;
+            }
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = err.saturating_mul(2);
+            if e2 >= dy {
+                err = err.saturating_add(dy);
+                x += sx;
+            }
+            if e2 <= dx {
+                err = err.saturating_add(dx);
+                y += sy;
+            }
+        }
+    }
+
+    /// Fill `w × h` pixels from (`x`, `y`).  Negative sizes fill nothing.
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, col: Rgba) {
+        let cols = clip(x, w.max(0) as usize, self.w);
+        for py in clip(y, h.max(0) as usize, self.h) {
+            let row = py as usize * self.w;
+            self.fb[row + cols.start as usize..row + cols.end as usize].fill(col);
+        }
+    }
+
+    /// Halve the brightness of `w × h` pixels from (`x`, `y`): a
+    /// translucent black backdrop.
+    pub fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        let cols = clip(x, w.max(0) as usize, self.w);
+        for py in clip(y, h.max(0) as usize, self.h) {
+            let row = py as usize * self.w;
+            for p in &mut self.fb[row + cols.start as usize..row + cols.end as usize] {
+                *p = 0xFF00_0000 | ((*p >> 1) & 0x7F_7F7F);
+            }
+        }
+    }
+
+    /// `patch` with its top-left corner at (`x`, `y`), each texel
+    /// `scale`×`scale` pixels coloured by `color`.  See-through texels
+    /// leave the frame alone.
+    pub fn blit_patch(
+        &mut self,
+        patch: &Texture,
+        x: i32,
+        y: i32,
+        scale: usize,
+        color: impl Fn(u8) -> Rgba,
+    ) {
+        self.blit(x, y, patch.w, patch.h, scale, |i| {
+            patch.is_opaque(i).then(|| color(patch.pixels[i]))
+        });
+    }
+
+    /// `text` in `font` with its top-left corner at (`x`, `y`), tinted
+    /// by `col`: white draws the font's own colours.
+    pub fn text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        let bank = font.bank();
+        for (glyph, gx) in glyphs(text, x, font) {
+            self.blit_patch(glyph, gx, y, font.scale, |t| {
+                tint(bank.get_color(0, t), col)
+            });
+        }
+    }
+
+    /// A `w × h` image of `texel(i)`s, row by row, `None` see-through.
+    fn blit(
+        &mut self,
+        x: i32,
+        y: i32,
+        w: usize,
+        h: usize,
+        scale: usize,
+        texel: impl Fn(usize) -> Option<Rgba>,
+    ) {
+        let scale = scale.max(1);
+        let cols = clip(x, w.saturating_mul(scale), self.w);
+        for py in clip(y, h.saturating_mul(scale), self.h) {
+            let ty = (py as i64 - y as i64) as usize / scale;
+            let row = &mut self.fb[py as usize * self.w..][..self.w];
+            for px in cols.clone() {
+                let tx = (px as i64 - x as i64) as usize / scale;
+                if let Some(c) = texel(ty * w + tx) {
+                    row[px as usize] = c;
+                }
+            }
+        }
+    }
+}
+
+/// One 2-D call, kept until the frame ends.
+#[derive(Clone, Debug)]
+enum Draw {
+    Line(i32, i32, i32, i32, Rgba),
+    Rect(i32, i32, i32, i32, Rgba),
+    Dim(i32, i32, i32, i32),
+    /// A patch already looked up in its palette: `w × h` texels of
+    /// [`Overlay::texels`], each `scale`×`scale` pixels.
+    Image {
+        x: i32,
+        y: i32,
+        w: usize,
+        h: usize,
+        scale: usize,
+        texels: Range<usize>,
+    },
+}
+
+/// The 2-D calls made during a frame, to be drawn over it at the end.
+/// Patches are copied in, already coloured, so nothing is borrowed past
+/// the call.  Cleared, not freed, from frame to frame.
+#[derive(Default)]
+pub struct Overlay {
+    draws: Vec<Draw>,
+    texels: Vec<Option<Rgba>>,
+}
+
+impl Overlay {
+    pub fn clear(&mut self) {
+        self.draws.clear();
+        self.texels.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.draws.is_empty()
+    }
+
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, col: Rgba) {
+        self.draws.push(Draw::Line(x0, y0, x1, y1, col));
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, col: Rgba) {
+        self.draws.push(Draw::Rect(x, y, w, h, col));
+    }
+
+    pub fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.draws.push(Draw::Dim(x, y, w, h));
+    }
+
+    /// See [`Canvas::blit_patch`].
+    pub fn patch(
+        &mut self,
+        patch: &Texture,
+        x: i32,
+        y: i32,
+        scale: usize,
+        color: impl Fn(u8) -> Rgba,
+    ) {
+        let start = self.texels.len();
+        self.texels.extend(
+            (0..patch.w * patch.h).map(|i| patch.is_opaque(i).then(|| color(patch.pixels[i]))),
+        );
+        self.draws.push(Draw::Image {
+            x,
+            y,
+            w: patch.w,
+            h: patch.h,
+            scale,
+            texels: start..self.texels.len(),
+        });
+    }
+
+    /// See [`Canvas::text`].
+    pub fn text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        let bank = font.bank();
+        for (glyph, gx) in glyphs(text, x, font) {
+            self.patch(glyph, gx, y, font.scale, |t| {
+                tint(bank.get_color(0, t), col)
+            });
+        }
+    }
+
+    /// Play every call onto `canvas`, oldest first, and forget them.
+    pub fn draw(&mut self, canvas: &mut Canvas) {
+        for draw in self.draws.drain(..) {
+            match draw {
+                Draw::Line(x0, y0, x1, y1, col) => canvas.line(x0, y0, x1, y1, col),
+                Draw::Rect(x, y, w, h, col) => canvas.fill_rect(x, y, w, h, col),
+                Draw::Dim(x, y, w, h) => canvas.dim_rect(x, y, w, h),
+                Draw::Image {
+                    x,
+                    y,
+                    w,
+                    h,
+                    scale,
+                    texels,
+                } => {
+                    let texels = &self.texels[texels];
+                    canvas.blit(x, y, w, h, scale, |i| texels[i]);
+                }
+            }
+        }
+        self.texels.clear();
+    }
+}
+
+/// Where text over a frame can go: a [`Canvas`], for the screens drawn
+/// without a renderer, or a renderer's 2-D calls during a frame.
+pub trait TextTarget {
+    /// See [`Canvas::dim_rect`].
+    fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32);
+
+    /// See [`Canvas::text`].
+    fn text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba);
+}
+
+impl TextTarget for Canvas<'_> {
+    fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        Canvas::dim_rect(self, x, y, w, h);
+    }
+
+    fn text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        Canvas::text(self, text, x, y, font, col);
+    }
+}
+
+impl<R: Renderer> TextTarget for R {
+    fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        Renderer::dim_rect(self, x, y, w, h);
+    }
+
+    fn text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        self.draw_text(text, x, y, font, col);
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Font, Palette, TexMask, TextureBank};
+
+    const W: usize = 7;
+    const H: usize = 5;
+
+    /// A `W × H` frame inside a margin that must never be written.
+    fn guarded(draw: impl FnOnce(&mut Canvas)) -> Vec<Rgba> {
+        const GUARD: Rgba = 0xDEAD_BEEF;
+        let mut buf = vec![GUARD; W * H + 16];
+        let (frame, tail) = buf.split_at_mut(W * H);
+        frame.fill(0);
+        draw(&mut Canvas::new(frame, W, H));
+        assert!(tail.iter().all(|&p| p == GUARD), "wrote past the frame");
+        buf.truncate(W * H);
+        buf
+    }
+
+    /// `w × h` patch of colour index `c`, its left column see-through.
+    fn patch(w: usize, h: usize, c: u8) -> Texture {
+        let mut mask = TexMask::new(w * h);
+        for i in (0..w * h).filter(|i| i % w != 0) {
+            mask.set(i);
+        }
+        Texture {
+            name: "PATCH".into(),
+            w,
+            h,
+            pixels: vec![c; w * h],
+            mask: Some(mask),
+        }
+    }
+
+    fn count(fb: &[Rgba], col: Rgba) -> usize {
+        fb.iter().filter(|&&p| p == col).count()
+    }
+
+    #[test]
+    fn rects_and_lines_clip_at_every_edge() {
+        for (x, y) in [(-3, -2), (5, 3), (-3, 3), (5, -2), (6, 4), (7, 5), (-4, -3)] {
+            let fb = guarded(|c| c.fill_rect(x, y, 4, 3, 9));
+            let inside = |v: i32, len: i32, lim: i32| (v.max(0)..(v + len).min(lim)).len();
+            assert_eq!(
+                count(&fb, 9),
+                inside(x, 4, 7) * inside(y, 3, 5),
+                "at ({x}, {y})"
+            );
+        }
+        let fb = guarded(|c| c.fill_rect(i32::MIN, i32::MIN, i32::MAX, i32::MAX, 9));
+        assert_eq!(count(&fb, 9), 0);
+        let fb = guarded(|c| c.fill_rect(2, 2, -5, 9, 9));
+        assert_eq!(count(&fb, 9), 0);
+        let fb = guarded(|c| c.fill_rect(-100, -100, 1000, 1000, 9));
+        assert_eq!(count(&fb, 9), W * H);
+
+        // half brightness, opaque, inside the frame only
+        let fb = guarded(|c| {
+            c.fill_rect(0, 0, 7, 5, 0xFF80_4020);
+            c.dim_rect(-3, 3, 100, 100);
+        });
+        assert_eq!(
+            (count(&fb, 0xFF80_4020), count(&fb, 0xFF40_2010)),
+            (3 * W, 2 * W)
+        );
+
+        // corner to far corner and well beyond
+        let fb = guarded(|c| c.line(-10, -10, 20, 20, 9));
+        assert_eq!(count(&fb, 9), 5);
+        let fb = guarded(|c| c.line(0, 4, 6, 4, 9));
+        assert_eq!(&fb[4 * W..], &[9; W]);
+        guarded(|c| c.line(-1_000_000, 3, 1_000_000, 3, 9));
+    }
+
+    #[test]
+    fn patches_clip_and_keep_their_holes() {
+        let pic = patch(3, 2, 4);
+        let color = |t: u8| t as Rgba * 10;
+        for (x, y) in [(-2, -1), (5, 4), (6, 0), (0, 4), (7, 5), (-3, -2)] {
+            let fb = guarded(|c| c.blit_patch(&pic, x, y, 1, color));
+            // two opaque columns of two rows, where they land on the frame
+            let opaque = (1..3).filter(|dx| (0..7).contains(&(x + dx))).count()
+                * (0..2).filter(|dy| (0..5).contains(&(y + dy))).count();
+            assert_eq!(count(&fb, 40), opaque, "at ({x}, {y})");
+        }
+        // doubled, hanging off the bottom-right corner
+        let fb = guarded(|c| c.blit_patch(&pic, 3, 2, 2, color));
+        assert_eq!(fb[2 * W + 3], 0, "see-through column");
+        // two doubled opaque columns of one and a half doubled rows
+        assert_eq!(count(&fb, 40), 2 * 3);
+        guarded(|c| c.blit_patch(&pic, i32::MAX - 1, i32::MIN, 1000, color));
+    }
+
+    #[test]
+    fn text_clips_and_tints() {
+        let mut bank = TextureBank::default_with_checker();
+        bank.set_palette(Palette([0xFF80_C040; 256]));
+        bank.build_shade_table();
+        let a = bank.insert("FONTA", patch(2, 3, 7)).unwrap();
+        bank.insert_font("TEST", Font::new(b'A', vec![Some(a)]));
+        let font = bank.font("TEST").unwrap();
+        assert_eq!(font.text_width("a a"), 2 + FONT_SPACE_W + 2);
+        assert_eq!(font.scaled(2).line_height(), 8);
+
+        let white = 0xFF80_C040;
+        let fb = guarded(|c| c.text("AA", 4, 3, &font, 0xFF_FF_FF));
+        // the first glyph's solid column and the second's clipped away
+        assert_eq!(count(&fb, white), 2);
+        let fb = guarded(|c| c.text("A", -1, -2, &font, 0));
+        assert_eq!(count(&fb, 0xFF00_0000), 1);
+        let fb = guarded(|c| c.text("A", 0, 0, &font, 0x80_80_80));
+        assert_eq!(count(&fb, 0xFF40_6020), 3);
+        guarded(|c| c.text("AAAA", i32::MAX - 3, 0, &font.scaled(100), 0xFF));
+    }
+
+    #[test]
+    fn overlay_replays_in_order() {
+        let pic = patch(2, 2, 1);
+        let mut overlay = Overlay::default();
+        overlay.fill_rect(0, 0, 7, 5, 1);
+        overlay.patch(&pic, 5, 3, 1, |_| 2);
+        overlay.line(0, 4, 6, 4, 3);
+        let fb = guarded(|c| overlay.draw(c));
+        assert!(overlay.is_empty());
+        assert_eq!(&fb[3 * W..4 * W], &[1, 1, 1, 1, 1, 1, 2]);
+        assert_eq!(&fb[4 * W..], &[3; W]);
+        assert_eq!(count(&fb, 1), W * H - W - 1);
+    }
+}
@@ -0,0 +1,278 @@
+//! From the frame the renderer drew to the window it's shown in.
+//!
+//! [`VideoMode`] picks how big a frame to draw for a window, and
+//! [`Presenter`] spreads a frame that isn't window-sized over the window,
+//! nearest neighbour.  In `Crispy` mode the frame is always vanilla's
+//! 320×200, shown at the 4:3 it had on a CRT: each frame pixel is a whole
+//! number of window pixels wide and 1.2 times that high, so rows come out
+//! unevenly deep (2, 1, 1, 1, 1 at 1×; 3, 2, 3, 2, 2 at 2×), and what's
+//! left of the window is black.  [`crispy_box`] is that policy on its own
+//! for backends that do their own scaling.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::{Rgba, ViewWindow};
+
+/// Vanilla's screen, which `Crispy` mode draws at.
+pub const CRISPY_W: usize = 320;
+pub const CRISPY_H: usize = 200;
+
+/// How much taller than wide a vanilla pixel is on a 4:3 screen.
+pub const ASPECT_STRETCH: f32 = 1.2;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum VideoModeError {
+    #[error("unknown video mode {0:?} (crispy, native or scaled:<percent>)")]
+    Unknown(String),
+
+    #[error("render scale {0:?} isn't a percentage from 25 to 100")]
+    BadScale(String),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VideoMode {
+    /// Draw at the window's resolution.
+    #[default]
+    Native,
+    /// Draw at this fraction (0.25‥1) of the window's resolution and
+    /// stretch it over all of it; the renderer's render scale.
+    Scaled(f32),
+    /// Draw at 320×200 and box it in, whatever the window.
+    Crispy,
+}
+
+impl VideoMode {
+    /// The frame to draw for a `win_w × win_h` window.
+    pub fn frame_size(self, win_w: usize, win_h: usize) -> (usize, usize) {
+        match self {
+            Self::Crispy => (CRISPY_W, CRISPY_H),
+            Self::Native | Self::Scaled(_) => (win_w, win_h),
+        }
+    }
+
+    /// The render scale the renderer should draw that frame at.
+    pub fn render_scale(self) -> f32 {
+        match self {
+            Self::Scaled(scale) => scale,
+            Self::Native | Self::Crispy => 1.0,
+        }
+    }
+}
+
+/// `crispy`, `native` or `scaled:<percent>`.
+impl FromStr for VideoMode {
+    type Err = VideoModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        if let Some(pct) = s.strip_prefix("scaled:") {
+            return pct
+                .trim_end_matches('%')
+                .parse::<f32>()
+                .ok()
+                .filter(|p| (25.0..=100.0).contains(p))
+                .map(|p| Self::Scaled(p / 100.0))
+                .ok_or_else(|| VideoModeError::BadScale(pct.to_owned()));
+        }
+        match s.as_str() {
+            "crispy" => Ok(Self::Crispy),
+            "native" => Ok(Self::Native),
+            _ => Err(VideoModeError::Unknown(s)),
+        }
+    }
+}
+
+/// Where a `w × h` frame goes in a `win_w × win_h` window in `Crispy`
+/// mode: pixels the largest whole number of window pixels wide that
+/// fits with the aspect stretch, centred.  A window too small for even
+/// one gets the biggest box of the right shape that fits; a window or
+/// frame with no pixels an empty one.
+pub fn crispy_box(w: usize, h: usize, win_w: usize, win_h: usize) -> ViewWindow {
+    let tall = (h as f32 * ASPECT_STRETCH).round() as usize;
+    if [w, tall, win_w, win_h].contains(&0) {
+        return ViewWindow {
+            x: win_w / 2,
+            y: win_h / 2,
+            w: 0,
+            h: 0,
+        };
+    }
+    let n = (win_w / w).min(win_h / tall);
+    let (bw, bh) = if n >= 1 {
+        (w * n, tall * n)
+    } else {
+        let fit = (win_w as f32 / w as f32).min(win_h as f32 / tall as f32);
+        (
+            ((w as f32 * fit) as usize).max(1),
+            ((tall as f32 * fit) as usize).max(1),
+        )
+    };
+    ViewWindow {
+        x: win_w.saturating_sub(bw) / 2,
+        y: win_h.saturating_sub(bh) / 2,
+        w: bw,
+        h: bh,
+    }
+}
+
+/// Scales frames up to the window for a [`VideoMode`].
+#[derive(Default)]
+pub struct Presenter {
+    pub mode: VideoMode,
+    /// The last frame as the window shows it.
+    out: Vec<Rgba>,
+    /// Frame column under each window column of the box.
+    cols: Vec<usize>,
+}
+
+impl Presenter {
+    pub fn new(mode: VideoMode) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// `fb`, a `w × h` frame, as the `win_w × win_h` window shows it.
+    /// Window-sized frames come back as they are.
+    pub fn present<'a>(
+        &'a mut self,
+        fb: &'a [Rgba],
+        w: usize,
+        h: usize,
+        win_w: usize,
+        win_h: usize,
+    ) -> &'a [Rgba] {
+        if (w, h) == (win_w, win_h) {
+            return fb;
+        }
+        let b = match self.mode {
+            VideoMode::Crispy => crispy_box(w, h, win_w, win_h),
+            VideoMode::Native | VideoMode::Scaled(_) => ViewWindow::full(win_w, win_h),
+        };
+        self.out.clear();
+        self.out.resize(win_w * win_h, 0);
+        self.cols.clear();
+        self.cols.extend((0..b.w).map(|x| x * w / b.w));
+
+        for y in 0..b.h {
+            let row = (b.y + y) * win_w + b.x;
+            let (sy, last) = (y * h / b.h, y.checked_sub(1).map(|p| p * h / b.h));
+            if last == Some(sy) {
+                // same frame row: repeat the window row just written
+                self.out.copy_within(row - win_w..row - win_w + b.w, row);
+                continue;
+            }
+            let src = &fb[sy * w..][..w];
+            for (o, &sx) in self.out[row..][..b.w].iter_mut().zip(&self.cols) {
+                *o = src[sx];
+            }
+        }
+        &self.out
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn video_modes_parse() {
+        assert_eq!("crispy".parse(), Ok(VideoMode::Crispy));
+        assert_eq!("Native".parse(), Ok(VideoMode::Native));
+        assert_eq!("scaled:50".parse(), Ok(VideoMode::Scaled(0.5)));
+        assert_eq!("scaled:75%".parse(), Ok(VideoMode::Scaled(0.75)));
+        assert!(matches!(
+            "scaled:10".parse::<VideoMode>(),
+            Err(VideoModeError::BadScale(_))
+        ));
+        assert!(matches!(
+            "blocky".parse::<VideoMode>(),
+            Err(VideoModeError::Unknown(_))
+        ));
+    }
+
+    /// A 1000 × 700 window fits 320×200 twice across and (×1.2) twice
+    /// down: a 640 × 480 picture with 180 columns of bars either side and
+    /// 110 rows above and below.
+    #[test]
+    fn crispy_letterbox_at_an_odd_window_size() {
+        let (win_w, win_h) = (1000, 700);
+        let b = crispy_box(CRISPY_W, CRISPY_H, win_w, win_h);
+        assert_eq!(
+            b,
+            ViewWindow {
+                x: 180,
+                y: 110,
+                w: 640,
+                h: 480
+            }
+        );
+
+        // every frame pixel a different colour
+        let fb: Vec<Rgba> = (0..CRISPY_W * CRISPY_H).map(|i| i as Rgba + 1).collect();
+        let mut presenter = Presenter::new(VideoMode::Crispy);
+        let out = presenter.present(&fb, CRISPY_W, CRISPY_H, win_w, win_h);
+        assert_eq!(out.len(), win_w * win_h);
+
+        let px = |x: usize, y: usize| out[y * win_w + x];
+        // the bars are black, the picture isn't
+        for y in 0..win_h {
+            for x in 0..win_w {
+                let inside = (180..820).contains(&x) && (110..590).contains(&y);
+                assert_eq!(px(x, y) != 0, inside, "({x}, {y})");
+            }
+        }
+        // corners land on corners
+        assert_eq!(px(180, 110), fb[0]);
+        assert_eq!(px(819, 589), fb[CRISPY_W * CRISPY_H - 1]);
+
+        // each column exactly doubled
+        let row: Vec<Rgba> = (180..820).map(|x| px(x, 110)).collect();
+        assert!(
+            row.chunks(2)
+                .zip(&fb[..CRISPY_W])
+                .all(|(c, &s)| c == [s, s])
+        );
+
+        // rows 3, 2, 3, 2, 2 deep, over and over
+        let mut depths = Vec::new();
+        let mut y = 110;
+        while y < 590 {
+            let start = y;
+            while y < 590 && px(180, y) == px(180, start) {
+                y += 1;
+            }
+            depths.push(y - start);
+        }
+        assert_eq!(depths.len(), CRISPY_H);
+        assert!(depths.chunks(5).all(|c| c == [3, 2, 3, 2, 2]), "{depths:?}");
+    }
+
+    #[test]
+    fn tiny_windows_still_get_the_whole_picture() {
+        let b = crispy_box(CRISPY_W, CRISPY_H, 200, 200);
+        assert_eq!((b.w, b.h), (200, 150));
+        assert_eq!((b.x, b.y), (0, 25));
+
+        // a minimised window, or nothing to show: an empty box
+        for (w, h, win_w, win_h) in [
+            (CRISPY_W, CRISPY_H, 0, 0),
+            (CRISPY_W, CRISPY_H, 0, 480),
+            (0, 0, 640, 480),
+        ] {
+            let b = crispy_box(w, h, win_w, win_h);
+            assert_eq!((b.w, b.h), (0, 0), "{w}×{h} in {win_w}×{win_h}");
+        }
+        let mut p = Presenter::new(VideoMode::Crispy);
+        assert!(
+            p.present(&[0; CRISPY_W * CRISPY_H], CRISPY_W, CRISPY_H, 0, 0)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Bump allocation for what one view builds up and throws away.
+//!
+//! A [`FrameArena`] hands out ranges of one growing buffer and takes
+//! them all back at once in [`FrameArena::reset`].  The buffer stays at
+//! the largest size any view needed, so once the renderer has drawn its
+//! busiest view a frame costs no allocations at all.
+
+use std::ops::{Deref, DerefMut, Range};
+
+#[derive(Default)]
+pub struct FrameArena<T> {
+    buf: Vec<T>,
+    cursor: usize,
+}
+
+impl<T: Copy + Default> FrameArena<T> {
+    /// `len` slots, left holding whatever an earlier view wrote.
+    pub fn alloc(&mut self, len: usize) -> Range<usize> {
+        let start = self.cursor;
+        self.cursor += len;
+        if self.cursor > self.buf.len() {
+            self.buf
+                .resize(self.cursor.next_power_of_two(), T::default());
+        }
+        start..self.cursor
+    }
+
+    /// `len` slots, all set to `value`.
+    pub fn alloc_filled(&mut self, len: usize, value: T) -> Range<usize> {
+        let range = self.alloc(len);
+        self.buf[range.clone()].fill(value);
+        range
+    }
+
+    /// Hand every slot back; the buffer is kept.
+    pub fn reset(&mut self) {
+        self.cursor = 0;
+    }
+}
+
+/// The whole buffer, index it with the ranges handed out.
+impl<T> Deref for FrameArena<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.buf
+    }
+}
+
+impl<T> DerefMut for FrameArena<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.buf
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_follow_on_and_come_back_on_reset() {
+        let mut arena = FrameArena::<u16>::default();
+        let a = arena.alloc_filled(3, 7);
+        let b = arena.alloc(5);
+        assert_eq!((a.clone(), b.clone()), (0..3, 3..8));
+        assert_eq!(arena[a], [7; 3]);
+        arena[b.clone()].fill(1);
+
+        let grown = arena.len();
+        arena.reset();
+        assert_eq!(arena.alloc_filled(4, 9), 0..4);
+        // nothing re-zeroed behind the filled range, nothing reallocated
+        assert_eq!(arena[4..8], [1; 4]);
+        assert_eq!(arena.len(), grown);
+    }
+}
@@ -1,7 +1,8 @@
+mod arena;
 mod planes;
 mod projection;
 mod renderer;
 mod sprites;
 mod subsector;
 
-pub use renderer::Software;
+pub use renderer::{CompatOptions, DIST_PER_SHADE, Filtering, LightMode, Software};
@@ -1,10 +1,17 @@
 use glam::Vec2;
 use std::collections::HashMap;
-use std::collections::hash_map::Entry;
-use std::ops::RangeInclusive;
-
-use super::Software;
-use crate::world::{Camera, NO_TEXTURE, TextureBank, TextureId};
+use std::ops::{Range, RangeInclusive};
+
+use super::{
+    Software,
+    arena::FrameArena,
+    renderer::{Filtering, lerp_rgb},
+    subsector::wall_texel,
+};
+use crate::renderer::{DrawCmd, DrawFlags, RenderStats};
+use crate::world::{
+    Angle, Camera, NO_TEXTURE, ShadeTable, TRANSPARENT_TEXTURE, TextureBank, TextureId,
+};
 
 pub type VisplaneId = u16;
 
@@ -21,11 +28,13 @@ pub struct VisPlane {
     pub max_x: u16,
 
     /// For every screen column we remember the highest and lowest pixel that is
-    /// still uncovered **after** drawing the front geometry.
-    pub top: Vec<u16>,
-    pub bottom: Vec<u16>,
+    /// still uncovered **after** drawing the front geometry: `top` then
+    /// `bottom`, a column each, in the map's column arena.
+    cols: Range<usize>,
 
     pub modified: bool,
+    /// The plane found before this one with the same key.
+    next: VisplaneId,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -37,29 +46,42 @@ struct PlaneKey {
 
 #[derive(Default)]
 pub struct PlaneMap {
-    map: HashMap<PlaneKey, Vec<VisplaneId>>,
+    /// The last plane found for each key; the rest chain on from it.
+    map: HashMap<PlaneKey, VisplaneId>,
     planes: Vec<VisPlane>,
+    /// Every plane's `top` and `bottom` columns.
+    columns: FrameArena<u16>,
     width: usize,
 }
 
 impl PlaneMap {
+    /// Drop every plane for a `width`-column frame.  Everything keeps its
+    /// capacity, so a frame no busier than one before doesn't allocate.
     pub fn clear(&mut self, width: usize) {
         self.map.clear();
         self.planes.clear();
+        self.columns.reset();
         self.width = width;
     }
 
-    pub fn get(&mut self, id: VisplaneId) -> Option<&mut VisPlane> {
-        if id == NO_PLANE {
-            None
-        } else {
-            self.planes.get_mut(id as usize)
-        }
+    /// Plane `id` is open from row `top` to `bottom` in column `col`.
+    /// Nothing for `NO_PLANE`.
+    #[inline]
+    pub fn mark(&mut self, id: VisplaneId, col: usize, top: u16, bottom: u16) {
+        let Some(plane) = self.planes.get_mut(id as usize) else {
+            return;
+        };
+        plane.modified = true;
+        self.columns[plane.cols.start + col] = top;
+        self.columns[plane.cols.start + self.width + col] = bottom;
     }
 
-    #[inline]
-    pub fn iter(&self) -> impl ExactSizeIterator<Item = &VisPlane> + '_ {
-        self.planes.iter()
+    /// Every plane with its `top` and `bottom` rows, a column each.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&VisPlane, &[u16], &[u16])> + '_ {
+        self.planes.iter().map(|p| {
+            let (top, bottom) = self.columns[p.cols.clone()].split_at(self.width);
+            (p, top, bottom)
+        })
     }
 
     pub fn find(
@@ -69,42 +91,47 @@ impl PlaneMap {
         light: i16,
         min_x: u16,
         max_x: u16,
+        stats: &mut RenderStats,
     ) -> VisplaneId {
         let key = PlaneKey { height, tex, light };
 
-        let ids = match self.map.entry(key) {
-            Entry::Occupied(o) => o.into_mut(),
-            Entry::Vacant(v) => v.insert(Vec::new()),
-        };
-
-        for &pid in ids.iter() {
+        let head = self.map.get(&key).copied().unwrap_or(NO_PLANE);
+        let mut pid = head;
+        while pid != NO_PLANE {
             let plane = &mut self.planes[pid as usize];
-            if Self::merge_plane(plane, min_x, max_x) {
+            if Self::merge_plane(plane, &self.columns, min_x, max_x) {
+                stats.visplanes_merged += 1;
                 return pid;
             }
+            pid = plane.next;
         }
 
         assert!(self.planes.len() < u16::MAX as usize);
 
         let new_id = self.planes.len() as VisplaneId;
 
+        // top unset, bottom straight after it
+        let top = self.columns.alloc_filled(self.width, u16::MAX);
+        let bottom = self.columns.alloc_filled(self.width, u16::MIN);
+        let cols = top.start..bottom.end;
         let new_plane = VisPlane {
             height,
             tex,
             light,
             min_x,
             max_x,
-            top: vec![u16::MAX; self.width],
-            bottom: vec![u16::MIN; self.width],
+            cols,
             modified: false,
+            next: head,
         };
 
         self.planes.push(new_plane);
-        ids.push(new_id);
+        stats.visplanes_created += 1;
+        self.map.insert(key, new_id);
         new_id
     }
 
-    fn merge_plane(plane: &mut VisPlane, min_x: u16, max_x: u16) -> bool {
+    fn merge_plane(plane: &mut VisPlane, columns: &[u16], min_x: u16, max_x: u16) -> bool {
         let intrl = min_x.max(plane.min_x);
         let intrh = max_x.min(plane.max_x);
         let unionl = min_x.min(plane.min_x);
@@ -113,7 +140,8 @@ impl PlaneMap {
         let lo = intrl as usize;
         let hi = intrh as usize;
 
-        if lo <= hi && plane.top[lo..=hi].iter().any(|&v| v != u16::MAX) {
+        let top = &columns[plane.cols.start..];
+        if lo <= hi && top[lo..=hi].iter().any(|&v| v != u16::MAX) {
             return false; // part of the span already drawn
         }
 
@@ -157,12 +185,15 @@ struct SpanContext<'a> {
     cam_right: Vec2,
     cam_base: Vec2,
     bank: &'a TextureBank,
+    shades: ShadeTable<'a>,
 }
 
 /// All data required to draw a single horizontal span of a visplane.
 struct PlaneDrawParams {
     tex_id: TextureId,
     light: i16,
+    /// Distance of the row along the view direction.
+    depth: f32,
     y_row: u16,
     x_range: RangeInclusive<u16>,
     step: UVStep,
@@ -170,11 +201,12 @@ struct PlaneDrawParams {
 }
 
 impl Software {
-    /// Draw and clear all cached visplanes.
+    /// Draw and clear all cached visplanes; those of texture `sky` are
+    /// the sky.
     ///
-    /// *Public signature unchanged – internal helpers were refactored to keep
-    ///  argument lists short and Clippy‑friendly.*
-    pub fn flush_planes(&mut self, cam: &Camera, bank: &TextureBank) {
+    /// *Internal helpers were refactored to keep argument lists short and
+    ///  Clippy‑friendly.*
+    pub fn flush_planes(&mut self, cam: &Camera, bank: &TextureBank, sky: TextureId) {
         let cam_fwd = cam.forward();
         let cam_right = cam.right();
         let cam_base = cam.pos.truncate();
@@ -184,16 +216,21 @@ impl Software {
             cam_right,
             cam_base,
             bank,
+            shades: self.shades(bank),
         };
 
         // Retrieve and replace the plane map so we can iterate without
         // borrowing issues.
-        let plane_map = std::mem::take(&mut self.visplane_map);
+        let plane_map = std::mem::take(&mut self.frame.visplane_map);
 
         // The original Doom drew floors & ceilings *after* the walls, so we
         // simply iterate as we stored them (front parts first = back parts last).
-        for vp in plane_map.iter() {
-            if vp.tex == NO_TEXTURE || !vp.modified {
+        for (vp, top, bottom) in plane_map.iter() {
+            if vp.tex == NO_TEXTURE || vp.tex == TRANSPARENT_TEXTURE || !vp.modified {
+                continue;
+            }
+            if vp.tex == sky {
+                self.draw_sky(&ctx, vp, top, bottom, cam.yaw);
                 continue;
             }
 
@@ -203,7 +240,7 @@ impl Software {
 
                 for x in vp.min_x..=vp.max_x {
                     let col = x as usize;
-                    let inside = vp.top[col] <= y && vp.bottom[col] >= y;
+                    let inside = top[col] <= y && bottom[col] >= y;
 
                     match (inside, run_start) {
                         (true, None) => run_start = Some(x), // run starts
@@ -225,7 +262,56 @@ impl Software {
 
         // Put the (now cleared) map back so the rest of the engine can keep
         // using the same allocation.
-        self.visplane_map = plane_map;
+        self.frame.visplane_map = plane_map;
+    }
+
+    /// Vanilla's sky: each column of the plane is the sky texture's
+    /// column at its view angle, four texture widths to a full turn,
+    /// drawn full bright at a fixed scale with texel row 100 on the
+    /// horizon.
+    fn draw_sky(
+        &mut self,
+        ctx: &SpanContext,
+        vp: &VisPlane,
+        top: &[u16],
+        bottom: &[u16],
+        yaw: Angle,
+    ) {
+        let Ok(tex) = ctx.bank.texture(vp.tex) else {
+            return;
+        };
+        // a texel a pixel at 320 columns and 90°, like `pspriteiscale`
+        let iscale = 160.0 / self.focal;
+        let tutti_frutti = self.compat.emulate_tutti_frutti;
+        for x in vp.min_x..=vp.max_x {
+            let col = x as usize;
+            let (y0, y1) = (top[col], bottom[col]);
+            if y0 > y1 {
+                continue;
+            }
+            // vanilla `(viewangle + xtoviewangle[x]) >> ANGLETOSKYSHIFT`
+            let ray = Angle::from_radians(((self.half_w - (col as f32 + 0.5)) / self.focal).atan());
+            let u = ((yaw.bam().wrapping_add(ray.bam()) >> 22) as i32).rem_euclid(tex.w as i32);
+            let mut v = 100.0 + (y0 as f32 + 0.5 - self.half_h) * iscale;
+            self.stats.plane_pixels += u32::from(y1 - y0) + 1;
+
+            if let Some(list) = &mut self.record {
+                list.push(DrawCmd::column(
+                    col,
+                    y0 as i32..=y1 as i32,
+                    vp.tex,
+                    0,
+                    Vec2::new(u as f32, v),
+                    iscale,
+                ));
+                continue;
+            }
+            for y in y0..=y1 {
+                let texel = wall_texel(tex, u as usize, v as i32, tutti_frutti);
+                self.put_pixel(col, y as usize, ctx.shades.get(0, texel));
+                v += iscale;
+            }
+        }
     }
 
     /// Convert a horizontal pixel run into a perspective‑correct span and hand
@@ -283,6 +369,7 @@ impl Software {
         let params = PlaneDrawParams {
             tex_id: vp.tex,
             light: vp.light,
+            depth: z,
             y_row: y,
             x_range,
             step,
@@ -299,29 +386,79 @@ impl Software {
             .texture(params.tex_id)
             .unwrap_or_else(|_| ctx.bank.texture(NO_TEXTURE).unwrap());
 
+        let base_sh = self.light_mode.shade(
+            (255u16.saturating_sub(params.light as u16) >> 3) as u8,
+            params.depth,
+        );
+
         // Row in the scratch buffer for this scanline
-        let row_idx = params.y_row as usize * self.width;
+        let row_idx = self.pixel_index(0, params.y_row as usize);
         let row = &mut self.scratch[row_idx..][..self.width];
 
-        let base_sh = (255u16.saturating_sub(params.light as u16) >> 3) as u8;
-
-        debug_assert!(
-            tex.w.is_power_of_two() && tex.h.is_power_of_two(),
-            "textures must be POT"
-        );
-
+        // flats are 64×64, but PWAD junk can make them odd sizes: those
+        // wrap the slow way
+        let pot = tex.w.is_power_of_two() && tex.h.is_power_of_two();
         let u_mask = (tex.w - 1) as i32;
         let v_mask = (tex.h - 1) as i32;
 
         let mut cursor = params.cursor;
         let step = params.step;
+        self.stats.plane_pixels += params.x_range.len() as u32;
+
+        if let Some(list) = &mut self.record {
+            list.push(DrawCmd {
+                x0: *params.x_range.start(),
+                y0: params.y_row,
+                x1: *params.x_range.end(),
+                y1: params.y_row,
+                tex: params.tex_id,
+                shade: base_sh,
+                flags: DrawFlags::empty(),
+                uv: Vec2::new(cursor.u, cursor.v),
+                duv_dx: Vec2::new(step.du, step.dv),
+                duv_dy: Vec2::ZERO,
+            });
+            return;
+        }
+
+        if self.filtering == Filtering::Bilinear {
+            let wrap = |u: i32, v: i32| {
+                let (u, v) = if pot {
+                    (u & u_mask, v & v_mask)
+                } else {
+                    (u.rem_euclid(tex.w as i32), v.rem_euclid(tex.h as i32))
+                };
+                ctx.shades
+                    .get(base_sh, tex.pixels[v as usize * tex.w + u as usize])
+            };
+            for x in params.x_range {
+                // texel centres sit half a unit in
+                let (fu, fv) = (cursor.u - 0.5, cursor.v - 0.5);
+                let (u0, v0) = (fu.floor(), fv.floor());
+                let tu = ((fu - u0) * 256.0) as u32;
+                let tv = ((fv - v0) * 256.0) as u32;
+                let (u0, v0) = (u0 as i32, v0 as i32);
+                let top = lerp_rgb(wrap(u0, v0), wrap(u0 + 1, v0), tu);
+                let bottom = lerp_rgb(wrap(u0, v0 + 1), wrap(u0 + 1, v0 + 1), tu);
+                row[x as usize] = lerp_rgb(top, bottom, tv);
+                cursor.advance(&step);
+            }
+            return;
+        }
 
         for x in params.x_range.clone() {
-            let u = ((cursor.u as i32) & u_mask) as usize;
-            let v = ((cursor.v as i32) & v_mask) as usize;
+            let (u, v) = if pot {
+                ((cursor.u as i32) & u_mask, (cursor.v as i32) & v_mask)
+            } else {
+                (
+                    (cursor.u as i32).rem_euclid(tex.w as i32),
+                    (cursor.v as i32).rem_euclid(tex.h as i32),
+                )
+            };
+            let (u, v) = (u as usize, v as usize);
             let col = tex.pixels[v * tex.w + u];
 
-            row[x as usize] = ctx.bank.get_color(base_sh, col);
+            row[x as usize] = ctx.shades.get(base_sh, col);
 
             cursor.advance(&step);
         }
@@ -1,9 +1,9 @@
 use glam::Vec2;
 
 use super::Software;
-use crate::world::{Camera, Level, SegmentId};
+use crate::world::{Angle, Camera, Level, SegmentId, VertexId, angle_to};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub struct Edge {
     pub x_l: i32,
     pub x_r: i32,
@@ -13,23 +13,93 @@ pub struct Edge {
     pub uoz_r: f32,
 }
 
+/// Vertices in camera space for the view being drawn, each transformed
+/// the first time a seg asks for it: most vertices end two or more segs.
+/// A stamp per vertex says which view it was worked out for, so starting
+/// a view doesn't mean clearing the table.
+#[derive(Default)]
+pub struct CamVertices {
+    view: u32,
+    stamps: Vec<u32>,
+    cam: Vec<Vec2>,
+}
+
+impl CamVertices {
+    /// Forget every vertex: a new view, or the camera moved.
+    pub fn next_view(&mut self) {
+        self.view = self.view.wrapping_add(1);
+        if self.view == 0 {
+            // wrapped: old stamps could pass for new ones
+            self.stamps.fill(0);
+            self.view = 1;
+        }
+    }
+
+    /// Vertex `id`, at `pos`, in `camera`'s space, and whether it had to
+    /// be transformed for it.
+    #[inline]
+    pub fn get(&mut self, id: VertexId, pos: Vec2, camera: &Camera) -> (Vec2, bool) {
+        let i = id as usize;
+        if i >= self.stamps.len() {
+            self.stamps.resize(i + 1, 0);
+            self.cam.resize(i + 1, Vec2::ZERO);
+        }
+        let fresh = self.stamps[i] != self.view;
+        if fresh {
+            self.stamps[i] = self.view;
+            self.cam[i] = camera.to_cam(&pos);
+        }
+        (self.cam[i], fresh)
+    }
+}
+
+/// `v`, a projected screen coordinate, as a whole one (rounded toward
+/// zero) held to `lo..=hi`; NaN lands on `lo`.  Every extent the wall and
+/// sprite passes loop over goes through here, so a wild projection near
+/// the eye can't walk a column loop off the frame or overflow the sums
+/// done on its ends.
+#[inline]
+pub fn to_screen(v: f32, lo: i32, hi: i32) -> i32 {
+    debug_assert!(lo <= hi);
+    if v.is_nan() {
+        lo
+    } else {
+        (v as i32).clamp(lo, hi)
+    }
+}
+
 impl Software {
-    pub fn project_seg(&self, seg_idx: SegmentId, level: &Level, camera: &Camera) -> Option<Edge> {
+    pub fn project_seg(
+        &mut self,
+        seg_idx: SegmentId,
+        level: &Level,
+        camera: &Camera,
+    ) -> Option<Edge> {
         let seg = &level.segs[seg_idx as usize];
         let v1 = &level.vertices[seg.v1 as usize].pos;
         let v2 = &level.vertices[seg.v2 as usize].pos;
+        let (sd_front, _, _) = self.sectors_for_seg(seg, level)?;
+
+        // Zero-length segs (node builders emit them at near-coincident
+        // vertices) have no facing and would divide by a zero span below;
+        // they aren't walls, so no cull counter counts them
+        if v1 == v2 {
+            return None;
+        }
 
         // Back‑face cull
         if Self::back_facing_seg(v1, v2, camera) {
+            self.stats.segs_culled_backface += 1;
             return None;
         }
 
         // ──────────────────────────────────────────────────────────────────────
         // 1. camera-space endpoints
         // ──────────────────────────────────────────────────────────────────────
-        let mut p1 = camera.to_cam(v1);
-        let mut p2 = camera.to_cam(v2);
-        debug_assert!(p1.y != 0.0 && p2.y != 0.0);
+        let (mut p1, fresh1) = self.frame.cam_vertices.get(seg.v1, *v1, camera);
+        let (mut p2, fresh2) = self.frame.cam_vertices.get(seg.v2, *v2, camera);
+        self.stats.vertices_transformed += fresh1 as u32 + fresh2 as u32;
+        // either may lie level with the eye (y = 0); the near clip takes it
 
         // ──────────────────────────────────────────────────────────────────────
         // 2. near-plane clip
@@ -37,6 +107,7 @@ impl Software {
         let mut t1 = 0.0;
         let mut t2 = 1.0;
         if !Self::clip_near(&mut p1, &mut p2, &mut t1, &mut t2, camera.near()) {
+            self.stats.segs_culled_bbox += 1;
             return None;
         }
 
@@ -49,6 +120,7 @@ impl Software {
         // Entirely to the left OR right of the viewport?
         let right_lim = self.width_f - 1.0;
         if (sx1 < 0.0 && sx2 < 0.0) || (sx1 > right_lim && sx2 > right_lim) {
+            self.stats.segs_culled_bbox += 1;
             return None;
         }
 
@@ -64,9 +136,11 @@ impl Software {
         // ──────────────────────────────────────────────────────────────────────
         // 5. clip to viewport X range, early-out degenerate
         // ──────────────────────────────────────────────────────────────────────
-        let x_l = sx1.max(0.0) as i32;
-        let x_r = sx2.min(self.width_f - 1.0) as i32;
+        let last_col = self.width as i32 - 1;
+        let x_l = to_screen(sx1, 0, last_col);
+        let x_r = to_screen(sx2, 0, last_col);
         if x_l >= x_r {
+            self.stats.segs_culled_bbox += 1;
             return None;
         }
 
@@ -75,10 +149,11 @@ impl Software {
         // ──────────────────────────────────────────────────────────────────────
         // Invariant: solid_segs is sorted; we can bail as soon as we find a
         // span whose `last` ≥ x_r.
-        if let Some(seg) = self.solid_segs.iter().find(|s| s.last >= x_r)
+        if let Some(seg) = self.frame.solid_segs.iter().find(|s| s.last >= x_r)
         // first candidate that can cover
         {
             if x_l >= seg.first && x_r <= seg.last {
+                self.stats.segs_culled_solid += 1;
                 return None; // fully hidden
             }
         }
@@ -88,25 +163,36 @@ impl Software {
         // ──────────────────────────────────────────────────────────────────────
         let span = sx2 - sx1;
         if span <= 1.0 {
+            self.stats.segs_culled_bbox += 1;
             return None;
         }
+        self.stats.segs_projected += 1;
         let invz_p1 = 1.0 / p1.y;
         let invz_p2 = 1.0 / p2.y;
-        let wall_len = (v2 - v1).length();
-        let uoz_p1 = t1 * wall_len * invz_p1;
-        let uoz_p2 = t2 * wall_len * invz_p2;
+        // texture u runs along the linedef: a split seg starts `offset`
+        // into it, shifted by the sidedef's x offset
+        let u_base = seg.offset + sd_front.x_off;
+        let uoz_p1 = (u_base + t1 * seg.length) * invz_p1;
+        let uoz_p2 = (u_base + t2 * seg.length) * invz_p2;
 
         let frac_l = (x_l as f32 - sx1) / span;
         let frac_r = (x_r as f32 - sx1) / span;
 
-        Some(Edge {
+        let edge = Edge {
             x_l,
             x_r,
             invz_l: invz_p1 + (invz_p2 - invz_p1) * frac_l,
             invz_r: invz_p1 + (invz_p2 - invz_p1) * frac_r,
             uoz_l: uoz_p1 + (uoz_p2 - uoz_p1) * frac_l,
             uoz_r: uoz_p1 + (uoz_p2 - uoz_p1) * frac_r,
-        })
+        };
+        debug_assert!(
+            [edge.invz_l, edge.invz_r, edge.uoz_l, edge.uoz_r]
+                .iter()
+                .all(|v| v.is_finite()),
+            "seg {seg_idx} projected to {edge:?}"
+        );
+        Some(edge)
     }
 
     /// Clip a segment to the near plane. Returns false if completely behind.
@@ -137,19 +223,9 @@ impl Software {
 
     fn back_facing_seg(p1: &Vec2, p2: &Vec2, camera: &Camera) -> bool {
         let cam_pos = camera.pos.truncate();
-
-        // vectors from camera to each endpoint
-        let v1 = p1 - cam_pos;
-        let v2 = p2 - cam_pos;
-
-        // compute angles in [–π, π]
-        let a1 = v1.y.atan2(v1.x);
-        let a2 = v2.y.atan2(v2.x);
-
-        // delta, normalized into [0, 2π)
-        let span = (a1 - a2).rem_euclid(2.0 * std::f32::consts::PI);
+        let span = angle_to(cam_pos, *p1) - angle_to(cam_pos, *p2);
 
         // if the angular span ≥ π, the wall is fully behind us
-        span >= std::f32::consts::PI
+        span >= Angle::ANG180
     }
 }
@@ -1,18 +1,81 @@
+use std::time::Instant;
+
 use crate::{
-    renderer::{Renderer, Rgba},
+    renderer::{
+        DrawCmd, RenderStats, Renderer, Rgba, ViewWindow,
+        overlay::{Canvas, Overlay},
+    },
+    screens::ViewBorder,
     sim::TicRunner,
-    world::{Camera, Level, SubsectorId, TextureBank},
+    world::{
+        Camera, FontRef, INVERSE_COLORMAP, Level, LineSet, PaletteEffect, ShadeTable, SubsectorId,
+        Texture, TextureBank,
+    },
 };
 
 use super::{
     planes::PlaneMap,
-    sprites::{DrawSeg, FrameScratch, VisSprite},
+    projection::CamVertices,
+    sprites::{DrawSeg, DrawSegBins, FrameScratch, SpriteClip, VisSprite},
 };
 
+/// Per column, the last row covered from above (`ceil`) and the first
+/// covered from below (`floor`): vanilla `ceilingclip` / `floorclip`.
+/// Both stay within `OPEN_CEIL..=height` and `ceil <= floor + 1`; a
+/// column with `ceil >= floor` has nothing left open.
 #[derive(Default)]
 pub struct ClipBands {
-    pub ceil: Vec<i16>,
-    pub floor: Vec<i16>,
+    pub ceil: Vec<i32>,
+    pub floor: Vec<i32>,
+    height: i32,
+}
+
+impl ClipBands {
+    /// `ceil` of a column nothing covers from above: the row over the
+    /// screen.
+    pub const OPEN_CEIL: i32 = -1;
+
+    /// Open every column of a `w`-wide, `h`-high frame.
+    pub fn reset(&mut self, w: usize, h: usize) {
+        self.height = h as i32;
+        self.ceil.clear();
+        self.ceil.resize(w, Self::OPEN_CEIL);
+        self.floor.clear();
+        self.floor.resize(w, self.open_floor());
+    }
+
+    /// `floor` of a column nothing covers from below: the row under the
+    /// screen.
+    #[inline]
+    pub fn open_floor(&self) -> i32 {
+        self.height
+    }
+
+    /// Whether column `col` still has rows to draw into.
+    #[inline]
+    pub fn is_open(&self, col: usize) -> bool {
+        self.ceil[col] < self.floor[col]
+    }
+
+    /// Cover all of column `col` (a one-sided wall).
+    #[inline]
+    pub fn close(&mut self, col: usize) {
+        self.ceil[col] = self.height;
+        self.floor[col] = self.height;
+    }
+
+    /// Check the band invariants of column `col` in debug builds.
+    #[inline]
+    pub fn debug_check(&self, col: usize) {
+        let (ceil, floor) = (self.ceil[col], self.floor[col]);
+        debug_assert!(
+            (Self::OPEN_CEIL..=self.height).contains(&ceil)
+                && (Self::OPEN_CEIL..=self.height).contains(&floor)
+                && ceil <= floor + 1,
+            "column {col}: clip bands {ceil}..{floor} outside -1..={}",
+            self.height
+        );
+    }
 }
 
 #[derive(Default, PartialEq, Debug)]
@@ -21,18 +84,222 @@ pub struct ClipRange {
     pub last: i32,
 }
 
+/// Size of the internal frame as a fraction of the window's.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct RenderScale(f32);
+
+impl Default for RenderScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// How the software renderer picks colormap rows; anything but
+/// `Vanilla` is a debugging / modding override.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LightMode {
+    /// Sector light, as the game draws it.
+    #[default]
+    Vanilla,
+    /// Every pixel at full palette brightness; no colormap applies, not
+    /// even invulnerability's.
+    Fullbright,
+    /// Every pixel from the one light row given (0 brightest, 31
+    /// darkest).
+    Flat(u8),
+    /// Sector light ignored; one row darker every `DIST_PER_SHADE` map
+    /// units away from the eye.
+    DistanceOnly,
+}
+
+/// Map units per colormap row in [`LightMode::DistanceOnly`].
+pub const DIST_PER_SHADE: f32 = 32.0;
+
+/// The darkest colormap row light can reach.
+const DARKEST_ROW: u8 = INVERSE_COLORMAP - 1;
+
+impl LightMode {
+    /// Parse a console argument: `vanilla`, `full`, `distance` or a row
+    /// number for `Flat`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "vanilla" => Some(Self::Vanilla),
+            "full" | "fullbright" => Some(Self::Fullbright),
+            "distance" => Some(Self::DistanceOnly),
+            row => row
+                .parse::<u8>()
+                .ok()
+                .filter(|&r| r <= DARKEST_ROW)
+                .map(Self::Flat),
+        }
+    }
+
+    /// The next mode for a cycling keybind; `Flat` cycles through mid
+    /// grey only.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Vanilla => Self::Fullbright,
+            Self::Fullbright => Self::Flat(16),
+            Self::Flat(_) => Self::DistanceOnly,
+            Self::DistanceOnly => Self::Vanilla,
+        }
+    }
+
+    /// Colormap row for a surface whose sector light gives `sector_shade`,
+    /// seen `depth` map units in front of the eye.
+    #[inline]
+    pub fn shade(self, sector_shade: u8, depth: f32) -> u8 {
+        match self {
+            Self::Vanilla => sector_shade,
+            Self::Fullbright => 0,
+            Self::Flat(row) => row.min(DARKEST_ROW),
+            Self::DistanceOnly => (depth / DIST_PER_SHADE).clamp(0.0, DARKEST_ROW as f32) as u8,
+        }
+    }
+}
+
+impl std::fmt::Display for LightMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Vanilla => f.write_str("vanilla"),
+            Self::Fullbright => f.write_str("fullbright"),
+            Self::Flat(row) => write!(f, "flat {row}"),
+            Self::DistanceOnly => f.write_str("distance"),
+        }
+    }
+}
+
+/// How walls and flats are sampled.  Sprites and masked textures are
+/// always `Nearest`: blending into their see-through texels would fringe
+/// them with whatever colour index 0 is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Filtering {
+    /// The nearest texel, as vanilla.
+    #[default]
+    Nearest,
+    /// Flats blended between the four nearest texels, walls between the
+    /// two nearest rows of their column.  Blending happens on the shaded
+    /// colours, since texels are palette indices.
+    Bilinear,
+}
+
+/// `a` and `b` mixed, `t` (0‥256) of the way to `b`.
+#[inline(always)]
+pub(super) fn lerp_rgb(a: Rgba, b: Rgba, t: u32) -> Rgba {
+    // red and blue in one multiply, green in another
+    let rb = ((a & 0xFF_00FF) * (256 - t) + (b & 0xFF_00FF) * t) >> 8;
+    let g = ((a & 0x00_FF00) * (256 - t) + (b & 0x00_FF00) * t) >> 8;
+    (rb & 0xFF_00FF) | (g & 0x00_FF00)
+}
+
+/// Vanilla rendering bugs to reproduce rather than fix.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompatOptions {
+    /// Mask wall rows with 127 like vanilla's column drawer, so textures
+    /// whose height isn't a power of two up to 128 run off into their
+    /// neighbouring columns instead of tiling.
+    pub emulate_tutti_frutti: bool,
+}
+
+/// What drawing one view builds up, all of it sized to the view.
+/// [`FrameState::reset`] starts it over; nothing in here carries from one
+/// view to the next, so one `Software` can draw several views a frame.
 #[derive(Default)]
-pub struct Software {
-    pub scratch: Vec<Rgba>,
+pub struct FrameState {
     pub clip_bands: ClipBands,
     pub visplane_map: PlaneMap,
     pub solid_segs: Vec<ClipRange>,
     pub sprites: Vec<VisSprite>,
     pub drawsegs: Vec<DrawSeg>,
+    pub ds_bins: DrawSegBins,
     pub frame_scratch: FrameScratch,
+    pub sprite_clip: SpriteClip,
+    pub cam_vertices: CamVertices,
+}
+
+impl FrameState {
+    /// Empty everything for a `w`×`h` view.  Buffers keep their capacity,
+    /// so a view no bigger than one drawn before doesn't allocate.
+    pub fn reset(&mut self, w: usize, h: usize) {
+        // fully open clips at start of frame
+        self.clip_bands.reset(w, h);
+        self.init_solid_segs(w);
+        self.visplane_map.clear(w);
+        self.sprites.clear();
+        self.drawsegs.clear();
+        self.ds_bins.reset(w);
+        self.frame_scratch.reset();
+        self.sprite_clip.clear();
+    }
+
+    pub fn init_solid_segs(&mut self, w: usize) {
+        let w = w as i32;
+        self.solid_segs.clear();
+        // Two sentinels so our add routine never has to worry
+        // about running off the ends of the array.
+        self.solid_segs.push(ClipRange {
+            first: -w,
+            last: -1,
+        });
+        self.solid_segs.push(ClipRange {
+            first: w,
+            last: w * 2,
+        });
+    }
+}
+
+/// A frame buffer put aside while frames of another size are drawn.
+pub(crate) struct ParkedFrame {
+    w: usize,
+    h: usize,
+    scratch: Vec<Rgba>,
+    view: ViewWindow,
+    border_stale: bool,
+}
 
+/// Frame sizes [`Software`] keeps a buffer for besides the current one.
+const PARKED_FRAMES: usize = 3;
+
+#[derive(Default)]
+pub struct Software {
+    /// The frame at render resolution (`frame_w` × `frame_h`); the view
+    /// is drawn into `view` of it.
+    pub scratch: Vec<Rgba>,
+    pub frame: FrameState,
+    /// Buffers of the last few other frame sizes, most recent last, so
+    /// switching between views of different sizes every frame neither
+    /// reallocates nor redraws the border.
+    pub(crate) parked: Vec<ParkedFrame>,
+    /// Subsectors `render_view` walks.
+    pub(crate) active: Vec<SubsectorId>,
+
+    /// Size of the 3-D view at render resolution; all projection and
+    /// clipping works in it.
     pub width: usize,
     pub height: usize,
+    /// The whole frame at render resolution: the window's scaled by
+    /// `render_scale`.
+    pub frame_w: usize,
+    pub frame_h: usize,
+    /// Where the view sits in the frame, at render resolution.
+    pub view: ViewWindow,
+    /// The frame around the view was resized or drawn over since the
+    /// border was last drawn.
+    pub(crate) border_stale: bool,
+    /// Window resolution `end_frame` submits at.
+    pub(crate) out_w: usize,
+    pub(crate) out_h: usize,
+    /// The window has no pixels (minimised): nothing is drawn until a
+    /// frame with some comes, and `end_frame` submits an empty buffer.
+    pub(crate) skip_frame: bool,
+    pub(crate) render_scale: RenderScale,
+    /// `scratch` blown up to the window when rendering below it.
+    pub(crate) output: Vec<Rgba>,
+    /// Source column of every output column, for `upscale`.
+    pub(crate) upscale_cols: Vec<usize>,
+    /// 2-D calls of this frame, drawn over it at window resolution in
+    /// `end_frame`.
+    pub(crate) overlay: Overlay,
 
     pub width_f: f32,
     pub height_f: f32,
@@ -40,10 +307,56 @@ pub struct Software {
     pub half_h: f32,
     pub focal: f32,
     pub view_z: f32,
+
+    /// When set, the raster loops append to this list instead of writing
+    /// `scratch` (see [`DrawCmd`]).
+    pub record: Option<Vec<DrawCmd>>,
+
+    /// Pain / pickup / radiation suit palette and invulnerability
+    /// colormap applied to everything drawn.
+    pub palette_effect: PaletteEffect,
+    /// Lighting override; see [`LightMode`].
+    pub light_mode: LightMode,
+    /// Texture sampling of walls and flats; see [`Filtering`].
+    pub filtering: Filtering,
+    pub compat: CompatOptions,
+    /// Where the next fuzz pixel reads `FUZZ_TABLE`; runs on across
+    /// frames so the shimmer moves.
+    pub fuzz_pos: usize,
+    /// Blend masked mid-textures 50/50 with what's behind them.
+    #[cfg(feature = "translucency")]
+    pub translucent_mids: bool,
+
+    pub stats: RenderStats,
 }
 
 impl Renderer for Software {
-    fn begin_frame(&mut self, w: usize, h: usize) {
+    fn begin_view(&mut self, out_w: usize, out_h: usize, view: ViewWindow) {
+        self.out_w = out_w;
+        self.out_h = out_h;
+        self.overlay.clear();
+        self.stats = RenderStats::default();
+        self.skip_frame = out_w == 0 || out_h == 0 || view.w == 0 || view.h == 0;
+        if self.skip_frame {
+            // so `mark_seen` finds none of the last drawn frame's drawsegs
+            self.frame.reset(0, 0);
+            if let Some(list) = &mut self.record {
+                list.clear();
+            }
+            return;
+        }
+        let scaled =
+            |n: usize| ((n as f32 * self.render_scale.0).round() as usize).clamp(n.min(1), n);
+        let (fw, fh) = (scaled(out_w), scaled(out_h));
+        if (fw, fh) != (self.frame_w, self.frame_h) {
+            self.switch_frame(fw, fh);
+        }
+        let view = view.scaled((out_w, out_h), (fw, fh));
+        if view != self.view {
+            self.view = view;
+            self.border_stale = true;
+        }
+        let (w, h) = (view.w, view.h);
         if w != self.width || h != self.height {
             self.width = w;
             self.height = h;
@@ -51,24 +364,18 @@ impl Renderer for Software {
             self.height_f = h as f32;
             self.half_w = self.width_f * 0.5;
             self.half_h = self.height_f * 0.5;
-            self.scratch.resize(w * h, 0);
-            self.clip_bands.ceil.resize(w, i16::MIN);
-            self.clip_bands.floor.resize(w, i16::MAX);
         }
         // dark‑grey clear
-        self.scratch.fill(0xFF_20_20_20);
-
-        // fully open clips at start of frame
-        self.clip_bands.ceil.fill(i16::MIN);
-        self.clip_bands.floor.fill(i16::MAX);
-
-        self.init_solid_segs();
-
-        self.visplane_map.clear(self.width);
+        match &mut self.record {
+            Some(list) => list.clear(),
+            None => {
+                for y in 0..h {
+                    self.view_row_mut(y).fill(0xFF_20_20_20);
+                }
+            }
+        }
 
-        self.sprites.clear();
-        self.drawsegs.clear();
-        self.frame_scratch.reset();
+        self.frame.reset(self.width, self.height);
     }
 
     fn draw_level(
@@ -80,19 +387,22 @@ impl Renderer for Software {
         texture_bank: &TextureBank,
     ) {
         // win.update_with_buffer(&self.scratch, self.width, self.height);
-        if subsectors.is_empty() {
+        if subsectors.is_empty() || self.skip_frame {
             return;
         }
 
         self.focal = camera.screen_scale(self.width);
         self.view_z = camera.pos.z;
+        self.frame.cam_vertices.next_view();
 
+        let t_walls = Instant::now();
+        self.stats.subsectors += subsectors.len() as u32;
         for ss_idx in subsectors.iter().copied() {
             let ss = &level.subsectors[ss_idx as usize];
             let start = ss.first_line;
             let end = start + ss.num_lines;
 
-            self.collect_sprites_for_subsector(ss_idx, sim, camera, texture_bank);
+            self.collect_sprites_for_subsector(level, ss_idx, sim, camera, texture_bank);
 
             for seg_idx in start..end {
                 if let Some(edge) = self.project_seg(seg_idx, level, camera) {
@@ -101,35 +411,61 @@ impl Renderer for Software {
             }
         }
 
-        self.flush_planes(camera, texture_bank);
+        let t_planes = Instant::now();
+        self.stats.wall_time += t_planes - t_walls;
+
+        self.flush_planes(camera, texture_bank, level.sky_texture);
+        let t_sprites = Instant::now();
+        self.stats.plane_time += t_sprites - t_planes;
 
         self.draw_sprites(level, texture_bank);
+        self.stats.sprite_time += t_sprites.elapsed();
     }
 
     fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, col: u32) {
-        let mut x0 = x0;
-        let mut y0 = y0;
-        let dx = (x1 - x0).abs();
-        let sx = if x0 < x1 { 1 } else { -1 };
-        let dy = -(y1 - y0).abs();
-        let sy = if y0 < y1 { 1 } else { -1 };
-        let mut err = dx + dy;
-        loop {
-            if (0..self.width as i32).contains(&x0) && (0..self.height as i32).contains(&y0) {
-                self.scratch[y0 as usize * self.width + x0 as usize] = col;
-            }
-            if x0 == x1 && y0 == y1 {
-                break;
-            }
-            let e2 = 2 * err;
-            if e2 >= dy {
-                err += dy;
-                x0 += sx;
-            }
-            if e2 <= dx {
-                err += dx;
-                y0 += sy;
-            }
+        self.overlay.line(x0, y0, x1, y1, col);
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, col: Rgba) {
+        self.overlay.fill_rect(x, y, w, h, col);
+    }
+
+    fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.overlay.dim_rect(x, y, w, h);
+    }
+
+    fn blit_patch(&mut self, patch: &Texture, x: i32, y: i32, shade: u8, bank: &TextureBank) {
+        // the palette tints the whole screen; colormaps are the view's
+        let shades = bank.shades(PaletteEffect {
+            fixed_colormap: None,
+            ..self.palette_effect
+        });
+        self.overlay.patch(patch, x, y, 1, |t| shades.get(shade, t));
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        self.overlay.text(text, x, y, font, col);
+    }
+
+    fn draw_border(&mut self, border: &ViewBorder) {
+        if !self.border_stale || self.record.is_some() || self.skip_frame {
+            return;
+        }
+        if !self.view.is_full(self.frame_w, self.frame_h) {
+            border.draw(self.view, &mut self.scratch, self.frame_w, self.frame_h);
+        }
+        self.border_stale = false;
+    }
+
+    fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+
+    /// Every drawseg's line: vanilla marks `ML_MAPPED` in
+    /// `R_StoreWallRange`, which is where drawsegs come from.
+    fn mark_seen(&self, level: &Level, seen: &mut LineSet) {
+        for ds in &self.frame.drawsegs {
+            seen.insert(level.segs[ds.cur_line as usize].linedef);
         }
     }
 
@@ -137,38 +473,211 @@ impl Renderer for Software {
     where
         F: FnOnce(&[Rgba], usize, usize),
     {
-        submit(&self.scratch, self.width, self.height);
+        if self.skip_frame {
+            return submit(&[], 0, 0);
+        }
+        // below native resolution the overlay goes on after upscaling,
+        // so the automap and text stay sharp
+        if !self.is_scaled() {
+            if !self.overlay.is_empty() {
+                let (w, h) = (self.frame_w, self.frame_h);
+                self.overlay.draw(&mut Canvas::new(&mut self.scratch, w, h));
+                self.border_stale = true;
+            }
+            return submit(&self.scratch, self.frame_w, self.frame_h);
+        }
+        self.upscale();
+        let (w, h) = (self.out_w, self.out_h);
+        self.overlay.draw(&mut Canvas::new(&mut self.output, w, h));
+        submit(&self.output, w, h);
     }
 }
 
 impl Software {
-    pub fn init_solid_segs(&mut self) {
-        let w = self.width as i32;
-        self.solid_segs.clear();
-        // Two sentinels so our add routine never has to worry
-        // about running off the ends of the array.
-        self.solid_segs.push(ClipRange {
-            first: -w,
-            last: -1,
-        });
-        self.solid_segs.push(ClipRange {
-            first: w,
-            last: w * 2,
+    /// Render at `scale` (clamped to 0.25‥1) of the window's resolution
+    /// from the next frame on; `end_frame` upscales to the window.
+    /// Vanilla's low detail mode is roughly 0.5.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = RenderScale(scale.clamp(0.25, 1.0));
+    }
+
+    #[inline]
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale.0
+    }
+
+    /// Make the frame buffer `w`×`h`: park the current one and take back
+    /// the one last used at that size, if it's still around.
+    fn switch_frame(&mut self, w: usize, h: usize) {
+        if !self.scratch.is_empty() {
+            if self.parked.len() == PARKED_FRAMES {
+                self.parked.remove(0);
+            }
+            self.parked.push(ParkedFrame {
+                w: self.frame_w,
+                h: self.frame_h,
+                scratch: std::mem::take(&mut self.scratch),
+                view: self.view,
+                border_stale: self.border_stale,
+            });
+        }
+        (self.frame_w, self.frame_h) = (w, h);
+        match self.parked.iter().position(|p| (p.w, p.h) == (w, h)) {
+            Some(i) => {
+                let parked = self.parked.remove(i);
+                self.scratch = parked.scratch;
+                self.view = parked.view;
+                self.border_stale = parked.border_stale;
+            }
+            None => {
+                self.scratch.resize(w * h, 0);
+                self.border_stale = true;
+            }
+        }
+    }
+
+    fn is_scaled(&self) -> bool {
+        (self.frame_w, self.frame_h) != (self.out_w, self.out_h)
+    }
+
+    /// `scratch` index of view pixel (`x`, `y`).
+    #[inline]
+    pub fn pixel_index(&self, x: usize, y: usize) -> usize {
+        (self.view.y + y) * self.frame_w + self.view.x + x
+    }
+
+    /// Write view pixel (`x`, `y`).
+    #[inline]
+    pub fn put_pixel(&mut self, x: usize, y: usize, col: Rgba) {
+        let i = self.pixel_index(x, y);
+        self.scratch[i] = col;
+    }
+
+    /// Row `y` of the view, `width` pixels.
+    #[inline]
+    pub fn view_row_mut(&mut self, y: usize) -> &mut [Rgba] {
+        let i = self.pixel_index(0, y);
+        &mut self.scratch[i..][..self.width]
+    }
+
+    /// Nearest-neighbour `scratch` → `output`, with a fast path for
+    /// exactly half the window.
+    fn upscale(&mut self) {
+        let (sw, sh) = (self.frame_w, self.frame_h);
+        let (ow, oh) = (self.out_w, self.out_h);
+        self.output.resize(ow * oh, 0);
+
+        if ow == sw * 2 && oh == sh * 2 {
+            for (src, out) in self
+                .scratch
+                .chunks_exact(sw)
+                .zip(self.output.chunks_exact_mut(ow * 2))
+            {
+                let (top, bottom) = out.split_at_mut(ow);
+                for (o, &px) in top.chunks_exact_mut(2).zip(src) {
+                    o.fill(px);
+                }
+                bottom.copy_from_slice(top);
+            }
+            return;
+        }
+
+        self.upscale_cols.clear();
+        self.upscale_cols.extend((0..ow).map(|x| x * sw / ow));
+        let xs = &self.upscale_cols;
+        let mut last_src = usize::MAX;
+        for y in 0..oh {
+            let sy = y * sh / oh;
+            if sy == last_src {
+                // same source row: repeat the one just written
+                self.output.copy_within((y - 1) * ow..y * ow, y * ow);
+                continue;
+            }
+            last_src = sy;
+            let src = &self.scratch[sy * sw..][..sw];
+            for (o, &sx) in self.output[y * ow..][..ow].iter_mut().zip(xs) {
+                *o = src[sx];
+            }
+        }
+    }
+
+    /// Palette (and fixed colormap) for the next frames; see
+    /// `PlayerStatus::palette_effect`.
+    pub fn set_palette_effect(&mut self, effect: PaletteEffect) {
+        self.palette_effect = effect;
+    }
+
+    /// Lighting for the next frames; see [`LightMode`].
+    pub fn set_light_mode(&mut self, mode: LightMode) {
+        self.light_mode = mode;
+    }
+
+    /// Texture sampling for the next frames; see [`Filtering`].
+    pub fn set_filtering(&mut self, filtering: Filtering) {
+        self.filtering = filtering;
+    }
+
+    pub fn set_compat(&mut self, compat: CompatOptions) {
+        self.compat = compat;
+    }
+
+    /// Draw `camera`'s view as a frame of its own, the size of `rect`, and
+    /// copy it into `rect` of `dest`, a frame `dest_w` pixels wide;
+    /// clipped to `dest`.  For a second view in a frame (split screen, a
+    /// picture-in-picture): anything drawn since the last `begin_frame`
+    /// should be submitted first.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_view(
+        &mut self,
+        level: &Level,
+        sim: &TicRunner,
+        camera: &Camera,
+        bank: &TextureBank,
+        dest: &mut [Rgba],
+        dest_w: usize,
+        rect: ViewWindow,
+    ) {
+        self.begin_frame(rect.w, rect.h);
+        let mut active = std::mem::take(&mut self.active);
+        level.fill_active_subsectors(camera, &mut active);
+        self.draw_level(&active, level, sim, camera, bank);
+        self.active = active;
+
+        let dest_h = dest.len() / dest_w.max(1);
+        self.end_frame(|fb, w, h| {
+            let cols = w.min(dest_w.saturating_sub(rect.x));
+            for y in 0..h.min(dest_h.saturating_sub(rect.y)) {
+                let at = (rect.y + y) * dest_w + rect.x;
+                dest[at..][..cols].copy_from_slice(&fb[y * w..][..cols]);
+            }
         });
     }
 
+    /// The shade rows every drawer looks its pixels up in: the palette
+    /// effect's, without its fixed colormap under `Fullbright`.
+    #[inline]
+    pub(super) fn shades<'b>(&self, bank: &'b TextureBank) -> ShadeTable<'b> {
+        match self.light_mode {
+            LightMode::Fullbright => bank.shades(PaletteEffect {
+                fixed_colormap: None,
+                ..self.palette_effect
+            }),
+            _ => bank.shades(self.palette_effect),
+        }
+    }
+
     pub fn add_solid_seg(&mut self, first: i32, last: i32) {
         let mut i = 0;
         // 1) skip all segments that end *before* ours minus one
-        while i < self.solid_segs.len() && self.solid_segs[i].last < first - 1 {
+        while i < self.frame.solid_segs.len() && self.frame.solid_segs[i].last < first - 1 {
             i += 1;
         }
 
         // if the new segment is completely swallowed by an existing one,
         // we’re done early
-        if i < self.solid_segs.len()
-            && first >= self.solid_segs[i].first
-            && last <= self.solid_segs[i].last
+        if i < self.frame.solid_segs.len()
+            && first >= self.frame.solid_segs[i].first
+            && last <= self.frame.solid_segs[i].last
         {
             return;
         }
@@ -176,14 +685,14 @@ impl Software {
         // 2) merge any overlapping or adjacent segments:
         let mut new_first = first;
         let mut new_last = last;
-        while i < self.solid_segs.len() && self.solid_segs[i].first <= new_last + 1 {
-            new_first = new_first.min(self.solid_segs[i].first);
-            new_last = new_last.max(self.solid_segs[i].last);
-            self.solid_segs.remove(i);
+        while i < self.frame.solid_segs.len() && self.frame.solid_segs[i].first <= new_last + 1 {
+            new_first = new_first.min(self.frame.solid_segs[i].first);
+            new_last = new_last.max(self.frame.solid_segs[i].last);
+            self.frame.solid_segs.remove(i);
         }
 
         // 3) insert the coalesced segment in its sorted place
-        self.solid_segs.insert(
+        self.frame.solid_segs.insert(
             i,
             ClipRange {
                 first: new_first,
@@ -195,7 +704,15 @@ impl Software {
 
 #[cfg(test)]
 mod tests {
-    use super::{ClipRange, Software}; // or whatever your types are called
+    use super::{ClipRange, CompatOptions, LightMode, Software}; // or whatever your types are called
+    use crate::renderer::{RenderStats, Renderer, ViewWindow};
+    use crate::screens::ViewBorder;
+    use crate::sim::TicRunner;
+    use crate::world::{
+        Camera, Level, LineSet, TRANSPARENT_TEXTURE, Texture, TextureBank, Vertex, VertexId,
+        testmap,
+    };
+    use glam::{Vec2, Vec3};
 
     /// Regression test for the “new_last not updated” bug in add_solid_seg().
     #[test]
@@ -211,7 +728,7 @@ mod tests {
             },
         ];
 
-        sw.solid_segs = segs;
+        sw.frame.solid_segs = segs;
 
         // new wall span that should close BOTH gaps (5-6 and 12-13)
         sw.add_solid_seg(6, 9);
@@ -219,8 +736,766 @@ mod tests {
         // after the fix we expect ONE merged span covering 0‥20
         let expected = vec![ClipRange { first: 0, last: 20 }];
         assert_eq!(
-            sw.solid_segs, expected,
+            sw.frame.solid_segs, expected,
             "solid_segs should be fully coalesced after inserting a bridging span"
         );
     }
+
+    #[test]
+    fn stats_count_one_frame_and_reset() {
+        let mut bank = TextureBank::default_with_checker();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.lower, sd.middle) = (wall, wall, wall);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (wall, wall);
+        }
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(32.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+
+        // record instead of rasterising: the counters don't need a palette
+        let mut sw = Software {
+            record: Some(Vec::new()),
+            ..Default::default()
+        };
+        sw.begin_frame(160, 100);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+
+        let s = sw.stats();
+        assert_eq!(s.subsectors, active.len() as u32);
+        assert!(s.segs_projected > 0 && s.wall_columns > 0);
+        assert!(s.segs_culled_backface + s.segs_culled_bbox + s.segs_culled_solid > 0);
+        assert!(s.visplanes_created > 0 && s.plane_pixels > 0);
+        // every column of the frame shows some wall
+        assert!(s.wall_columns >= 160);
+        // each vertex is transformed once, however many segs end on it
+        assert!(s.vertices_transformed > 0);
+        assert!(s.vertices_transformed as usize <= level.vertices.len());
+        assert!(s.vertices_transformed < 2 * s.segs_projected);
+
+        sw.begin_frame(160, 100);
+        assert_eq!(*sw.stats(), RenderStats::ZERO);
+    }
+
+    #[test]
+    fn drawn_walls_are_marked_seen() {
+        let bank = TextureBank::default_with_checker();
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let sim = TicRunner::new(&level);
+        // in room A looking east at the wall into M
+        let camera = Camera::new(Vec3::new(32.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let mut sw = Software {
+            record: Some(Vec::new()),
+            ..Default::default()
+        };
+        sw.begin_frame(160, 100);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+
+        let mut seen = LineSet::new(level.linedefs.len());
+        sw.mark_seen(&level, &mut seen);
+        // A's south, north and east walls; not the west one behind, nor
+        // anything past the wall
+        assert_eq!(seen.count(), 3);
+        assert!([0, 1, 8].into_iter().all(|l| seen.contains(l)));
+        assert!(!seen.contains(6) && !seen.contains(9));
+    }
+
+    #[test]
+    fn split_seg_texture_continues_across_the_split() {
+        let mut bank = TextureBank::default_with_checker();
+        let wide = Texture {
+            name: "WIDE".into(),
+            w: 256,
+            h: 8,
+            pixels: vec![0; 256 * 8],
+            mask: None,
+        };
+        let wall = bank.insert("WIDE", wide).unwrap();
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+
+        // room A's east wall, (128,128) → (128,0), on a sidedef of its own
+        // and split at y=88, off the texel grid
+        let east = level
+            .segs
+            .iter()
+            .position(|s| (s.v1, s.v2) == (5, 1))
+            .unwrap();
+        let mut side = level.sidedefs[0].clone();
+        side.middle = wall;
+        level.sidedefs.push(side);
+        let line = level.segs[east].linedef as usize;
+        level.linedefs[line].right_sidedef = Some(level.sidedefs.len() as u16 - 1);
+        level.vertices.push(Vertex {
+            pos: Vec2::new(128.0, 88.0),
+        });
+        let mid = level.vertices.len() as VertexId - 1;
+        let tail = crate::world::Segment {
+            v1: mid,
+            offset: 40.0,
+            ..level.segs[east].clone()
+        };
+        level.segs[east].v2 = mid;
+        level.segs.insert(east + 1, tail);
+        level.subsectors[0].num_lines += 1;
+        for ss in &mut level.subsectors[1..] {
+            ss.first_line += 1;
+        }
+        level.measure_segs();
+
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(32.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        // texture u of the wall's columns, left to right
+        let columns = |level: &Level| {
+            let mut active = Vec::new();
+            level.fill_active_subsectors(&camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(160, 100);
+            sw.draw_level(&active, level, &sim, &camera, &bank);
+            let mut us: Vec<_> = sw
+                .record
+                .unwrap()
+                .iter()
+                .filter(|c| c.tex == wall && c.x0 == c.x1)
+                .map(|c| (c.x0, c.uv.x))
+                .collect();
+            us.sort_by_key(|&(x, _)| x);
+            us.dedup_by_key(|&mut (x, _)| x);
+            us.into_iter().map(|(_, u)| u).collect::<Vec<_>>()
+        };
+
+        let us = columns(&level);
+        assert!(us.len() > 80, "{} columns", us.len());
+        assert!(us[0] <= 2.0 && *us.last().unwrap() >= 126.0, "{us:?}");
+        for w in us.windows(2) {
+            assert!((0.0..=3.0).contains(&(w[1] - w[0])), "u jumps: {us:?}");
+        }
+
+        // the sidedef's x offset shifts the whole wall
+        level.sidedefs.last_mut().unwrap().x_off = 7.0;
+        let shifted = columns(&level);
+        assert_eq!(shifted.len(), us.len());
+        for (a, b) in us.iter().zip(&shifted) {
+            assert!((b - a - 7.0).abs() <= 1.0, "{a} → {b}");
+        }
+    }
+
+    /// Node builders leave zero-length segs behind at near-coincident
+    /// vertices; they project to nothing instead of NaN spans, and don't
+    /// count as culled.
+    #[test]
+    fn zero_length_segs_are_skipped() {
+        let bank = TextureBank::default_with_checker();
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let sim = TicRunner::new(&level);
+        let culls = |level: &Level, camera: &Camera| {
+            let mut active = Vec::new();
+            level.fill_active_subsectors(camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(160, 100);
+            sw.draw_level(&active, level, &sim, camera, &bank);
+            let s = &sw.stats;
+            (
+                s.segs_culled_backface,
+                s.segs_culled_bbox,
+                s.segs_culled_solid,
+            )
+        };
+        let ahead = Camera::new(Vec3::new(32.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let before = culls(&level, &ahead);
+
+        let east = level
+            .segs
+            .iter()
+            .position(|s| (s.v1, s.v2) == (5, 1))
+            .unwrap();
+        level.vertices.push(level.vertices[1]);
+        let dup = level.vertices.len() as VertexId - 1;
+        let stub = crate::world::Segment {
+            v1: 1,
+            v2: dup,
+            ..level.segs[east].clone()
+        };
+        level.segs.insert(east + 1, stub);
+        level.subsectors[0].num_lines += 1;
+        for ss in &mut level.subsectors[1..] {
+            ss.first_line += 1;
+        }
+        level.measure_segs();
+        assert_eq!(culls(&level, &ahead), before);
+
+        for yaw in [0.0, 45.0, 90.0, 315.0] {
+            let camera = Camera::new(
+                Vec3::new(32.0, 64.0, 41.0),
+                f32::to_radians(yaw),
+                90_f32.to_radians(),
+            );
+            let mut active = Vec::new();
+            level.fill_active_subsectors(&camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(160, 100);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+            for c in sw.record.as_ref().unwrap() {
+                assert!(c.uv.is_finite(), "{yaw}°: {c:?}");
+            }
+        }
+    }
+
+    /// Standing against a wall and looking along it projects its ends far
+    /// off screen; the clip bands and visplane columns must stay on it.
+    #[test]
+    fn wall_at_the_nose_keeps_clip_bands_on_screen() {
+        let mut bank = TextureBank::default_with_checker();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.lower, sd.middle) = (wall, wall, TRANSPARENT_TEXTURE);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (wall, wall);
+        }
+        level.sectors[0].ceil_h = 4096.0;
+        let sim = TicRunner::new(&level);
+
+        let (w, h) = (160, 100);
+        for (x, yaw) in [(127.9, 90.0), (127.9, 270.0), (0.1, 90.0), (127.9, 5.0)] {
+            let camera = Camera::new(
+                Vec3::new(x, 64.0, 41.0),
+                f32::to_radians(yaw),
+                90_f32.to_radians(),
+            );
+            let mut active = Vec::new();
+            level.fill_active_subsectors(&camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(w, h);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+
+            for col in 0..w {
+                let (ceil, floor) = (
+                    sw.frame.clip_bands.ceil[col],
+                    sw.frame.clip_bands.floor[col],
+                );
+                assert!((-1..=h as i32).contains(&ceil), "{yaw}°: ceil {ceil}");
+                assert!((-1..=h as i32).contains(&floor), "{yaw}°: floor {floor}");
+                assert!(ceil <= floor + 1, "{yaw}°: column {col} {ceil} > {floor}+1");
+            }
+            for (_, tops, bottoms) in sw.frame.visplane_map.iter() {
+                for col in 0..w {
+                    if tops[col] == u16::MAX {
+                        continue;
+                    }
+                    let (top, bottom) = (tops[col], bottoms[col]);
+                    assert!(top <= bottom, "{yaw}°: column {col} plane {top} > {bottom}");
+                    assert!(
+                        (bottom as usize) < h,
+                        "{yaw}°: column {col} plane ends at {bottom}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shrunken_view_draws_inside_its_window_only() {
+        let bank = TextureBank::default_with_checker();
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(32.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let mut playpal = vec![0u8; 768];
+        playpal[3..6].copy_from_slice(&[0x10, 0x20, 0x30]);
+        let wad = crate::wad::Wad::from_lumps(&[("PLAYPAL", playpal), ("FLOOR7_2", vec![1; 4096])]);
+        let border = ViewBorder::new(&wad);
+
+        let (w, h) = (160, 100);
+        let view = ViewWindow::for_screenblocks(7, w, h);
+        let mut sw = Software::default();
+        sw.begin_view(w, h, view);
+        assert_eq!((sw.frame_w, sw.frame_h), (w, h));
+        assert_eq!((sw.width, sw.height), (view.w, view.h));
+        sw.draw_border(&border);
+        assert_eq!(sw.scratch[0], 0xFF10_2030);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+        let outside = |sw: &Software| {
+            (0..w * h)
+                .filter(|i| !view.contains(i % w, i / w))
+                .map(|i| sw.scratch[i])
+                .collect::<Vec<_>>()
+        };
+        assert!(outside(&sw).iter().all(|&px| px == 0xFF10_2030));
+        let inside = sw.scratch[(view.y + view.h / 2) * w + view.x + view.w / 2];
+        assert_ne!(inside, 0xFF10_2030);
+
+        // the border stays put: redrawn only once the size changes
+        sw.scratch.fill(7);
+        sw.begin_view(w, h, view);
+        sw.draw_border(&border);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+        assert!(outside(&sw).iter().all(|&px| px == 7));
+        assert_ne!(sw.scratch[view.y * w + view.x], 7);
+        sw.begin_view(w, h, ViewWindow::for_screenblocks(8, w, h));
+        sw.draw_border(&border);
+        assert_eq!(sw.scratch[0], 0xFF10_2030);
+
+        let mut size = (0, 0);
+        sw.end_frame(|_, fw, fh| size = (fw, fh));
+        assert_eq!(size, (w, h));
+    }
+
+    #[test]
+    fn views_of_different_sizes_share_one_renderer() {
+        let bank = TextureBank::default_with_checker();
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(32.0, 40.0, 41.0), 0.3, 90_f32.to_radians());
+        let (w, h) = (160, 100);
+        let pip = ViewWindow {
+            x: 90,
+            y: 4,
+            w: 64,
+            h: 48,
+        };
+        let alone = |(w, h)| {
+            let mut out = vec![0; w * h];
+            let mut sw = Software::default();
+            sw.render_view(
+                &level,
+                &sim,
+                &camera,
+                &bank,
+                &mut out,
+                w,
+                ViewWindow::full(w, h),
+            );
+            out
+        };
+        let (main, small) = (alone((w, h)), alone((pip.w, pip.h)));
+
+        let mut sw = Software::default();
+        let mut buffers = None;
+        for _ in 0..3 {
+            let mut out = vec![0; w * h];
+            sw.render_view(
+                &level,
+                &sim,
+                &camera,
+                &bank,
+                &mut out,
+                w,
+                ViewWindow::full(w, h),
+            );
+            assert_eq!(out, main);
+            let main_buf = sw.scratch.as_ptr();
+            sw.render_view(&level, &sim, &camera, &bank, &mut out, w, pip);
+            for (i, &px) in out.iter().enumerate() {
+                let (x, y) = (i % w, i / w);
+                let want = match pip.contains(x, y) {
+                    true => small[(y - pip.y) * pip.w + x - pip.x],
+                    false => main[i],
+                };
+                assert_eq!(px, want, "({x}, {y})");
+            }
+            // each size keeps its own frame buffer from frame to frame
+            let now = (main_buf, sw.scratch.as_ptr());
+            assert_eq!(*buffers.get_or_insert(now), now);
+        }
+    }
+
+    #[test]
+    fn overlay_goes_over_whatever_the_view_drew() {
+        let mut sw = Software::default();
+        sw.begin_frame(8, 6);
+        sw.fill_rect(-2, 4, 5, 9, 0xAA);
+        sw.draw_line(7, 0, 7, 100, 0xBB);
+        // what `draw_level` would do after the 2-D calls came in
+        sw.scratch.fill(1);
+        let mut fb = Vec::new();
+        sw.end_frame(|f, _, _| fb.extend_from_slice(f));
+        for (i, &px) in fb.iter().enumerate() {
+            let (x, y) = (i % 8, i / 8);
+            let want = match (x, y) {
+                (7, _) => 0xBB,
+                (..3, 4..) => 0xAA,
+                _ => 1,
+            };
+            assert_eq!(px, want, "({x}, {y})");
+        }
+        // and is gone from the next frame
+        sw.begin_frame(8, 6);
+        sw.scratch.fill(1);
+        sw.end_frame(|f, _, _| assert!(f.iter().all(|&px| px == 1)));
+    }
+
+    #[test]
+    fn render_scale_upscales_to_the_window() {
+        let mut sw = Software::default();
+        sw.set_render_scale(0.5);
+        sw.begin_frame(8, 6);
+        assert_eq!((sw.width, sw.height), (4, 3));
+        for (i, px) in sw.scratch.iter_mut().enumerate() {
+            *px = i as u32;
+        }
+        // window coordinates, drawn over the upscaled frame
+        sw.draw_line(0, 5, 7, 5, 0xFF);
+        let mut out = Vec::new();
+        sw.end_frame(|fb, w, h| out.extend(fb.iter().copied().chain([w as u32, h as u32])));
+        let (fb, size) = out.split_at(48);
+        assert_eq!(size, [8, 6]);
+        for y in 0..5 {
+            for x in 0..8 {
+                assert_eq!(fb[y * 8 + x], (y / 2 * 4 + x / 2) as u32, "({x}, {y})");
+            }
+        }
+        assert!(fb[40..].iter().all(|&px| px == 0xFF));
+
+        // no integer ratio: nearest neighbour
+        sw.set_render_scale(0.75);
+        sw.begin_frame(8, 4);
+        assert_eq!((sw.width, sw.height), (6, 3));
+        for (i, px) in sw.scratch.iter_mut().enumerate() {
+            *px = i as u32;
+        }
+        let mut fb = Vec::new();
+        sw.end_frame(|f, _, _| fb.extend_from_slice(f));
+        assert_eq!(&fb[..8], &[0, 0, 1, 2, 3, 3, 4, 5]);
+        assert_eq!(&fb[8..16], &fb[..8]);
+        assert_eq!(&fb[24..], &[12, 12, 13, 14, 15, 15, 16, 17]);
+    }
+
+    #[test]
+    fn fuzz_copies_darkened_neighbours_inside_the_frame() {
+        let mut sw = Software::default();
+        sw.begin_frame(4, 8);
+        // each row its own grey, so a fuzz pixel names its source row
+        for y in 0..8 {
+            sw.scratch[y * 4..][..4].fill(0x10_10_10 * (y as u32 + 1));
+        }
+
+        // the whole column, edges included: must not read off-screen
+        sw.draw_fuzz_column(1, 0, 7);
+
+        let col: Vec<_> = (0..8).map(|y| sw.scratch[y * 4 + 1]).collect();
+        assert_eq!(col[0], 0x10_10_10, "top row untouched");
+        assert_eq!(col[7], 0x80_80_80, "bottom row untouched");
+        // darkened copies of a neighbour (the one above is already fuzzed)
+        for (y, &c) in col.iter().enumerate().take(7).skip(1) {
+            let below = 0x10 * (y as u32 + 2) * 26 / 32;
+            assert!(c & 0xFF <= below && c != 0x10_10_10 * (y as u32 + 1));
+            assert_eq!(c, (c & 0xFF) * 0x01_01_01, "row {y}: {c:06x}");
+        }
+        assert_eq!(sw.fuzz_pos, 6);
+    }
+
+    #[test]
+    fn light_modes_override_the_shade_rows() {
+        let mut bank = TextureBank::default_with_checker();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        for sd in &mut level.sidedefs {
+            sd.middle = wall;
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex, s.light) = (wall, wall, 0.5);
+        }
+        let sim = TicRunner::new(&level);
+        // looking along room A, so the side walls recede
+        let camera = Camera::new(Vec3::new(8.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let shades = |mode| {
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                light_mode: mode,
+                ..Default::default()
+            };
+            sw.begin_frame(160, 100);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+            let mut rows: Vec<u8> = sw.record.unwrap().iter().map(|c| c.shade).collect();
+            rows.sort_unstable();
+            rows.dedup();
+            rows
+        };
+
+        // walls and planes round the sector's light differently
+        assert_eq!(shades(LightMode::Vanilla), [15, 16]);
+        assert_eq!(shades(LightMode::Fullbright), [0]);
+        assert_eq!(shades(LightMode::Flat(9)), [9]);
+        // from the bottom screen row's floor, ~66 units away, to the far
+        // wall at 120
+        let rows = shades(LightMode::DistanceOnly);
+        let dist = |d: f32| (d / super::DIST_PER_SHADE) as u8;
+        assert_eq!(rows, [dist(66.0), dist(120.0)]);
+    }
+
+    #[test]
+    fn sky_ceilings_draw_the_sky_texture() {
+        let mut bank = TextureBank::default_with_checker();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        let sky = bank.insert("SKY1", Texture::default()).unwrap();
+        // open sky over all three rooms, the strip between them lower
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 60.0,
+        });
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.middle) = (wall, wall);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex, s.light) = (wall, wall, 0.5);
+            s.is_sky_ceiling = true;
+        }
+        level.sky_texture = sky;
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(8.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let mut sw = Software {
+            record: Some(Vec::new()),
+            ..Default::default()
+        };
+        sw.begin_frame(160, 100);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+        let list = sw.record.unwrap();
+
+        // full bright columns at a texel a pixel (twice that at 160
+        // wide), row 100 of the texture on the horizon
+        let sky: Vec<_> = list.iter().filter(|c| c.tex == sky).collect();
+        for c in &sky {
+            assert!(c.x0 == c.x1 && c.shade == 0, "{c:?}");
+            assert_eq!(c.duv_dy.y, 2.0);
+            assert_eq!(c.uv.y, 100.0 + (c.y0 as f32 + 0.5 - 50.0) * 2.0);
+        }
+        // no upper wall between two skies: straight ahead the sky goes
+        // down to the top of room B's far wall, row 23
+        let ahead = sky.iter().find(|c| c.x0 == 80).unwrap();
+        assert_eq!((ahead.y0, ahead.y1), (0, 23));
+        // and no ceiling is drawn as a flat
+        assert!(
+            !list
+                .iter()
+                .any(|c| c.tex == wall && c.y0 == c.y1 && c.y0 < 50)
+        );
+    }
+
+    #[test]
+    fn fullbright_ignores_the_fixed_colormap() {
+        use crate::world::{Colormap, INVERSE_COLORMAP, Palette, PaletteEffect};
+        let mut bank = TextureBank::default_with_checker();
+        let mut grey = Palette::default();
+        let mut colormap = Colormap::default();
+        for i in 0..256 {
+            grey[i] = (i as u32) * 0x01_01_01;
+            for row in colormap.0.iter_mut() {
+                row[i] = 255 - i as u8;
+            }
+            colormap[0][i] = i as u8;
+        }
+        bank.set_palettes(vec![grey]);
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+
+        let mut sw = Software::default();
+        sw.set_palette_effect(PaletteEffect {
+            palette: 0,
+            fixed_colormap: Some(INVERSE_COLORMAP),
+        });
+        assert_eq!(sw.shades(&bank).get(0, 10), 245 * 0x01_01_01);
+        sw.set_light_mode(LightMode::Fullbright);
+        assert_eq!(sw.shades(&bank).get(0, 10), 10 * 0x01_01_01);
+        assert_eq!(LightMode::parse("full"), Some(LightMode::Fullbright));
+        assert_eq!(LightMode::parse("31"), Some(LightMode::Flat(31)));
+        assert_eq!(LightMode::parse("32"), None);
+    }
+
+    /// A 72-high wall texture on a 128-high wall: tiled by default,
+    /// vanilla's masking runs off into the neighbouring column.  The odd
+    /// 64×65 flats must draw too.
+    #[test]
+    fn tutti_frutti_is_opt_in() {
+        use crate::world::{Colormap, Palette};
+        let mut bank = TextureBank::default_with_checker();
+        let mut grey = Palette::default();
+        let mut colormap = Colormap::default();
+        for i in 0..256 {
+            grey[i] = i as u32 * 0x01_01_01;
+            colormap[0][i] = i as u8;
+        }
+        bank.set_palettes(vec![grey]);
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+        // texel (u, v) is u·128 + v, so a pixel names its column
+        let step = Texture {
+            name: "STEP".into(),
+            w: 2,
+            h: 72,
+            pixels: (0..72).flat_map(|v| [v, 128 + v]).collect(),
+            mask: None,
+        };
+        let step = bank.insert("STEP", step).unwrap();
+        let junk = Texture {
+            name: "JUNK".into(),
+            w: 64,
+            h: 65,
+            pixels: vec![255; 64 * 65],
+            mask: None,
+        };
+        let junk = bank.insert("JUNK", junk).unwrap();
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        for sd in &mut level.sidedefs {
+            sd.middle = step;
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (junk, junk);
+        }
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(8.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        // the texture columns down the middle of the screen
+        let columns = |tutti_frutti| {
+            let mut sw = Software {
+                light_mode: LightMode::Fullbright,
+                compat: CompatOptions {
+                    emulate_tutti_frutti: tutti_frutti,
+                },
+                ..Default::default()
+            };
+            sw.begin_frame(160, 100);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+            let mut us: Vec<u32> = (0..100)
+                .map(|y| sw.scratch[y * 160 + 80])
+                .filter(|&px| px < 200 * 0x01_01_01)
+                .map(|px| px & 0xFF)
+                .map(|px| {
+                    assert!(px & 127 < 72, "row {} drawn", px & 127);
+                    px >> 7
+                })
+                .collect();
+            assert!(sw.scratch.contains(&0xFF_FF_FF), "no flats drawn");
+            us.dedup();
+            us.len()
+        };
+        assert_eq!(columns(false), 1);
+        assert!(columns(true) > 1);
+    }
+
+    #[test]
+    fn colours_blend_per_channel() {
+        use super::lerp_rgb;
+        let (a, b) = (0x00_10_80_F0, 0x00_30_00_10);
+        assert_eq!(lerp_rgb(a, b, 0), a);
+        assert_eq!(lerp_rgb(a, b, 128), 0x00_20_40_80);
+        assert_eq!(lerp_rgb(a, b, 256), b);
+        assert_eq!(lerp_rgb(0xFF_FFFF, 0xFF_FFFF, 200), 0xFF_FFFF);
+    }
+
+    #[test]
+    fn minimised_and_tiny_windows_draw_then_recover() {
+        let bank = TextureBank::default_with_checker();
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(32.0, 40.0, 41.0), 0.3, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let wad = crate::wad::Wad::from_lumps(&[("FLOOR7_2", vec![1; 4096])]);
+        let border = ViewBorder::new(&wad);
+        let frame = |sw: &mut Software, w, h| {
+            sw.begin_view(w, h, ViewWindow::for_screenblocks(10, w, h));
+            sw.draw_border(&border);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+            sw.fill_rect(0, 0, 2, 2, 0xAB);
+            let mut out = None;
+            sw.end_frame(|fb, fw, fh| out = Some((fb.to_vec(), fw, fh)));
+            out.unwrap()
+        };
+        let want = frame(&mut Software::default(), 640, 400);
+
+        for scale in [1.0, 0.5] {
+            let mut sw = Software::default();
+            sw.set_render_scale(scale);
+            let (fb, w, h) = frame(&mut sw, 0, 0);
+            assert!(
+                fb.is_empty() && (w, h) == (0, 0),
+                "a minimised window gets nothing"
+            );
+            assert!(sw.stats().subsectors == 0);
+            for (w, h) in [(1, 1), (3, 7), (0, 5), (2, 0)] {
+                let (fb, fw, fh) = frame(&mut sw, w, h);
+                assert_eq!(fb.len(), fw * fh, "{w}×{h} at {scale}");
+            }
+            if scale == 1.0 {
+                assert_eq!(frame(&mut sw, 640, 400), want);
+            } else {
+                let (fb, w, h) = frame(&mut sw, 640, 400);
+                assert_eq!((fb.len(), w, h), (640 * 400, 640, 400));
+                assert_eq!(fb[0], 0xAB);
+            }
+        }
+    }
+
+    /// Every pixel through a window into a sector with a lower ceiling and
+    /// a higher floor is drawn by something: the bands stop on the rows the
+    /// upper and lower walls end on, not a row past them.
+    #[test]
+    fn portal_edges_leave_no_unpainted_rows() {
+        let mut bank = TextureBank::default_with_checker();
+        bank.build_shade_table();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        for (floor, ceil) in [(24.0, 96.0), (-24.0, 160.0), (24.0, 128.0)] {
+            let mut level = testmap::three_rooms(testmap::Middle::Open { floor, ceil });
+            for sd in &mut level.sidedefs {
+                (sd.upper, sd.lower, sd.middle) = (wall, wall, wall);
+            }
+            for s in &mut level.sectors {
+                (s.floor_tex, s.ceil_tex) = (wall, wall);
+            }
+            let sim = TicRunner::new(&level);
+            for yaw in [-15.0_f32, 0.0, 20.0] {
+                let camera = Camera::new(
+                    Vec3::new(16.0, 60.0, 41.0),
+                    yaw.to_radians(),
+                    90_f32.to_radians(),
+                );
+                let mut active = Vec::new();
+                level.fill_active_subsectors(&camera, &mut active);
+                let mut sw = Software::default();
+                sw.begin_frame(320, 200);
+                // no colour the (all black) shade table can make
+                sw.scratch.fill(0xDEAD);
+                sw.draw_level(&active, &level, &sim, &camera, &bank);
+                let holes = sw.scratch.iter().filter(|&&p| p == 0xDEAD).count();
+                assert_eq!(holes, 0, "middle {floor}..{ceil}, {yaw}°");
+            }
+        }
+    }
 }
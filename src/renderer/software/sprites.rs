@@ -1,34 +1,96 @@
 use bitflags::bitflags;
 use std::ops::Range;
 
+use glam::Vec2;
+
 use crate::{
     defs::flags::MobjFlags as MF,
+    renderer::{DrawCmd, DrawFlags, Rgba},
     sim,
-    world::{Camera, Level, NO_TEXTURE, SegmentId, SubsectorId, TextureBank, TextureId},
+    world::{
+        self, Angle, Camera, Level, NO_TEXTURE, SegmentId, SubsectorId, TRANSPARENT_TEXTURE,
+        TextureBank, TextureId,
+    },
+};
+
+use super::{
+    Software,
+    arena::FrameArena,
+    projection::{Edge, to_screen},
 };
 
-use super::{Software, projection::Edge};
+/// Drawsegs' clip rows and masked columns (vanilla `openings`).
+pub type FrameScratch = FrameArena<i16>;
 
+/// Screen columns per [`DrawSegBins`] strip.
+const DS_BIN: usize = 32;
+
+/// Drawseg indices by screen strip, filled as drawsegs are pushed, so a
+/// sprite only looks at the walls sharing its columns.
 #[derive(Default)]
-pub struct FrameScratch {
-    openings: Vec<i16>,
-    cursor: usize,
+pub struct DrawSegBins {
+    /// As many as the widest view needed, so a narrower one in between
+    /// doesn't throw their buffers away.
+    bins: Vec<Vec<u32>>,
+    /// Strips in this view.
+    used: usize,
 }
-impl FrameScratch {
-    /// Allocate `len` consecutive i16 slots inside `openings`
-    /// and return the index range that was handed out.
-    pub fn alloc(&mut self, len: usize) -> Range<usize> {
-        let start = self.cursor;
-        self.cursor += len;
-
-        if self.cursor > self.openings.len() {
-            self.openings.resize(self.cursor.next_power_of_two(), 0);
+
+impl DrawSegBins {
+    pub fn reset(&mut self, width: usize) {
+        self.used = width.div_ceil(DS_BIN).max(1);
+        if self.bins.len() < self.used {
+            self.bins.resize_with(self.used, Vec::new);
         }
-        start..start + len
+        for bin in &mut self.bins[..self.used] {
+            bin.clear();
+        }
+    }
+
+    fn strips(&self, x1: i32, x2: i32) -> Range<usize> {
+        let last = self.used - 1;
+        let b1 = (x1.max(0) as usize / DS_BIN).min(last);
+        let b2 = (x2.max(0) as usize / DS_BIN).min(last);
+        b1..b2 + 1
     }
 
-    pub fn reset(&mut self) {
-        self.cursor = 0;
+    pub fn insert(&mut self, ds_idx: usize, x1: i32, x2: i32) {
+        for b in self.strips(x1, x2) {
+            self.bins[b].push(ds_idx as u32);
+        }
+    }
+
+    /// Drawsegs in any strip of columns `x1..=x2`, last pushed first.
+    /// Strips are coarse: callers still check the exact range.
+    pub fn overlapping(&self, x1: i32, x2: i32, out: &mut Vec<u32>) {
+        out.clear();
+        let strips = self.strips(x1, x2);
+        let many = strips.len() > 1;
+        for b in strips {
+            out.extend_from_slice(&self.bins[b]);
+        }
+        if many {
+            out.sort_unstable();
+            out.dedup();
+        }
+        out.reverse();
+    }
+}
+
+/// One sprite's clip rows over its on-screen columns (vanilla
+/// `clipbot` / `cliptop`), plus the drawseg list they came from.
+#[derive(Default)]
+pub struct SpriteClip {
+    top: Vec<i32>,
+    bottom: Vec<i32>,
+    segs: Vec<u32>,
+}
+
+impl SpriteClip {
+    pub fn clear(&mut self) {
+        self.top.clear();
+        self.bottom.clear();
+        self.segs.clear();
     }
 }
 
@@ -52,8 +114,8 @@ pub struct DrawSeg {
 
     pub masked_mid: TextureId,
     pub masked_mid_w: i32,
-    pub z_top: f32, // front sector ceiling world-Z
-    pub z_bot: f32, // front sector floor   world-Z
+    pub z_top: f32, // masked mid's top    world-Z
+    pub z_bot: f32, // masked mid's bottom world-Z
 
     // per-column *flag* slice:
     //   >=0  – u already filled by wall loop
@@ -87,6 +149,56 @@ pub struct VisSprite {
     pub tex: TextureId,
     pub u_step: f32, // how far to advance U per screen pixel X
     pub flip: bool,
+    /// Owner's flags; `SHADOW` picks the fuzz drawer.
+    pub flags: MF,
+}
+
+/// How far past the frame a sprite's edges are kept.  Its texture is
+/// mapped from the unclipped edges, so they can't simply be cut to the
+/// frame; this is far enough out that nothing visible maps differently
+/// and near enough that widths and heights taken from them can't overflow.
+const OFFSCREEN: i32 = 1 << 16;
+
+/// Closest a sprite's centre may be and still be drawn (vanilla `MINZ`);
+/// nearer, its projection blows up past anything worth drawing.
+const MIN_Z: f32 = 4.0;
+
+/// Vanilla `R_ProjectSprite`'s rotation: 1 when a thing at `thing`
+/// facing `angle` looks straight at a viewer at `view`, counting up
+/// anticlockwise around it to 8.
+pub fn sprite_rotation(view: Vec2, thing: Vec2, angle: Angle) -> u8 {
+    rotation(world::angle_to(view, thing) - angle)
+}
+
+/// The rotation seen along `rel`, the viewer → thing direction relative
+/// to the way the thing faces.  Each of the eight takes the 45° centred
+/// on it, the lower edge included, as vanilla's `+ ANG45/2*9` has it.
+fn rotation(rel: Angle) -> u8 {
+    ((rel + Angle(Angle::ANG45.0 / 2 * 9)).0 >> 29) as u8 + 1
+}
+
+/// Vanilla `fuzzoffset`: which neighbour row (above or below) each fuzz
+/// pixel copies.  Cycled through across columns and frames.
+const FUZZ_TABLE: [i8; 50] = [
+    1, -1, 1, -1, 1, 1, -1, 1, 1, -1, 1, 1, 1, -1, 1, 1, 1, -1, -1, -1, -1, 1, -1, -1, 1, 1, 1, 1,
+    -1, 1, -1, 1, 1, -1, -1, 1, 1, -1, -1, -1, -1, 1, 1, 1, 1, -1, 1, 1, -1, 1,
+];
+
+/// Colormap 6 as a brightness, in 32nds.  `scratch` already holds RGB,
+/// so the fuzz darkens channels instead of remapping palette indices.
+const FUZZ_LIGHT: u32 = 32 - 6;
+
+#[inline]
+fn fuzz_darken(c: Rgba) -> Rgba {
+    let ch = |shift: u32| ((c >> shift & 0xFF) * FUZZ_LIGHT / 32) << shift;
+    (c & 0xFF00_0000) | ch(16) | ch(8) | ch(0)
+}
+
+/// 50% blend of `dst` and `src`.
+#[cfg(feature = "translucency")]
+#[inline]
+fn blend_half(dst: Rgba, src: Rgba) -> Rgba {
+    (dst >> 1 & 0x7F7F_7F7F) + (src >> 1 & 0x7F7F_7F7F)
 }
 
 impl Software {
@@ -104,7 +216,7 @@ impl Software {
         let scale_step = (scale2 - scale1) / ((edge.x_r - edge.x_l) as f32);
         let count = (edge.x_r - edge.x_l + 1) as usize;
 
-        let masked_mid_w = if masked_mid != NO_TEXTURE {
+        let masked_mid_w = if masked_mid != TRANSPARENT_TEXTURE {
             texture_bank.texture(masked_mid).unwrap().w as i32
         } else {
             0
@@ -124,33 +236,48 @@ impl Software {
             masked_mid_w,
             z_top,
             z_bot,
-            masked_cols: self.frame_scratch.alloc(count),
-            top_clip: self.frame_scratch.alloc(count),
-            bot_clip: self.frame_scratch.alloc(count),
+            masked_cols: self.frame.frame_scratch.alloc(count),
+            top_clip: self.frame.frame_scratch.alloc(count),
+            bot_clip: self.frame.frame_scratch.alloc(count),
         }
     }
 
+    /// Keep `ds` for sprite clipping and masked mids.
+    pub fn push_draw_seg(&mut self, ds: DrawSeg) {
+        self.frame
+            .ds_bins
+            .insert(self.frame.drawsegs.len(), ds.x1, ds.x2);
+        self.frame.drawsegs.push(ds);
+    }
+
     pub fn store_wall_range(&mut self, ds: &mut DrawSeg, col: usize, uoz_invz: i32) {
         let idx = col - ds.x1 as usize;
 
         debug_assert!(idx < ds.masked_cols.len());
 
-        if ds.silhouette.contains(Silhouette::TOP) {
-            self.frame_scratch.openings[ds.top_clip.start + idx] = self.clip_bands.ceil[col];
+        // a masked mid is clipped to the opening whatever the silhouette
+        let masked = ds.masked_mid != TRANSPARENT_TEXTURE;
+        if masked || ds.silhouette.contains(Silhouette::TOP) {
+            self.frame.frame_scratch[ds.top_clip.start + idx] =
+                self.frame.clip_bands.ceil[col] as i16;
         }
 
-        if ds.silhouette.contains(Silhouette::BOTTOM) {
-            self.frame_scratch.openings[ds.bot_clip.start + idx] = self.clip_bands.floor[col];
+        if masked || ds.silhouette.contains(Silhouette::BOTTOM) {
+            self.frame.frame_scratch[ds.bot_clip.start + idx] =
+                self.frame.clip_bands.floor[col] as i16;
         }
 
-        if ds.masked_mid != NO_TEXTURE {
-            self.frame_scratch.openings[ds.masked_cols.start + idx] =
+        if masked {
+            self.frame.frame_scratch[ds.masked_cols.start + idx] =
                 uoz_invz.rem_euclid(ds.masked_mid_w) as i16;
         }
     }
 
+    /// Project the things linked into BSP leaf `ss_idx` (vanilla
+    /// `R_AddSprites`).
     pub fn collect_sprites_for_subsector(
         &mut self,
+        level: &Level,
         ss_idx: SubsectorId,
         sim: &sim::TicRunner,
         camera: &Camera,
@@ -159,37 +286,47 @@ impl Software {
         let focal = camera.screen_scale(self.width);
         let half_w = self.half_w;
         let half_h = self.half_h;
+        let alpha = sim.frame_alpha();
 
-        for (_, (pos, anim, angle, class, ssec)) in sim
-            .world()
-            .query::<(
+        for &ent in sim.things_in_subsector(ss_idx) {
+            let Ok(mut q) = sim.world().query_one::<(
                 &sim::Position,
+                Option<&sim::PrevPosition>,
                 &sim::Animation,
                 &sim::Angle,
                 &sim::Class,
-                &sim::Subsector,
-            )>()
-            .iter()
-        {
-            // Keep only those in the requested BSP leaf
-            if ssec.0 != ss_idx {
+                Option<&sim::ActorFlags>,
+            )>(ent) else {
                 continue;
-            }
-
-            let frame = (b'A' + anim.state.frame()) as char;
+            };
+            let Some((pos, prev, anim, angle, class, flags)) = q.get() else {
+                continue;
+            };
+            // whatever moved it should have relinked it
+            debug_assert_eq!(
+                level.locate_subsector(pos.0),
+                ss_idx,
+                "{} at {:?} linked into the wrong subsector",
+                class.0.id,
+                pos.0
+            );
+
+            // blend towards this tic's spot when drawing between tics
+            let (pos, angle) = match prev {
+                Some(prev) => sim::interp::lerp(prev, pos, angle, alpha),
+                None => (*pos, *angle),
+            };
 
-            let dx = camera.pos.x - pos.0.x;
-            let dy = camera.pos.y - pos.0.y;
-            let dir_to_view = dy.atan2(dx); // world angle sprite→camera
-            let rel_angle = (dir_to_view - angle.0).to_degrees().rem_euclid(360.0);
+            let st = sim.definitions().state(anim.state);
+            let frame = (b'A' + st.frame) as char;
 
-            let rot = if class.0.flags.contains(MF::NOBLOOD) {
-                0u8 // billboard
+            let rot = if tex_bank.sprite_rotates(st.sprite, frame) {
+                sprite_rotation(camera.pos.truncate(), pos.0, angle)
             } else {
-                (((rel_angle + 22.5) / 45.0) as u8 & 7) + 1 // 1‥8
+                0 // billboard
             };
 
-            let (tex_id, flip) = tex_bank.sprite_id(anim.state.sprite(), frame, rot);
+            let (tex_id, flip) = tex_bank.sprite_id(st.sprite, frame, rot);
 
             if tex_id == NO_TEXTURE {
                 continue;
@@ -197,9 +334,8 @@ impl Software {
 
             // camera space -------------------------------------------------
             let rel = camera.to_cam(&pos.0); // z=0 floor aligned
-            if rel.y <= 4.0 {
-                // “behind” or too close to near-plane
-                continue;
+            if rel.y < camera.near().max(MIN_Z) {
+                continue; // centre behind the near plane, or right at the eye
             }
             let invz = 1.0 / rel.y;
             let scale = focal * invz;
@@ -209,10 +345,11 @@ impl Software {
             let sprite_h = tex.h as f32 * scale;
 
             let xc = half_w + rel.x * scale;
-            let x0 = (xc - sprite_w * 0.5).floor() as i32;
-            let x1 = (xc + sprite_w * 0.5).ceil() as i32;
+            let (w, h) = (self.width as i32, self.height as i32);
+            let x0 = to_screen((xc - sprite_w * 0.5).floor(), -OFFSCREEN, w + OFFSCREEN);
+            let x1 = to_screen((xc + sprite_w * 0.5).ceil(), -OFFSCREEN, w + OFFSCREEN);
 
-            if x1 < 0 || x0 >= self.width as i32 {
+            if x1 < 0 || x0 >= w {
                 continue; // completely off-screen
             }
 
@@ -221,10 +358,12 @@ impl Software {
 
             let y_bottom = half_h - rel_z * scale;
 
-            let y0 = (y_bottom - sprite_h).floor() as i32; // top
-            let y1 = (y_bottom).ceil() as i32; // bottom (touching floor)
+            let y0 = to_screen((y_bottom - sprite_h).floor(), -OFFSCREEN, h + OFFSCREEN); // top
+            let y1 = to_screen(y_bottom.ceil(), -OFFSCREEN, h + OFFSCREEN); // bottom (touching floor)
+            debug_assert!(x0 <= x1 && y0 <= y1, "sprite at {x0},{y0}..{x1},{y1}");
 
-            self.sprites.push(VisSprite {
+            self.stats.sprites_projected += 1;
+            self.frame.sprites.push(VisSprite {
                 x0,
                 x1,
                 y0,
@@ -235,6 +374,7 @@ impl Software {
                 tex: tex_id,
                 u_step: tex.w as f32 / (x1 - x0 + 1) as f32,
                 flip,
+                flags: flags.map_or(class.0.flags, |f| f.0),
             });
         }
     }
@@ -242,21 +382,27 @@ impl Software {
     pub fn draw_sprites(&mut self, level: &Level, tex: &TextureBank) {
         let focal = self.focal;
         let h_scr = self.height as i32;
+        let shades = self.shades(tex);
 
-        self.sprites.sort_unstable_by(|a, b| {
+        self.frame.sprites.sort_unstable_by(|a, b| {
             a.invz
                 .partial_cmp(&b.invz) // smaller invz == farther
                 .unwrap()
         });
 
-        for i in 0..self.sprites.len() {
-            let vis = self.sprites[i]; // copy: no borrow lives
+        for i in 0..self.frame.sprites.len() {
+            let vis = self.frame.sprites[i]; // copy: no borrow lives
             let tex_spr = tex.texture(vis.tex).unwrap();
             let spr_scale = focal * vis.invz;
+            let shade = self.light_mode.shade(0, 1.0 / vis.invz);
 
-            let mut x = vis.x0.max(0);
+            let x_start = vis.x0.max(0);
             let x_end = vis.x1.min(self.width as i32 - 1);
-            let x_clip_left = x - vis.x0; // how many columns we skipped
+            if x_start > x_end {
+                continue;
+            }
+            let x_clip_left = x_start - vis.x0; // how many columns we skipped
+            self.clip_sprite(level, spr_scale, &vis, x_start, x_end, tex);
 
             let mut u_step = vis.u_step;
             let mut u_acc = x_clip_left as f32 * u_step;
@@ -265,9 +411,15 @@ impl Software {
                 u_step = -u_step; // march leftward
                 u_acc = (tex_spr.w as f32 - 1.0) - u_acc;
             }
+            let mut drawn = false;
 
+            let mut x = x_start;
             while x <= x_end {
-                let (ceil, floor) = self.column_clips(level, spr_scale, &vis, x, tex);
+                let c = (x - x_start) as usize;
+                let (ceil, floor) = (
+                    self.frame.sprite_clip.top[c],
+                    self.frame.sprite_clip.bottom[c],
+                );
 
                 if ceil >= floor {
                     u_acc += u_step;
@@ -286,96 +438,142 @@ impl Software {
 
                 let v_step = tex_spr.h as f32 / (vis.y1 - vis.y0 + 1) as f32;
                 let mut v_acc = (y0 - vis.y0) as f32 * v_step;
+                drawn |= y0 <= y1;
+
+                if let Some(list) = &mut self.record {
+                    if y0 <= y1 {
+                        list.push(DrawCmd {
+                            flags: DrawFlags::MASKED | DrawFlags::CLAMP_V,
+                            ..DrawCmd::column(
+                                x as usize,
+                                y0..=y1,
+                                vis.tex,
+                                shade,
+                                Vec2::new(u as f32, v_acc),
+                                v_step,
+                            )
+                        });
+                    }
+                    u_acc += u_step;
+                    x += 1;
+                    continue;
+                }
 
-                for y in y0..=y1 {
-                    let v = (v_acc as usize).min(tex_spr.h - 1);
-                    let idx = tex_spr.pixels[v * tex_spr.w + u];
-                    if idx != 0 {
-                        self.scratch[y as usize * self.width + x as usize] = tex.get_color(0, idx);
+                if vis.flags.contains(MF::SHADOW) {
+                    // fuzz each opaque run (vanilla's posts)
+                    let mut run = None;
+                    for y in y0..=y1 + 1 {
+                        let v = (v_acc as usize).min(tex_spr.h - 1);
+                        let opaque = y <= y1 && tex_spr.is_opaque(v * tex_spr.w + u);
+                        match (run, opaque) {
+                            (None, true) => run = Some(y),
+                            (Some(top), false) => {
+                                self.draw_fuzz_column(x as usize, top, y - 1);
+                                run = None;
+                            }
+                            _ => {}
+                        }
+                        v_acc += v_step;
+                    }
+                } else {
+                    for y in y0..=y1 {
+                        let v = (v_acc as usize).min(tex_spr.h - 1);
+                        let texel = v * tex_spr.w + u;
+                        if tex_spr.is_opaque(texel) {
+                            self.put_pixel(
+                                x as usize,
+                                y as usize,
+                                shades.get(shade, tex_spr.pixels[texel]),
+                            );
+                        }
+                        v_acc += v_step;
                     }
-                    v_acc += v_step;
                 }
 
                 u_acc += u_step;
                 x += 1;
             }
+            self.stats.sprites_drawn += drawn as u32;
         }
 
         // second pass: any masked mids not yet drawn
-        for ds_idx in (0..self.drawsegs.len()).rev() {
-            if self.drawsegs[ds_idx].masked_mid != NO_TEXTURE {
-                let ds = &self.drawsegs[ds_idx];
+        for ds_idx in (0..self.frame.drawsegs.len()).rev() {
+            if self.frame.drawsegs[ds_idx].masked_mid != TRANSPARENT_TEXTURE {
+                let ds = &self.frame.drawsegs[ds_idx];
                 self.render_masked_seg_range(ds_idx, ds.x1, ds.x2, tex);
             }
         }
     }
 
-    fn column_clips(
+    /// Fill `sprite_clip` for columns `x0..=x1` of `vis` (vanilla
+    /// `R_DrawSprite`).  Each drawseg sharing those columns is classified
+    /// once: masked mids behind the sprite are drawn now, silhouettes in
+    /// front of it narrow the clip rows.
+    fn clip_sprite(
         &mut self,
         level: &Level,
         spr_scale: f32,
         vis: &VisSprite,
-        x: i32,
+        x0: i32,
+        x1: i32,
         tex: &TextureBank,
-    ) -> (i32, i32) {
-        let mut ceil = -1;
-        let mut floor = self.height as i32;
-
-        for ds_idx in (0..self.drawsegs.len()).rev() {
-            let (behind, masked, t_idx, b_idx) = {
-                let ds = &self.drawsegs[ds_idx];
-                if x < ds.x1 || x > ds.x2 {
-                    continue;
-                }
-
-                let max = ds.scale1.max(ds.scale2);
-                let min = ds.scale1.min(ds.scale2);
-                let back = if max < spr_scale {
-                    true
-                } else if min < spr_scale {
-                    Self::point_on_seg_backside(level, vis.gx, vis.gy, ds.cur_line)
-                } else {
-                    false
-                };
-
-                (
-                    back,
-                    ds.masked_mid != NO_TEXTURE,
-                    ds.silhouette
-                        .contains(Silhouette::TOP)
-                        .then(|| ds.top_clip.start + (x - ds.x1) as usize),
-                    ds.silhouette
-                        .contains(Silhouette::BOTTOM)
-                        .then(|| ds.bot_clip.start + (x - ds.x1) as usize),
-                )
-            }; // borrow ends here
+    ) {
+        let n = (x1 - x0 + 1) as usize;
+        let mut clip = std::mem::take(&mut self.frame.sprite_clip);
+        clip.top.clear();
+        clip.top.resize(n, -1);
+        clip.bottom.clear();
+        clip.bottom.resize(n, self.height as i32);
+        self.frame.ds_bins.overlapping(x0, x1, &mut clip.segs);
+
+        for &ds_idx in &clip.segs {
+            let ds = &self.frame.drawsegs[ds_idx as usize];
+            let masked = ds.masked_mid != TRANSPARENT_TEXTURE;
+            if ds.x1 > x1 || ds.x2 < x0 || (ds.silhouette.is_empty() && !masked) {
+                continue;
+            }
+            self.stats.sprite_clip_segs += 1;
+            let (r1, r2) = (ds.x1.max(x0), ds.x2.min(x1));
+
+            let max = ds.scale1.max(ds.scale2);
+            let min = ds.scale1.min(ds.scale2);
+            let behind = if max < spr_scale {
+                true
+            } else if min < spr_scale {
+                Self::point_on_seg_backside(level, vis.gx, vis.gy, ds.cur_line)
+            } else {
+                false
+            };
 
             if behind {
                 if masked {
-                    self.render_masked_seg_range(ds_idx, x, x, tex);
+                    self.render_masked_seg_range(ds_idx as usize, r1, r2, tex);
                 }
                 continue;
             }
 
-            if let Some(i) = t_idx {
-                ceil = ceil.max(self.frame_scratch.openings[i] as i32);
-            }
-            if let Some(i) = b_idx {
-                floor = floor.min(self.frame_scratch.openings[i] as i32);
-            }
-
-            if ceil >= floor {
-                break;
+            let openings = &*self.frame.frame_scratch;
+            for x in r1..=r2 {
+                let (c, col) = ((x - x0) as usize, (x - ds.x1) as usize);
+                if ds.silhouette.contains(Silhouette::TOP) {
+                    clip.top[c] = clip.top[c].max(openings[ds.top_clip.start + col] as i32);
+                }
+                if ds.silhouette.contains(Silhouette::BOTTOM) {
+                    clip.bottom[c] = clip.bottom[c].min(openings[ds.bot_clip.start + col] as i32);
+                }
             }
         }
-
-        (ceil, floor)
+        self.frame.sprite_clip = clip;
     }
 
     fn render_masked_seg_range(&mut self, ds_idx: usize, x0: i32, x1: i32, tex_bank: &TextureBank) {
-        let ds = &self.drawsegs[ds_idx];
-        let openings = &mut self.frame_scratch.openings;
+        let shades = self.shades(tex_bank);
+        let (light_mode, focal) = (self.light_mode, self.focal);
+        let ds = &self.frame.drawsegs[ds_idx];
+        let openings = &mut *self.frame.frame_scratch;
         let tex_mid = tex_bank.texture(ds.masked_mid).unwrap();
+        #[cfg(feature = "translucency")]
+        let translucent = self.translucent_mids;
 
         // ------------------------------------------------------------------
         // vertical stepping
@@ -394,34 +592,53 @@ impl Software {
 
             // integer texel column
             let u = *entry as usize; // 0 … tex_mid.w-1
+            let shade = light_mode.shade(0, focal / scale);
 
             // ------- project vertical extents --------------------------------
-            let y_top = (self.half_h - (ds.z_top - self.view_z) * scale).floor() as i32;
-            let y_bot = (self.half_h - (ds.z_bot - self.view_z) * scale).ceil() as i32;
-
-            let mut y0 = y_top.max(0);
-            let mut y1 = y_bot.min(self.height as i32 - 1);
+            // the texture once, top to bottom: the column ends where its
+            // last post does, it doesn't repeat
+            let y_top = (self.half_h - (ds.z_top - self.view_z) * scale).ceil() as i32;
+            let y_bot = (self.half_h - (ds.z_bot - self.view_z) * scale).ceil() as i32 - 1;
 
-            if ds.silhouette.contains(Silhouette::TOP) {
-                y0 = y0.max(ds_top_clip);
-            }
-            if ds.silhouette.contains(Silhouette::BOTTOM) {
-                y1 = y1.min(ds_bot_clip);
-            }
+            let y0 = y_top.max(0).max(ds_top_clip);
+            let y1 = y_bot.min(self.height as i32 - 1).min(ds_bot_clip);
 
             // ------- draw the column ----------------------------------------
             if y0 <= y1 {
-                let v_step = tex_mid.h as f32 / (y_bot - y_top + 1) as f32;
-                let mut v_f = (y0 - y_top) as f32 * v_step;
-
-                for y in y0..=y1 {
-                    let v = (v_f as usize).min(tex_mid.h - 1);
-                    let idx = tex_mid.pixels[v * tex_mid.w + u];
-                    if idx != 0 {
-                        self.scratch[y as usize * self.width + x as usize] =
-                            tex_bank.get_color(0, idx);
+                self.stats.masked_columns += 1;
+                let v_step = 1.0 / scale;
+                let mut v_f = (ds.z_top - self.view_z) + (y0 as f32 - self.half_h) * v_step;
+
+                if let Some(list) = &mut self.record {
+                    list.push(DrawCmd {
+                        flags: DrawFlags::MASKED | DrawFlags::CLAMP_V,
+                        ..DrawCmd::column(
+                            x as usize,
+                            y0..=y1,
+                            ds.masked_mid,
+                            shade,
+                            Vec2::new(u as f32, v_f),
+                            v_step,
+                        )
+                    });
+                } else {
+                    for y in y0..=y1 {
+                        let v = (v_f as usize).min(tex_mid.h - 1);
+                        let texel = v * tex_mid.w + u;
+                        if tex_mid.is_opaque(texel) {
+                            // `pixel_index`, field by field: `openings` holds
+                            // `frame_scratch`
+                            let i = (self.view.y + y as usize) * self.frame_w
+                                + self.view.x
+                                + x as usize;
+                            let dst = &mut self.scratch[i];
+                            let c = shades.get(shade, tex_mid.pixels[texel]);
+                            #[cfg(feature = "translucency")]
+                            let c = if translucent { blend_half(*dst, c) } else { c };
+                            *dst = c;
+                        }
+                        v_f += v_step;
                     }
-                    v_f += v_step;
                 }
             }
 
@@ -430,6 +647,21 @@ impl Software {
         }
     }
 
+    /// Vanilla `R_DrawFuzzColumn`: darken rows `y0..=y1` of column `x`
+    /// with copies of the pixel just above or below, per `FUZZ_TABLE`.
+    /// The first and last screen rows are left alone so the neighbour
+    /// read never leaves the frame.
+    pub fn draw_fuzz_column(&mut self, x: usize, y0: i32, y1: i32) {
+        let y0 = y0.max(1);
+        let y1 = y1.min(self.height as i32 - 2);
+        for y in y0..=y1 {
+            let off = FUZZ_TABLE[self.fuzz_pos] as i32;
+            let src = self.pixel_index(x, (y + off) as usize);
+            self.put_pixel(x, y as usize, fuzz_darken(self.scratch[src]));
+            self.fuzz_pos = (self.fuzz_pos + 1) % FUZZ_TABLE.len();
+        }
+    }
+
     fn point_on_seg_backside(level: &Level, px: f32, py: f32, seg_id: SegmentId) -> bool {
         let seg = &level.segs[seg_id as usize];
         let v1 = &level.vertices[seg.v1 as usize].pos;
@@ -445,3 +677,356 @@ impl Software {
         (dy * dx1 - dx * dy1) > 0.0 // true  == sprite is on back side
     }
 }
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::Renderer;
+    use crate::world::{TexMask, Texture, testmap};
+    use glam::Vec3;
+
+    /// `w`×`h` texture of index `c` with every other column see-through.
+    fn comb(name: &str, w: usize, h: usize, c: u8) -> Texture {
+        let mut mask = TexMask::new(w * h);
+        for i in (0..w * h).filter(|i| (i % w).is_multiple_of(2)) {
+            mask.set(i);
+        }
+        Texture {
+            name: name.into(),
+            w,
+            h,
+            pixels: vec![c; w * h],
+            mask: Some(mask),
+        }
+    }
+
+    /// A crowd of cacodemons over all three rooms, seen from room B
+    /// through a masked mid-texture on the far separator.
+    fn caco_crowd(count: usize) -> (Level, sim::TicRunner, TextureBank, Camera) {
+        let mut bank = TextureBank::default_with_checker();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        let grate = bank.insert("GRATE", comb("GRATE", 16, 16, 3)).unwrap();
+        let head = bank.insert("HEADA0", comb("HEADA0", 62, 56, 5)).unwrap();
+        bank.register_sprite_lump("HEADA0", head);
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 16.0,
+            ceil: 112.0,
+        });
+        for (i, sd) in level.sidedefs.iter_mut().enumerate() {
+            let mid = if i == 1 { grate } else { TRANSPARENT_TEXTURE };
+            (sd.upper, sd.lower, sd.middle) = (wall, wall, mid);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (wall, wall);
+        }
+
+        let mut sim = sim::TicRunner::new(&level);
+        let info = crate::defs::by_id("HEAD").unwrap();
+        for i in 0..count {
+            let pos = Vec2::new(
+                24.0 + (i % 20) as f32 * 11.5,
+                8.0 + (i / 20 % 10) as f32 * 12.0,
+            );
+            let ss = level.locate_subsector(pos);
+            sim.spawn_mobj(&level, info, pos.x, pos.y, 0.0, ss);
+        }
+        let camera = Camera::new(
+            Vec3::new(268.0, 64.0, 41.0),
+            std::f32::consts::PI,
+            90_f32.to_radians(),
+        );
+        (level, sim, bank, camera)
+    }
+
+    /// The clip rows the old renderer built column by column: every
+    /// drawseg under `x`, side-tested at every column.
+    fn column_clips(sw: &Software, level: &Level, vis: &VisSprite, x: i32) -> (i32, i32, u32) {
+        let spr_scale = sw.focal * vis.invz;
+        let (mut ceil, mut floor, mut tests) = (-1, sw.height as i32, 0);
+        for ds in sw.frame.drawsegs.iter().rev() {
+            if x < ds.x1 || x > ds.x2 {
+                continue;
+            }
+            tests += 1;
+            let behind = if ds.scale1.max(ds.scale2) < spr_scale {
+                true
+            } else if ds.scale1.min(ds.scale2) < spr_scale {
+                Software::point_on_seg_backside(level, vis.gx, vis.gy, ds.cur_line)
+            } else {
+                false
+            };
+            if behind {
+                continue;
+            }
+            let col = (x - ds.x1) as usize;
+            let openings = &*sw.frame.frame_scratch;
+            if ds.silhouette.contains(Silhouette::TOP) {
+                ceil = ceil.max(openings[ds.top_clip.start + col] as i32);
+            }
+            if ds.silhouette.contains(Silhouette::BOTTOM) {
+                floor = floor.min(openings[ds.bot_clip.start + col] as i32);
+            }
+        }
+        (ceil, floor, tests)
+    }
+
+    #[test]
+    fn crowd_clips_once_per_drawseg() {
+        let (level, sim, bank, camera) = caco_crowd(200);
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let mut sw = Software {
+            record: Some(Vec::new()),
+            ..Default::default()
+        };
+        sw.begin_frame(320, 200);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+        assert_eq!(sw.stats().sprites_drawn, 200);
+        assert!(sw.stats().masked_columns > 0);
+
+        // same rows as the per-column scan, for a fraction of the work
+        let sprites = sw.frame.sprites.clone();
+        let (mut checks, mut column_checks) = (0, 0);
+        for vis in &sprites {
+            let (x0, x1) = (vis.x0.max(0), vis.x1.min(sw.width as i32 - 1));
+            let before = sw.stats.sprite_clip_segs;
+            sw.clip_sprite(&level, sw.focal * vis.invz, vis, x0, x1, &bank);
+            checks += sw.stats.sprite_clip_segs - before;
+
+            for x in x0..=x1 {
+                let (ceil, floor, tests) = column_clips(&sw, &level, vis, x);
+                let c = (x - x0) as usize;
+                assert_eq!(
+                    (sw.frame.sprite_clip.top[c], sw.frame.sprite_clip.bottom[c]),
+                    (ceil, floor),
+                    "column {x}"
+                );
+                column_checks += tests;
+            }
+        }
+        assert!(checks <= sprites.len() as u32 * sw.frame.drawsegs.len() as u32);
+        assert!(checks * 10 < column_checks, "{checks} vs {column_checks}");
+    }
+
+    #[test]
+    fn teleported_thing_shows_up_where_it_landed() {
+        let (level, mut sim, bank, camera) = caco_crowd(1);
+        let caco = sim.things_in_subsector(level.locate_subsector(Vec2::new(24.0, 8.0)))[0];
+        let render = |sim: &sim::TicRunner| {
+            let mut active = Vec::new();
+            level.fill_active_subsectors(&camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(320, 200);
+            sw.draw_level(&active, &level, sim, &camera, &bank);
+            sw.frame
+                .sprites
+                .iter()
+                .map(|v| (v.gx, v.gy))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(render(&sim), [(24.0, 8.0)]);
+
+        // across the map into room B, the way the teleporter moves things
+        let to = Vec2::new(200.0, 64.0);
+        let old = sim.subsector_of(caco);
+        sim.world_mut().get::<&mut sim::Position>(caco).unwrap().0 = to;
+        let ss = sim.relink_thing(&level, caco).unwrap();
+        assert_eq!(ss, level.locate_subsector(to));
+        assert_ne!(Some(ss), old);
+        assert!(sim.things_in_subsector(old.unwrap()).is_empty());
+        assert_eq!(sim.things_in_subsector(ss), [caco]);
+        assert_eq!(render(&sim), [(200.0, 64.0)]);
+    }
+
+    #[test]
+    fn sprites_cull_on_centre_depth() {
+        let (level, _, bank, camera) = caco_crowd(0);
+        let info = crate::defs::by_id("HEAD").unwrap();
+        // cacodemons straight ahead at these depths, against a near plane
+        // well past the vanilla one
+        let project = |near: f32, depth: f32| {
+            let camera = Camera::with_near(camera.pos, camera.yaw, camera.fov, near);
+            let pos = camera.pos.truncate() + camera.forward() * depth;
+            let mut sim = sim::TicRunner::new(&level);
+            sim.spawn_mobj(&level, info, pos.x, pos.y, 0.0, level.locate_subsector(pos));
+            let mut active = Vec::new();
+            level.fill_active_subsectors(&camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(320, 200);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+            sw.frame.sprites.first().map(|v| (v.x0, v.x1, v.invz))
+        };
+
+        // past the near plane: as it always was
+        let (x0, x1, invz) = project(16.0, 40.0).unwrap();
+        assert_eq!(invz, 1.0 / 40.0);
+        assert!(x0 < 160 && 160 < x1);
+
+        // centre behind it, though the front of its radius (31) is ahead
+        assert_eq!(project(16.0, 4.0), None);
+
+        // wholly behind it
+        assert_eq!(project(16.0, -20.0), None);
+
+        // the default plane still drops what's within vanilla's 4 units
+        assert_eq!(project(Camera::NEAR, 3.0), None);
+        let (_, _, invz) = project(Camera::NEAR, 4.0).unwrap();
+        assert_eq!(invz, 1.0 / 4.0);
+    }
+
+    #[test]
+    fn rotation_follows_the_viewer_around() {
+        let mut bank = TextureBank::default_with_checker();
+        for name in ["TROOA1", "TROOA2A8", "TROOA3A7", "TROOA4A6", "TROOA5"] {
+            let id = bank.insert(name, Texture::default()).unwrap();
+            bank.register_sprite_lump(name, id);
+        }
+        let lump = |name| bank.id(name).unwrap();
+        assert!(bank.sprite_rotates("TROO", 'A'));
+
+        // an imp at the origin facing east, the viewer 100 units off in
+        // each compass direction, anticlockwise from east
+        let expected = [
+            (1, "TROOA1", false),
+            (2, "TROOA2A8", false),
+            (3, "TROOA3A7", false),
+            (4, "TROOA4A6", false),
+            (5, "TROOA5", false),
+            (6, "TROOA4A6", true),
+            (7, "TROOA3A7", true),
+            (8, "TROOA2A8", true),
+        ];
+        for (i, (rot, name, flip)) in expected.into_iter().enumerate() {
+            let view = Vec2::from_angle(i as f32 * std::f32::consts::FRAC_PI_4) * 100.0;
+            assert_eq!(
+                sprite_rotation(view, Vec2::ZERO, Angle::ZERO),
+                rot,
+                "viewer {i}"
+            );
+            assert_eq!(bank.sprite_id("TROO", 'A', rot), (lump(name), flip));
+        }
+
+        // turning the imp is the same as walking the viewer the other way
+        let south = Vec2::new(0.0, -100.0);
+        assert_eq!(sprite_rotation(south, Vec2::ZERO, (-0.1).into()), 7);
+        assert_eq!(sprite_rotation(south, Vec2::ZERO, 6.1.into()), 7);
+        // right on a boundary, vanilla's +22.5° bias rounds up
+        let ene = Vec2::from_angle(22.5_f32.to_radians()) * 100.0;
+        assert_eq!(sprite_rotation(ene, Vec2::ZERO, Angle::ZERO), 2);
+    }
+
+    #[test]
+    fn eight_way_rotation_is_exact_at_the_edges() {
+        let half = Angle(Angle::ANG45.0 / 2);
+        for facing in [Angle::ZERO, Angle::ANG90 + Angle(7), Angle(u32::MAX)] {
+            for k in 0..8u32 {
+                // seen from in front (viewer → thing opposes the facing)
+                // is rotation 1, then anticlockwise round the thing
+                let centre = facing + Angle::ANG180 + Angle(Angle::ANG45.0 * k);
+                let rot = k as u8 + 1;
+                let next = rot % 8 + 1;
+                assert_eq!(rotation(centre - facing), rot);
+                assert_eq!(rotation(centre - half - facing), rot, "lower edge {k}");
+                assert_eq!(rotation(centre + half - Angle(1) - facing), rot);
+                assert_eq!(rotation(centre + half - facing), next, "upper edge {k}");
+            }
+        }
+    }
+
+    /// An 8×16 patch of palette index 0: posts the full height in columns
+    /// 0‥3, the top half only in 4‥7.
+    fn half_comb_patch() -> Vec<u8> {
+        crate::wad::encode_patch(8, 16, (0, 0), |x, y| (x < 4 || y < 8).then_some(0))
+    }
+
+    #[test]
+    fn colour_zero_in_a_post_is_drawn_and_gaps_are_not() {
+        let wad = crate::wad::Wad::from_lumps(&[("GRATE", half_comb_patch())]);
+        let grate = crate::wad::load_patch(&wad, "GRATE").unwrap();
+        assert_eq!(grate.pixels, [0; 8 * 16]);
+        assert!(grate.is_opaque(15 * 8 + 3));
+        assert!(grate.is_opaque(7 * 8 + 4));
+        assert!(!grate.is_opaque(8 * 8 + 4));
+
+        // index 0 red, everything else blue
+        let mut bank = TextureBank::default_with_checker();
+        let mut palette = crate::world::Palette::default();
+        palette.0.fill(0x0000_00FF);
+        palette.0[0] = 0x00FF_0000;
+        bank.set_palette(palette);
+        let mut colormap = crate::world::Colormap::default();
+        for shades in &mut colormap.0 {
+            for (i, c) in shades.iter_mut().enumerate() {
+                *c = i as u8;
+            }
+        }
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+        let wall = Texture {
+            name: "WALL".into(),
+            w: 8,
+            h: 8,
+            pixels: vec![7; 64],
+            mask: None,
+        };
+        let wall = bank.insert("WALL", wall).unwrap();
+        let grate = bank.insert("GRATE", grate).unwrap();
+        let (mut level, sim, _, camera) = caco_crowd(0);
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.lower, sd.middle) = (wall, wall, TRANSPARENT_TEXTURE);
+        }
+        // the grate on the far separator's side only, not the middle
+        // room's walls
+        let mut side = level.sidedefs[1].clone();
+        side.middle = grate;
+        level.sidedefs.push(side);
+        let sep = (level.linedefs.iter_mut())
+            .find(|ld| ld.left_sidedef == Some(0))
+            .unwrap();
+        sep.right_sidedef = Some(level.sidedefs.len() as u16 - 1);
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (wall, wall);
+        }
+
+        let (w, h) = (640, 400);
+        let mut sw = Software::default();
+        let mut active = Vec::new();
+        let mut fb = Vec::new();
+        sw.begin_frame(w, h);
+        level.fill_active_subsectors(&camera, &mut active);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+        sw.end_frame(|px, _, _| fb.extend_from_slice(px));
+
+        // red rows per screen column: the full posts' run is twice the
+        // half posts', and below the half posts the wall shows through
+        let runs: Vec<usize> = (0..w)
+            .map(|x| (0..h).filter(|&y| fb[y * w + x] == 0x00FF_0000).count())
+            .filter(|&n| n > 0)
+            .collect();
+        let (short, tall) = (runs.iter().min().unwrap(), runs.iter().max().unwrap());
+        assert!(runs.len() > 16, "{runs:?}");
+        assert!(tall.abs_diff(short * 2) <= 2, "{short} vs {tall}");
+        assert!(
+            runs.iter()
+                .all(|n| n.abs_diff(*short) <= 1 || n.abs_diff(*tall) <= 1)
+        );
+    }
+
+    #[test]
+    fn single_lump_frames_are_billboards() {
+        let mut bank = TextureBank::default_with_checker();
+        let id = bank.insert("BAR1A0", Texture::default()).unwrap();
+        bank.register_sprite_lump("BAR1A0", id);
+        assert!(!bank.sprite_rotates("BAR1", 'A'));
+        assert_eq!(bank.sprite_id("BAR1", 'A', 0), (id, false));
+    }
+}
@@ -1,15 +1,33 @@
+use glam::Vec2;
+
 use super::{
     Software,
     planes::{NO_PLANE, VisplaneId},
     projection::Edge,
+    renderer::{Filtering, lerp_rgb},
     sprites::{DrawSeg, Silhouette},
 };
 
+use crate::renderer::DrawCmd;
 use crate::world::{
-    Level, Linedef, LinedefFlags, NO_TEXTURE, Sector, Segment, SegmentId, Sidedef, Texture,
-    TextureBank, TextureId,
+    Level, Linedef, LinedefFlags, NO_TEXTURE, Sector, Segment, SegmentId, Sidedef,
+    TRANSPARENT_TEXTURE, Texture, TextureBank, TextureId,
 };
 
+/// Row `v` of column `u`, tiled.  With `tutti_frutti` heights that aren't
+/// a power of two up to 128 get vanilla's `& 127` instead: past the last
+/// row the lookup runs on into the next column, as vanilla's composites
+/// lie column after column in memory.
+#[inline(always)]
+pub(super) fn wall_texel(tex: &Texture, u: usize, v: i32, tutti_frutti: bool) -> u8 {
+    if !tutti_frutti || (tex.h.is_power_of_two() && tex.h <= 128) {
+        return tex.pixels[v.rem_euclid(tex.h as i32) as usize * tex.w + u];
+    }
+    let at = u * tex.h + (v & 127) as usize;
+    let (u, v) = (at / tex.h % tex.w, at % tex.h);
+    tex.pixels[v * tex.w + u]
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum ClipKind {
     Solid,
@@ -129,28 +147,49 @@ struct ColumnJob<'a> {
     cur: &'a WallCursor,
     span: &'a WallSpan,
     tex: &'a Texture,
-    y_min: i16,
-    y_max: i16,
+    y_min: i32,
+    y_max: i32,
     bank: &'a TextureBank,
 }
 
+/// Where a two-sided line's mid texture hangs, vanilla
+/// `R_RenderMaskedSegRange`: from the lower of the two ceilings, or
+/// standing on the higher floor if the line is lower-unpegged, moved up
+/// by the side's row offset.  Drawn once, never tiled.  Its top and
+/// bottom world Z.
+fn masked_mid_span(
+    front: &Sector,
+    back: &Sector,
+    ld: &Linedef,
+    y_off: f32,
+    tex_h: f32,
+) -> (f32, f32) {
+    let top = if ld.flags.contains(LinedefFlags::LOWER_UNPEGGED) {
+        front.floor_h.max(back.floor_h) + tex_h
+    } else {
+        front.ceil_h.min(back.ceil_h)
+    } + y_off;
+    (top, top - tex_h)
+}
+
 impl Software {
-    fn sectors_for_seg<'l>(
+    pub(super) fn sectors_for_seg<'l>(
         &self,
         seg: &Segment,
         level: &'l Level,
-    ) -> (&'l Sidedef, Option<&'l Sector>, &'l Linedef) {
-        let ld = &level.linedefs[seg.linedef as usize];
+    ) -> Option<(&'l Sidedef, Option<&'l Sector>, &'l Linedef)> {
+        let ld = level.linedefs.get(seg.linedef as usize)?;
         let (sd_front_idx, sd_back_idx) = if seg.dir == 0 {
             (ld.right_sidedef, ld.left_sidedef)
         } else {
             (ld.left_sidedef, ld.right_sidedef)
         };
-        let front = &level.sidedefs[sd_front_idx.unwrap() as usize];
+        // a side `Level::repair` didn't get to (hand-built maps) isn't drawn
+        let front = level.sidedefs.get(sd_front_idx? as usize)?;
         let back = sd_back_idx
             .and_then(|i| level.sidedefs.get(i as usize))
-            .map(|sd| &level.sectors[sd.sector as usize]);
-        (front, back, ld)
+            .and_then(|sd| level.sectors.get(sd.sector as usize));
+        Some((front, back, ld))
     }
 
     pub fn draw_edge(
@@ -161,47 +200,62 @@ impl Software {
         texture_bank: &TextureBank,
     ) {
         let seg = &level.segs[seg_idx as usize];
-        let (sd_front, sec_back_opt, ld) = self.sectors_for_seg(seg, level);
-        let sec_front = &level.sectors[sd_front.sector as usize];
+        let Some((sd_front, sec_back_opt, ld)) = self.sectors_for_seg(seg, level) else {
+            return;
+        };
+        let Some(sec_front) = level.sectors.get(sd_front.sector as usize) else {
+            return;
+        };
 
         let light = (sec_front.light * 255.0) as i16;
 
         let floor_vis = if sec_front.floor_h < self.view_z {
-            self.visplane_map.find(
+            self.frame.visplane_map.find(
                 sec_front.floor_h as i16,
                 sec_front.floor_tex,
                 light,
                 edge.x_l as u16,
                 edge.x_r as u16,
+                &mut self.stats,
             )
         } else {
             NO_PLANE
         };
 
-        let ceil_vis = if sec_front.ceil_h > self.view_z {
-            self.visplane_map.find(
+        let ceil_vis = if sec_front.is_sky_ceiling {
+            // vanilla keys every sky plane alike, so they all merge
+            self.frame.visplane_map.find(
+                0,
+                level.sky_texture,
+                0,
+                edge.x_l as u16,
+                edge.x_r as u16,
+                &mut self.stats,
+            )
+        } else if sec_front.ceil_h > self.view_z {
+            self.frame.visplane_map.find(
                 sec_front.ceil_h as i16,
                 sec_front.ceil_tex,
                 light,
                 edge.x_l as u16,
                 edge.x_r as u16,
+                &mut self.stats,
             )
         } else {
             NO_PLANE
         };
 
-        let mut ds = self.create_draw_seg(
-            seg_idx,
-            &edge,
-            sec_front.ceil_h,
-            sec_front.floor_h,
-            if sec_back_opt.is_some() {
-                sd_front.middle
-            } else {
-                NO_TEXTURE
-            },
-            texture_bank,
-        );
+        let (masked_mid, mid_top, mid_bot) = match sec_back_opt {
+            Some(sec_back) if sd_front.middle != TRANSPARENT_TEXTURE => {
+                let tex_h = texture_bank.texture(sd_front.middle).map_or(0, |t| t.h);
+                let (top, bot) =
+                    masked_mid_span(sec_front, sec_back, ld, sd_front.y_off, tex_h as f32);
+                (sd_front.middle, top, bot)
+            }
+            _ => (TRANSPARENT_TEXTURE, sec_front.ceil_h, sec_front.floor_h),
+        };
+        let mut ds =
+            self.create_draw_seg(seg_idx, &edge, mid_top, mid_bot, masked_mid, texture_bank);
 
         let pass = self.decide_pass(sec_front, sec_back_opt, sd_front, ld);
 
@@ -285,7 +339,7 @@ impl Software {
             }
         }
 
-        self.drawsegs.push(ds);
+        self.push_draw_seg(ds);
     }
 
     fn decide_pass(
@@ -298,10 +352,17 @@ impl Software {
         let world_top = sec_front.ceil_h;
         let world_bottom = sec_front.floor_h;
 
-        if sec_back_opt.is_some() && ld.flags.contains(LinedefFlags::TWO_SIDED) {
-            let sec_back = sec_back_opt.unwrap();
+        let two_sided = ld.flags.contains(LinedefFlags::TWO_SIDED);
+        if let Some(sec_back) = sec_back_opt.filter(|_| two_sided) {
             let worldhigh = sec_back.ceil_h;
             let worldlow = sec_back.floor_h;
+            // the sky hack: no upper wall between two skies, so the sky
+            // shows down to the lower ceiling
+            let world_top = if sec_front.is_sky_ceiling && sec_back.is_sky_ceiling {
+                worldhigh
+            } else {
+                world_top
+            };
 
             let mut mark_floor;
             let mut mark_ceiling;
@@ -338,7 +399,7 @@ impl Software {
             let upper_tex = if worldhigh < world_top {
                 sd_front.upper
             } else {
-                NO_TEXTURE
+                TRANSPARENT_TEXTURE
             };
 
             // ─ lower portal
@@ -346,7 +407,7 @@ impl Software {
             let lower_tex = if worldlow > world_bottom {
                 sd_front.lower
             } else {
-                NO_TEXTURE
+                TRANSPARENT_TEXTURE
             };
             WallPass::TwoSided {
                 pegged: ld.flags.contains(LinedefFlags::UPPER_UNPEGGED),
@@ -378,6 +439,14 @@ impl Software {
         };
 
         let e = job.edge; // alias
+        let last_col = self.width as i32 - 1;
+        debug_assert!(
+            0 <= e.x_l && e.x_l <= e.x_r && e.x_r <= last_col,
+            "wall columns {}..={} off a {}-wide view",
+            e.x_l,
+            e.x_r,
+            self.width
+        );
         let span = WallSpan {
             /* projection --------------------------------------------------- */
             tex_id: job.tex,
@@ -386,8 +455,8 @@ impl Software {
             u1_over_z: e.uoz_r,
             inv_z0: e.invz_l,
             inv_z1: e.invz_r,
-            x_start: e.x_l,
-            x_end: e.x_r,
+            x_start: e.x_l.clamp(0, last_col),
+            x_end: e.x_r.clamp(0, last_col),
             y_top0: self.half_h - (job.ceil_h - self.view_z) * self.focal * e.invz_l,
             y_top1: self.half_h - (job.ceil_h - self.view_z) * self.focal * e.invz_r,
             y_bot0: self.half_h - (job.floor_h - self.view_z) * self.focal * e.invz_l,
@@ -396,6 +465,12 @@ impl Software {
             wall_h: (job.ceil_h - job.floor_h).abs(),
             texturemid_mu,
         };
+        debug_assert!(
+            [span.y_top0, span.y_top1, span.y_bot0, span.y_bot1]
+                .iter()
+                .all(|v| v.is_finite()),
+            "non-finite wall span {span:?}"
+        );
 
         self.emit_and_clip(
             &span,
@@ -409,7 +484,8 @@ impl Software {
 
     #[inline]
     fn column_visible(&self, col: usize, y_top: f32, y_bot: f32) -> bool {
-        y_top < self.clip_bands.floor[col] as f32 && y_bot > self.clip_bands.ceil[col] as f32
+        y_top < self.frame.clip_bands.floor[col] as f32
+            && y_bot > self.frame.clip_bands.ceil[col] as f32
     }
 
     #[inline]
@@ -417,6 +493,7 @@ impl Software {
         if job.y_max < job.y_min {
             return;
         }
+        self.stats.wall_columns += 1;
 
         // Fixed-ratio DOOM vertical scaling.
         let col_px_h = (job.cur.y_bot - job.cur.y_top).max(1.0);
@@ -427,16 +504,47 @@ impl Software {
         let u_tex =
             ((job.cur.u_over_z / job.cur.inv_z) as i32).rem_euclid(job.tex.w as i32) as usize;
 
+        let shade = self
+            .light_mode
+            .shade(job.span.shade_idx, 1.0 / job.cur.inv_z);
+        if let Some(list) = &mut self.record {
+            list.push(DrawCmd::column(
+                job.col,
+                job.y_min..=job.y_max,
+                job.span.tex_id,
+                shade,
+                Vec2::new(u_tex as f32, v_mu),
+                dv_mu,
+            ));
+            return;
+        }
+
+        let shades = self.shades(job.bank);
+        let tutti_frutti = self.compat.emulate_tutti_frutti;
+        if self.filtering == Filtering::Bilinear {
+            for y in job.y_min..=job.y_max {
+                let v = v_mu - 0.5;
+                let v0 = v.floor();
+                let t = ((v - v0) * 256.0) as u32;
+                let texel = |v: i32| shades.get(shade, wall_texel(job.tex, u_tex, v, tutti_frutti));
+                let (above, below) = (texel(v0 as i32), texel(v0 as i32 + 1));
+                self.put_pixel(job.col, y as usize, lerp_rgb(above, below, t));
+                v_mu += dv_mu;
+            }
+            return;
+        }
         for y in job.y_min..=job.y_max {
-            let v_tex = (v_mu as i32).rem_euclid(job.tex.h as i32) as usize;
-            self.scratch[y as usize * self.width + job.col] = job.bank.get_color(
-                job.span.shade_idx,
-                job.tex.pixels[v_tex * job.tex.w + u_tex],
-            );
+            let texel = wall_texel(job.tex, u_tex, v_mu as i32, tutti_frutti);
+            self.put_pixel(job.col, y as usize, shades.get(shade, texel));
             v_mu += dv_mu;
         }
     }
 
+    /// Draw one wall piece column by column, in vanilla's order: the wall,
+    /// then the ceiling / floor planes between it and the clip bands, then
+    /// the bands themselves.  Marking reads the bands from before the wall
+    /// and an upper or lower piece leaves them on its own edge, so the
+    /// opening between two pieces is exactly the rows nothing nearer drew.
     fn emit_and_clip(
         &mut self,
         proto: &WallSpan,
@@ -456,63 +564,79 @@ impl Software {
         for x in proto.x_start..=proto.x_end {
             let col = x as usize;
 
-            let ceil_band = self.clip_bands.ceil[col];
-            let floor_band = self.clip_bands.floor[col];
+            let ceil_band = self.frame.clip_bands.ceil[col];
+            let floor_band = self.frame.clip_bands.floor[col];
 
-            if ceil_band < floor_band {
-                // part of the wall that was visible in this column
-                let y0 = cur.y_top.max((ceil_band + 1) as f32).ceil() as i16;
-                let y1 = cur.y_bot.min((floor_band - 1) as f32).floor() as i16;
+            if self.frame.clip_bands.is_open(col) {
+                /* 1: the wall's rows still open in this column: y0 in
+                 *    ceil_band+1..=floor_band, y1 in ceil_band..=floor_band-1 */
+                let y0 = (cur.y_top.max((ceil_band + 1) as f32).ceil() as i32).min(floor_band);
+                let y1 = (cur.y_bot.min((floor_band - 1) as f32).floor() as i32).max(ceil_band);
 
-                if proto.tex_id != NO_TEXTURE && self.column_visible(col, cur.y_top, cur.y_bot) {
+                /* 2: the wall itself */
+                let textured = proto.tex_id != TRANSPARENT_TEXTURE;
+                if textured && self.column_visible(col, cur.y_top, cur.y_bot) {
                     self.draw_column(ColumnJob {
                         col,
                         cur: &cur,
                         span: proto,
                         tex,
-                        y_min: y0.max(0),
-                        y_max: y1.min((self.height - 1) as i16),
+                        y_min: y0,
+                        y_max: y1,
                         bank: texture_bank,
                     });
                 }
 
-                if let Some(vp) = self.visplane_map.get(ceil_vis) {
-                    let top = ceil_band + 1;
-                    let bottom = (y0 - 1).min(floor_band - 1);
-
-                    if top <= bottom {
-                        vp.modified = true;
-                        vp.top[col] = top.max(0) as u16;
-                        vp.bottom[col] = bottom.max(0) as u16;
-                    }
+                /* 3: the planes above and below it, from the bands as they
+                 *    were before this wall */
+                let top = ceil_band + 1;
+                let bottom = y0 - 1;
+                if top <= bottom {
+                    let map = &mut self.frame.visplane_map;
+                    map.mark(ceil_vis, col, top as u16, bottom as u16);
                 }
 
-                if let Some(vp) = self.visplane_map.get(floor_vis) {
-                    let top = (y1 + 1).max(ceil_band);
-                    let bottom = floor_band;
-                    if top <= bottom {
-                        vp.modified = true;
-                        vp.top[col] = top.max(0) as u16;
-                        vp.bottom[col] = bottom.max(0) as u16;
-                    }
+                let top = y1 + 1;
+                let bottom = floor_band - 1;
+                if top <= bottom {
+                    let map = &mut self.frame.visplane_map;
+                    map.mark(floor_vis, col, top as u16, bottom as u16);
                 }
 
+                /* 4: the bands, as vanilla R_RenderSegLoop moves them */
+                let bands = &mut self.frame.clip_bands;
                 match kind {
-                    ClipKind::Solid => {
-                        self.clip_bands.ceil[col] = i16::MAX;
-                        self.clip_bands.floor[col] = i16::MIN;
-                    }
+                    ClipKind::Solid => bands.close(col),
+                    // an upper wall covers down to its last row (y1); an
+                    // untextured one only hides the ceiling plane marked
+                    // above it, or nothing if none was
                     ClipKind::Upper => {
-                        if proto.tex_id != NO_TEXTURE || ceil_vis != NO_PLANE {
-                            self.clip_bands.ceil[col] = self.clip_bands.ceil[col].max(y1 + 1);
+                        let covered = if textured && y1 >= y0 {
+                            Some(y1)
+                        } else if textured || ceil_vis != NO_PLANE {
+                            Some(y0 - 1)
+                        } else {
+                            None
+                        };
+                        if let Some(row) = covered {
+                            bands.ceil[col] = ceil_band.max(row);
                         }
                     }
+                    // mirrored: a lower wall covers up to its first row (y0)
                     ClipKind::Lower => {
-                        if proto.tex_id != NO_TEXTURE || floor_vis != NO_PLANE {
-                            self.clip_bands.floor[col] = floor_band.min(y0 - 1);
+                        let covered = if textured && y0 <= y1 {
+                            Some(y0)
+                        } else if textured || floor_vis != NO_PLANE {
+                            Some(y1 + 1)
+                        } else {
+                            None
+                        };
+                        if let Some(row) = covered {
+                            bands.floor[col] = floor_band.min(row);
                         }
                     }
                 }
+                bands.debug_check(col);
             }
 
             cur.advance(&step);
@@ -0,0 +1,114 @@
+//! Per-frame render counters.
+//!
+//! Everything is a plain `u32` bumped inline; `begin_frame` zeroes the lot.
+//! The phase timings are measured around the wall, plane and sprite passes
+//! of `draw_level`.
+
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderStats {
+    /// Subsectors handed to `draw_level`.
+    pub subsectors: u32,
+    /// Segs that survived projection and were drawn.
+    pub segs_projected: u32,
+    /// Vertices moved into camera space, each at most once a view.
+    pub vertices_transformed: u32,
+    /// Segs facing away from the camera.
+    pub segs_culled_backface: u32,
+    /// Segs behind the near plane, off either side of the screen or
+    /// thinner than a column.
+    pub segs_culled_bbox: u32,
+    /// Segs entirely hidden behind solid walls already drawn.
+    pub segs_culled_solid: u32,
+    /// Wall texture columns drawn.
+    pub wall_columns: u32,
+    /// Visplanes started for a new height/texture/light or span.
+    pub visplanes_created: u32,
+    /// Visplane lookups that extended an existing plane instead.
+    pub visplanes_merged: u32,
+    /// Floor and ceiling pixels drawn.
+    pub plane_pixels: u32,
+    /// Things that projected onto the screen.
+    pub sprites_projected: u32,
+    /// Sprites with at least one visible column.
+    pub sprites_drawn: u32,
+    /// Drawsegs sprites were clipped against (one side test each).
+    pub sprite_clip_segs: u32,
+    /// Masked mid-texture columns drawn.
+    pub masked_columns: u32,
+
+    /// BSP walk, seg projection and wall columns.
+    pub wall_time: Duration,
+    /// Visplane flush.
+    pub plane_time: Duration,
+    /// Sprites and masked mid-textures.
+    pub sprite_time: Duration,
+}
+
+impl RenderStats {
+    /// All zero; what renderers without counters report.
+    pub const ZERO: Self = Self {
+        subsectors: 0,
+        segs_projected: 0,
+        vertices_transformed: 0,
+        segs_culled_backface: 0,
+        segs_culled_bbox: 0,
+        segs_culled_solid: 0,
+        wall_columns: 0,
+        visplanes_created: 0,
+        visplanes_merged: 0,
+        plane_pixels: 0,
+        sprites_projected: 0,
+        sprites_drawn: 0,
+        sprite_clip_segs: 0,
+        masked_columns: 0,
+        wall_time: Duration::ZERO,
+        plane_time: Duration::ZERO,
+        sprite_time: Duration::ZERO,
+    };
+
+    /// Counters as short `name value` lines, for text overlays.
+    pub fn lines(&self) -> [String; 7] {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        [
+            format!(
+                "ssec {}  segs {}  verts {}",
+                self.subsectors, self.segs_projected, self.vertices_transformed
+            ),
+            format!(
+                "cull back {} bbox {} solid {}",
+                self.segs_culled_backface, self.segs_culled_bbox, self.segs_culled_solid
+            ),
+            format!("wall cols {}", self.wall_columns),
+            format!(
+                "planes {} (+{} merged) px {}",
+                self.visplanes_created, self.visplanes_merged, self.plane_pixels
+            ),
+            format!(
+                "sprites {}/{} clip {}  masked {}",
+                self.sprites_drawn,
+                self.sprites_projected,
+                self.sprite_clip_segs,
+                self.masked_columns
+            ),
+            format!(
+                "wall {:.2} plane {:.2} sprite {:.2} ms",
+                ms(self.wall_time),
+                ms(self.plane_time),
+                ms(self.sprite_time)
+            ),
+            format!(
+                "total {:.2} ms",
+                ms(self.wall_time + self.plane_time + self.sprite_time)
+            ),
+        ]
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.lines().join("\n"))
+    }
+}
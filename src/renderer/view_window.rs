@@ -0,0 +1,146 @@
+//! Where in the frame the 3-D view goes: vanilla `viewwindowx`,
+//! `viewwindowy`, `scaledviewwidth` and `viewheight`, picked by the
+//! screen size (`screenblocks`).  Everything around it is the border.
+
+/// Smallest vanilla `screenblocks`.
+pub const SCREENBLOCKS_MIN: u8 = 3;
+/// Full width over the status bar.
+pub const SCREENBLOCKS_STATUS_BAR: u8 = 10;
+/// The whole screen, no status bar.
+pub const SCREENBLOCKS_MAX: u8 = 11;
+
+/// Vanilla's screen, which the sizes below are in.
+const SCREEN_W: usize = 320;
+const SCREEN_H: usize = 200;
+/// Rows the status bar takes off the bottom (`SBARHEIGHT`).
+const SBAR_HEIGHT: usize = 32;
+
+/// A rectangle of the frame, in pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ViewWindow {
+    pub x: usize,
+    pub y: usize,
+    pub w: usize,
+    pub h: usize,
+}
+
+impl ViewWindow {
+    /// All of a `w × h` frame.
+    pub fn full(w: usize, h: usize) -> Self {
+        Self { x: 0, y: 0, w, h }
+    }
+
+    /// Vanilla `R_ExecuteSetViewSize` for `blocks` (clamped to 3‥11),
+    /// scaled from 320×200 to a `w × h` frame.
+    pub fn for_screenblocks(blocks: u8, w: usize, h: usize) -> Self {
+        let blocks = blocks.clamp(SCREENBLOCKS_MIN, SCREENBLOCKS_MAX) as usize;
+        if blocks == SCREENBLOCKS_MAX as usize {
+            return Self::full(w, h);
+        }
+        let bar_top = SCREEN_H - SBAR_HEIGHT;
+        let (vw, vh) = match blocks {
+            b if b == SCREENBLOCKS_STATUS_BAR as usize => (SCREEN_W, bar_top),
+            _ => (blocks * 32, (blocks * bar_top / 10) & !7),
+        };
+        let x = (SCREEN_W - vw) / 2;
+        let y = if vw == SCREEN_W {
+            0
+        } else {
+            (bar_top - vh) / 2
+        };
+        Self { x, y, w: vw, h: vh }.scaled((SCREEN_W, SCREEN_H), (w, h))
+    }
+
+    /// The same part of a frame resized from `from` to `to`; never
+    /// narrower or shorter than a pixel.
+    pub fn scaled(self, from: (usize, usize), to: (usize, usize)) -> Self {
+        if from == to || from.0 == 0 || from.1 == 0 {
+            return self;
+        }
+        let edge = |v: usize, from: usize, to: usize| (v * to / from).min(to);
+        let (x0, x1) = (
+            edge(self.x, from.0, to.0),
+            edge(self.x + self.w, from.0, to.0),
+        );
+        let (y0, y1) = (
+            edge(self.y, from.1, to.1),
+            edge(self.y + self.h, from.1, to.1),
+        );
+        let x0 = x0.min(to.0.saturating_sub(1));
+        let y0 = y0.min(to.1.saturating_sub(1));
+        Self {
+            x: x0,
+            y: y0,
+            w: x1.saturating_sub(x0).max(1),
+            h: y1.saturating_sub(y0).max(1),
+        }
+    }
+
+    /// Whether this covers all of a `w × h` frame.
+    pub fn is_full(&self, w: usize, h: usize) -> bool {
+        *self == Self::full(w, h)
+    }
+
+    #[inline]
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.w).contains(&x) && (self.y..self.y + self.h).contains(&y)
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screenblocks_match_vanilla() {
+        let at_320 = |b| ViewWindow::for_screenblocks(b, 320, 200);
+        assert_eq!(at_320(11), ViewWindow::full(320, 200));
+        assert_eq!(at_320(10), ViewWindow::full(320, 168));
+        let nine = ViewWindow {
+            x: 16,
+            y: 12,
+            w: 288,
+            h: 144,
+        };
+        assert_eq!(at_320(9), nine);
+        assert_eq!(
+            at_320(3),
+            ViewWindow {
+                x: 112,
+                y: 60,
+                w: 96,
+                h: 48
+            }
+        );
+        assert_eq!(at_320(0), at_320(3));
+        assert_eq!(at_320(200), at_320(11));
+
+        // a 2× frame doubles everything
+        let big = ViewWindow::for_screenblocks(9, 640, 400);
+        assert_eq!(
+            big,
+            ViewWindow {
+                x: 32,
+                y: 24,
+                w: 576,
+                h: 288
+            }
+        );
+        assert_eq!(big.scaled((640, 400), (320, 200)), nine);
+    }
+
+    #[test]
+    fn sizes_shrink_step_by_step() {
+        let areas: Vec<usize> = (SCREENBLOCKS_MIN..=SCREENBLOCKS_MAX)
+            .map(|b| {
+                let v = ViewWindow::for_screenblocks(b, 1280, 800);
+                assert!(v.x + v.w <= 1280 && v.y + v.h <= 800, "{b}: {v:?}");
+                v.w * v.h
+            })
+            .collect();
+        assert!(areas.windows(2).all(|a| a[0] < a[1]), "{areas:?}");
+    }
+}
@@ -0,0 +1,800 @@
+//! Hardware renderer on top of `wgpu` (cargo feature `wgpu`).
+//!
+//! The BSP walk, clipping, visplanes and sprite sorting are the software
+//! renderer's: a [`Software`] instance runs in record mode and every column
+//! or span it would have drawn becomes one instanced quad.  The fragment
+//! shader does the texel fetch and the COLORMAP lookup, so the picture
+//! matches the software path pixel for pixel up to float rounding.
+//!
+//! * **Atlas** – every texture in the bank, shelf-packed into one `Rg8Uint`
+//!   texture (R = palette index, G = opaque).  Re-uploaded whenever the
+//!   bank grows.
+//! * **LUT** – the 34 × 256 shade table as an `Rgba8Unorm` texture.
+//! * **Frame** – drawn off-screen and read back, so `end_frame` hands out
+//!   the same `&[Rgba]` as the software renderer.  The readback is the
+//!   bottleneck at high resolutions; presenting straight to a surface is the
+//!   next step once the front-end owns one.
+
+use ::wgpu::util::DeviceExt;
+use bytemuck::{Pod, Zeroable};
+use thiserror::Error;
+
+use super::{
+    DrawCmd, DrawFlags, RenderStats, Renderer, Rgba, Software, ViewWindow, overlay::Canvas,
+};
+use crate::{
+    sim::TicRunner,
+    world::{Camera, FontRef, Level, NO_TEXTURE, SubsectorId, Texture, TextureBank},
+};
+
+const TARGET_FORMAT: ::wgpu::TextureFormat = ::wgpu::TextureFormat::Rgba8Unorm;
+
+/// Smallest atlas side tried by the packer.
+const MIN_ATLAS: u32 = 256;
+
+/// Same dark grey the software renderer clears to.
+const CLEAR: f64 = 0x20 as f64 / 255.0;
+
+const SHADER: &str = r#"
+const MASKED: u32 = 1u;
+const CLAMP_V: u32 = 2u;
+
+struct Screen { size: vec2<f32>, pad: vec2<f32> };
+
+@group(0) @binding(0) var<uniform> screen: Screen;
+@group(0) @binding(1) var atlas: texture_2d<u32>;
+@group(0) @binding(2) var lut: texture_2d<f32>;
+
+struct Cmd {
+    @location(0) rect: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) duv_dx: vec2<f32>,
+    @location(3) duv_dy: vec2<f32>,
+    @location(4) tex: vec4<u32>,
+    @location(5) params: vec2<u32>,
+};
+
+struct VsOut {
+    @builtin(position) pos: vec4<f32>,
+    @location(0) @interpolate(flat) rect: vec4<f32>,
+    @location(1) @interpolate(flat) uv: vec2<f32>,
+    @location(2) @interpolate(flat) duv_dx: vec2<f32>,
+    @location(3) @interpolate(flat) duv_dy: vec2<f32>,
+    @location(4) @interpolate(flat) tex: vec4<u32>,
+    @location(5) @interpolate(flat) params: vec2<u32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vi: u32, cmd: Cmd) -> VsOut {
+    var corners = array<vec2<f32>, 6>(
+        vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(0.0, 1.0),
+        vec2(0.0, 1.0), vec2(1.0, 0.0), vec2(1.0, 1.0),
+    );
+    let px = mix(cmd.rect.xy, cmd.rect.zw, corners[vi]);
+
+    var out: VsOut;
+    out.pos = vec4(px.x / screen.size.x * 2.0 - 1.0, 1.0 - px.y / screen.size.y * 2.0, 0.0, 1.0);
+    out.rect = cmd.rect;
+    out.uv = cmd.uv;
+    out.duv_dx = cmd.duv_dx;
+    out.duv_dy = cmd.duv_dy;
+    out.tex = cmd.tex;
+    out.params = cmd.params;
+    return out;
+}
+
+fn wrap(a: i32, n: i32) -> i32 {
+    return ((a % n) + n) % n;
+}
+
+@fragment
+fn fs_main(f: VsOut) -> @location(0) vec4<f32> {
+    let d = floor(f.pos.xy) - f.rect.xy;
+    let uv = f.uv + f.duv_dx * d.x + f.duv_dy * d.y;
+    let size = vec2<i32>(f.tex.zw);
+
+    let u = wrap(i32(uv.x), size.x);
+    var v = wrap(i32(uv.y), size.y);
+    if (f.params.y & CLAMP_V) != 0u {
+        v = clamp(i32(uv.y), 0, size.y - 1);
+    }
+
+    let texel = textureLoad(atlas, vec2<i32>(f.tex.xy) + vec2(u, v), 0);
+    if (f.params.y & MASKED) != 0u && texel.g == 0u {
+        discard;
+    }
+    return textureLoad(lut, vec2<i32>(i32(texel.r), i32(f.params.x)), 0);
+}
+"#;
+
+/// Things that can go wrong setting up or feeding the GPU.
+#[derive(Debug, Error)]
+pub enum WgpuError {
+    #[error("no usable GPU adapter: {0}")]
+    NoAdapter(#[from] ::wgpu::RequestAdapterError),
+    #[error("GPU device request failed: {0}")]
+    Device(#[from] ::wgpu::RequestDeviceError),
+    #[error("textures don't fit a {max}×{max} atlas")]
+    AtlasTooLarge { max: u32 },
+}
+
+/// One `DrawCmd` as the vertex shader sees it.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+struct Instance {
+    /// `x0, y0, x1 + 1, y1 + 1` in pixels.
+    rect: [f32; 4],
+    uv: [f32; 2],
+    duv_dx: [f32; 2],
+    duv_dy: [f32; 2],
+    /// Atlas `x, y, w, h`.
+    tex: [u32; 4],
+    /// `shade, flags`.
+    params: [u32; 2],
+}
+
+impl Instance {
+    const ATTRIBS: [::wgpu::VertexAttribute; 6] = ::wgpu::vertex_attr_array![
+        0 => Float32x4, 1 => Float32x2, 2 => Float32x2,
+        3 => Float32x2, 4 => Uint32x4, 5 => Uint32x2
+    ];
+
+    fn new(cmd: &DrawCmd, tex: [u32; 4]) -> Self {
+        Self {
+            rect: [
+                cmd.x0 as f32,
+                cmd.y0 as f32,
+                cmd.x1 as f32 + 1.0,
+                cmd.y1 as f32 + 1.0,
+            ],
+            uv: cmd.uv.to_array(),
+            duv_dx: cmd.duv_dx.to_array(),
+            duv_dy: cmd.duv_dy.to_array(),
+            tex,
+            params: [cmd.shade as u32, cmd.flags.bits() as u32],
+        }
+    }
+}
+
+/// Shelf-pack `sizes` (w, h) into the smallest power-of-two square, up to
+/// `max_side`, that holds them all.  Returns the side and every
+/// rectangle's top-left corner, or `None` if even `max_side` is too small.
+fn pack_atlas(sizes: &[(u32, u32)], max_side: u32) -> Option<(u32, Vec<(u32, u32)>)> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i].1));
+
+    let mut side = MIN_ATLAS.min(max_side);
+    loop {
+        if let Some(spots) = shelf_pack(sizes, &order, side) {
+            return Some((side, spots));
+        }
+        if side >= max_side {
+            return None;
+        }
+        side = (side * 2).min(max_side);
+    }
+}
+
+fn shelf_pack(sizes: &[(u32, u32)], order: &[usize], side: u32) -> Option<Vec<(u32, u32)>> {
+    let mut spots = vec![(0, 0); sizes.len()];
+    let (mut x, mut y, mut shelf_h) = (0, 0, 0);
+
+    for &i in order {
+        let (w, h) = sizes[i];
+        if w > side {
+            return None;
+        }
+        if x + w > side {
+            y += shelf_h;
+            x = 0;
+            shelf_h = 0;
+        }
+        if y + h > side {
+            return None;
+        }
+        spots[i] = (x, y);
+        x += w;
+        shelf_h = shelf_h.max(h);
+    }
+    Some(spots)
+}
+
+/// Textures as the GPU sees them; rebuilt when the bank grows.
+struct Atlas {
+    /// `x, y, w, h` per `TextureId`.
+    rects: Vec<[u32; 4]>,
+    bind_group: ::wgpu::BindGroup,
+}
+
+/// Off-screen colour target plus its readback buffer.
+struct Target {
+    texture: ::wgpu::Texture,
+    view: ::wgpu::TextureView,
+    readback: ::wgpu::Buffer,
+    padded_row: u32,
+    width: u32,
+    height: u32,
+}
+
+pub struct WgpuRenderer {
+    sw: Software,
+    device: ::wgpu::Device,
+    queue: ::wgpu::Queue,
+    pipeline: ::wgpu::RenderPipeline,
+    layout: ::wgpu::BindGroupLayout,
+    screen: ::wgpu::Buffer,
+    max_side: u32,
+
+    atlas: Option<Atlas>,
+    uploaded: usize,
+    target: Option<Target>,
+
+    instances: Vec<Instance>,
+    instance_buf: Option<::wgpu::Buffer>,
+}
+
+impl WgpuRenderer {
+    /// Pick the default adapter and build the pipeline.
+    pub fn new() -> Result<Self, WgpuError> {
+        pollster::block_on(Self::new_async())
+    }
+
+    async fn new_async() -> Result<Self, WgpuError> {
+        let instance = ::wgpu::Instance::new(&::wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&::wgpu::RequestAdapterOptions {
+                power_preference: ::wgpu::PowerPreference::HighPerformance,
+                force_fallback_adapter: false,
+                compatible_surface: None,
+            })
+            .await?;
+        let limits = adapter.limits();
+        let (device, queue) = adapter
+            .request_device(&::wgpu::DeviceDescriptor {
+                label: Some("yadoom"),
+                required_features: ::wgpu::Features::empty(),
+                required_limits: limits.clone(),
+                memory_hints: ::wgpu::MemoryHints::Performance,
+                trace: ::wgpu::Trace::Off,
+            })
+            .await?;
+
+        let shader = device.create_shader_module(::wgpu::ShaderModuleDescriptor {
+            label: Some("yadoom columns"),
+            source: ::wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+
+        let texture_entry = |binding, sample_type| ::wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: ::wgpu::ShaderStages::FRAGMENT,
+            ty: ::wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension: ::wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&::wgpu::BindGroupLayoutDescriptor {
+            label: Some("yadoom textures"),
+            entries: &[
+                ::wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ::wgpu::ShaderStages::VERTEX,
+                    ty: ::wgpu::BindingType::Buffer {
+                        ty: ::wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                texture_entry(1, ::wgpu::TextureSampleType::Uint),
+                texture_entry(2, ::wgpu::TextureSampleType::Float { filterable: false }),
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&::wgpu::PipelineLayoutDescriptor {
+            label: Some("yadoom"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&::wgpu::RenderPipelineDescriptor {
+            label: Some("yadoom columns"),
+            layout: Some(&pipeline_layout),
+            vertex: ::wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                compilation_options: Default::default(),
+                buffers: &[::wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<Instance>() as u64,
+                    step_mode: ::wgpu::VertexStepMode::Instance,
+                    attributes: &Instance::ATTRIBS,
+                }],
+            },
+            primitive: ::wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: ::wgpu::MultisampleState::default(),
+            fragment: Some(::wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(::wgpu::ColorTargetState {
+                    format: TARGET_FORMAT,
+                    blend: None,
+                    write_mask: ::wgpu::ColorWrites::ALL,
+                })],
+            }),
+            multiview: None,
+            cache: None,
+        });
+
+        let screen = device.create_buffer(&::wgpu::BufferDescriptor {
+            label: Some("yadoom screen"),
+            size: 16,
+            usage: ::wgpu::BufferUsages::UNIFORM | ::wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Ok(Self {
+            sw: Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            },
+            device,
+            queue,
+            pipeline,
+            layout,
+            screen,
+            max_side: limits.max_texture_dimension_2d,
+            atlas: None,
+            uploaded: 0,
+            target: None,
+            instances: Vec::new(),
+            instance_buf: None,
+        })
+    }
+
+    /// Upload every texture in `bank` plus its shade table.  `draw_level`
+    /// does this on its own when the bank has grown; call it up front to
+    /// get an error instead of a panic if the atlas would be too large.
+    pub fn upload_textures(&mut self, bank: &TextureBank) -> Result<(), WgpuError> {
+        let textures: Vec<_> = (0..bank.len())
+            .map(|id| bank.texture(id as u16).expect("id below bank.len()"))
+            .collect();
+        let sizes: Vec<_> = textures.iter().map(|t| (t.w as u32, t.h as u32)).collect();
+        let (side, spots) = pack_atlas(&sizes, self.max_side)
+            .ok_or(WgpuError::AtlasTooLarge { max: self.max_side })?;
+
+        /* R = palette index, G = opaque */
+        let mut texels = vec![0u8; (side * side * 2) as usize];
+        for (tex, &(x0, y0)) in textures.iter().zip(&spots) {
+            for y in 0..tex.h {
+                for x in 0..tex.w {
+                    let src = y * tex.w + x;
+                    let dst = ((y0 as usize + y) * side as usize + x0 as usize + x) * 2;
+                    texels[dst] = tex.pixels[src];
+                    texels[dst + 1] = if tex.is_opaque(src) { 255 } else { 0 };
+                }
+            }
+        }
+        let atlas = self.device.create_texture_with_data(
+            &self.queue,
+            &::wgpu::TextureDescriptor {
+                label: Some("yadoom atlas"),
+                size: ::wgpu::Extent3d {
+                    width: side,
+                    height: side,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: ::wgpu::TextureDimension::D2,
+                format: ::wgpu::TextureFormat::Rg8Uint,
+                usage: ::wgpu::TextureUsages::TEXTURE_BINDING | ::wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            ::wgpu::util::TextureDataOrder::LayerMajor,
+            &texels,
+        );
+
+        /* shade table → RGBA */
+        let lut_px: Vec<u8> = bank
+            .shade_table()
+            .iter()
+            .flat_map(|&c| [(c >> 16) as u8, (c >> 8) as u8, c as u8, (c >> 24) as u8])
+            .collect();
+        let rows = (lut_px.len() / (256 * 4)).max(1) as u32;
+        let lut_px = if lut_px.is_empty() {
+            vec![0; 256 * 4]
+        } else {
+            lut_px
+        };
+        let lut = self.device.create_texture_with_data(
+            &self.queue,
+            &::wgpu::TextureDescriptor {
+                label: Some("yadoom colormap"),
+                size: ::wgpu::Extent3d {
+                    width: 256,
+                    height: rows,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: ::wgpu::TextureDimension::D2,
+                format: ::wgpu::TextureFormat::Rgba8Unorm,
+                usage: ::wgpu::TextureUsages::TEXTURE_BINDING | ::wgpu::TextureUsages::COPY_DST,
+                view_formats: &[],
+            },
+            ::wgpu::util::TextureDataOrder::LayerMajor,
+            &lut_px,
+        );
+
+        let atlas_view = atlas.create_view(&Default::default());
+        let lut_view = lut.create_view(&Default::default());
+        let bind_group = self.device.create_bind_group(&::wgpu::BindGroupDescriptor {
+            label: Some("yadoom textures"),
+            layout: &self.layout,
+            entries: &[
+                ::wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.screen.as_entire_binding(),
+                },
+                ::wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: ::wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                ::wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: ::wgpu::BindingResource::TextureView(&lut_view),
+                },
+            ],
+        });
+
+        self.atlas = Some(Atlas {
+            rects: sizes
+                .iter()
+                .zip(&spots)
+                .map(|(&(w, h), &(x, y))| [x, y, w, h])
+                .collect(),
+            bind_group,
+        });
+        self.uploaded = bank.len();
+        Ok(())
+    }
+
+    /// (Re)create the colour target and readback buffer for `w × h`.
+    fn ensure_target(&mut self, w: u32, h: u32) {
+        if self
+            .target
+            .as_ref()
+            .is_some_and(|t| t.width == w && t.height == h)
+        {
+            return;
+        }
+        let texture = self.device.create_texture(&::wgpu::TextureDescriptor {
+            label: Some("yadoom frame"),
+            size: ::wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: ::wgpu::TextureDimension::D2,
+            format: TARGET_FORMAT,
+            usage: ::wgpu::TextureUsages::RENDER_ATTACHMENT | ::wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let padded_row = (w * 4).next_multiple_of(::wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let readback = self.device.create_buffer(&::wgpu::BufferDescriptor {
+            label: Some("yadoom readback"),
+            size: padded_row as u64 * h as u64,
+            usage: ::wgpu::BufferUsages::COPY_DST | ::wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        self.queue.write_buffer(
+            &self.screen,
+            0,
+            bytemuck::cast_slice(&[w as f32, h as f32, 0.0, 0.0]),
+        );
+        self.target = Some(Target {
+            view: texture.create_view(&Default::default()),
+            texture,
+            readback,
+            padded_row,
+            width: w,
+            height: h,
+        });
+    }
+
+    /// Upload this frame's instances, growing the buffer if needed.
+    fn upload_instances(&mut self) {
+        let bytes: &[u8] = bytemuck::cast_slice(&self.instances);
+        let fits = self
+            .instance_buf
+            .as_ref()
+            .is_some_and(|b| b.size() >= bytes.len() as u64);
+        if fits {
+            self.queue
+                .write_buffer(self.instance_buf.as_ref().unwrap(), 0, bytes);
+        } else {
+            self.instance_buf = Some(self.device.create_buffer_init(
+                &::wgpu::util::BufferInitDescriptor {
+                    label: Some("yadoom instances"),
+                    contents: bytes,
+                    usage: ::wgpu::BufferUsages::VERTEX | ::wgpu::BufferUsages::COPY_DST,
+                },
+            ));
+        }
+    }
+
+    /// Draw the recorded frame and read it back into `self.sw.scratch`.
+    fn render(&mut self) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        let mut enc = self
+            .device
+            .create_command_encoder(&::wgpu::CommandEncoderDescriptor {
+                label: Some("yadoom frame"),
+            });
+        {
+            let mut pass = enc.begin_render_pass(&::wgpu::RenderPassDescriptor {
+                label: Some("yadoom columns"),
+                color_attachments: &[Some(::wgpu::RenderPassColorAttachment {
+                    view: &target.view,
+                    resolve_target: None,
+                    ops: ::wgpu::Operations {
+                        load: ::wgpu::LoadOp::Clear(::wgpu::Color {
+                            r: CLEAR,
+                            g: CLEAR,
+                            b: CLEAR,
+                            a: 1.0,
+                        }),
+                        store: ::wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            if let (Some(atlas), Some(buf)) = (&self.atlas, &self.instance_buf) {
+                if !self.instances.is_empty() {
+                    pass.set_pipeline(&self.pipeline);
+                    pass.set_bind_group(0, &atlas.bind_group, &[]);
+                    pass.set_vertex_buffer(0, buf.slice(..));
+                    pass.draw(0..6, 0..self.instances.len() as u32);
+                }
+            }
+        }
+        enc.copy_texture_to_buffer(
+            target.texture.as_image_copy(),
+            ::wgpu::TexelCopyBufferInfo {
+                buffer: &target.readback,
+                layout: ::wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(target.padded_row),
+                    rows_per_image: None,
+                },
+            },
+            ::wgpu::Extent3d {
+                width: target.width,
+                height: target.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit([enc.finish()]);
+
+        let slice = target.readback.slice(..);
+        slice.map_async(::wgpu::MapMode::Read, |_| ());
+        self.device
+            .poll(::wgpu::PollType::Wait)
+            .expect("GPU readback");
+        {
+            let data = slice.get_mapped_range();
+            let w = target.width as usize;
+            for (row, dst) in data
+                .chunks_exact(target.padded_row as usize)
+                .zip(self.sw.scratch.chunks_exact_mut(w))
+            {
+                for (px, out) in row[..w * 4].chunks_exact(4).zip(dst) {
+                    *out = u32::from_le_bytes([px[2], px[1], px[0], px[3]]);
+                }
+            }
+        }
+        target.readback.unmap();
+    }
+}
+
+impl Renderer for WgpuRenderer {
+    /// Always draws the view over the whole window.
+    fn begin_view(&mut self, w: usize, h: usize, _view: ViewWindow) {
+        self.sw.begin_frame(w, h);
+        self.instances.clear();
+        // no zero-sized textures; the recording renderer skips the frame
+        if !self.sw.skip_frame {
+            self.ensure_target(w as u32, h as u32);
+        }
+    }
+
+    /// # Panics
+    /// If the bank has outgrown the largest atlas the device supports; call
+    /// [`WgpuRenderer::upload_textures`] first to handle that gracefully.
+    fn draw_level(
+        &mut self,
+        subsectors: &[SubsectorId],
+        level: &Level,
+        sim: &TicRunner,
+        camera: &Camera,
+        texture_bank: &TextureBank,
+    ) {
+        if self.sw.skip_frame {
+            return;
+        }
+        if self.atlas.is_none() || self.uploaded != texture_bank.len() {
+            self.upload_textures(texture_bank)
+                .unwrap_or_else(|e| panic!("{e}"));
+        }
+        self.sw
+            .draw_level(subsectors, level, sim, camera, texture_bank);
+
+        let rects = &self.atlas.as_ref().expect("uploaded above").rects;
+        let cmds = self.sw.record.as_deref().unwrap_or_default();
+        self.instances.extend(cmds.iter().map(|cmd| {
+            let tex = rects
+                .get(cmd.tex as usize)
+                .unwrap_or(&rects[NO_TEXTURE as usize]);
+            Instance::new(cmd, *tex)
+        }));
+    }
+
+    // The 2-D calls wait in the recording renderer's overlay and go over
+    // the read-back frame in `end_frame`.
+    fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, col: u32) {
+        self.sw.draw_line(x0, y0, x1, y1, col);
+    }
+
+    fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, col: Rgba) {
+        self.sw.fill_rect(x, y, w, h, col);
+    }
+
+    fn dim_rect(&mut self, x: i32, y: i32, w: i32, h: i32) {
+        self.sw.dim_rect(x, y, w, h);
+    }
+
+    fn blit_patch(&mut self, patch: &Texture, x: i32, y: i32, shade: u8, bank: &TextureBank) {
+        self.sw.blit_patch(patch, x, y, shade, bank);
+    }
+
+    fn draw_text(&mut self, text: &str, x: i32, y: i32, font: &FontRef, col: Rgba) {
+        self.sw.draw_text(text, x, y, font, col);
+    }
+
+    /// The recording pass's counters; the GPU time isn't included.
+    fn stats(&self) -> &RenderStats {
+        self.sw.stats()
+    }
+
+    fn end_frame<F>(&mut self, submit: F)
+    where
+        F: FnOnce(&[Rgba], usize, usize),
+    {
+        if self.sw.skip_frame {
+            return self.sw.end_frame(submit);
+        }
+        self.upload_instances();
+        self.render();
+        let (w, h) = (self.sw.frame_w, self.sw.frame_h);
+        (self.sw.overlay).draw(&mut Canvas::new(&mut self.sw.scratch, w, h));
+        submit(&self.sw.scratch, self.sw.frame_w, self.sw.frame_h);
+    }
+}
+
+/// Flags survive the trip to the shader unchanged.
+const _: () = assert!(DrawFlags::MASKED.bits() == 1 && DrawFlags::CLAMP_V.bits() == 2);
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Colormap, Palette, testmap};
+    use glam::Vec3;
+
+    #[test]
+    fn atlas_packs_without_overlap() {
+        let sizes = [(64, 64), (128, 16), (8, 8), (256, 128), (64, 64), (3, 200)];
+        let (side, spots) = pack_atlas(&sizes, 4096).unwrap();
+        assert_eq!(side, 512);
+
+        let rects: Vec<_> = sizes
+            .iter()
+            .zip(&spots)
+            .map(|(&(w, h), &(x, y))| (x, y, x + w, y + h))
+            .collect();
+        for (i, a) in rects.iter().enumerate() {
+            assert!(a.2 <= side && a.3 <= side);
+            for b in &rects[i + 1..] {
+                assert!(a.2 <= b.0 || b.2 <= a.0 || a.3 <= b.1 || b.3 <= a.1);
+            }
+        }
+    }
+
+    #[test]
+    fn atlas_too_large() {
+        assert!(pack_atlas(&[(300, 10)], 256).is_none());
+        assert!(pack_atlas(&[(200, 200), (200, 200)], 256).is_none());
+        assert_eq!(pack_atlas(&[], 256), Some((256, Vec::new())));
+    }
+
+    fn frame<R: Renderer>(
+        r: &mut R,
+        active: &[SubsectorId],
+        level: &Level,
+        sim: &TicRunner,
+        camera: &Camera,
+        bank: &TextureBank,
+    ) -> Vec<Rgba> {
+        let mut out = Vec::new();
+        r.begin_frame(160, 100);
+        r.draw_level(active, level, sim, camera, bank);
+        r.end_frame(|fb, _, _| out = fb.to_vec());
+        out
+    }
+
+    /// Both backends draw the test map alike (a software adapter will do).
+    /// Skipped without any GPU.
+    #[test]
+    fn matches_software_renderer() {
+        let Ok(mut gpu) = WgpuRenderer::new() else {
+            eprintln!("no GPU adapter – skipping");
+            return;
+        };
+
+        let mut bank = TextureBank::default_with_checker();
+        let mut palette = Palette::default();
+        for (i, c) in palette.0.iter_mut().enumerate() {
+            *c = (i as u32 * 0x01_03_07) & 0xFF_FF_FF;
+        }
+        bank.set_palette(palette);
+        let mut colormap = Colormap::default();
+        for (row, shades) in colormap.0.iter_mut().enumerate() {
+            for (i, c) in shades.iter_mut().enumerate() {
+                *c = (i * (34 - row) / 34) as u8;
+            }
+        }
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 96.0,
+        });
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.lower, sd.middle) = (wall, wall, wall);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (wall, wall);
+        }
+        let sim = TicRunner::new(&level);
+        let camera = Camera::new(Vec3::new(32.0, 64.0, 41.0), 0.0, 90_f32.to_radians());
+
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        let soft = frame(
+            &mut Software::default(),
+            &active,
+            &level,
+            &sim,
+            &camera,
+            &bank,
+        );
+        let hw = frame(&mut gpu, &active, &level, &sim, &camera, &bank);
+        let same = soft.iter().zip(&hw).filter(|(a, b)| a == b).count();
+        // the view is filled edge to edge, and only texel-boundary
+        // rounding may differ
+        assert!(!soft.contains(&0xFF_20_20_20));
+        assert!(same * 100 >= soft.len() * 97, "{same} of {}", soft.len());
+    }
+}
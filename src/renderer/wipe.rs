@@ -0,0 +1,147 @@
+//! The screen melt between scenes – vanilla `f_wipe.c` (`wipe_Melt`).
+//!
+//! Renderer-agnostic: the frontend hands over the last frame it showed,
+//! then each frame renders the new scene as usual and lets
+//! [`Wipe::compose`] slide the old picture down over it.  Columns are
+//! 2 pixels wide at 320, start after a random delay of up to 15 tics and
+//! speed up to 8 rows a tic, all in vanilla's 200-row units so the melt
+//! takes as long at any resolution.
+
+use super::Rgba;
+use crate::sim::Rng;
+
+/// Vanilla's screen height, the unit column progress is kept in.
+const ROWS: i32 = 200;
+/// Columns across a 320-wide screen.
+const COLUMNS: usize = 160;
+
+pub struct Wipe {
+    old: Vec<Rgba>,
+    w: usize,
+    h: usize,
+    /// Per column, how far (in `ROWS`) the old frame has slid; negative
+    /// while it is still waiting to start.
+    y: Vec<i32>,
+}
+
+impl Wipe {
+    /// Start melting `old`, a `w × h` frame, the start delays drawn from
+    /// a copy of `rng`: seeded from the game's, each melt differs while
+    /// the sim's sequence stays put (vanilla draws them from `M_Random`).
+    pub fn new(old: Vec<Rgba>, w: usize, h: usize, mut rng: Rng) -> Self {
+        debug_assert_eq!(old.len(), w * h);
+        let columns = COLUMNS.min(w.div_ceil(2)).max(1);
+        // wipe_initMelt: a random walk of start delays
+        let mut y = Vec::with_capacity(columns);
+        y.push(-(rng.p_random() % 16));
+        for i in 1..columns {
+            let r = rng.p_random() % 3 - 1;
+            y.push((y[i - 1] + r).clamp(-15, 0));
+        }
+        Self { old, w, h, y }
+    }
+
+    /// Run the melt `tics` tics on (vanilla `wipe_doMelt`).  `true` once
+    /// every column has slid off the screen.
+    pub fn advance(&mut self, tics: u32) -> bool {
+        for _ in 0..tics {
+            for y in &mut self.y {
+                if *y < 0 {
+                    *y += 1;
+                } else if *y < ROWS {
+                    let dy = if *y < 16 { *y + 1 } else { 8 };
+                    *y = (*y + dy).min(ROWS);
+                }
+            }
+        }
+        self.is_done()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.y.iter().all(|&y| y >= ROWS)
+    }
+
+    /// Rows of column `x` the new frame shows through at the top.
+    #[inline]
+    fn slid(&self, x: usize) -> usize {
+        let col = x * self.y.len() / self.w;
+        (self.y[col].max(0) as usize * self.h).div_ceil(ROWS as usize)
+    }
+
+    /// Write `new` (also `w × h`) with the old frame slid down over it
+    /// into `out`.
+    pub fn compose(&self, new: &[Rgba], out: &mut Vec<Rgba>) {
+        let (w, h) = (self.w, self.h);
+        out.clear();
+        out.extend_from_slice(&new[..w * h]);
+        for x in 0..w {
+            let top = self.slid(x).min(h);
+            for row in top..h {
+                out[row * w + x] = self.old[(row - top) * w + x];
+            }
+        }
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Old frame: each pixel its row number; new frame: all `NEW`.
+    const NEW: Rgba = 0xFF;
+
+    fn melt() -> Wipe {
+        let old = (0..8).flat_map(|row| [row; 8]).collect();
+        Wipe::new(old, 8, 8, Rng::new(17))
+    }
+
+    /// Per column, how many rows of the new frame show at the top.
+    fn fronts(wipe: &Wipe) -> Vec<usize> {
+        let mut out = Vec::new();
+        wipe.compose(&[NEW; 64], &mut out);
+        (0..8)
+            .map(|x| (0..8).take_while(|&y| out[y * 8 + x] == NEW).count())
+            .collect()
+    }
+
+    #[test]
+    fn starts_as_the_old_frame_in_two_pixel_columns() {
+        let wipe = melt();
+        assert_eq!(wipe.y.len(), 4);
+        assert!(wipe.y.iter().all(|y| (-15..=0).contains(y)));
+        assert!(wipe.y.windows(2).all(|p| (p[0] - p[1]).abs() <= 1));
+        let mut out = Vec::new();
+        wipe.compose(&[NEW; 64], &mut out);
+        assert_eq!(out, (0..8).flat_map(|row| [row; 8]).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn columns_fall_monotonically_and_finish() {
+        let mut wipe = melt();
+        let mut last = fronts(&wipe);
+        let mut tics = 0;
+        while !wipe.advance(1) {
+            tics += 1;
+            assert!(tics < 50, "wipe never ends");
+            let now = fronts(&wipe);
+            for x in 0..8 {
+                assert!(now[x] >= last[x], "column {x} rose: {last:?} → {now:?}");
+                // the old frame slides rather than being overwritten
+                let mut out = Vec::new();
+                wipe.compose(&[NEW; 64], &mut out);
+                for y in now[x]..8 {
+                    assert_eq!(out[y * 8 + x], (y - now[x]) as Rgba);
+                }
+            }
+            // both pixels of a column move together
+            assert!(now.chunks(2).all(|c| c[0] == c[1]));
+            last = now;
+        }
+        assert!(tics >= 20, "melted in {tics} tics");
+        assert_eq!(fronts(&wipe), [8; 8]);
+        assert!(wipe.advance(1));
+    }
+}
@@ -0,0 +1,394 @@
+//! Full-screen pictures outside a level: the title loop (vanilla
+//! `D_PageTicker` / `D_DoAdvanceDemo`, minus the demos) and the ENDOOM
+//! text shown on the way out.
+//!
+//! Pages are ordinary patches drawn straight into the frame with
+//! palette 0, independent of the 3-D renderer; [`Screen320`] places
+//! smaller patches the way vanilla's 320×200 screen would.  The border
+//! around a shrunken view ([`ViewBorder`]) is drawn the same way.
+
+use crate::renderer::{Rgba, ViewWindow};
+use crate::wad::{Wad, load_flat, load_palettes, load_patch};
+use crate::world::{Palette, Texture};
+
+/// Tics the title picture stays up (vanilla `pagetic`).
+const TITLE_TICS: u32 = 170;
+/// Doom II plays its title music for longer.
+const TITLE_TICS_COMMERCIAL: u32 = 35 * 11;
+/// Tics for every other page.
+const PAGE_TICS: u32 = 200;
+
+/// ENDOOM is an 80×25 text screen of (character, attribute) pairs.
+const ENDOOM_COLS: usize = 80;
+const ENDOOM_ROWS: usize = 25;
+
+/// Draw `pic` as large as it fits in the `w`×`h` frame, centred on black.
+/// Transparent texels stay black.
+pub fn draw_page(pic: &Texture, palette: &Palette, fb: &mut [Rgba], w: usize, h: usize) {
+    fb.fill(0xFF00_0000);
+    if pic.w == 0 || pic.h == 0 {
+        return;
+    }
+    let scale = (w as f32 / pic.w as f32).min(h as f32 / pic.h as f32);
+    let (dw, dh) = (
+        (pic.w as f32 * scale) as usize,
+        (pic.h as f32 * scale) as usize,
+    );
+    let (x0, y0) = ((w - dw) / 2, (h - dh) / 2);
+    for y in 0..dh {
+        let sy = (y * pic.h / dh).min(pic.h - 1);
+        let row = &mut fb[(y0 + y) * w + x0..][..dw];
+        for (x, p) in row.iter_mut().enumerate() {
+            let i = sy * pic.w + (x * pic.w / dw).min(pic.w - 1);
+            if pic.is_opaque(i) {
+                *p = 0xFF00_0000 | palette[pic.pixels[i] as usize];
+            }
+        }
+    }
+}
+
+/// Vanilla's 320×200 screen laid over a `w`×`h` frame, as large as it
+/// fits and centred, for drawing patches at their vanilla coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Screen320 {
+    w: usize,
+    h: usize,
+    scale: f32,
+    x0: f32,
+    y0: f32,
+}
+
+impl Screen320 {
+    pub fn new(w: usize, h: usize) -> Self {
+        let scale = (w as f32 / 320.0).min(h as f32 / 200.0);
+        Self {
+            w,
+            h,
+            scale,
+            x0: (w as f32 - 320.0 * scale) / 2.0,
+            y0: (h as f32 - 200.0 * scale) / 2.0,
+        }
+    }
+
+    /// Frame pixels per 320×200 pixel.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Frame position of the 320×200 point `(x, y)`.
+    pub fn to_frame(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            (self.x0 + x as f32 * self.scale) as i32,
+            (self.y0 + y as f32 * self.scale) as i32,
+        )
+    }
+
+    /// Draw `pic` with its top-left corner at `(x, y)`, clipped to the
+    /// frame.  Transparent texels are skipped.
+    pub fn draw_patch(&self, pic: &Texture, x: i32, y: i32, palette: &Palette, fb: &mut [Rgba]) {
+        let (fx0, fy0) = self.to_frame(x, y);
+        let (fx1, fy1) = self.to_frame(x + pic.w as i32, y + pic.h as i32);
+        let (dw, dh) = ((fx1 - fx0).max(1) as usize, (fy1 - fy0).max(1) as usize);
+        for py in fy0.max(0)..fy1.min(self.h as i32) {
+            let sy = (py - fy0) as usize * pic.h / dh;
+            let row = &mut fb[py as usize * self.w..][..self.w];
+            for px in fx0.max(0)..fx1.min(self.w as i32) {
+                let i = sy * pic.w + (px - fx0) as usize * pic.w / dw;
+                if pic.is_opaque(i) {
+                    row[px as usize] = 0xFF00_0000 | palette[pic.pixels[i] as usize];
+                }
+            }
+        }
+    }
+}
+
+/// Bevel patches around the view, in [`ViewBorder::edges`] order.
+const BORDER_PATCHES: [&str; 8] = [
+    "BRDR_T", "BRDR_B", "BRDR_L", "BRDR_R", "BRDR_TL", "BRDR_TR", "BRDR_BL", "BRDR_BR",
+];
+
+/// What vanilla fills the screen around a shrunken view with
+/// (`R_FillBackScreen`): a flat tiled from the top-left corner, and the
+/// `BRDR_*` bevel patches along the view's edges.
+pub struct ViewBorder {
+    /// FLOOR7_2, or GRNROCK in Doom II.
+    flat: Option<Texture>,
+    /// Top, bottom, left, right, then the four corners.
+    edges: [Option<Texture>; 8],
+    palette: Palette,
+}
+
+impl ViewBorder {
+    pub fn new(wad: &Wad) -> Self {
+        let commercial = wad.find_lump("MAP01").is_some();
+        let flat = if commercial { "GRNROCK" } else { "FLOOR7_2" };
+        Self {
+            flat: load_flat(wad, flat),
+            edges: BORDER_PATCHES.map(|name| load_patch(wad, name)),
+            palette: load_palettes(wad)
+                .and_then(|p| p.into_iter().next())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Fill the `w`×`h` frame outside `view` with the border, scaled
+    /// from 320×200 like the view window is.  The bevel only goes round
+    /// views narrower than the frame, like vanilla's.
+    pub fn draw(&self, view: ViewWindow, fb: &mut [Rgba], w: usize, h: usize) {
+        let (sx, sy) = (w as f32 / 320.0, h as f32 / 200.0);
+        for y in 0..h {
+            let row = &mut fb[y * w..][..w];
+            let ty = (y as f32 / sy) as usize;
+            for (x, p) in row.iter_mut().enumerate() {
+                if view.contains(x, y) {
+                    continue;
+                }
+                *p = match &self.flat {
+                    Some(flat) => {
+                        let i = (ty % flat.h) * flat.w + (x as f32 / sx) as usize % flat.w;
+                        0xFF00_0000 | self.palette[flat.pixels[i] as usize]
+                    }
+                    None => 0xFF00_0000,
+                };
+            }
+        }
+        if view.w >= w {
+            return;
+        }
+
+        // 8×8 patches in 320×200 units, stepped along each edge
+        let step = (8.0 * sx, 8.0 * sy);
+        let (x0, y0) = (view.x as f32, view.y as f32);
+        let (x1, y1) = ((view.x + view.w) as f32, (view.y + view.h) as f32);
+        let mut put = |edge: usize, x: f32, y: f32| {
+            if let Some(pic) = &self.edges[edge] {
+                self.blit(pic, (x, y), (sx, sy), fb, w, h);
+            }
+        };
+        let mut x = x0;
+        while x < x1 {
+            put(0, x, y0 - step.1);
+            put(1, x, y1);
+            x += step.0;
+        }
+        let mut y = y0;
+        while y < y1 {
+            put(2, x0 - step.0, y);
+            put(3, x1, y);
+            y += step.1;
+        }
+        put(4, x0 - step.0, y0 - step.1);
+        put(5, x1, y0 - step.1);
+        put(6, x0 - step.0, y1);
+        put(7, x1, y1);
+    }
+
+    /// Draw `pic` `scale`d with its top-left corner at frame `at`,
+    /// clipped to the frame.
+    fn blit(
+        &self,
+        pic: &Texture,
+        at: (f32, f32),
+        scale: (f32, f32),
+        fb: &mut [Rgba],
+        w: usize,
+        h: usize,
+    ) {
+        let (fx0, fy0) = (at.0.round() as i32, at.1.round() as i32);
+        let fx1 = (at.0 + pic.w as f32 * scale.0).round() as i32;
+        let fy1 = (at.1 + pic.h as f32 * scale.1).round() as i32;
+        let (dw, dh) = ((fx1 - fx0).max(1) as usize, (fy1 - fy0).max(1) as usize);
+        for py in fy0.max(0)..fy1.min(h as i32) {
+            let sy = (py - fy0) as usize * pic.h / dh;
+            for px in fx0.max(0)..fx1.min(w as i32) {
+                let i = sy * pic.w + (px - fx0) as usize * pic.w / dw;
+                if pic.is_opaque(i) {
+                    fb[py as usize * w + px as usize] =
+                        0xFF00_0000 | self.palette[pic.pixels[i] as usize];
+                }
+            }
+        }
+    }
+}
+
+/// The attract loop: TITLEPIC, CREDIT and the help screen in turn, each
+/// for its vanilla page time.  Pages the WAD lacks are skipped.
+pub struct TitleLoop {
+    pages: Vec<(Texture, u32)>,
+    palette: Palette,
+    page: usize,
+    /// Tics until the next page (vanilla `pagetic`).
+    tics_left: u32,
+}
+
+impl TitleLoop {
+    pub fn new(wad: &Wad) -> Self {
+        let commercial = wad.find_lump("MAP01").is_some();
+        let title_tics = if commercial {
+            TITLE_TICS_COMMERCIAL
+        } else {
+            TITLE_TICS
+        };
+        let pages: Vec<_> = [
+            ("TITLEPIC", title_tics),
+            ("CREDIT", PAGE_TICS),
+            ("HELP2", PAGE_TICS),
+        ]
+        .into_iter()
+        .filter_map(|(name, tics)| Some((load_patch(wad, name)?, tics)))
+        .collect();
+        let palette = load_palettes(wad)
+            .and_then(|p| p.into_iter().next())
+            .unwrap_or_default();
+        let tics_left = pages.first().map_or(0, |p| p.1);
+        Self {
+            pages,
+            palette,
+            page: 0,
+            tics_left,
+        }
+    }
+
+    /// Run one 35 Hz tic; moves on to the next page when this one's time
+    /// is up.
+    pub fn ticker(&mut self) {
+        self.tics_left = self.tics_left.saturating_sub(1);
+        if self.tics_left == 0 && !self.pages.is_empty() {
+            self.page = (self.page + 1) % self.pages.len();
+            self.tics_left = self.pages[self.page].1;
+        }
+    }
+
+    /// The page on screen, if the WAD has any.
+    pub fn current(&self) -> Option<&Texture> {
+        self.pages.get(self.page).map(|p| &p.0)
+    }
+
+    /// Draw the current page letterboxed into the frame.
+    pub fn draw(&self, fb: &mut [Rgba], w: usize, h: usize) {
+        match self.current() {
+            Some(pic) => draw_page(pic, &self.palette, fb, w, h),
+            None => fb.fill(0xFF00_0000),
+        }
+    }
+}
+
+/// The ENDOOM screen as plain text, one line per row with trailing blanks
+/// trimmed.  Colours are dropped; the block and line characters of code
+/// page 437 that exit screens use are kept.
+pub fn endoom_text(wad: &Wad) -> Option<String> {
+    let bytes = wad.lump_bytes(wad.find_lump("ENDOOM")?).ok()?;
+    if bytes.len() < ENDOOM_COLS * ENDOOM_ROWS * 2 {
+        return None;
+    }
+    let mut out = String::new();
+    for row in bytes.chunks_exact(ENDOOM_COLS * 2).take(ENDOOM_ROWS) {
+        let line: String = row.chunks_exact(2).map(|c| cp437(c[0])).collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    Some(out)
+}
+
+/// A code page 437 byte as Unicode; unknown graphics become blanks.
+fn cp437(b: u8) -> char {
+    match b {
+        0x20..=0x7E => b as char,
+        0xB0 => '░',
+        0xB1 => '▒',
+        0xB2 => '▓',
+        0xB3 => '│',
+        0xBA => '║',
+        0xC4 => '─',
+        0xCD => '═',
+        0xDB => '█',
+        0xDC => '▄',
+        0xDD => '▌',
+        0xDE => '▐',
+        0xDF => '▀',
+        0xFE => '■',
+        _ => ' ',
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::encode_patch;
+
+    /// 2×1 patch whose right texel is transparent.
+    fn half_patch(c: u8) -> Vec<u8> {
+        encode_patch(2, 1, (0, 0), |x, _| (x == 0).then_some(c))
+    }
+
+    #[test]
+    fn title_cycles_pages_it_has() {
+        let mut playpal = vec![0u8; 768];
+        playpal[3..6].copy_from_slice(&[0x10, 0x20, 0x30]);
+        let wad = Wad::from_lumps(&[
+            ("PLAYPAL", playpal),
+            ("TITLEPIC", half_patch(1)),
+            ("CREDIT", half_patch(2)),
+        ]);
+        let mut title = TitleLoop::new(&wad);
+        assert_eq!(title.current().unwrap().name, "TITLEPIC");
+        for _ in 0..TITLE_TICS {
+            title.ticker();
+        }
+        assert_eq!(title.current().unwrap().name, "CREDIT");
+        for _ in 0..PAGE_TICS {
+            title.ticker();
+        }
+        assert_eq!(title.current().unwrap().name, "TITLEPIC");
+
+        // 2×1 into 8×8: 4 pixels per texel, rows 2‥5, right half black
+        let mut fb = vec![0; 64];
+        title.draw(&mut fb, 8, 8);
+        assert_eq!(fb[2 * 8], 0xFF10_2030);
+        assert_eq!(fb[5 * 8 + 3], 0xFF10_2030);
+        assert_eq!(fb[2 * 8 + 4], 0xFF00_0000);
+        assert_eq!(fb[0], 0xFF00_0000);
+    }
+
+    #[test]
+    fn border_tiles_the_flat_around_the_view() {
+        let mut playpal = vec![0u8; 768];
+        playpal[3..6].copy_from_slice(&[0x10, 0x20, 0x30]);
+        playpal[6..9].copy_from_slice(&[0x40, 0x50, 0x60]);
+        // flat texel 1 in the left half, 2 in the right
+        let flat: Vec<u8> = (0..4096).map(|i| if i % 64 < 32 { 1 } else { 2 }).collect();
+        let wad = Wad::from_lumps(&[("PLAYPAL", playpal), ("FLOOR7_2", flat)]);
+        let border = ViewBorder::new(&wad);
+
+        // 640×400: two frame pixels per flat texel
+        let (w, h) = (640, 400);
+        let view = ViewWindow::for_screenblocks(9, w, h);
+        let mut fb = vec![7; w * h];
+        border.draw(view, &mut fb, w, h);
+        assert_eq!(fb[0], 0xFF10_2030);
+        assert_eq!(fb[63], 0xFF10_2030);
+        assert_eq!(fb[64], 0xFF40_5060);
+        assert_eq!(fb[128], 0xFF10_2030, "tiles every 64 texels");
+        // the view itself is left alone
+        assert_eq!(fb[view.y * w + view.x], 7);
+        assert_eq!(fb[(view.y + view.h - 1) * w + view.x + view.w - 1], 7);
+        assert_ne!(fb[(view.y + view.h) * w + view.x], 7);
+    }
+
+    #[test]
+    fn endoom_becomes_text() {
+        let mut endoom = vec![0u8; 4000];
+        for (i, c) in b"Bye".iter().enumerate() {
+            endoom[i * 2] = *c;
+        }
+        endoom[ENDOOM_COLS * 2] = 0xDB;
+        let wad = Wad::from_lumps(&[("ENDOOM", endoom)]);
+        let text = endoom_text(&wad).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), ENDOOM_ROWS);
+        assert_eq!((lines[0], lines[1], lines[2]), ("Bye", "█", ""));
+    }
+}
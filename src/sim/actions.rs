@@ -0,0 +1,127 @@
+//! Codepointers – the `action` of a state, run when a mobj enters it
+//! (vanilla `P_SetMobjState` → `st->action`).
+//!
+//! Only a handful are ported; the rest are no-ops until their systems
+//! exist.
+
+use hecs::{Entity, World};
+
+use super::events::SimEvent;
+use super::{ActorFlags, Class, GameRules, Position, Rng, Target, ThingGrid, damage, enemy, mob};
+use crate::defs::{Action, Definitions, MobjFlags, Sound};
+use crate::world::Level;
+
+/// Blast damage and radius of `A_Explode` (barrels, rockets).
+const EXPLODE_DAMAGE: i32 = 128;
+
+/// Run `action` for `ent`, which has just entered the state carrying it.
+#[allow(clippy::too_many_arguments)]
+pub fn call_action(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    ent: Entity,
+    action: Action,
+) {
+    match action {
+        Action::Explode => a_explode(world, thing_grid, level, rng, rules, defs, ent),
+        Action::Scream => a_scream(world, rng, events, ent),
+        Action::XScream => enemy::sound(world, events, ent, Sound::slop),
+        Action::Pain => a_pain(world, events, ent),
+        Action::Chase => enemy::a_chase(world, rng, events, ent),
+        Action::Fall => a_fall(world, thing_grid, level, rng, defs, ent),
+        Action::FaceTarget => enemy::a_face_target(world, rng, ent),
+        Action::PosAttack => {
+            enemy::a_pos_attack(world, thing_grid, level, rng, rules, defs, events, ent)
+        }
+        Action::SargAttack => enemy::a_sarg_attack(world, level, rng, rules, defs, ent),
+        Action::SkullAttack => enemy::a_skull_attack(world, rng, events, ent),
+        _ => {}
+    }
+}
+
+/// Vanilla `A_Explode`.
+fn a_explode(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    ent: Entity,
+) {
+    let source = world.get::<&Target>(ent).ok().map(|t| t.0);
+    damage::p_radius_attack(
+        world,
+        thing_grid,
+        level,
+        rng,
+        rules,
+        defs,
+        ent,
+        source,
+        EXPLODE_DAMAGE,
+    );
+}
+
+/// Vanilla `A_Fall`: the body is on the ground now, so it can be walked
+/// over.  Whatever it carried falls out with it.
+fn a_fall(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    defs: &Definitions,
+    ent: Entity,
+) {
+    if let Ok(mut flags) = world.get::<&mut ActorFlags>(ent) {
+        flags.0.remove(MobjFlags::SOLID);
+    }
+    mob::drop_item(world, thing_grid, level, rng, defs, ent);
+}
+
+/// Vanilla `A_Scream`: the death sound, one of the zombie or imp
+/// variants picked at random; the bosses are heard map-wide.
+fn a_scream(world: &World, rng: &mut Rng, events: &mut Vec<SimEvent>, ent: Entity) {
+    let Ok(mut q) = world.query_one::<(&Position, &Class)>(ent) else {
+        return;
+    };
+    let Some((pos, class)) = q.get() else {
+        return;
+    };
+    let sound = match class.0.deathsound {
+        Sound::None => return,
+        Sound::podth1 | Sound::podth2 | Sound::podth3 => {
+            [Sound::podth1, Sound::podth2, Sound::podth3][(rng.p_random() % 3) as usize]
+        }
+        Sound::bgdth1 | Sound::bgdth2 => {
+            [Sound::bgdth1, Sound::bgdth2][(rng.p_random() % 2) as usize]
+        }
+        sound => sound,
+    };
+    events.push(SimEvent::Sound {
+        sound,
+        origin: pos.0,
+        full_volume: is_boss(class),
+    });
+}
+
+/// Vanilla `A_Pain`.
+fn a_pain(world: &World, events: &mut Vec<SimEvent>, ent: Entity) {
+    let sound = world
+        .get::<&Class>(ent)
+        .map_or(Sound::None, |c| c.0.painsound);
+    if !matches!(sound, Sound::None) {
+        enemy::sound(world, events, ent, sound);
+    }
+}
+
+/// The Spider Mastermind and the Cyberdemon, whose sight and death cries
+/// carry across the map.
+pub(super) fn is_boss(class: &Class) -> bool {
+    matches!(class.0.id, "SPIDER" | "CYBORG")
+}
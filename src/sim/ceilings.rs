@@ -0,0 +1,270 @@
+//! Ceiling movers – vanilla `p_ceilng.c`.
+//!
+//! Only the crushers are ported: a crusher ceiling comes down to 8 units
+//! above the floor, goes back up and repeats until a stop line puts it in
+//! stasis.  Whatever it lands on is handled by
+//! [`p_change_sector`](super::movers::p_change_sector).
+
+use hecs::World;
+
+use super::doors::Door;
+use super::events::SimEvent;
+use super::floors::Floor;
+use super::movers::{MoveResult, sector_busy, t_move_ceiling};
+use super::plats::Platform;
+use super::{GameRules, PhysicsConfig, Rng, ThingGrid};
+use crate::defs::{Definitions, Sound};
+use crate::world::{Level, SectorId};
+
+/// Crusher speed in units per tic (vanilla `CEILSPEED`).
+pub const CEIL_SPEED: f32 = 1.0;
+
+/// Gap a crusher leaves above the floor.
+const CRUSH_GAP: f32 = 8.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CeilingKind {
+    /// Down at `CEIL_SPEED`, slowing to an eighth while crushing; up again.
+    CrushAndRaise,
+    /// Down and up at twice `CEIL_SPEED`, never slowing.
+    FastCrushAndRaise,
+}
+
+/// One running ceiling mover (vanilla `ceiling_t`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ceiling {
+    pub sector: SectorId,
+    pub kind: CeilingKind,
+    pub bottom: f32,
+    pub top: f32,
+    pub speed: f32,
+    pub crush: bool,
+    /// 1 up, -1 down, 0 in stasis.
+    pub direction: i32,
+    /// Where to go when a stasis ends.
+    pub old_direction: i32,
+    pub tag: u16,
+}
+
+/// Vanilla `EV_DoCeiling`: start a `kind` mover in every idle sector
+/// tagged `tag`.  Stopped crushers with the tag start up again.  `true`
+/// if anything moves now.
+pub fn ev_do_ceiling(
+    level: &Level,
+    ceilings: &mut Vec<Ceiling>,
+    doors: &[Door],
+    plats: &[Platform],
+    floors: &[Floor],
+    tag: u16,
+    kind: CeilingKind,
+) -> bool {
+    // reactivate in-stasis ceilings… for certain types
+    let mut rtn = p_activate_in_stasis_ceiling(ceilings, tag);
+
+    for (id, sector) in level.sectors.iter().enumerate() {
+        let id = id as SectorId;
+        if sector.tag as u16 != tag || sector_busy(doors, ceilings, plats, floors, id) {
+            continue;
+        }
+        rtn = true;
+        let speed = match kind {
+            CeilingKind::CrushAndRaise => CEIL_SPEED,
+            CeilingKind::FastCrushAndRaise => CEIL_SPEED * 2.0,
+        };
+        ceilings.push(Ceiling {
+            sector: id,
+            kind,
+            bottom: sector.floor_h + CRUSH_GAP,
+            top: sector.ceil_h,
+            speed,
+            crush: true,
+            direction: -1,
+            old_direction: -1,
+            tag,
+        });
+    }
+    rtn
+}
+
+/// Vanilla `P_ActivateInStasisCeiling`.
+fn p_activate_in_stasis_ceiling(ceilings: &mut [Ceiling], tag: u16) -> bool {
+    let mut rtn = false;
+    for c in ceilings.iter_mut() {
+        if c.tag == tag && c.direction == 0 {
+            c.direction = c.old_direction;
+            rtn = true;
+        }
+    }
+    rtn
+}
+
+/// Vanilla `EV_CeilingCrushStop`: freeze every moving crusher tagged
+/// `tag`.
+pub fn ev_ceiling_crush_stop(ceilings: &mut [Ceiling], tag: u16) -> bool {
+    let mut rtn = false;
+    for c in ceilings.iter_mut() {
+        if c.tag == tag && c.direction != 0 {
+            c.old_direction = c.direction;
+            c.direction = 0;
+            rtn = true;
+        }
+    }
+    rtn
+}
+
+/// Advance every ceiling one tic (vanilla `T_MoveCeiling`).
+#[allow(clippy::too_many_arguments)]
+pub fn run_ceilings(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    gametic: u32,
+    ceilings: &mut [Ceiling],
+) {
+    for c in ceilings.iter_mut() {
+        if c.direction == 0 {
+            continue; // in stasis
+        }
+        let dest = if c.direction > 0 { c.top } else { c.bottom };
+        let res = t_move_ceiling(
+            world,
+            thing_grid,
+            level,
+            cfg,
+            rng,
+            rules,
+            defs,
+            gametic,
+            c.sector,
+            c.speed,
+            dest,
+            c.crush && c.direction < 0,
+            c.direction,
+        );
+
+        if gametic & 7 == 0 {
+            events.push(SimEvent::Sound {
+                sound: Sound::stnmov,
+                origin: level.sound_origin(c.sector),
+                full_volume: false,
+            });
+        }
+
+        match (c.direction, res) {
+            (1, MoveResult::PastDest) => c.direction = -1,
+            (-1, MoveResult::PastDest) => {
+                if c.kind == CeilingKind::CrushAndRaise {
+                    c.speed = CEIL_SPEED;
+                }
+                c.direction = 1;
+            }
+            (-1, MoveResult::Crushed) if c.kind == CeilingKind::CrushAndRaise => {
+                c.speed = CEIL_SPEED / 8.0;
+            }
+            _ => {}
+        }
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::{self, MobjFlags},
+        sim::{ActorFlags, Angle, InputCmd, TicRunner},
+        world::testmap,
+    };
+
+    #[test]
+    fn crusher_switch_destroys_barrel() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        level.sectors[1].tag = 3;
+        // room A's west wall, facing the player
+        let west = level
+            .linedefs
+            .iter()
+            .position(|l| (l.v1, l.v2) == (0, 4))
+            .unwrap();
+        level.linedefs[west].special = 49;
+        level.linedefs[west].tag = 3;
+
+        let mut sim = TicRunner::new(&level);
+        let barrel = sim.spawn_mobj(&level, defs::by_id("BARREL").unwrap(), 136.0, 64.0, 0.0, 1);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 40.0, 64.0, 0.0, 0);
+        *sim.world_mut().get::<&mut Angle>(player).unwrap() = Angle::ANG180;
+        sim.set_player(player);
+
+        let press = InputCmd {
+            use_act: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(&mut level, press);
+        assert_eq!(sim.ceilings().len(), 1);
+
+        for _ in 0..200 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let gone = sim
+            .world()
+            .get::<&ActorFlags>(barrel)
+            .map_or(true, |f| f.0.contains(MobjFlags::CORPSE));
+        assert!(gone, "barrel survived the crusher");
+        assert!(level.sectors[1].ceil_h < 128.0);
+    }
+
+    #[test]
+    fn stop_line_freezes_and_restarts() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        level.sectors[1].tag = 5;
+        let mut ceilings = Vec::new();
+
+        assert!(ev_do_ceiling(
+            &level,
+            &mut ceilings,
+            &[],
+            &[],
+            &[],
+            5,
+            CeilingKind::FastCrushAndRaise
+        ));
+        assert!(!ev_do_ceiling(
+            &level,
+            &mut ceilings,
+            &[],
+            &[],
+            &[],
+            5,
+            CeilingKind::CrushAndRaise
+        ));
+        assert_eq!(ceilings[0].speed, 2.0 * CEIL_SPEED);
+        assert_eq!((ceilings[0].bottom, ceilings[0].top), (8.0, 128.0));
+
+        assert!(ev_ceiling_crush_stop(&mut ceilings, 5));
+        assert_eq!(ceilings[0].direction, 0);
+        assert!(ev_do_ceiling(
+            &level,
+            &mut ceilings,
+            &[],
+            &[],
+            &[],
+            5,
+            CeilingKind::CrushAndRaise
+        ));
+        assert_eq!(ceilings[0].direction, -1);
+        assert_eq!(ceilings.len(), 1);
+    }
+}
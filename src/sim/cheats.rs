@@ -0,0 +1,130 @@
+//! Debug cheats: noclip, god mode and free flight.
+//!
+//! The cheat bits live in a `Cheats` component on the player; the ones
+//! that change collision are mirrored into `ActorFlags` (`NOCLIP`,
+//! `NOGRAVITY`) so the movement code only ever looks at mobj flags.
+
+use hecs::{Entity, World};
+
+use super::{ActorFlags, CheatFlags, Cheats, Class, Position, Subsector, Velocity};
+use crate::defs::MobjFlags;
+use crate::world::Level;
+
+/// Flip `cheat` on `ent` and return whether it is now on, or `None` if
+/// `ent` isn't a live mobj.
+pub fn toggle_cheat(world: &mut World, ent: Entity, cheat: CheatFlags) -> Option<bool> {
+    if world.get::<&Cheats>(ent).is_err() {
+        world.insert_one(ent, Cheats::default()).ok()?;
+    }
+
+    let (cheats, flags, class) = world
+        .query_one_mut::<(&mut Cheats, &mut ActorFlags, &Class)>(ent)
+        .ok()?;
+
+    cheats.0.toggle(cheat);
+    let on = cheats.0.contains(cheat);
+
+    let mobj_flag = if cheat == CheatFlags::NOCLIP {
+        MobjFlags::NOCLIP
+    } else if cheat == CheatFlags::FLY {
+        MobjFlags::NOGRAVITY
+    } else {
+        return Some(on);
+    };
+    // the class may float anyway – only take away what the cheat added
+    flags
+        .0
+        .set(mobj_flag, on || class.0.flags.contains(mobj_flag));
+    Some(on)
+}
+
+/// Vertical half of flight: apply the z momentum `player_input` set from
+/// `InputCmd::fly`, kept between the floor and the ceiling.
+pub fn fly_movement(world: &mut World, level: &Level) {
+    for (_, (cheats, pos, vel, class, ss)) in
+        world.query_mut::<(&Cheats, &mut Position, &Velocity, &Class, &Subsector)>()
+    {
+        if !cheats.0.contains(CheatFlags::FLY) || vel.0.z == 0.0 {
+            continue;
+        }
+        let sector = &level.sectors[level.subsectors[ss.0 as usize].sector as usize];
+        pos.1 = (pos.1 + vel.0.z)
+            .min(sector.ceil_h - class.0.height as f32)
+            .max(sector.floor_h);
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{InputCmd, TicRunner},
+        world::testmap,
+    };
+
+    fn walk_east(level: &mut Level, sim: &mut TicRunner, player: Entity, tics: usize) -> f32 {
+        let walk = InputCmd {
+            forward: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..tics {
+            sim.run_tic(level, walk);
+        }
+        sim.world().get::<&Position>(player).unwrap().0.x
+    }
+
+    #[test]
+    fn noclip_walks_through_walls() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+
+        assert!(walk_east(&mut level, &mut sim, player, 20) < 128.0);
+
+        assert_eq!(sim.toggle_cheat(CheatFlags::NOCLIP), Some(true));
+        // a few tics take it into room B without leaving the map
+        let x = walk_east(&mut level, &mut sim, player, 10);
+        assert!(x > 144.0 && x < 272.0, "x = {x}");
+
+        assert_eq!(sim.toggle_cheat(CheatFlags::NOCLIP), Some(false));
+        let flags = sim.world().get::<&ActorFlags>(player).unwrap().0;
+        assert!(!flags.contains(MobjFlags::NOCLIP));
+    }
+
+    #[test]
+    fn fly_rises_and_stops_at_the_ceiling() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+
+        let up = InputCmd {
+            fly: 1.0,
+            ..Default::default()
+        };
+        sim.run_tic(&mut level, up);
+        assert_eq!(sim.world().get::<&Position>(player).unwrap().1, 0.0);
+
+        sim.toggle_cheat(CheatFlags::FLY);
+        for _ in 0..100 {
+            sim.run_tic(&mut level, up);
+        }
+        let height = defs::by_id("PLAYER").unwrap().height as f32;
+        assert_eq!(
+            sim.world().get::<&Position>(player).unwrap().1,
+            128.0 - height
+        );
+
+        // walking doesn't drop a flyer back onto the floor
+        walk_east(&mut level, &mut sim, player, 5);
+        assert_eq!(
+            sim.world().get::<&Position>(player).unwrap().1,
+            128.0 - height
+        );
+    }
+}
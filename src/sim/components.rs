@@ -1,7 +1,9 @@
+use bitflags::bitflags;
 use glam::{Vec2, Vec3};
 
-use crate::defs::{MobjFlags, MobjInfo, State};
-use crate::world::SubsectorId;
+use super::systems::VIEW_HEIGHT;
+use crate::defs::{Definitions, MobjFlags, MobjInfo, State};
+use crate::world::{INVERSE_COLORMAP, PaletteEffect, SubsectorId, Thing};
 
 /// World‑space position.  z is separate to match Doom’s 2½‑D maths.
 #[derive(Debug, Clone, Copy)]
@@ -18,14 +20,19 @@ impl Velocity {
     }
 }
 
+/// Which way a thing faces.
+pub use crate::world::Angle;
+
+/// Where a thing stood and faced when the current tic began; the renderer
+/// blends from here to `Position` / `Angle` between tics.
 #[derive(Debug, Clone, Copy)]
-pub struct Angle(pub f32);
+pub struct PrevPosition(pub Position, pub Angle);
 
 #[derive(Debug, Clone, Copy)]
 pub struct Subsector(pub SubsectorId);
 
 #[derive(Debug, Copy, Clone)]
-pub struct Class(pub &'static MobjInfo);
+pub struct Class(pub MobjInfo);
 
 #[derive(Debug, Clone, Copy)]
 pub struct Animation {
@@ -33,6 +40,70 @@ pub struct Animation {
     pub tics: i32,
 }
 
+/// Hit points left (vanilla `mo->health`); starts at `spawnhealth`.
+#[derive(Debug, Clone, Copy)]
+pub struct Health(pub i32);
+
+/// Tics left before the actor may act again (vanilla `mo->reactiontime`).
+/// Players get one after teleporting and can't move until it runs out.
+#[derive(Debug, Clone, Copy)]
+pub struct ReactionTime(pub i32);
+
+/// Which way a walking monster is headed (vanilla `dirtype_t`, kept in
+/// `mo->movedir`).  A monster without one has `NoDir` and stays put.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoveDir {
+    East,
+    NorthEast,
+    North,
+    NorthWest,
+    West,
+    SouthWest,
+    South,
+    SouthEast,
+    #[default]
+    NoDir,
+}
+
+impl MoveDir {
+    /// Vanilla `xspeed` / `yspeed`: a step of one unit of speed this way;
+    /// diagonals go 47000/65536 along each axis.
+    pub fn step(self) -> Vec2 {
+        const D: f32 = 47000.0 / 65536.0;
+        match self {
+            Self::East => Vec2::new(1.0, 0.0),
+            Self::NorthEast => Vec2::new(D, D),
+            Self::North => Vec2::new(0.0, 1.0),
+            Self::NorthWest => Vec2::new(-D, D),
+            Self::West => Vec2::new(-1.0, 0.0),
+            Self::SouthWest => Vec2::new(-D, -D),
+            Self::South => Vec2::new(0.0, -1.0),
+            Self::SouthEast => Vec2::new(D, -D),
+            Self::NoDir => Vec2::ZERO,
+        }
+    }
+}
+
+/// Steps left before a walking monster picks a new heading (vanilla
+/// `mo->movecount`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MoveCount(pub i32);
+
+/// The map `THINGS` entry a thing was spawned from (vanilla
+/// `mo->spawnpoint`); a Nightmare monster comes back there.
+#[derive(Debug, Clone)]
+pub struct SpawnPoint(pub Thing);
+
+/// Who a monster is after; on a missile, who fired it (vanilla
+/// `mo->target`).  Not saved: vanilla drops it on load too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target(pub hecs::Entity);
+
+/// Who last hurt a player (vanilla `player->attacker`); a dying player's
+/// view turns to face it.  Not saved, like `Target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attacker(pub hecs::Entity);
+
 /// Player-size flag wrapper – fill in later
 #[derive(Clone, Copy, Debug)]
 pub struct ActorFlags(pub MobjFlags);
@@ -46,4 +117,198 @@ pub struct InputCmd {
     pub fire: bool,         // Ctrl
     pub use_act: bool,      // Space
     pub weapon: Option<u8>, // 1-7 if pressed this tic
+    pub fly: f32,           // –1 … +1  (down / up, `fly` cheat only)
+}
+
+bitflags! {
+    /// Debug cheats active on a player (vanilla `player->cheats`).
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct CheatFlags: u8 {
+        /// Walk through walls and things (`MF_NOCLIP`).
+        const NOCLIP = 0x01;
+        /// Ignore damage.
+        const GOD    = 0x02;
+        /// No gravity; `InputCmd::fly` moves up and down.
+        const FLY    = 0x04;
+    }
+}
+
+/// Cheats toggled from the console; only ever on the player.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Cheats(pub CheatFlags);
+
+/*──────────────────────────── Player ───────────────────────────────*/
+
+/// Ammo kinds, in vanilla `ammotype_t` order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmmoType {
+    Clip,
+    Shell,
+    Cell,
+    Misl,
+}
+
+pub const NUM_AMMO: usize = 4;
+
+/// Powerups, in vanilla `powertype_t` order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Power {
+    Invulnerability,
+    Strength,
+    Invisibility,
+    IronFeet,
+    AllMap,
+    Infrared,
+}
+
+pub const NUM_POWERS: usize = 6;
+
+/* PLAYPAL layout (vanilla st_stuff.c) */
+const START_RED_PALS: i32 = 1;
+const NUM_RED_PALS: i32 = 8;
+const START_BONUS_PALS: i32 = 9;
+const NUM_BONUS_PALS: i32 = 4;
+const RADIATION_PAL: i32 = 13;
+
+/// Colormap row light-amp goggles draw everything with: full brightness.
+const LIGHT_AMP_COLORMAP: u8 = 0;
+
+bitflags! {
+    /// Keycards and skull keys held (vanilla `player->cards`).
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Keys: u8 {
+        const BLUE_CARD    = 0x01;
+        const YELLOW_CARD  = 0x02;
+        const RED_CARD     = 0x04;
+        const BLUE_SKULL   = 0x08;
+        const YELLOW_SKULL = 0x10;
+        const RED_SKULL    = 0x20;
+    }
+}
+
+/// What the player is carrying (the inventory half of vanilla `player_t`).
+/// `health` mirrors the mobj's `Health` whenever a pickup changes it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayerStatus {
+    pub health: i32,
+    pub armor: i32,
+    /// 0 = none, 1 = green (absorbs 1/3), 2 = blue (absorbs 1/2).
+    pub armor_type: u8,
+    pub ammo: [i32; NUM_AMMO],
+    pub max_ammo: [i32; NUM_AMMO],
+    pub backpack: bool,
+    pub keys: Keys,
+    /// Tics left per power; strength and the computer map just hold 1.
+    pub powers: [i32; NUM_POWERS],
+    /// Tics of yellow palette flash left after a pickup.
+    pub bonus_count: i32,
+    /// Red palette flash from recent damage; grows with the hit, capped
+    /// at 100, and fades one per tic.
+    pub damage_count: i32,
+    /// Monsters killed this level.
+    pub kill_count: i32,
+    /// Items with `COUNTITEM` picked up this level.
+    pub item_count: i32,
+    /// Secret sectors entered this level.
+    pub secret_count: i32,
+    /// Eye height above the feet, bob aside (vanilla `viewheight`); dips
+    /// on landing and eases back to `VIEW_HEIGHT`.
+    pub view_height: f32,
+    /// Per-tic change of `view_height` (vanilla `deltaviewheight`).
+    pub delta_view_height: f32,
+    /// View bob amplitude from the current speed (vanilla `bob`).
+    pub bob: f32,
+    /// Eye z this tic (vanilla `viewz`).
+    pub view_z: f32,
+    /// What's left of the nudge toward the last attacker, in signed BAM;
+    /// paid out a little a tic on top of the player's own turning.
+    pub turn_hint: i32,
+}
+
+impl Default for PlayerStatus {
+    /// [`PlayerStatus::new`] by the compiled-in tables.
+    fn default() -> Self {
+        Self::new(&Definitions::shared_builtin())
+    }
+}
+
+impl PlayerStatus {
+    /// A fresh player: full health, a pistol's 50 bullets (vanilla
+    /// `G_PlayerReborn`), or what a patch made of them.
+    pub fn new(defs: &Definitions) -> Self {
+        Self {
+            health: defs.misc.initial_health,
+            armor: 0,
+            armor_type: 0,
+            ammo: [defs.misc.initial_bullets, 0, 0, 0],
+            max_ammo: defs.max_ammo,
+            backpack: false,
+            keys: Keys::empty(),
+            powers: [0; NUM_POWERS],
+            bonus_count: 0,
+            damage_count: 0,
+            kill_count: 0,
+            item_count: 0,
+            secret_count: 0,
+            view_height: VIEW_HEIGHT,
+            delta_view_height: 0.0,
+            bob: 0.0,
+            view_z: VIEW_HEIGHT,
+            turn_hint: 0,
+        }
+    }
+
+    /// What carries over into the next map (vanilla `G_PlayerFinishLevel`):
+    /// keys, powers and the level tallies go, health and ammo stay.
+    pub fn finish_level(&mut self) {
+        self.keys = Keys::empty();
+        self.powers = [0; NUM_POWERS];
+        self.bonus_count = 0;
+        self.damage_count = 0;
+        self.kill_count = 0;
+        self.item_count = 0;
+        self.secret_count = 0;
+    }
+
+    /// Is `power` on and not in its last four seconds of blinking off?
+    /// (The `& 8` makes an expiring power flicker.)
+    fn power_showing(&self, power: Power) -> bool {
+        let left = self.powers[power as usize];
+        left > 4 * 32 || left & 8 != 0
+    }
+
+    /// Screen tint for this status (vanilla `ST_doPaletteStuff`): red for
+    /// damage or berserk, else yellow after a pickup, else green in a
+    /// radiation suit.  Invulnerability swaps in the inverse colormap and
+    /// light amp the brightest row (vanilla `P_PlayerThink`), both
+    /// blinking off as they run out.
+    pub fn palette_effect(&self) -> PaletteEffect {
+        let mut cnt = self.damage_count;
+        if self.powers[Power::Strength as usize] != 0 {
+            // slowly fade the berserk out
+            cnt = cnt.max(12 - (self.powers[Power::Strength as usize] >> 6));
+        }
+
+        let palette = if cnt > 0 {
+            START_RED_PALS + ((cnt + 7) >> 3).min(NUM_RED_PALS - 1)
+        } else if self.bonus_count > 0 {
+            START_BONUS_PALS + ((self.bonus_count + 7) >> 3).min(NUM_BONUS_PALS - 1)
+        } else if self.power_showing(Power::IronFeet) {
+            RADIATION_PAL
+        } else {
+            0
+        };
+        // an expiring invulnerability hides the goggles too
+        let fixed_colormap = if self.powers[Power::Invulnerability as usize] != 0 {
+            self.power_showing(Power::Invulnerability)
+                .then_some(INVERSE_COLORMAP)
+        } else {
+            self.power_showing(Power::Infrared)
+                .then_some(LIGHT_AMP_COLORMAP)
+        };
+        PaletteEffect {
+            palette: palette as u8,
+            fixed_colormap,
+        }
+    }
 }
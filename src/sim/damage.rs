@@ -0,0 +1,497 @@
+//! Damage – vanilla `P_DamageMobj` (p_inter.c) and `P_RadiusAttack`
+//! (p_map.c).
+
+use glam::{Vec2, Vec3};
+use hecs::{Entity, World};
+use smallvec::SmallVec;
+
+use super::spacial::fetch_thing;
+use super::{
+    ActorFlags, Angle, Animation, Attacker, CheatFlags, Cheats, Class, GameRules, Health,
+    PlayerStatus, Position, Power, ReactionTime, Rng, Target, ThingGrid, Velocity, WeaponType,
+    Weapons, mob,
+};
+use crate::defs::{Definitions, MobjFlags, MobjInfo, State};
+use crate::world::{Aabb, Level, angle_to};
+
+/// Largest thing radius; widens the blockmap search (vanilla `MAXRADIUS`).
+pub(super) const MAX_RADIUS: f32 = 32.0;
+
+/// Damage at or above this ignores god mode (telefrags).
+const UNSTOPPABLE: i32 = 1000;
+
+/// Furthest a hit turns the player toward whoever landed it.
+pub const TURN_HINT_MAX: i32 = (Angle::ANG45.0 / 9) as i32;
+
+/// Vanilla `P_DamageMobj`: hurt `target` by `damage`, push it away from
+/// `inflictor` (the missile, puff or exploding barrel) unless `source` is
+/// sawing at it, and kill it once its health runs out.  `source` is
+/// whoever is to blame.  Pain and see states last as long as `rules` say.
+#[allow(clippy::too_many_arguments)]
+pub fn p_damage_mobj(
+    world: &mut World,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    target: Entity,
+    inflictor: Option<Entity>,
+    source: Option<Entity>,
+    mut damage: i32,
+) {
+    let from = inflictor
+        .and_then(|i| world.get::<&Position>(i).ok().map(|p| *p))
+        .map(|p| (p.0, p.1));
+    let god = world
+        .get::<&Cheats>(target)
+        .is_ok_and(|c| c.0.contains(CheatFlags::GOD));
+    let blame = source
+        .filter(|&s| s != target)
+        .and_then(|s| world.get::<&Position>(s).ok().map(|p| p.0));
+    // the chainsaw holds its victim instead of pushing it away
+    let sawing = source
+        .and_then(|s| world.get::<&Weapons>(s).ok())
+        .is_some_and(|w| w.ready == WeaponType::Chainsaw);
+    let mut hurt_player = false;
+
+    let killed = {
+        let Ok((pos, vel, flags, class, health, anim, status, angle)) = world.query_one_mut::<(
+            &Position,
+            &mut Velocity,
+            &mut ActorFlags,
+            &Class,
+            &mut Health,
+            &mut Animation,
+            Option<&mut PlayerStatus>,
+            Option<&Angle>,
+        )>(target) else {
+            return;
+        };
+        if !flags.0.contains(MobjFlags::SHOOTABLE) || health.0 <= 0 {
+            return; // shouldn't happen…
+        }
+        if flags.0.contains(MobjFlags::SKULLFLY) {
+            vel.0 = Vec3::ZERO;
+        }
+
+        /* thrust away from the inflictor */
+        if let Some((at, z)) = from {
+            if !flags.0.contains(MobjFlags::NOCLIP) && !sawing {
+                let mut dir = (pos.0 - at).normalize_or(Vec2::X);
+                let mut thrust = damage as f32 * 12.5 / class.0.mass as f32;
+                // make fall forwards sometimes
+                if damage < 40 && damage > health.0 && pos.1 - z > 64.0 && rng.p_random() & 1 != 0 {
+                    dir = -dir;
+                    thrust *= 4.0;
+                }
+                vel.0.x += dir.x * thrust;
+                vel.0.y += dir.y * thrust;
+            }
+        }
+
+        /* player specific */
+        if let Some(st) = status {
+            let invulnerable = god || st.powers[Power::Invulnerability as usize] != 0;
+            if invulnerable && damage < UNSTOPPABLE {
+                return;
+            }
+            if st.armor_type != 0 {
+                let mut saved = if st.armor_type == 1 {
+                    damage / 3
+                } else {
+                    damage / 2
+                };
+                if st.armor <= saved {
+                    // armor is used up
+                    saved = st.armor;
+                    st.armor_type = 0;
+                }
+                st.armor -= saved;
+                damage -= saved;
+            }
+            st.health = (st.health - damage).max(0);
+            // add damage after armor / invuln; `damage_count` is the red flash
+            st.damage_count = (st.damage_count + damage).min(100);
+            hurt_player = true;
+            // a glance toward whoever did it; the dying turn all the way
+            if let (Some(at), Some(&angle)) = (blame, angle) {
+                if st.health > 0 {
+                    let delta = (angle_to(pos.0, at) - angle).0 as i32;
+                    st.turn_hint = delta.clamp(-TURN_HINT_MAX, TURN_HINT_MAX);
+                }
+            }
+        } else if god && damage < UNSTOPPABLE {
+            return;
+        }
+
+        health.0 -= damage;
+        let info = class.0;
+        if health.0 <= 0 {
+            Some(health.0 < -info.spawnhealth)
+        } else {
+            if rng.p_random() < info.painchance && !flags.0.contains(MobjFlags::SKULLFLY) {
+                flags.0.insert(MobjFlags::JUSTHIT); // fight back!
+                if info.painstate != State::NULL {
+                    anim.state = info.painstate;
+                    anim.tics = rules.state_tics(defs, info.painstate);
+                }
+            }
+            None
+        }
+    };
+
+    if hurt_player {
+        if let Some(source) = source {
+            let _ = world.insert_one(target, Attacker(source));
+        } else {
+            let _ = world.remove_one::<Attacker>(target);
+        }
+    }
+    if let Some(gib) = killed {
+        mob::kill_mobj(world, rng, defs, target, gib);
+        return;
+    }
+    if let Ok(mut rt) = world.get::<&mut ReactionTime>(target) {
+        rt.0 = 0; // we're awake now…
+    }
+
+    // if not intent on another player, chase after this one; there is no
+    // `threshold` until something chases, so the last attacker always wins
+    let vile = |e: Entity| world.get::<&Class>(e).is_ok_and(|c| c.0.id == "VILE");
+    if let Some(source) = source {
+        if source != target && !vile(source) {
+            let _ = world.insert_one(target, Target(source));
+            p_wake(world, rules, defs, target);
+        }
+    }
+}
+
+/// Out of the spawn state into the see state, as a hurt monster does,
+/// for as long as `rules` make it last.
+pub fn p_wake(world: &mut World, rules: GameRules, defs: &Definitions, target: Entity) {
+    if let Ok((class, anim)) = world.query_one_mut::<(&Class, &mut Animation)>(target) {
+        if anim.state == class.0.spawnstate && class.0.seestate != State::NULL {
+            anim.state = class.0.seestate;
+            anim.tics = rules.state_tics(defs, class.0.seestate);
+        }
+    }
+}
+
+/// Vanilla's infighting rule: missiles don't hurt the species that fired
+/// them, and barons and hell knights count as one.
+pub fn same_species(a: &MobjInfo, b: &MobjInfo) -> bool {
+    a.id == b.id || matches!((a.id, b.id), ("KNIGHT", "BRUISER") | ("BRUISER", "KNIGHT"))
+}
+
+/// Vanilla `P_RadiusAttack`: `damage` to everything shootable within
+/// `damage` units of `spot` that `spot` can see, less the distance.
+/// Cyberdemons and spider masterminds shrug blasts off.
+#[allow(clippy::too_many_arguments)]
+pub fn p_radius_attack(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    spot: Entity,
+    source: Option<Entity>,
+    damage: i32,
+) {
+    let Ok((centre, spot_h)) = world
+        .query_one_mut::<(&Position, &Class)>(spot)
+        .map(|(p, c)| (*p, c.0.height as f32))
+    else {
+        return;
+    };
+    let reach = damage as f32 + MAX_RADIUS;
+    let bbox = Aabb {
+        min: centre.0 - Vec2::splat(reach),
+        max: centre.0 + Vec2::splat(reach),
+    };
+    let blast = centre.0.extend(centre.1 + spot_h * 0.5);
+
+    /* PIT_RadiusAttack – collect first, damage needs the world mutably */
+    let mut hits: SmallVec<[(Entity, i32); 8]> = SmallVec::new();
+    thing_grid.for_each_in_bbox(bbox, |other| {
+        let Some((pos, class, flags)) = fetch_thing(world, other.ent) else {
+            return true;
+        };
+        let info = class.0;
+        if !flags.0.contains(MobjFlags::SHOOTABLE) || matches!(info.id, "CYBORG" | "SPIDER") {
+            return true;
+        }
+        let d = (pos.0 - centre.0).abs();
+        let dist = (d.x.max(d.y) - info.radius as f32).max(0.0) as i32;
+        if dist >= damage {
+            return true; // out of range
+        }
+        // must be in direct path
+        let eye = pos.0.extend(pos.1 + info.height as f32 * 0.75);
+        if level.check_sight(eye, blast) {
+            hits.push((other.ent, damage - dist));
+        }
+        true
+    });
+
+    for (target, amount) in hits {
+        p_damage_mobj(world, rng, rules, defs, target, Some(spot), source, amount);
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::{self, Sound},
+        sim::{InputCmd, SimEvent, TicRunner},
+        world::testmap,
+    };
+
+    /// Gone from the world, or lying there as a corpse.
+    fn dead(sim: &TicRunner, ent: Entity) -> bool {
+        sim.world()
+            .get::<&ActorFlags>(ent)
+            .map_or(true, |f| f.0.contains(MobjFlags::CORPSE))
+    }
+
+    #[test]
+    fn barrels_chain_react_but_not_through_walls() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let info = defs::by_id("BARREL").unwrap();
+        let row: Vec<_> = [30.0, 60.0, 90.0]
+            .into_iter()
+            .map(|x| sim.spawn_mobj(&level, info, x, 64.0, 0.0, 0))
+            .collect();
+        // room B, within blast range of the last barrel
+        let ss = level.locate_subsector(Vec2::new(160.0, 64.0));
+        let behind = sim.spawn_mobj(&level, info, 160.0, 64.0, 0.0, ss);
+
+        sim.damage_mobj(row[0], None, None, 20);
+        for _ in 0..100 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+
+        assert!(row.iter().all(|&b| dead(&sim, b)));
+        assert!(!dead(&sim, behind));
+        assert_eq!(sim.world().get::<&Health>(behind).unwrap().0, 20);
+    }
+
+    #[test]
+    fn god_mode_only_stops_small_hits() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        sim.toggle_cheat(CheatFlags::GOD);
+
+        sim.damage_mobj(player, None, None, 50);
+        assert_eq!(sim.world().get::<&Health>(player).unwrap().0, 100);
+
+        sim.damage_mobj(player, None, None, UNSTOPPABLE);
+        sim.run_tic(&mut level, InputCmd::default());
+        assert!(dead(&sim, player));
+    }
+
+    #[test]
+    fn armor_soaks_and_pain_flashes_red() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        {
+            let mut st = sim.world_mut().get::<&mut PlayerStatus>(player).unwrap();
+            st.armor = 100;
+            st.armor_type = 1;
+        }
+
+        sim.damage_mobj(player, None, None, 30);
+        let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!((st.armor, st.health, st.damage_count), (90, 80, 20));
+        assert_eq!(sim.world().get::<&Health>(player).unwrap().0, 80);
+        assert_eq!(st.palette_effect().palette, 4);
+
+        for _ in 0..20 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!(st.damage_count, 0);
+        assert_eq!(st.palette_effect(), Default::default());
+
+        // invulnerable: no hurt, no flash, inverse colormap
+        sim.world_mut()
+            .get::<&mut PlayerStatus>(player)
+            .unwrap()
+            .powers[Power::Invulnerability as usize] = 30 * 35;
+        sim.damage_mobj(player, None, None, 50);
+        let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!((st.health, st.damage_count), (80, 0));
+        assert_eq!(st.palette_effect().fixed_colormap, Some(32));
+    }
+
+    #[test]
+    fn hurt_monsters_turn_on_their_attacker() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let spawn = |sim: &mut TicRunner, id, x| {
+            sim.spawn_mobj(&level, defs::by_id(id).unwrap(), x, 64.0, 0.0, 0)
+        };
+        let imp = spawn(&mut sim, "TROOP", 32.0);
+        let zombie = spawn(&mut sim, "POSSESSED", 96.0);
+        let vile = spawn(&mut sim, "VILE", 64.0);
+        let target = |sim: &TicRunner| sim.world().get::<&Target>(imp).ok().map(|t| t.0);
+        let imp_info = defs::by_id("TROOP").unwrap();
+
+        // the arch-vile's attacks don't draw attention
+        sim.damage_mobj(imp, Some(vile), Some(vile), 1);
+        assert_eq!(target(&sim), None);
+
+        sim.damage_mobj(imp, Some(zombie), Some(zombie), 1);
+        assert_eq!(target(&sim), Some(zombie));
+        let state = sim.world().get::<&Animation>(imp).unwrap().state;
+        assert_ne!(state, imp_info.spawnstate, "still asleep");
+
+        // world damage blames nobody
+        sim.damage_mobj(imp, None, None, 1);
+        assert_eq!(target(&sim), Some(zombie));
+    }
+
+    #[test]
+    fn chainsaw_hits_hold_their_victim() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 32.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        let imp = sim.spawn_mobj(&level, defs::by_id("TROOP").unwrap(), 64.0, 64.0, 0.0, 0);
+        let pushed = |sim: &mut TicRunner, weapon| {
+            sim.world_mut().get::<&mut Weapons>(player).unwrap().ready = weapon;
+            sim.world_mut().get::<&mut Velocity>(imp).unwrap().0 = Vec3::ZERO;
+            sim.damage_mobj(imp, Some(player), Some(player), 2);
+            sim.world().get::<&Velocity>(imp).unwrap().0 != Vec3::ZERO
+        };
+
+        assert!(pushed(&mut sim, WeaponType::Fist));
+        assert!(!pushed(&mut sim, WeaponType::Chainsaw));
+    }
+
+    /// `-fast` halves the demon's pain and run frames, and a hit that
+    /// flinches or wakes one starts them at the halved length.
+    #[test]
+    fn hurt_fast_demons_flinch_and_wake_quickly() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        sim.set_rules(GameRules {
+            fast_monsters: true,
+            ..GameRules::VANILLA
+        });
+        let info = defs::by_id("SERGEANT").unwrap();
+        let demon = sim.spawn_mobj(&level, info, 32.0, 64.0, 0.0, 0);
+        let zombie = sim.spawn_mobj(
+            &level,
+            defs::by_id("POSSESSED").unwrap(),
+            96.0,
+            64.0,
+            0.0,
+            0,
+        );
+        let anim = |sim: &TicRunner| *sim.world().get::<&Animation>(demon).unwrap();
+
+        sim.damage_mobj(demon, Some(zombie), Some(zombie), 1);
+        let woken = anim(&sim);
+        assert!([info.seestate, info.painstate].contains(&woken.state));
+        assert_eq!(woken.tics, woken.state.tics() / 2);
+
+        let mut flinched = false;
+        for _ in 0..50 {
+            sim.damage_mobj(demon, None, None, 1);
+            let now = anim(&sim);
+            if now.state == info.painstate {
+                assert_eq!(now.tics, info.painstate.tics() / 2);
+                flinched = true;
+            }
+        }
+        assert!(flinched);
+    }
+
+    #[test]
+    fn barons_and_knights_are_one_species() {
+        let info = |id| defs::by_id(id).unwrap();
+        assert!(same_species(info("TROOP"), info("TROOP")));
+        assert!(same_species(info("KNIGHT"), info("BRUISER")));
+        assert!(same_species(info("BRUISER"), info("KNIGHT")));
+        assert!(!same_species(info("TROOP"), info("POSSESSED")));
+    }
+
+    #[test]
+    fn corpses_fall_flat_and_stop_blocking() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 32.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        let info = defs::by_id("POSSESSED").unwrap();
+        let zombie = sim.spawn_mobj(&level, info, 80.0, 64.0, 0.0, 0);
+
+        sim.damage_mobj(zombie, None, None, 25);
+        let flags = |sim: &TicRunner| sim.world().get::<&ActorFlags>(zombie).unwrap().0;
+        // down, but still in the way until A_Fall
+        assert!(flags(&sim).contains(MobjFlags::CORPSE | MobjFlags::SOLID));
+        assert!(!flags(&sim).contains(MobjFlags::SHOOTABLE));
+        for _ in 0..30 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert!(!flags(&sim).contains(MobjFlags::SOLID));
+        let anim = *sim.world().get::<&Animation>(zombie).unwrap();
+        assert_eq!((anim.state, anim.tics), (State::POSS_DIE5, -1));
+        let class = *sim.world().get::<&Class>(zombie).unwrap();
+        let height = mob::mobj_height(&class, &ActorFlags(flags(&sim)));
+        assert_eq!(height, info.height as f32 / 4.0);
+
+        // the player walks over the body, which stays
+        let walk = InputCmd {
+            forward: 1.0,
+            ..InputCmd::default()
+        };
+        for _ in 0..15 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert!(sim.world().get::<&Position>(player).unwrap().0.x > 100.0);
+        assert!(sim.world().contains(zombie));
+
+        // overkill gibs it instead
+        let other = sim.spawn_mobj(&level, info, 40.0, 100.0, 0.0, 0);
+        sim.damage_mobj(other, None, None, 50);
+        let state = sim.world().get::<&Animation>(other).unwrap().state;
+        assert_eq!(state, info.xdeathstate);
+    }
+
+    #[test]
+    fn killing_a_zombieman_screams_once() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let info = defs::by_id("POSSESSED").unwrap();
+        let zombie = sim.spawn_mobj(&level, info, 80.0, 64.0, 0.0, 0);
+        sim.drain_events().for_each(drop);
+
+        sim.damage_mobj(zombie, None, None, 25);
+        let mut cries = 0;
+        for _ in 0..30 {
+            sim.run_tic(&mut level, InputCmd::default());
+            for ev in sim.drain_events() {
+                if let SimEvent::Sound {
+                    sound: Sound::podth1 | Sound::podth2 | Sound::podth3,
+                    origin,
+                    full_volume,
+                } = ev
+                {
+                    assert_eq!(origin, Vec2::new(80.0, 64.0));
+                    assert!(!full_volume);
+                    cries += 1;
+                }
+            }
+        }
+        assert_eq!(cries, 1);
+    }
+}
@@ -0,0 +1,473 @@
+//! Demo recording / playback.
+//!
+//! A demo is the header plus one quantised `InputCmd` per tic.  Because the
+//! sim runs at a fixed 35 Hz and draws randomness only from `Rng`, feeding
+//! the same commands into a fresh `TicRunner` reproduces the game exactly.
+//!
+//! On-disk layout (little-endian):
+//!
+//! ```text
+//! "YDMO"  version:u8  skill:u8  seed:u8  map:[u8;8]  tics:u32
+//! tics × { forward:i8  strafe:i8  turn:i16  buttons:u8  weapon:u8 }
+//! ```
+
+use byteorder::{LittleEndian as LE, ReadBytesExt, WriteBytesExt};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+
+use super::{InputCmd, InputSource};
+
+const MAGIC: &[u8; 4] = b"YDMO";
+
+/// Bumped whenever the command encoding or the sim's determinism changes.
+pub const DEMO_VERSION: u8 = 1;
+
+/* per-tic encoding */
+const AXIS_SCALE: f32 = 127.0; // forward / strafe ∈ [-1, 1] → i8
+const TURN_SCALE: f32 = 256.0; // turn → i16 in 1/256 steps
+
+const BT_RUN: u8 = 0x01;
+const BT_FIRE: u8 = 0x02;
+const BT_USE: u8 = 0x04;
+
+/*──────────────────────────── Error type ───────────────────────────*/
+
+#[derive(Error, Debug)]
+pub enum DemoError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("not a demo file")]
+    BadMagic,
+
+    #[error("demo version {0} unsupported (engine is {DEMO_VERSION})")]
+    Version(u8),
+}
+
+/*──────────────────────────── Header ───────────────────────────────*/
+
+/// Everything needed to put the sim back into the recorded start state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemoHeader {
+    pub version: u8,
+    /// Map marker name (`E1M1`, `MAP01`, …).
+    pub map: String,
+    /// 0-based like vanilla `gameskill`.
+    pub skill: u8,
+    /// `Rng` index at the first tic.
+    pub seed: u8,
+}
+
+impl DemoHeader {
+    pub fn new(map: &str, skill: u8, seed: u8) -> Self {
+        Self {
+            version: DEMO_VERSION,
+            map: map.to_owned(),
+            skill,
+            seed,
+        }
+    }
+
+    fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut name = [0u8; 8];
+        for (dst, src) in name.iter_mut().zip(self.map.bytes()) {
+            *dst = src;
+        }
+        w.write_all(MAGIC)?;
+        w.write_u8(self.version)?;
+        w.write_u8(self.skill)?;
+        w.write_u8(self.seed)?;
+        w.write_all(&name)
+    }
+
+    fn read_from<R: Read>(r: &mut R) -> Result<Self, DemoError> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(DemoError::BadMagic);
+        }
+        let version = r.read_u8()?;
+        if version != DEMO_VERSION {
+            return Err(DemoError::Version(version));
+        }
+        let skill = r.read_u8()?;
+        let seed = r.read_u8()?;
+        let mut name = [0u8; 8];
+        r.read_exact(&mut name)?;
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+
+        Ok(Self {
+            version,
+            map: String::from_utf8_lossy(&name[..end]).into_owned(),
+            skill,
+            seed,
+        })
+    }
+}
+
+/*──────────────────────── Tic command codec ────────────────────────*/
+
+/// Compact wire form of one `InputCmd`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TicCmd {
+    forward: i8,
+    strafe: i8,
+    turn: i16,
+    buttons: u8,
+    weapon: u8,
+}
+
+impl TicCmd {
+    fn encode(cmd: &InputCmd) -> Self {
+        let axis = |v: f32| (v.clamp(-1.0, 1.0) * AXIS_SCALE).round() as i8;
+        let mut buttons = 0;
+        if cmd.run {
+            buttons |= BT_RUN;
+        }
+        if cmd.fire {
+            buttons |= BT_FIRE;
+        }
+        if cmd.use_act {
+            buttons |= BT_USE;
+        }
+        Self {
+            forward: axis(cmd.forward),
+            strafe: axis(cmd.strafe),
+            turn: (cmd.turn * TURN_SCALE)
+                .round()
+                .clamp(i16::MIN as f32, i16::MAX as f32) as i16,
+            buttons,
+            weapon: cmd.weapon.unwrap_or(0),
+        }
+    }
+
+    fn decode(self) -> InputCmd {
+        InputCmd {
+            forward: self.forward as f32 / AXIS_SCALE,
+            strafe: self.strafe as f32 / AXIS_SCALE,
+            turn: self.turn as f32 / TURN_SCALE,
+            run: self.buttons & BT_RUN != 0,
+            fire: self.buttons & BT_FIRE != 0,
+            use_act: self.buttons & BT_USE != 0,
+            weapon: (self.weapon != 0).then_some(self.weapon),
+            fly: 0.0, // cheats aren't recorded
+        }
+    }
+
+    fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_i8(self.forward)?;
+        w.write_i8(self.strafe)?;
+        w.write_i16::<LE>(self.turn)?;
+        w.write_u8(self.buttons)?;
+        w.write_u8(self.weapon)
+    }
+
+    fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            forward: r.read_i8()?,
+            strafe: r.read_i8()?,
+            turn: r.read_i16::<LE>()?,
+            buttons: r.read_u8()?,
+            weapon: r.read_u8()?,
+        })
+    }
+}
+
+/*──────────────────────────── Recorder ─────────────────────────────*/
+
+/// Collects the commands of a running game.
+pub struct DemoRecorder {
+    header: DemoHeader,
+    tics: Vec<TicCmd>,
+}
+
+impl DemoRecorder {
+    pub fn new(header: DemoHeader) -> Self {
+        Self {
+            header,
+            tics: Vec::new(),
+        }
+    }
+
+    /// Append `cmd` and return its quantised form.  Feed *that* to the sim
+    /// so the recording session sees exactly what playback will.
+    pub fn record(&mut self, cmd: InputCmd) -> InputCmd {
+        let tic = TicCmd::encode(&cmd);
+        self.tics.push(tic);
+        tic.decode()
+    }
+
+    /// Wrap `src` so every command it yields is recorded on the way through.
+    pub fn tee<'a, S: InputSource + ?Sized>(&'a mut self, src: &'a mut S) -> Recording<'a, S> {
+        Recording { rec: self, src }
+    }
+
+    #[inline]
+    pub fn header(&self) -> &DemoHeader {
+        &self.header
+    }
+
+    /// Number of tics recorded so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tics.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tics.is_empty()
+    }
+
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        self.header.write_to(w)?;
+        w.write_u32::<LE>(self.tics.len() as u32)?;
+        for tic in &self.tics {
+            tic.write_to(w)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(17 + self.tics.len() * 6);
+        self.write_to(&mut out)
+            .expect("writing to a Vec cannot fail");
+        out
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()
+    }
+}
+
+/// `InputSource` adapter returned by [`DemoRecorder::tee`].
+pub struct Recording<'a, S: InputSource + ?Sized> {
+    rec: &'a mut DemoRecorder,
+    src: &'a mut S,
+}
+
+impl<S: InputSource + ?Sized> InputSource for Recording<'_, S> {
+    fn next_cmd(&mut self) -> Option<InputCmd> {
+        self.src.next_cmd().map(|cmd| self.rec.record(cmd))
+    }
+}
+
+/*──────────────────────────── Player ───────────────────────────────*/
+
+/// Feeds a recorded command stream back into the sim.
+pub struct DemoPlayer {
+    header: DemoHeader,
+    tics: Vec<TicCmd>,
+    cursor: usize,
+}
+
+impl DemoPlayer {
+    pub fn from_reader<R: Read>(r: &mut R) -> Result<Self, DemoError> {
+        let header = DemoHeader::read_from(r)?;
+        let count = r.read_u32::<LE>()? as usize;
+        let mut tics = Vec::with_capacity(count.min(1 << 20));
+        for _ in 0..count {
+            tics.push(TicCmd::read_from(r)?);
+        }
+        Ok(Self {
+            header,
+            tics,
+            cursor: 0,
+        })
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, DemoError> {
+        Self::from_reader(&mut bytes)
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, DemoError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    #[inline]
+    pub fn header(&self) -> &DemoHeader {
+        &self.header
+    }
+
+    /// Total number of tics in the demo.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.tics.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tics.is_empty()
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.tics.len()
+    }
+}
+
+impl InputSource for DemoPlayer {
+    fn next_cmd(&mut self) -> Option<InputCmd> {
+        let tic = self.tics.get(self.cursor)?;
+        self.cursor += 1;
+        Some(tic.decode())
+    }
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sim::{Angle, Position, Rng, TicRunner},
+        wad::{Wad, load_level},
+        world::TextureBank,
+    };
+    use std::path::PathBuf;
+
+    fn doom_wad() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join("doom.wad")
+    }
+
+    /// Deterministic pseudo-input: walk, strafe, turn and press buttons.
+    fn scripted(tic: usize) -> InputCmd {
+        InputCmd {
+            forward: if tic % 120 < 90 { 1.0 } else { -0.5 },
+            strafe: if tic % 50 < 10 { 0.7 } else { 0.0 },
+            turn: ((tic as f32) * 0.05).sin(),
+            run: tic % 200 > 150,
+            fire: tic.is_multiple_of(17),
+            use_act: tic.is_multiple_of(33),
+            weapon: tic.is_multiple_of(97).then_some(2),
+            fly: 0.0,
+        }
+    }
+
+    struct Scripted(usize);
+    impl InputSource for Scripted {
+        fn next_cmd(&mut self) -> Option<InputCmd> {
+            self.0 += 1;
+            Some(scripted(self.0))
+        }
+    }
+
+    #[test]
+    fn stream_roundtrip() {
+        let mut rec = DemoRecorder::new(DemoHeader::new("E1M1", 2, 17));
+        let fed: Vec<InputCmd> = (0..64).map(|t| rec.record(scripted(t))).collect();
+
+        let mut player = DemoPlayer::from_bytes(&rec.to_bytes()).unwrap();
+        assert_eq!(player.header(), rec.header());
+        assert_eq!(player.len(), 64);
+
+        for want in fed {
+            let got = player.next_cmd().unwrap();
+            assert_eq!(got.forward, want.forward);
+            assert_eq!(got.strafe, want.strafe);
+            assert_eq!(got.turn, want.turn);
+            assert_eq!(
+                (got.run, got.fire, got.use_act, got.weapon),
+                (want.run, want.fire, want.use_act, want.weapon)
+            );
+        }
+        assert!(player.is_finished());
+        assert!(player.next_cmd().is_none());
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        assert!(matches!(
+            DemoPlayer::from_bytes(b"IWAD\0\0\0\0"),
+            Err(DemoError::BadMagic)
+        ));
+        let mut bytes = DemoRecorder::new(DemoHeader::new("E1M1", 2, 0)).to_bytes();
+        bytes[4] = DEMO_VERSION + 1;
+        assert!(matches!(
+            DemoPlayer::from_bytes(&bytes),
+            Err(DemoError::Version(_))
+        ));
+    }
+
+    fn run_e1m1(input: &mut dyn InputSource, seed: u8) -> (Position, Angle) {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = TextureBank::default_with_checker();
+        let mut level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        level.finalise_bsp();
+
+        let mut sim = TicRunner::new(&level);
+        sim.set_rng(Rng::new(seed));
+        for thing in &level.things {
+            if let Some(info) = crate::defs::by_doomednum(thing.type_id) {
+                sim.spawn_mobj(
+                    &level,
+                    info,
+                    thing.pos.x,
+                    thing.pos.y,
+                    thing.angle,
+                    thing.sub_sector,
+                );
+            }
+        }
+        let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+        let player = sim.spawn_mobj(
+            &level,
+            crate::defs::by_id("PLAYER").unwrap(),
+            start.pos.x,
+            start.pos.y,
+            start.angle,
+            start.sub_sector,
+        );
+        sim.set_player(player);
+        sim.spawn_specials(&mut level);
+
+        while let Some(cmd) = input.next_cmd() {
+            sim.run_tic(&mut level, cmd);
+        }
+
+        let mut q = sim
+            .world()
+            .query_one::<(&Position, &Angle)>(player)
+            .unwrap();
+        let (pos, ang) = q.get().unwrap();
+        (*pos, *ang)
+    }
+
+    /// Golden determinism check: 500 recorded tics replay to the same spot.
+    #[test]
+    fn e1m1_replay_is_deterministic() {
+        struct Limit<S>(S, usize);
+        impl<S: InputSource> InputSource for Limit<S> {
+            fn next_cmd(&mut self) -> Option<InputCmd> {
+                if self.1 == 0 {
+                    return None;
+                }
+                self.1 -= 1;
+                self.0.next_cmd()
+            }
+        }
+
+        let mut rec = DemoRecorder::new(DemoHeader::new("E1M1", 2, 0));
+        let mut live = Limit(Scripted(0), 500);
+        let (pos_a, ang_a) = run_e1m1(&mut rec.tee(&mut live), 0);
+        assert_eq!(rec.len(), 500);
+
+        let mut player = DemoPlayer::from_bytes(&rec.to_bytes()).unwrap();
+        let seed = player.header().seed;
+        let (pos_b, ang_b) = run_e1m1(&mut player, seed);
+
+        assert!((pos_a.0 - pos_b.0).length() <= f32::EPSILON);
+        assert!((pos_a.1 - pos_b.1).abs() <= f32::EPSILON);
+        assert_eq!(ang_a, ang_b);
+    }
+}
@@ -0,0 +1,659 @@
+//! Doors – vanilla `p_doors.c`.
+//!
+//! A door is a ceiling that rises to just under the lowest neighbouring
+//! ceiling, waits, and comes back down to the floor; something in the
+//! way on the way down sends it back up.  Tagged doors are started by
+//! switches, walk-over and gun lines (`EV_DoDoor`), manual ones by using
+//! the door itself (`EV_VerticalDoor`), and two sector specials start
+//! timed ones at level start (`P_SpawnDoorCloseIn30`,
+//! `P_SpawnDoorRaiseIn5Mins`).  Blazing doors move four times as fast
+//! and have their own sounds.
+
+use hecs::World;
+
+use super::ceilings::Ceiling;
+use super::events::SimEvent;
+use super::floors::Floor;
+use super::movers::{MoveResult, sector_busy, t_move_ceiling};
+use super::plats::Platform;
+use super::{GameRules, PhysicsConfig, Rng, ThingGrid};
+use crate::defs::{Definitions, Sound};
+use crate::world::{Level, LinedefId, SectorId};
+
+/// Door speed in units per tic (vanilla `VDOORSPEED`).
+pub const DOOR_SPEED: f32 = 2.0;
+
+/// Tics a raised door stays open (vanilla `VDOORWAIT`).
+pub const DOOR_WAIT: i32 = 150;
+
+/// How long a `Close30ThenOpen` door stays shut, and how long sector
+/// special 10 waits before closing its door.
+const CLOSE30_WAIT: i32 = 35 * 30;
+
+/// How long sector special 14 waits before raising its door.
+const RAISE_IN_5_MINS_WAIT: i32 = 35 * 60 * 5;
+
+/// Sector special: close the door after 30 seconds.
+pub const SECTOR_CLOSE_IN_30: i16 = 10;
+
+/// Sector special: raise the door after 5 minutes.
+pub const SECTOR_RAISE_IN_5_MINS: i16 = 14;
+
+/// Gap left between an open door and the lowest neighbouring ceiling.
+const DOOR_LIP: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DoorKind {
+    /// Open, wait `DOOR_WAIT`, close.
+    Normal,
+    /// Close, wait 30 seconds, open again.
+    Close30ThenOpen,
+    Close,
+    /// Open and stay open.
+    Open,
+    /// Sector special 14: wait 5 minutes, then behave as `Normal`.
+    RaiseIn5Mins,
+    /// `Normal` at four times the speed.
+    BlazeRaise,
+    /// `Open` at four times the speed.
+    BlazeOpen,
+    /// `Close` at four times the speed.
+    BlazeClose,
+}
+
+impl DoorKind {
+    /// Blazing doors move at four times `DOOR_SPEED` and sound
+    /// different.
+    pub fn blazing(self) -> bool {
+        matches!(self, Self::BlazeRaise | Self::BlazeOpen | Self::BlazeClose)
+    }
+
+    /// `(opening, closing)` sounds.
+    fn sounds(self) -> (Sound, Sound) {
+        if self.blazing() {
+            (Sound::bdopn, Sound::bdcls)
+        } else {
+            (Sound::doropn, Sound::dorcls)
+        }
+    }
+}
+
+/// One running door (vanilla `vldoor_t`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Door {
+    pub sector: SectorId,
+    pub kind: DoorKind,
+    /// Ceiling height when open.
+    pub top: f32,
+    pub speed: f32,
+    /// 1 up, 0 waiting, -1 down, 2 waiting to start (`RaiseIn5Mins`).
+    pub direction: i32,
+    /// Tics to wait when open.
+    pub top_wait: i32,
+    /// Tics left of the current wait.
+    pub top_countdown: i32,
+}
+
+impl Door {
+    fn new(level: &Level, sector: SectorId, kind: DoorKind, direction: i32) -> Self {
+        Self {
+            sector,
+            kind,
+            top: level.lowest_neighbor_ceiling(sector) - DOOR_LIP,
+            speed: if kind.blazing() {
+                DOOR_SPEED * 4.0
+            } else {
+                DOOR_SPEED
+            },
+            direction,
+            top_wait: DOOR_WAIT,
+            top_countdown: 0,
+        }
+    }
+}
+
+fn door_sound(level: &Level, events: &mut Vec<SimEvent>, sector: SectorId, sound: Sound) {
+    events.push(SimEvent::Sound {
+        sound,
+        origin: level.sound_origin(sector),
+        full_volume: false,
+    });
+}
+
+/// Vanilla `EV_DoDoor`: start a `kind` door in every idle sector tagged
+/// `tag`.  `true` if one started.
+#[allow(clippy::too_many_arguments)]
+pub fn ev_do_door(
+    level: &Level,
+    doors: &mut Vec<Door>,
+    ceilings: &[Ceiling],
+    plats: &[Platform],
+    floors: &[Floor],
+    events: &mut Vec<SimEvent>,
+    tag: u16,
+    kind: DoorKind,
+) -> bool {
+    let mut rtn = false;
+    for (id, sector) in level.sectors.iter().enumerate() {
+        let id = id as SectorId;
+        if sector.tag as u16 != tag || sector_busy(doors, ceilings, plats, floors, id) {
+            continue;
+        }
+        rtn = true;
+
+        let mut door = Door::new(level, id, kind, 1);
+        let (open, close) = kind.sounds();
+        match kind {
+            DoorKind::Close | DoorKind::BlazeClose => {
+                door.direction = -1;
+                door_sound(level, events, id, close);
+            }
+            DoorKind::Close30ThenOpen => {
+                door.top = sector.ceil_h;
+                door.direction = -1;
+                door_sound(level, events, id, close);
+            }
+            DoorKind::Normal
+            | DoorKind::Open
+            | DoorKind::BlazeRaise
+            | DoorKind::BlazeOpen
+            | DoorKind::RaiseIn5Mins => {
+                if door.top != sector.ceil_h {
+                    door_sound(level, events, id, open);
+                }
+            }
+        }
+        doors.push(door);
+    }
+    rtn
+}
+
+/// Vanilla `EV_VerticalDoor`: `line` was used as a manual door, which
+/// opens the sector behind it.  Using a repeatable raise door (`Normal`
+/// or `BlazeRaise`) that is already moving sends it the other way –
+/// though only players close doors.  `true` if a door started.
+#[allow(clippy::too_many_arguments)]
+pub fn ev_vertical_door(
+    level: &Level,
+    doors: &mut Vec<Door>,
+    ceilings: &[Ceiling],
+    plats: &[Platform],
+    floors: &[Floor],
+    events: &mut Vec<SimEvent>,
+    line: LinedefId,
+    kind: DoorKind,
+    repeat: bool,
+    by_player: bool,
+) -> bool {
+    let Some(back) = level.linedefs[line as usize].left_sidedef else {
+        eprintln!("warning: line {line}: manual door without a back side");
+        return false;
+    };
+    let sector = level.sidedefs[back as usize].sector;
+
+    if let Some(door) = doors.iter_mut().find(|d| d.sector == sector) {
+        if !matches!(kind, DoorKind::Normal | DoorKind::BlazeRaise) || !repeat {
+            return false;
+        }
+        if door.direction == -1 {
+            door.direction = 1; // go back up
+        } else if by_player {
+            door.direction = -1; // start going down immediately
+        }
+        return false;
+    }
+    if sector_busy(doors, ceilings, plats, floors, sector) {
+        return false;
+    }
+
+    door_sound(level, events, sector, kind.sounds().0);
+    doors.push(Door::new(level, sector, kind, 1));
+    true
+}
+
+/// The door part of vanilla `P_SpawnSpecials`: start a waiting door in
+/// every sector with special 10 or 14, and clear the special.
+pub fn spawn_sector_doors(level: &mut Level) -> Vec<Door> {
+    let mut doors = Vec::new();
+    for id in 0..level.sectors.len() as SectorId {
+        let door = match level.sectors[id as usize].special {
+            // vanilla `P_SpawnDoorCloseIn30`
+            SECTOR_CLOSE_IN_30 => Door {
+                top_countdown: CLOSE30_WAIT,
+                ..Door::new(level, id, DoorKind::Normal, 0)
+            },
+            // vanilla `P_SpawnDoorRaiseIn5Mins`
+            SECTOR_RAISE_IN_5_MINS => Door {
+                top_countdown: RAISE_IN_5_MINS_WAIT,
+                ..Door::new(level, id, DoorKind::RaiseIn5Mins, 2)
+            },
+            _ => continue,
+        };
+        level.sectors[id as usize].special = 0;
+        doors.push(door);
+    }
+    doors
+}
+
+/// Advance every door one tic (vanilla `T_VerticalDoor`); doors that are
+/// done leave the list.
+#[allow(clippy::too_many_arguments)]
+pub fn run_doors(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    gametic: u32,
+    doors: &mut Vec<Door>,
+) {
+    doors.retain_mut(|d| {
+        let (open, close) = d.kind.sounds();
+        match d.direction {
+            // waiting
+            0 => {
+                d.top_countdown -= 1;
+                if d.top_countdown == 0 {
+                    match d.kind {
+                        DoorKind::Normal | DoorKind::BlazeRaise => {
+                            d.direction = -1; // time to go back down
+                            door_sound(level, events, d.sector, close);
+                        }
+                        DoorKind::Close30ThenOpen => {
+                            d.direction = 1;
+                            door_sound(level, events, d.sector, open);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            // initial wait
+            2 => {
+                d.top_countdown -= 1;
+                if d.top_countdown == 0 && d.kind == DoorKind::RaiseIn5Mins {
+                    d.direction = 1;
+                    d.kind = DoorKind::Normal;
+                    door_sound(level, events, d.sector, open);
+                }
+            }
+            // down
+            -1 => {
+                let floor = level.sectors[d.sector as usize].floor_h;
+                let res = t_move_ceiling(
+                    world, thing_grid, level, cfg, rng, rules, defs, gametic, d.sector, d.speed,
+                    floor, false, -1,
+                );
+                match res {
+                    MoveResult::PastDest => match d.kind {
+                        DoorKind::Close30ThenOpen => {
+                            d.direction = 0;
+                            d.top_countdown = CLOSE30_WAIT;
+                        }
+                        DoorKind::BlazeRaise | DoorKind::BlazeClose => {
+                            door_sound(level, events, d.sector, close);
+                            return false;
+                        }
+                        _ => return false,
+                    },
+                    // a closing door doesn't go back up; the rest reopen
+                    // with the normal sound, blazing or not
+                    MoveResult::Crushed
+                        if !matches!(d.kind, DoorKind::Close | DoorKind::BlazeClose) =>
+                    {
+                        d.direction = 1;
+                        door_sound(level, events, d.sector, Sound::doropn);
+                    }
+                    _ => {}
+                }
+            }
+            // up
+            _ => {
+                let res = t_move_ceiling(
+                    world, thing_grid, level, cfg, rng, rules, defs, gametic, d.sector, d.speed,
+                    d.top, false, 1,
+                );
+                if res == MoveResult::PastDest {
+                    match d.kind {
+                        DoorKind::Normal | DoorKind::BlazeRaise => {
+                            d.direction = 0; // wait at top
+                            d.top_countdown = d.top_wait;
+                        }
+                        _ => return false,
+                    }
+                }
+            }
+        }
+        true
+    });
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{
+            Angle, Class, InputCmd, MoveDir, Position, TicRunner,
+            xy_movement::{Mover, p_check_position},
+        },
+        world::testmap,
+    };
+
+    fn sounds(sim: &mut TicRunner) -> Vec<Sound> {
+        sim.drain_events()
+            .filter_map(|e| match e {
+                SimEvent::Sound { sound, .. } => Some(sound),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Room A and B with a closed door between them; room A's west wall
+    /// is a `special` switch tagged for it.
+    fn door_level(special: u16) -> Level {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        });
+        level.sectors[1].tag = 5;
+        let west = level
+            .linedefs
+            .iter()
+            .position(|l| (l.v1, l.v2) == (0, 4))
+            .unwrap();
+        level.linedefs[west].special = special;
+        level.linedefs[west].tag = 5;
+        level
+    }
+
+    #[test]
+    fn raised_door_opens_waits_and_closes() {
+        let mut level = door_level(63); // SR door raise
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 40.0, 64.0, 0.0, 0);
+        *sim.world_mut().get::<&mut Angle>(player).unwrap() = Angle::ANG180;
+        sim.set_player(player);
+
+        let press = InputCmd {
+            use_act: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(&mut level, press);
+        assert_eq!(sim.doors().len(), 1);
+        let top = level.lowest_neighbor_ceiling(1) - DOOR_LIP;
+        assert_eq!(sim.doors()[0].top, top);
+
+        let ceil = |level: &Level| level.sectors[1].ceil_h;
+        let mut tics = 0;
+        while ceil(&level) < top {
+            sim.run_tic(&mut level, InputCmd::default());
+            tics += 1;
+        }
+        assert_eq!(tics as f32, (top / DOOR_SPEED).ceil() - 1.0);
+        // one tic to notice it's at the top, the wait, one tic to start down
+        for _ in 0..DOOR_WAIT + 2 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert!(ceil(&level) < top, "closing after the wait");
+        for _ in 0..100 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(ceil(&level), 0.0);
+        assert!(sim.doors().is_empty());
+    }
+
+    #[test]
+    fn blazing_door_is_fast_and_sounds_different() {
+        let mut level = door_level(114); // SR blazing door raise
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 40.0, 64.0, 0.0, 0);
+        *sim.world_mut().get::<&mut Angle>(player).unwrap() = Angle::ANG180;
+        sim.set_player(player);
+        sim.drain_events().for_each(drop);
+
+        let press = InputCmd {
+            use_act: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(&mut level, press);
+        assert_eq!(sim.doors()[0].speed, DOOR_SPEED * 4.0);
+        assert!(sounds(&mut sim).contains(&Sound::bdopn));
+
+        let top = sim.doors()[0].top;
+        let mut tics = 0;
+        while level.sectors[1].ceil_h < top {
+            sim.run_tic(&mut level, InputCmd::default());
+            tics += 1;
+        }
+        assert_eq!(tics as f32, (top / (DOOR_SPEED * 4.0)).ceil() - 1.0);
+        sounds(&mut sim);
+
+        let mut heard = Vec::new();
+        for _ in 0..DOOR_WAIT + 40 {
+            sim.run_tic(&mut level, InputCmd::default());
+            heard.extend(sounds(&mut sim));
+        }
+        assert!(sim.doors().is_empty());
+        // once to start down, once to slam shut
+        assert_eq!(heard, [Sound::bdcls, Sound::bdcls]);
+    }
+
+    #[test]
+    fn sector_specials_start_timed_doors() {
+        let mut level = door_level(0);
+        level.sectors[1].ceil_h = 124.0;
+        level.sectors[1].special = SECTOR_CLOSE_IN_30;
+        let mut sim = TicRunner::new(&level);
+        sim.spawn_specials(&mut level);
+        assert_eq!(level.sectors[1].special, 0);
+        assert_eq!(sim.doors().len(), 1);
+
+        for _ in 0..CLOSE30_WAIT - 1 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].ceil_h, 124.0, "still open");
+        sim.run_tic(&mut level, InputCmd::default());
+        assert_eq!(sounds(&mut sim), [Sound::dorcls]);
+        for _ in 0..70 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].ceil_h, 0.0);
+        assert!(sim.doors().is_empty());
+
+        // and the other way round, five minutes later
+        level.sectors[1].special = SECTOR_RAISE_IN_5_MINS;
+        sim.spawn_specials(&mut level);
+        assert_eq!(sim.doors()[0].direction, 2);
+        for _ in 0..RAISE_IN_5_MINS_WAIT {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(sounds(&mut sim), [Sound::doropn]);
+        assert_eq!(sim.doors()[0].kind, DoorKind::Normal);
+        sim.run_tic(&mut level, InputCmd::default());
+        assert!(level.sectors[1].ceil_h > 0.0);
+    }
+
+    #[test]
+    fn monsters_open_plain_manual_doors_in_their_way() {
+        for (special, opens) in [(1, true), (117, false), (26, false)] {
+            let mut level = door_level(0);
+            // room B's side of the door, facing east
+            let door = level
+                .linedefs
+                .iter()
+                .position(|l| (l.v1, l.v2) == (2, 6))
+                .unwrap();
+            level.linedefs[door].special = special;
+            let mut sim = TicRunner::new(&level);
+            let imp = sim.spawn_mobj(&level, defs::by_id("TROOP").unwrap(), 166.0, 64.0, 0.0, 2);
+
+            // a step west runs into the closed door
+            let mover = Mover {
+                ent: imp,
+                pos: *sim.world().get::<&Position>(imp).unwrap(),
+                class: *sim.world().get::<&Class>(imp).unwrap(),
+                flags: crate::sim::ActorFlags(defs::by_id("TROOP").unwrap().flags),
+            };
+            let cfg = *sim.physics();
+            let check = p_check_position(
+                &level,
+                &cfg,
+                sim.world(),
+                sim.thing_grid(),
+                &mover,
+                glam::Vec2::new(150.0, 64.0),
+            );
+            assert_eq!(check.special_lines.as_slice(), [door as LinedefId]);
+
+            assert_eq!(
+                sim.monster_use_lines(&mut level, imp, &check.special_lines),
+                opens,
+                "special {special}"
+            );
+            assert_eq!(sim.doors().len(), opens as usize, "special {special}");
+        }
+    }
+
+    #[test]
+    fn walking_monsters_wait_for_the_doors_they_open() {
+        let mut level = door_level(0);
+        let door = level
+            .linedefs
+            .iter()
+            .position(|l| (l.v1, l.v2) == (2, 6))
+            .unwrap();
+        level.linedefs[door].special = 1;
+        let mut sim = TicRunner::new(&level);
+        let imp = sim.spawn_mobj(&level, defs::by_id("TROOP").unwrap(), 166.0, 64.0, 0.0, 2);
+        sim.world_mut().insert_one(imp, MoveDir::West).unwrap();
+
+        // bumping the door opens it and counts as a step, heading dropped
+        assert!(sim.monster_try_walk(&mut level, imp));
+        assert_eq!(sim.doors().len(), 1);
+        assert_eq!(*sim.world().get::<&MoveDir>(imp).unwrap(), MoveDir::NoDir);
+        assert_eq!(sim.world().get::<&Position>(imp).unwrap().0.x, 166.0);
+        // so the next step asks for a new one
+        assert!(!sim.monster_try_walk(&mut level, imp));
+    }
+
+    #[test]
+    fn closing_door_bounces_off_a_thing() {
+        let mut level = door_level(0);
+        level.sectors[1].ceil_h = 124.0;
+        let mut doors = vec![Door {
+            sector: 1,
+            kind: DoorKind::Normal,
+            top: 124.0,
+            speed: DOOR_SPEED,
+            direction: -1,
+            top_wait: DOOR_WAIT,
+            top_countdown: 0,
+        }];
+        let mut sim = TicRunner::new(&level);
+        sim.spawn_mobj(
+            &level,
+            defs::by_id("POSSESSED").unwrap(),
+            136.0,
+            64.0,
+            0.0,
+            1,
+        );
+        sim.set_doors(std::mem::take(&mut doors));
+        for _ in 0..80 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        // came down onto the zombieman's head and went back up
+        assert!(
+            level.sectors[1].ceil_h >= 56.0,
+            "{}",
+            level.sectors[1].ceil_h
+        );
+        assert_eq!(sim.doors().len(), 1);
+    }
+
+    #[test]
+    fn one_mover_per_sector_whatever_its_kind() {
+        use crate::sim::ceilings::{CeilingKind, ev_do_ceiling};
+        use crate::sim::floors::{FloorKind, ev_do_floor};
+        use crate::sim::plats::{PlatKind, ev_do_plat};
+
+        let level = door_level(0);
+        let (mut doors, mut ceilings, mut plats, mut floors) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let mut rng = Rng::default();
+        let mut events = Vec::new();
+
+        // a floor on the sector keeps doors off it
+        assert!(ev_do_floor(
+            &level,
+            &mut floors,
+            &doors,
+            &ceilings,
+            &plats,
+            5,
+            FloorKind::Raise24
+        ));
+        assert!(!ev_do_door(
+            &level,
+            &mut doors,
+            &ceilings,
+            &plats,
+            &floors,
+            &mut events,
+            5,
+            DoorKind::Open
+        ));
+
+        // and a door keeps everything else off
+        floors.clear();
+        assert!(ev_do_door(
+            &level,
+            &mut doors,
+            &ceilings,
+            &plats,
+            &floors,
+            &mut events,
+            5,
+            DoorKind::Open
+        ));
+        assert!(!ev_do_floor(
+            &level,
+            &mut floors,
+            &doors,
+            &ceilings,
+            &plats,
+            5,
+            FloorKind::Raise24
+        ));
+        assert!(!ev_do_ceiling(
+            &level,
+            &mut ceilings,
+            &doors,
+            &plats,
+            &floors,
+            5,
+            CeilingKind::CrushAndRaise
+        ));
+        assert!(!ev_do_plat(
+            &level,
+            &mut plats,
+            &doors,
+            &ceilings,
+            &floors,
+            &mut rng,
+            &mut events,
+            5,
+            PlatKind::DownWaitUpStay
+        ));
+        assert_eq!(
+            (doors.len(), ceilings.len(), plats.len(), floors.len()),
+            (1, 0, 0, 0)
+        );
+    }
+}
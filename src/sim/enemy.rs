@@ -0,0 +1,725 @@
+//! Monster attacks – the attack codepointers of vanilla p_enemy.c.
+//!
+//! `A_Look` wakes monsters up; nothing chases the player yet, so the
+//! attacks run whenever a monster with a `Target` enters one of its
+//! attack states.  The Arch-vile's chase only does its raising.
+
+use glam::Vec3;
+use hecs::{Entity, World};
+
+use super::actions;
+use super::damage::MAX_RADIUS;
+use super::events::SimEvent;
+use super::hitscan::{self, MISSILE_RANGE};
+use super::mob::{self, aprox_distance};
+use super::noise::SectorSounds;
+use super::spacial::fetch_thing;
+use super::specials::SpecialCtx;
+use super::xy_movement::{self, Mover, p_check_position};
+use super::{
+    ActorFlags, Angle, Animation, Class, GameRules, MoveCount, MoveDir, PhysicsConfig,
+    PlayerStatus, Position, Rng, Subsector, Target, ThingGrid, Velocity, damage,
+};
+use crate::defs::{Definitions, MobjFlags, Sound, State};
+use crate::world::{Aabb, Level, angle_to};
+
+/// Reach of a claw or bite (vanilla `MELEERANGE`).
+pub(super) const MELEE_RANGE: f32 = 64.0;
+
+/// How fast a lost soul charges (vanilla `SKULLSPEED`).
+pub const SKULL_SPEED: f32 = 20.0;
+
+/// Who `actor` is after.
+fn target_of(world: &World, actor: Entity) -> Option<Entity> {
+    world.get::<&Target>(actor).ok().map(|t| t.0)
+}
+
+pub(super) fn sound(world: &World, events: &mut Vec<SimEvent>, actor: Entity, sound: Sound) {
+    if let Ok(pos) = world.get::<&Position>(actor) {
+        events.push(SimEvent::Sound {
+            sound,
+            origin: pos.0,
+            full_volume: false,
+        });
+    }
+}
+
+/// Vanilla `A_FaceTarget`: turn to the target, with a random error
+/// against a fuzzy (`MF_SHADOW`) one.
+pub fn a_face_target(world: &mut World, rng: &mut Rng, actor: Entity) {
+    let Some((to, _, to_flags)) = target_of(world, actor).and_then(|t| fetch_thing(world, t))
+    else {
+        return;
+    };
+    let Ok((pos, flags, angle)) =
+        world.query_one_mut::<(&Position, &mut ActorFlags, &mut Angle)>(actor)
+    else {
+        return;
+    };
+    flags.0.remove(MobjFlags::AMBUSH);
+    *angle = angle_to(pos.0, to.0);
+    if to_flags.0.contains(MobjFlags::SHADOW) {
+        *angle += Angle((rng.p_subrandom() << 21) as u32);
+    }
+}
+
+/// Vanilla `P_CheckSight`: can `looker` see `target`, eyes to middle?
+fn p_check_sight(world: &World, level: &Level, looker: Entity, target: Entity) -> bool {
+    let (Some((pos, class, _)), Some((to, to_class, _))) =
+        (fetch_thing(world, looker), fetch_thing(world, target))
+    else {
+        return false;
+    };
+    let eye = pos.0.extend(pos.1 + class.0.height as f32 * 0.75);
+    let mid = to.0.extend(to.1 + to_class.0.height as f32 * 0.5);
+    level.check_sight(eye, mid)
+}
+
+/// Vanilla `P_LookForPlayers` without `allaround`: a living player in
+/// sight, unless they are behind `actor` and out of reach.
+fn p_look_for_players(world: &World, level: &Level, actor: Entity) -> Option<Entity> {
+    let (pos, angle) = {
+        let mut q = world.query_one::<(&Position, &Angle)>(actor).ok()?;
+        q.get().map(|(p, a)| (p.0, *a))?
+    };
+    let mut players = world.query::<(&PlayerStatus, &Position)>();
+    players
+        .iter()
+        .filter(|(_, (st, _))| st.health > 0)
+        .find(|&(player, (_, to))| {
+            if !p_check_sight(world, level, actor, player) {
+                return false;
+            }
+            let delta = to.0 - pos;
+            let an = angle_to(pos, to.0) - angle;
+            !(Angle::ANG90 < an && an < Angle::ANG270) || aprox_distance(delta) <= MELEE_RANGE
+        })
+        .map(|(player, _)| player)
+}
+
+/// Vanilla `A_Look`: stand still until a noise reaches the sector or the
+/// player walks into view, then target them and give chase.  An ambusher
+/// has to see whoever it heard.
+#[allow(clippy::too_many_arguments)]
+pub fn a_look(
+    world: &mut World,
+    level: &Level,
+    sounds: &SectorSounds,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    actor: Entity,
+) {
+    let Some((pos, class, flags)) = fetch_thing(world, actor) else {
+        return;
+    };
+    let Ok(ss) = world.get::<&Subsector>(actor).map(|ss| ss.0) else {
+        return;
+    };
+    let heard = sounds
+        .target(level.sector_of_subsector(ss))
+        .filter(|&t| {
+            fetch_thing(world, t).is_some_and(|(_, _, f)| f.0.contains(MobjFlags::SHOOTABLE))
+        })
+        .filter(|&t| !flags.0.contains(MobjFlags::AMBUSH) || p_check_sight(world, level, actor, t));
+    let Some(target) = heard.or_else(|| p_look_for_players(world, level, actor)) else {
+        return;
+    };
+    let _ = world.insert_one(actor, Target(target));
+
+    let seesound = match class.0.seesound {
+        Sound::posit1 | Sound::posit2 | Sound::posit3 => {
+            [Sound::posit1, Sound::posit2, Sound::posit3][(rng.p_random() % 3) as usize]
+        }
+        Sound::bgsit1 | Sound::bgsit2 => {
+            [Sound::bgsit1, Sound::bgsit2][(rng.p_random() % 2) as usize]
+        }
+        sound => sound,
+    };
+    if !matches!(seesound, Sound::None) {
+        events.push(SimEvent::Sound {
+            sound: seesound,
+            origin: pos.0,
+            full_volume: actions::is_boss(&class),
+        });
+    }
+    if let Ok(mut anim) = world.get::<&mut Animation>(actor) {
+        if class.0.seestate != State::NULL {
+            anim.state = class.0.seestate;
+            anim.tics = rules.state_tics(defs, class.0.seestate);
+        }
+    }
+}
+
+/// The tail of vanilla `A_Chase`: now and then (3 in 256 calls) make
+/// the active sound.  The walking and attacking before it aren't here
+/// yet.
+pub fn a_chase(world: &World, rng: &mut Rng, events: &mut Vec<SimEvent>, actor: Entity) {
+    let Ok(active) = world.get::<&Class>(actor).map(|c| c.0.activesound) else {
+        return;
+    };
+    if !matches!(active, Sound::None) && rng.p_random() < 3 {
+        sound(world, events, actor, active);
+    }
+}
+
+/// Vanilla `P_TryWalk`: take a step (see [`xy_movement::p_move`]) and,
+/// if it went, walk on for another 0‥15 steps before picking a new
+/// heading.  `false` if the monster should turn now.
+pub(super) fn p_try_walk(ctx: &mut SpecialCtx, cfg: &PhysicsConfig, actor: Entity) -> bool {
+    if !xy_movement::p_move(ctx, cfg, actor) {
+        return false;
+    }
+    let count = ctx.rng.p_random() & 15;
+    ctx.world.insert_one(actor, MoveCount(count)).ok();
+    true
+}
+
+/// Vanilla `P_CheckMeleeRange`: is the target within claw reach and in
+/// sight?
+fn p_check_melee_range(world: &World, level: &Level, actor: Entity) -> bool {
+    let Some(target) = target_of(world, actor) else {
+        return false;
+    };
+    let (Some((pos, class, _)), Some((to, to_class, _))) =
+        (fetch_thing(world, actor), fetch_thing(world, target))
+    else {
+        return false;
+    };
+    if aprox_distance(to.0 - pos.0) >= MELEE_RANGE - 20.0 + to_class.0.radius as f32 {
+        return false;
+    }
+    let eye = pos.0.extend(pos.1 + class.0.height as f32 * 0.75);
+    let mid = to.0.extend(to.1 + to_class.0.height as f32 * 0.5);
+    level.check_sight(eye, mid)
+}
+
+/// Vanilla `A_PosAttack`: the zombieman's pistol shot, 3‥15 damage with
+/// a little spread.
+#[allow(clippy::too_many_arguments)]
+pub fn a_pos_attack(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    actor: Entity,
+) {
+    if target_of(world, actor).is_none() {
+        return;
+    }
+    a_face_target(world, rng, actor);
+    let Ok(angle) = world.get::<&Angle>(actor).map(|a| *a) else {
+        return;
+    };
+    let slope = hitscan::p_aim_line_attack(world, level, thing_grid, actor, angle, MISSILE_RANGE)
+        .map_or(0.0, |(_, slope)| slope);
+
+    sound(world, events, actor, Sound::pistol);
+    let angle = angle + Angle((rng.p_subrandom() << 20) as u32);
+    let damage = (rng.p_random() % 5 + 1) * 3;
+    hitscan::p_line_attack(
+        world,
+        thing_grid,
+        level,
+        rng,
+        rules,
+        defs,
+        events,
+        actor,
+        angle,
+        MISSILE_RANGE,
+        slope,
+        damage,
+    );
+}
+
+/// Vanilla `A_TroopAttack`: the imp claws a target in reach and throws a
+/// fireball at anything further.
+#[allow(clippy::too_many_arguments)]
+pub fn a_troop_attack(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    actor: Entity,
+) {
+    let Some(target) = target_of(world, actor) else {
+        return;
+    };
+    a_face_target(world, rng, actor);
+    if p_check_melee_range(world, level, actor) {
+        sound(world, events, actor, Sound::claw);
+        let damage = (rng.p_random() % 8 + 1) * 3;
+        damage::p_damage_mobj(
+            world,
+            rng,
+            rules,
+            defs,
+            target,
+            Some(actor),
+            Some(actor),
+            damage,
+        );
+        return;
+    }
+
+    // launch a missile
+    let info = defs
+        .by_id("TROOPSHOT")
+        .expect("TROOPSHOT missing from MOBJINFO");
+    mob::p_spawn_missile(
+        world, thing_grid, level, rng, rules, defs, events, actor, target, info,
+    );
+}
+
+/// Vanilla `A_SargAttack`: the demon's bite, 4‥40 damage, only in reach.
+pub fn a_sarg_attack(
+    world: &mut World,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    actor: Entity,
+) {
+    let Some(target) = target_of(world, actor) else {
+        return;
+    };
+    a_face_target(world, rng, actor);
+    if p_check_melee_range(world, level, actor) {
+        let damage = (rng.p_random() % 10 + 1) * 4;
+        damage::p_damage_mobj(
+            world,
+            rng,
+            rules,
+            defs,
+            target,
+            Some(actor),
+            Some(actor),
+            damage,
+        );
+    }
+}
+
+/// Vanilla `A_SkullAttack`: the lost soul screams and flies at its
+/// target's middle at [`SKULL_SPEED`], `MF_SKULLFLY` set until it hits
+/// something (see `xy_movement`).
+pub fn a_skull_attack(world: &mut World, rng: &mut Rng, events: &mut Vec<SimEvent>, actor: Entity) {
+    let Some((to, to_class, _)) = target_of(world, actor).and_then(|t| fetch_thing(world, t))
+    else {
+        return;
+    };
+    a_face_target(world, rng, actor);
+    let Ok((pos, vel, flags, class, angle)) =
+        world.query_one_mut::<(&Position, &mut Velocity, &mut ActorFlags, &Class, &Angle)>(actor)
+    else {
+        return;
+    };
+    flags.0.insert(MobjFlags::SKULLFLY);
+    let (from, scream) = (*pos, class.0.attacksound);
+    let dir = angle.unit() * SKULL_SPEED;
+    let tics = (aprox_distance(to.0 - from.0) / SKULL_SPEED)
+        .floor()
+        .max(1.0);
+    let mid = to.1 + to_class.0.height as f32 / 2.0;
+    vel.0 = Vec3::new(dir.x, dir.y, (mid - from.1) / tics);
+    sound(world, events, actor, scream);
+}
+
+/// Vanilla `PIT_VileCheck`: is `corpse` a body `vile` can raise from
+/// `spot` – lying still, with a raise state, in reach, and with room to
+/// stand up again?
+fn vile_check(
+    world: &World,
+    thing_grid: &ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    vile: Entity,
+    corpse: Entity,
+    spot: glam::Vec2,
+) -> bool {
+    let Ok(mut q) = world.query_one::<(&Position, &Class, &ActorFlags, &Animation)>(corpse) else {
+        return false;
+    };
+    let Some((pos, class, flags, anim)) = q.get() else {
+        return false;
+    };
+    let info = class.0;
+    if !flags.0.contains(MobjFlags::CORPSE) || anim.tics != -1 || info.raisestate == State::NULL {
+        return false;
+    }
+    let vile_radius = world.get::<&Class>(vile).map_or(0, |c| c.0.radius);
+    let reach = (info.radius + vile_radius) as f32;
+    let d = (pos.0 - spot).abs();
+    if d.x > reach || d.y > reach {
+        return false;
+    }
+    // stood up, with its full height, does it fit?
+    let risen = Mover {
+        ent: corpse,
+        pos: *pos,
+        class: *class,
+        flags: ActorFlags(info.flags),
+    };
+    !p_check_position(level, cfg, world, thing_grid, &risen, pos.0).blocked
+}
+
+/// Vanilla `A_VileChase`: raise a corpse next to the Arch-vile's next
+/// step, if there is one.  The step is along its [`MoveDir`], or its
+/// facing when it has none, and the chase itself is left out.
+#[allow(clippy::too_many_arguments)]
+pub fn a_vile_chase(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    actor: Entity,
+) {
+    let Some((pos, class, _)) = fetch_thing(world, actor) else {
+        return;
+    };
+    let Ok(angle) = world.get::<&Angle>(actor).map(|a| *a) else {
+        return;
+    };
+    let heading = match world.get::<&MoveDir>(actor).map(|d| *d) {
+        Ok(dir) if dir != MoveDir::NoDir => dir.step(),
+        _ => angle.unit(),
+    };
+    let spot = pos.0 + heading * class.0.speed as f32;
+    let reach = glam::Vec2::splat(MAX_RADIUS * 2.0);
+    let area = Aabb {
+        min: spot - reach,
+        max: spot + reach,
+    };
+
+    let mut corpse = None;
+    thing_grid.for_each_in_bbox(area, |t| {
+        if t.ent != actor && vile_check(world, thing_grid, level, cfg, actor, t.ent, spot) {
+            corpse = Some(t.ent);
+            return false;
+        }
+        true
+    });
+    let Some(corpse) = corpse else {
+        return;
+    };
+
+    // face the corpse without forgetting who it was after
+    let target = world.get::<&Target>(actor).ok().map(|t| *t);
+    world.insert_one(actor, Target(corpse)).ok();
+    a_face_target(world, rng, actor);
+    if let Some(t) = target {
+        world.insert_one(actor, t).ok();
+    } else {
+        world.remove_one::<Target>(actor).ok();
+    }
+    if let Ok(mut anim) = world.get::<&mut Animation>(actor) {
+        anim.state = State::VILE_HEAL1;
+        anim.tics = defs.state(State::VILE_HEAL1).tics;
+    }
+    sound(world, events, corpse, Sound::slop);
+    mob::raise_corpse(world, defs, corpse);
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::{self, State},
+        sim::{Animation, Health, InputCmd, PlayerStatus, TicRunner},
+        world::{Thing, testmap},
+    };
+    use glam::Vec2;
+
+    /// A monster at `x = at` targeting the player in room A, one tic from
+    /// entering the state after `state`.
+    fn attacker(id: &str, at: f32, state: State) -> (TicRunner, Entity, Entity) {
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 100.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        let info = defs::by_id(id).unwrap();
+        let ss = level.locate_subsector(Vec2::new(at, 64.0));
+        let monster = sim.spawn_mobj(&level, info, at, 64.0, 0.0, ss);
+        sim.world_mut().insert_one(monster, Target(player)).unwrap();
+        *sim.world_mut().get::<&mut Animation>(monster).unwrap() = Animation { state, tics: 1 };
+        (sim, monster, player)
+    }
+
+    /// A zombieman in room B facing away from the player in room A; runs
+    /// `tics` tics, firing once the pistol is up, and says whether it woke
+    /// up.
+    fn wakes_up(middle: testmap::Middle, fire: bool) -> bool {
+        let mut level = testmap::three_rooms(middle);
+        let mut sim = TicRunner::new(&level);
+        let at = |x: f32| level.locate_subsector(Vec2::new(x, 64.0));
+        let player = sim.spawn_mobj(
+            &level,
+            defs::by_id("PLAYER").unwrap(),
+            32.0,
+            64.0,
+            0.0,
+            at(32.0),
+        );
+        sim.set_player(player);
+        let info = defs::by_id("POSSESSED").unwrap();
+        let monster = sim.spawn_mobj(&level, info, 200.0, 64.0, 0.0, at(200.0));
+        // bring the pistol up
+        for _ in 0..20 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        sim.run_tic(
+            &mut level,
+            InputCmd {
+                fire,
+                ..InputCmd::default()
+            },
+        );
+        for _ in 0..25 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let target = sim.world().get::<&Target>(monster).ok().map(|t| t.0);
+        let state = sim.world().get::<&Animation>(monster).unwrap().state;
+        assert_eq!(
+            target.is_some(),
+            !matches!(state, State::POSS_STND | State::POSS_STND2)
+        );
+        target == Some(player)
+    }
+
+    #[test]
+    fn gunshots_wake_monsters_through_open_doors() {
+        let open = testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        };
+        let shut = testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        };
+        assert!(wakes_up(open, true));
+        assert!(!wakes_up(open, false), "saw the player behind its back");
+        assert!(!wakes_up(shut, true), "heard through a closed door");
+    }
+
+    #[test]
+    fn ambushers_wake_on_sight_not_sound() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let at = |level: &Level, x: f32| level.locate_subsector(Vec2::new(x, 64.0));
+        // rooms A and B never see each other, whatever the BSP says
+        let sector = |x| level.sector_of_subsector(at(&level, x)) as usize;
+        let (a, b, n) = (sector(32.0), sector(250.0), level.sectors.len());
+        let mut bits = vec![0u8; (n * n).div_ceil(8)];
+        for i in [a * n + b, b * n + a] {
+            bits[i / 8] |= 1 << (i % 8);
+        }
+        level.reject = Some(crate::world::BitMatrix::from_lump(&bits, n));
+
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(
+            &level,
+            defs::by_id("PLAYER").unwrap(),
+            32.0,
+            64.0,
+            0.0,
+            at(&level, 32.0),
+        );
+        sim.set_player(player);
+        // two imps in room B looking west, one of them deaf
+        let imp = |y: f32, is_deaf: bool| Thing {
+            pos: Vec2::new(250.0, y),
+            angle: Angle::ANG180,
+            type_id: 3001,
+            skills: 7,
+            is_deaf,
+            multiplayer: false,
+            sub_sector: at(&level, 250.0),
+        };
+        let deaf = sim.spawn_map_thing(&level, &imp(30.0, true)).unwrap();
+        let hearing = sim.spawn_map_thing(&level, &imp(100.0, false)).unwrap();
+        let flags = sim.world().get::<&ActorFlags>(deaf).unwrap().0;
+        assert!(flags.contains(MobjFlags::AMBUSH));
+        let awake = |sim: &TicRunner, imp| sim.world().get::<&Target>(imp).is_ok();
+
+        for _ in 0..20 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let fire = InputCmd {
+            fire: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(&mut level, fire);
+        for _ in 0..25 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert!(awake(&sim, hearing));
+        assert!(!awake(&sim, deaf), "woke to a shot it couldn't see");
+
+        // the player walks east, into the room the imps can see
+        let walk = InputCmd {
+            forward: 1.0,
+            ..InputCmd::default()
+        };
+        for _ in 0..30 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert_eq!(sim.world().get::<&Target>(deaf).unwrap().0, player);
+    }
+
+    fn health(sim: &TicRunner, ent: Entity) -> i32 {
+        sim.world().get::<&PlayerStatus>(ent).unwrap().health
+    }
+
+    #[test]
+    fn zombieman_shoots_the_player() {
+        let (mut sim, monster, player) = attacker("POSSESSED", 160.0, State::POSS_ATK1);
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        sim.run_tic(&mut level, InputCmd::default());
+        // turned round to face the player, west
+        assert_eq!(*sim.world().get::<&Angle>(monster).unwrap(), Angle::ANG180);
+        for _ in 0..20 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let hurt = 100 - health(&sim, player);
+        assert!(hurt >= 3 && hurt % 3 == 0, "took {hurt}");
+    }
+
+    #[test]
+    fn imp_claws_close_and_throws_fireballs_far() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let (mut sim, _, player) = attacker("TROOP", 140.0, State::TROO_ATK2);
+        sim.run_tic(&mut level, InputCmd::default());
+        let hurt = 100 - health(&sim, player);
+        assert!((3..=24).contains(&hurt), "claw did {hurt}");
+
+        let (mut sim, imp, player) = attacker("TROOP", 250.0, State::TROO_ATK2);
+        sim.run_tic(&mut level, InputCmd::default());
+        assert_eq!(health(&sim, player), 100);
+        let ball = sim
+            .world()
+            .query::<(&Target, &crate::sim::Class)>()
+            .iter()
+            .find(|(_, (t, c))| t.0 == imp && c.0.id == "TROOPSHOT")
+            .map(|(e, _)| e)
+            .expect("no fireball");
+        let v = sim.world().get::<&crate::sim::Velocity>(ball).unwrap().0;
+        assert!(v.x < -9.0 && v.y.abs() < 1e-3, "{v}");
+
+        for _ in 0..30 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let hurt = 100 - health(&sim, player);
+        assert!(
+            (3..=24).contains(&hurt) && hurt % 3 == 0,
+            "fireball did {hurt}"
+        );
+        let exploded = sim
+            .world()
+            .get::<&Animation>(ball)
+            .map_or(true, |a| a.state >= State::TBALLX1);
+        assert!(exploded);
+    }
+
+    #[test]
+    fn fireballs_explode_against_walls_and_spare_their_shooter() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, imp, player) = attacker("TROOP", 60.0, State::TROO_ATK2);
+        // aim through the wall at a target in room B
+        let ss = level.locate_subsector(Vec2::new(200.0, 64.0));
+        let far = sim.spawn_mobj(
+            &level,
+            defs::by_id("POSSESSED").unwrap(),
+            200.0,
+            64.0,
+            0.0,
+            ss,
+        );
+        sim.world_mut().insert_one(imp, Target(far)).unwrap();
+        sim.world_mut().get::<&mut Position>(player).unwrap().0 = Vec2::new(20.0, 20.0);
+        for _ in 0..30 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let info = defs::by_id("TROOP").unwrap();
+        assert_eq!(sim.world().get::<&Health>(imp).unwrap().0, info.spawnhealth);
+        assert_eq!(sim.world().get::<&Health>(far).unwrap().0, 20);
+        let balls = sim
+            .world()
+            .query::<&crate::sim::Class>()
+            .iter()
+            .filter(|(_, c)| c.0.id == "TROOPSHOT")
+            .count();
+        assert!(balls <= 1);
+        let flying = sim
+            .world()
+            .query::<(&crate::sim::Class, &ActorFlags)>()
+            .iter()
+            .any(|(_, (c, f))| c.0.id == "TROOPSHOT" && f.0.contains(MobjFlags::MISSILE));
+        assert!(!flying, "fireball went through the wall");
+    }
+
+    #[test]
+    fn arch_vile_raises_the_corpse_ahead() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        let ss = level.locate_subsector(Vec2::new(200.0, 64.0));
+        let info = defs::by_id("POSSESSED").unwrap();
+        let zombie = sim.spawn_mobj(&level, info, 200.0, 64.0, 0.0, ss);
+        sim.damage_mobj(zombie, None, None, 25);
+        for _ in 0..40 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        sim.drain_events().for_each(drop);
+
+        let ss = level.locate_subsector(Vec2::new(150.0, 64.0));
+        let vile = sim.spawn_mobj(&level, defs::by_id("VILE").unwrap(), 150.0, 64.0, 0.0, ss);
+        *sim.world_mut().get::<&mut Animation>(vile).unwrap() = Animation {
+            state: State::VILE_RUN1,
+            tics: 1,
+        };
+        sim.run_tic(&mut level, InputCmd::default());
+
+        let state = |e| sim.world().get::<&Animation>(e).unwrap().state;
+        assert_eq!(state(vile), State::VILE_HEAL1);
+        assert_eq!(state(zombie), State::POSS_RAISE1);
+        let flags = sim.world().get::<&ActorFlags>(zombie).unwrap().0;
+        assert_eq!(flags.bits(), info.flags.bits());
+        assert_eq!(
+            sim.world().get::<&Health>(zombie).unwrap().0,
+            info.spawnhealth
+        );
+        assert!(sim.drain_events().any(|e| matches!(
+            e,
+            SimEvent::Sound {
+                sound: Sound::slop,
+                ..
+            }
+        )));
+    }
+}
@@ -0,0 +1,43 @@
+//! Sim → frontend notifications.
+//!
+//! Systems push events while a tic runs; the frontend drains them once per
+//! frame (`TicRunner::drain_events`) to play sounds, print messages, …
+
+use glam::Vec2;
+use hecs::Entity;
+
+use crate::defs::Sound;
+use crate::world::LinedefId;
+
+/// Which exit ended the level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelExit {
+    Normal,
+    Secret,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum SimEvent {
+    /// Start `sound` at map position `origin`; `full_volume` ones are
+    /// heard the same everywhere (vanilla `S_StartSound(NULL, …)`, the
+    /// bosses' sight and death cries).
+    Sound {
+        sound: Sound,
+        origin: Vec2,
+        full_volume: bool,
+    },
+    /// The player picked something up; `count` is their new bonus count,
+    /// which the yellow palette flash fades from.
+    Bonus { count: i32 },
+    /// An exit switch or line was triggered (vanilla `G_ExitLevel`).
+    Exit(LevelExit),
+    /// A line for the player's message bar, e.g. a locked door's.
+    Message(&'static str),
+    /// A bullet crossed the special `line` from `side`; the sim sets off
+    /// its gun special once the tic is done (vanilla `P_ShootSpecialLine`).
+    LineShot {
+        line: LinedefId,
+        side: usize,
+        shooter: Entity,
+    },
+}
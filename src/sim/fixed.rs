@@ -0,0 +1,239 @@
+//! Vanilla 16.16 fixed-point math (m_fixed.c, tables.c) for
+//! `PhysicsConfig::fixed_point`.
+//!
+//! The components stay `f32`; movement converts at the system boundary
+//! and keeps the exact values in a [`FixedMotion`] beside them, so f32
+//! rounding never feeds back into the next tic.
+
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use std::sync::OnceLock;
+
+use glam::Vec2;
+
+pub const FRACBITS: u32 = 16;
+
+/// Entries in the fine sine table per turn.
+const FINEANGLES: usize = 8192;
+/// BAM → fine table index.
+const ANGLETOFINESHIFT: u32 = 19;
+pub const ANG90: u32 = 0x4000_0000;
+
+/// A vanilla `fixed_t`: 16 integer and 16 fraction bits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(pub i32);
+
+impl Fixed {
+    pub const ZERO: Self = Self(0);
+    /// `FRACUNIT`.
+    pub const ONE: Self = Self(1 << FRACBITS);
+
+    #[inline]
+    pub const fn from_int(n: i32) -> Self {
+        Self(n << FRACBITS)
+    }
+
+    /// Nearest fixed value to `v`.
+    #[inline]
+    pub fn from_f32(v: f32) -> Self {
+        Self((f64::from(v) * f64::from(1 << FRACBITS)).round() as i32)
+    }
+
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        (f64::from(self.0) / f64::from(1 << FRACBITS)) as f32
+    }
+
+    /// `x / 2` the C way, truncating towards zero (`>> 1` floors).
+    #[inline]
+    pub fn half(self) -> Self {
+        Self(self.0 / 2)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+    #[inline]
+    fn add(self, o: Self) -> Self {
+        Self(self.0.wrapping_add(o.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+    #[inline]
+    fn sub(self, o: Self) -> Self {
+        Self(self.0.wrapping_sub(o.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    #[inline]
+    fn add_assign(&mut self, o: Self) {
+        *self = *self + o;
+    }
+}
+
+impl SubAssign for Fixed {
+    #[inline]
+    fn sub_assign(&mut self, o: Self) {
+        *self = *self - o;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+/// Vanilla `FixedMul`.
+impl Mul for Fixed {
+    type Output = Self;
+    #[inline]
+    fn mul(self, o: Self) -> Self {
+        Self(((i64::from(self.0) * i64::from(o.0)) >> FRACBITS) as i32)
+    }
+}
+
+/// Vanilla `FixedDiv`: saturates instead of overflowing.
+impl Div for Fixed {
+    type Output = Self;
+    #[inline]
+    fn div(self, o: Self) -> Self {
+        if (self.0.unsigned_abs() >> 14) >= o.0.unsigned_abs() {
+            return Self(if (self.0 ^ o.0) < 0 {
+                i32::MIN
+            } else {
+                i32::MAX
+            });
+        }
+        Self(((i64::from(self.0) << FRACBITS) / i64::from(o.0)) as i32)
+    }
+}
+
+/// `angle` (radians) as a vanilla BAM angle.  Rounded to whole
+/// `angleturn` steps (1/65536 turn), which every vanilla angle is, so the
+/// f32 error can't tip it into the neighbouring fine table entry.
+pub fn to_bam(angle: f32) -> u32 {
+    let turns = f64::from(angle) / std::f64::consts::TAU;
+    ((turns * 65536.0).round() as i64 as u32) << 16
+}
+
+/// Vanilla `finesine`: 5/4 of a turn so `finecosine` can start a quarter
+/// in.  Sampled mid-step and truncated, like tables.c.
+fn fine_table() -> &'static [i32] {
+    static TABLE: OnceLock<Vec<i32>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        (0..FINEANGLES * 5 / 4)
+            .map(|i| {
+                let a = (i as f64 + 0.5) * std::f64::consts::TAU / FINEANGLES as f64;
+                (a.sin() * f64::from(1 << FRACBITS)) as i32
+            })
+            .collect()
+    })
+}
+
+/// `finesine[bam >> ANGLETOFINESHIFT]`.
+#[inline]
+pub fn fine_sine(bam: u32) -> Fixed {
+    Fixed(fine_table()[(bam >> ANGLETOFINESHIFT) as usize])
+}
+
+/// `finecosine[bam >> ANGLETOFINESHIFT]`.
+#[inline]
+pub fn fine_cosine(bam: u32) -> Fixed {
+    Fixed(fine_table()[(bam >> ANGLETOFINESHIFT) as usize + FINEANGLES / 4])
+}
+
+/// Exact position and momentum of a thing moved in fixed point.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FixedMotion {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub momx: Fixed,
+    pub momy: Fixed,
+}
+
+impl FixedMotion {
+    pub fn from_f32(pos: Vec2, vel: Vec2) -> Self {
+        Self {
+            x: Fixed::from_f32(pos.x),
+            y: Fixed::from_f32(pos.y),
+            momx: Fixed::from_f32(vel.x),
+            momy: Fixed::from_f32(vel.y),
+        }
+    }
+
+    /// `prev` if `pos` and `vel` are still what it rounds to; otherwise
+    /// something else moved the thing since, so start over from them.
+    pub fn sync(prev: Option<Self>, pos: Vec2, vel: Vec2) -> Self {
+        match prev {
+            Some(m) if m.pos() == pos && m.vel() == vel => m,
+            _ => Self::from_f32(pos, vel),
+        }
+    }
+
+    /// Vanilla `P_Thrust`: push along the BAM `angle`.
+    pub fn thrust(&mut self, angle: u32, mv: Fixed) {
+        self.momx += mv * fine_cosine(angle);
+        self.momy += mv * fine_sine(angle);
+    }
+
+    #[inline]
+    pub fn pos(&self) -> Vec2 {
+        Vec2::new(self.x.to_f32(), self.y.to_f32())
+    }
+
+    #[inline]
+    pub fn vel(&self) -> Vec2 {
+        Vec2::new(self.momx.to_f32(), self.momy.to_f32())
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_and_div_round_like_vanilla() {
+        let (a, b) = (Fixed(0xE800), Fixed(-0x1_2345));
+        // FixedMul shifts the 64-bit product, so negatives round down
+        assert_eq!(a * b, Fixed(((0xE800_i64 * -0x1_2345) >> 16) as i32));
+        assert_eq!(Fixed::from_int(3) * Fixed::ONE, Fixed::from_int(3));
+        assert_eq!(Fixed::from_int(7) / Fixed::from_int(2), Fixed(0x3_8000));
+        assert_eq!(Fixed::from_int(-1) / Fixed(3), Fixed(i32::MIN));
+        assert_eq!(Fixed(-3).half(), Fixed(-1));
+    }
+
+    #[test]
+    fn fine_tables_match_tables_c() {
+        // spot checks against vanilla's finesine
+        let sine: Vec<i32> = (0..4).map(|i| fine_table()[i]).collect();
+        assert_eq!(sine, [25, 75, 125, 175]);
+        assert_eq!(fine_sine(ANG90), Fixed(65535));
+        assert_eq!(fine_cosine(ANG90), Fixed(-25));
+        assert_eq!(fine_cosine(0), Fixed(65535));
+        assert_eq!(to_bam(std::f32::consts::FRAC_PI_2), ANG90);
+        assert_eq!(to_bam(90_f32.to_radians()), ANG90);
+    }
+
+    #[test]
+    fn motion_survives_f32_round_trips() {
+        let m = FixedMotion {
+            x: Fixed(1056 << 16 | 0x1234),
+            y: Fixed(-3616 << 16 | 0x0F0F),
+            momx: Fixed(-40),
+            momy: Fixed(0x1_9000),
+        };
+        assert_eq!(FixedMotion::sync(Some(m), m.pos(), m.vel()), m);
+        // moved by something else: start over from the f32 values
+        let moved = FixedMotion::sync(Some(m), m.pos() + Vec2::X, m.vel());
+        assert_eq!(moved, FixedMotion::from_f32(m.pos() + Vec2::X, m.vel()));
+        assert_ne!(moved.x, m.x + Fixed::ONE);
+    }
+}
@@ -0,0 +1,231 @@
+//! Moving floors – vanilla `p_floor.c` (`EV_DoFloor`, `T_MoveFloor`).
+//!
+//! A floor mover takes a sector's floor to a height picked from its
+//! neighbours when it starts, then is done.  Stairs, donuts and the
+//! texture-changing kinds aren't here yet.
+
+use hecs::World;
+
+use super::ceilings::Ceiling;
+use super::doors::Door;
+use super::events::SimEvent;
+use super::movers::{MoveResult, sector_busy, t_move_floor};
+use super::plats::Platform;
+use super::{GameRules, PhysicsConfig, Rng, ThingGrid};
+use crate::defs::{Definitions, Sound};
+use crate::world::{Level, SectorId};
+
+/// Floor speed in units per tic (vanilla `FLOORSPEED`).
+pub const FLOOR_SPEED: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloorKind {
+    /// Down to the highest neighbouring floor.
+    Lower,
+    /// Down to the lowest neighbouring floor.
+    LowerToLowest,
+    /// Down to 8 above the highest neighbouring floor, at four times
+    /// `FLOOR_SPEED`.
+    TurboLower,
+    /// Up to the lowest neighbouring ceiling.
+    Raise,
+    /// Up to 8 below the lowest neighbouring ceiling, crushing.
+    RaiseCrush,
+    /// Up to the next higher neighbouring floor.
+    RaiseToNearest,
+    /// `RaiseToNearest` at four times `FLOOR_SPEED`.
+    RaiseTurbo,
+    Raise24,
+    Raise512,
+}
+
+/// One running floor (vanilla `floormove_t`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Floor {
+    pub sector: SectorId,
+    pub kind: FloorKind,
+    pub dest: f32,
+    pub speed: f32,
+    /// 1 up, -1 down.
+    pub direction: i32,
+    pub crush: bool,
+}
+
+/// Vanilla `EV_DoFloor`: start a `kind` floor in every sector tagged
+/// `tag` whose floor isn't already moving.  `true` if one started.
+pub fn ev_do_floor(
+    level: &Level,
+    floors: &mut Vec<Floor>,
+    doors: &[Door],
+    ceilings: &[Ceiling],
+    plats: &[Platform],
+    tag: u16,
+    kind: FloorKind,
+) -> bool {
+    let mut rtn = false;
+    for (id, sector) in level.sectors.iter().enumerate() {
+        let id = id as SectorId;
+        if sector.tag as u16 != tag || sector_busy(doors, ceilings, plats, floors, id) {
+            continue;
+        }
+        rtn = true;
+
+        let floor = sector.floor_h;
+        let (direction, speed, dest) = match kind {
+            FloorKind::Lower => (-1, FLOOR_SPEED, level.highest_neighbor_floor(id)),
+            FloorKind::LowerToLowest => (-1, FLOOR_SPEED, level.lowest_neighbor_floor(id)),
+            FloorKind::TurboLower => {
+                let mut dest = level.highest_neighbor_floor(id);
+                if dest != floor {
+                    dest += 8.0;
+                }
+                (-1, FLOOR_SPEED * 4.0, dest)
+            }
+            FloorKind::Raise | FloorKind::RaiseCrush => {
+                let mut dest = level.lowest_neighbor_ceiling(id).min(sector.ceil_h);
+                if kind == FloorKind::RaiseCrush {
+                    dest -= 8.0;
+                }
+                (1, FLOOR_SPEED, dest)
+            }
+            FloorKind::RaiseToNearest => (1, FLOOR_SPEED, level.next_higher_floor(id, floor)),
+            FloorKind::RaiseTurbo => (1, FLOOR_SPEED * 4.0, level.next_higher_floor(id, floor)),
+            FloorKind::Raise24 => (1, FLOOR_SPEED, floor + 24.0),
+            FloorKind::Raise512 => (1, FLOOR_SPEED, floor + 512.0),
+        };
+        floors.push(Floor {
+            sector: id,
+            kind,
+            dest,
+            speed,
+            direction,
+            crush: kind == FloorKind::RaiseCrush,
+        });
+    }
+    rtn
+}
+
+/// Advance every floor one tic (vanilla `T_MoveFloor`); floors that got
+/// there leave the list.
+#[allow(clippy::too_many_arguments)]
+pub fn run_floors(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    gametic: u32,
+    floors: &mut Vec<Floor>,
+) {
+    floors.retain(|f| {
+        let res = t_move_floor(
+            world,
+            thing_grid,
+            level,
+            cfg,
+            rng,
+            rules,
+            defs,
+            gametic,
+            f.sector,
+            f.speed,
+            f.dest,
+            f.crush,
+            f.direction,
+        );
+        let origin = level.sound_origin(f.sector);
+        if gametic & 7 == 0 {
+            events.push(SimEvent::Sound {
+                sound: Sound::stnmov,
+                origin,
+                full_volume: false,
+            });
+        }
+        if res == MoveResult::PastDest {
+            events.push(SimEvent::Sound {
+                sound: Sound::pstop,
+                origin,
+                full_volume: false,
+            });
+            return false;
+        }
+        true
+    });
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::testmap;
+
+    #[test]
+    fn floors_pick_their_destination_from_the_neighbours() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 32.0,
+            ceil: 96.0,
+        });
+        level.sectors[1].tag = 2;
+        level.sectors[2].floor_h = 16.0;
+        let dest = |level: &Level, kind| {
+            let mut floors = Vec::new();
+            assert!(ev_do_floor(level, &mut floors, &[], &[], &[], 2, kind));
+            floors[0].dest
+        };
+        assert_eq!(dest(&level, FloorKind::Lower), 16.0);
+        assert_eq!(dest(&level, FloorKind::LowerToLowest), 0.0);
+        assert_eq!(dest(&level, FloorKind::TurboLower), 24.0);
+        assert_eq!(dest(&level, FloorKind::Raise), 96.0);
+        assert_eq!(dest(&level, FloorKind::RaiseCrush), 88.0);
+        // no higher neighbouring floor: stays put
+        assert_eq!(dest(&level, FloorKind::RaiseToNearest), 32.0);
+        assert_eq!(dest(&level, FloorKind::Raise24), 56.0);
+
+        // a lift on the sector keeps floors off it
+        let mut floors = Vec::new();
+        let mut sim = crate::sim::TicRunner::new(&level);
+        let mut rng = Rng::default();
+        let mut events = Vec::new();
+        let mut plats = Vec::new();
+        crate::sim::plats::ev_do_plat(
+            &level,
+            &mut plats,
+            &[],
+            &[],
+            &[],
+            &mut rng,
+            &mut events,
+            2,
+            crate::sim::plats::PlatKind::DownWaitUpStay,
+        );
+        assert!(!ev_do_floor(
+            &level,
+            &mut floors,
+            &[],
+            &[],
+            &plats,
+            2,
+            FloorKind::Raise
+        ));
+
+        // and a running one gets there, then stops
+        sim.set_floors(vec![Floor {
+            sector: 1,
+            kind: FloorKind::Raise24,
+            dest: 56.0,
+            speed: FLOOR_SPEED,
+            direction: 1,
+            crush: false,
+        }]);
+        for _ in 0..30 {
+            sim.run_tic(&mut level, crate::sim::InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].floor_h, 56.0);
+        assert!(sim.floors().is_empty());
+    }
+}
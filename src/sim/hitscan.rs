@@ -0,0 +1,548 @@
+//! Hitscan attacks – the shooting half of vanilla p_map.c
+//! (`P_AimLineAttack`, `P_LineAttack`).
+//!
+//! What the shot crosses comes from [`trace`](super::trace), nearest
+//! first.
+
+use glam::Vec2;
+use hecs::{Entity, World};
+
+use super::enemy::MELEE_RANGE;
+use super::events::SimEvent;
+use super::spacial::fetch_thing;
+use super::specials::point_on_line_side;
+use super::trace::{self, Crossed, Intercept};
+use super::{GameRules, Rng, ThingGrid, damage, mob};
+use crate::defs::{Definitions, MobjFlags};
+use crate::world::{Angle, BlockmapQuery, Level, LinedefFlags, LinedefId, SidedefId};
+
+/// How far bullets and monster missiles reach (vanilla `MISSILERANGE`).
+pub const MISSILE_RANGE: f32 = 32.0 * 64.0;
+
+/// How far either side of straight ahead the player's shots look for
+/// something to aim at when nothing is dead ahead (vanilla `1<<26`).
+pub const AUTOAIM_NUDGE: Angle = Angle(1 << 26);
+
+/// Vertical aim window of `P_AimLineAttack`, as slopes.
+const AIM_SLOPE: f32 = 100.0 / 160.0;
+
+/// Opening of the two-sided `line`: (floors differ, ceilings differ,
+/// top, bottom).  `None` for a one-sided wall.
+fn opening(level: &Level, line: LinedefId) -> Option<(bool, bool, f32, f32)> {
+    let line = &level.linedefs[line as usize];
+    if !line.flags.contains(LinedefFlags::TWO_SIDED) {
+        return None;
+    }
+    let (front, back) = (line.right_sidedef?, line.left_sidedef?);
+    let front = &level.sectors[level.sidedefs[front as usize].sector as usize];
+    let back = &level.sectors[level.sidedefs[back as usize].sector as usize];
+    Some((
+        front.floor_h != back.floor_h,
+        front.ceil_h != back.ceil_h,
+        front.ceil_h.min(back.ceil_h),
+        front.floor_h.max(back.floor_h),
+    ))
+}
+
+/// A shot stopped by `line` at height `z` went into the sky, and leaves
+/// no puff: above a sky front ceiling, or at a sky hack wall between two
+/// skies.
+fn hits_sky(level: &Level, line: LinedefId, z: f32) -> bool {
+    let line = &level.linedefs[line as usize];
+    let sector = |side: Option<SidedefId>| {
+        side.map(|sd| &level.sectors[level.sidedefs[sd as usize].sector as usize])
+    };
+    let Some(front) = sector(line.right_sidedef).filter(|s| s.is_sky_ceiling) else {
+        return false;
+    };
+    z > front.ceil_h || sector(line.left_sidedef).is_some_and(|s| s.is_sky_ceiling)
+}
+
+/// Where `shooter` fires from: `origin`, `shootz`.
+fn shot_origin(world: &World, shooter: Entity) -> Option<(Vec2, f32)> {
+    let (pos, class, _) = fetch_thing(world, shooter)?;
+    Some((pos.0, pos.1 + class.0.height as f32 * 0.5 + 8.0))
+}
+
+/// How far the trace from `origin` along `delta`, at height `z`, gets
+/// before a wall stops it, as a fraction of `delta`: 1 if nothing does.
+/// The chase camera backs off along this.
+pub fn trace_to_wall(
+    level: &Level,
+    query: &BlockmapQuery,
+    origin: Vec2,
+    z: f32,
+    delta: Vec2,
+) -> f32 {
+    let mut frac = 1.0;
+    trace::trace_lines_only(level, query, origin, delta, |ic| {
+        let Crossed::Line(line) = ic.what else {
+            return true;
+        };
+        match opening(level, line) {
+            Some((_, _, top, bottom)) if z > bottom && z < top => true,
+            _ => {
+                frac = ic.frac;
+                false
+            }
+        }
+    });
+    frac
+}
+
+/// Vanilla `P_AimLineAttack`: the first shootable thing `shooter` could
+/// hit at `angle` within `range`, and the slope to aim at it.
+pub fn p_aim_line_attack(
+    world: &World,
+    level: &Level,
+    grid: &ThingGrid,
+    shooter: Entity,
+    angle: Angle,
+    range: f32,
+) -> Option<(Entity, f32)> {
+    let (origin, shootz) = shot_origin(world, shooter)?;
+    let delta = angle.unit() * range;
+    let (mut top_slope, mut bottom_slope) = (AIM_SLOPE, -AIM_SLOPE);
+
+    let mut target = None;
+    trace::trace_all(level, grid, origin, delta, |ic| {
+        let dist = range * ic.frac;
+        match ic.what {
+            Crossed::Line(line) => {
+                // crosses a two-sided line: the opening narrows the window
+                let Some((floors, ceils, top, bottom)) = opening(level, line) else {
+                    return false;
+                };
+                if bottom >= top {
+                    return false;
+                }
+                if floors {
+                    bottom_slope = bottom_slope.max((bottom - shootz) / dist);
+                }
+                if ceils {
+                    top_slope = top_slope.min((top - shootz) / dist);
+                }
+                top_slope > bottom_slope
+            }
+            Crossed::Thing(ent) => {
+                if ent == shooter {
+                    return true;
+                }
+                let Some((pos, class, flags)) = fetch_thing(world, ent) else {
+                    return true;
+                };
+                if !flags.0.contains(MobjFlags::SHOOTABLE) {
+                    return true;
+                }
+                let thing_top = (pos.1 + class.0.height as f32 - shootz) / dist;
+                let thing_bottom = (pos.1 - shootz) / dist;
+                if thing_top < bottom_slope || thing_bottom > top_slope {
+                    return true; // shot over or under it
+                }
+                let slope = (thing_top.min(top_slope) + thing_bottom.max(bottom_slope)) / 2.0;
+                target = Some((ent, slope));
+                false
+            }
+        }
+    });
+    target
+}
+
+/// Vanilla `P_LineAttack`: fire a bullet from `shooter` at `angle` and
+/// `slope`; the first shootable thing in its path takes `damage`.  A
+/// wall gets a puff of smoke, a thing a splat of blood (or a puff, if it
+/// doesn't bleed).  Every special line the bullet reaches is reported
+/// as a [`SimEvent::LineShot`].  Returns what was hit.
+#[allow(clippy::too_many_arguments)]
+pub fn p_line_attack(
+    world: &mut World,
+    grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    shooter: Entity,
+    angle: Angle,
+    range: f32,
+    slope: f32,
+    damage: i32,
+) -> Option<Entity> {
+    let (origin, shootz) = shot_origin(world, shooter)?;
+    let delta = angle.unit() * range;
+
+    // what stops the shot
+    let mut stop = None;
+    trace::trace_all(level, grid, origin, delta, |ic| {
+        let dist = range * ic.frac;
+        if let Crossed::Line(line) = ic.what {
+            let ld = &level.linedefs[line as usize];
+            if ld.special != 0 {
+                let v1 = level.vertices[ld.v1 as usize].pos;
+                let v2 = level.vertices[ld.v2 as usize].pos;
+                events.push(SimEvent::LineShot {
+                    line,
+                    side: point_on_line_side(origin, v1, v2),
+                    shooter,
+                });
+            }
+        }
+        let stops = match ic.what {
+            Crossed::Line(line) => match opening(level, line) {
+                Some((floors, ceils, top, bottom)) => {
+                    let z = shootz + slope * dist;
+                    (floors && z < bottom) || (ceils && z > top)
+                }
+                None => true,
+            },
+            Crossed::Thing(ent) => {
+                ent != shooter
+                    && fetch_thing(world, ent).is_some_and(|(pos, class, flags)| {
+                        let thing_top = (pos.1 + class.0.height as f32 - shootz) / dist;
+                        let thing_bottom = (pos.1 - shootz) / dist;
+                        // not shot over or under it
+                        flags.0.contains(MobjFlags::SHOOTABLE)
+                            && thing_top >= slope
+                            && thing_bottom <= slope
+                    })
+            }
+        };
+        if stops {
+            stop = Some(ic);
+        }
+        !stops
+    });
+
+    // where the trace is `back` units short of `frac` along
+    let short_of = |frac: f32, back: f32| {
+        let frac = frac - back / range;
+        (origin + delta * frac, shootz + slope * frac * range)
+    };
+    let melee = range == MELEE_RANGE;
+    let hit = match stop? {
+        Intercept {
+            frac,
+            what: Crossed::Line(line),
+        } => {
+            // hit line: position a bit closer
+            let (at, z) = short_of(frac, 4.0);
+            if !hits_sky(level, line, z) {
+                mob::p_spawn_puff(world, grid, level, rng, defs, at, z, melee);
+            }
+            return None;
+        }
+        Intercept {
+            frac,
+            what: Crossed::Thing(ent),
+        } => {
+            // hit thing: position a bit closer
+            let (at, z) = short_of(frac, 10.0);
+            let noblood = fetch_thing(world, ent)
+                .is_some_and(|(_, _, flags)| flags.0.contains(MobjFlags::NOBLOOD));
+            if noblood {
+                mob::p_spawn_puff(world, grid, level, rng, defs, at, z, melee);
+            } else {
+                mob::p_spawn_blood(world, grid, level, rng, defs, at, z, damage);
+            }
+            ent
+        }
+    };
+
+    damage::p_damage_mobj(
+        world,
+        rng,
+        rules,
+        defs,
+        hit,
+        Some(shooter),
+        Some(shooter),
+        damage,
+    );
+    Some(hit)
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{
+            Class, Health, InputCmd, PlayerStatus, Position, TicRunner, WeaponType, Weapons, mob,
+        },
+        world::testmap,
+    };
+
+    #[test]
+    fn bullets_stop_at_walls_and_the_first_target() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut world = World::new();
+        let mut grid = ThingGrid::new(level.blockmap.origin);
+        let mut rng = Rng::default();
+        let info = defs::by_id("POSSESSED").unwrap();
+        let mut spawn = |x: f32| {
+            mob::spawn_mobj_at(
+                &mut world,
+                &mut grid,
+                &level,
+                &Definitions::shared_builtin(),
+                info,
+                Vec2::new(x, 64.0),
+                Angle::ZERO,
+            )
+        };
+        let (shooter, near, far, behind) = (spawn(20.0), spawn(70.0), spawn(110.0), spawn(200.0));
+        let health = |world: &World, e| world.get::<&Health>(e).unwrap().0;
+
+        let aim = p_aim_line_attack(&world, &level, &grid, shooter, Angle::ZERO, MISSILE_RANGE);
+        assert_eq!(aim.map(|(e, _)| e), Some(near));
+        let shot = |world: &mut World, grid: &mut ThingGrid, rng: &mut Rng, from, angle, slope| {
+            p_line_attack(
+                world,
+                grid,
+                &level,
+                rng,
+                GameRules::VANILLA,
+                &Definitions::shared_builtin(),
+                &mut Vec::new(),
+                from,
+                angle,
+                MISSILE_RANGE,
+                slope,
+                5,
+            )
+        };
+        assert_eq!(
+            shot(&mut world, &mut grid, &mut rng, shooter, Angle::ZERO, 0.0),
+            Some(near)
+        );
+        assert_eq!((health(&world, near), health(&world, far)), (15, 20));
+
+        // the wall between the rooms stops the shot
+        assert_eq!(
+            p_aim_line_attack(&world, &level, &grid, far, Angle::ZERO, MISSILE_RANGE),
+            None
+        );
+        assert_eq!(
+            shot(&mut world, &mut grid, &mut rng, far, Angle::ZERO, 0.0),
+            None
+        );
+        assert_eq!(health(&world, behind), 20);
+        let back = Angle::ANG180;
+        assert_eq!(
+            shot(&mut world, &mut grid, &mut rng, far, back, 0.0),
+            Some(near)
+        );
+
+        // a steep slope flies over everyone's head
+        assert_eq!(
+            shot(&mut world, &mut grid, &mut rng, shooter, Angle::ZERO, 2.0),
+            None
+        );
+    }
+
+    #[test]
+    fn aim_goes_through_windows_and_past_decorations() {
+        // a window 48‥80 high between rooms A and C
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 48.0,
+            ceil: 80.0,
+        });
+        let mut world = World::new();
+        let mut grid = ThingGrid::new(level.blockmap.origin);
+        let spawn = |world: &mut World, grid: &mut ThingGrid, id: &str, x: f32| {
+            let info = defs::by_id(id).unwrap();
+            let at = Vec2::new(x, 64.0);
+            mob::spawn_mobj_at(
+                world,
+                grid,
+                &level,
+                &Definitions::shared_builtin(),
+                info,
+                at,
+                Angle::ZERO,
+            )
+        };
+        let shooter = spawn(&mut world, &mut grid, "POSSESSED", 60.0);
+        let target = spawn(&mut world, &mut grid, "POSSESSED", 230.0);
+        let aim = |world: &World, grid: &ThingGrid| {
+            p_aim_line_attack(world, &level, grid, shooter, Angle::ZERO, MISSILE_RANGE)
+        };
+
+        // standing on room C's floor it's all below the sill
+        assert_eq!(aim(&world, &grid), None);
+
+        // lifted into view, the aim threads the window at both its edges
+        world.get::<&mut Position>(target).unwrap().1 = 40.0;
+        let (hit, slope) = aim(&world, &grid).unwrap();
+        assert_eq!(hit, target);
+        let shootz = 56.0 / 2.0 + 8.0;
+        for edge in [128.0, 144.0] {
+            let z = shootz + slope * (edge - 60.0);
+            assert!((48.0..=80.0).contains(&z), "{z} at x = {edge}");
+        }
+
+        // a pillar in the way isn't shootable, so it isn't aimed at
+        let pillar = spawn(&mut world, &mut grid, "MISC32", 90.0);
+        assert_eq!(aim(&world, &grid), Some((target, slope)));
+        world.despawn(pillar).unwrap();
+        let near = spawn(&mut world, &mut grid, "POSSESSED", 90.0);
+        assert_eq!(aim(&world, &grid).map(|(e, _)| e), Some(near));
+    }
+
+    /// Every `id` mobj, by position.
+    fn effects(sim: &TicRunner, id: &str) -> Vec<Position> {
+        sim.world()
+            .query::<(&Class, &Position)>()
+            .iter()
+            .filter(|(_, (c, _))| c.0.id == id)
+            .map(|(_, (_, p))| *p)
+            .collect()
+    }
+
+    /// A player in room A facing the wall east, `weapon` up; pulls the
+    /// trigger once and runs until the shot is out.
+    fn fire_at_wall(level: &mut Level, weapon: WeaponType, target: Option<f32>) -> TicRunner {
+        let mut sim = TicRunner::new(level);
+        let player = sim.spawn_mobj(level, defs::by_id("PLAYER").unwrap(), 32.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        {
+            let world = sim.world_mut();
+            let owned = [true; crate::sim::weapons::NUM_WEAPONS];
+            world
+                .insert_one(
+                    player,
+                    Weapons::new(&Definitions::shared_builtin(), owned, weapon),
+                )
+                .unwrap();
+            world.get::<&mut PlayerStatus>(player).unwrap().ammo = [50; 4];
+        }
+        if let Some(x) = target {
+            let info = defs::by_id("POSSESSED").unwrap();
+            sim.spawn_mobj(level, info, x, 64.0, 0.0, 0);
+        }
+        for _ in 0..20 {
+            sim.run_tic(level, InputCmd::default());
+        }
+        let fire = InputCmd {
+            fire: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(level, fire);
+        // PISTOL1 and SGUN1 both wait 4 tics before the shot
+        for _ in 0..4 {
+            sim.run_tic(level, InputCmd::default());
+        }
+        sim
+    }
+
+    #[test]
+    fn walls_puff_and_things_bleed() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = fire_at_wall(&mut level, WeaponType::Pistol, None);
+        let puffs = effects(&sim, "PUFF");
+        assert_eq!(puffs.len(), 1);
+        // pulled back off the wall, a little below the eye
+        let puff = puffs[0];
+        assert!(puff.0.x < 128.0 - 3.0 && puff.0.x > 100.0, "{puff:?}");
+        assert!((puff.1 - 36.0).abs() <= 4.0, "{puff:?}");
+        // smoke drifts up, then is gone
+        sim.run_tic(&mut level, InputCmd::default());
+        assert!(effects(&sim, "PUFF")[0].1 > puff.1);
+        for _ in 0..20 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert!(effects(&sim, "PUFF").is_empty());
+
+        // the zombieman takes the bullet, and bleeds instead
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = fire_at_wall(&mut level, WeaponType::Pistol, Some(96.0));
+        assert!(effects(&sim, "PUFF").is_empty());
+        let blood = effects(&sim, "BLOOD");
+        assert_eq!(blood.len(), 1);
+        assert!(blood[0].0.x < 96.0, "{blood:?}");
+        for _ in 0..30 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert!(effects(&sim, "BLOOD").is_empty());
+    }
+
+    #[test]
+    fn no_puffs_on_the_sky() {
+        // under a sky ceiling a wall still puffs
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        level.sectors[0].is_sky_ceiling = true;
+        let sim = fire_at_wall(&mut level, WeaponType::Pistol, None);
+        assert_eq!(effects(&sim, "PUFF").len(), 1);
+
+        // a closed strip between two skies is a sky hack wall: the shot
+        // goes into the sky
+        let closed = testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        };
+        let mut level = testmap::three_rooms(closed);
+        level.sectors[0].is_sky_ceiling = true;
+        level.sectors[1].is_sky_ceiling = true;
+        let sim = fire_at_wall(&mut level, WeaponType::Pistol, None);
+        assert!(effects(&sim, "PUFF").is_empty());
+    }
+
+    #[test]
+    fn shotgun_spreads_seven_puffs_on_a_wall() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let sim = fire_at_wall(&mut level, WeaponType::Shotgun, None);
+        let puffs = effects(&sim, "PUFF");
+        assert_eq!(puffs.len(), 7);
+        let ys: Vec<f32> = puffs.iter().map(|p| p.0.y).collect();
+        let spread = ys.iter().copied().fold(f32::MIN, f32::max)
+            - ys.iter().copied().fold(f32::MAX, f32::min);
+        assert!(spread > 1.0, "no spread: {ys:?}");
+    }
+
+    #[test]
+    fn gun_lines_go_off_when_shot() {
+        // the closed strip M is a door behind a GR line
+        let closed = testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        };
+        let mut level = testmap::three_rooms(closed);
+        let sep = level
+            .linedefs
+            .iter()
+            .position(|l| (l.v1, l.v2) == (1, 5))
+            .unwrap();
+        level.linedefs[sep].special = 46; // GR door open
+        level.linedefs[sep].tag = 7;
+        level.sectors[1].tag = 7;
+
+        let mut sim = fire_at_wall(&mut level, WeaponType::Pistol, None);
+        assert_eq!(sim.doors().len(), 1);
+        for _ in 0..70 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].ceil_h, 124.0);
+        assert!(sim.doors().is_empty());
+        assert_eq!(level.linedefs[sep].special, 46, "GR lines stay armed");
+
+        // shut again, it opens to a second shot
+        level.sectors[1].ceil_h = 0.0;
+        let sim = fire_at_wall(&mut level, WeaponType::Pistol, None);
+        assert_eq!(sim.doors().len(), 1);
+
+        // a G1 floor is spent by the shot that starts it
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 64.0,
+        });
+        level.linedefs[sep].special = 24; // G1 floor raise
+        level.linedefs[sep].tag = 7;
+        level.sectors[1].tag = 7;
+        let sim = fire_at_wall(&mut level, WeaponType::Pistol, None);
+        assert_eq!(sim.floors().len(), 1);
+        assert_eq!(level.linedefs[sep].special, 0);
+    }
+}
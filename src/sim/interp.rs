@@ -0,0 +1,162 @@
+//! Render interpolation between sim tics.
+//!
+//! The sim runs at a fixed 35 Hz.  Every thing remembers where it stood
+//! when the current tic began (`PrevPosition`); a frontend drawing faster
+//! than that blends from there towards the current spot by the fraction
+//! of a tic that has passed since.  Sector floors and ceilings the movers
+//! push about blend the same way ([`SectorHeights`]).
+
+use hecs::World;
+
+use super::{Angle, Position, PrevPosition};
+use crate::world::Level;
+
+/// Blend two angles along the shorter arc.
+#[inline]
+pub fn lerp_angle(from: Angle, to: Angle, alpha: f32) -> Angle {
+    let delta = (to - from).0 as i32 as f32 * alpha;
+    from + Angle(delta as i32 as u32)
+}
+
+/// Position and angle `alpha` of the way from `prev` to the current ones.
+#[inline]
+pub fn lerp(prev: &PrevPosition, pos: &Position, angle: &Angle, alpha: f32) -> (Position, Angle) {
+    let PrevPosition(p, a) = prev;
+    (
+        Position(p.0.lerp(pos.0, alpha), p.1 + (pos.1 - p.1) * alpha),
+        lerp_angle(*a, *angle, alpha),
+    )
+}
+
+/// Start-of-tic snapshot: every `PrevPosition` catches up with its thing.
+pub fn store_prev_positions(world: &mut World) {
+    for (_, (prev, pos, angle)) in world.query_mut::<(&mut PrevPosition, &Position, &Angle)>() {
+        *prev = PrevPosition(*pos, *angle);
+    }
+}
+
+/// Every sector's floor and ceiling height when the current tic began,
+/// and while a blended frame is drawn, where the tic left them.
+#[derive(Clone, Debug, Default)]
+pub struct SectorHeights {
+    prev: Vec<(f32, f32)>,
+    current: Vec<(f32, f32)>,
+}
+
+impl SectorHeights {
+    /// Start-of-tic snapshot of `level`'s sectors.
+    pub fn store(&mut self, level: &Level) {
+        self.prev.clear();
+        (self.prev).extend(level.sectors.iter().map(|s| (s.floor_h, s.ceil_h)));
+    }
+
+    /// Move `level`'s sectors `alpha` of the way from the snapshot to
+    /// where they are, remembering where that was for [`Self::restore`].
+    /// Nothing moves without a snapshot of this map.
+    pub fn blend(&mut self, level: &mut Level, alpha: f32) {
+        self.current.clear();
+        if alpha >= 1.0 || self.prev.len() != level.sectors.len() {
+            return;
+        }
+        for (sector, &(floor, ceil)) in level.sectors.iter_mut().zip(&self.prev) {
+            self.current.push((sector.floor_h, sector.ceil_h));
+            sector.floor_h = floor + (sector.floor_h - floor) * alpha;
+            sector.ceil_h = ceil + (sector.ceil_h - ceil) * alpha;
+        }
+    }
+
+    /// Put back the heights [`Self::blend`] replaced.
+    pub fn restore(&mut self, level: &mut Level) {
+        for (sector, &(floor, ceil)) in level.sectors.iter_mut().zip(&self.current) {
+            (sector.floor_h, sector.ceil_h) = (floor, ceil);
+        }
+        self.current.clear();
+    }
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{InputCmd, TicRunner},
+        world::testmap,
+    };
+    use glam::Vec2;
+
+    #[test]
+    fn angle_takes_the_short_way_round() {
+        let a = lerp_angle(Angle(u32::MAX - 99), Angle(100), 0.5);
+        assert_eq!(a, Angle::ZERO);
+        assert_eq!(lerp_angle(Angle(200), Angle(600), 0.25), Angle(300));
+        assert_eq!(
+            lerp_angle(Angle::ANG45, Angle::ANG270 + Angle::ANG45, 0.5),
+            Angle::ZERO
+        );
+    }
+
+    #[test]
+    fn things_blend_between_tics() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+
+        // nothing to blend from before the first tic
+        sim.set_frame_alpha(0.0);
+        assert_eq!(sim.frame_alpha(), 1.0);
+
+        let walk = InputCmd {
+            forward: 1.0,
+            ..Default::default()
+        };
+        sim.run_tic(&mut level, walk);
+        sim.run_tic(&mut level, walk);
+
+        let prev = sim.world().get::<&PrevPosition>(player).unwrap().0;
+        let cur = sim.world().get::<&Position>(player).unwrap().0;
+        assert!(cur.x > prev.0.x);
+
+        sim.set_frame_alpha(0.5);
+        let (mid, _) = sim.interpolated(player).unwrap();
+        assert_eq!(mid.0, prev.0.lerp(cur, 0.5));
+        assert_eq!(mid.0.y, 64.0);
+
+        // a reset snaps until the next tic
+        sim.reset_interpolation();
+        assert_eq!(sim.interpolated(player).unwrap().0.0, cur);
+        sim.run_tic(&mut level, InputCmd::default());
+        assert_eq!(sim.frame_alpha(), 0.5);
+        assert!(sim.interpolated(player).unwrap().0.0.x > Vec2::new(64.0, 64.0).x);
+    }
+
+    #[test]
+    fn sector_heights_blend_and_restore() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut heights = SectorHeights::default();
+        // no snapshot of this map yet
+        heights.blend(&mut level, 0.5);
+        heights.restore(&mut level);
+        assert_eq!(level.sectors[0].floor_h, 0.0);
+
+        heights.store(&level);
+        level.sectors[0].floor_h = 8.0;
+        level.sectors[0].ceil_h += 16.0;
+        let ceil = level.sectors[0].ceil_h;
+        heights.blend(&mut level, 0.25);
+        assert_eq!(level.sectors[0].floor_h, 2.0);
+        assert_eq!(level.sectors[0].ceil_h, ceil - 12.0);
+
+        heights.restore(&mut level);
+        assert_eq!(
+            (level.sectors[0].floor_h, level.sectors[0].ceil_h),
+            (8.0, ceil)
+        );
+    }
+}
@@ -0,0 +1,374 @@
+//! Sector lighting effects – vanilla `p_lights.c`.
+//!
+//! Light thinkers are spawned once at level start from `sector.special`
+//! and write `Sector::light` directly every tic; the renderer picks the
+//! new level up on the next frame like any other sector change.
+//!
+//! The thinkers count in vanilla 0‥255 light units so timings and step
+//! sizes match the original; `Sector::light` stores the same value
+//! quantised to the renderer's 32 levels.
+
+use thiserror::Error;
+
+use super::Rng;
+use crate::world::{Level, Sector, SectorId};
+
+/// Tics a strobe stays bright (vanilla `STROBEBRIGHT`).
+pub const STROBE_BRIGHT: i32 = 5;
+/// Dark period of a fast strobe (vanilla `FASTDARK`).
+pub const FAST_DARK: i32 = 15;
+/// Dark period of a slow strobe (vanilla `SLOWDARK`).
+pub const SLOW_DARK: i32 = 35;
+/// Light units a glowing sector moves per tic (vanilla `GLOWSPEED`).
+const GLOW_SPEED: i32 = 8;
+
+/// Per-effect state that doesn't fit the shared `count / min / max`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightKind {
+    /// Random blink (special 1).
+    Flash { max_time: i32, min_time: i32 },
+    /// Regular strobe (specials 2, 3, 4, 12, 13).
+    Strobe { dark_time: i32, bright_time: i32 },
+    /// Smooth oscillation (special 8); `direction` is ±1.
+    Glow { direction: i32 },
+    /// Fire flicker (special 17).
+    Flicker,
+}
+
+/// One running light effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Light {
+    pub sector: SectorId,
+    pub kind: LightKind,
+    pub count: i32,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// Sector light as a vanilla 0‥255 level.
+#[inline]
+fn level_of(sector: &Sector) -> i32 {
+    (sector.light * 31.0).round() as i32 * 8
+}
+
+#[inline]
+fn set_level(sector: &mut Sector, level: i32) {
+    sector.light = f32::from((level.clamp(0, 255) >> 3) as u8) / 31.0;
+}
+
+/// Scan `level.sectors` and start a thinker for every lighting special
+/// (the light part of vanilla `P_SpawnSpecials`).
+pub fn spawn_lights(level: &mut Level, rng: &mut Rng) -> Vec<Light> {
+    let mut lights = Vec::new();
+
+    for id in 0..level.sectors.len() as SectorId {
+        let max = level_of(&level.sectors[id as usize]);
+        let neighbour = (level.min_neighbor_light(id) * 31.0).round() as i32 * 8;
+
+        let light = match level.sectors[id as usize].special {
+            1 => flash(id, max, neighbour, rng),
+            2 => strobe(id, max, neighbour, FAST_DARK, false, rng),
+            3 => strobe(id, max, neighbour, SLOW_DARK, false, rng),
+            4 => strobe(id, max, neighbour, FAST_DARK, false, rng),
+            8 => Light {
+                sector: id,
+                kind: LightKind::Glow { direction: -1 },
+                count: 0,
+                min: neighbour,
+                max,
+            },
+            12 => strobe(id, max, neighbour, SLOW_DARK, true, rng),
+            13 => strobe(id, max, neighbour, FAST_DARK, true, rng),
+            17 => Light {
+                sector: id,
+                kind: LightKind::Flicker,
+                count: 4,
+                min: neighbour + 16,
+                max,
+            },
+            _ => continue,
+        };
+
+        // the light is now handled by its thinker; 4 keeps its damage
+        let sector = &mut level.sectors[id as usize];
+        sector.special = if sector.special == 4 { 4 } else { 0 };
+        lights.push(light);
+    }
+    lights
+}
+
+/// Vanilla `P_SpawnLightFlash`.
+fn flash(sector: SectorId, max: i32, min: i32, rng: &mut Rng) -> Light {
+    let (max_time, min_time) = (64, 7);
+    Light {
+        sector,
+        kind: LightKind::Flash { max_time, min_time },
+        count: (rng.p_random() & max_time) + 1,
+        min,
+        max,
+    }
+}
+
+/// Vanilla `P_SpawnStrobeFlash`.
+fn strobe(
+    sector: SectorId,
+    max: i32,
+    min: i32,
+    dark_time: i32,
+    in_sync: bool,
+    rng: &mut Rng,
+) -> Light {
+    Light {
+        sector,
+        kind: LightKind::Strobe {
+            dark_time,
+            bright_time: STROBE_BRIGHT,
+        },
+        count: if in_sync { 1 } else { (rng.p_random() & 7) + 1 },
+        min: if min == max { 0 } else { min },
+        max,
+    }
+}
+
+/// [`set_sector_light`] was asked for a sector the level doesn't have.
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("no sector {sector}: the level has {count}")]
+pub struct NoSuchSector {
+    pub sector: SectorId,
+    pub count: usize,
+}
+
+/// Pin sector `sector` at `light` (0‥1, quantised like every other
+/// level), stopping any effect running there so it sticks.  The single
+/// entry point for scripted or special-driven light changes.
+pub fn set_sector_light(
+    level: &mut Level,
+    lights: &mut Vec<Light>,
+    sector: SectorId,
+    light: f32,
+) -> Result<(), NoSuchSector> {
+    let count = level.sectors.len();
+    let target = level
+        .sectors
+        .get_mut(sector as usize)
+        .ok_or(NoSuchSector { sector, count })?;
+    lights.retain(|l| l.sector != sector);
+    set_level(target, (light.clamp(0.0, 1.0) * 255.0).round() as i32);
+    Ok(())
+}
+
+/// Advance every light by one tic.
+pub fn run_lights(level: &mut Level, lights: &mut [Light], rng: &mut Rng) {
+    for light in lights {
+        let sector = &mut level.sectors[light.sector as usize];
+        let cur = level_of(sector);
+
+        match &mut light.kind {
+            /* T_LightFlash */
+            LightKind::Flash { max_time, min_time } => {
+                light.count -= 1;
+                if light.count > 0 {
+                    continue;
+                }
+                if cur == light.max {
+                    set_level(sector, light.min);
+                    light.count = (rng.p_random() & *min_time) + 1;
+                } else {
+                    set_level(sector, light.max);
+                    light.count = (rng.p_random() & *max_time) + 1;
+                }
+            }
+
+            /* T_StrobeFlash */
+            LightKind::Strobe {
+                dark_time,
+                bright_time,
+            } => {
+                light.count -= 1;
+                if light.count > 0 {
+                    continue;
+                }
+                if cur == light.min {
+                    set_level(sector, light.max);
+                    light.count = *bright_time;
+                } else {
+                    set_level(sector, light.min);
+                    light.count = *dark_time;
+                }
+            }
+
+            /* T_Glow */
+            LightKind::Glow { direction } => {
+                let mut next = cur + *direction * GLOW_SPEED;
+                if *direction < 0 && next <= light.min {
+                    next += GLOW_SPEED;
+                    *direction = 1;
+                } else if *direction > 0 && next >= light.max {
+                    next -= GLOW_SPEED;
+                    *direction = -1;
+                }
+                set_level(sector, next);
+            }
+
+            /* T_FireFlicker */
+            LightKind::Flicker => {
+                light.count -= 1;
+                if light.count > 0 {
+                    continue;
+                }
+                let amount = (rng.p_random() & 3) * 16;
+                if light.max - amount < light.min {
+                    set_level(sector, light.min);
+                } else {
+                    set_level(sector, light.max - amount);
+                }
+                light.count = 4;
+            }
+        }
+    }
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::testmap;
+
+    /// Three-room map with room A at light 160 next to a darker strip.
+    fn lit_map(special: i16) -> Level {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        for (sector, raw) in level.sectors.iter_mut().zip([160u8, 96, 200]) {
+            sector.light = f32::from(raw >> 3) / 31.0;
+        }
+        level.sectors[0].special = special;
+        level
+    }
+
+    fn levels_over(
+        level: &mut Level,
+        lights: &mut [Light],
+        rng: &mut Rng,
+        tics: usize,
+    ) -> Vec<i32> {
+        (0..tics)
+            .map(|_| {
+                run_lights(level, lights, rng);
+                level_of(&level.sectors[0])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn min_neighbor_light_skips_unconnected() {
+        let level = lit_map(0);
+        // A only touches M; B (brighter) isn't adjacent, M's darker.
+        assert_eq!(level.min_neighbor_light(0), level.sectors[1].light);
+        // M touches both and is itself the darkest
+        assert_eq!(level.min_neighbor_light(1), level.sectors[1].light);
+    }
+
+    #[test]
+    fn plain_sectors_get_no_thinker() {
+        let mut level = lit_map(0);
+        assert!(spawn_lights(&mut level, &mut Rng::default()).is_empty());
+    }
+
+    #[test]
+    fn synced_strobe_timings() {
+        let mut level = lit_map(12);
+        let mut rng = Rng::default();
+        let mut lights = spawn_lights(&mut level, &mut rng);
+        assert_eq!(lights.len(), 1);
+        assert_eq!(level.sectors[0].special, 0);
+
+        let seen = levels_over(
+            &mut level,
+            &mut lights,
+            &mut rng,
+            1 + SLOW_DARK as usize + 1,
+        );
+        // dark on the first tic, for SLOW_DARK tics, then bright again
+        assert!(seen[..SLOW_DARK as usize].iter().all(|&l| l == 96));
+        assert_eq!(seen[SLOW_DARK as usize], 160);
+    }
+
+    #[test]
+    fn blinking_strobes_fast_and_slow() {
+        for (special, dark) in [(2, FAST_DARK), (3, SLOW_DARK), (4, FAST_DARK)] {
+            let mut level = lit_map(special);
+            let mut rng = Rng::default();
+            let mut lights = spawn_lights(&mut level, &mut rng);
+            let seen = levels_over(&mut level, &mut lights, &mut rng, 200);
+            let onsets: Vec<usize> = (1..seen.len())
+                .filter(|&t| seen[t] == 160 && seen[t - 1] == 96)
+                .collect();
+            assert!(onsets.len() >= 3, "{special}");
+            assert!(
+                onsets
+                    .windows(2)
+                    .all(|w| w[1] - w[0] == (dark + STROBE_BRIGHT) as usize),
+                "{special}: bright at {onsets:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn glow_bounces_between_limits() {
+        let mut level = lit_map(8);
+        let mut rng = Rng::default();
+        let mut lights = spawn_lights(&mut level, &mut rng);
+        let seen = levels_over(&mut level, &mut lights, &mut rng, 40);
+
+        assert!(seen.iter().all(|&l| (96..=160).contains(&l)));
+        assert_eq!(*seen.iter().min().unwrap(), 96 + GLOW_SPEED);
+        assert_eq!(*seen.iter().max().unwrap(), 160 - GLOW_SPEED);
+        assert!(seen.windows(2).all(|w| (w[0] - w[1]).abs() <= GLOW_SPEED));
+    }
+
+    #[test]
+    fn flash_and_flicker_stay_in_range() {
+        for (special, lo) in [(1, 96), (17, 96 + 16)] {
+            let mut level = lit_map(special);
+            let mut rng = Rng::default();
+            let mut lights = spawn_lights(&mut level, &mut rng);
+            let seen = levels_over(&mut level, &mut lights, &mut rng, 500);
+            assert!(seen.iter().all(|&l| (lo..=160).contains(&l)), "{special}");
+            assert!(seen.contains(&lo) && seen.contains(&160), "{special}");
+        }
+    }
+
+    #[test]
+    fn damaging_strobe_keeps_its_special() {
+        let mut level = lit_map(4);
+        spawn_lights(&mut level, &mut Rng::default());
+        assert_eq!(level.sectors[0].special, 4);
+    }
+
+    #[test]
+    fn override_stops_the_effect() {
+        let mut level = lit_map(8);
+        let mut rng = Rng::default();
+        let mut lights = spawn_lights(&mut level, &mut rng);
+        set_sector_light(&mut level, &mut lights, 0, 1.0).unwrap();
+        assert!(lights.is_empty());
+        levels_over(&mut level, &mut lights, &mut rng, 10);
+        assert_eq!(level.sectors[0].light, 1.0);
+
+        set_sector_light(&mut level, &mut lights, 1, 0.25).unwrap();
+        assert_eq!(level_of(&level.sectors[1]), 64);
+
+        // past the last sector: an error, and nothing changes
+        let count = level.sectors.len();
+        let sector = count as SectorId;
+        assert_eq!(
+            set_sector_light(&mut level, &mut lights, sector, 0.5),
+            Err(NoSuchSector { sector, count })
+        );
+        assert_eq!(level_of(&level.sectors[1]), 64);
+    }
+}
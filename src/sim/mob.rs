@@ -1,21 +1,34 @@
+use super::events::SimEvent;
+use super::spacial::{fetch_thing, p_set_thing_position, p_unset_thing_position};
 use super::{
-    ActorFlags, Angle, Animation, Class, Position, Subsector, ThingGrid, ThingSpatial, Velocity,
+    ActorFlags, Angle, Animation, Class, GameRules, Health, PlayerStatus, Position, PrevPosition,
+    Rng, Subsector, Target, ThingGrid, Velocity, actions, hitscan,
 };
-use crate::defs::{MobjInfo, flags::MobjFlags};
-use crate::world::Level;
+use crate::defs::{Action, Definitions, MobjInfo, Sound, State, flags::MobjFlags};
+use crate::world::{Level, SubsectorId, angle_to};
 use glam::{Vec2, Vec3};
-use hecs::World;
+use hecs::{Entity, World};
 
+/// Vanilla `P_SpawnMobj`: `info` at `x, y`, on the floor (or under the
+/// ceiling) of `subsector`, which must be the one `x, y` is in.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_mobj(
     world: &mut World,
     thing_grid: &mut ThingGrid,
     level: &Level,
-    info: &'static MobjInfo,
+    defs: &Definitions,
+    info: &MobjInfo,
     x: f32,
     y: f32,
-    angle: f32,
-    subsector: u16,
+    angle: Angle,
+    subsector: SubsectorId,
 ) -> hecs::Entity {
+    debug_assert_eq!(
+        level.locate_subsector(Vec2::new(x, y)),
+        subsector,
+        "{} spawned at ({x}, {y}) with the wrong subsector",
+        info.id
+    );
     let sec_idx = level.subsectors[subsector as usize].sector;
     let sector = &level.sectors[sec_idx as usize];
 
@@ -26,30 +39,510 @@ pub fn spawn_mobj(
     };
 
     let pos = Position(Vec2::new(x, y), z);
-    let class = Class(info);
+    let class = Class(*info);
     let flags = ActorFlags(info.flags);
 
     let ent = world.spawn((
         flags,
         pos,
+        PrevPosition(pos, angle),
         Velocity(Vec3::ZERO),
-        Angle(angle),
+        angle,
+        Health(info.spawnhealth),
         Subsector(subsector),
         Animation {
             state: info.spawnstate,
-            tics: info.spawnstate.tics(),
+            tics: defs.state(info.spawnstate).tics,
         },
         class,
     ));
 
-    if !flags.0.contains(MobjFlags::NOBLOCKMAP) {
-        thing_grid.insert(ThingSpatial {
-            ent,
-            pos,
-            class,
-            flags,
+    p_set_thing_position(thing_grid, ent, &pos, &class, &flags, subsector);
+    ent
+}
+
+/// Spawn `info` at an arbitrary map spot, locating its subsector first.
+pub fn spawn_mobj_at(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    defs: &Definitions,
+    info: &MobjInfo,
+    at: Vec2,
+    angle: Angle,
+) -> Entity {
+    let ss = level.locate_subsector(at);
+    spawn_mobj(world, thing_grid, level, defs, info, at.x, at.y, angle, ss)
+}
+
+/// Teleport fog (`MT_TFOG`) at `at`.
+pub fn spawn_tele_fog(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    defs: &Definitions,
+    at: Vec2,
+) -> Entity {
+    let info = defs.by_id("TFOG").expect("TFOG missing from MOBJINFO");
+    spawn_mobj_at(world, thing_grid, level, defs, info, at, Angle::ZERO)
+}
+
+/// Vanilla `P_RemoveMobj`: unlink `ent` from the grid and despawn it.
+pub fn remove_mobj(world: &mut World, thing_grid: &mut ThingGrid, ent: Entity) {
+    p_unset_thing_position(thing_grid, ent);
+    world.despawn(ent).ok();
+}
+
+/// Vanilla `P_KillMobj`: the thing stops being shootable, shrinks to a
+/// corpse and starts its death (or gib, if `gib`) sequence.  It keeps
+/// blocking until the sequence reaches `A_Fall`, and stays in the grid
+/// for an Arch-vile to find.  Its weapon falls out at `A_Fall` too
+/// ([`drop_item`]) rather than here, where the map isn't to hand.  Every
+/// monster death counts as the player's kill, whoever did it.
+pub fn kill_mobj(world: &mut World, rng: &mut Rng, defs: &Definitions, ent: Entity, gib: bool) {
+    let Ok((flags, class, anim)) =
+        world.query_one_mut::<(&mut ActorFlags, &Class, &mut Animation)>(ent)
+    else {
+        return;
+    };
+
+    flags
+        .0
+        .remove(MobjFlags::SHOOTABLE | MobjFlags::FLOAT | MobjFlags::SKULLFLY);
+    if class.0.id != "SKULL" {
+        flags.0.remove(MobjFlags::NOGRAVITY);
+    }
+    flags.0.insert(MobjFlags::CORPSE | MobjFlags::DROPOFF);
+
+    let info = &class.0;
+    let count_kill = info.flags.contains(MobjFlags::COUNTKILL);
+    anim.state = if gib && info.xdeathstate != State::NULL {
+        info.xdeathstate
+    } else {
+        info.deathstate
+    };
+    anim.tics = (defs.state(anim.state).tics - (rng.p_random() & 3)).max(1);
+
+    if count_kill {
+        if let Some((_, status)) = world.query_mut::<&mut PlayerStatus>().into_iter().next() {
+            status.kill_count += 1;
+        }
+    }
+}
+
+/// How fast a dropped item is tossed up out of the body.
+const TOSS_SPEED: f32 = 4.0;
+
+/// What a former human drops when it dies: vanilla `P_KillMobj`'s list.
+fn dropped_item(class: &MobjInfo) -> Option<&'static str> {
+    match class.id {
+        "WOLFSS" | "POSSESSED" => Some("CLIP"),
+        "SHOTGUY" => Some("SHOTGUN"),
+        "CHAINGUY" => Some("CHAINGUN"),
+        _ => None,
+    }
+}
+
+/// Drop what `ent`'s class carries, if anything: a `DROPPED` copy of the
+/// item (half the ammo when picked up), a few units off the body and
+/// tossed up, to land on the floor under gravity.
+pub fn drop_item(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    defs: &Definitions,
+    ent: Entity,
+) -> Option<Entity> {
+    let (pos, class) = {
+        let mut q = world.query_one::<(&Position, &Class)>(ent).ok()?;
+        let (pos, class) = q.get()?;
+        (pos.0, class.0)
+    };
+    let info = defs.by_id(dropped_item(&class)?)?;
+    let nudge = Vec2::new(rng.p_subrandom() as f32, rng.p_subrandom() as f32) / 64.0;
+    let item = spawn_mobj_at(
+        world,
+        thing_grid,
+        level,
+        defs,
+        info,
+        pos + nudge,
+        Angle::ZERO,
+    );
+    if let Ok((flags, vel)) = world.query_one_mut::<(&mut ActorFlags, &mut Velocity)>(item) {
+        flags.0.insert(MobjFlags::DROPPED);
+        vel.0.z = TOSS_SPEED;
+    }
+    Some(item)
+}
+
+/// How tall `ent` stands: a quarter of its class's height once it is a
+/// corpse (vanilla `P_KillMobj` does `height >>= 2`; a raise undoes it).
+pub fn mobj_height(class: &Class, flags: &ActorFlags) -> f32 {
+    let height = class.0.height as f32;
+    if flags.0.contains(MobjFlags::CORPSE) {
+        height / 4.0
+    } else {
+        height
+    }
+}
+
+/// The Arch-vile's half of vanilla `A_VileChase`: bring the corpse `ent`
+/// back as it spawned, minus its grudge.
+pub fn raise_corpse(world: &mut World, defs: &Definitions, ent: Entity) {
+    let Ok((class, flags, health, anim)) =
+        world.query_one_mut::<(&Class, &mut ActorFlags, &mut Health, &mut Animation)>(ent)
+    else {
+        return;
+    };
+    let info = class.0;
+    anim.state = info.raisestate;
+    anim.tics = defs.state(info.raisestate).tics;
+    flags.0 = info.flags;
+    health.0 = info.spawnhealth;
+    world.remove_one::<Target>(ent).ok();
+}
+
+/// Vanilla `P_AproxDistance`: octagonal distance, cheaper than a root.
+pub fn aprox_distance(d: Vec2) -> f32 {
+    let (dx, dy) = (d.x.abs(), d.y.abs());
+    dx + dy - dx.min(dy) / 2.0
+}
+
+/// Vanilla `P_SpawnMissile`: launch an `info` missile from `source` at
+/// `dest`'s feet, at the speed `rules` give the class.  The missile remembers `source`
+/// as its `Target` so it flies straight out of its shooter.
+#[allow(clippy::too_many_arguments)]
+pub fn p_spawn_missile(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    source: Entity,
+    dest: Entity,
+    info: &MobjInfo,
+) -> Option<Entity> {
+    let (from, _, _) = fetch_thing(world, source)?;
+    let (to, _, to_flags) = fetch_thing(world, dest)?;
+
+    let th = spawn_mobj_at(world, thing_grid, level, defs, info, from.0, Angle::ZERO);
+    if !matches!(info.seesound, Sound::None) {
+        events.push(SimEvent::Sound {
+            sound: info.seesound,
+            origin: from.0,
+            full_volume: false,
         });
     }
 
-    ent
+    let mut an = angle_to(from.0, to.0);
+    // fuzzy player
+    if to_flags.0.contains(MobjFlags::SHADOW) {
+        an += Angle((rng.p_subrandom() << 20) as u32);
+    }
+    let speed = rules.missile_speed(info);
+    let z = from.1 + 32.0;
+    let tics = (aprox_distance(to.0 - from.0) / speed).floor().max(1.0);
+
+    if let Ok((pos, vel, angle, anim)) =
+        world.query_one_mut::<(&mut Position, &mut Velocity, &mut Angle, &mut Animation)>(th)
+    {
+        pos.1 = z;
+        *angle = an;
+        let dir = an.unit() * speed;
+        vel.0 = Vec3::new(dir.x, dir.y, (to.1 - from.1) / tics);
+        // vanilla P_CheckMissileSpawn: a little randomness; its first
+        // move explodes it if it starts out inside a wall
+        anim.tics = (anim.tics - (rng.p_random() & 3)).max(1);
+    }
+    world.insert_one(th, Target(source)).ok();
+    Some(th)
+}
+
+/// Spawn an `info` effect at `at`, `z` jittered by up to ±4 units, with
+/// `vz` and a random shortening of its first state.
+#[allow(clippy::too_many_arguments)]
+fn spawn_effect(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    defs: &Definitions,
+    info: &MobjInfo,
+    at: Vec2,
+    z: f32,
+    vz: f32,
+) -> Entity {
+    let z = z + (rng.p_random() - rng.p_random()) as f32 / 64.0;
+    let th = spawn_mobj_at(world, thing_grid, level, defs, info, at, Angle::ZERO);
+    if let Ok((pos, vel, anim)) =
+        world.query_one_mut::<(&mut Position, &mut Velocity, &mut Animation)>(th)
+    {
+        pos.1 = z;
+        vel.0.z = vz;
+        anim.tics = (anim.tics - (rng.p_random() & 3)).max(1);
+    }
+    th
+}
+
+/// Vanilla `P_SpawnPuff`: bullet smoke drifting up from where a shot
+/// hit.  A `melee` puff skips the spark frames (punches don't spark on
+/// the wall).
+#[allow(clippy::too_many_arguments)]
+pub fn p_spawn_puff(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    defs: &Definitions,
+    at: Vec2,
+    z: f32,
+    melee: bool,
+) -> Entity {
+    let info = defs.by_id("PUFF").expect("PUFF missing from MOBJINFO");
+    let th = spawn_effect(world, thing_grid, level, rng, defs, info, at, z, 1.0);
+    if melee {
+        if let Ok(mut anim) = world.get::<&mut Animation>(th) {
+            anim.state = State::PUFF3;
+            anim.tics = defs.state(State::PUFF3).tics;
+        }
+    }
+    th
+}
+
+/// Vanilla `P_SpawnBlood`: a splat thrown up from a thing hit for
+/// `damage`; lighter hits start further into the splat.
+#[allow(clippy::too_many_arguments)]
+pub fn p_spawn_blood(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    defs: &Definitions,
+    at: Vec2,
+    z: f32,
+    damage: i32,
+) -> Entity {
+    let info = defs.by_id("BLOOD").expect("BLOOD missing from MOBJINFO");
+    let th = spawn_effect(world, thing_grid, level, rng, defs, info, at, z, 2.0);
+    let state = match damage {
+        9..=12 => State::BLOOD2,
+        ..9 => State::BLOOD3,
+        _ => return th,
+    };
+    if let Ok(mut anim) = world.get::<&mut Animation>(th) {
+        anim.state = state;
+        anim.tics = defs.state(state).tics;
+    }
+    th
+}
+
+/// Vanilla `P_ZMovement` for the effects outside the blockmap: puffs
+/// float up, blood arcs under gravity and settles on the floor.  Dropped
+/// items fall the same way.  Nothing else in the blockmap is touched, so
+/// this stays cheap with lots of them about.
+pub fn p_effect_z_movement(world: &mut World, level: &Level, gravity: f32) {
+    for (_, (pos, vel, flags, class, ss)) in world.query_mut::<(
+        &mut Position,
+        &mut Velocity,
+        &ActorFlags,
+        &Class,
+        &Subsector,
+    )>() {
+        let floats = flags.0.contains(MobjFlags::NOGRAVITY);
+        if !flags
+            .0
+            .intersects(MobjFlags::NOBLOCKMAP | MobjFlags::DROPPED)
+            || flags.0.contains(MobjFlags::MISSILE)
+            || (vel.0.z == 0.0 && floats)
+        {
+            continue;
+        }
+        let sector = &level.sectors[level.sector_of_subsector(ss.0) as usize];
+        let height = class.0.height as f32;
+        pos.1 += vel.0.z;
+        if pos.1 <= sector.floor_h {
+            // hit the floor
+            pos.1 = sector.floor_h;
+            vel.0.z = vel.0.z.max(0.0);
+        } else if !floats {
+            vel.0.z -= if vel.0.z == 0.0 {
+                gravity * 2.0
+            } else {
+                gravity
+            };
+        }
+        if pos.1 + height > sector.ceil_h {
+            // hit the ceiling
+            pos.1 = sector.ceil_h - height;
+            vel.0.z = vel.0.z.min(0.0);
+        }
+    }
+}
+
+/// The lost soul part of vanilla `P_ZMovement`: a charging skull climbs
+/// or dives along its z velocity and bounces back off the floor and the
+/// ceiling.
+pub fn p_skull_z_movement(world: &mut World, level: &Level) {
+    for (_, (pos, vel, flags, class, ss)) in world.query_mut::<(
+        &mut Position,
+        &mut Velocity,
+        &ActorFlags,
+        &Class,
+        &Subsector,
+    )>() {
+        if !flags.0.contains(MobjFlags::SKULLFLY) || vel.0.z == 0.0 {
+            continue;
+        }
+        let sector = &level.sectors[level.sector_of_subsector(ss.0) as usize];
+        let height = class.0.height as f32;
+        pos.1 += vel.0.z;
+        if pos.1 <= sector.floor_h {
+            pos.1 = sector.floor_h;
+            vel.0.z = -vel.0.z;
+        }
+        if pos.1 + height > sector.ceil_h {
+            pos.1 = sector.ceil_h - height;
+            vel.0.z = -vel.0.z;
+        }
+    }
+}
+
+/// Vanilla `P_SpawnPlayerMissile`: launch an `info` missile the way the
+/// player faces, autoaimed up or down at whatever is in line (trying a
+/// little either side before giving up and firing level).  Without
+/// `autoaim` it flies straight ahead, level.
+#[allow(clippy::too_many_arguments)]
+pub fn p_spawn_player_missile(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    source: Entity,
+    info: &MobjInfo,
+    autoaim: bool,
+) -> Option<Entity> {
+    let (from, _, _) = fetch_thing(world, source)?;
+    let facing = *world.get::<&Angle>(source).ok()?;
+
+    // see which target is to be aimed at
+    let aim = |an: Angle| {
+        let found = autoaim
+            .then(|| hitscan::p_aim_line_attack(world, level, thing_grid, source, an, 16.0 * 64.0));
+        found.flatten().map(|(_, slope)| (an, slope))
+    };
+    let (an, slope) = aim(facing)
+        .or_else(|| aim(facing + hitscan::AUTOAIM_NUDGE))
+        .or_else(|| aim(facing - hitscan::AUTOAIM_NUDGE))
+        .unwrap_or((facing, 0.0));
+
+    let th = spawn_mobj_at(world, thing_grid, level, defs, info, from.0, Angle::ZERO);
+    if !matches!(info.seesound, Sound::None) {
+        events.push(SimEvent::Sound {
+            sound: info.seesound,
+            origin: from.0,
+            full_volume: false,
+        });
+    }
+    let speed = info.speed as f32;
+    if let Ok((pos, vel, angle, anim)) =
+        world.query_one_mut::<(&mut Position, &mut Velocity, &mut Angle, &mut Animation)>(th)
+    {
+        pos.1 = from.1 + 32.0;
+        *angle = an;
+        let dir = an.unit() * speed;
+        vel.0 = Vec3::new(dir.x, dir.y, speed * slope);
+        anim.tics = (anim.tics - (rng.p_random() & 3)).max(1);
+    }
+    world.insert_one(th, Target(source)).ok();
+    Some(th)
+}
+
+/// Vanilla `P_ExplodeMissile`: stop, drop `MF_MISSILE` and go to the
+/// death state with its sound and action.
+#[allow(clippy::too_many_arguments)]
+pub fn p_explode_missile(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    ent: Entity,
+) {
+    let Ok((pos, vel, flags, class, anim)) = world.query_one_mut::<(
+        &Position,
+        &mut Velocity,
+        &mut ActorFlags,
+        &Class,
+        &mut Animation,
+    )>(ent) else {
+        return;
+    };
+    vel.0 = Vec3::ZERO;
+    flags.0.remove(MobjFlags::MISSILE);
+    let info = class.0;
+    let origin = pos.0;
+    anim.state = info.deathstate;
+    if anim.state == State::NULL {
+        remove_mobj(world, thing_grid, ent);
+        return;
+    }
+    anim.tics = (defs.state(anim.state).tics - (rng.p_random() & 3)).max(1);
+
+    if !matches!(info.deathsound, Sound::None) {
+        events.push(SimEvent::Sound {
+            sound: info.deathsound,
+            origin,
+            full_volume: false,
+        });
+    }
+    let action = defs.state(info.deathstate).action;
+    if !matches!(action, Action::None) {
+        actions::call_action(
+            world, thing_grid, level, rng, rules, defs, events, ent, action,
+        );
+    }
+}
+
+/// The missile part of vanilla `P_ZMovement`: climb or dive along the
+/// z velocity and explode on reaching the floor or ceiling.
+pub fn p_missile_z_movement(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+) {
+    let mut hit = Vec::new();
+    for (ent, (pos, vel, flags, class, ss)) in
+        world.query_mut::<(&mut Position, &Velocity, &ActorFlags, &Class, &Subsector)>()
+    {
+        if !flags.0.contains(MobjFlags::MISSILE) || flags.0.contains(MobjFlags::NOCLIP) {
+            continue;
+        }
+        let sector = &level.sectors[level.sector_of_subsector(ss.0) as usize];
+        let height = class.0.height as f32;
+        pos.1 += vel.0.z;
+        if pos.1 <= sector.floor_h {
+            pos.1 = sector.floor_h;
+            hit.push(ent);
+        } else if pos.1 + height > sector.ceil_h {
+            // vanilla explodes even against a sky ceiling; only a sky
+            // hack wall swallows missiles (`p_xy_movement`)
+            pos.1 = sector.ceil_h - height;
+            hit.push(ent);
+        }
+    }
+    for ent in hit {
+        p_explode_missile(world, thing_grid, level, rng, rules, defs, events, ent);
+    }
 }
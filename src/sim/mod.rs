@@ -1,15 +1,50 @@
+mod actions;
+pub mod ceilings;
+pub mod cheats;
 mod components;
+pub mod damage;
+pub mod demo;
+pub mod doors;
+mod enemy;
+mod events;
+pub mod fixed;
+pub mod floors;
+pub mod hitscan;
+pub mod interp;
+pub mod lights;
 mod mob;
-// mod physics;
+mod movers;
+mod noise;
+mod physics;
+mod pickups;
+pub mod plats;
+mod random;
+mod respawn;
+mod rules;
+pub mod saveload;
+mod session;
 mod spacial;
+mod specials;
+pub mod switches;
 mod systems;
+mod teleport;
 mod tic;
+pub mod trace;
+pub mod weapons;
 mod xy_movement;
 
 pub use components::{
-    ActorFlags, Angle, Animation, Class, InputCmd, Position, Subsector, Velocity,
+    ActorFlags, AmmoType, Angle, Animation, Attacker, CheatFlags, Cheats, Class, Health, InputCmd,
+    Keys, MoveCount, MoveDir, NUM_AMMO, NUM_POWERS, PlayerStatus, Position, Power, PrevPosition,
+    ReactionTime, SpawnPoint, Subsector, Target, Velocity,
 };
+pub use events::{LevelExit, SimEvent};
+pub use physics::{DEFAULT_PHYSICS, PhysicsConfig, PhysicsError};
+pub use random::Rng;
+pub use rules::{GameRules, RESPAWN_MIN_TICS};
+pub use session::{GameSession, LevelProgress};
 pub use spacial::{ThingGrid, ThingSpatial};
-pub use systems::player_input;
-pub use tic::{SIM_FPS, TicRunner};
+pub use systems::{VIEW_HEIGHT, player_input};
+pub use tic::{InputSource, LevelTotals, MAX_PLAYERS, SIM_FPS, Skill, TicRunner};
+pub use weapons::{WeaponType, Weapons};
 pub use xy_movement::xy_movement_system;
@@ -0,0 +1,306 @@
+//! Moving sector planes – vanilla `T_MovePlane` (p_floor.c) and the
+//! `P_ChangeSector` fit / crush check (p_map.c) every mover runs after
+//! changing a height.
+
+use glam::Vec2;
+use hecs::{Entity, World};
+use smallvec::SmallVec;
+
+use super::ceilings::Ceiling;
+use super::damage::MAX_RADIUS;
+use super::doors::Door;
+use super::floors::Floor;
+use super::plats::Platform;
+use super::spacial::fetch_thing;
+use super::xy_movement::{Mover, p_check_position};
+use super::{
+    ActorFlags, Animation, GameRules, Health, PhysicsConfig, Position, Rng, ThingGrid, Velocity,
+    damage, mob,
+};
+use crate::defs::{Definitions, MobjFlags, State};
+use crate::world::{Aabb, Angle, Level, SectorId};
+
+/// Hit points a crusher takes every fourth tic.
+const CRUSH_DAMAGE: i32 = 10;
+
+/// Whether any mover – door, ceiling, lift or floor, running or in
+/// stasis – already owns `sector` (vanilla `sector->specialdata`, which
+/// allows one thinker per sector).
+pub fn sector_busy(
+    doors: &[Door],
+    ceilings: &[Ceiling],
+    plats: &[Platform],
+    floors: &[Floor],
+    sector: SectorId,
+) -> bool {
+    doors.iter().any(|d| d.sector == sector)
+        || ceilings.iter().any(|c| c.sector == sector)
+        || plats.iter().any(|p| p.sector == sector)
+        || floors.iter().any(|f| f.sector == sector)
+}
+
+/// Outcome of one `T_MovePlane` step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveResult {
+    Ok,
+    /// Something doesn't fit; the plane was put back unless crushing.
+    Crushed,
+    /// The plane reached `dest` (and stopped there).
+    PastDest,
+}
+
+/// Vanilla `T_MovePlane` for a ceiling: move `sector`'s ceiling `speed`
+/// units towards `dest` in `direction` (±1).  A ceiling coming down onto
+/// something stops unless `crush`, in which case it keeps going and
+/// hurts it.
+#[allow(clippy::too_many_arguments)]
+pub fn t_move_ceiling(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    gametic: u32,
+    sector: SectorId,
+    speed: f32,
+    dest: f32,
+    crush: bool,
+    direction: i32,
+) -> MoveResult {
+    let last = level.sectors[sector as usize].ceil_h;
+    let mut change = |level: &mut Level, h: f32| {
+        level.sectors[sector as usize].ceil_h = h;
+        p_change_sector(
+            world, thing_grid, level, cfg, rng, rules, defs, gametic, sector, crush,
+        )
+    };
+
+    if direction < 0 {
+        /* down */
+        if last - speed < dest {
+            if change(level, dest) {
+                change(level, last);
+            }
+            return MoveResult::PastDest;
+        }
+        if change(level, last - speed) {
+            if crush {
+                return MoveResult::Crushed;
+            }
+            change(level, last);
+            return MoveResult::Crushed;
+        }
+    } else {
+        /* up */
+        if last + speed > dest {
+            change(level, dest);
+            return MoveResult::PastDest;
+        }
+        change(level, last + speed);
+    }
+    MoveResult::Ok
+}
+
+/// Vanilla `T_MovePlane` for a floor: move `sector`'s floor `speed`
+/// units towards `dest` in `direction` (±1).  A floor rising into
+/// something that no longer fits goes back unless `crush`.
+#[allow(clippy::too_many_arguments)]
+pub fn t_move_floor(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    gametic: u32,
+    sector: SectorId,
+    speed: f32,
+    dest: f32,
+    crush: bool,
+    direction: i32,
+) -> MoveResult {
+    let last = level.sectors[sector as usize].floor_h;
+    let mut change = |level: &mut Level, h: f32| {
+        level.sectors[sector as usize].floor_h = h;
+        p_change_sector(
+            world, thing_grid, level, cfg, rng, rules, defs, gametic, sector, crush,
+        )
+    };
+
+    if direction < 0 {
+        /* down */
+        if last - speed < dest {
+            if change(level, dest) {
+                change(level, last);
+            }
+            return MoveResult::PastDest;
+        }
+        if change(level, last - speed) {
+            change(level, last);
+            return MoveResult::Crushed;
+        }
+    } else {
+        /* up */
+        if last + speed > dest {
+            if change(level, dest) {
+                change(level, last);
+            }
+            return MoveResult::PastDest;
+        }
+        if change(level, last + speed) {
+            if crush {
+                return MoveResult::Crushed;
+            }
+            change(level, last);
+            return MoveResult::Crushed;
+        }
+    }
+    MoveResult::Ok
+}
+
+/// Vanilla `P_ChangeSector`: `sector`'s floor or ceiling just moved, so
+/// re-fit every thing near it.  Corpses are squashed into gibs, dropped
+/// items vanish, and with `crush` the living take `CRUSH_DAMAGE` every
+/// fourth tic.  Returns `true` when something shootable no longer fits.
+#[allow(clippy::too_many_arguments)]
+pub fn p_change_sector(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    gametic: u32,
+    sector: SectorId,
+    crush: bool,
+) -> bool {
+    let bbox = level.sector_bbox(sector);
+    // padded by the largest radius like vanilla's blockbox
+    let bbox = Aabb {
+        min: bbox.min - Vec2::splat(MAX_RADIUS),
+        max: bbox.max + Vec2::splat(MAX_RADIUS),
+    };
+    // collect first: crushing spawns blood and removes items
+    let mut near: SmallVec<[Entity; 16]> = SmallVec::new();
+    thing_grid.for_each_in_bbox(bbox, |t| {
+        near.push(t.ent);
+        true
+    });
+
+    let mut no_fit = false;
+    for ent in near {
+        no_fit |= pit_change_sector(
+            world, thing_grid, level, cfg, rng, rules, defs, gametic, ent, crush,
+        );
+    }
+    no_fit
+}
+
+/// One thing of `P_ChangeSector`; `true` if it's shootable and stuck.
+#[allow(clippy::too_many_arguments)]
+fn pit_change_sector(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    gametic: u32,
+    ent: Entity,
+    crush: bool,
+) -> bool {
+    let Ok((health, mut anim)) = world
+        .query_one_mut::<(&Health, &Animation)>(ent)
+        .map(|(h, a)| (*h, *a))
+    else {
+        return false;
+    };
+    let Some((pos, class, flags)) = fetch_thing(world, ent) else {
+        return false;
+    };
+    let mut thing = Mover {
+        ent,
+        pos,
+        class,
+        flags,
+    };
+    let fits = p_thing_height_clip(level, cfg, world, thing_grid, &mut thing, &anim);
+    if let Ok(p) = world.query_one_mut::<&mut Position>(ent) {
+        *p = thing.pos;
+    }
+    if fits {
+        return false;
+    }
+
+    // crunch bodies to giblets
+    if health.0 <= 0 {
+        anim.state = State::GIBS;
+        anim.tics = defs.state(State::GIBS).tics;
+        if let Ok((a, f)) = world.query_one_mut::<(&mut Animation, &mut ActorFlags)>(ent) {
+            *a = anim;
+            f.0.remove(MobjFlags::SOLID);
+        }
+        return false;
+    }
+
+    // crunch dropped items
+    if flags.0.contains(MobjFlags::DROPPED) {
+        mob::remove_mobj(world, thing_grid, ent);
+        return false;
+    }
+
+    // assume it is bloody gibs or something
+    if !flags.0.contains(MobjFlags::SHOOTABLE) {
+        return false;
+    }
+
+    if crush && gametic & 3 == 0 {
+        let spray = thing.pos.0;
+        let z = thing.pos.1 + class.0.height as f32 * 0.5;
+        damage::p_damage_mobj(world, rng, rules, defs, ent, None, None, CRUSH_DAMAGE);
+
+        // spray blood in a random direction
+        let info = defs.by_id("BLOOD").expect("BLOOD missing from MOBJINFO");
+        let blood = mob::spawn_mobj_at(world, thing_grid, level, defs, info, spray, Angle::ZERO);
+        let vel = Vec2::new(rng.p_subrandom() as f32, rng.p_subrandom() as f32) / 16.0;
+        if let Ok((pos, v)) = world.query_one_mut::<(&mut Position, &mut Velocity)>(blood) {
+            pos.1 = z;
+            v.0.x = vel.x;
+            v.0.y = vel.y;
+        }
+    }
+    true
+}
+
+/// Vanilla `P_ThingHeightClip`: settle `thing` on its (possibly moved)
+/// floor, or squeeze a floater under the ceiling.  `false` if the
+/// opening is now shorter than the thing.
+fn p_thing_height_clip(
+    level: &Level,
+    cfg: &PhysicsConfig,
+    world: &World,
+    thing_grid: &ThingGrid,
+    thing: &mut Mover,
+    anim: &Animation,
+) -> bool {
+    let check = p_check_position(level, cfg, world, thing_grid, thing, thing.pos.0);
+    // gibs lie flat (vanilla zeroes their height)
+    let height = if anim.state == State::GIBS {
+        0.0
+    } else {
+        mob::mobj_height(&thing.class, &thing.flags)
+    };
+
+    let z = &mut thing.pos.1;
+    *z = if thing.flags.0.contains(MobjFlags::NOGRAVITY) {
+        z.min(check.ceiling_z - height).max(check.floor_z)
+    } else {
+        check.floor_z
+    };
+    check.ceiling_z - check.floor_z >= height
+}
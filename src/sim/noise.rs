@@ -0,0 +1,123 @@
+//! Sound propagation – vanilla `P_NoiseAlert` / `P_RecursiveSound`.
+//!
+//! A gunshot floods out from the shooter's sector through every open
+//! two-sided line; each sector reached remembers who made the noise, and
+//! monsters asleep in it wake up on their next `A_Look`.  A closed door
+//! stops the flood, a `BLOCK_SOUND` line lets it through once.
+
+use hecs::Entity;
+
+use crate::world::{Level, LinedefFlags, SectorId};
+
+/// Who was last heard in each sector (vanilla `sector_t::soundtarget`).
+/// Not saved, like vanilla.
+#[derive(Clone, Debug, Default)]
+pub struct SectorSounds {
+    targets: Vec<Option<Entity>>,
+    /// Sound blocks crossed + 1 on the way in during the current flood,
+    /// 0 if not reached (vanilla `soundtraversed`).
+    traversed: Vec<u8>,
+}
+
+impl SectorSounds {
+    pub fn new(sectors: usize) -> Self {
+        Self {
+            targets: vec![None; sectors],
+            traversed: vec![0; sectors],
+        }
+    }
+
+    /// Who was last heard in `sector`.
+    #[inline]
+    pub fn target(&self, sector: SectorId) -> Option<Entity> {
+        self.targets.get(sector as usize).copied().flatten()
+    }
+}
+
+/// Vanilla `P_NoiseAlert`: `target` made a noise in `sector`; wake up
+/// everything in earshot.
+pub fn p_noise_alert(level: &Level, sounds: &mut SectorSounds, target: Entity, sector: SectorId) {
+    let len = level.sectors.len();
+    sounds.targets.resize(len, None);
+    sounds.traversed.clear();
+    sounds.traversed.resize(len, 0);
+    if sector as usize >= len {
+        return;
+    }
+
+    // vanilla recurses; the stack gives the same fixpoint
+    let mut stack = vec![(sector, 0u8)];
+    while let Some((sec, blocks)) = stack.pop() {
+        let seen = sounds.traversed[sec as usize];
+        if seen != 0 && seen <= blocks + 1 {
+            continue;
+        }
+        sounds.traversed[sec as usize] = blocks + 1;
+        sounds.targets[sec as usize] = Some(target);
+
+        let lines = level.sector_portals(sec);
+        for (&other, &line) in level.sector_neighbors(sec).iter().zip(lines) {
+            if !is_open(level, sec, other) {
+                continue; // closed door
+            }
+            let block = level.linedefs[line as usize]
+                .flags
+                .contains(LinedefFlags::BLOCK_SOUND);
+            match (block, blocks) {
+                (false, _) => stack.push((other, blocks)),
+                (true, 0) => stack.push((other, 1)),
+                (true, _) => {}
+            }
+        }
+    }
+}
+
+/// Vanilla `P_LineOpening` > 0: is there a gap between the two sectors?
+fn is_open(level: &Level, a: SectorId, b: SectorId) -> bool {
+    let (a, b) = (&level.sectors[a as usize], &level.sectors[b as usize]);
+    a.ceil_h.min(b.ceil_h) > a.floor_h.max(b.floor_h)
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::testmap::{Middle, three_rooms};
+
+    fn heard(level: &Level) -> Vec<bool> {
+        let mut sounds = SectorSounds::new(level.sectors.len());
+        let shooter = hecs::World::new().spawn(());
+        p_noise_alert(level, &mut sounds, shooter, 0);
+        (0..3).map(|s| sounds.target(s) == Some(shooter)).collect()
+    }
+
+    #[test]
+    fn noise_floods_through_open_lines_only() {
+        let open = Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        };
+        assert_eq!(heard(&three_rooms(open)), [true, true, true]);
+        // a closed door in the middle
+        let shut = Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        };
+        assert_eq!(heard(&three_rooms(shut)), [true, false, false]);
+    }
+
+    #[test]
+    fn sound_blocking_lines_stop_it_the_second_time() {
+        let mut level = three_rooms(Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let portals = [level.sector_portals(1)[0], level.sector_portals(1)[1]];
+        level.linedefs[portals[0] as usize].flags |= LinedefFlags::BLOCK_SOUND;
+        assert_eq!(heard(&level), [true, true, true]);
+        level.linedefs[portals[1] as usize].flags |= LinedefFlags::BLOCK_SOUND;
+        assert_eq!(heard(&level), [true, true, false]);
+    }
+}
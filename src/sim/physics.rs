@@ -0,0 +1,141 @@
+//! Movement constants and their INI-style config file.
+
+use std::{fs, io, path::Path};
+
+use thiserror::Error;
+
+/// The physics shipped with the game: vanilla's.
+pub const DEFAULT_PHYSICS: &str = include_str!("physics.ini");
+
+#[derive(Error, Debug)]
+pub enum PhysicsError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("line {line}: expected `key = value`")]
+    Syntax { line: usize },
+
+    #[error("line {line}: unknown key {name:?}")]
+    UnknownKey { line: usize, name: String },
+
+    #[error("line {line}: bad value for {name:?}")]
+    BadValue { line: usize, name: String },
+}
+
+/// Knobs for the movement code; the defaults behave like vanilla.
+/// Lengths are map units and speeds map units per tic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PhysicsConfig {
+    /// Momentum kept per tic on the ground (`FRICTION`).
+    pub friction: f32,
+    /// Below this on both axes, ground momentum stops (`STOPSPEED`).
+    pub stop_speed: f32,
+    /// Momentum clamp per axis (`MAXMOVE`).
+    pub max_move: f32,
+    /// Tallest ledge a thing walks up.
+    pub max_step: f32,
+    /// Fall acceleration for z movement (`GRAVITY`).
+    pub gravity: f32,
+    /// Share of the walking thrust a player gets off the ground; vanilla
+    /// gives none.
+    pub air_control: f32,
+    /// Move on vanilla's 16.16 fixed-point math ([`super::fixed`]) for
+    /// bit-exact positions.
+    pub fixed_point: bool,
+    /// Solid things block at any height (vanilla).  Off, things can be
+    /// walked on, stepped onto and flown under.
+    pub infinitely_tall_actors: bool,
+}
+
+impl PhysicsConfig {
+    pub const VANILLA: Self = Self {
+        friction: 0.90625,
+        stop_speed: 0.0625,
+        max_move: 30.0,
+        max_step: 24.0,
+        gravity: 1.0,
+        air_control: 0.0,
+        fixed_point: false,
+        infinitely_tall_actors: true,
+    };
+
+    /// Parse `key = value` lines over the vanilla defaults; `#` / `;`
+    /// start comments and `[section]` headers are ignored.
+    pub fn parse(text: &str) -> Result<Self, PhysicsError> {
+        let mut out = Self::VANILLA;
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let s = raw.split(['#', ';']).next().unwrap_or("").trim();
+            if s.is_empty() || s.starts_with('[') {
+                continue;
+            }
+            let (name, value) = s.split_once('=').ok_or(PhysicsError::Syntax { line })?;
+            let (name, value) = (name.trim(), value.trim());
+            let bad = || PhysicsError::BadValue {
+                line,
+                name: name.to_owned(),
+            };
+            let num = || value.parse::<f32>().ok().filter(|v| v.is_finite());
+            match name {
+                "friction" => out.friction = num().ok_or_else(bad)?,
+                "stop_speed" => out.stop_speed = num().ok_or_else(bad)?,
+                "max_move" => out.max_move = num().ok_or_else(bad)?,
+                "max_step" => out.max_step = num().ok_or_else(bad)?,
+                "gravity" => out.gravity = num().ok_or_else(bad)?,
+                "air_control" => out.air_control = num().ok_or_else(bad)?,
+                "fixed_point" => out.fixed_point = value.parse().map_err(|_| bad())?,
+                "infinitely_tall_actors" => {
+                    out.infinitely_tall_actors = value.parse().map_err(|_| bad())?
+                }
+                _ => {
+                    return Err(PhysicsError::UnknownKey {
+                        line,
+                        name: name.to_owned(),
+                    });
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PhysicsError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self::VANILLA
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_file_is_vanilla() {
+        assert_eq!(
+            PhysicsConfig::parse(DEFAULT_PHYSICS).unwrap(),
+            PhysicsConfig::VANILLA
+        );
+    }
+
+    #[test]
+    fn missing_keys_keep_their_defaults() {
+        let cfg = PhysicsConfig::parse("[sim]\nfixed_point = true\nfriction=0.5 ; icy").unwrap();
+        assert!(cfg.fixed_point);
+        assert_eq!(cfg.friction, 0.5);
+        assert_eq!(cfg.max_move, 30.0);
+
+        let err = PhysicsConfig::parse("gravity = 1\njump = 8").unwrap_err();
+        assert!(matches!(err, PhysicsError::UnknownKey { line: 2, .. }));
+        let err = PhysicsConfig::parse("fixed_point = yes").unwrap_err();
+        assert!(matches!(err, PhysicsError::BadValue { line: 1, .. }));
+        let err = PhysicsConfig::parse("\ngravity").unwrap_err();
+        assert!(matches!(err, PhysicsError::Syntax { line: 2 }));
+    }
+}
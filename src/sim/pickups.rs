@@ -0,0 +1,697 @@
+//! Item pickups – vanilla `P_TouchSpecialThing` and the `P_Give*`
+//! helpers (p_inter.c).
+//!
+//! Movement only notices that a `PICKUP` thing overlapped a `SPECIAL` one;
+//! this decides, by the item's sprite, what that is worth.  Items the
+//! player can't use (a stimpack at full health, ammo already maxed) stay
+//! where they are.
+
+use hecs::{Entity, World};
+
+use super::events::SimEvent;
+use super::{
+    ActorFlags, AmmoType, Animation, Class, Health, Keys, PlayerStatus, Position, Power, Skill,
+    ThingGrid, WeaponType, Weapons, mob,
+};
+use crate::defs::{Definitions, MobjFlags, Sound};
+
+/// Health cap for stimpacks and medikits (vanilla `MAXHEALTH`).
+const MAX_HEALTH: i32 = 100;
+/// Palette flash added per pickup (vanilla `BONUSADD`).
+const BONUS_ADD: i32 = 6;
+
+/// Power durations in tics.
+const INVULN_TICS: i32 = 30 * 35;
+const INVIS_TICS: i32 = 60 * 35;
+const INFRA_TICS: i32 = 120 * 35;
+const IRON_TICS: i32 = 60 * 35;
+
+/// Vanilla `P_TouchSpecialThing`: `toucher` (which has `PlayerStatus`)
+/// walked into `special`.  On a successful pickup the item is removed and
+/// a sound plus a bonus flash are raised.
+pub fn p_touch_special_thing(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    defs: &Definitions,
+    skill: Skill,
+    events: &mut Vec<SimEvent>,
+    special: Entity,
+    toucher: Entity,
+) {
+    let Ok((item_z, sprite, item_flags)) = world
+        .query_one_mut::<(&Position, &Animation, &ActorFlags)>(special)
+        .map(|(p, a, f)| (p.1, defs.state(a.state).sprite, f.0))
+    else {
+        return; // already picked up earlier this tic
+    };
+
+    let Ok((pos, class, health, flags, status, mut weapons)) = world.query_one_mut::<(
+        &Position,
+        &Class,
+        &mut Health,
+        &mut ActorFlags,
+        &mut PlayerStatus,
+        Option<&mut Weapons>,
+    )>(toucher) else {
+        return;
+    };
+
+    // out of reach
+    let delta = item_z - pos.1;
+    if delta > class.0.height as f32 || delta < -8.0 {
+        return;
+    }
+    // can happen with a sliding player corpse
+    if health.0 <= 0 {
+        return;
+    }
+
+    // caps and armor classes, as a DeHackEd patch may have set them
+    let misc = defs.misc;
+    let (green, blue) = (misc.green_armor_class as u8, misc.blue_armor_class as u8);
+    let mut sound = Sound::itemup;
+    match sprite {
+        /* armor */
+        "ARM1" => {
+            if !give_armor(status, green) {
+                return;
+            }
+        }
+        "ARM2" => {
+            if !give_armor(status, blue) {
+                return;
+            }
+        }
+
+        /* bonus items */
+        "BON1" => {
+            status.health = (status.health + 1).min(misc.max_health);
+            health.0 = status.health;
+        }
+        "BON2" => {
+            status.armor = (status.armor + 1).min(misc.max_armor);
+            if status.armor_type == 0 {
+                status.armor_type = green;
+            }
+        }
+        "SOUL" => {
+            status.health = (status.health + misc.soulsphere_health).min(misc.max_soulsphere);
+            health.0 = status.health;
+            sound = Sound::getpow;
+        }
+        "MEGA" => {
+            status.health = misc.megasphere_health;
+            health.0 = status.health;
+            give_armor(status, blue);
+            sound = Sound::getpow;
+        }
+
+        /* keys – taken even when already held */
+        "BKEY" => status.keys |= Keys::BLUE_CARD,
+        "YKEY" => status.keys |= Keys::YELLOW_CARD,
+        "RKEY" => status.keys |= Keys::RED_CARD,
+        "BSKU" => status.keys |= Keys::BLUE_SKULL,
+        "YSKU" => status.keys |= Keys::YELLOW_SKULL,
+        "RSKU" => status.keys |= Keys::RED_SKULL,
+
+        /* medikits, heals */
+        "STIM" => {
+            if !give_body(status, health, 10) {
+                return;
+            }
+        }
+        "MEDI" => {
+            if !give_body(status, health, 25) {
+                return;
+            }
+        }
+
+        /* power ups */
+        "PINV" | "PSTR" | "PINS" | "SUIT" | "PMAP" | "PVIS" => {
+            let power = match sprite {
+                "PINV" => Power::Invulnerability,
+                "PSTR" => Power::Strength,
+                "PINS" => Power::Invisibility,
+                "SUIT" => Power::IronFeet,
+                "PMAP" => Power::AllMap,
+                _ => Power::Infrared,
+            };
+            if !give_power(status, health, flags, power) {
+                return;
+            }
+            if power == Power::Strength {
+                if let Some(wp) = weapons {
+                    if wp.ready != WeaponType::Fist {
+                        wp.pending = Some(WeaponType::Fist);
+                    }
+                }
+            }
+            sound = Sound::getpow;
+        }
+
+        /* ammo */
+        "CLIP" => {
+            // dropped clips are half-full
+            let clips = if item_flags.contains(MobjFlags::DROPPED) {
+                0
+            } else {
+                1
+            };
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Clip,
+                clips,
+            ) {
+                return;
+            }
+        }
+        "AMMO" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Clip,
+                5,
+            ) {
+                return;
+            }
+        }
+        "ROCK" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Misl,
+                1,
+            ) {
+                return;
+            }
+        }
+        "BROK" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Misl,
+                5,
+            ) {
+                return;
+            }
+        }
+        "CELL" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Cell,
+                1,
+            ) {
+                return;
+            }
+        }
+        "CELP" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Cell,
+                5,
+            ) {
+                return;
+            }
+        }
+        "SHEL" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Shell,
+                1,
+            ) {
+                return;
+            }
+        }
+        "SBOX" => {
+            if !give_ammo(
+                status,
+                defs,
+                skill,
+                weapons.as_deref_mut(),
+                AmmoType::Shell,
+                5,
+            ) {
+                return;
+            }
+        }
+        "BPAK" => {
+            if !status.backpack {
+                for max in &mut status.max_ammo {
+                    *max *= 2;
+                }
+                status.backpack = true;
+            }
+            for ammo in [
+                AmmoType::Clip,
+                AmmoType::Shell,
+                AmmoType::Cell,
+                AmmoType::Misl,
+            ] {
+                give_ammo(status, defs, skill, weapons.as_deref_mut(), ammo, 1);
+            }
+        }
+
+        /* weapons */
+        "BFUG" | "MGUN" | "CSAW" | "LAUN" | "PLAS" | "SHOT" | "SGN2" => {
+            let weapon = match sprite {
+                "BFUG" => WeaponType::Bfg,
+                "MGUN" => WeaponType::Chaingun,
+                "CSAW" => WeaponType::Chainsaw,
+                "LAUN" => WeaponType::Missile,
+                "PLAS" => WeaponType::Plasma,
+                "SHOT" => WeaponType::Shotgun,
+                _ => WeaponType::SuperShotgun,
+            };
+            let Some(wp) = weapons else {
+                return;
+            };
+            let dropped = item_flags.contains(MobjFlags::DROPPED);
+            if !give_weapon(status, defs, skill, wp, weapon, dropped) {
+                return;
+            }
+            sound = Sound::wpnup;
+        }
+
+        _ => return,
+    }
+
+    if item_flags.contains(MobjFlags::COUNTITEM) {
+        status.item_count += 1;
+    }
+    status.bonus_count += BONUS_ADD;
+    let (origin, count) = (pos.0, status.bonus_count);
+
+    mob::remove_mobj(world, thing_grid, special);
+    events.push(SimEvent::Sound {
+        sound,
+        origin,
+        full_volume: false,
+    });
+    events.push(SimEvent::Bonus { count });
+}
+
+/*──────────────────────────── P_Give* ──────────────────────────────*/
+
+/// Vanilla `P_GiveBody`: heal by `num` up to 100.  `false` if already
+/// there.
+fn give_body(status: &mut PlayerStatus, health: &mut Health, num: i32) -> bool {
+    if status.health >= MAX_HEALTH {
+        return false;
+    }
+    status.health = (status.health + num).min(MAX_HEALTH);
+    health.0 = status.health;
+    true
+}
+
+/// Vanilla `P_GiveArmor`: `armor_type` 1 is green (100), 2 is blue (200).
+/// `false` if the player already has at least that much.
+fn give_armor(status: &mut PlayerStatus, armor_type: u8) -> bool {
+    let hits = armor_type as i32 * 100;
+    if status.armor >= hits {
+        return false;
+    }
+    status.armor_type = armor_type;
+    status.armor = hits;
+    true
+}
+
+/// Vanilla `P_GiveAmmo`: `clips` clip-sized portions, or half a clip for
+/// 0, twice that on the easiest and hardest skills.  `false` if that
+/// ammo is already full.  Ammo for a weapon the player owns, found while
+/// out of it, switches to that weapon if the one in hand is weaker.
+fn give_ammo(
+    status: &mut PlayerStatus,
+    defs: &Definitions,
+    skill: Skill,
+    weapons: Option<&mut Weapons>,
+    ammo: AmmoType,
+    clips: i32,
+) -> bool {
+    let i = ammo as usize;
+    if status.ammo[i] == status.max_ammo[i] {
+        return false;
+    }
+    let clip = defs.clip_ammo[i];
+    let mut num = if clips == 0 { clip / 2 } else { clips * clip };
+    if matches!(skill, Skill::Baby | Skill::Nightmare) {
+        // give double ammo in trainer mode, you'll need in nightmare
+        num <<= 1;
+    }
+    let old = status.ammo[i];
+    status.ammo[i] = (old + num).min(status.max_ammo[i]);
+
+    // if non zero ammo, don't change up weapons, player was lower on
+    // purpose
+    let Some(wp) = weapons.filter(|_| old == 0) else {
+        return true;
+    };
+    use WeaponType::*;
+    let weak = matches!(wp.ready, Fist | Pistol);
+    let switch = match ammo {
+        AmmoType::Clip if wp.ready == Fist => {
+            Some(if wp.owns(Chaingun) { Chaingun } else { Pistol })
+        }
+        AmmoType::Shell if weak && wp.owns(Shotgun) => Some(Shotgun),
+        AmmoType::Cell if weak && wp.owns(Plasma) => Some(Plasma),
+        AmmoType::Misl if wp.ready == Fist && wp.owns(Missile) => Some(Missile),
+        _ => None,
+    };
+    if switch.is_some() {
+        wp.pending = switch;
+    }
+    true
+}
+
+/// Vanilla `P_GiveWeapon`: the weapon and two clips' worth of its ammo
+/// (one if `dropped` by a monster).  A new weapon is switched to.
+/// `false` if there was nothing to take.
+fn give_weapon(
+    status: &mut PlayerStatus,
+    defs: &Definitions,
+    skill: Skill,
+    weapons: &mut Weapons,
+    weapon: WeaponType,
+    dropped: bool,
+) -> bool {
+    let gave_ammo = weapon.info().ammo.is_some_and(|ammo| {
+        let clips = if dropped { 1 } else { 2 };
+        give_ammo(status, defs, skill, Some(weapons), ammo, clips)
+    });
+    if weapons.owns(weapon) {
+        return gave_ammo;
+    }
+    weapons.owned[weapon as usize] = true;
+    weapons.pending = Some(weapon);
+    true
+}
+
+/// Vanilla `P_GivePower`.  Timed powers restart their timer; the map and
+/// berserk are only taken once.
+fn give_power(
+    status: &mut PlayerStatus,
+    health: &mut Health,
+    flags: &mut ActorFlags,
+    power: Power,
+) -> bool {
+    let slot = &mut status.powers[power as usize];
+    match power {
+        Power::Invulnerability => *slot = INVULN_TICS,
+        Power::Invisibility => {
+            *slot = INVIS_TICS;
+            flags.0.insert(MobjFlags::SHADOW);
+        }
+        Power::Infrared => *slot = INFRA_TICS,
+        Power::IronFeet => *slot = IRON_TICS,
+        Power::Strength => {
+            *slot = 1;
+            give_body(status, health, 100);
+        }
+        Power::AllMap => {
+            if *slot != 0 {
+                return false;
+            }
+            *slot = 1;
+        }
+    }
+    true
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{InputCmd, NUM_AMMO, TicRunner, Velocity},
+        world::{Level, testmap},
+    };
+    use glam::Vec2;
+
+    fn setup() -> (Level, TicRunner, Entity) {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 32.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        (level, sim, player)
+    }
+
+    fn walk_east(level: &mut Level, sim: &mut TicRunner) {
+        let walk = InputCmd {
+            forward: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            sim.run_tic(level, walk);
+        }
+    }
+
+    fn status(sim: &TicRunner, player: Entity) -> PlayerStatus {
+        *sim.world().get::<&PlayerStatus>(player).unwrap()
+    }
+
+    #[test]
+    fn green_armor_is_taken_and_disappears() {
+        let (mut level, mut sim, player) = setup();
+        let armor = sim.spawn_mobj(&level, defs::by_id("MISC0").unwrap(), 80.0, 64.0, 0.0, 0);
+
+        walk_east(&mut level, &mut sim);
+
+        let st = status(&sim, player);
+        assert_eq!((st.armor, st.armor_type), (100, 1));
+        // the flash has been fading since the pickup
+        assert!(st.bonus_count < BONUS_ADD);
+        assert!(!sim.world().contains(armor));
+        let events: Vec<_> = sim.drain_events().collect();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            SimEvent::Sound {
+                sound: Sound::itemup,
+                ..
+            }
+        )));
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, SimEvent::Bonus { count: BONUS_ADD }))
+        );
+    }
+
+    #[test]
+    fn stimpack_stays_at_full_health_and_caps_at_100() {
+        let (mut level, mut sim, player) = setup();
+        let stim = defs::by_id("MISC10").unwrap();
+        let first = sim.spawn_mobj(&level, stim, 80.0, 64.0, 0.0, 0);
+
+        walk_east(&mut level, &mut sim);
+        assert!(sim.world().contains(first));
+        assert_eq!(status(&sim, player).health, 100);
+
+        // hurt, then walk back over it
+        sim.world_mut()
+            .get::<&mut PlayerStatus>(player)
+            .unwrap()
+            .health = 95;
+        sim.world_mut().get::<&mut Health>(player).unwrap().0 = 95;
+        let back = InputCmd {
+            forward: -1.0,
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            sim.run_tic(&mut level, back);
+        }
+        assert!(!sim.world().contains(first));
+        assert_eq!(status(&sim, player).health, 100);
+        assert_eq!(sim.world().get::<&Health>(player).unwrap().0, 100);
+    }
+
+    #[test]
+    fn former_humans_drop_their_guns_half_loaded() {
+        let (mut level, mut sim, player) = setup();
+        let killed = [("POSSESSED", 88.0, 40.0), ("SHOTGUY", 88.0, 88.0)]
+            .map(|(id, x, y)| sim.spawn_mobj(&level, defs::by_id(id).unwrap(), x, y, 0.0, 0));
+        for guy in killed {
+            let health = sim.world().get::<&Health>(guy).unwrap().0;
+            sim.damage_mobj(guy, None, Some(player), health);
+        }
+        let dropped = |sim: &TicRunner| {
+            let world = sim.world();
+            let mut q = world.query::<(&Class, &ActorFlags, &Position, &Velocity)>();
+            q.iter()
+                .filter(|(_, (c, ..))| matches!(c.0.id, "CLIP" | "SHOTGUN"))
+                .map(|(_, (c, f, p, v))| (c.0.id, f.0, *p, v.0.z))
+                .collect::<Vec<_>>()
+        };
+
+        // out at A_Fall, tossed up, and down on the floor soon after
+        let mut highest = 0.0_f32;
+        for _ in 0..35 {
+            sim.run_tic(&mut level, InputCmd::default());
+            highest = (dropped(&sim).iter()).fold(highest, |h, (_, _, p, _)| h.max(p.1));
+        }
+        let items = dropped(&sim);
+        assert_eq!(items.len(), 2, "{items:?}");
+        assert!(highest > 4.0);
+        for (id, flags, pos, vz) in items {
+            assert!(flags.contains(MobjFlags::DROPPED), "{id}");
+            assert_eq!((pos.1, vz), (0.0, 0.0), "{id} still in the air");
+            let body = if id == "CLIP" { 40.0 } else { 88.0 };
+            assert!(
+                pos.0.distance(Vec2::new(88.0, body)) < 6.0,
+                "{id} at {}",
+                pos.0
+            );
+        }
+
+        let before = status(&sim, player);
+        walk_east(&mut level, &mut sim);
+        let st = status(&sim, player);
+        assert!(dropped(&sim).is_empty());
+        // half a clip, and one clip of shells rather than a placed gun's two
+        let ammo = |st: &PlayerStatus, a: AmmoType| st.ammo[a as usize];
+        assert_eq!(ammo(&st, AmmoType::Clip), ammo(&before, AmmoType::Clip) + 5);
+        assert_eq!(ammo(&st, AmmoType::Shell), 4);
+        // a new gun, and the first shells for it: switched to
+        let wp = sim.world().get::<&Weapons>(player).unwrap();
+        assert!(wp.owns(WeaponType::Shotgun));
+        assert!(wp.pending == Some(WeaponType::Shotgun) || wp.ready == WeaponType::Shotgun);
+    }
+
+    #[test]
+    fn ammo_boxes_keys_and_backpack() {
+        let (mut level, mut sim, player) = setup();
+        for (id, x) in [("CLIP", 56.0), ("MISC4", 72.0), ("MISC24", 88.0)] {
+            sim.spawn_mobj(&level, defs::by_id(id).unwrap(), x, 64.0, 0.0, 0);
+        }
+
+        walk_east(&mut level, &mut sim);
+
+        let st = status(&sim, player);
+        assert!(st.backpack);
+        assert_eq!(st.max_ammo, [400, 100, 600, 100]);
+        // 50 + clip + backpack's clip
+        assert_eq!(st.ammo[AmmoType::Clip as usize], 70);
+        assert_eq!(st.ammo[AmmoType::Shell as usize], 4);
+        assert_eq!(st.keys, Keys::BLUE_CARD);
+        let flashes = sim
+            .drain_events()
+            .filter(|e| matches!(e, SimEvent::Bonus { .. }))
+            .count();
+        assert_eq!(flashes, 3);
+    }
+
+    #[test]
+    fn ammo_is_doubled_on_baby_and_nightmare() {
+        for (skill, clip) in [
+            (Skill::Baby, 70),
+            (Skill::Medium, 60),
+            (Skill::Nightmare, 70),
+        ] {
+            let (mut level, mut sim, player) = setup();
+            sim.set_skill(skill);
+            sim.spawn_mobj(&level, defs::by_id("CLIP").unwrap(), 80.0, 64.0, 0.0, 0);
+
+            walk_east(&mut level, &mut sim);
+
+            assert_eq!(
+                status(&sim, player).ammo[AmmoType::Clip as usize],
+                clip,
+                "{skill:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn new_weapon_is_switched_to_and_its_ammo_given() {
+        let (mut level, mut sim, player) = setup();
+        sim.spawn_mobj(&level, defs::by_id("SHOTGUN").unwrap(), 80.0, 64.0, 0.0, 0);
+
+        walk_east(&mut level, &mut sim);
+
+        let wp = *sim.world().get::<&Weapons>(player).unwrap();
+        assert!(wp.owns(WeaponType::Shotgun));
+        assert_eq!(
+            WeaponType::Shotgun,
+            wp.pending.unwrap_or(wp.ready),
+            "no switch to the new weapon"
+        );
+        assert_eq!(status(&sim, player).ammo[AmmoType::Shell as usize], 8);
+        assert!(sim.drain_events().any(|e| matches!(
+            e,
+            SimEvent::Sound {
+                sound: Sound::wpnup,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn ammo_found_while_punching_brings_back_the_gun() {
+        let (mut level, mut sim, player) = setup();
+        {
+            let world = sim.world_mut();
+            world.get::<&mut PlayerStatus>(player).unwrap().ammo = [0; NUM_AMMO];
+            let mut wp = world.get::<&mut Weapons>(player).unwrap();
+            *wp = Weapons::new(&Definitions::shared_builtin(), wp.owned, WeaponType::Fist);
+        }
+        sim.spawn_mobj(&level, defs::by_id("CLIP").unwrap(), 80.0, 64.0, 0.0, 0);
+
+        walk_east(&mut level, &mut sim);
+
+        let wp = *sim.world().get::<&Weapons>(player).unwrap();
+        assert_eq!(wp.pending, Some(WeaponType::Pistol));
+    }
+
+    #[test]
+    fn light_amp_goggles_light_everything_then_blink_out() {
+        let (mut level, mut sim, player) = setup();
+        sim.spawn_mobj(&level, defs::by_id("MISC16").unwrap(), 80.0, 64.0, 0.0, 0);
+
+        walk_east(&mut level, &mut sim);
+
+        let st = status(&sim, player);
+        assert!(st.powers[Power::Infrared as usize] > INFRA_TICS - 10);
+        assert_eq!(st.palette_effect().fixed_colormap, Some(0));
+
+        // the last seconds toggle every 8 tics
+        sim.world_mut()
+            .get::<&mut PlayerStatus>(player)
+            .unwrap()
+            .powers[Power::Infrared as usize] = 4 * 32;
+        let blink: Vec<_> = (0..32)
+            .map(|_| {
+                sim.run_tic(&mut level, InputCmd::default());
+                status(&sim, player).palette_effect().fixed_colormap
+            })
+            .collect();
+        assert!(blink.contains(&None) && blink.contains(&Some(0)));
+        assert_eq!(blink[0], blink[7]);
+        assert_ne!(blink[7], blink[8]);
+
+        for _ in 0..4 * 32 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        let st = status(&sim, player);
+        assert_eq!(st.powers[Power::Infrared as usize], 0);
+        assert_eq!(st.palette_effect().fixed_colormap, None);
+    }
+}
@@ -0,0 +1,371 @@
+//! Lifts – vanilla `p_plats.c`.
+//!
+//! A lift floor lowers to the lowest neighbouring floor, waits, and comes
+//! back up; something in the way on the rise sends it down again.
+//! Perpetual lifts keep going until a stop line puts them in stasis.
+//! The running plats double as vanilla's `activeplats` list.
+
+use hecs::World;
+
+use super::ceilings::Ceiling;
+use super::doors::Door;
+use super::events::SimEvent;
+use super::floors::Floor;
+use super::movers::{MoveResult, sector_busy, t_move_floor};
+use super::{GameRules, PhysicsConfig, Rng, ThingGrid};
+use crate::defs::{Definitions, Sound};
+use crate::world::{Level, SectorId};
+
+/// Lift speed in units per tic (vanilla `PLATSPEED`).
+pub const PLAT_SPEED: f32 = 1.0;
+
+/// Tics a lift waits at either end (vanilla `35 * PLATWAIT`).
+pub const PLAT_WAIT: i32 = 35 * 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlatKind {
+    /// Between the lowest and highest neighbouring floors, for ever.
+    PerpetualRaise,
+    /// Down at four times `PLAT_SPEED`, wait, back up and done.
+    DownWaitUpStay,
+    /// `DownWaitUpStay` at eight times `PLAT_SPEED`.
+    BlazeDwus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlatStatus {
+    Up,
+    Down,
+    Waiting,
+    InStasis,
+}
+
+/// One running lift (vanilla `plat_t`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Platform {
+    pub sector: SectorId,
+    pub kind: PlatKind,
+    pub speed: f32,
+    pub low: f32,
+    pub high: f32,
+    /// Tics to wait at either end.
+    pub wait: i32,
+    /// Tics left of the current wait.
+    pub count: i32,
+    pub status: PlatStatus,
+    /// Where to go when a stasis ends.
+    pub old_status: PlatStatus,
+    pub crush: bool,
+    pub tag: u16,
+}
+
+/// Vanilla `EV_DoPlat`: start a `kind` lift in every idle sector tagged
+/// `tag`.  Perpetual lifts in stasis with the tag start up again first.
+/// `true` if a new lift started.
+#[allow(clippy::too_many_arguments)]
+pub fn ev_do_plat(
+    level: &Level,
+    plats: &mut Vec<Platform>,
+    doors: &[Door],
+    ceilings: &[Ceiling],
+    floors: &[Floor],
+    rng: &mut Rng,
+    events: &mut Vec<SimEvent>,
+    tag: u16,
+    kind: PlatKind,
+) -> bool {
+    // activate all <type> plats that are in_stasis
+    if kind == PlatKind::PerpetualRaise {
+        p_activate_in_stasis(plats, tag);
+    }
+
+    let mut rtn = false;
+    for (id, sector) in level.sectors.iter().enumerate() {
+        let id = id as SectorId;
+        if sector.tag as u16 != tag || sector_busy(doors, ceilings, plats, floors, id) {
+            continue;
+        }
+        rtn = true;
+
+        let floor = sector.floor_h;
+        let low = level.lowest_neighbor_floor(id).min(floor);
+        let (speed, high, status) = match kind {
+            PlatKind::PerpetualRaise => {
+                let high = level.highest_neighbor_floor(id).max(floor);
+                let status = if rng.p_random() & 1 == 0 {
+                    PlatStatus::Up
+                } else {
+                    PlatStatus::Down
+                };
+                (PLAT_SPEED, high, status)
+            }
+            PlatKind::DownWaitUpStay => (PLAT_SPEED * 4.0, floor, PlatStatus::Down),
+            PlatKind::BlazeDwus => (PLAT_SPEED * 8.0, floor, PlatStatus::Down),
+        };
+        plats.push(Platform {
+            sector: id,
+            kind,
+            speed,
+            low,
+            high,
+            wait: PLAT_WAIT,
+            count: 0,
+            status,
+            old_status: status,
+            crush: false,
+            tag,
+        });
+        events.push(SimEvent::Sound {
+            sound: Sound::pstart,
+            origin: level.sound_origin(id),
+            full_volume: false,
+        });
+    }
+    rtn
+}
+
+/// Vanilla `P_ActivateInStasis`.
+fn p_activate_in_stasis(plats: &mut [Platform], tag: u16) {
+    for p in plats.iter_mut() {
+        if p.tag == tag && p.status == PlatStatus::InStasis {
+            p.status = p.old_status;
+        }
+    }
+}
+
+/// Vanilla `EV_StopPlat`: freeze every moving lift tagged `tag`.  `true`
+/// if one stopped.
+pub fn ev_stop_plat(plats: &mut [Platform], tag: u16) -> bool {
+    let mut rtn = false;
+    for p in plats.iter_mut() {
+        if p.tag == tag && p.status != PlatStatus::InStasis {
+            p.old_status = p.status;
+            p.status = PlatStatus::InStasis;
+            rtn = true;
+        }
+    }
+    rtn
+}
+
+/// Advance every lift one tic (vanilla `T_PlatRaise`); lifts that are
+/// done leave the list.
+#[allow(clippy::too_many_arguments)]
+pub fn run_plats(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    gametic: u32,
+    plats: &mut Vec<Platform>,
+) {
+    plats.retain_mut(|p| {
+        let mut sound = |level: &Level, sound| {
+            events.push(SimEvent::Sound {
+                sound,
+                origin: level.sound_origin(p.sector),
+                full_volume: false,
+            });
+        };
+        match p.status {
+            PlatStatus::Up => {
+                let res = t_move_floor(
+                    world, thing_grid, level, cfg, rng, rules, defs, gametic, p.sector, p.speed,
+                    p.high, p.crush, 1,
+                );
+                match res {
+                    MoveResult::Crushed if !p.crush => {
+                        p.count = p.wait;
+                        p.status = PlatStatus::Down;
+                        sound(level, Sound::pstart);
+                    }
+                    MoveResult::PastDest => {
+                        p.count = p.wait;
+                        p.status = PlatStatus::Waiting;
+                        sound(level, Sound::pstop);
+                        if p.kind != PlatKind::PerpetualRaise {
+                            return false; // P_RemoveActivePlat
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            PlatStatus::Down => {
+                let res = t_move_floor(
+                    world, thing_grid, level, cfg, rng, rules, defs, gametic, p.sector, p.speed,
+                    p.low, false, -1,
+                );
+                if res == MoveResult::PastDest {
+                    p.count = p.wait;
+                    p.status = PlatStatus::Waiting;
+                    sound(level, Sound::pstop);
+                }
+            }
+            PlatStatus::Waiting => {
+                p.count -= 1;
+                if p.count == 0 {
+                    p.status = if level.sectors[p.sector as usize].floor_h == p.low {
+                        PlatStatus::Up
+                    } else {
+                        PlatStatus::Down
+                    };
+                    sound(level, Sound::pstart);
+                }
+            }
+            PlatStatus::InStasis => {}
+        }
+        true
+    });
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{Angle, InputCmd, TicRunner},
+        world::testmap,
+    };
+
+    /// The middle strip as a lift 64 units above rooms A and B.
+    fn lift_level(ceil: f32) -> testmap::Middle {
+        testmap::Middle::Open { floor: 64.0, ceil }
+    }
+
+    /// Room A's west wall becomes a `special` switch for the lift.
+    fn lift_switch(level: &mut Level, special: u16) {
+        level.sectors[1].tag = 7;
+        let west = level
+            .linedefs
+            .iter()
+            .position(|l| (l.v1, l.v2) == (0, 4))
+            .unwrap();
+        level.linedefs[west].special = special;
+        level.linedefs[west].tag = 7;
+    }
+
+    fn press(level: &mut Level) -> TicRunner {
+        let mut sim = TicRunner::new(level);
+        let player = sim.spawn_mobj(level, defs::by_id("PLAYER").unwrap(), 40.0, 64.0, 0.0, 0);
+        *sim.world_mut().get::<&mut Angle>(player).unwrap() = Angle::ANG180;
+        sim.set_player(player);
+        let press = InputCmd {
+            use_act: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(level, press);
+        sim
+    }
+
+    fn sounds(sim: &mut TicRunner) -> Vec<Sound> {
+        sim.drain_events()
+            .filter_map(|e| match e {
+                SimEvent::Sound { sound, .. } => Some(sound),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lift_lowers_waits_and_comes_back() {
+        let mut level = testmap::three_rooms(lift_level(128.0));
+        lift_switch(&mut level, 62);
+        let mut sim = press(&mut level);
+        assert_eq!(sim.plats().len(), 1);
+        assert_eq!(sim.plats()[0].low, 0.0);
+        assert!(sounds(&mut sim).contains(&Sound::pstart));
+
+        // 64 units at 4 per tic
+        for _ in 0..16 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].floor_h, 0.0);
+        assert_eq!(sim.plats()[0].status, PlatStatus::Waiting);
+        assert!(sounds(&mut sim).contains(&Sound::pstop));
+
+        for _ in 0..PLAT_WAIT {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(sim.plats()[0].status, PlatStatus::Up);
+        // landing exactly on `high` takes one more tic to notice
+        for _ in 0..17 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].floor_h, 64.0);
+        assert!(sim.plats().is_empty(), "finished lift still active");
+    }
+
+    #[test]
+    fn monster_under_the_ceiling_sends_lift_back_down() {
+        // an imp (56 tall) fits under a 112 ceiling only below floor 56
+        let mut level = testmap::three_rooms(lift_level(112.0));
+        lift_switch(&mut level, 62);
+        let mut sim = press(&mut level);
+        for _ in 0..16 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(level.sectors[1].floor_h, 0.0);
+        sim.spawn_mobj(&level, defs::by_id("TROOP").unwrap(), 136.0, 64.0, 0.0, 1);
+
+        for _ in 0..PLAT_WAIT {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        sounds(&mut sim);
+        let mut reversed = false;
+        for _ in 0..16 {
+            sim.run_tic(&mut level, InputCmd::default());
+            assert!(level.sectors[1].floor_h <= 56.0);
+            reversed |= sim.plats()[0].status == PlatStatus::Down;
+        }
+        assert!(reversed, "blocked lift kept rising");
+        assert!(sounds(&mut sim).contains(&Sound::pstart));
+    }
+
+    #[test]
+    fn perpetual_lift_stops_and_restarts() {
+        let mut level = testmap::three_rooms(lift_level(128.0));
+        level.sectors[1].tag = 4;
+        let mut plats = Vec::new();
+        let mut rng = Rng::default();
+        let mut events = Vec::new();
+
+        assert!(ev_do_plat(
+            &level,
+            &mut plats,
+            &[],
+            &[],
+            &[],
+            &mut rng,
+            &mut events,
+            4,
+            PlatKind::PerpetualRaise
+        ));
+        assert_eq!((plats[0].low, plats[0].high), (0.0, 64.0));
+        let moving = plats[0].status;
+
+        assert!(ev_stop_plat(&mut plats, 4));
+        assert_eq!(plats[0].status, PlatStatus::InStasis);
+        assert!(!ev_stop_plat(&mut plats, 4));
+
+        // restarting resumes the old direction; no second lift
+        assert!(!ev_do_plat(
+            &level,
+            &mut plats,
+            &[],
+            &[],
+            &[],
+            &mut rng,
+            &mut events,
+            4,
+            PlatKind::PerpetualRaise
+        ));
+        assert_eq!(plats[0].status, moving);
+        assert_eq!(plats.len(), 1);
+    }
+}
@@ -0,0 +1,194 @@
+//! Nightmare respawning: the corpse check of vanilla `P_MobjThinker` and
+//! `P_NightmareRespawn`.
+
+use hecs::{Entity, World};
+
+use super::events::SimEvent;
+use super::xy_movement::{Mover, p_check_position};
+use super::{
+    ActorFlags, Animation, Class, MoveCount, PhysicsConfig, Position, RESPAWN_MIN_TICS, Rng,
+    SpawnPoint, ThingGrid, mob,
+};
+use crate::defs::{Definitions, MobjFlags, Sound};
+use crate::world::Level;
+
+/// Give the corpses that have lain long enough their chance to get up.
+/// A corpse is a `COUNTKILL` thing whose state lasts forever; it counts
+/// its tics in `MoveCount`, as vanilla does in `movecount`.  Every 32nd
+/// tic past [`RESPAWN_MIN_TICS`] it gets up 5 times in 256.
+#[allow(clippy::too_many_arguments)]
+pub fn respawn_monsters(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    defs: &Definitions,
+    rng: &mut Rng,
+    events: &mut Vec<SimEvent>,
+    gametic: u32,
+) {
+    let mut ready = Vec::new();
+    let mut fresh = Vec::new();
+    for (ent, (anim, flags, count)) in
+        world.query_mut::<(&Animation, &ActorFlags, Option<&mut MoveCount>)>()
+    {
+        if anim.tics != -1 || !flags.0.contains(MobjFlags::COUNTKILL) {
+            continue;
+        }
+        let Some(count) = count else {
+            fresh.push(ent);
+            continue;
+        };
+        count.0 += 1;
+        if count.0 >= RESPAWN_MIN_TICS && gametic & 31 == 0 {
+            ready.push(ent);
+        }
+    }
+    for ent in fresh {
+        world.insert_one(ent, MoveCount(1)).ok();
+    }
+    for ent in ready {
+        if rng.p_random() <= 4 {
+            p_nightmare_respawn(world, thing_grid, level, cfg, defs, events, ent);
+        }
+    }
+}
+
+/// Vanilla `P_NightmareRespawn`: unless something stands on its map
+/// spot, the corpse `ent` vanishes in teleport fog and a fresh monster
+/// of its kind appears at the spot in more fog.  `None` if it stayed put.
+pub fn p_nightmare_respawn(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    ent: Entity,
+) -> Option<Entity> {
+    let spot = world.get::<&SpawnPoint>(ent).ok()?.0.clone();
+    let mover = {
+        let mut q = world
+            .query_one::<(&Position, &Class, &ActorFlags)>(ent)
+            .ok()?;
+        let (pos, class, flags) = q.get()?;
+        Mover {
+            ent,
+            pos: *pos,
+            class: *class,
+            flags: *flags,
+        }
+    };
+    if p_check_position(level, cfg, world, thing_grid, &mover, spot.pos).blocked {
+        return None;
+    }
+
+    for at in [mover.pos.0, spot.pos] {
+        mob::spawn_tele_fog(world, thing_grid, level, defs, at);
+        events.push(SimEvent::Sound {
+            sound: Sound::telept,
+            origin: at,
+            full_volume: false,
+        });
+    }
+
+    let info = *defs.by_doomednum(spot.type_id).unwrap_or(&mover.class.0);
+    let mo = mob::spawn_mobj(
+        world,
+        thing_grid,
+        level,
+        defs,
+        &info,
+        spot.pos.x,
+        spot.pos.y,
+        spot.angle,
+        spot.sub_sector,
+    );
+    if spot.is_deaf {
+        if let Ok(mut flags) = world.get::<&mut ActorFlags>(mo) {
+            flags.0.insert(MobjFlags::AMBUSH);
+        }
+    }
+    world.insert_one(mo, SpawnPoint(spot)).ok();
+    mob::remove_mobj(world, thing_grid, ent);
+    Some(mo)
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+
+    use super::*;
+    use crate::sim::{Angle, GameRules, Health, InputCmd, Skill, TicRunner};
+    use crate::world::{Thing, testmap};
+
+    const SPOT: Vec2 = Vec2::new(64.0, 64.0);
+
+    /// A zombieman at [`SPOT`] with the player two rooms away, killed.
+    fn dead_zombie(skill: Skill) -> (Level, TicRunner, Entity) {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        sim.set_skill(skill);
+        sim.set_rules(GameRules::for_skill(skill));
+        let thing = |type_id, pos: Vec2| Thing {
+            pos,
+            angle: Angle::ZERO,
+            type_id,
+            skills: 7,
+            is_deaf: false,
+            multiplayer: false,
+            sub_sector: level.locate_subsector(pos),
+        };
+        sim.spawn_map_thing(&level, &thing(1, Vec2::new(448.0, 64.0)));
+        sim.spawn_player(&level).unwrap();
+        let zombie = sim.spawn_map_thing(&level, &thing(3004, SPOT)).unwrap();
+        // shove the body off its spot, as a blast would
+        sim.damage_mobj(zombie, None, None, 1000);
+        sim.world_mut().get::<&mut Position>(zombie).unwrap().0 += Vec2::new(24.0, 16.0);
+        sim.relink_thing(&level, zombie);
+        (level, sim, zombie)
+    }
+
+    fn live_zombies(sim: &TicRunner) -> Vec<(Entity, Vec2)> {
+        let mut q = sim.world().query::<(&Class, &Health, &Position)>();
+        q.iter()
+            .filter(|(_, (c, h, _))| c.0.id == "POSSESSED" && h.0 > 0)
+            .map(|(e, (_, _, p))| (e, p.0))
+            .collect()
+    }
+
+    #[test]
+    fn nightmare_zombieman_gets_up_at_its_spot() {
+        let (mut level, mut sim, corpse) = dead_zombie(Skill::Nightmare);
+        assert!(live_zombies(&sim).is_empty());
+        let mut back = None;
+        for tic in 0..35 * 90 {
+            sim.run_tic(&mut level, InputCmd::default());
+            if let Some(&(ent, pos)) = live_zombies(&sim).first() {
+                back = Some((tic, ent, pos));
+                break;
+            }
+        }
+        let (tic, ent, pos) = back.expect("never came back");
+        assert!(tic >= RESPAWN_MIN_TICS, "up after {tic} tics");
+        assert_eq!(pos, SPOT);
+        assert!(!sim.world().contains(corpse), "the body is gone");
+        assert!(sim.world().get::<&SpawnPoint>(ent).is_ok());
+        let mut classes = sim.world().query::<&Class>();
+        let fog = classes.iter().filter(|(_, c)| c.0.id == "TFOG");
+        assert_eq!(fog.count(), 2);
+    }
+
+    #[test]
+    fn corpses_stay_down_below_nightmare() {
+        let (mut level, mut sim, corpse) = dead_zombie(Skill::Hard);
+        for _ in 0..35 * 90 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert!(live_zombies(&sim).is_empty());
+        assert!(sim.world().contains(corpse));
+    }
+}
@@ -0,0 +1,142 @@
+//! What Nightmare changes, and `-fast` / `-respawn` can ask for on any
+//! skill (vanilla `fastparm`, `respawnparm` and the `sk_nightmare` tests).
+
+use super::{SIM_FPS, Skill};
+use crate::defs::{Definitions, MobjInfo, State};
+
+/// Missile speed of the fast imp, cacodemon and baron balls (vanilla
+/// `G_InitNew` sets them all to 20).
+const FAST_MISSILE_SPEED: i32 = 20;
+
+/// How long a corpse lies before it may get up again (vanilla's
+/// `12*TICRATE`; after that it's a 5 in 256 chance every 32 tics).
+pub const RESPAWN_MIN_TICS: i32 = 12 * SIM_FPS as i32;
+
+/// Sim behaviour beyond the skill's thing selection and damage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GameRules {
+    /// Demons run, bite and flinch twice as fast and the imp, cacodemon
+    /// and baron missiles fly at 20.
+    pub fast_monsters: bool,
+    /// Dead monsters come back at their map spot a while later.
+    pub respawn_monsters: bool,
+    /// The `gameskill == sk_nightmare` half of vanilla `A_Chase`'s two
+    /// tests, whose other half is `fastparm`: with either, a monster
+    /// that has `MF_JUSTATTACKED` clears it without `P_NewChaseDir`, and
+    /// may try a missile while `movecount` is still running.  Saved and
+    /// merged, but not read: `enemy::a_chase` doesn't walk or attack yet.
+    pub no_pain_chance_reduction: bool,
+}
+
+impl GameRules {
+    /// Vanilla, for every skill but Nightmare.
+    pub const VANILLA: Self = Self {
+        fast_monsters: false,
+        respawn_monsters: false,
+        no_pain_chance_reduction: false,
+    };
+
+    /// Everything on.
+    pub const NIGHTMARE: Self = Self {
+        fast_monsters: true,
+        respawn_monsters: true,
+        no_pain_chance_reduction: true,
+    };
+
+    /// What `skill` plays by on its own.
+    pub fn for_skill(skill: Skill) -> Self {
+        match skill {
+            Skill::Nightmare => Self::NIGHTMARE,
+            _ => Self::VANILLA,
+        }
+    }
+
+    /// Every behaviour either set has.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            fast_monsters: self.fast_monsters || other.fast_monsters,
+            respawn_monsters: self.respawn_monsters || other.respawn_monsters,
+            no_pain_chance_reduction: self.no_pain_chance_reduction
+                || other.no_pain_chance_reduction,
+        }
+    }
+
+    /// Packed one bit each, for savegames.
+    pub fn bits(self) -> u8 {
+        self.fast_monsters as u8
+            | (self.respawn_monsters as u8) << 1
+            | (self.no_pain_chance_reduction as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            fast_monsters: bits & 1 != 0,
+            respawn_monsters: bits & 2 != 0,
+            no_pain_chance_reduction: bits & 4 != 0,
+        }
+    }
+
+    /// How long `state` lasts by `defs`: the demon's run, attack and pain
+    /// frames (`S_SARG_RUN1`‥`S_SARG_PAIN2`) are halved when fast.
+    pub fn state_tics(self, defs: &Definitions, state: State) -> i32 {
+        let tics = defs.state(state).tics;
+        let demon =
+            (State::SARG_RUN1 as usize..=State::SARG_PAIN2 as usize).contains(&(state as usize));
+        if self.fast_monsters && demon {
+            tics >> 1
+        } else {
+            tics
+        }
+    }
+
+    /// Speed `info` flies at as a missile.
+    pub fn missile_speed(self, info: &MobjInfo) -> f32 {
+        let fast = matches!(info.id, "BRUISERSHOT" | "HEADSHOT" | "TROOPSHOT");
+        if self.fast_monsters && fast {
+            FAST_MISSILE_SPEED as f32
+        } else {
+            info.speed as f32
+        }
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs;
+
+    #[test]
+    fn nightmare_is_fast_and_the_rest_are_not() {
+        let fast = GameRules::for_skill(Skill::Nightmare);
+        let slow = GameRules::for_skill(Skill::Hard);
+        assert_eq!(slow, GameRules::VANILLA);
+        assert_eq!(GameRules::from_bits(fast.bits()), fast);
+
+        let defs = Definitions::shared_builtin();
+        assert_eq!(fast.state_tics(&defs, State::SARG_ATK1), 4);
+        assert_eq!(slow.state_tics(&defs, State::SARG_ATK1), 8);
+        assert_eq!(
+            fast.state_tics(&defs, State::SARG_DIE1),
+            State::SARG_DIE1.tics()
+        );
+        assert_eq!(
+            fast.state_tics(&defs, State::POSS_RUN1),
+            State::POSS_RUN1.tics()
+        );
+
+        let imp_ball = defs::by_id("TROOPSHOT").unwrap();
+        assert_eq!(slow.missile_speed(imp_ball), 10.0);
+        assert_eq!(fast.missile_speed(imp_ball), 20.0);
+        let rocket = defs::by_id("ROCKET").unwrap();
+        assert_eq!(fast.missile_speed(rocket), rocket.speed as f32);
+
+        let respawn = GameRules {
+            respawn_monsters: true,
+            ..GameRules::VANILLA
+        };
+        assert_eq!(slow.union(respawn), respawn);
+    }
+}
@@ -0,0 +1,1391 @@
+//! Savegames.
+//!
+//! A save is a snapshot of everything that diverges from the WAD while the
+//! level runs: every mobj, the current sector heights / light levels /
+//! specials, the line specials triggers have used up, which switches are
+//! on and the ones about to pop back, the running light effects, ceiling
+//! movers, lifts, doors and floors, the RNG index and the gametic.  Static data (geometry, `MobjInfo`, states)
+//! is *not* stored – classes are written by their `MobjInfo::id` and states
+//! by index, so a save rebinds to the compiled-in tables on load.
+//!
+//! On-disk layout (little-endian):
+//!
+//! ```text
+//! "YDSV"  version:u32  map:[u8;8]  gametic:u32  rng:u8  skill:u8  rules:u8
+//! total_kills total_items total_secrets:i32
+//! sectors:u32 × { floor:f32  ceil:f32  light:f32  special:i16 }
+//! lines:u32 × special:u16
+//! switches:u32 × { sidedef:u32  part:u8  on:u8 }
+//! buttons:u32 × { sidedef:u32  part:u8  on:u8  timer:i32  x y:f32 }
+//! lights:u32 × { sector:u16  kind:u8  a:i32  b:i32  count:i32  min:i32  max:i32 }
+//! ceilings:u32 × { sector:u16  kind:u8  bottom top speed:f32  crush:u8
+//!                  direction old_direction:i8  tag:u16 }
+//! plats:u32 × { sector:u16  kind:u8  speed low high:f32  wait count:i32
+//!               status old_status:u8  crush:u8  tag:u16 }
+//! doors:u32 × { sector:u16  kind:u8  top speed:f32  direction:i8
+//!               top_wait top_countdown:i32 }
+//! floors:u32 × { sector:u16  kind:u8  dest speed:f32  direction:i8  crush:u8 }
+//! mobjs:u32  player:i32
+//! mobjs × { id_len:u8 id:[u8]  x y z:f32  vx vy vz:f32  angle:u32 (BAM)
+//!           state:u32  tics:i32  flags:u32  health:i32
+//!           spot:u8  if spot: { type:u16  x y:f32  angle:u32  deaf:u8 } }
+//! if player ≥ 0:
+//!   health armor:i32  armor_type:u8  ammo:[i32;4]  max_ammo:[i32;4]
+//!   backpack:u8  keys:u8  powers:[i32;6]  bonus damage:i32
+//!   kills items secrets:i32
+//!   owned:[u8;9]  ready:u8  pending:u8 (0xFF = none)
+//!   psprites:2 × { state:u32  tics:i32  sx sy:f32 }  refire:i32
+//!   attack_down:u8
+//! session: total_tics:u32  maps:u32 ×
+//!   { map:[u8;8]  tics:u32  kills items secrets:i32
+//!     total_kills total_items total_secrets:i32  seen:u32 × u64 }
+//! ```
+
+use byteorder::{LittleEndian as LE, ReadBytesExt, WriteBytesExt};
+use glam::{Vec2, Vec3};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::Arc,
+};
+use thiserror::Error;
+
+use super::ceilings::{Ceiling, CeilingKind};
+use super::doors::{Door, DoorKind};
+use super::floors::{Floor, FloorKind};
+use super::lights::{Light, LightKind};
+use super::plats::{PlatKind, PlatStatus, Platform};
+use super::spacial::p_set_thing_position;
+use super::switches::{self, Button, SidePart, SwitchList};
+use super::weapons::NUM_WEAPONS;
+use super::{
+    ActorFlags, Angle, Animation, Class, GameRules, GameSession, Health, Keys, LevelProgress,
+    LevelTotals, PlayerStatus, Position, PrevPosition, Rng, Skill, SpawnPoint, Subsector,
+    TicRunner, Velocity, WeaponType, Weapons,
+};
+use crate::defs::{Definitions, MobjFlags};
+use crate::world::{Level, LineSet, SidedefId, Thing};
+
+const MAGIC: &[u8; 4] = b"YDSV";
+
+/// Bumped whenever the layout above changes.
+pub const SAVE_VERSION: u32 = 15;
+
+/*──────────────────────────── Error type ───────────────────────────*/
+
+#[derive(Error, Debug)]
+pub enum SaveError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("not a savegame")]
+    BadMagic,
+
+    #[error("savegame version {found} unsupported (engine is {SAVE_VERSION})")]
+    Version { found: u32 },
+
+    #[error("unknown skill {0}")]
+    BadSkill(u8),
+
+    #[error("savegame is for {found}, but {expected} is loaded")]
+    WrongLevel { expected: String, found: String },
+
+    #[error("savegame has {found} sectors, level has {expected}")]
+    SectorCount { expected: usize, found: usize },
+
+    #[error("savegame has {found} lines, level has {expected}")]
+    LineCount { expected: usize, found: usize },
+
+    #[error("switch on missing sidedef {0}")]
+    SwitchSidedef(u32),
+
+    #[error("unknown sidedef part {0}")]
+    BadSidePart(u8),
+
+    #[error("unknown mobj class {0:?}")]
+    UnknownClass(String),
+
+    #[error("state index {0} out of range")]
+    BadState(u32),
+
+    #[error("unknown light effect {0}")]
+    BadLight(u8),
+
+    #[error("light effect for missing sector {0}")]
+    LightSector(u16),
+
+    #[error("unknown ceiling mover {0}")]
+    BadCeiling(u8),
+
+    #[error("ceiling mover for missing sector {0}")]
+    CeilingSector(u16),
+
+    #[error("unknown lift {0}")]
+    BadPlat(u8),
+
+    #[error("unknown lift status {0}")]
+    BadPlatStatus(u8),
+
+    #[error("lift for missing sector {0}")]
+    PlatSector(u16),
+
+    #[error("unknown door {0}")]
+    BadDoor(u8),
+
+    #[error("door for missing sector {0}")]
+    DoorSector(u16),
+
+    #[error("unknown floor mover {0}")]
+    BadFloor(u8),
+
+    #[error("floor mover for missing sector {0}")]
+    FloorSector(u16),
+
+    #[error("unknown weapon {0}")]
+    BadWeapon(u8),
+}
+
+/*──────────────────────────── Public API ───────────────────────────*/
+
+/// Write the current game to `path`.
+pub fn save_game<P: AsRef<Path>>(
+    path: P,
+    sim: &TicRunner,
+    level: &Level,
+    session: &GameSession,
+) -> Result<(), SaveError> {
+    let mut w = BufWriter::new(File::create(path)?);
+    write_game(&mut w, sim, level, session)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// Restore a game saved with [`save_game`].
+///
+/// `level` must be the same map freshly loaded from the WAD; its sectors,
+/// line specials and switch textures are overwritten with the saved ones.
+/// `switches` are the level's SW1↔SW2 pairs, which the restored sim keeps,
+/// and `defs` the definitions it runs on.  A save that doesn't read to
+/// the end leaves `level` as it was.
+pub fn load_game<P: AsRef<Path>>(
+    path: P,
+    level: &mut Level,
+    switches: &SwitchList,
+    defs: Arc<Definitions>,
+) -> Result<(TicRunner, GameSession), SaveError> {
+    let mut r = BufReader::new(File::open(path)?);
+    read_game(&mut r, level, switches, defs)
+}
+
+/// Serialize `sim`, the mutable parts of `level` and `session` into `w`.
+pub fn write_game<W: Write>(
+    w: &mut W,
+    sim: &TicRunner,
+    level: &Level,
+    session: &GameSession,
+) -> Result<(), SaveError> {
+    /* header */
+    w.write_all(MAGIC)?;
+    w.write_u32::<LE>(SAVE_VERSION)?;
+    write_name8(w, &level.name)?;
+    w.write_u32::<LE>(sim.gametic())?;
+    w.write_u8(sim.rng().index())?;
+    w.write_u8(sim.skill().index())?;
+    w.write_u8(sim.rules().bits())?;
+    let totals = sim.totals();
+    w.write_i32::<LE>(totals.kills)?;
+    w.write_i32::<LE>(totals.items)?;
+    w.write_i32::<LE>(totals.secrets)?;
+
+    /* sectors */
+    w.write_u32::<LE>(level.sectors.len() as u32)?;
+    for s in &level.sectors {
+        w.write_f32::<LE>(s.floor_h)?;
+        w.write_f32::<LE>(s.ceil_h)?;
+        w.write_f32::<LE>(s.light)?;
+        w.write_i16::<LE>(s.special)?;
+    }
+
+    /* line specials */
+    w.write_u32::<LE>(level.linedefs.len() as u32)?;
+    for l in &level.linedefs {
+        w.write_u16::<LE>(l.special)?;
+    }
+
+    /* switch textures, on or off */
+    let switches = sim.switch_list();
+    let mut on = Vec::new();
+    for (sd, side) in level.sidedefs.iter().enumerate() {
+        for (part, tex) in SidePart::ALL
+            .into_iter()
+            .zip([side.upper, side.middle, side.lower])
+        {
+            if let Some(is_on) = switches.is_on(tex) {
+                on.push((sd as u32, part, is_on));
+            }
+        }
+    }
+    w.write_u32::<LE>(on.len() as u32)?;
+    for (sd, part, is_on) in on {
+        w.write_u32::<LE>(sd)?;
+        w.write_u8(side_part_byte(part))?;
+        w.write_u8(is_on as u8)?;
+    }
+
+    /* switches about to pop back */
+    w.write_u32::<LE>(sim.buttons().len() as u32)?;
+    for b in sim.buttons() {
+        w.write_u32::<LE>(b.sidedef.into())?;
+        w.write_u8(side_part_byte(b.part))?;
+        w.write_u8(switches.is_on(b.texture).unwrap_or(false) as u8)?;
+        w.write_i32::<LE>(b.timer)?;
+        w.write_f32::<LE>(b.origin.x)?;
+        w.write_f32::<LE>(b.origin.y)?;
+    }
+
+    /* light effects */
+    w.write_u32::<LE>(sim.lights().len() as u32)?;
+    for l in sim.lights() {
+        let (kind, a, b) = match l.kind {
+            LightKind::Flash { max_time, min_time } => (0, max_time, min_time),
+            LightKind::Strobe {
+                dark_time,
+                bright_time,
+            } => (1, dark_time, bright_time),
+            LightKind::Glow { direction } => (2, direction, 0),
+            LightKind::Flicker => (3, 0, 0),
+        };
+        w.write_u16::<LE>(l.sector)?;
+        w.write_u8(kind)?;
+        w.write_i32::<LE>(a)?;
+        w.write_i32::<LE>(b)?;
+        w.write_i32::<LE>(l.count)?;
+        w.write_i32::<LE>(l.min)?;
+        w.write_i32::<LE>(l.max)?;
+    }
+
+    /* ceiling movers */
+    w.write_u32::<LE>(sim.ceilings().len() as u32)?;
+    for c in sim.ceilings() {
+        w.write_u16::<LE>(c.sector)?;
+        w.write_u8(match c.kind {
+            CeilingKind::CrushAndRaise => 0,
+            CeilingKind::FastCrushAndRaise => 1,
+        })?;
+        w.write_f32::<LE>(c.bottom)?;
+        w.write_f32::<LE>(c.top)?;
+        w.write_f32::<LE>(c.speed)?;
+        w.write_u8(c.crush as u8)?;
+        w.write_i8(c.direction as i8)?;
+        w.write_i8(c.old_direction as i8)?;
+        w.write_u16::<LE>(c.tag)?;
+    }
+
+    /* lifts */
+    w.write_u32::<LE>(sim.plats().len() as u32)?;
+    for p in sim.plats() {
+        w.write_u16::<LE>(p.sector)?;
+        w.write_u8(match p.kind {
+            PlatKind::PerpetualRaise => 0,
+            PlatKind::DownWaitUpStay => 1,
+            PlatKind::BlazeDwus => 2,
+        })?;
+        w.write_f32::<LE>(p.speed)?;
+        w.write_f32::<LE>(p.low)?;
+        w.write_f32::<LE>(p.high)?;
+        w.write_i32::<LE>(p.wait)?;
+        w.write_i32::<LE>(p.count)?;
+        w.write_u8(plat_status_byte(p.status))?;
+        w.write_u8(plat_status_byte(p.old_status))?;
+        w.write_u8(p.crush as u8)?;
+        w.write_u16::<LE>(p.tag)?;
+    }
+
+    /* doors */
+    w.write_u32::<LE>(sim.doors().len() as u32)?;
+    for d in sim.doors() {
+        w.write_u16::<LE>(d.sector)?;
+        w.write_u8(DOOR_KINDS.iter().position(|&k| k == d.kind).unwrap() as u8)?;
+        w.write_f32::<LE>(d.top)?;
+        w.write_f32::<LE>(d.speed)?;
+        w.write_i8(d.direction as i8)?;
+        w.write_i32::<LE>(d.top_wait)?;
+        w.write_i32::<LE>(d.top_countdown)?;
+    }
+
+    /* floors */
+    w.write_u32::<LE>(sim.floors().len() as u32)?;
+    for f in sim.floors() {
+        w.write_u16::<LE>(f.sector)?;
+        w.write_u8(FLOOR_KINDS.iter().position(|&k| k == f.kind).unwrap() as u8)?;
+        w.write_f32::<LE>(f.dest)?;
+        w.write_f32::<LE>(f.speed)?;
+        w.write_i8(f.direction as i8)?;
+        w.write_u8(f.crush as u8)?;
+    }
+
+    /* mobjs */
+    let mut q = sim.world().query::<(
+        &Position,
+        &Velocity,
+        &Angle,
+        &Animation,
+        &ActorFlags,
+        &Class,
+        &Health,
+    )>();
+    let mobjs: Vec<_> = q.iter().collect();
+
+    let player = sim
+        .player()
+        .and_then(|p| mobjs.iter().position(|(e, _)| *e == p))
+        .map_or(-1, |i| i as i32);
+
+    w.write_u32::<LE>(mobjs.len() as u32)?;
+    w.write_i32::<LE>(player)?;
+
+    for (ent, (pos, vel, ang, anim, flags, class, health)) in mobjs {
+        let id = class.0.id.as_bytes();
+        w.write_u8(id.len() as u8)?;
+        w.write_all(id)?;
+        w.write_f32::<LE>(pos.0.x)?;
+        w.write_f32::<LE>(pos.0.y)?;
+        w.write_f32::<LE>(pos.1)?;
+        w.write_f32::<LE>(vel.0.x)?;
+        w.write_f32::<LE>(vel.0.y)?;
+        w.write_f32::<LE>(vel.0.z)?;
+        w.write_u32::<LE>(ang.0)?;
+        w.write_u32::<LE>(anim.state as u32)?;
+        w.write_i32::<LE>(anim.tics)?;
+        w.write_u32::<LE>(flags.0.bits())?;
+        w.write_i32::<LE>(health.0)?;
+        let spot = sim.world().get::<&SpawnPoint>(ent).ok();
+        write_spawn_point(w, spot.as_ref().map(|s| &s.0))?;
+    }
+
+    /* player inventory */
+    if let Some(p) = sim.player() {
+        if player >= 0 {
+            let st = sim
+                .world()
+                .get::<&PlayerStatus>(p)
+                .map_or_else(|_| PlayerStatus::default(), |s| *s);
+            write_status(w, &st)?;
+            let wp = sim
+                .world()
+                .get::<&Weapons>(p)
+                .map_or_else(|_| Weapons::default(), |w| *w);
+            write_weapons(w, &wp)?;
+        }
+    }
+
+    write_session(w, session)?;
+    Ok(())
+}
+
+/// Rebuild a `TicRunner` (world, `ThingGrid`, subsector links) and the
+/// session from `r`; see [`load_game`].
+pub fn read_game<R: Read>(
+    r: &mut R,
+    level: &mut Level,
+    switches: &SwitchList,
+    defs: Arc<Definitions>,
+) -> Result<(TicRunner, GameSession), SaveError> {
+    /* header */
+    let mut magic = [0u8; 4];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(SaveError::BadMagic);
+    }
+    let found = r.read_u32::<LE>()?;
+    if found != SAVE_VERSION {
+        return Err(SaveError::Version { found });
+    }
+    let map = read_name8(r)?;
+    if !map.eq_ignore_ascii_case(&level.name) {
+        return Err(SaveError::WrongLevel {
+            expected: level.name.clone(),
+            found: map,
+        });
+    }
+    let gametic = r.read_u32::<LE>()?;
+    let rng = r.read_u8()?;
+    let skill = r.read_u8()?;
+    let skill = Skill::from_index(skill).ok_or(SaveError::BadSkill(skill))?;
+    let rules = GameRules::from_bits(r.read_u8()?);
+    let totals = LevelTotals {
+        kills: r.read_i32::<LE>()?,
+        items: r.read_i32::<LE>()?,
+        secrets: r.read_i32::<LE>()?,
+    };
+
+    /* sectors */
+    let n_sectors = r.read_u32::<LE>()? as usize;
+    if n_sectors != level.sectors.len() {
+        return Err(SaveError::SectorCount {
+            expected: level.sectors.len(),
+            found: n_sectors,
+        });
+    }
+    // nothing is put into `level` until the whole save has read cleanly
+    let mut sectors = Vec::with_capacity(n_sectors);
+    for _ in 0..n_sectors {
+        // floor, ceiling, light, special
+        sectors.push((
+            r.read_f32::<LE>()?,
+            r.read_f32::<LE>()?,
+            r.read_f32::<LE>()?,
+            r.read_i16::<LE>()?,
+        ));
+    }
+
+    /* line specials */
+    let n_lines = r.read_u32::<LE>()? as usize;
+    if n_lines != level.linedefs.len() {
+        return Err(SaveError::LineCount {
+            expected: level.linedefs.len(),
+            found: n_lines,
+        });
+    }
+    let mut line_specials = Vec::with_capacity(n_lines);
+    for _ in 0..n_lines {
+        line_specials.push(r.read_u16::<LE>()?);
+    }
+
+    /* switch textures */
+    let n_switches = r.read_u32::<LE>()? as usize;
+    let mut switch_states = Vec::with_capacity(n_switches.min(level.sidedefs.len() * 3));
+    for _ in 0..n_switches {
+        let (sd, part) = read_side_part(r, level)?;
+        switch_states.push((sd, part, r.read_u8()? != 0));
+    }
+
+    /* buttons */
+    let n_buttons = r.read_u32::<LE>()? as usize;
+    let mut buttons = Vec::with_capacity(n_buttons.min(1024));
+    for _ in 0..n_buttons {
+        let (sidedef, part) = read_side_part(r, level)?;
+        let on = r.read_u8()? != 0;
+        let timer = r.read_i32::<LE>()?;
+        let origin = Vec2::new(r.read_f32::<LE>()?, r.read_f32::<LE>()?);
+        buttons.push((sidedef, part, on, timer, origin));
+    }
+
+    /* light effects */
+    let n_lights = r.read_u32::<LE>()? as usize;
+    let mut lights = Vec::with_capacity(n_lights.min(level.sectors.len()));
+    for _ in 0..n_lights {
+        let sector = r.read_u16::<LE>()?;
+        if sector as usize >= level.sectors.len() {
+            return Err(SaveError::LightSector(sector));
+        }
+        let kind = r.read_u8()?;
+        let (a, b) = (r.read_i32::<LE>()?, r.read_i32::<LE>()?);
+        let kind = match kind {
+            0 => LightKind::Flash {
+                max_time: a,
+                min_time: b,
+            },
+            1 => LightKind::Strobe {
+                dark_time: a,
+                bright_time: b,
+            },
+            2 => LightKind::Glow { direction: a },
+            3 => LightKind::Flicker,
+            k => return Err(SaveError::BadLight(k)),
+        };
+        lights.push(Light {
+            sector,
+            kind,
+            count: r.read_i32::<LE>()?,
+            min: r.read_i32::<LE>()?,
+            max: r.read_i32::<LE>()?,
+        });
+    }
+
+    /* ceiling movers */
+    let n_ceilings = r.read_u32::<LE>()? as usize;
+    let mut ceilings = Vec::with_capacity(n_ceilings.min(level.sectors.len()));
+    for _ in 0..n_ceilings {
+        let sector = r.read_u16::<LE>()?;
+        if sector as usize >= level.sectors.len() {
+            return Err(SaveError::CeilingSector(sector));
+        }
+        let kind = match r.read_u8()? {
+            0 => CeilingKind::CrushAndRaise,
+            1 => CeilingKind::FastCrushAndRaise,
+            k => return Err(SaveError::BadCeiling(k)),
+        };
+        ceilings.push(Ceiling {
+            sector,
+            kind,
+            bottom: r.read_f32::<LE>()?,
+            top: r.read_f32::<LE>()?,
+            speed: r.read_f32::<LE>()?,
+            crush: r.read_u8()? != 0,
+            direction: r.read_i8()?.into(),
+            old_direction: r.read_i8()?.into(),
+            tag: r.read_u16::<LE>()?,
+        });
+    }
+
+    /* lifts */
+    let n_plats = r.read_u32::<LE>()? as usize;
+    let mut plats = Vec::with_capacity(n_plats.min(level.sectors.len()));
+    for _ in 0..n_plats {
+        let sector = r.read_u16::<LE>()?;
+        if sector as usize >= level.sectors.len() {
+            return Err(SaveError::PlatSector(sector));
+        }
+        let kind = match r.read_u8()? {
+            0 => PlatKind::PerpetualRaise,
+            1 => PlatKind::DownWaitUpStay,
+            2 => PlatKind::BlazeDwus,
+            k => return Err(SaveError::BadPlat(k)),
+        };
+        plats.push(Platform {
+            sector,
+            kind,
+            speed: r.read_f32::<LE>()?,
+            low: r.read_f32::<LE>()?,
+            high: r.read_f32::<LE>()?,
+            wait: r.read_i32::<LE>()?,
+            count: r.read_i32::<LE>()?,
+            status: read_plat_status(r)?,
+            old_status: read_plat_status(r)?,
+            crush: r.read_u8()? != 0,
+            tag: r.read_u16::<LE>()?,
+        });
+    }
+
+    /* doors */
+    let n_doors = r.read_u32::<LE>()? as usize;
+    let mut doors = Vec::with_capacity(n_doors.min(level.sectors.len()));
+    for _ in 0..n_doors {
+        let sector = r.read_u16::<LE>()?;
+        if sector as usize >= level.sectors.len() {
+            return Err(SaveError::DoorSector(sector));
+        }
+        let k = r.read_u8()?;
+        let kind = *DOOR_KINDS.get(k as usize).ok_or(SaveError::BadDoor(k))?;
+        doors.push(Door {
+            sector,
+            kind,
+            top: r.read_f32::<LE>()?,
+            speed: r.read_f32::<LE>()?,
+            direction: r.read_i8()?.into(),
+            top_wait: r.read_i32::<LE>()?,
+            top_countdown: r.read_i32::<LE>()?,
+        });
+    }
+
+    /* floors */
+    let n_floors = r.read_u32::<LE>()? as usize;
+    let mut floors = Vec::with_capacity(n_floors.min(level.sectors.len()));
+    for _ in 0..n_floors {
+        let sector = r.read_u16::<LE>()?;
+        if sector as usize >= level.sectors.len() {
+            return Err(SaveError::FloorSector(sector));
+        }
+        let k = r.read_u8()?;
+        let kind = *FLOOR_KINDS.get(k as usize).ok_or(SaveError::BadFloor(k))?;
+        floors.push(Floor {
+            sector,
+            kind,
+            dest: r.read_f32::<LE>()?,
+            speed: r.read_f32::<LE>()?,
+            direction: r.read_i8()?.into(),
+            crush: r.read_u8()? != 0,
+        });
+    }
+
+    let mut sim = TicRunner::new(level);
+    sim.set_gametic(gametic);
+    sim.set_rng(Rng::new(rng));
+    sim.set_skill(skill);
+    sim.set_rules(rules);
+    sim.set_totals(totals);
+    sim.set_lights(lights);
+    sim.set_ceilings(ceilings);
+    sim.set_plats(plats);
+    sim.set_doors(doors);
+    sim.set_floors(floors);
+    sim.set_switch_list(switches.clone());
+    sim.set_definitions(defs.clone());
+
+    /* mobjs */
+    let n_mobjs = r.read_u32::<LE>()? as usize;
+    let player = r.read_i32::<LE>()?;
+
+    for i in 0..n_mobjs {
+        let len = r.read_u8()? as usize;
+        let mut id = vec![0u8; len];
+        r.read_exact(&mut id)?;
+        let id = String::from_utf8_lossy(&id).into_owned();
+        let info = *defs.by_id(&id).ok_or(SaveError::UnknownClass(id))?;
+
+        let pos = Position(
+            Vec2::new(r.read_f32::<LE>()?, r.read_f32::<LE>()?),
+            r.read_f32::<LE>()?,
+        );
+        let vel = Velocity(Vec3::new(
+            r.read_f32::<LE>()?,
+            r.read_f32::<LE>()?,
+            r.read_f32::<LE>()?,
+        ));
+        let ang = Angle(r.read_u32::<LE>()?);
+        let state_idx = r.read_u32::<LE>()?;
+        let state = defs
+            .states
+            .get(state_idx as usize)
+            .ok_or(SaveError::BadState(state_idx))?
+            .state;
+        let anim = Animation {
+            state,
+            tics: r.read_i32::<LE>()?,
+        };
+        let flags = ActorFlags(MobjFlags::from_bits_retain(r.read_u32::<LE>()?));
+        let health = Health(r.read_i32::<LE>()?);
+        let class = Class(info);
+        let spot = read_spawn_point(r, level)?;
+
+        let ss = level.locate_subsector(pos.0);
+        let ent = sim.world_mut().spawn((
+            flags,
+            pos,
+            PrevPosition(pos, ang),
+            vel,
+            ang,
+            Subsector(ss),
+            anim,
+            class,
+            health,
+        ));
+
+        p_set_thing_position(sim.thing_grid_mut(), ent, &pos, &class, &flags, ss);
+        if let Some(spot) = spot {
+            sim.world_mut().insert_one(ent, SpawnPoint(spot)).ok();
+        }
+        if i as i32 == player {
+            sim.set_player(ent);
+        }
+    }
+
+    /* player inventory */
+    if let Some(p) = sim.player() {
+        let mut status = read_status(r)?;
+        // the view isn't saved; it starts over at eye height
+        if let Ok(fresh) = sim.world().get::<&PlayerStatus>(p) {
+            status.view_z = fresh.view_z;
+        }
+        let weapons = read_weapons(r, &defs)?;
+        sim.world_mut().insert(p, (status, weapons)).ok();
+    }
+
+    let session = read_session(r)?;
+
+    /* all read: now the level */
+    for (s, &(floor_h, ceil_h, light, special)) in level.sectors.iter_mut().zip(&sectors) {
+        (s.floor_h, s.ceil_h, s.light, s.special) = (floor_h, ceil_h, light, special);
+    }
+    for (l, &special) in level.linedefs.iter_mut().zip(&line_specials) {
+        l.special = special;
+    }
+    for (sd, part, on) in switch_states {
+        let tex = switches::side_texture(level, sd, part);
+        *tex = switches.with_state(*tex, on);
+    }
+    let buttons = buttons
+        .into_iter()
+        .map(|(sidedef, part, on, timer, origin)| Button {
+            sidedef,
+            part,
+            texture: switches.with_state(*switches::side_texture(level, sidedef, part), on),
+            timer,
+            origin,
+        })
+        .collect();
+    sim.set_buttons(buttons);
+    Ok((sim, session))
+}
+
+/*──────────────────────────── Helpers ──────────────────────────────*/
+
+fn write_spawn_point<W: Write>(w: &mut W, spot: Option<&Thing>) -> io::Result<()> {
+    let Some(t) = spot else {
+        return w.write_u8(0);
+    };
+    w.write_u8(1)?;
+    w.write_u16::<LE>(t.type_id)?;
+    w.write_f32::<LE>(t.pos.x)?;
+    w.write_f32::<LE>(t.pos.y)?;
+    w.write_u32::<LE>(t.angle.0)?;
+    w.write_u8(t.is_deaf as u8)
+}
+
+/// The map spot a mobj came from, if it has one; only what respawning
+/// needs of the map thing comes back.
+fn read_spawn_point<R: Read>(r: &mut R, level: &Level) -> io::Result<Option<Thing>> {
+    if r.read_u8()? == 0 {
+        return Ok(None);
+    }
+    let type_id = r.read_u16::<LE>()?;
+    let pos = Vec2::new(r.read_f32::<LE>()?, r.read_f32::<LE>()?);
+    let angle = Angle(r.read_u32::<LE>()?);
+    let is_deaf = r.read_u8()? != 0;
+    Ok(Some(Thing {
+        pos,
+        angle,
+        type_id,
+        skills: 0,
+        is_deaf,
+        multiplayer: false,
+        sub_sector: level.locate_subsector(pos),
+    }))
+}
+
+fn write_status<W: Write>(w: &mut W, st: &PlayerStatus) -> io::Result<()> {
+    w.write_i32::<LE>(st.health)?;
+    w.write_i32::<LE>(st.armor)?;
+    w.write_u8(st.armor_type)?;
+    for &n in st.ammo.iter().chain(&st.max_ammo) {
+        w.write_i32::<LE>(n)?;
+    }
+    w.write_u8(st.backpack as u8)?;
+    w.write_u8(st.keys.bits())?;
+    for &t in &st.powers {
+        w.write_i32::<LE>(t)?;
+    }
+    w.write_i32::<LE>(st.bonus_count)?;
+    w.write_i32::<LE>(st.damage_count)?;
+    w.write_i32::<LE>(st.kill_count)?;
+    w.write_i32::<LE>(st.item_count)?;
+    w.write_i32::<LE>(st.secret_count)
+}
+
+fn read_status<R: Read>(r: &mut R) -> io::Result<PlayerStatus> {
+    let mut st = PlayerStatus {
+        health: r.read_i32::<LE>()?,
+        armor: r.read_i32::<LE>()?,
+        armor_type: r.read_u8()?,
+        ..PlayerStatus::default()
+    };
+    for n in st.ammo.iter_mut().chain(&mut st.max_ammo) {
+        *n = r.read_i32::<LE>()?;
+    }
+    st.backpack = r.read_u8()? != 0;
+    st.keys = Keys::from_bits_retain(r.read_u8()?);
+    for t in &mut st.powers {
+        *t = r.read_i32::<LE>()?;
+    }
+    st.bonus_count = r.read_i32::<LE>()?;
+    st.damage_count = r.read_i32::<LE>()?;
+    st.kill_count = r.read_i32::<LE>()?;
+    st.item_count = r.read_i32::<LE>()?;
+    st.secret_count = r.read_i32::<LE>()?;
+    Ok(st)
+}
+
+fn write_weapons<W: Write>(w: &mut W, wp: &Weapons) -> io::Result<()> {
+    for &owned in &wp.owned {
+        w.write_u8(owned as u8)?;
+    }
+    w.write_u8(wp.ready as u8)?;
+    w.write_u8(wp.pending.map_or(0xFF, |p| p as u8))?;
+    for psp in &wp.psprites {
+        w.write_u32::<LE>(psp.state as u32)?;
+        w.write_i32::<LE>(psp.tics)?;
+        w.write_f32::<LE>(psp.sx)?;
+        w.write_f32::<LE>(psp.sy)?;
+    }
+    w.write_i32::<LE>(wp.refire)?;
+    w.write_u8(wp.attack_down as u8)
+}
+
+fn read_weapons<R: Read>(r: &mut R, defs: &Definitions) -> Result<Weapons, SaveError> {
+    let weapon = |i: u8| WeaponType::from_index(i).ok_or(SaveError::BadWeapon(i));
+    let mut owned = [false; NUM_WEAPONS];
+    for o in &mut owned {
+        *o = r.read_u8()? != 0;
+    }
+    let mut wp = Weapons::new(defs, owned, weapon(r.read_u8()?)?);
+    wp.pending = match r.read_u8()? {
+        0xFF => None,
+        i => Some(weapon(i)?),
+    };
+    for psp in &mut wp.psprites {
+        let state_idx = r.read_u32::<LE>()?;
+        psp.state = defs
+            .states
+            .get(state_idx as usize)
+            .ok_or(SaveError::BadState(state_idx))?
+            .state;
+        psp.tics = r.read_i32::<LE>()?;
+        psp.sx = r.read_f32::<LE>()?;
+        psp.sy = r.read_f32::<LE>()?;
+    }
+    wp.refire = r.read_i32::<LE>()?;
+    wp.attack_down = r.read_u8()? != 0;
+    Ok(wp)
+}
+
+fn write_session<W: Write>(w: &mut W, session: &GameSession) -> io::Result<()> {
+    w.write_u32::<LE>(session.total_tics)?;
+    w.write_u32::<LE>(session.levels().count() as u32)?;
+    for (map, p) in session.levels() {
+        write_name8(w, map)?;
+        w.write_u32::<LE>(p.tics)?;
+        for n in [p.kills, p.items, p.secrets] {
+            w.write_i32::<LE>(n)?;
+        }
+        for n in [p.totals.kills, p.totals.items, p.totals.secrets] {
+            w.write_i32::<LE>(n)?;
+        }
+        w.write_u32::<LE>(p.seen.words().len() as u32)?;
+        for &word in p.seen.words() {
+            w.write_u64::<LE>(word)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_session<R: Read>(r: &mut R) -> io::Result<GameSession> {
+    let mut session = GameSession::default();
+    session.total_tics = r.read_u32::<LE>()?;
+    for _ in 0..r.read_u32::<LE>()? {
+        let map = read_name8(r)?;
+        // fields are read in the order they're written
+        let mut progress = LevelProgress {
+            tics: r.read_u32::<LE>()?,
+            kills: r.read_i32::<LE>()?,
+            items: r.read_i32::<LE>()?,
+            secrets: r.read_i32::<LE>()?,
+            totals: LevelTotals {
+                kills: r.read_i32::<LE>()?,
+                items: r.read_i32::<LE>()?,
+                secrets: r.read_i32::<LE>()?,
+            },
+            seen: LineSet::default(),
+        };
+        let words = r.read_u32::<LE>()?;
+        progress.seen = LineSet::from_words(
+            (0..words)
+                .map(|_| r.read_u64::<LE>())
+                .collect::<io::Result<_>>()?,
+        );
+        *session.level_mut(&map) = progress;
+    }
+    Ok(session)
+}
+
+fn write_name8<W: Write>(w: &mut W, name: &str) -> io::Result<()> {
+    let mut buf = [0u8; 8];
+    for (dst, src) in buf.iter_mut().zip(name.bytes()) {
+        *dst = src;
+    }
+    w.write_all(&buf)
+}
+
+fn read_name8<R: Read>(r: &mut R) -> io::Result<String> {
+    let mut buf = [0u8; 8];
+    r.read_exact(&mut buf)?;
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(String::from_utf8_lossy(&buf[..end]).into_owned())
+}
+
+/// Door kinds by their byte in a save.
+const DOOR_KINDS: [DoorKind; 8] = [
+    DoorKind::Normal,
+    DoorKind::Close30ThenOpen,
+    DoorKind::Close,
+    DoorKind::Open,
+    DoorKind::RaiseIn5Mins,
+    DoorKind::BlazeRaise,
+    DoorKind::BlazeOpen,
+    DoorKind::BlazeClose,
+];
+
+/// Floor kinds by their byte in a save.
+const FLOOR_KINDS: [FloorKind; 9] = [
+    FloorKind::Lower,
+    FloorKind::LowerToLowest,
+    FloorKind::TurboLower,
+    FloorKind::Raise,
+    FloorKind::RaiseCrush,
+    FloorKind::RaiseToNearest,
+    FloorKind::RaiseTurbo,
+    FloorKind::Raise24,
+    FloorKind::Raise512,
+];
+
+fn side_part_byte(part: SidePart) -> u8 {
+    match part {
+        SidePart::Top => 0,
+        SidePart::Middle => 1,
+        SidePart::Bottom => 2,
+    }
+}
+
+/// A sidedef of `level` and one of its textures.
+fn read_side_part<R: Read>(r: &mut R, level: &Level) -> Result<(SidedefId, SidePart), SaveError> {
+    let sd = r.read_u32::<LE>()?;
+    if sd as usize >= level.sidedefs.len() {
+        return Err(SaveError::SwitchSidedef(sd));
+    }
+    let sd = sd as SidedefId;
+    let part = match r.read_u8()? {
+        0 => SidePart::Top,
+        1 => SidePart::Middle,
+        2 => SidePart::Bottom,
+        p => return Err(SaveError::BadSidePart(p)),
+    };
+    Ok((sd, part))
+}
+
+fn plat_status_byte(status: PlatStatus) -> u8 {
+    match status {
+        PlatStatus::Up => 0,
+        PlatStatus::Down => 1,
+        PlatStatus::Waiting => 2,
+        PlatStatus::InStasis => 3,
+    }
+}
+
+fn read_plat_status<R: Read>(r: &mut R) -> Result<PlatStatus, SaveError> {
+    Ok(match r.read_u8()? {
+        0 => PlatStatus::Up,
+        1 => PlatStatus::Down,
+        2 => PlatStatus::Waiting,
+        3 => PlatStatus::InStasis,
+        s => return Err(SaveError::BadPlatStatus(s)),
+    })
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{InputCmd, specials::SECRET_SECTOR},
+        wad::{Wad, load_level},
+        world::{TextureBank, testmap},
+    };
+    use std::path::PathBuf;
+
+    fn doom_wad() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join("doom.wad")
+    }
+
+    fn load_e1m1() -> Level {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = TextureBank::default_with_checker();
+        let mut level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        level.finalise_bsp();
+        level
+    }
+
+    /// Everything a save must preserve, in a comparable form.
+    fn snapshot(sim: &TicRunner, level: &Level) -> Vec<String> {
+        let mut out = vec![format!(
+            "tic {} rng {} {:?} player {:?} {:?}",
+            sim.gametic(),
+            sim.rng().index(),
+            sim.totals(),
+            sim.player()
+                .and_then(|p| sim.world().get::<&Position>(p).ok().map(|p| *p))
+                .map(|p| (p.0, p.1)),
+            sim.player()
+                .and_then(|p| sim.world().get::<&PlayerStatus>(p).ok().map(|s| *s))
+        )];
+        for s in &level.sectors {
+            out.push(format!(
+                "sector {} {} {} {}",
+                s.floor_h, s.ceil_h, s.light, s.special
+            ));
+        }
+        out.push(format!(
+            "lines {:?}",
+            level.linedefs.iter().map(|l| l.special).collect::<Vec<_>>()
+        ));
+        for sd in &level.sidedefs {
+            out.push(format!("side {} {} {}", sd.upper, sd.middle, sd.lower));
+        }
+        for b in sim.buttons() {
+            out.push(format!("{b:?}"));
+        }
+        for l in sim.lights() {
+            out.push(format!("{l:?}"));
+        }
+        for c in sim.ceilings() {
+            out.push(format!("{c:?}"));
+        }
+        for p in sim.plats() {
+            out.push(format!("{p:?}"));
+        }
+        for d in sim.doors() {
+            out.push(format!("{d:?}"));
+        }
+        for f in sim.floors() {
+            out.push(format!("{f:?}"));
+        }
+        let mut q = sim.world().query::<(
+            &Position,
+            &Velocity,
+            &Angle,
+            &Animation,
+            &ActorFlags,
+            &Class,
+            &Subsector,
+            &Health,
+        )>();
+        for (_, (pos, vel, ang, anim, flags, class, ss, hp)) in q.iter() {
+            out.push(format!(
+                "{} {:?} {} {:?} {} {:?} {} {:?} {} {}",
+                class.0.id, pos.0, pos.1, vel.0, ang.0, anim.state, anim.tics, flags.0, ss.0, hp.0
+            ));
+        }
+        out
+    }
+
+    #[test]
+    fn rejects_foreign_files() {
+        let mut level = load_e1m1();
+        let err = read_game(
+            &mut &b"YDMO\x01\0\0\0"[..],
+            &mut level,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, SaveError::BadMagic));
+
+        let err = read_game(
+            &mut &b"YDSV\x63\0\0\0"[..],
+            &mut level,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, SaveError::Version { found: 99 }));
+    }
+
+    #[test]
+    fn e1m1_roundtrip() {
+        let mut level = load_e1m1();
+        let mut sim = TicRunner::new(&level);
+        for thing in &level.things {
+            sim.spawn_map_thing(&level, thing);
+        }
+        let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+        let player = sim.spawn_mobj(
+            &level,
+            defs::by_id("PLAYER").unwrap(),
+            start.pos.x,
+            start.pos.y,
+            start.angle,
+            start.sub_sector,
+        );
+        sim.set_player(player);
+        sim.spawn_specials(&mut level);
+        assert!(!sim.lights().is_empty());
+
+        let walk = InputCmd {
+            forward: 1.0,
+            turn: 0.3,
+            ..Default::default()
+        };
+        for _ in 0..40 {
+            sim.run_tic(&mut level, walk);
+        }
+        level.sectors[0].floor_h += 8.0; // pretend a mover ran
+
+        let mut bytes = Vec::new();
+        write_game(&mut bytes, &sim, &level, &GameSession::default()).unwrap();
+        let before = snapshot(&sim, &level);
+
+        let mut fresh = load_e1m1();
+        let (restored, _) = read_game(
+            &mut bytes.as_slice(),
+            &mut fresh,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .unwrap();
+        assert_eq!(snapshot(&restored, &fresh), before);
+    }
+
+    #[test]
+    fn running_crusher_roundtrip() {
+        let open = testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        };
+        let mut bank = TextureBank::default_with_checker();
+        let sw1 = bank.insert("SW1STARG", Default::default()).unwrap();
+        let sw2 = bank.insert("SW2STARG", Default::default()).unwrap();
+        let switches = SwitchList::new(&bank);
+        // room A's west wall, an S1 switch pressed from inside; room B's
+        // walls a switch of their own
+        let map = || {
+            let mut level = testmap::three_rooms(open);
+            let west = level
+                .linedefs
+                .iter()
+                .position(|l| (l.v1, l.v2) == (0, 4))
+                .unwrap();
+            level.sectors[1].tag = 2;
+            level.linedefs[west].special = 49;
+            level.linedefs[west].tag = 2;
+            level.sidedefs[0].middle = sw1;
+            level.sidedefs[2].middle = sw1;
+            (level, west)
+        };
+        let (mut level, west) = map();
+
+        let mut sim = TicRunner::new(&level);
+        sim.set_switch_list(switches.clone());
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 40.0, 64.0, 3.2, 0);
+        sim.set_player(player);
+        let press = InputCmd {
+            use_act: true,
+            ..Default::default()
+        };
+        sim.run_tic(&mut level, press);
+        // and switching to the fist, gun halfway down
+        let punch = InputCmd {
+            weapon: Some(1),
+            ..Default::default()
+        };
+        for cmd in [punch].into_iter().chain([InputCmd::default(); 30]) {
+            sim.run_tic(&mut level, cmd);
+        }
+        assert_eq!(sim.ceilings().len(), 1);
+        assert_eq!(level.linedefs[west].special, 0, "used up");
+        assert_eq!(level.sidedefs[0].middle, sw2);
+        // room B's switch pressed a moment ago, about to pop back
+        level.sidedefs[2].middle = sw2;
+        sim.set_buttons(vec![Button {
+            sidedef: 2,
+            part: SidePart::Middle,
+            texture: sw1,
+            timer: 20,
+            origin: Vec2::new(200.0, 0.0),
+        }]);
+
+        // a map played before this one, and this one's automap so far
+        let mut session = GameSession::default();
+        session.seen_mut("E1M1").insert(70);
+        session.leave_level("E1M1", &sim);
+        session.seen_mut(&level.name).insert(2);
+
+        let mut bytes = Vec::new();
+        write_game(&mut bytes, &sim, &level, &session).unwrap();
+        let before = snapshot(&sim, &level);
+        let weapons = *sim.world().get::<&Weapons>(player).unwrap();
+
+        let (mut fresh, _) = map();
+        let (mut restored, saved) = read_game(
+            &mut bytes.as_slice(),
+            &mut fresh,
+            &switches,
+            Definitions::shared_builtin(),
+        )
+        .unwrap();
+        assert_eq!(snapshot(&restored, &fresh), before);
+        let player = restored.player().unwrap();
+        assert_eq!(*restored.world().get::<&Weapons>(player).unwrap(), weapons);
+        assert_eq!(saved.total_tics, 32);
+        assert!(saved.levels().eq(session.levels()));
+        assert!(saved.level("E1M1").unwrap().seen.contains(70));
+
+        // used switches stay used; the pending one still pops back
+        for _ in 0..20 {
+            restored.run_tic(&mut fresh, InputCmd::default());
+        }
+        assert_eq!(fresh.sidedefs[2].middle, sw1, "popped back");
+        assert_eq!(fresh.sidedefs[0].middle, sw2);
+    }
+
+    #[test]
+    fn running_door_and_floor_roundtrip() {
+        let open = testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        };
+        let mut level = testmap::three_rooms(open);
+        let mut sim = TicRunner::new(&level);
+        sim.set_doors(vec![Door {
+            sector: 1,
+            kind: DoorKind::Close30ThenOpen,
+            top: 0.0,
+            speed: 2.0,
+            direction: 0,
+            top_wait: 150,
+            top_countdown: 700,
+        }]);
+        sim.set_floors(vec![Floor {
+            sector: 2,
+            kind: FloorKind::Raise512,
+            dest: 64.0,
+            speed: 1.0,
+            direction: 1,
+            crush: false,
+        }]);
+        for _ in 0..10 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+
+        let mut bytes = Vec::new();
+        write_game(&mut bytes, &sim, &level, &GameSession::default()).unwrap();
+        let before = snapshot(&sim, &level);
+        let mut fresh = testmap::three_rooms(open);
+        let (restored, _) = read_game(
+            &mut bytes.as_slice(),
+            &mut fresh,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .unwrap();
+        assert_eq!(snapshot(&restored, &fresh), before);
+        assert_eq!(restored.doors()[0].top_countdown, 690);
+        assert_eq!(fresh.sectors[2].floor_h, 10.0);
+    }
+
+    #[test]
+    fn failed_loads_leave_the_level_alone() {
+        let map = || testmap::three_rooms(testmap::Middle::Wall);
+        let mut level = map();
+        level.sectors[0].special = SECRET_SECTOR;
+        level.sectors[1].floor_h += 24.0;
+        level.linedefs[0].special = 11;
+        let sim = TicRunner::new(&level);
+        let mut bytes = Vec::new();
+        write_game(&mut bytes, &sim, &level, &GameSession::default()).unwrap();
+
+        let fresh = map();
+        let state = |l: &Level| {
+            let sectors = l.sectors.iter().map(|s| (s.floor_h, s.special));
+            (sectors.collect::<Vec<_>>(), l.linedefs[0].special)
+        };
+        for len in 0..bytes.len() {
+            let mut target = map();
+            assert!(
+                read_game(
+                    &mut &bytes[..len],
+                    &mut target,
+                    &SwitchList::default(),
+                    Definitions::shared_builtin()
+                )
+                .is_err()
+            );
+            assert_eq!(
+                state(&target),
+                state(&fresh),
+                "cut at {len} of {}",
+                bytes.len()
+            );
+        }
+        let mut target = map();
+        read_game(
+            &mut bytes.as_slice(),
+            &mut target,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .unwrap();
+        assert_eq!(state(&target), state(&level));
+    }
+
+    #[test]
+    fn found_secrets_stay_found_after_a_load() {
+        let map = || {
+            let mut level = testmap::three_rooms(testmap::Middle::Wall);
+            level.sectors[0].special = SECRET_SECTOR;
+            level
+        };
+        let mut level = map();
+        let mut sim = TicRunner::new(&level);
+        sim.spawn_specials(&mut level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        sim.run_tic(&mut level, InputCmd::default());
+        let secrets = |sim: &TicRunner| {
+            let player = sim.player().unwrap();
+            sim.world()
+                .get::<&PlayerStatus>(player)
+                .unwrap()
+                .secret_count
+        };
+        assert_eq!(secrets(&sim), 1);
+
+        let mut bytes = Vec::new();
+        write_game(&mut bytes, &sim, &level, &GameSession::default()).unwrap();
+        let mut fresh = map();
+        let (mut restored, _) = read_game(
+            &mut bytes.as_slice(),
+            &mut fresh,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .unwrap();
+        assert_eq!(fresh.sectors[0].special, 0);
+        for _ in 0..10 {
+            restored.run_tic(&mut fresh, InputCmd::default());
+        }
+        assert_eq!(secrets(&restored), 1, "counted again");
+        assert_eq!(restored.totals().secrets, 1);
+    }
+
+    #[test]
+    fn nightmare_rules_and_map_spots_roundtrip() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        sim.set_skill(Skill::Nightmare);
+        sim.set_rules(GameRules::for_skill(Skill::Nightmare));
+        let pos = Vec2::new(64.0, 64.0);
+        let imp = sim
+            .spawn_map_thing(
+                &level,
+                &Thing {
+                    pos,
+                    angle: Angle::from_degrees(90.0),
+                    type_id: 3001,
+                    skills: 7,
+                    is_deaf: true,
+                    multiplayer: false,
+                    sub_sector: level.locate_subsector(pos),
+                },
+            )
+            .unwrap();
+        sim.run_tic(&mut level, InputCmd::default());
+
+        let mut bytes = Vec::new();
+        write_game(&mut bytes, &sim, &level, &GameSession::default()).unwrap();
+        let mut fresh = testmap::three_rooms(testmap::Middle::Wall);
+        let (restored, _) = read_game(
+            &mut bytes.as_slice(),
+            &mut fresh,
+            &SwitchList::default(),
+            Definitions::shared_builtin(),
+        )
+        .unwrap();
+        assert_eq!(restored.rules(), GameRules::NIGHTMARE);
+
+        let mut q = restored.world().query::<&SpawnPoint>();
+        let spots: Vec<_> = q.iter().map(|(_, s)| s.0.clone()).collect();
+        assert_eq!(spots.len(), 1);
+        let before = sim.world().get::<&SpawnPoint>(imp).unwrap().0.clone();
+        assert_eq!(spots[0].type_id, 3001);
+        assert_eq!((spots[0].pos, spots[0].angle), (before.pos, before.angle));
+        assert!(spots[0].is_deaf);
+        assert_eq!(spots[0].sub_sector, before.sub_sector);
+    }
+}
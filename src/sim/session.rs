@@ -0,0 +1,194 @@
+//! What outlasts a map: the player's inventory on the way to the next
+//! one, how much of each map's automap has been seen, the tallies of
+//! the maps played and the time spent.
+//!
+//! A `TicRunner` is thrown away with its map.  The frontend keeps one
+//! `GameSession` for the whole game, tells it when a map is left
+//! ([`GameSession::leave_level`]) and hands the next map's sim to
+//! [`GameSession::enter_level`] to give the player back what they were
+//! carrying.
+
+use std::collections::BTreeMap;
+
+use super::{Health, LevelTotals, PlayerStatus, TicRunner, Weapons};
+use crate::world::LineSet;
+
+/// One map's record.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LevelProgress {
+    /// Lines drawn at least once (vanilla `ML_MAPPED`); kept when the
+    /// map is left, so the automap is still there on coming back.
+    pub seen: LineSet,
+    /// Monsters killed, items picked up and secrets found on the last
+    /// visit.
+    pub kills: i32,
+    pub items: i32,
+    pub secrets: i32,
+    /// What the map had to find on that visit.
+    pub totals: LevelTotals,
+    /// Tics spent on the map, every visit added up.
+    pub tics: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GameSession {
+    /// By marker name; ordered so saves come out the same every time.
+    levels: BTreeMap<String, LevelProgress>,
+    /// Tics played on maps already left.
+    pub total_tics: u32,
+    /// Inventory the next map's player starts with (vanilla
+    /// `G_PlayerFinishLevel`): keys and powers gone, the rest kept.
+    carried: Option<(PlayerStatus, Weapons)>,
+}
+
+impl GameSession {
+    pub fn level(&self, map: &str) -> Option<&LevelProgress> {
+        self.levels.get(map)
+    }
+
+    /// Every map visited, by name.
+    pub fn levels(&self) -> impl Iterator<Item = (&str, &LevelProgress)> {
+        self.levels.iter().map(|(name, p)| (name.as_str(), p))
+    }
+
+    /// `map`'s record, made on first use.
+    pub fn level_mut(&mut self, map: &str) -> &mut LevelProgress {
+        if !self.levels.contains_key(map) {
+            self.levels.insert(map.to_owned(), LevelProgress::default());
+        }
+        self.levels.get_mut(map).unwrap()
+    }
+
+    /// Lines of `map` seen so far; the renderer adds to it every view.
+    pub fn seen_mut(&mut self, map: &str) -> &mut LineSet {
+        &mut self.level_mut(map).seen
+    }
+
+    /// Tics played, the map `sim` is running included.
+    pub fn play_time(&self, sim: &TicRunner) -> u32 {
+        self.total_tics + sim.gametic()
+    }
+
+    /// `sim`'s map, `map`, is being left: add its time and keep the
+    /// player's tallies.
+    pub fn leave_level(&mut self, map: &str, sim: &TicRunner) {
+        let status = player_status(sim);
+        let totals = sim.totals();
+        let level = self.level_mut(map);
+        level.tics += sim.gametic();
+        level.totals = totals;
+        if let Some(st) = status {
+            (level.kills, level.items, level.secrets) =
+                (st.kill_count, st.item_count, st.secret_count);
+        }
+        self.total_tics += sim.gametic();
+    }
+
+    /// Take what `sim`'s player carries out of the map to
+    /// [`GameSession::enter_level`] with.
+    pub fn carry_inventory(&mut self, sim: &TicRunner) {
+        let weapons = sim
+            .player()
+            .and_then(|p| sim.world().get::<&Weapons>(p).ok().map(|w| *w));
+        self.carried = player_status(sim)
+            .zip(weapons)
+            .map(|(mut status, weapons)| {
+                status.finish_level();
+                (status, weapons.respawned(sim.definitions()))
+            });
+    }
+
+    /// Give the player of a freshly spawned map what was carried out of
+    /// the last one.
+    pub fn enter_level(&mut self, sim: &mut TicRunner) {
+        let (Some((status, weapons)), Some(player)) = (self.carried.take(), sim.player()) else {
+            return;
+        };
+        let world = sim.world_mut();
+        world.insert(player, (status, weapons)).ok();
+        if let Ok(mut hp) = world.get::<&mut Health>(player) {
+            hp.0 = status.health;
+        }
+    }
+}
+
+fn player_status(sim: &TicRunner) -> Option<PlayerStatus> {
+    let player = sim.player()?;
+    sim.world().get::<&PlayerStatus>(player).ok().map(|s| *s)
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::specials::{SECRET_MESSAGE, SECRET_SECTOR};
+    use crate::sim::{InputCmd, Keys, SimEvent, WeaponType};
+    use crate::{defs, world::testmap};
+
+    #[test]
+    fn secrets_and_inventory_outlast_the_map() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        level.sectors[1].special = SECRET_SECTOR;
+
+        let mut sim = TicRunner::new(&level);
+        sim.spawn_specials(&mut level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        {
+            let mut st = sim.world().get::<&mut PlayerStatus>(player).unwrap();
+            (st.health, st.keys) = (60, Keys::BLUE_CARD);
+            let mut wp = sim.world().get::<&mut Weapons>(player).unwrap();
+            wp.owned[WeaponType::Shotgun as usize] = true;
+        }
+        let walk = InputCmd {
+            forward: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert!(
+            sim.drain_events()
+                .any(|e| matches!(e, SimEvent::Message(m) if m == SECRET_MESSAGE))
+        );
+
+        let mut session = GameSession::default();
+        session.seen_mut(&level.name).insert(3);
+        session.leave_level(&level.name, &sim);
+        session.carry_inventory(&sim);
+        let progress = session.level(&level.name).unwrap();
+        assert_eq!((progress.secrets, progress.totals.secrets), (1, 1));
+        assert_eq!((progress.tics, session.total_tics), (20, 20));
+
+        // on to the next map: keys go, health and weapons stay
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        sim.spawn_specials(&mut level);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        session.enter_level(&mut sim);
+        let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!(
+            (st.health, st.keys, st.secret_count),
+            (60, Keys::empty(), 0)
+        );
+        assert_eq!(sim.world().get::<&Health>(player).unwrap().0, 60);
+        assert!(
+            sim.world()
+                .get::<&Weapons>(player)
+                .unwrap()
+                .owns(WeaponType::Shotgun)
+        );
+        // the map seen before is still seen
+        assert!(session.seen_mut(&level.name).contains(3));
+        assert_eq!(session.play_time(&sim), 20);
+    }
+}
@@ -1,28 +1,36 @@
 //! Runtime “thing” grid – a very small, cache‑friendly spatial hash.
 //!
-//! * One `BlockMap` cell ≙ 128×128 map‑units (vanilla constant).
+//! * One `BlockMap` cell ≙ 128×128 map‑units (vanilla constant).
 //! * Each cell keeps a `SmallVec` – Doom maps rarely exceed a handful
 //!   of live mobjs per block, so this is fast and allocation‑free in
 //!   the common case.
+//! * The grid only holds what pruning needs (origin + radius).  Flags,
+//!   heights and the rest are read from the `World` by whoever asks, so
+//!   nothing here goes stale when a component changes; only
+//!   [`p_set_thing_position`] / [`p_unset_thing_position`] touch it.
+//! * It also keeps each BSP leaf's things (vanilla's sector thing lists,
+//!   kept per subsector), which is what the sprite pass walks.  Whatever
+//!   moves a thing outside `p_try_move` calls [`relink_thing`].
 
 use glam::Vec2;
-use hecs::Entity;
+use hecs::{Entity, World};
 use smallvec::SmallVec;
 use std::collections::HashMap;
 
-use crate::world::{Aabb, Level};
+use crate::defs::MobjFlags;
+use crate::world::{Aabb, BlockmapQuery, Level, MAPBLOCKSIZE, SubsectorId};
 
-use super::{ActorFlags, Class, Position};
+use super::{ActorFlags, Class, Position, Subsector};
 
 /*──────────────────────── core types ────────────────────────*/
 
-/// Pre‑baked data we need during collision / AI without touching `World`.
-#[derive(Clone, Copy)]
+/// What the grid knows about a linked thing.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ThingSpatial {
     pub ent: Entity,
-    pub pos: Position,
-    pub class: Class,
-    pub flags: ActorFlags,
+    /// Map x/y of the thing's origin.
+    pub pos: Vec2,
+    pub radius: f32,
 }
 
 /// Small fixed‑capacity cell
@@ -32,6 +40,13 @@ type Cell = SmallVec<[ThingSpatial; 8]>;
 pub struct ThingGrid {
     origin: Vec2,
     cells: HashMap<(i32, i32), Cell>,
+    /// Cell each linked thing sits in, so unlinking needs only the entity.
+    home: HashMap<Entity, (i32, i32)>,
+    /// Line stamps for the sim's walks over the level's blockmap.
+    lines: BlockmapQuery,
+    /// Things in each subsector, and the subsector each one is in.
+    leaves: HashMap<SubsectorId, SmallVec<[Entity; 4]>>,
+    leaf_of: HashMap<Entity, SubsectorId>,
 }
 
 /*───────────────────────── API ──────────────────────────────*/
@@ -41,27 +56,89 @@ impl ThingGrid {
         ThingGrid {
             origin,
             cells: HashMap::new(),
+            home: HashMap::new(),
+            lines: BlockmapQuery::default(),
+            leaves: HashMap::new(),
+            leaf_of: HashMap::new(),
         }
     }
 
-    /// Insert / update a stub at its current BLOCKMAP coordinates.
+    /// Things linked into subsector `ss`, in no particular order.
+    #[inline]
+    pub fn things_in_subsector(&self, ss: SubsectorId) -> &[Entity] {
+        self.leaves.get(&ss).map_or(&[], |l| l.as_slice())
+    }
+
+    /// The subsector `ent` is linked into, if it is.
+    #[inline]
+    pub fn subsector_of(&self, ent: Entity) -> Option<SubsectorId> {
+        self.leaf_of.get(&ent).copied()
+    }
+
+    fn link_leaf(&mut self, ent: Entity, ss: SubsectorId) {
+        self.unlink_leaf(ent);
+        self.leaves.entry(ss).or_default().push(ent);
+        self.leaf_of.insert(ent, ss);
+    }
+
+    fn unlink_leaf(&mut self, ent: Entity) {
+        if let Some(ss) = self.leaf_of.remove(&ent) {
+            if let Some(leaf) = self.leaves.get_mut(&ss) {
+                if let Some(i) = leaf.iter().position(|&e| e == ent) {
+                    leaf.swap_remove(i);
+                }
+            }
+        }
+    }
+
+    /// Scratch for [`Level::block_lines_iter`] and traces run alongside
+    /// this grid.
+    #[inline]
+    pub fn line_query(&self) -> &BlockmapQuery {
+        &self.lines
+    }
+
+    #[inline]
+    fn block(&self, p: Vec2) -> (i32, i32) {
+        (
+            Level::world_to_block(p.x, self.origin.x),
+            Level::world_to_block(p.y, self.origin.y),
+        )
+    }
+
+    /// Insert a stub at its BLOCKMAP coordinates, replacing the entity's
+    /// old one if it was linked already.
     #[inline]
     pub fn insert(&mut self, stub: ThingSpatial) {
-        let bx = Level::world_to_block(stub.pos.0.x, self.origin.x);
-        let by = Level::world_to_block(stub.pos.0.y, self.origin.y);
-        self.cells.entry((bx, by)).or_default().push(stub);
+        self.remove(stub.ent);
+        let key = self.block(stub.pos);
+        self.cells.entry(key).or_default().push(stub);
+        self.home.insert(stub.ent, key);
     }
 
-    /// Remove the stub from the cell it used to occupy.
+    /// Unlink `ent`; `false` if it wasn't in the grid.
     #[inline]
-    pub fn remove(&mut self, stub: &ThingSpatial) {
-        let bx = Level::world_to_block(stub.pos.0.x, self.origin.x);
-        let by = Level::world_to_block(stub.pos.0.y, self.origin.y);
-        if let Some(cell) = self.cells.get_mut(&(bx, by)) {
-            if let Some(i) = cell.iter().position(|s| s.ent == stub.ent) {
+    pub fn remove(&mut self, ent: Entity) -> bool {
+        let Some(key) = self.home.remove(&ent) else {
+            return false;
+        };
+        if let Some(cell) = self.cells.get_mut(&key) {
+            if let Some(i) = cell.iter().position(|s| s.ent == ent) {
                 cell.swap_remove(i);
             }
         }
+        true
+    }
+
+    /// Linked things.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.home.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.home.is_empty()
     }
 
     /// Visit every stub whose **origin** lies in the blocks overlapped by
@@ -71,10 +148,8 @@ impl ThingGrid {
     where
         F: FnMut(&ThingSpatial) -> bool,
     {
-        let xl = Level::world_to_block(bbox.min.x, self.origin.x);
-        let xh = Level::world_to_block(bbox.max.x, self.origin.x);
-        let yl = Level::world_to_block(bbox.min.y, self.origin.y);
-        let yh = Level::world_to_block(bbox.max.y, self.origin.y);
+        let (xl, yl) = self.block(bbox.min);
+        let (xh, yh) = self.block(bbox.max);
 
         for bx in xl..=xh {
             for by in yl..=yh {
@@ -89,4 +164,255 @@ impl ThingGrid {
         }
         true
     }
+
+    /// Visit the stubs linked in the single block `(bx, by)` (vanilla
+    /// `P_BlockThingsIterator`).  Stops early like [`Self::for_each_in_bbox`].
+    pub fn for_each_in_block<F>(&self, block: (i32, i32), f: F) -> bool
+    where
+        F: FnMut(&ThingSpatial) -> bool,
+    {
+        match self.cells.get(&block) {
+            Some(cell) => cell.iter().all(f),
+            None => true,
+        }
+    }
+
+    /// Visit every stub whose origin is within `r` of `center`: blocks
+    /// first, then the exact distance.  Stops early like
+    /// [`Self::for_each_in_bbox`].
+    pub fn for_each_in_radius<F>(&self, center: Vec2, r: f32, mut f: F) -> bool
+    where
+        F: FnMut(&ThingSpatial) -> bool,
+    {
+        let bbox = Aabb {
+            min: center - Vec2::splat(r),
+            max: center + Vec2::splat(r),
+        };
+        self.for_each_in_bbox(bbox, |stub| {
+            stub.pos.distance_squared(center) > r * r || f(stub)
+        })
+    }
+
+    /// The thing closest to `center` (by origin) that `pred` accepts.
+    /// Searches outward one ring of blocks at a time.
+    pub fn nearest<F>(&self, center: Vec2, mut pred: F) -> Option<Entity>
+    where
+        F: FnMut(&ThingSpatial) -> bool,
+    {
+        let (cx, cy) = self.block(center);
+        let reach = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(&(bx, by), _)| (bx - cx).abs().max((by - cy).abs()))
+            .max()?;
+
+        let mut best: Option<(f32, Entity)> = None;
+        for ring in 0..=reach {
+            // nothing in this ring or beyond can beat what we have
+            if let Some((d2, _)) = best {
+                if ((ring - 1) as f32 * MAPBLOCKSIZE).powi(2) > d2 {
+                    break;
+                }
+            }
+            for bx in cx - ring..=cx + ring {
+                for by in cy - ring..=cy + ring {
+                    if (bx - cx).abs().max((by - cy).abs()) != ring {
+                        continue;
+                    }
+                    let Some(cell) = self.cells.get(&(bx, by)) else {
+                        continue;
+                    };
+                    for stub in cell {
+                        let d2 = stub.pos.distance_squared(center);
+                        if best.is_none_or(|(b, _)| d2 < b) && pred(stub) {
+                            best = Some((d2, stub.ent));
+                        }
+                    }
+                }
+            }
+        }
+        best.map(|(_, ent)| ent)
+    }
+
+    /// Things whose origin lies inside `bbox` (edges included).
+    pub fn count_in_bbox(&self, bbox: Aabb) -> usize {
+        let mut n = 0;
+        self.for_each_in_bbox(bbox, |stub| {
+            let p = stub.pos;
+            n += (p.cmpge(bbox.min) & p.cmple(bbox.max)).all() as usize;
+            true
+        });
+        n
+    }
+}
+
+/// Position, class and flags of a thing the grid returned, read fresh
+/// from the world; `None` once it has been despawned.
+pub(super) fn fetch_thing(world: &World, ent: Entity) -> Option<(Position, Class, ActorFlags)> {
+    let mut q = world
+        .query_one::<(&Position, &Class, &ActorFlags)>(ent)
+        .ok()?;
+    q.get().map(|(p, c, f)| (*p, *c, *f))
+}
+
+/// Vanilla `P_SetThingPosition`: link `ent` into subsector `ss` (the one
+/// `pos` is in) unless it is `NOSECTOR`, and into the grid at `pos`
+/// unless it is `NOBLOCKMAP`.  The caller keeps `Subsector` in step.
+pub(super) fn p_set_thing_position(
+    grid: &mut ThingGrid,
+    ent: Entity,
+    pos: &Position,
+    class: &Class,
+    flags: &ActorFlags,
+    ss: SubsectorId,
+) {
+    if !flags.0.contains(MobjFlags::NOSECTOR) {
+        grid.link_leaf(ent, ss);
+    }
+    if !flags.0.contains(MobjFlags::NOBLOCKMAP) {
+        grid.insert(ThingSpatial {
+            ent,
+            pos: pos.0,
+            radius: class.0.radius as f32,
+        });
+    }
+}
+
+/// Vanilla `P_UnsetThingPosition`: take `ent` out of the grid and its
+/// subsector.
+pub(super) fn p_unset_thing_position(grid: &mut ThingGrid, ent: Entity) {
+    grid.remove(ent);
+    grid.unlink_leaf(ent);
+}
+
+/// Link `ent` wherever its `Position` now is: the grid, its subsector's
+/// list and the `Subsector` component.  The subsector it landed in, or
+/// `None` if it's gone.
+pub fn relink_thing(
+    world: &mut World,
+    grid: &mut ThingGrid,
+    level: &Level,
+    ent: Entity,
+) -> Option<SubsectorId> {
+    let (pos, class, flags) = fetch_thing(world, ent)?;
+    let ss = level.locate_subsector(pos.0);
+    p_unset_thing_position(grid, ent);
+    p_set_thing_position(grid, ent, &pos, &class, &flags, ss);
+    if let Ok(mut sub) = world.get::<&mut Subsector>(ent) {
+        sub.0 = ss;
+    }
+    Some(ss)
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::Rng;
+
+    /// `n` things scattered over a 1024² area around `origin`, linked.
+    fn scatter(n: usize, rng: &mut Rng) -> (ThingGrid, Vec<ThingSpatial>) {
+        let origin = Vec2::new(-512.0, -512.0);
+        let mut world = hecs::World::new();
+        let mut grid = ThingGrid::new(origin);
+        let mut all = Vec::new();
+        for _ in 0..n {
+            let stub = ThingSpatial {
+                ent: world.spawn(()),
+                pos: origin + Vec2::new(rng.p_random() as f32, rng.p_random() as f32) * 4.0,
+                radius: 16.0,
+            };
+            grid.insert(stub);
+            all.push(stub);
+        }
+        (grid, all)
+    }
+
+    fn sorted(mut v: Vec<Entity>) -> Vec<Entity> {
+        v.sort_by_key(|e| e.to_bits());
+        v
+    }
+
+    #[test]
+    fn radius_query_matches_brute_force() {
+        let mut rng = Rng::new(7);
+        let (grid, all) = scatter(200, &mut rng);
+        for _ in 0..50 {
+            let center = Vec2::new(rng.p_subrandom() as f32, rng.p_subrandom() as f32) * 2.0;
+            let r = rng.p_random() as f32;
+
+            let mut got = Vec::new();
+            grid.for_each_in_radius(center, r, |s| {
+                got.push(s.ent);
+                true
+            });
+            let want = all
+                .iter()
+                .filter(|s| s.pos.distance(center) <= r)
+                .map(|s| s.ent)
+                .collect();
+            assert_eq!(sorted(got), sorted(want), "r {r} at {center}");
+
+            let bbox = Aabb {
+                min: center - Vec2::splat(r),
+                max: center + Vec2::splat(r * 0.5),
+            };
+            let inside = all
+                .iter()
+                .filter(|s| (s.pos.cmpge(bbox.min) & s.pos.cmple(bbox.max)).all())
+                .count();
+            assert_eq!(grid.count_in_bbox(bbox), inside);
+        }
+    }
+
+    #[test]
+    fn nearest_matches_brute_force() {
+        let mut rng = Rng::new(3);
+        let (grid, all) = scatter(60, &mut rng);
+        for i in 0..50 {
+            let center = Vec2::new(rng.p_subrandom() as f32, rng.p_subrandom() as f32) * 3.0;
+            // every other query skips half the things
+            let keep = |s: &ThingSpatial| i % 2 == 0 || s.ent.to_bits().get().is_multiple_of(2);
+            let best = all
+                .iter()
+                .filter(|s| keep(s))
+                .map(|s| s.pos.distance(center))
+                .fold(f32::INFINITY, f32::min);
+
+            let got = grid.nearest(center, keep).unwrap();
+            let got = all.iter().find(|s| s.ent == got).unwrap();
+            assert_eq!(got.pos.distance(center), best);
+        }
+        assert_eq!(
+            ThingGrid::new(Vec2::ZERO).nearest(Vec2::ZERO, |_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn relinking_moves_and_unlinks() {
+        let mut rng = Rng::new(1);
+        let (mut grid, all) = scatter(10, &mut rng);
+        let mut moved = all[0];
+        moved.pos += Vec2::splat(300.0);
+        grid.insert(moved);
+        assert_eq!(grid.len(), 10);
+
+        let mut seen = Vec::new();
+        let spot = Aabb {
+            min: moved.pos,
+            max: moved.pos,
+        };
+        grid.for_each_in_bbox(spot, |s| {
+            seen.push(*s);
+            true
+        });
+        assert!(seen.contains(&moved));
+        assert!(grid.remove(moved.ent));
+        assert!(!grid.remove(moved.ent));
+        assert_eq!(grid.len(), 9);
+    }
 }
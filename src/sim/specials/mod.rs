@@ -0,0 +1,565 @@
+//! Linedef specials – vanilla `p_map.c` (use traces), `p_switch.c`
+//! (`P_UseSpecialLine`) and `p_spec.c` (`P_CrossSpecialLine`,
+//! `P_ShootSpecialLine`).
+//!
+//! Which special does what lives in [`table`]; [`try_activate`] checks a
+//! line against its row and starts the effect.  Only switch textures,
+//! exits, teleporters, doors, crushers, lifts and plain floor movers
+//! exist so far; the rest of the rows log that they fired and do
+//! nothing.
+
+pub mod table;
+
+use glam::Vec2;
+use hecs::{Entity, World};
+
+use super::ceilings::{Ceiling, ev_ceiling_crush_stop, ev_do_ceiling};
+use super::doors::{Door, ev_do_door, ev_vertical_door};
+use super::events::SimEvent;
+use super::floors::{Floor, ev_do_floor};
+use super::plats::{Platform, ev_do_plat, ev_stop_plat};
+use super::switches::{Button, SwitchList, p_change_switch_texture};
+use super::teleport::ev_teleport;
+use super::trace::{self, Crossed};
+use super::xy_movement::line_opening;
+use super::{Angle, Class, GameRules, PlayerStatus, Position, Rng, Skill, Subsector, ThingGrid};
+use crate::defs::{Definitions, MobjFlags, Sound};
+use crate::world::{Level, LinedefFlags, LinedefId};
+use table::{Activation, Effect, line_special};
+
+/// How far the player can reach to press things (vanilla `USERANGE`).
+pub const USE_RANGE: f32 = 64.0;
+
+/// Everything a line special can touch: the map, the things in it and
+/// the sim's running movers.
+pub struct SpecialCtx<'a> {
+    pub world: &'a mut World,
+    pub thing_grid: &'a mut ThingGrid,
+    pub level: &'a mut Level,
+    pub rng: &'a mut Rng,
+    pub rules: GameRules,
+    pub skill: Skill,
+    pub defs: &'a Definitions,
+    pub switches: &'a SwitchList,
+    pub buttons: &'a mut Vec<Button>,
+    pub ceilings: &'a mut Vec<Ceiling>,
+    pub plats: &'a mut Vec<Platform>,
+    pub doors: &'a mut Vec<Door>,
+    pub floors: &'a mut Vec<Floor>,
+    pub events: &'a mut Vec<SimEvent>,
+}
+
+/// 0 = front (right) side, 1 = back – vanilla `P_PointOnLineSide`.
+#[inline]
+pub fn point_on_line_side(p: Vec2, v1: Vec2, v2: Vec2) -> usize {
+    if (v2 - v1).perp_dot(p - v1) < 0.0 {
+        0
+    } else {
+        1
+    }
+}
+
+/// Player pressed *use*: find the first line within `USE_RANGE` along the
+/// view direction and activate it (vanilla `P_UseLines`).
+pub fn p_use_lines(ctx: &mut SpecialCtx, player: Entity) {
+    let Ok((pos, ang)) = ctx
+        .world
+        .query_one_mut::<(&Position, &Angle)>(player)
+        .map(|(p, a)| (p.0, *a))
+    else {
+        return;
+    };
+    let from = pos;
+    let delta = ang.unit() * USE_RANGE;
+
+    // vanilla PTR_UseTraverse, nearest line first
+    let level = &*ctx.level;
+    let mut used = None;
+    trace::trace_lines_only(level, ctx.thing_grid.line_query(), from, delta, |ic| {
+        let Crossed::Line(line) = ic.what else {
+            return true;
+        };
+        let ld = &level.linedefs[line as usize];
+        if ld.special == 0 {
+            // not a special line, but keep checking unless it's a wall
+            let (_, _, range, _) = line_opening(level, ld);
+            return range > 0.0;
+        }
+        let v1 = level.vertices[ld.v1 as usize].pos;
+        let v2 = level.vertices[ld.v2 as usize].pos;
+        used = Some((line, point_on_line_side(from, v1, v2)));
+        false // can't use more than one special line in a row
+    });
+    if let Some((line, side)) = used {
+        try_activate(ctx, line, side, Activation::Use, player);
+    }
+}
+
+/// A monster's step was blocked: try *use* on the special lines the
+/// failed move touched, latest first, which opens doors in its way (the
+/// `spechit` loop of vanilla `P_Move`).  `true` if any reacted, in which
+/// case the monster keeps its heading and waits for the door.
+pub fn p_monster_use_lines(ctx: &mut SpecialCtx, actor: Entity, lines: &[LinedefId]) -> bool {
+    let mut good = false;
+    for &line in lines.iter().rev() {
+        good |= try_activate(ctx, line, 0, Activation::Use, actor);
+    }
+    good
+}
+
+/// `activator` set off `line` from `side` by `activation` (vanilla
+/// `P_UseSpecialLine`, `P_CrossSpecialLine` and `P_ShootSpecialLine`):
+/// check the line's [`table`] row, key and all, start its effect and
+/// disarm or flip the line as the row says.
+///
+/// `true` if the line reacted – for use lines that is whenever the
+/// activation was valid, even if nothing moved, so a use can't reach
+/// through a switch to the line behind it.
+pub fn try_activate(
+    ctx: &mut SpecialCtx,
+    line: LinedefId,
+    side: usize,
+    activation: Activation,
+    activator: Entity,
+) -> bool {
+    let ld = &ctx.level.linedefs[line as usize];
+    let (special, tag, flags) = (ld.special, ld.tag, ld.flags);
+    let Some(spec) = line_special(special) else {
+        return false;
+    };
+    if spec.activation != activation {
+        return false;
+    }
+    let Ok(class) = ctx.world.get::<&Class>(activator).map(|c| *c) else {
+        return false;
+    };
+    let is_player = class.0.id == "PLAYER";
+
+    if is_player && !spec.players {
+        return false;
+    }
+    if !is_player {
+        if !spec.monsters {
+            return false;
+        }
+        match activation {
+            // projectiles never trigger lines they fly over
+            Activation::Cross if class.0.flags.contains(MobjFlags::MISSILE) => return false,
+            // nor can monsters open doors hidden as walls
+            Activation::Use if flags.contains(LinedefFlags::SECRET) => return false,
+            _ => {}
+        }
+    }
+    // switches are only pressable from the front
+    if activation == Activation::Use && side != 0 {
+        return false;
+    }
+
+    if let Some(lock) = spec.lock {
+        let keys = ctx.world.get::<&PlayerStatus>(activator).map(|s| s.keys);
+        if !keys.is_ok_and(|k| k.intersects(lock.keys())) {
+            if is_player {
+                let door = spec.target == table::Target::BackSector;
+                ctx.events.push(SimEvent::Message(lock.message(door)));
+                let origin = ctx
+                    .world
+                    .get::<&Position>(activator)
+                    .map_or(Vec2::ZERO, |p| p.0);
+                ctx.events.push(SimEvent::Sound {
+                    sound: Sound::oof,
+                    origin,
+                    full_volume: false,
+                });
+            }
+            return false;
+        }
+    }
+
+    let done = match spec.effect {
+        Effect::Ceiling(kind) => ev_do_ceiling(
+            ctx.level,
+            ctx.ceilings,
+            ctx.doors,
+            ctx.plats,
+            ctx.floors,
+            tag,
+            kind,
+        ),
+        Effect::CeilingCrushStop => ev_ceiling_crush_stop(ctx.ceilings, tag),
+        Effect::Plat(kind) => ev_do_plat(
+            ctx.level,
+            ctx.plats,
+            ctx.doors,
+            ctx.ceilings,
+            ctx.floors,
+            ctx.rng,
+            ctx.events,
+            tag,
+            kind,
+        ),
+        Effect::PlatStop => ev_stop_plat(ctx.plats, tag),
+        Effect::Door(kind) if spec.target == table::Target::BackSector => ev_vertical_door(
+            ctx.level,
+            ctx.doors,
+            ctx.ceilings,
+            ctx.plats,
+            ctx.floors,
+            ctx.events,
+            line,
+            kind,
+            spec.repeat,
+            is_player,
+        ),
+        Effect::Door(kind) => ev_do_door(
+            ctx.level,
+            ctx.doors,
+            ctx.ceilings,
+            ctx.plats,
+            ctx.floors,
+            ctx.events,
+            tag,
+            kind,
+        ),
+        Effect::Floor(kind) => ev_do_floor(
+            ctx.level,
+            ctx.floors,
+            ctx.doors,
+            ctx.ceilings,
+            ctx.plats,
+            tag,
+            kind,
+        ),
+        Effect::Exit(exit) => {
+            ctx.events.push(SimEvent::Exit(exit));
+            true
+        }
+        Effect::Teleport => ev_teleport(
+            ctx.world,
+            ctx.thing_grid,
+            ctx.level,
+            ctx.rng,
+            ctx.rules,
+            ctx.defs,
+            ctx.events,
+            line,
+            side,
+            activator,
+        ),
+        Effect::Unimplemented(what) => {
+            eprintln!("warning: line {line}: special {special} ({what}) not implemented");
+            true // the switch still clicks
+        }
+    };
+
+    match activation {
+        // W1 lines are spent even if nothing moved
+        Activation::Cross => {
+            if !spec.repeat {
+                ctx.level.linedefs[line as usize].special = 0;
+            }
+            done
+        }
+        Activation::Use => {
+            if done {
+                disarm_or_flip(ctx, line, spec.repeat, spec.flips_switch());
+            }
+            true
+        }
+        // gun lines flip whether or not anything started, as vanilla's
+        // `P_ShootSpecialLine` does; G1 ones are spent with it
+        Activation::Shoot => {
+            disarm_or_flip(ctx, line, spec.repeat, true);
+            done
+        }
+    }
+}
+
+/// Flip the switch on `line` – which disarms it unless `repeat` – or,
+/// for a manual door, just disarm a once-only one.
+fn disarm_or_flip(ctx: &mut SpecialCtx, line: LinedefId, repeat: bool, flip: bool) {
+    if flip {
+        p_change_switch_texture(
+            ctx.level,
+            ctx.switches,
+            ctx.buttons,
+            ctx.events,
+            line,
+            repeat,
+        );
+    } else if !repeat {
+        ctx.level.linedefs[line as usize].special = 0;
+    }
+}
+
+/// Sector special of a secret area.
+pub const SECRET_SECTOR: i16 = 9;
+
+/// Shown on finding one (Boom's; vanilla finds secrets silently).
+pub const SECRET_MESSAGE: &str = "A secret is revealed!";
+
+/// The sector-special checks for a player standing on the floor (vanilla
+/// `P_PlayerInSpecialSector`).  Only secrets so far; damaging floors
+/// follow with health.
+pub fn p_player_in_special_sector(
+    world: &mut World,
+    level: &mut Level,
+    events: &mut Vec<SimEvent>,
+    player: Entity,
+) {
+    let Ok((pos, ss, status)) =
+        world.query_one_mut::<(&Position, &Subsector, &mut PlayerStatus)>(player)
+    else {
+        return;
+    };
+    let sector = &mut level.sectors[level.subsectors[ss.0 as usize].sector as usize];
+    // falling, not all the way down yet
+    if pos.1 != sector.floor_h {
+        return;
+    }
+    if sector.special == SECRET_SECTOR {
+        status.secret_count += 1;
+        sector.special = 0;
+        events.push(SimEvent::Message(SECRET_MESSAGE));
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{InputCmd, Keys, LevelExit, TicRunner};
+    use crate::{defs, world::testmap};
+    use table::{LINE_SPECIALS, Lock};
+
+    /// Room A's west wall, which the player at (40, 64) faces, as
+    /// `special`.
+    fn west_wall(special: u16) -> (Level, usize) {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let west = level
+            .linedefs
+            .iter()
+            .position(|l| (l.v1, l.v2) == (0, 4))
+            .unwrap();
+        level.linedefs[west].special = special;
+        level.linedefs[west].tag = 7;
+        (level, west)
+    }
+
+    fn player_facing_west(level: &Level, keys: Keys) -> (TicRunner, Entity) {
+        let mut sim = TicRunner::new(level);
+        let player = sim.spawn_mobj(level, defs::by_id("PLAYER").unwrap(), 40.0, 64.0, 0.0, 0);
+        *sim.world_mut().get::<&mut Angle>(player).unwrap() = Angle::ANG180;
+        sim.set_player(player);
+        sim.world_mut()
+            .get::<&mut PlayerStatus>(player)
+            .unwrap()
+            .keys = keys;
+        (sim, player)
+    }
+
+    fn press(sim: &mut TicRunner, level: &mut Level) -> Vec<SimEvent> {
+        let cmd = InputCmd {
+            use_act: true,
+            ..InputCmd::default()
+        };
+        sim.run_tic(level, cmd);
+        sim.drain_events().collect()
+    }
+
+    #[test]
+    fn table_is_sorted_and_vanilla_shaped() {
+        assert!(LINE_SPECIALS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert!(line_special(0).is_none() && line_special(48).is_none());
+
+        // the S1 / SR switches of p_switch.c
+        let switches = |repeat| {
+            LINE_SPECIALS
+                .iter()
+                .filter(|(_, s)| {
+                    s.activation == Activation::Use && s.flips_switch() && s.repeat == repeat
+                })
+                .map(|&(n, _)| n)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            switches(false),
+            [
+                7, 9, 11, 14, 15, 18, 20, 21, 23, 29, 41, 49, 50, 51, 55, 71, 101, 102, 103, 111,
+                112, 113, 122, 127, 131, 133, 135, 137, 140
+            ]
+        );
+        assert_eq!(
+            switches(true),
+            [
+                42, 43, 45, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 99, 114, 115, 116, 123,
+                132, 134, 136, 138, 139
+            ]
+        );
+        // what monsters can walk over
+        let monster_cross: Vec<_> = LINE_SPECIALS
+            .iter()
+            .filter(|(_, s)| s.activation == Activation::Cross && s.monsters)
+            .map(|&(n, _)| n)
+            .collect();
+        assert_eq!(monster_cross, [4, 10, 39, 88, 97, 125, 126]);
+        for n in [26, 32] {
+            assert_eq!(line_special(n).unwrap().lock, Some(Lock::Blue));
+        }
+    }
+
+    #[test]
+    fn locked_door_wants_the_key() {
+        let (mut level, west) = west_wall(26);
+        let (mut sim, _) = player_facing_west(&level, Keys::empty());
+        let events = press(&mut sim, &mut level);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            SimEvent::Message("You need a blue key to open this door")
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            SimEvent::Sound {
+                sound: Sound::oof,
+                ..
+            }
+        )));
+
+        // a skull does as well as a card; DR doors stay armed
+        let (mut sim, _) = player_facing_west(&level, Keys::BLUE_SKULL);
+        let events = press(&mut sim, &mut level);
+        assert!(!events.iter().any(|e| matches!(e, SimEvent::Message(_))));
+        assert_eq!(level.linedefs[west].special, 26);
+    }
+
+    #[test]
+    fn use_reaches_only_as_far_as_use_range() {
+        let (mut level, _) = west_wall(26);
+        let (mut sim, player) = player_facing_west(&level, Keys::empty());
+        // the wall is at x = 0; step back out of reach
+        sim.world_mut().get::<&mut Position>(player).unwrap().0.x = USE_RANGE + 16.0;
+        sim.relink_thing(&level, player);
+        assert!(press(&mut sim, &mut level).is_empty());
+
+        let (mut sim, _) = player_facing_west(&level, Keys::empty());
+        assert!(!press(&mut sim, &mut level).is_empty());
+    }
+
+    #[test]
+    fn once_only_lines_disarm_and_sides_matter() {
+        // W1: spent by the crossing even though nothing is tagged 7
+        let (mut level, west) = west_wall(2);
+        let (mut sim, player) = player_facing_west(&level, Keys::empty());
+        let mut ctx = sim.special_ctx(&mut level);
+        assert!(!try_activate(
+            &mut ctx,
+            west as LinedefId,
+            0,
+            Activation::Use,
+            player
+        ));
+        assert!(!try_activate(
+            &mut ctx,
+            west as LinedefId,
+            0,
+            Activation::Cross,
+            player
+        ));
+        assert_eq!(ctx.level.linedefs[west].special, 0);
+
+        // G1 as well, shot with nothing tagged to raise
+        ctx.level.linedefs[west].special = 24;
+        assert!(!try_activate(
+            &mut ctx,
+            west as LinedefId,
+            0,
+            Activation::Shoot,
+            player
+        ));
+        assert_eq!(ctx.level.linedefs[west].special, 0);
+
+        // S1 exit: only from the front, then gone
+        ctx.level.linedefs[west].special = 11;
+        assert!(!try_activate(
+            &mut ctx,
+            west as LinedefId,
+            1,
+            Activation::Use,
+            player
+        ));
+        assert!(try_activate(
+            &mut ctx,
+            west as LinedefId,
+            0,
+            Activation::Use,
+            player
+        ));
+        assert_eq!(ctx.level.linedefs[west].special, 0);
+        assert!(matches!(
+            ctx.events.last(),
+            Some(SimEvent::Exit(LevelExit::Normal))
+        ));
+
+        // monster-only teleports ignore the player and stay armed
+        ctx.level.linedefs[west].special = 125;
+        assert!(!try_activate(
+            &mut ctx,
+            west as LinedefId,
+            0,
+            Activation::Cross,
+            player
+        ));
+        assert_eq!(ctx.level.linedefs[west].special, 125);
+    }
+
+    #[test]
+    fn line_sides_match_vanilla() {
+        // line pointing north: east is the front (right-hand) side
+        let (v1, v2) = (Vec2::new(0.0, 0.0), Vec2::new(0.0, 10.0));
+        assert_eq!(point_on_line_side(Vec2::new(5.0, 5.0), v1, v2), 0);
+        assert_eq!(point_on_line_side(Vec2::new(-5.0, 5.0), v1, v2), 1);
+    }
+
+    #[test]
+    fn exit_line_and_secret_sector() {
+        use crate::{
+            defs,
+            sim::{InputCmd, TicRunner},
+            world::testmap,
+        };
+
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let line = level
+            .linedefs
+            .iter()
+            .position(|l| l.right_sidedef == Some(2) && l.left_sidedef == Some(1))
+            .unwrap();
+        level.linedefs[line].special = 52;
+        level.sectors[1].special = SECRET_SECTOR;
+
+        let mut sim = TicRunner::new(&level);
+        sim.spawn_specials(&mut level);
+        assert_eq!(sim.totals().secrets, 1);
+        let player = sim.spawn_mobj(&level, defs::by_id("PLAYER").unwrap(), 64.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+
+        let walk = InputCmd {
+            forward: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert_eq!(sim.exit(), Some(LevelExit::Normal));
+        let status = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!(status.secret_count, 1);
+        assert_eq!(level.sectors[1].special, 0);
+    }
+}
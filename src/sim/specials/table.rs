@@ -0,0 +1,370 @@
+//! Vanilla linedef specials as data: how each is triggered, whether it
+//! can be again, what it acts on and which effect it starts.
+//!
+//! A new special is a row here plus, when it needs a new kind of mover,
+//! an [`Effect`] variant that [`super::try_activate`] dispatches.  Rows
+//! whose mover hasn't been ported yet carry [`Effect::Unimplemented`].
+
+use crate::sim::ceilings::CeilingKind;
+use crate::sim::components::Keys;
+use crate::sim::doors::DoorKind;
+use crate::sim::events::LevelExit;
+use crate::sim::floors::FloorKind;
+use crate::sim::plats::PlatKind;
+
+/// How a line is set off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Activation {
+    /// Pressed with *use*: switches and manual doors (S / D lines).
+    Use,
+    /// Walked over (W lines).
+    Cross,
+    /// Hit by a hitscan shot (G lines).
+    Shoot,
+}
+
+/// What the effect acts on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// Every sector tagged like the line.
+    Tagged,
+    /// The sector behind the line (manual doors).
+    BackSector,
+    /// Nothing in the map: exits.
+    None,
+}
+
+/// The key colour a locked line wants; card or skull both do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lock {
+    Blue,
+    Yellow,
+    Red,
+}
+
+impl Lock {
+    /// Keys that open it.
+    pub fn keys(self) -> Keys {
+        match self {
+            Self::Blue => Keys::BLUE_CARD | Keys::BLUE_SKULL,
+            Self::Yellow => Keys::YELLOW_CARD | Keys::YELLOW_SKULL,
+            Self::Red => Keys::RED_CARD | Keys::RED_SKULL,
+        }
+    }
+
+    /// Vanilla `PD_*K` (manual doors) or `PD_*O` (locked switches).
+    pub fn message(self, door: bool) -> &'static str {
+        match (self, door) {
+            (Self::Blue, true) => "You need a blue key to open this door",
+            (Self::Yellow, true) => "You need a yellow key to open this door",
+            (Self::Red, true) => "You need a red key to open this door",
+            (Self::Blue, false) => "You need a blue key to activate this object",
+            (Self::Yellow, false) => "You need a yellow key to activate this object",
+            (Self::Red, false) => "You need a red key to activate this object",
+        }
+    }
+}
+
+/// What a special does once it fires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    Ceiling(CeilingKind),
+    /// Vanilla `EV_CeilingCrushStop`.
+    CeilingCrushStop,
+    /// Tagged sectors, or the sector behind a manual door line.
+    Door(DoorKind),
+    Floor(FloorKind),
+    Plat(PlatKind),
+    /// Vanilla `EV_StopPlat`.
+    PlatStop,
+    Exit(LevelExit),
+    Teleport,
+    /// Not ported yet; names the vanilla effect for the log.
+    Unimplemented(&'static str),
+}
+
+/// One row of the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineSpecial {
+    pub activation: Activation,
+    /// Stays armed after firing (R lines) rather than once-only.
+    pub repeat: bool,
+    pub target: Target,
+    /// Players may set it off; all but the monster-only teleports.
+    pub players: bool,
+    /// Monsters (and, for G lines, their shots) may set it off too.
+    pub monsters: bool,
+    pub lock: Option<Lock>,
+    pub effect: Effect,
+}
+
+impl LineSpecial {
+    const fn new(activation: Activation, repeat: bool, effect: Effect) -> Self {
+        let target = match effect {
+            Effect::Exit(_) => Target::None,
+            _ => Target::Tagged,
+        };
+        Self {
+            activation,
+            repeat,
+            target,
+            players: true,
+            monsters: false,
+            lock: None,
+            effect,
+        }
+    }
+
+    const fn monsters(self) -> Self {
+        Self {
+            monsters: true,
+            ..self
+        }
+    }
+
+    const fn monsters_only(self) -> Self {
+        Self {
+            players: false,
+            monsters: true,
+            ..self
+        }
+    }
+
+    const fn locked(self, lock: Lock) -> Self {
+        Self {
+            lock: Some(lock),
+            ..self
+        }
+    }
+
+    /// Whether firing flips the line's switch texture: every G line and
+    /// every S line, but not manual doors.
+    pub fn flips_switch(&self) -> bool {
+        match self.activation {
+            Activation::Use => self.target != Target::BackSector,
+            Activation::Shoot => true,
+            Activation::Cross => false,
+        }
+    }
+}
+
+const fn w1(effect: Effect) -> LineSpecial {
+    LineSpecial::new(Activation::Cross, false, effect)
+}
+const fn wr(effect: Effect) -> LineSpecial {
+    LineSpecial::new(Activation::Cross, true, effect)
+}
+const fn s1(effect: Effect) -> LineSpecial {
+    LineSpecial::new(Activation::Use, false, effect)
+}
+const fn sr(effect: Effect) -> LineSpecial {
+    LineSpecial::new(Activation::Use, true, effect)
+}
+const fn g1(effect: Effect) -> LineSpecial {
+    LineSpecial::new(Activation::Shoot, false, effect)
+}
+const fn gr(effect: Effect) -> LineSpecial {
+    LineSpecial::new(Activation::Shoot, true, effect)
+}
+/// Manual door: used from the front, acts on the sector behind.
+const fn d(repeat: bool, effect: Effect) -> LineSpecial {
+    LineSpecial {
+        target: Target::BackSector,
+        ..LineSpecial::new(Activation::Use, repeat, effect)
+    }
+}
+
+use Effect::Unimplemented as Todo;
+
+const FLOOR_LOWER_CHANGE: Effect = Todo("floor: lower to lowest, change texture");
+const FLOOR_RAISE_24_CHANGE: Effect = Todo("floor: raise 24, change texture");
+const FLOOR_RAISE_TEXTURE: Effect = Todo("floor: raise by shortest lower texture");
+const STAIRS: Effect = Todo("floor: build 8-unit stairs");
+const STAIRS_TURBO: Effect = Todo("floor: build 16-unit turbo stairs");
+const DONUT: Effect = Todo("floor: donut");
+const PLAT_RAISE_24: Effect = Todo("plat: raise 24, change texture");
+const PLAT_RAISE_32: Effect = Todo("plat: raise 32, change texture");
+const PLAT_RAISE_NEAREST: Effect = Todo("plat: raise to next floor, change texture");
+const CEIL_LOWER: Effect = Todo("ceiling: lower to floor");
+const CEIL_LOWER_CRUSH: Effect = Todo("ceiling: lower to floor + 8 and crush");
+const CEIL_RAISE: Effect = Todo("ceiling: raise to highest neighbour, floor: lower to lowest");
+const CEIL_SILENT_CRUSH: Effect = Todo("ceiling: silent crush and raise");
+const LIGHT_MAX_NEIGHBOUR: Effect = Todo("light: brightest neighbour");
+const LIGHT_35: Effect = Todo("light: 35");
+const LIGHT_255: Effect = Todo("light: 255");
+const LIGHT_OFF: Effect = Todo("light: dimmest neighbour");
+const LIGHT_STROBE: Effect = Todo("light: start strobing");
+
+const DOOR_RAISE: Effect = Effect::Door(DoorKind::Normal);
+const DOOR_OPEN: Effect = Effect::Door(DoorKind::Open);
+const DOOR_CLOSE: Effect = Effect::Door(DoorKind::Close);
+const DOOR_CLOSE30: Effect = Effect::Door(DoorKind::Close30ThenOpen);
+const BLAZE_RAISE: Effect = Effect::Door(DoorKind::BlazeRaise);
+const BLAZE_OPEN: Effect = Effect::Door(DoorKind::BlazeOpen);
+const BLAZE_CLOSE: Effect = Effect::Door(DoorKind::BlazeClose);
+const FLOOR_LOWER: Effect = Effect::Floor(FloorKind::Lower);
+const FLOOR_LOWEST: Effect = Effect::Floor(FloorKind::LowerToLowest);
+const FLOOR_TURBO: Effect = Effect::Floor(FloorKind::TurboLower);
+const FLOOR_RAISE: Effect = Effect::Floor(FloorKind::Raise);
+const FLOOR_RAISE_NEAREST: Effect = Effect::Floor(FloorKind::RaiseToNearest);
+const FLOOR_RAISE_CRUSH: Effect = Effect::Floor(FloorKind::RaiseCrush);
+const FLOOR_RAISE_TURBO: Effect = Effect::Floor(FloorKind::RaiseTurbo);
+const FLOOR_RAISE_24: Effect = Effect::Floor(FloorKind::Raise24);
+const FLOOR_RAISE_512: Effect = Effect::Floor(FloorKind::Raise512);
+const CRUSH: Effect = Effect::Ceiling(CeilingKind::CrushAndRaise);
+const FAST_CRUSH: Effect = Effect::Ceiling(CeilingKind::FastCrushAndRaise);
+const DWUS: Effect = Effect::Plat(PlatKind::DownWaitUpStay);
+const BLAZE_DWUS: Effect = Effect::Plat(PlatKind::BlazeDwus);
+const PERPETUAL: Effect = Effect::Plat(PlatKind::PerpetualRaise);
+const EXIT: Effect = Effect::Exit(LevelExit::Normal);
+const SECRET_EXIT: Effect = Effect::Exit(LevelExit::Secret);
+
+/// Every vanilla DOOM / DOOM II line special, sorted by number.
+pub static LINE_SPECIALS: &[(u16, LineSpecial)] = &[
+    (1, d(true, DOOR_RAISE).monsters()),
+    (2, w1(DOOR_OPEN)),
+    (3, w1(DOOR_CLOSE)),
+    (4, w1(DOOR_RAISE).monsters()),
+    (5, w1(FLOOR_RAISE)),
+    (6, w1(FAST_CRUSH)),
+    (7, s1(STAIRS)),
+    (8, w1(STAIRS)),
+    (9, s1(DONUT)),
+    (10, w1(DWUS).monsters()),
+    (11, s1(EXIT)),
+    (12, w1(LIGHT_MAX_NEIGHBOUR)),
+    (13, w1(LIGHT_255)),
+    (14, s1(PLAT_RAISE_32)),
+    (15, s1(PLAT_RAISE_24)),
+    (16, w1(DOOR_CLOSE30)),
+    (17, w1(LIGHT_STROBE)),
+    (18, s1(FLOOR_RAISE_NEAREST)),
+    (19, w1(FLOOR_LOWER)),
+    (20, s1(PLAT_RAISE_NEAREST)),
+    (21, s1(DWUS)),
+    (22, w1(PLAT_RAISE_NEAREST)),
+    (23, s1(FLOOR_LOWEST)),
+    (24, g1(FLOOR_RAISE)),
+    (25, w1(CRUSH)),
+    (26, d(true, DOOR_RAISE).locked(Lock::Blue)),
+    (27, d(true, DOOR_RAISE).locked(Lock::Yellow)),
+    (28, d(true, DOOR_RAISE).locked(Lock::Red)),
+    (29, s1(DOOR_RAISE)),
+    (30, w1(FLOOR_RAISE_TEXTURE)),
+    (31, d(false, DOOR_OPEN)),
+    (32, d(false, DOOR_OPEN).locked(Lock::Blue).monsters()),
+    (33, d(false, DOOR_OPEN).locked(Lock::Red).monsters()),
+    (34, d(false, DOOR_OPEN).locked(Lock::Yellow).monsters()),
+    (35, w1(LIGHT_35)),
+    (36, w1(FLOOR_TURBO)),
+    (37, w1(FLOOR_LOWER_CHANGE)),
+    (38, w1(FLOOR_LOWEST)),
+    (39, w1(Effect::Teleport).monsters()),
+    (40, w1(CEIL_RAISE)),
+    (41, s1(CEIL_LOWER)),
+    (42, sr(DOOR_CLOSE)),
+    (43, sr(CEIL_LOWER)),
+    (44, w1(CEIL_LOWER_CRUSH)),
+    (45, sr(FLOOR_LOWER)),
+    (46, gr(DOOR_OPEN).monsters()),
+    (47, g1(PLAT_RAISE_NEAREST)),
+    (49, s1(CRUSH)),
+    (50, s1(DOOR_CLOSE)),
+    (51, s1(SECRET_EXIT)),
+    (52, w1(EXIT)),
+    (53, w1(PERPETUAL)),
+    (54, w1(Effect::PlatStop)),
+    (55, s1(FLOOR_RAISE_CRUSH)),
+    (56, w1(FLOOR_RAISE_CRUSH)),
+    (57, w1(Effect::CeilingCrushStop)),
+    (58, w1(FLOOR_RAISE_24)),
+    (59, w1(FLOOR_RAISE_24_CHANGE)),
+    (60, sr(FLOOR_LOWEST)),
+    (61, sr(DOOR_OPEN)),
+    (62, sr(DWUS)),
+    (63, sr(DOOR_RAISE)),
+    (64, sr(FLOOR_RAISE)),
+    (65, sr(FLOOR_RAISE_CRUSH)),
+    (66, sr(PLAT_RAISE_24)),
+    (67, sr(PLAT_RAISE_32)),
+    (68, sr(PLAT_RAISE_NEAREST)),
+    (69, sr(FLOOR_RAISE_NEAREST)),
+    (70, sr(FLOOR_TURBO)),
+    (71, s1(FLOOR_TURBO)),
+    (72, wr(CEIL_LOWER_CRUSH)),
+    (73, wr(CRUSH)),
+    (74, wr(Effect::CeilingCrushStop)),
+    (75, wr(DOOR_CLOSE)),
+    (76, wr(DOOR_CLOSE30)),
+    (77, wr(FAST_CRUSH)),
+    (79, wr(LIGHT_35)),
+    (80, wr(LIGHT_MAX_NEIGHBOUR)),
+    (81, wr(LIGHT_255)),
+    (82, wr(FLOOR_LOWEST)),
+    (83, wr(FLOOR_LOWER)),
+    (84, wr(FLOOR_LOWER_CHANGE)),
+    (86, wr(DOOR_OPEN)),
+    (87, wr(PERPETUAL)),
+    (88, wr(DWUS).monsters()),
+    (89, wr(Effect::PlatStop)),
+    (90, wr(DOOR_RAISE)),
+    (91, wr(FLOOR_RAISE)),
+    (92, wr(FLOOR_RAISE_24)),
+    (93, wr(FLOOR_RAISE_24_CHANGE)),
+    (94, wr(FLOOR_RAISE_CRUSH)),
+    (95, wr(PLAT_RAISE_NEAREST)),
+    (96, wr(FLOOR_RAISE_TEXTURE)),
+    (97, wr(Effect::Teleport).monsters()),
+    (98, wr(FLOOR_TURBO)),
+    (99, sr(BLAZE_OPEN).locked(Lock::Blue)),
+    (100, w1(STAIRS_TURBO)),
+    (101, s1(FLOOR_RAISE)),
+    (102, s1(FLOOR_LOWER)),
+    (103, s1(DOOR_OPEN)),
+    (104, w1(LIGHT_OFF)),
+    (105, wr(BLAZE_RAISE)),
+    (106, wr(BLAZE_OPEN)),
+    (107, wr(BLAZE_CLOSE)),
+    (108, w1(BLAZE_RAISE)),
+    (109, w1(BLAZE_OPEN)),
+    (110, w1(BLAZE_CLOSE)),
+    (111, s1(BLAZE_RAISE)),
+    (112, s1(BLAZE_OPEN)),
+    (113, s1(BLAZE_CLOSE)),
+    (114, sr(BLAZE_RAISE)),
+    (115, sr(BLAZE_OPEN)),
+    (116, sr(BLAZE_CLOSE)),
+    (117, d(true, BLAZE_RAISE)),
+    (118, d(false, BLAZE_OPEN)),
+    (119, w1(FLOOR_RAISE_NEAREST)),
+    (120, wr(BLAZE_DWUS)),
+    (121, w1(BLAZE_DWUS)),
+    (122, s1(BLAZE_DWUS)),
+    (123, sr(BLAZE_DWUS)),
+    (124, w1(SECRET_EXIT)),
+    (125, w1(Effect::Teleport).monsters_only()),
+    (126, wr(Effect::Teleport).monsters_only()),
+    (127, s1(STAIRS_TURBO)),
+    (128, wr(FLOOR_RAISE_NEAREST)),
+    (129, wr(FLOOR_RAISE_TURBO)),
+    (130, w1(FLOOR_RAISE_TURBO)),
+    (131, s1(FLOOR_RAISE_TURBO)),
+    (132, sr(FLOOR_RAISE_TURBO)),
+    (133, s1(BLAZE_OPEN).locked(Lock::Blue)),
+    (134, sr(BLAZE_OPEN).locked(Lock::Red)),
+    (135, s1(BLAZE_OPEN).locked(Lock::Red)),
+    (136, sr(BLAZE_OPEN).locked(Lock::Yellow)),
+    (137, s1(BLAZE_OPEN).locked(Lock::Yellow)),
+    (138, sr(LIGHT_255)),
+    (139, sr(LIGHT_35)),
+    (140, s1(FLOOR_RAISE_512)),
+    (141, w1(CEIL_SILENT_CRUSH)),
+];
+
+/// The row for linedef special `special`, if it is one.
+pub fn line_special(special: u16) -> Option<&'static LineSpecial> {
+    LINE_SPECIALS
+        .binary_search_by_key(&special, |&(n, _)| n)
+        .ok()
+        .map(|i| &LINE_SPECIALS[i].1)
+}
@@ -0,0 +1,290 @@
+//! Wall switches – vanilla `p_switch.c`.
+//!
+//! Activating a switch special swaps the sidedef texture to its SW1↔SW2
+//! partner.  Repeatable switches start a button thinker that swaps it back
+//! after `BUTTON_TIME` tics.
+
+use glam::Vec2;
+use std::collections::HashMap;
+
+use super::events::SimEvent;
+use crate::defs::Sound;
+use crate::world::{Level, LinedefId, SidedefId, TextureBank, TextureId};
+
+/// Tics before a repeatable switch pops back (vanilla `BUTTONTIME`).
+pub const BUTTON_TIME: i32 = 35;
+
+/// Vanilla `alphSwitchList` – (off, on) texture pairs for every IWAD.
+pub const SWITCH_LIST: &[(&str, &str)] = &[
+    /* shareware */
+    ("SW1BRCOM", "SW2BRCOM"),
+    ("SW1BRN1", "SW2BRN1"),
+    ("SW1BRN2", "SW2BRN2"),
+    ("SW1BRNGN", "SW2BRNGN"),
+    ("SW1BROWN", "SW2BROWN"),
+    ("SW1COMM", "SW2COMM"),
+    ("SW1COMP", "SW2COMP"),
+    ("SW1DIRT", "SW2DIRT"),
+    ("SW1EXIT", "SW2EXIT"),
+    ("SW1GRAY", "SW2GRAY"),
+    ("SW1GRAY1", "SW2GRAY1"),
+    ("SW1METAL", "SW2METAL"),
+    ("SW1PIPE", "SW2PIPE"),
+    ("SW1SLAD", "SW2SLAD"),
+    ("SW1STARG", "SW2STARG"),
+    ("SW1STON1", "SW2STON1"),
+    ("SW1STON2", "SW2STON2"),
+    ("SW1STONE", "SW2STONE"),
+    ("SW1STRTN", "SW2STRTN"),
+    /* registered */
+    ("SW1BLUE", "SW2BLUE"),
+    ("SW1CMT", "SW2CMT"),
+    ("SW1GARG", "SW2GARG"),
+    ("SW1GSTON", "SW2GSTON"),
+    ("SW1HOT", "SW2HOT"),
+    ("SW1LION", "SW2LION"),
+    ("SW1SATYR", "SW2SATYR"),
+    ("SW1SKIN", "SW2SKIN"),
+    ("SW1VINE", "SW2VINE"),
+    ("SW1WOOD", "SW2WOOD"),
+    /* commercial */
+    ("SW1PANEL", "SW2PANEL"),
+    ("SW1ROCK", "SW2ROCK"),
+    ("SW1MET2", "SW2MET2"),
+    ("SW1WDMET", "SW2WDMET"),
+    ("SW1BRIK", "SW2BRIK"),
+    ("SW1MOD1", "SW2MOD1"),
+    ("SW1ZIM", "SW2ZIM"),
+    ("SW1STON6", "SW2STON6"),
+    ("SW1TEK", "SW2TEK"),
+    ("SW1MARB", "SW2MARB"),
+    ("SW1SKULL", "SW2SKULL"),
+];
+
+/// `TextureId` → partner id, both directions, and whether it's the
+/// pair's SW2 ("on") half.
+#[derive(Debug, Default, Clone)]
+pub struct SwitchList {
+    partner: HashMap<TextureId, (TextureId, bool)>,
+}
+
+impl SwitchList {
+    /// Bind `SWITCH_LIST` to the ids of `bank`; pairs whose textures are
+    /// not loaded are skipped.
+    pub fn new(bank: &TextureBank) -> Self {
+        let mut partner = HashMap::new();
+        for &(off, on) in SWITCH_LIST {
+            if let (Some(a), Some(b)) = (bank.id(off), bank.id(on)) {
+                partner.insert(a, (b, false));
+                partner.insert(b, (a, true));
+            }
+        }
+        Self { partner }
+    }
+
+    #[inline]
+    pub fn partner(&self, tex: TextureId) -> Option<TextureId> {
+        self.partner.get(&tex).map(|&(other, _)| other)
+    }
+
+    /// Whether `tex` is the SW2 half of its pair; `None` if it's no switch.
+    #[inline]
+    pub fn is_on(&self, tex: TextureId) -> Option<bool> {
+        self.partner.get(&tex).map(|&(_, on)| on)
+    }
+
+    /// The half of `tex`'s pair that is on or off as `on` says; `tex`
+    /// itself if it's no switch.
+    pub fn with_state(&self, tex: TextureId, on: bool) -> TextureId {
+        match self.partner.get(&tex) {
+            Some(&(other, is_on)) if is_on != on => other,
+            _ => tex,
+        }
+    }
+}
+
+/// Which sidedef texture a button swapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidePart {
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl SidePart {
+    pub const ALL: [Self; 3] = [Self::Top, Self::Middle, Self::Bottom];
+}
+
+/// Pending "pop back" of a repeatable switch (vanilla `button_t`).
+#[derive(Debug, Clone, Copy)]
+pub struct Button {
+    pub sidedef: SidedefId,
+    pub part: SidePart,
+    pub texture: TextureId,
+    pub timer: i32,
+    pub origin: Vec2,
+}
+
+pub(super) fn side_texture(
+    level: &mut Level,
+    sidedef: SidedefId,
+    part: SidePart,
+) -> &mut TextureId {
+    let sd = &mut level.sidedefs[sidedef as usize];
+    match part {
+        SidePart::Top => &mut sd.upper,
+        SidePart::Middle => &mut sd.middle,
+        SidePart::Bottom => &mut sd.lower,
+    }
+}
+
+/// Flip the switch on `line`'s front side (vanilla `P_ChangeSwitchTexture`).
+///
+/// Once-only switches lose their special; `use_again` ones get a button
+/// thinker that flips them back.
+pub fn p_change_switch_texture(
+    level: &mut Level,
+    switches: &SwitchList,
+    buttons: &mut Vec<Button>,
+    events: &mut Vec<SimEvent>,
+    line: LinedefId,
+    use_again: bool,
+) {
+    let ld = &mut level.linedefs[line as usize];
+    let special = ld.special;
+    if !use_again {
+        ld.special = 0;
+    }
+    let Some(sidedef) = ld.right_sidedef else {
+        return;
+    };
+
+    let v1 = level.vertices[ld.v1 as usize].pos;
+    let v2 = level.vertices[ld.v2 as usize].pos;
+    let origin = (v1 + v2) * 0.5;
+
+    /* exit switches get their own clunk */
+    let sound = if special == 11 {
+        Sound::swtchx
+    } else {
+        Sound::swtchn
+    };
+
+    for part in SidePart::ALL {
+        let tex = side_texture(level, sidedef, part);
+        let Some(other) = switches.partner(*tex) else {
+            continue;
+        };
+
+        let old = std::mem::replace(tex, other);
+        events.push(SimEvent::Sound {
+            sound,
+            origin,
+            full_volume: false,
+        });
+        if use_again {
+            buttons.push(Button {
+                sidedef,
+                part,
+                texture: old,
+                timer: BUTTON_TIME,
+                origin,
+            });
+        }
+        return;
+    }
+}
+
+/// Count down button thinkers and restore expired switch textures.
+pub fn run_buttons(level: &mut Level, buttons: &mut Vec<Button>, events: &mut Vec<SimEvent>) {
+    buttons.retain_mut(|b| {
+        b.timer -= 1;
+        if b.timer > 0 {
+            return true;
+        }
+        *side_texture(level, b.sidedef, b.part) = b.texture;
+        events.push(SimEvent::Sound {
+            sound: Sound::swtchn,
+            origin: b.origin,
+            full_volume: false,
+        });
+        false
+    });
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        sim::{InputCmd, TicRunner},
+        wad::{Wad, load_level},
+    };
+    use std::path::PathBuf;
+
+    fn doom_wad() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("assets")
+            .join("doom.wad")
+    }
+
+    /// Walk up to E1M1's exit switch, press it and watch SW1 → SW2.
+    #[test]
+    fn e1m1_exit_switch_flips() {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = TextureBank::default_with_checker();
+        let mut level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        level.finalise_bsp();
+
+        let exit = level.linedefs.iter().find(|l| l.special == 11).unwrap();
+        let (line, sd) = (exit.id, exit.right_sidedef.unwrap());
+        let v1 = level.vertices[exit.v1 as usize].pos;
+        let v2 = level.vertices[exit.v2 as usize].pos;
+        let front = (v2 - v1).perp().normalize() * -1.0; // right-hand normal
+        let spot = (v1 + v2) * 0.5 + front * 24.0;
+
+        let mut sim = TicRunner::new(&level);
+        sim.set_switch_list(SwitchList::new(&bank));
+        let player = sim.spawn_mobj(
+            &level,
+            defs::by_id("PLAYER").unwrap(),
+            spot.x,
+            spot.y,
+            (-front).to_angle(),
+            level.locate_subsector(spot),
+        );
+        sim.set_player(player);
+
+        let before = level.sidedefs[sd as usize].clone();
+        let press = InputCmd {
+            use_act: true,
+            ..Default::default()
+        };
+        sim.run_tic(&mut level, press);
+
+        let after = &level.sidedefs[sd as usize];
+        let flipped = [
+            (before.upper, after.upper),
+            (before.middle, after.middle),
+            (before.lower, after.lower),
+        ]
+        .into_iter()
+        .find(|(b, a)| b != a)
+        .expect("exit switch texture did not change");
+        let name = |id| bank.texture(id).unwrap().name.to_string();
+        assert!(name(flipped.0).starts_with("SW1"));
+        assert!(name(flipped.1).starts_with("SW2"));
+
+        assert_eq!(level.linedefs[line as usize].special, 0); // S1: used up
+        assert!(sim.drain_events().any(|e| matches!(
+            e,
+            SimEvent::Sound {
+                sound: Sound::swtchx,
+                ..
+            }
+        )));
+    }
+}
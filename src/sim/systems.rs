@@ -1,33 +1,155 @@
-use hecs::World;
+use hecs::{Entity, World};
 
-use super::{Angle, Animation, InputCmd, ThingGrid, Velocity, tic::DT, xy_movement_system};
-use crate::world::Level;
+use super::ceilings::Ceiling;
+use super::damage::TURN_HINT_MAX;
+use super::doors::Door;
+use super::events::SimEvent;
+use super::fixed::{ANG90, Fixed, FixedMotion};
+use super::floors::Floor;
+use super::noise::SectorSounds;
+use super::plats::Platform;
+use super::switches::{Button, SwitchList};
+use super::xy_movement::get_floor_z;
+use super::{
+    ActorFlags, Angle, Animation, Attacker, CheatFlags, Cheats, GameRules, Health, InputCmd,
+    PhysicsConfig, PlayerStatus, Position, Power, ReactionTime, Rng, Skill, Subsector, ThingGrid,
+    Velocity, actions, cheats, enemy, mob, tic::DT, xy_movement_system,
+};
+use crate::defs::{Action, Definitions, MobjFlags, State};
+use crate::world::{Level, angle_to};
 
 /* ── Animation system ─────────────────────────────────────────────── */
-pub fn animation(world: &mut World) {
-    for (_, anim) in world.query_mut::<&mut Animation>() {
+#[allow(clippy::too_many_arguments)]
+pub fn animation(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    sounds: &SectorSounds,
+    events: &mut Vec<SimEvent>,
+) {
+    let mut expired = Vec::new();
+    let mut entered = Vec::new();
+    for (ent, anim) in world.query_mut::<&mut Animation>() {
         if anim.tics > 0 {
             anim.tics -= 1;
             if anim.tics == 0 {
-                anim.state = anim.state.next();
-                anim.tics = anim.state.tics();
+                anim.state = defs.state(anim.state).next_state;
+                anim.tics = rules.state_tics(defs, anim.state);
+                let action = defs.state(anim.state).action;
+                if anim.state == State::NULL {
+                    expired.push(ent);
+                } else if !matches!(action, Action::None) {
+                    entered.push((ent, action));
+                }
             }
         }
     }
+
+    for (ent, action) in entered {
+        match action {
+            // the only codepointer that listens for noise
+            Action::Look => enemy::a_look(world, level, sounds, rng, rules, defs, events, ent),
+            // and the only one that has to fit a corpse back in the map
+            Action::VileChase => {
+                enemy::a_vile_chase(world, thing_grid, level, cfg, rng, defs, events, ent)
+            }
+            // and the missile attacks, which fly faster when fast
+            Action::TroopAttack => {
+                enemy::a_troop_attack(world, thing_grid, level, rng, rules, defs, events, ent)
+            }
+            _ => actions::call_action(
+                world, thing_grid, level, rng, rules, defs, events, ent, action,
+            ),
+        }
+    }
+
+    // S_NULL removes the mobj (vanilla P_SetMobjState)
+    for ent in expired {
+        mob::remove_mobj(world, thing_grid, ent);
+    }
 }
 
-pub fn physics(world: &mut World, thing_grid: &mut ThingGrid, level: &Level) {
-    xy_movement_system(world, thing_grid, level);
+#[allow(clippy::too_many_arguments)]
+pub fn physics(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    skill: Skill,
+    defs: &Definitions,
+    switches: &SwitchList,
+    buttons: &mut Vec<Button>,
+    ceilings: &mut Vec<Ceiling>,
+    plats: &mut Vec<Platform>,
+    doors: &mut Vec<Door>,
+    floors: &mut Vec<Floor>,
+    events: &mut Vec<SimEvent>,
+) {
+    xy_movement_system(
+        world, thing_grid, level, cfg, rng, rules, skill, defs, switches, buttons, ceilings, plats,
+        doors, floors, events,
+    );
+    mob::p_missile_z_movement(world, thing_grid, level, rng, rules, defs, events);
+    mob::p_skull_z_movement(world, level);
+    mob::p_effect_z_movement(world, level, cfg.gravity);
+    cheats::fly_movement(world, level);
 }
 
 pub const MOVE_SPEED: f32 = 250.0; // map-units / second
 pub const TURN_RATE: f32 = std::f32::consts::PI; // rad / second (180°/s)
-pub fn player_input(world: &mut World, player: hecs::Entity, cmd: InputCmd) {
-    if let Ok(mut q) = world.query_one::<(&mut Angle, &mut Velocity)>(player) {
-        if let Some((ang, vel)) = q.get() {
+/// Vanilla `forwardmove` / `sidemove` for a full-speed command, walking
+/// and running.
+const FORWARD_MOVE: [i32; 2] = [0x19, 0x32];
+const SIDE_MOVE: [i32; 2] = [0x18, 0x28];
+/// Most of a hit's turn hint paid out in one tic: the nudge takes four.
+const TURN_HINT_STEP: i32 = TURN_HINT_MAX / 4;
+
+pub fn player_input(
+    world: &mut World,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    player: Entity,
+    cmd: InputCmd,
+) {
+    // frozen after a teleport: count down instead of moving
+    let frozen = match world.get::<&mut ReactionTime>(player) {
+        Ok(mut rt) if rt.0 > 0 => {
+            rt.0 -= 1;
+            true
+        }
+        _ => false,
+    };
+    let flying = world
+        .get::<&Cheats>(player)
+        .is_ok_and(|c| c.0.contains(CheatFlags::FLY));
+    let thrust = cfg.fixed_point && !flying;
+
+    if let Ok(mut q) = world.query_one::<(
+        &mut Angle,
+        &mut Velocity,
+        &Position,
+        &Subsector,
+        Option<&mut PlayerStatus>,
+    )>(player)
+    {
+        if let Some((ang, vel, pos, ss, status)) = q.get() {
             /* 1. turn (scaled inside system) */
-            if cmd.turn != 0.0 {
-                ang.0 = (ang.0 + cmd.turn * TURN_RATE * DT).rem_euclid(std::f32::consts::TAU);
+            if !frozen && cmd.turn != 0.0 {
+                *ang += Angle::from_radians(cmd.turn * TURN_RATE * DT);
+            }
+            // the nudge toward an attacker rides on top of the player's turn
+            if let Some(st) = status {
+                if !frozen && st.turn_hint != 0 {
+                    let step = st.turn_hint.clamp(-TURN_HINT_STEP, TURN_HINT_STEP);
+                    *ang += Angle(step as u32);
+                    st.turn_hint -= step;
+                }
             }
 
             let speed = if cmd.run {
@@ -37,28 +159,402 @@ pub fn player_input(world: &mut World, player: hecs::Entity, cmd: InputCmd) {
             };
 
             /* 2. wish-vel (scaled inside system) */
-            if cmd.forward != 0.0 || cmd.strafe != 0.0 {
-                let (s, c) = ang.0.sin_cos();
-                let fwd = glam::Vec2::new(c, s);
+            if frozen || thrust {
+                // keep whatever momentum is left; `p_move_player` thrusts
+            } else if cmd.forward != 0.0 || cmd.strafe != 0.0 {
+                let fwd = ang.unit();
                 let right = fwd.perp();
                 let dir = (fwd * cmd.forward) - (right * cmd.strafe);
-                let wish = dir.normalize_or_zero();
+                let wish = dir.normalize_or_zero() * speed * DT;
 
-                vel.0.x = wish.x * speed * DT;
-                vel.0.y = wish.y * speed * DT;
+                if flying || pos.1 <= get_floor_z(level, ss) {
+                    (vel.0.x, vel.0.y) = (wish.x, wish.y);
+                } else {
+                    let v = vel.0.truncate().lerp(wish, cfg.air_control);
+                    (vel.0.x, vel.0.y) = (v.x, v.y);
+                }
             } else {
                 vel.zero_xy();
             }
+            vel.0.z = if flying && !frozen {
+                cmd.fly * speed * DT
+            } else {
+                0.0
+            };
+        }
+    }
+
+    if thrust && !frozen {
+        p_move_player(world, level, cfg, player, cmd);
+    }
+}
 
-            if cmd.fire {
-                println!("FIRE!");
+/// Vanilla `P_MovePlayer` for `PhysicsConfig::fixed_point`: thrust the
+/// momentum along the facing by `cmd`'s `forwardmove` / `sidemove`, on
+/// the fine tables, scaled by `air_control` off the ground.
+fn p_move_player(
+    world: &mut World,
+    level: &Level,
+    cfg: &PhysicsConfig,
+    player: Entity,
+    cmd: InputCmd,
+) {
+    let Ok((pos, vel, ang, ss)) = world
+        .query_one_mut::<(&Position, &Velocity, &Angle, &Subsector)>(player)
+        .map(|(p, v, a, ss)| (*p, *v, *a, *ss))
+    else {
+        return;
+    };
+    let prev = world.get::<&FixedMotion>(player).ok().map(|m| *m);
+    let mut motion = FixedMotion::sync(prev, pos.0, vel.0.truncate());
+
+    let control = if pos.1 <= get_floor_z(level, &ss) {
+        Fixed::ONE
+    } else {
+        Fixed::from_f32(cfg.air_control)
+    };
+    let speed = usize::from(cmd.run);
+    let forward = (cmd.forward * FORWARD_MOVE[speed] as f32).round() as i32;
+    let side = (cmd.strafe * SIDE_MOVE[speed] as f32).round() as i32;
+    let angle = ang.bam();
+    if forward != 0 {
+        motion.thrust(angle, Fixed(forward * 2048) * control);
+    }
+    if side != 0 {
+        motion.thrust(angle.wrapping_sub(ANG90), Fixed(side * 2048) * control);
+    }
+
+    if let Ok(mut vel) = world.get::<&mut Velocity>(player) {
+        (vel.0.x, vel.0.y) = (motion.momx.to_f32(), motion.momy.to_f32());
+    }
+    let _ = world.insert_one(player, motion);
+}
+
+/// Eye height above the floor (vanilla `VIEWHEIGHT`).
+pub const VIEW_HEIGHT: f32 = 41.0;
+
+/// Where a dead player's eyes sink to, a unit a tic.
+const DEAD_VIEW_HEIGHT: f32 = 6.0;
+
+/// Vanilla `ANG5`: how far a dead player turns toward the killer a tic.
+const ANG5: Angle = Angle(Angle::ANG45.0 / 9);
+
+/// Largest view bob (vanilla `MAXBOB`).
+const MAX_BOB: f32 = 16.0;
+
+/// The player's feet moved `dz` with its last step (vanilla leaves the
+/// mobj's z alone there and lets `P_ZMovement` catch up).  Stepping up
+/// sinks the view for it to ease back; stepping off a ledge lands as
+/// hard as falling that far would, since the sim puts walkers straight
+/// on the floor.  `true` if the landing was hard enough to grunt.
+pub fn p_player_z_step(status: &mut PlayerStatus, dz: f32, gravity: f32) -> bool {
+    if dz > 0.0 {
+        status.view_height -= dz;
+        status.delta_view_height = (VIEW_HEIGHT - status.view_height) / 8.0;
+        return false;
+    }
+    let momz = -(2.0 * gravity * -dz).sqrt();
+    if momz < -gravity * 8.0 {
+        status.delta_view_height = momz / 8.0;
+        return true;
+    }
+    false
+}
+
+/// Vanilla `P_CalcHeight`: the eye z for this tic.  On the ground the
+/// view bobs with speed and `view_height` eases back after a landing;
+/// in the air it just rides `view_height`.  Never closer than 4 to the
+/// ceiling.
+pub fn p_calc_height(world: &mut World, level: &Level, gametic: u32, player: Entity) {
+    let Ok((pos, vel, ss, st, health)) = world.query_one_mut::<(
+        &Position,
+        &Velocity,
+        &Subsector,
+        &mut PlayerStatus,
+        Option<&Health>,
+    )>(player) else {
+        return;
+    };
+    let sector = &level.sectors[level.sector_of_subsector(ss.0) as usize];
+    let ceiling = sector.ceil_h - 4.0;
+
+    st.bob = ((vel.0.x * vel.0.x + vel.0.y * vel.0.y) / 4.0).min(MAX_BOB);
+    if pos.1 > sector.floor_h {
+        st.view_z = (pos.1 + st.view_height).min(ceiling);
+        return;
+    }
+    let phase = (gametic % 20) as f32 / 20.0 * std::f32::consts::TAU;
+    let bob = st.bob / 2.0 * phase.sin();
+
+    // only the living get back up
+    if health.is_none_or(|h| h.0 > 0) {
+        st.view_height += st.delta_view_height;
+        if st.view_height > VIEW_HEIGHT {
+            st.view_height = VIEW_HEIGHT;
+            st.delta_view_height = 0.0;
+        }
+        if st.view_height < VIEW_HEIGHT / 2.0 {
+            st.view_height = VIEW_HEIGHT / 2.0;
+            if st.delta_view_height <= 0.0 {
+                st.delta_view_height = 1.0 / 65536.0;
             }
-            if cmd.use_act {
-                println!("USE / OPEN");
+        }
+        if st.delta_view_height != 0.0 {
+            st.delta_view_height += 0.25;
+            if st.delta_view_height == 0.0 {
+                st.delta_view_height = 1.0 / 65536.0;
             }
-            if let Some(w) = cmd.weapon {
-                println!("select weapon {}", w);
+        }
+    }
+    st.view_z = (pos.1 + st.view_height + bob).min(ceiling);
+}
+
+/// Vanilla `P_DeathThink`, which stands in for the rest of a dead
+/// player's think: the view sinks to the floor and turns to face the
+/// killer, the red flash fading only once it does.  Movement, turning
+/// and firing are ignored; `true` if `cmd` presses use to start over.
+pub fn p_death_think(world: &mut World, player: Entity, cmd: InputCmd) -> bool {
+    let killer = world
+        .get::<&Attacker>(player)
+        .ok()
+        .map(|a| a.0)
+        .filter(|&a| a != player)
+        .and_then(|a| world.get::<&Position>(a).ok().map(|p| p.0));
+    let Ok((pos, ang, st)) =
+        world.query_one_mut::<(&Position, &mut Angle, &mut PlayerStatus)>(player)
+    else {
+        return false;
+    };
+
+    st.view_height = (st.view_height - 1.0).max(DEAD_VIEW_HEIGHT);
+    st.delta_view_height = 0.0;
+    st.turn_hint = 0;
+
+    let mut fade = true;
+    if let Some(at) = killer {
+        let angle = angle_to(pos.0, at);
+        let delta = (angle - *ang).0;
+        if delta < ANG5.0 || delta > ANG5.0.wrapping_neg() {
+            // looking at the killer, so fade the damage flash down
+            *ang = angle;
+        } else {
+            fade = false;
+            if delta < Angle::ANG180.0 {
+                *ang += ANG5;
+            } else {
+                *ang -= ANG5;
             }
         }
     }
+    if fade && st.damage_count > 0 {
+        st.damage_count -= 1;
+    }
+    cmd.use_act
+}
+
+/// The tail of vanilla `P_PlayerThink`: run the power timers down
+/// (berserk counts *up*, fading its red tint) and fade the damage and
+/// pickup flashes.
+pub fn player_counters(world: &mut World, player: hecs::Entity) {
+    let Ok((st, flags)) = world.query_one_mut::<(&mut PlayerStatus, &mut ActorFlags)>(player)
+    else {
+        return;
+    };
+    let powers = &mut st.powers;
+
+    if powers[Power::Strength as usize] != 0 {
+        powers[Power::Strength as usize] += 1;
+    }
+    for power in [Power::Invulnerability, Power::Infrared, Power::IronFeet] {
+        if powers[power as usize] > 0 {
+            powers[power as usize] -= 1;
+        }
+    }
+    if powers[Power::Invisibility as usize] > 0 {
+        powers[Power::Invisibility as usize] -= 1;
+        if powers[Power::Invisibility as usize] == 0 {
+            flags.0.remove(MobjFlags::SHADOW);
+        }
+    }
+
+    if st.damage_count > 0 {
+        st.damage_count -= 1;
+    }
+    if st.bonus_count > 0 {
+        st.bonus_count -= 1;
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::{self, Sound},
+        sim::TicRunner,
+        world::testmap,
+    };
+
+    fn walker(level: &Level, x: f32) -> (TicRunner, Entity) {
+        let mut sim = TicRunner::new(level);
+        let ss = level.locate_subsector(glam::Vec2::new(x, 64.0));
+        let player = sim.spawn_mobj(level, defs::by_id("PLAYER").unwrap(), x, 64.0, 0.0, ss);
+        sim.set_player(player);
+        (sim, player)
+    }
+
+    const WALK: InputCmd = InputCmd {
+        forward: 1.0,
+        strafe: 0.0,
+        turn: 0.0,
+        run: false,
+        fire: false,
+        use_act: false,
+        weapon: None,
+        fly: 0.0,
+    };
+
+    #[test]
+    fn walking_bobs_the_view_and_standing_still_does_not() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = walker(&level, 24.0);
+        assert_eq!(sim.view_height(player), Some(VIEW_HEIGHT));
+
+        let mut heights = Vec::new();
+        for _ in 0..20 {
+            sim.run_tic(&mut level, WALK);
+            heights.push(sim.view_height(player).unwrap());
+        }
+        let (lo, hi) = heights
+            .iter()
+            .fold((f32::MAX, f32::MIN), |(lo, hi), &h| (lo.min(h), hi.max(h)));
+        assert!(hi - lo > 2.0, "a gentle bounce: {lo}..{hi}");
+        assert!(lo >= VIEW_HEIGHT - MAX_BOB / 2.0 && hi <= VIEW_HEIGHT + MAX_BOB / 2.0);
+
+        for _ in 0..35 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(sim.view_height(player), Some(VIEW_HEIGHT));
+    }
+
+    #[test]
+    fn dropping_off_a_ledge_dips_the_view() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        level.sectors[0].floor_h = 64.0;
+        let (mut sim, player) = walker(&level, 100.0);
+        sim.world_mut().get::<&mut Position>(player).unwrap().1 = 64.0;
+        sim.drain_events().for_each(drop);
+
+        let mut lowest = VIEW_HEIGHT;
+        let mut grunted = false;
+        for tic in 0..60 {
+            sim.run_tic(&mut level, if tic < 8 { WALK } else { InputCmd::default() });
+            grunted |= sim.drain_events().any(|e| {
+                matches!(
+                    e,
+                    SimEvent::Sound {
+                        sound: Sound::oof,
+                        ..
+                    }
+                )
+            });
+            let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+            lowest = lowest.min(st.view_height);
+        }
+        assert_eq!(sim.world().get::<&Position>(player).unwrap().1, 0.0);
+        assert!(grunted);
+        assert!(lowest < VIEW_HEIGHT - 2.0, "{lowest}");
+        assert_eq!(sim.view_height(player), Some(VIEW_HEIGHT), "eased back");
+    }
+
+    #[test]
+    fn view_stays_under_a_low_ceiling() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = walker(&level, 24.0);
+        level.sectors[0].ceil_h = 30.0;
+        sim.run_tic(&mut level, InputCmd::default());
+        assert_eq!(sim.view_height(player), Some(26.0));
+    }
+
+    /// A barrel at (64, 120): due north of a walker at x 64, who faces
+    /// east.
+    fn barrel_north(sim: &mut TicRunner, level: &Level) -> Entity {
+        let ss = level.locate_subsector(glam::Vec2::new(64.0, 120.0));
+        sim.spawn_mobj(level, defs::by_id("BARREL").unwrap(), 64.0, 120.0, 0.0, ss)
+    }
+
+    fn angle(sim: &TicRunner, player: Entity) -> Angle {
+        *sim.world().get::<&Angle>(player).unwrap()
+    }
+
+    #[test]
+    fn hits_nudge_the_view_toward_the_attacker() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = walker(&level, 64.0);
+        let barrel = barrel_north(&mut sim, &level);
+
+        sim.damage_mobj(player, None, Some(barrel), 10);
+        for _ in 0..8 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        // 5° to the left, not all the way round
+        assert_eq!(angle(&sim, player), Angle(TURN_HINT_MAX as u32));
+
+        // the player's own turning isn't fought
+        let turn = InputCmd {
+            turn: -0.5,
+            ..InputCmd::default()
+        };
+        let before = angle(&sim, player);
+        sim.damage_mobj(player, None, Some(barrel), 10);
+        sim.run_tic(&mut level, turn);
+        let own = Angle::from_radians(turn.turn * TURN_RATE * DT);
+        assert_eq!(
+            angle(&sim, player),
+            before + own + Angle(TURN_HINT_STEP as u32)
+        );
+    }
+
+    #[test]
+    fn the_dead_sink_face_the_killer_and_wait_for_use() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = walker(&level, 64.0);
+        let barrel = barrel_north(&mut sim, &level);
+
+        sim.damage_mobj(player, None, Some(barrel), 200);
+        assert!(sim.world().get::<&Health>(player).unwrap().0 <= 0);
+        let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!((st.health, st.damage_count), (0, 100));
+
+        // walking, turning and firing do nothing now
+        let flail = InputCmd {
+            turn: 1.0,
+            fire: true,
+            ..WALK
+        };
+        for _ in 0..40 {
+            sim.run_tic(&mut level, flail);
+        }
+        assert_eq!(sim.world().get::<&Position>(player).unwrap().0.x, 64.0);
+        let st = *sim.world().get::<&PlayerStatus>(player).unwrap();
+        assert_eq!(st.view_height, DEAD_VIEW_HEIGHT);
+        // 90° is 18 tics of turning; the flash fades from then on
+        assert_eq!(angle(&sim, player), Angle::ANG90);
+        assert_eq!(st.damage_count, 100 - (40 - 18));
+        assert!(!sim.reborn());
+
+        sim.run_tic(
+            &mut level,
+            InputCmd {
+                use_act: true,
+                ..InputCmd::default()
+            },
+        );
+        assert!(sim.reborn());
+    }
 }
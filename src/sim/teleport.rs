@@ -0,0 +1,367 @@
+//! Teleporters – vanilla `p_telept.c`.
+//!
+//! A teleport line sends whoever crosses it to the `MT_TELEPORTMAN` thing
+//! standing in the sector tagged like the line.  Anything shootable already
+//! occupying the destination is telefragged.
+
+use glam::Vec2;
+use hecs::{Entity, World};
+use smallvec::SmallVec;
+
+use super::events::SimEvent;
+use super::spacial::{fetch_thing, relink_thing};
+use super::{
+    ActorFlags, Angle, Class, GameRules, Position, PrevPosition, ReactionTime, Rng, Subsector,
+    ThingGrid, Velocity, damage, mob,
+};
+use crate::defs::{Definitions, MobjFlags, Sound};
+use crate::world::{Aabb, Level, LinedefId};
+
+/// Editor number of the teleport destination (`MT_TELEPORTMAN`).
+pub const TELEPORT_DEST: u16 = 14;
+
+/// Tics a player stands frozen after arriving.
+const TELEPORT_FREEZE: i32 = 18;
+
+/// The arrival fog appears this far in front of the destination.
+const FOG_DIST: f32 = 20.0;
+
+/// First thing with editor number `doomednum` inside a sector tagged `tag`.
+/// Sectors are searched in index order, like vanilla.
+pub fn find_thing_in_sector(
+    world: &World,
+    level: &Level,
+    tag: u16,
+    doomednum: u16,
+) -> Option<Entity> {
+    let mut q = world.query::<(&Class, &Subsector)>();
+    q.iter()
+        .filter(|(_, (class, _))| class.0.doomednum == doomednum as i32)
+        .filter_map(|(e, (_, ss))| {
+            let sec = level.sector_of_subsector(ss.0);
+            (level.sectors[sec as usize].tag as u16 == tag).then_some((sec, e))
+        })
+        .min_by_key(|&(sec, _)| sec)
+        .map(|(_, e)| e)
+}
+
+/// Vanilla `EV_Teleport`: move `thing` to the destination in the sector
+/// tagged like `line`.  Returns `true` if it went.
+#[allow(clippy::too_many_arguments)]
+pub fn ev_teleport(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    events: &mut Vec<SimEvent>,
+    line: LinedefId,
+    side: usize,
+    thing: Entity,
+) -> bool {
+    let Ok((flags, class)) = world
+        .query_one_mut::<(&ActorFlags, &Class)>(thing)
+        .map(|(f, c)| (*f, *c))
+    else {
+        return false;
+    };
+
+    // don't teleport missiles, and only when crossing from the front
+    if flags.0.contains(MobjFlags::MISSILE) || side == 1 {
+        return false;
+    }
+
+    let tag = level.linedefs[line as usize].tag;
+    let Some(dest) = find_thing_in_sector(world, level, tag, TELEPORT_DEST) else {
+        return false;
+    };
+    let Ok((dest_pos, dest_angle)) = world
+        .query_one_mut::<(&Position, &Angle)>(dest)
+        .map(|(p, a)| (p.0, *a))
+    else {
+        return false;
+    };
+
+    let old = match world.get::<&Position>(thing) {
+        Ok(p) => p.0,
+        Err(_) => return false,
+    };
+
+    if !p_teleport_move(world, thing_grid, level, rng, rules, defs, thing, dest_pos) {
+        return false;
+    }
+
+    // fog at the source and in front of the destination
+    let ahead = dest_pos + dest_angle.unit() * FOG_DIST;
+    for at in [old, ahead] {
+        mob::spawn_tele_fog(world, thing_grid, level, defs, at);
+        events.push(SimEvent::Sound {
+            sound: Sound::telept,
+            origin: at,
+            full_volume: false,
+        });
+    }
+
+    if let Ok((pos, ang, vel, prev)) = world.query_one_mut::<(
+        &Position,
+        &mut Angle,
+        &mut Velocity,
+        Option<&mut PrevPosition>,
+    )>(thing)
+    {
+        *ang = dest_angle;
+        vel.0 = glam::Vec3::ZERO;
+        // no blending across the jump
+        if let Some(prev) = prev {
+            *prev = PrevPosition(*pos, *ang);
+        }
+    }
+    if class.0.id == "PLAYER" {
+        world.insert_one(thing, ReactionTime(TELEPORT_FREEZE)).ok();
+    }
+    true
+}
+
+/// Vanilla `P_TeleportMove`: telefrag whatever stands at `to`, then relink
+/// `thing` there.  Monsters only stomp on MAP30; elsewhere an occupied
+/// destination blocks them.
+#[allow(clippy::too_many_arguments)]
+fn p_teleport_move(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &Level,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    thing: Entity,
+    to: Vec2,
+) -> bool {
+    let Some((_, class, _)) = fetch_thing(world, thing) else {
+        return false;
+    };
+
+    let radius = class.0.radius as f32;
+    let bbox = Aabb {
+        min: to - Vec2::splat(radius),
+        max: to + Vec2::splat(radius),
+    };
+    let can_stomp = class.0.id == "PLAYER" || level.name.eq_ignore_ascii_case("MAP30");
+
+    /* PIT_StompThing */
+    let mut victims: SmallVec<[Entity; 2]> = SmallVec::new();
+    let clear = thing_grid.for_each_in_bbox(bbox, |other| {
+        let shootable = fetch_thing(world, other.ent)
+            .is_some_and(|(_, _, f)| f.0.contains(MobjFlags::SHOOTABLE));
+        if other.ent == thing || !shootable {
+            return true;
+        }
+        let block_dist = other.radius + radius;
+        if (other.pos.x - to.x).abs() >= block_dist || (other.pos.y - to.y).abs() >= block_dist {
+            return true; // didn't hit it
+        }
+        if !can_stomp {
+            return false;
+        }
+        victims.push(other.ent);
+        true
+    });
+    if !clear {
+        return false;
+    }
+    for victim in victims {
+        damage::p_damage_mobj(
+            world,
+            rng,
+            rules,
+            defs,
+            victim,
+            Some(thing),
+            Some(thing),
+            10_000,
+        );
+    }
+
+    /* relink */
+    if let Ok(p) = world.query_one_mut::<&mut Position>(thing) {
+        *p = Position(to, level.floor_height_at(to));
+    }
+    relink_thing(world, thing_grid, level, thing);
+    true
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs::{self, State},
+        sim::{Animation, InputCmd, TicRunner},
+        world::testmap,
+    };
+
+    const DEST: Vec2 = Vec2::new(64.0, 64.0);
+
+    /// Three-room map where the M→B doorway (crossed westwards from B) is
+    /// a teleporter of type `special` leading to a destination in room A.
+    fn teleport_map(special: u16) -> (Level, TicRunner, Entity) {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let line = level
+            .linedefs
+            .iter()
+            .position(|l| l.right_sidedef == Some(2) && l.left_sidedef == Some(1))
+            .unwrap();
+        level.linedefs[line].special = special;
+        level.linedefs[line].tag = 7;
+        level.sectors[0].tag = 7;
+
+        let mut sim = TicRunner::new(&level);
+        let dest = defs::by_doomednum(TELEPORT_DEST).unwrap();
+        sim.spawn_mobj(&level, dest, DEST.x, DEST.y, Angle::ANG90, 0);
+
+        let player = sim.spawn_mobj(
+            &level,
+            defs::by_id("PLAYER").unwrap(),
+            170.0,
+            64.0,
+            Angle::ANG180,
+            2,
+        );
+        sim.set_player(player);
+        (level, sim, player)
+    }
+
+    /// Walk west until the player lands in room A (or give up).
+    fn walk_through(level: &mut Level, sim: &mut TicRunner, player: Entity) -> bool {
+        let walk = InputCmd {
+            forward: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            sim.run_tic(level, walk);
+            if sim.world().get::<&Position>(player).unwrap().0 == DEST {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn count(sim: &TicRunner, id: &str) -> usize {
+        sim.world()
+            .query::<&Class>()
+            .iter()
+            .filter(|(_, c)| c.0.id == id)
+            .count()
+    }
+
+    #[test]
+    fn finds_destination_by_tag() {
+        let (level, sim, _) = teleport_map(97);
+        assert!(find_thing_in_sector(sim.world(), &level, 7, TELEPORT_DEST).is_some());
+        assert!(find_thing_in_sector(sim.world(), &level, 8, TELEPORT_DEST).is_none());
+    }
+
+    #[test]
+    fn wr_teleport_moves_player() {
+        let (mut level, mut sim, player) = teleport_map(97);
+        assert!(walk_through(&mut level, &mut sim, player));
+
+        let w = sim.world();
+        assert_eq!(*w.get::<&Angle>(player).unwrap(), Angle::ANG90);
+        assert_eq!(w.get::<&Velocity>(player).unwrap().0, glam::Vec3::ZERO);
+        assert_eq!(w.get::<&Subsector>(player).unwrap().0, 0);
+        assert_eq!(w.get::<&PrevPosition>(player).unwrap().0.0, DEST);
+        assert!(w.get::<&ReactionTime>(player).unwrap().0 > 0);
+        assert_eq!(count(&sim, "TFOG"), 2);
+
+        let sounds = sim
+            .drain_events()
+            .filter(|e| {
+                matches!(
+                    e,
+                    SimEvent::Sound {
+                        sound: Sound::telept,
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(sounds, 2);
+
+        // repeatable line keeps its special
+        assert!(level.linedefs.iter().any(|l| l.special == 97));
+    }
+
+    #[test]
+    fn player_is_frozen_after_arrival() {
+        let (mut level, mut sim, player) = teleport_map(97);
+        assert!(walk_through(&mut level, &mut sim, player));
+
+        let walk = InputCmd {
+            forward: 1.0,
+            turn: 1.0,
+            ..Default::default()
+        };
+        for _ in 0..TELEPORT_FREEZE - 2 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert_eq!(sim.world().get::<&Position>(player).unwrap().0, DEST);
+        assert_eq!(*sim.world().get::<&Angle>(player).unwrap(), Angle::ANG90);
+
+        for _ in 0..4 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert_ne!(sim.world().get::<&Position>(player).unwrap().0, DEST);
+    }
+
+    #[test]
+    fn w1_teleport_clears_special() {
+        let (mut level, mut sim, player) = teleport_map(39);
+        assert!(walk_through(&mut level, &mut sim, player));
+        assert!(level.linedefs.iter().all(|l| l.special == 0));
+    }
+
+    #[test]
+    fn telefrag_kills_occupant() {
+        let (mut level, mut sim, player) = teleport_map(97);
+        let info = defs::by_id("POSSESSED").unwrap();
+        let victim = sim.spawn_mobj(&level, info, DEST.x, DEST.y, 0.0, 0);
+
+        assert!(walk_through(&mut level, &mut sim, player));
+        let flags = sim.world().get::<&ActorFlags>(victim).unwrap().0;
+        assert!(!flags.contains(MobjFlags::SHOOTABLE));
+        assert!(flags.contains(MobjFlags::CORPSE));
+        let state = sim.world().get::<&Animation>(victim).unwrap().state;
+        assert!(state >= info.xdeathstate && state != State::NULL);
+    }
+
+    /// Shove an imp westwards across the teleporter and report whether
+    /// the thing parked on the destination survived.
+    fn imp_crossing(map: &str) -> bool {
+        let (mut level, mut sim, _) = teleport_map(97);
+        level.name = map.into();
+        let info = defs::by_id("POSSESSED").unwrap();
+        let occupant = sim.spawn_mobj(&level, info, DEST.x, DEST.y, 0.0, 0);
+
+        let imp = defs::by_id("TROOP").unwrap();
+        let imp = sim.spawn_mobj(&level, imp, 200.0, 100.0, Angle::ANG180, 2);
+        sim.world_mut().get::<&mut Velocity>(imp).unwrap().0.x = -8.0;
+        for _ in 0..20 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+
+        let flags = sim.world().get::<&ActorFlags>(occupant).unwrap().0;
+        flags.contains(MobjFlags::SHOOTABLE)
+    }
+
+    #[test]
+    fn monsters_only_telefrag_on_map30() {
+        assert!(imp_crossing("E1M1"));
+        assert!(!imp_crossing("MAP30"));
+    }
+}
@@ -1,18 +1,146 @@
 use hecs::World;
-use std::time::{Duration, Instant};
+use std::sync::Arc;
 
-use super::{ThingGrid, mob, systems};
-use crate::world::Level;
+use super::ceilings::{self, Ceiling};
+use super::doors::{self, Door};
+use super::events::{LevelExit, SimEvent};
+use super::floors::{self, Floor};
+use super::interp::SectorHeights;
+use super::lights::{self, Light};
+use super::noise::SectorSounds;
+use super::plats::{self, Platform};
+use super::specials::{SpecialCtx, table::Activation};
+use super::switches::{self, Button, SwitchList};
+use super::weapons::{self, WeaponCtx, Weapons};
+use super::{
+    ActorFlags, Angle, CheatFlags, Cheats, GameRules, Health, InputCmd, PhysicsConfig,
+    PlayerStatus, Position, PrevPosition, Rng, SpawnPoint, ThingGrid, cheats, damage, enemy,
+    interp, mob, movers, respawn, spacial, specials, systems,
+};
+use crate::defs::{Definitions, MobjFlags, MobjInfo};
+use crate::world::{BlockmapQuery, Level, LinedefId, SectorId, SubsectorId, Thing};
 
 pub const SIM_FPS: u32 = 35;
 pub const DT: f32 = 1.0 / SIM_FPS as f32;
-const TIC: Duration = Duration::from_micros(1_000_000 / SIM_FPS as u64);
+/// Vanilla `MAXPLAYERS`.
+pub const MAX_PLAYERS: usize = 4;
+/// Doomednum of a deathmatch start.
+const DEATHMATCH_START: u16 = 11;
+
+/// Anything that can hand the sim one `InputCmd` per tic: the live
+/// keyboard, a demo being played back, a scripted test…
+pub trait InputSource {
+    /// Command for the next tic, or `None` when the stream has ended.
+    fn next_cmd(&mut self) -> Option<InputCmd>;
+}
+
+/// Live input: the frontend's latest command, repeated for every tic
+/// run this frame.  Edge-triggered buttons fire only once.
+impl InputSource for InputCmd {
+    fn next_cmd(&mut self) -> Option<InputCmd> {
+        let cmd = *self;
+        self.use_act = false;
+        self.weapon = None;
+        Some(cmd)
+    }
+}
+
+/// What the level holds to be found, for the intermission percentages
+/// (vanilla `totalkills`, `totalitems`, `totalsecret`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LevelTotals {
+    pub kills: i32,
+    pub items: i32,
+    pub secrets: i32,
+}
+
+/// Vanilla `skill_t`; the menu's five choices, easiest first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Skill {
+    Baby,
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Nightmare,
+}
+
+impl Skill {
+    pub const ALL: [Skill; 5] = [
+        Skill::Baby,
+        Skill::Easy,
+        Skill::Medium,
+        Skill::Hard,
+        Skill::Nightmare,
+    ];
+
+    /// From vanilla's 0-based `gameskill`.
+    pub fn from_index(i: u8) -> Option<Self> {
+        Self::ALL.get(i as usize).copied()
+    }
+
+    pub fn index(self) -> u8 {
+        self as u8
+    }
+
+    /// The `THINGS` option bit of things placed on this skill.
+    pub fn thing_bit(self) -> u8 {
+        match self {
+            Skill::Baby | Skill::Easy => 1,
+            Skill::Medium => 2,
+            Skill::Hard | Skill::Nightmare => 4,
+        }
+    }
+}
 
 /// Owns the ECS world and drives all game‑logic systems.
 pub struct TicRunner {
     world: World,
     thing_grid: ThingGrid,
-    last: Instant,
+    /// Player entities in player order; player `n` takes the `n`th
+    /// command of each tic.
+    players: Vec<hecs::Entity>,
+    /// Where players 1‥4 start, from the map's `THINGS` (vanilla
+    /// `playerstarts`).
+    player_starts: [Option<Thing>; MAX_PLAYERS],
+    /// Deathmatch spawn spots (doomednum 11), kept for when deathmatch
+    /// respawning lands.
+    deathmatch_starts: Vec<Thing>,
+    rng: Rng,
+    gametic: u32,
+    switches: SwitchList,
+    buttons: Vec<Button>,
+    lights: Vec<Light>,
+    ceilings: Vec<Ceiling>,
+    /// Running lifts (vanilla `activeplats`).
+    plats: Vec<Platform>,
+    doors: Vec<Door>,
+    floors: Vec<Floor>,
+    events: Vec<SimEvent>,
+    /// Fraction of a tic the frontend is drawing at (1 = latest tic).
+    frame_alpha: f32,
+    /// `PrevPosition`s are stale (level/savegame load); draw the current
+    /// positions until the next tic has refreshed them.
+    interpolation_reset: bool,
+    /// Sector heights to blend from, like `PrevPosition`.
+    sector_heights: SectorHeights,
+    totals: LevelTotals,
+    /// Set by the first exit triggered; the game ends the level on it.
+    exit: Option<LevelExit>,
+    /// A dead player pressed use; the game starts the map over on it.
+    reborn: bool,
+    physics: PhysicsConfig,
+    skill: Skill,
+    /// Nightmare's fast and respawning monsters, on any skill.
+    rules: GameRules,
+    /// The info tables, DeHackEd patches and all.
+    defs: Arc<Definitions>,
+    /// More than one player: the things flagged multiplayer-only spawn.
+    netgame: bool,
+    /// Players' shots aim up or down at what's in line.
+    autoaim: bool,
+    /// Who each sector last heard firing.
+    sounds: SectorSounds,
 }
 
 impl TicRunner {
@@ -20,7 +148,32 @@ impl TicRunner {
         Self {
             world: World::new(),
             thing_grid: ThingGrid::new(level.blockmap.origin),
-            last: Instant::now(),
+            players: Vec::new(),
+            player_starts: Default::default(),
+            deathmatch_starts: Vec::new(),
+            rng: Rng::default(),
+            gametic: 0,
+            switches: SwitchList::default(),
+            buttons: Vec::new(),
+            lights: Vec::new(),
+            ceilings: Vec::new(),
+            plats: Vec::new(),
+            doors: Vec::new(),
+            floors: Vec::new(),
+            events: Vec::new(),
+            frame_alpha: 1.0,
+            interpolation_reset: true,
+            sector_heights: SectorHeights::default(),
+            totals: LevelTotals::default(),
+            exit: None,
+            reborn: false,
+            physics: PhysicsConfig::default(),
+            skill: Skill::default(),
+            rules: GameRules::VANILLA,
+            defs: Definitions::shared_builtin(),
+            netgame: false,
+            autoaim: true,
+            sounds: SectorSounds::new(level.sectors.len()),
         }
     }
 
@@ -29,48 +182,866 @@ impl TicRunner {
         &self.world
     }
 
+    /// Blockmap scratch for walks outside the tic, like the chase
+    /// camera's.
+    #[inline]
+    pub fn line_query(&self) -> &BlockmapQuery {
+        self.thing_grid.line_query()
+    }
+
+    /// Things linked into subsector `ss`.
+    #[inline]
+    pub fn things_in_subsector(&self, ss: SubsectorId) -> &[hecs::Entity] {
+        self.thing_grid.things_in_subsector(ss)
+    }
+
+    /// Subsector `ent` is linked into, if any.
+    #[inline]
+    pub fn subsector_of(&self, ent: hecs::Entity) -> Option<SubsectorId> {
+        self.thing_grid.subsector_of(ent)
+    }
+
+    /// Relink `ent` after its `Position` was changed by hand; see
+    /// [`spacial::relink_thing`].
+    pub fn relink_thing(&mut self, level: &Level, ent: hecs::Entity) -> Option<SubsectorId> {
+        spacial::relink_thing(&mut self.world, &mut self.thing_grid, level, ent)
+    }
+
     #[inline]
     pub fn world_mut(&mut self) -> &mut hecs::World {
         &mut self.world
     }
 
-    /// Spawn a monster/item entity and return its `Entity` handle.
+    /// Entity that receives player 1's `InputCmd`.  Gets a fresh
+    /// `PlayerStatus` and `Weapons` unless it already carries them.
+    pub fn set_player(&mut self, player: hecs::Entity) {
+        match self.players.first_mut() {
+            Some(first) => *first = player,
+            None => self.players.push(player),
+        }
+        self.equip_player(player);
+    }
+
+    /// Add the next player; returns its number (0 for player 1), which is
+    /// also where its command sits in [`TicRunner::run_tic_cmds`].
+    pub fn add_player(&mut self, player: hecs::Entity) -> usize {
+        self.players.push(player);
+        self.equip_player(player);
+        self.players.len() - 1
+    }
+
+    fn equip_player(&mut self, player: hecs::Entity) {
+        if self.world.get::<&PlayerStatus>(player).is_err() {
+            let mut status = PlayerStatus::new(&self.defs);
+            if let Ok(pos) = self.world.get::<&Position>(player) {
+                status.view_z = pos.1 + status.view_height;
+            }
+            // a patch can change what a fresh player starts with
+            if let Ok(mut health) = self.world.get::<&mut Health>(player) {
+                health.0 = status.health;
+            }
+            self.world.insert_one(player, status).ok();
+        }
+        if self.world.get::<&Weapons>(player).is_err() {
+            self.world
+                .insert_one(player, Weapons::reborn(&self.defs))
+                .ok();
+        }
+    }
+
+    /// Player 1.
+    #[inline]
+    pub fn player(&self) -> Option<hecs::Entity> {
+        self.players.first().copied()
+    }
+
+    /// Every player, in player order.
+    #[inline]
+    pub fn players(&self) -> &[hecs::Entity] {
+        &self.players
+    }
+
+    /// Player `n`'s start (0 for player 1), if the map has one.
+    pub fn player_start(&self, n: usize) -> Option<&Thing> {
+        self.player_starts.get(n)?.as_ref()
+    }
+
+    /// The map's deathmatch starts.
+    pub fn deathmatch_starts(&self) -> &[Thing] {
+        &self.deathmatch_starts
+    }
+
+    /// Vanilla `P_SpawnPlayer`: put the next player at its start.  `None`
+    /// if the map has no start for it.
+    pub fn spawn_player(&mut self, level: &Level) -> Option<hecs::Entity> {
+        let start = self.player_start(self.players.len())?.clone();
+        let info = *self
+            .defs
+            .by_id("PLAYER")
+            .expect("PLAYER missing from MOBJINFO");
+        let player = self.spawn_mobj(
+            level,
+            &info,
+            start.pos.x,
+            start.pos.y,
+            start.angle,
+            start.sub_sector,
+        );
+        self.add_player(player);
+        Some(player)
+    }
+
+    /// Tics simulated since the level started.
+    #[inline]
+    pub fn gametic(&self) -> u32 {
+        self.gametic
+    }
+
+    #[inline]
+    pub fn rng(&self) -> &Rng {
+        &self.rng
+    }
+
+    /// Re-seed the gameplay RNG (demo playback restores the recorded seed).
+    #[inline]
+    pub fn set_rng(&mut self, rng: Rng) {
+        self.rng = rng;
+    }
+
+    /// Skill the map's things were spawned for.
+    #[inline]
+    pub fn skill(&self) -> Skill {
+        self.skill
+    }
+
+    /// Set before [`Self::spawn_map_thing`]; it leaves out the things
+    /// that aren't placed on `skill`.
+    pub fn set_skill(&mut self, skill: Skill) {
+        self.skill = skill;
+    }
+
+    #[inline]
+    pub fn rules(&self) -> GameRules {
+        self.rules
+    }
+
+    /// Vanilla unless set; [`GameRules::for_skill`] is what the skill
+    /// itself plays by.
+    pub fn set_rules(&mut self, rules: GameRules) {
+        self.rules = rules;
+    }
+
+    #[inline]
+    pub fn definitions(&self) -> &Arc<Definitions> {
+        &self.defs
+    }
+
+    /// The compiled-in tables unless set.  Set before any things spawn:
+    /// those already out keep the class they were spawned with.
+    pub fn set_definitions(&mut self, defs: Arc<Definitions>) {
+        self.defs = defs;
+    }
+
+    #[inline]
+    pub fn netgame(&self) -> bool {
+        self.netgame
+    }
+
+    /// Set before [`Self::spawn_map_thing`]; outside a netgame it leaves
+    /// out the things placed for multiplayer only.
+    pub fn set_netgame(&mut self, netgame: bool) {
+        self.netgame = netgame;
+    }
+
+    #[inline]
+    pub fn autoaim(&self) -> bool {
+        self.autoaim
+    }
+
+    /// Off, the players' shots and missiles fly level; vanilla (and every
+    /// demo) has it on.
+    pub fn set_autoaim(&mut self, autoaim: bool) {
+        self.autoaim = autoaim;
+    }
+
+    /// How things move and collide.
+    #[inline]
+    pub fn physics(&self) -> &PhysicsConfig {
+        &self.physics
+    }
+
+    #[inline]
+    pub fn set_physics(&mut self, physics: PhysicsConfig) {
+        self.physics = physics;
+    }
+
+    /// The blockmap and subsector links of every thing.
+    #[inline]
+    pub fn thing_grid(&self) -> &ThingGrid {
+        &self.thing_grid
+    }
+
+    #[inline]
+    pub(super) fn thing_grid_mut(&mut self) -> &mut ThingGrid {
+        &mut self.thing_grid
+    }
+
+    #[inline]
+    pub(super) fn set_gametic(&mut self, gametic: u32) {
+        self.gametic = gametic;
+    }
+
+    /// SW1↔SW2 texture pairs, built from the level's `TextureBank`.
+    #[inline]
+    pub fn set_switch_list(&mut self, switches: SwitchList) {
+        self.switches = switches;
+    }
+
+    #[inline]
+    pub fn switch_list(&self) -> &SwitchList {
+        &self.switches
+    }
+
+    /// Switches waiting to pop back.
+    #[inline]
+    pub fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    #[inline]
+    pub(super) fn set_buttons(&mut self, buttons: Vec<Button>) {
+        self.buttons = buttons;
+    }
+
+    /// Start the sector light effects (flicker, strobe, glow…) and the
+    /// timed doors.  Call once at level start, after seeding the RNG.
+    pub fn spawn_specials(&mut self, level: &mut Level) {
+        self.totals.secrets = level
+            .sectors
+            .iter()
+            .filter(|s| s.special == specials::SECRET_SECTOR)
+            .count() as i32;
+        self.lights = lights::spawn_lights(level, &mut self.rng);
+        self.doors = doors::spawn_sector_doors(level);
+    }
+
+    /// Kills, items and secrets the level started with.
+    #[inline]
+    pub fn totals(&self) -> LevelTotals {
+        self.totals
+    }
+
+    #[inline]
+    pub(super) fn set_totals(&mut self, totals: LevelTotals) {
+        self.totals = totals;
+    }
+
+    /// The exit that ended the level, once one has been triggered.
+    #[inline]
+    pub fn exit(&self) -> Option<LevelExit> {
+        self.exit
+    }
+
+    /// Has a dead player asked to start over?
+    #[inline]
+    pub fn reborn(&self) -> bool {
+        self.reborn
+    }
+
+    /// What a line special set off now can act on.
+    pub(super) fn special_ctx<'a>(&'a mut self, level: &'a mut Level) -> SpecialCtx<'a> {
+        SpecialCtx {
+            world: &mut self.world,
+            thing_grid: &mut self.thing_grid,
+            level,
+            rng: &mut self.rng,
+            rules: self.rules,
+            skill: self.skill,
+            defs: &self.defs,
+            switches: &self.switches,
+            buttons: &mut self.buttons,
+            ceilings: &mut self.ceilings,
+            plats: &mut self.plats,
+            doors: &mut self.doors,
+            floors: &mut self.floors,
+            events: &mut self.events,
+        }
+    }
+
+    /// Monster `actor` couldn't step past `lines` (the special lines its
+    /// failed move touched): let it use them, which opens the doors
+    /// monsters may open.  See [`specials::p_monster_use_lines`].
+    pub fn monster_use_lines(
+        &mut self,
+        level: &mut Level,
+        actor: hecs::Entity,
+        lines: &[LinedefId],
+    ) -> bool {
+        specials::p_monster_use_lines(&mut self.special_ctx(level), actor, lines)
+    }
+
+    /// Step monster `actor` along its [`MoveDir`](super::MoveDir), the
+    /// way vanilla `P_TryWalk` does: floaters rise or sink over ledges
+    /// and doors in the way get opened.  `false` if it couldn't move and
+    /// wants a new heading.
+    pub fn monster_try_walk(&mut self, level: &mut Level, actor: hecs::Entity) -> bool {
+        let cfg = self.physics;
+        enemy::p_try_walk(&mut self.special_ctx(level), &cfg, actor)
+    }
+
+    /// Running sector light effects.
+    #[inline]
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Override sector `sector`'s light; see [`lights::set_sector_light`].
+    pub fn set_sector_light(
+        &mut self,
+        level: &mut Level,
+        sector: SectorId,
+        light: f32,
+    ) -> Result<(), lights::NoSuchSector> {
+        lights::set_sector_light(level, &mut self.lights, sector, light)
+    }
+
+    #[inline]
+    pub(super) fn set_lights(&mut self, lights: Vec<Light>) {
+        self.lights = lights;
+    }
+
+    /// Running ceiling movers (crushers).
+    #[inline]
+    pub fn ceilings(&self) -> &[Ceiling] {
+        &self.ceilings
+    }
+
+    #[inline]
+    pub(super) fn set_ceilings(&mut self, ceilings: Vec<Ceiling>) {
+        self.ceilings = ceilings;
+    }
+
+    /// Running lifts.
+    #[inline]
+    pub fn plats(&self) -> &[Platform] {
+        &self.plats
+    }
+
+    /// Running doors.
+    #[inline]
+    pub fn doors(&self) -> &[Door] {
+        &self.doors
+    }
+
+    #[inline]
+    pub(super) fn set_doors(&mut self, doors: Vec<Door>) {
+        self.doors = doors;
+    }
+
+    /// Running floor movers.
+    #[inline]
+    pub fn floors(&self) -> &[Floor] {
+        &self.floors
+    }
+
+    #[inline]
+    pub(super) fn set_floors(&mut self, floors: Vec<Floor>) {
+        self.floors = floors;
+    }
+
+    #[inline]
+    pub(super) fn set_plats(&mut self, plats: Vec<Platform>) {
+        self.plats = plats;
+    }
+
+    /// Blend factor for [`interpolated`](Self::interpolated); 1.0 (the
+    /// default) draws every thing where the last tic left it.
+    #[inline]
+    pub fn set_frame_alpha(&mut self, alpha: f32) {
+        self.frame_alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    #[inline]
+    pub fn frame_alpha(&self) -> f32 {
+        if self.interpolation_reset {
+            1.0
+        } else {
+            self.frame_alpha
+        }
+    }
+
+    /// Snap instead of blending until the next tic, e.g. after moving
+    /// everything at once.
+    #[inline]
+    pub fn reset_interpolation(&mut self) {
+        self.interpolation_reset = true;
+    }
+
+    /// Blend `level`'s floors and ceilings to the current frame alpha for
+    /// drawing; [`TicRunner::unblend_sectors`] puts them back before the
+    /// next tic runs.
+    pub fn blend_sectors(&mut self, level: &mut Level) {
+        let alpha = self.frame_alpha();
+        self.sector_heights.blend(level, alpha);
+    }
+
+    /// Undo [`TicRunner::blend_sectors`].
+    pub fn unblend_sectors(&mut self, level: &mut Level) {
+        self.sector_heights.restore(level);
+    }
+
+    /// Position and angle of `ent` at the current frame alpha.
+    pub fn interpolated(&self, ent: hecs::Entity) -> Option<(Position, Angle)> {
+        let mut q = self
+            .world
+            .query_one::<(&Position, &Angle, Option<&PrevPosition>)>(ent)
+            .ok()?;
+        let (pos, angle, prev) = q.get()?;
+        Some(match prev {
+            Some(prev) => interp::lerp(prev, pos, angle, self.frame_alpha()),
+            None => (*pos, *angle),
+        })
+    }
+
+    /// How far above its feet `player` sees from this tic (vanilla
+    /// `viewz` less its z): the view height with bob and landing dip,
+    /// kept under low ceilings.  Add it to the interpolated position.
+    pub fn view_height(&self, player: hecs::Entity) -> Option<f32> {
+        let mut q = self
+            .world
+            .query_one::<(&Position, &PlayerStatus)>(player)
+            .ok()?;
+        let (pos, st) = q.get()?;
+        Some(st.view_z - pos.1)
+    }
+
+    /// Flip a cheat on the player; `Some(on)` with its new state, `None`
+    /// when there is no player.
+    pub fn toggle_cheat(&mut self, cheat: CheatFlags) -> Option<bool> {
+        let player = self.player()?;
+        cheats::toggle_cheat(&mut self.world, player, cheat)
+    }
+
+    /// Cheats currently active on the player.
+    pub fn cheats(&self) -> CheatFlags {
+        self.player()
+            .and_then(|p| self.world.get::<&Cheats>(p).ok().map(|c| c.0))
+            .unwrap_or_default()
+    }
+
+    /// Hurt `target` as if `inflictor` hit it, blaming `source`.
+    pub fn damage_mobj(
+        &mut self,
+        target: hecs::Entity,
+        inflictor: Option<hecs::Entity>,
+        source: Option<hecs::Entity>,
+        damage: i32,
+    ) {
+        damage::p_damage_mobj(
+            &mut self.world,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            target,
+            inflictor,
+            source,
+            damage,
+        );
+    }
+
+    /// Re-fit everything around `sector` after its floor or ceiling moved
+    /// (vanilla `P_ChangeSector`); `true` if something no longer fits.
+    pub fn change_sector(&mut self, level: &Level, sector: SectorId, crush: bool) -> bool {
+        movers::p_change_sector(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            self.gametic,
+            sector,
+            crush,
+        )
+    }
+
+    /// Events raised since the last call (sounds, …).
+    #[inline]
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, SimEvent> {
+        self.events.drain(..)
+    }
+
+    /// Only the XY movement of one tic ([`xy_movement_system`](super::xy_movement_system)), for the
+    /// benches; everything else in the world stands still.
+    pub fn run_xy_movement(&mut self, level: &mut Level) {
+        super::xy_movement_system(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            self.skill,
+            &self.defs,
+            &self.switches,
+            &mut self.buttons,
+            &mut self.ceilings,
+            &mut self.plats,
+            &mut self.doors,
+            &mut self.floors,
+            &mut self.events,
+        );
+    }
+
+    /// Spawn a monster/item entity and return its `Entity` handle;
+    /// `angle` is an [`Angle`] or `f32` radians.
     #[inline]
     pub fn spawn_mobj(
         &mut self,
         level: &Level,
-        info: &'static crate::defs::MobjInfo,
+        info: &MobjInfo,
         x: f32,
         y: f32,
-        angle: f32,
-        subsector: u16,
+        angle: impl Into<Angle>,
+        subsector: SubsectorId,
     ) -> hecs::Entity {
         mob::spawn_mobj(
             &mut self.world,
             &mut self.thing_grid,
             level,
+            &self.defs,
             info,
             x,
             y,
-            angle,
+            angle.into(),
             subsector,
         )
     }
 
-    /// Advance enough tics to synchronise simulation with real time.
-    pub fn pump(&mut self, level: &Level) {
-        while self.last.elapsed() >= TIC {
-            self.tick(level);
-            self.last += TIC;
+    /// Spawn one map `THINGS` entry (vanilla `P_SpawnMapThing`), counting
+    /// it towards the level totals.  Player and deathmatch starts are only
+    /// remembered; [`TicRunner::spawn_player`] uses them.  `None` for
+    /// those, types without a class, things not placed on the current
+    /// skill and, outside a netgame, multiplayer-only ones.  Deaf things
+    /// lie in ambush.  The thing remembers `thing` as its
+    /// [`SpawnPoint`].
+    pub fn spawn_map_thing(&mut self, level: &Level, thing: &Thing) -> Option<hecs::Entity> {
+        if thing.type_id == DEATHMATCH_START {
+            self.deathmatch_starts.push(thing.clone());
+            return None;
+        }
+        if let Some(slot) = (thing.type_id as usize)
+            .checked_sub(1)
+            .and_then(|n| self.player_starts.get_mut(n))
+        {
+            *slot = Some(thing.clone());
+            return None;
+        }
+        if thing.skills & self.skill.thing_bit() == 0 || (thing.multiplayer && !self.netgame) {
+            return None;
+        }
+        let info = *self.defs.by_doomednum(thing.type_id)?;
+        if info.flags.contains(MobjFlags::COUNTKILL) {
+            self.totals.kills += 1;
+        }
+        if info.flags.contains(MobjFlags::COUNTITEM) {
+            self.totals.items += 1;
+        }
+        let ent = self.spawn_mobj(
+            level,
+            &info,
+            thing.pos.x,
+            thing.pos.y,
+            thing.angle,
+            thing.sub_sector,
+        );
+        if thing.is_deaf {
+            if let Ok(mut flags) = self.world.get::<&mut ActorFlags>(ent) {
+                flags.0.insert(MobjFlags::AMBUSH);
+            }
+        }
+        self.world.insert_one(ent, SpawnPoint(thing.clone())).ok();
+        Some(ent)
+    }
+
+    /// Run up to `tics` tics, pulling one command per tic from `input`;
+    /// stops early when it runs dry.  Returns how many ran.
+    pub fn run_tics(&mut self, level: &mut Level, input: &mut dyn InputSource, tics: u32) -> u32 {
+        for ran in 0..tics {
+            let Some(cmd) = input.next_cmd() else {
+                return ran;
+            };
+            self.run_tic(level, cmd);
+        }
+        tics
+    }
+
+    /// Run exactly one tic with `cmd` for player 1, independent of
+    /// wall-clock time.
+    pub fn run_tic(&mut self, level: &mut Level, cmd: InputCmd) {
+        self.run_tic_cmds(level, &[cmd]);
+    }
+
+    /// Run one tic with a command per player, in player order; players
+    /// past the end of `cmds` stand still.
+    pub fn run_tic_cmds(&mut self, level: &mut Level, cmds: &[InputCmd]) {
+        interp::store_prev_positions(&mut self.world);
+        self.sector_heights.store(level);
+        self.interpolation_reset = false;
+        let first_event = self.events.len();
+
+        for i in 0..self.players.len() {
+            let player = self.players[i];
+            let cmd = cmds.get(i).copied().unwrap_or_default();
+            let dead = self.world.get::<&Health>(player).is_ok_and(|h| h.0 <= 0);
+            if dead {
+                self.reborn |= systems::p_death_think(&mut self.world, player, cmd);
+            } else {
+                systems::player_input(&mut self.world, level, &self.physics, player, cmd);
+                if cmd.use_act {
+                    specials::p_use_lines(&mut self.special_ctx(level), player);
+                }
+            }
+            weapons::p_player_weapons(
+                &mut WeaponCtx {
+                    world: &mut self.world,
+                    thing_grid: &mut self.thing_grid,
+                    level,
+                    rng: &mut self.rng,
+                    rules: self.rules,
+                    defs: &self.defs,
+                    events: &mut self.events,
+                    sounds: &mut self.sounds,
+                    player,
+                    fire: cmd.fire && !dead,
+                    autoaim: self.autoaim,
+                },
+                cmd.weapon.filter(|_| !dead),
+            );
+            if !dead {
+                systems::player_counters(&mut self.world, player);
+            }
+        }
+        self.tick(level);
+
+        // gun specials go off once everything has moved
+        let shots: Vec<_> = self.events[first_event..]
+            .iter()
+            .filter_map(|e| match *e {
+                SimEvent::LineShot {
+                    line,
+                    side,
+                    shooter,
+                } => Some((line, side, shooter)),
+                _ => None,
+            })
+            .collect();
+        for (line, side, shooter) in shots {
+            specials::try_activate(
+                &mut self.special_ctx(level),
+                line,
+                side,
+                Activation::Shoot,
+                shooter,
+            );
+        }
+
+        if self.exit.is_none() {
+            self.exit = self.events.iter().find_map(|e| match e {
+                SimEvent::Exit(exit) => Some(*exit),
+                _ => None,
+            });
         }
     }
 
     /* ---------------------------------------------------------------- */
     /* internal: run one fixed‑rate game tic                             */
     /* ---------------------------------------------------------------- */
-    fn tick(&mut self, level: &Level) {
-        systems::animation(&mut self.world);
-        systems::physics(&mut self.world, &mut self.thing_grid, level);
-        // TODO: AI, door, platform systems go here.
+    fn tick(&mut self, level: &mut Level) {
+        systems::animation(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            &self.sounds,
+            &mut self.events,
+        );
+        if self.rules.respawn_monsters {
+            respawn::respawn_monsters(
+                &mut self.world,
+                &mut self.thing_grid,
+                level,
+                &self.physics,
+                &self.defs,
+                &mut self.rng,
+                &mut self.events,
+                self.gametic,
+            );
+        }
+        systems::physics(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            self.skill,
+            &self.defs,
+            &self.switches,
+            &mut self.buttons,
+            &mut self.ceilings,
+            &mut self.plats,
+            &mut self.doors,
+            &mut self.floors,
+            &mut self.events,
+        );
+        for &player in &self.players {
+            systems::p_calc_height(&mut self.world, level, self.gametic, player);
+            specials::p_player_in_special_sector(&mut self.world, level, &mut self.events, player);
+        }
+        switches::run_buttons(level, &mut self.buttons, &mut self.events);
+        lights::run_lights(level, &mut self.lights, &mut self.rng);
+        ceilings::run_ceilings(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            &mut self.events,
+            self.gametic,
+            &mut self.ceilings,
+        );
+        plats::run_plats(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            &mut self.events,
+            self.gametic,
+            &mut self.plats,
+        );
+        doors::run_doors(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            &mut self.events,
+            self.gametic,
+            &mut self.doors,
+        );
+        floors::run_floors(
+            &mut self.world,
+            &mut self.thing_grid,
+            level,
+            &self.physics,
+            &mut self.rng,
+            self.rules,
+            &self.defs,
+            &mut self.events,
+            self.gametic,
+            &mut self.floors,
+        );
+        self.gametic += 1;
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::testmap;
+    use glam::Vec2;
+
+    fn start(type_id: u16, x: f32, y: f32) -> Thing {
+        Thing {
+            pos: Vec2::new(x, y),
+            angle: Angle::ZERO,
+            type_id,
+            skills: 7,
+            is_deaf: false,
+            multiplayer: false,
+            sub_sector: 0,
+        }
+    }
+
+    #[test]
+    fn multiplayer_things_stay_out_of_single_player() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        // a health bonus, which counts towards the items
+        let bonus = Thing {
+            multiplayer: true,
+            ..start(2014, 64.0, 64.0)
+        };
+        let mut sim = TicRunner::new(&level);
+        assert!(sim.spawn_map_thing(&level, &bonus).is_none());
+        assert!(sim.world().is_empty());
+        assert_eq!(sim.totals().items, 0);
+
+        sim.set_netgame(true);
+        assert!(sim.spawn_map_thing(&level, &bonus).is_some());
+        assert_eq!(sim.totals().items, 1);
+    }
+
+    #[test]
+    fn players_spawn_at_their_starts_and_follow_their_own_commands() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        for thing in [
+            start(2, 64.0, 96.0),
+            start(11, 200.0, 64.0),
+            start(1, 64.0, 32.0),
+            start(11, 300.0, 64.0),
+        ] {
+            assert!(sim.spawn_map_thing(&level, &thing).is_none());
+        }
+        assert_eq!(sim.deathmatch_starts().len(), 2);
+        assert!(sim.world().is_empty());
+
+        let one = sim.spawn_player(&level).unwrap();
+        let two = sim.spawn_player(&level).unwrap();
+        assert!(sim.spawn_player(&level).is_none(), "no player 3 start");
+        assert_eq!(sim.players(), [one, two]);
+        assert_eq!(sim.player(), Some(one));
+        let pos = |sim: &TicRunner, p| sim.world().get::<&Position>(p).unwrap().0;
+        assert_eq!(pos(&sim, one), Vec2::new(64.0, 32.0));
+        assert_eq!(pos(&sim, two), Vec2::new(64.0, 96.0));
+        assert!(sim.world().get::<&Weapons>(two).is_ok());
+
+        // player 1 walks east, player 2 turns; a short slice leaves
+        // player 2 standing
+        let walk = InputCmd {
+            forward: 1.0,
+            ..InputCmd::default()
+        };
+        let turn = InputCmd {
+            turn: 1.0,
+            ..InputCmd::default()
+        };
+        for _ in 0..10 {
+            sim.run_tic_cmds(&mut level, &[walk, turn]);
+        }
+        assert!(pos(&sim, one).x > 64.0 + 32.0);
+        assert_eq!(pos(&sim, two), Vec2::new(64.0, 96.0));
+        let facing = *sim.world().get::<&Angle>(two).unwrap();
+        assert_ne!(facing, Angle::ZERO);
+        sim.run_tic_cmds(&mut level, &[walk]);
+        assert_eq!(*sim.world().get::<&Angle>(two).unwrap(), facing);
     }
 }
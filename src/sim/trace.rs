@@ -0,0 +1,305 @@
+//! Intercept traversal – vanilla p_maputl.c `P_PathTraverse`.
+//!
+//! A trace walks the blockmap cells it passes through from `origin` to
+//! `origin + delta`, gathers every line and thing it crosses with the
+//! fraction of `delta` where it does, then hands them to the caller
+//! nearest first until the caller has seen enough.  Hitscan, use-lines,
+//! sight and the chase camera all ask their questions through here.
+
+use glam::Vec2;
+use hecs::Entity;
+
+use super::ThingGrid;
+use crate::world::{BlockmapQuery, Level, LinedefId, MAPBLOCKSIZE};
+
+/// What a trace crossed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crossed {
+    Line(LinedefId),
+    Thing(Entity),
+}
+
+/// Something the trace crosses `frac` of the way along (vanilla
+/// `intercept_t`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Intercept {
+    pub frac: f32,
+    pub what: Crossed,
+}
+
+/// Where `(o1, d1)` crosses the line through `(o2, d2)`, as a fraction
+/// of `d1` (vanilla `P_InterceptVector`).
+pub fn intercept_vector(o1: Vec2, d1: Vec2, o2: Vec2, d2: Vec2) -> Option<f32> {
+    let den = d2.perp_dot(d1);
+    if den == 0.0 {
+        return None; // parallel
+    }
+    Some(d2.perp_dot(o1 - o2) / -den)
+}
+
+/// Walk the lines the trace from `origin` along `delta` crosses, nearest
+/// first; `visit` returns `false` to stop.  `false` if it did.
+pub fn trace_lines_only<F>(
+    level: &Level,
+    query: &BlockmapQuery,
+    origin: Vec2,
+    delta: Vec2,
+    visit: F,
+) -> bool
+where
+    F: FnMut(Intercept) -> bool,
+{
+    p_path_traverse(level, query, None, origin, delta, visit)
+}
+
+/// [`trace_lines_only`], with the things linked in `grid` as well.
+pub fn trace_all<F>(level: &Level, grid: &ThingGrid, origin: Vec2, delta: Vec2, visit: F) -> bool
+where
+    F: FnMut(Intercept) -> bool,
+{
+    p_path_traverse(level, grid.line_query(), Some(grid), origin, delta, visit)
+}
+
+/// Vanilla `P_PathTraverse`.  Cells are stepped in the order the trace
+/// enters them.  Where it runs exactly through a cell corner vanilla
+/// stalls and misses everything beyond; here both cells beside the
+/// corner are gathered and the walk steps diagonally on.
+fn p_path_traverse<F>(
+    level: &Level,
+    query: &BlockmapQuery,
+    grid: Option<&ThingGrid>,
+    origin: Vec2,
+    delta: Vec2,
+    mut visit: F,
+) -> bool
+where
+    F: FnMut(Intercept) -> bool,
+{
+    let org = level.blockmap.origin;
+    let start = (origin - org) / MAPBLOCKSIZE;
+    let end = (origin + delta - org) / MAPBLOCKSIZE;
+    let (mut map_x, mut map_y) = (start.x.floor() as i32, start.y.floor() as i32);
+    let (end_x, end_y) = (end.x.floor() as i32, end.y.floor() as i32);
+
+    // per axis: cell step, trace fraction at the next cell edge, and the
+    // fraction one whole cell takes
+    let axis = |from: f32, cell: i32, to: i32, d: f32| -> (i32, f32, f32) {
+        let step = (to - cell).signum();
+        let edge = match step {
+            1 => cell as f32 + 1.0,
+            -1 => cell as f32,
+            _ => return (0, f32::INFINITY, f32::INFINITY),
+        };
+        (step, (edge - from) / d, 1.0 / d.abs())
+    };
+    let (step_x, mut next_x, cell_x) = axis(start.x, map_x, end_x, end.x - start.x);
+    let (step_y, mut next_y, cell_y) = axis(start.y, map_y, end_y, end.y - start.y);
+
+    let mut out = Vec::new();
+    query.walk(level.linedefs.len(), |seen| {
+        let mut gather = |bx: i32, by: i32| {
+            for &li in level.block_lines(bx, by) {
+                if seen.first_visit(li) {
+                    add_line_intercept(level, li, origin, delta, &mut out);
+                }
+            }
+            if let Some(grid) = grid {
+                grid.for_each_in_block((bx, by), |t| {
+                    add_thing_intercept(t.ent, t.pos, t.radius, origin, delta, &mut out);
+                    true
+                });
+            }
+        };
+
+        let cells = (end_x - map_x).abs() + (end_y - map_y).abs();
+        for _ in 0..=cells {
+            gather(map_x, map_y);
+            if (map_x, map_y) == (end_x, end_y) {
+                break;
+            }
+            if next_x < next_y {
+                next_x += cell_x;
+                map_x += step_x;
+            } else if next_y < next_x {
+                next_y += cell_y;
+                map_y += step_y;
+            } else {
+                // through the corner
+                gather(map_x + step_x, map_y);
+                gather(map_x, map_y + step_y);
+                (next_x, next_y) = (next_x + cell_x, next_y + cell_y);
+                (map_x, map_y) = (map_x + step_x, map_y + step_y);
+            }
+        }
+    });
+
+    // vanilla `P_TraverseIntercepts`: nearest first, earlier gathered
+    // first on a tie
+    out.sort_by(|a, b| a.frac.total_cmp(&b.frac));
+    out.into_iter().all(&mut visit)
+}
+
+/// Vanilla `PIT_AddLineIntercepts`: `li` if the trace crosses it.
+fn add_line_intercept(
+    level: &Level,
+    li: LinedefId,
+    origin: Vec2,
+    delta: Vec2,
+    out: &mut Vec<Intercept>,
+) {
+    let line = &level.linedefs[li as usize];
+    let v1 = level.vertices[line.v1 as usize].pos;
+    let v2 = level.vertices[line.v2 as usize].pos;
+    let side = |p: Vec2| delta.perp_dot(p - origin) > 0.0;
+    if side(v1) == side(v2) {
+        return; // line isn't crossed
+    }
+    let ldir = v2 - v1;
+    if let Some(frac) = intercept_vector(origin, delta, v1, ldir) {
+        if (0.0..=1.0).contains(&frac) {
+            out.push(Intercept {
+                frac,
+                what: Crossed::Line(li),
+            });
+        }
+    }
+}
+
+/// Vanilla `PIT_AddThingIntercepts`: things are crossed on the diagonal
+/// of their box that faces the trace.
+fn add_thing_intercept(
+    ent: Entity,
+    pos: Vec2,
+    r: f32,
+    origin: Vec2,
+    delta: Vec2,
+    out: &mut Vec<Intercept>,
+) {
+    // vanilla `(dx ^ dy) > 0`: the signs agree
+    let tracepositive = delta.x.is_sign_negative() == delta.y.is_sign_negative();
+    let (a, b) = if tracepositive {
+        (pos + Vec2::new(-r, r), pos + Vec2::new(r, -r))
+    } else {
+        (pos + Vec2::new(-r, -r), pos + Vec2::new(r, r))
+    };
+    let side = |p: Vec2| delta.perp_dot(p - origin) > 0.0;
+    if side(a) != side(b) {
+        if let Some(frac) = intercept_vector(origin, delta, a, b - a) {
+            if (0.0..=1.0).contains(&frac) {
+                out.push(Intercept {
+                    frac,
+                    what: Crossed::Thing(ent),
+                });
+            }
+        }
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::ThingSpatial;
+    use crate::world::{Aabb, Blockmap, Vertex, VertexId, testmap};
+    use hecs::World;
+
+    fn collect(level: &Level, grid: Option<&ThingGrid>, from: Vec2, to: Vec2) -> Vec<Intercept> {
+        let mut out = Vec::new();
+        let push = |ic| {
+            out.push(ic);
+            true
+        };
+        match grid {
+            Some(grid) => trace_all(level, grid, from, to - from, push),
+            None => trace_lines_only(level, &BlockmapQuery::default(), from, to - from, push),
+        };
+        out
+    }
+
+    #[test]
+    fn corridor_lines_come_nearest_first() {
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let xs = |ics: &[Intercept]| -> Vec<f32> {
+            ics.iter()
+                .map(|ic| match ic.what {
+                    Crossed::Line(li) => {
+                        level.vertices[level.linedefs[li as usize].v1 as usize]
+                            .pos
+                            .x
+                    }
+                    Crossed::Thing(_) => -1.0,
+                })
+                .collect()
+        };
+
+        let (from, to) = (Vec2::new(32.0, 64.0), Vec2::new(300.0, 64.0));
+        let east = collect(&level, None, from, to);
+        assert_eq!(xs(&east), [128.0, 144.0, 272.0]);
+        assert!((east[0].frac - 96.0 / 268.0).abs() < 1e-6);
+        let west = collect(&level, None, to, from);
+        assert_eq!(xs(&west), [272.0, 144.0, 128.0]);
+
+        // a thing between the middle strip and the far wall
+        let mut world = World::new();
+        let ent = world.spawn(());
+        let mut grid = ThingGrid::new(level.blockmap.origin);
+        grid.insert(ThingSpatial {
+            ent,
+            pos: Vec2::new(200.0, 64.0),
+            radius: 20.0,
+        });
+        let all = collect(&level, Some(&grid), from, to);
+        assert_eq!(xs(&all), [128.0, 144.0, -1.0, 272.0]);
+        // crossed on its diagonal, through the middle
+        assert!((all[2].frac - 168.0 / 268.0).abs() < 1e-6);
+
+        // and the caller can stop early
+        let mut seen = 0;
+        assert!(!trace_all(&level, &grid, from, to - from, |ic| {
+            seen += 1;
+            ic.what != Crossed::Thing(ent)
+        }));
+        assert_eq!(seen, 3);
+    }
+
+    #[test]
+    fn traces_through_a_cell_corner_reach_the_diagonal_cell() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        // a short line across the diagonal, registered only in cell (1, 1)
+        for pos in [Vec2::new(170.0, 150.0), Vec2::new(150.0, 170.0)] {
+            level.vertices.push(Vertex { pos });
+        }
+        let v = level.vertices.len() as VertexId;
+        let mut line = level.linedefs[0].clone();
+        (line.v1, line.v2) = (v - 2, v - 1);
+        line.id = level.linedefs.len() as LinedefId;
+        line.bbox = Aabb {
+            min: Vec2::splat(150.0),
+            max: Vec2::splat(170.0),
+        };
+        level.linedefs.push(line.clone());
+        let mut lines = vec![Vec::new(); 9];
+        lines[4].push(line.id);
+        level.blockmap = Blockmap {
+            origin: Vec2::ZERO,
+            width: 3,
+            height: 3,
+            lines,
+        };
+
+        // both ways through the corner at (128, 128)
+        let (a, b) = (Vec2::new(64.0, 64.0), Vec2::new(300.0, 300.0));
+        for (from, to) in [(a, b), (b, a)] {
+            let ics = collect(&level, None, from, to);
+            assert_eq!(ics.len(), 1, "{from} → {to}");
+            assert_eq!(ics[0].what, Crossed::Line(line.id));
+            let at = from + (to - from) * ics[0].frac;
+            assert!(at.distance(Vec2::splat(160.0)) < 1e-3, "{at}");
+        }
+    }
+}
@@ -0,0 +1,1023 @@
+//! Weapons – vanilla p_pspr.c.
+//!
+//! The gun on screen is a pair of player sprites ("psprites"): the weapon
+//! itself and its muzzle flash.  Each runs its own state chain through
+//! the state table, and the codepointers on the way raise and lower it,
+//! fire it and decide whether to fire again.
+
+use hecs::{Entity, World};
+
+use super::enemy::{MELEE_RANGE, sound};
+use super::events::SimEvent;
+use super::hitscan::{self, AUTOAIM_NUDGE, MISSILE_RANGE};
+use super::noise::{self, SectorSounds};
+use super::{
+    ActorFlags, AmmoType, Angle, Animation, GameRules, PlayerStatus, Position, Power, Rng,
+    Subsector, ThingGrid, mob,
+};
+use crate::defs::{Action, Definitions, MobjFlags, Sound, State};
+use crate::world::{self, Level};
+
+/// Weapons, in vanilla `weapontype_t` order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeaponType {
+    Fist,
+    Pistol,
+    Shotgun,
+    Chaingun,
+    Missile,
+    Plasma,
+    Bfg,
+    Chainsaw,
+    SuperShotgun,
+}
+
+pub const NUM_WEAPONS: usize = 9;
+
+impl WeaponType {
+    pub const ALL: [WeaponType; NUM_WEAPONS] = [
+        WeaponType::Fist,
+        WeaponType::Pistol,
+        WeaponType::Shotgun,
+        WeaponType::Chaingun,
+        WeaponType::Missile,
+        WeaponType::Plasma,
+        WeaponType::Bfg,
+        WeaponType::Chainsaw,
+        WeaponType::SuperShotgun,
+    ];
+
+    pub fn from_index(i: u8) -> Option<Self> {
+        Self::ALL.get(i as usize).copied()
+    }
+
+    pub fn info(self) -> &'static WeaponInfo {
+        &WEAPON_INFO[self as usize]
+    }
+
+    /// Rounds one shot takes.
+    fn ammo_per_shot(self, defs: &Definitions) -> i32 {
+        match self {
+            WeaponType::Bfg => defs.misc.bfg_cells_per_shot,
+            WeaponType::SuperShotgun => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// A weapon's ammo and state chains (vanilla `weaponinfo_t`).
+#[derive(Clone, Copy, Debug)]
+pub struct WeaponInfo {
+    /// `None` for the fist and chainsaw.
+    pub ammo: Option<AmmoType>,
+    pub up: State,
+    pub down: State,
+    pub ready: State,
+    pub attack: State,
+    pub flash: State,
+}
+
+pub static WEAPON_INFO: [WeaponInfo; NUM_WEAPONS] = [
+    WeaponInfo {
+        ammo: None,
+        up: State::PUNCHUP,
+        down: State::PUNCHDOWN,
+        ready: State::PUNCH,
+        attack: State::PUNCH1,
+        flash: State::NULL,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Clip),
+        up: State::PISTOLUP,
+        down: State::PISTOLDOWN,
+        ready: State::PISTOL,
+        attack: State::PISTOL1,
+        flash: State::PISTOLFLASH,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Shell),
+        up: State::SGUNUP,
+        down: State::SGUNDOWN,
+        ready: State::SGUN,
+        attack: State::SGUN1,
+        flash: State::SGUNFLASH1,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Clip),
+        up: State::CHAINUP,
+        down: State::CHAINDOWN,
+        ready: State::CHAIN,
+        attack: State::CHAIN1,
+        flash: State::CHAINFLASH1,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Misl),
+        up: State::MISSILEUP,
+        down: State::MISSILEDOWN,
+        ready: State::MISSILE,
+        attack: State::MISSILE1,
+        flash: State::MISSILEFLASH1,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Cell),
+        up: State::PLASMAUP,
+        down: State::PLASMADOWN,
+        ready: State::PLASMA,
+        attack: State::PLASMA1,
+        flash: State::PLASMAFLASH1,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Cell),
+        up: State::BFGUP,
+        down: State::BFGDOWN,
+        ready: State::BFG,
+        attack: State::BFG1,
+        flash: State::BFGFLASH1,
+    },
+    WeaponInfo {
+        ammo: None,
+        up: State::SAWUP,
+        down: State::SAWDOWN,
+        ready: State::SAW,
+        attack: State::SAW1,
+        flash: State::NULL,
+    },
+    WeaponInfo {
+        ammo: Some(AmmoType::Shell),
+        up: State::DSGUNUP,
+        down: State::DSGUNDOWN,
+        ready: State::DSGUN,
+        attack: State::DSGUN1,
+        flash: State::DSGUNFLASH1,
+    },
+];
+
+/// Psprite heights, in 320×200 screen rows, for a weapon fully up and
+/// fully out of view.
+pub const WEAPON_TOP: f32 = 32.0;
+pub const WEAPON_BOTTOM: f32 = 128.0;
+const LOWER_SPEED: f32 = 6.0;
+const RAISE_SPEED: f32 = 6.0;
+
+/// The gun and its muzzle flash (vanilla `ps_weapon`, `ps_flash`).
+pub const PS_WEAPON: usize = 0;
+pub const PS_FLASH: usize = 1;
+pub const NUM_PSPRITES: usize = 2;
+
+/// One player sprite (vanilla `pspdef_t`); `State::NULL` is off.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Psprite {
+    pub state: State,
+    /// Tics left in `state`; -1 stays for good.
+    pub tics: i32,
+    /// Offset on the 320×200 screen.
+    pub sx: f32,
+    pub sy: f32,
+}
+
+impl Default for Psprite {
+    fn default() -> Self {
+        Self {
+            state: State::NULL,
+            tics: 0,
+            sx: 1.0,
+            sy: WEAPON_TOP,
+        }
+    }
+}
+
+/// The weapon half of vanilla `player_t`: what the player owns, holds
+/// and is switching to, and the psprites drawing it.  Ammo lives in
+/// `PlayerStatus`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weapons {
+    pub owned: [bool; NUM_WEAPONS],
+    pub ready: WeaponType,
+    /// Being switched to once the current weapon is down.
+    pub pending: Option<WeaponType>,
+    pub psprites: [Psprite; NUM_PSPRITES],
+    /// Shots fired with the trigger held; the pistol and chaingun lose
+    /// accuracy after the first.
+    pub refire: i32,
+    /// The trigger was held last tic; rockets and the BFG want it let go
+    /// between shots.
+    pub attack_down: bool,
+}
+
+impl Default for Weapons {
+    /// [`Weapons::reborn`] by the compiled-in tables.
+    fn default() -> Self {
+        Self::reborn(&Definitions::shared_builtin())
+    }
+}
+
+impl Weapons {
+    /// A fresh player's fist and pistol, pistol in hand (vanilla
+    /// `G_PlayerReborn`).
+    pub fn reborn(defs: &Definitions) -> Self {
+        let mut owned = [false; NUM_WEAPONS];
+        owned[WeaponType::Fist as usize] = true;
+        owned[WeaponType::Pistol as usize] = true;
+        Self::new(defs, owned, WeaponType::Pistol)
+    }
+
+    /// `owned`, with `ready` coming up from the bottom of the screen
+    /// (vanilla `P_SetupPsprites` on spawn).
+    pub fn new(defs: &Definitions, owned: [bool; NUM_WEAPONS], ready: WeaponType) -> Self {
+        let mut weapons = Self {
+            owned,
+            ready,
+            pending: None,
+            psprites: [Psprite::default(); NUM_PSPRITES],
+            refire: 0,
+            attack_down: false,
+        };
+        // P_BringUpWeapon: entering the up state runs A_Raise once
+        let psp = &mut weapons.psprites[PS_WEAPON];
+        psp.state = ready.info().up;
+        psp.tics = defs.state(psp.state).tics;
+        psp.sy = WEAPON_BOTTOM - RAISE_SPEED;
+        weapons
+    }
+
+    /// The same weapons after a level change or respawn: held one
+    /// coming up, psprites reset.
+    pub fn respawned(&self, defs: &Definitions) -> Self {
+        Self::new(defs, self.owned, self.ready)
+    }
+
+    pub fn owns(&self, weapon: WeaponType) -> bool {
+        self.owned[weapon as usize]
+    }
+}
+
+/// What the weapon codepointers reach into.
+pub struct WeaponCtx<'a> {
+    pub world: &'a mut World,
+    pub thing_grid: &'a mut ThingGrid,
+    pub level: &'a Level,
+    pub rng: &'a mut Rng,
+    pub rules: GameRules,
+    pub defs: &'a Definitions,
+    pub events: &'a mut Vec<SimEvent>,
+    pub sounds: &'a mut SectorSounds,
+    pub player: Entity,
+    /// The trigger is held this tic.
+    pub fire: bool,
+    /// Aim shots at what's in line; see [`TicRunner::set_autoaim`].
+    ///
+    /// [`TicRunner::set_autoaim`]: super::TicRunner::set_autoaim
+    pub autoaim: bool,
+}
+
+/// The weapon part of vanilla `P_PlayerThink`: switch to `weapon`
+/// (1‥7, as `InputCmd::weapon`) if asked, then run the psprites.
+pub fn p_player_weapons(ctx: &mut WeaponCtx, weapon: Option<u8>) {
+    let Ok(mut wp) = ctx.world.get::<&Weapons>(ctx.player).map(|w| *w) else {
+        return;
+    };
+    if let Some(slot) = weapon {
+        change_weapon(ctx.world, ctx.player, &mut wp, slot);
+    }
+    p_move_psprites(ctx, &mut wp);
+    if let Ok(mut w) = ctx.world.get::<&mut Weapons>(ctx.player) {
+        *w = wp;
+    }
+}
+
+/// Key `slot` pressed: the fist key means the chainsaw when owned (the
+/// berserk fist stays put), the shotgun key the super shotgun.
+fn change_weapon(world: &World, player: Entity, wp: &mut Weapons, slot: u8) {
+    let Some(mut new) = slot.checked_sub(1).and_then(WeaponType::from_index) else {
+        return;
+    };
+    let berserk = world
+        .get::<&PlayerStatus>(player)
+        .is_ok_and(|st| st.powers[Power::Strength as usize] != 0);
+    if new == WeaponType::Fist
+        && wp.owns(WeaponType::Chainsaw)
+        && !(wp.ready == WeaponType::Chainsaw && berserk)
+    {
+        new = WeaponType::Chainsaw;
+    }
+    if new == WeaponType::Shotgun
+        && wp.owns(WeaponType::SuperShotgun)
+        && wp.ready != WeaponType::SuperShotgun
+    {
+        new = WeaponType::SuperShotgun;
+    }
+    if wp.owns(new) && new != wp.ready {
+        wp.pending = Some(new);
+    }
+}
+
+/// Vanilla `P_MovePsprites`: count both psprites down and step their
+/// state chains; the flash follows the gun.
+fn p_move_psprites(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    for which in 0..NUM_PSPRITES {
+        let psp = &mut wp.psprites[which];
+        // a -1 tic count never changes
+        if psp.state == State::NULL || psp.tics == -1 {
+            continue;
+        }
+        psp.tics -= 1;
+        if psp.tics == 0 {
+            let next = ctx.defs.state(psp.state).next_state;
+            p_set_psprite(ctx, wp, which, next);
+        }
+    }
+    let (sx, sy) = (wp.psprites[PS_WEAPON].sx, wp.psprites[PS_WEAPON].sy);
+    (wp.psprites[PS_FLASH].sx, wp.psprites[PS_FLASH].sy) = (sx, sy);
+}
+
+/// Vanilla `P_SetPsprite`: enter `state`, running its codepointer, and
+/// on through any zero-tic states after it.
+fn p_set_psprite(ctx: &mut WeaponCtx, wp: &mut Weapons, which: usize, mut state: State) {
+    loop {
+        if state == State::NULL {
+            // object removed itself
+            wp.psprites[which].state = State::NULL;
+            return;
+        }
+        let info = *ctx.defs.state(state);
+        let psp = &mut wp.psprites[which];
+        psp.state = state;
+        psp.tics = info.tics;
+        if info.misc1 != 0 {
+            // coordinate set
+            psp.sx = info.misc1 as f32;
+            psp.sy = info.misc2 as f32;
+        }
+        if !matches!(info.action, Action::None) {
+            call_weapon_action(ctx, wp, which, info.action);
+            if wp.psprites[which].state == State::NULL {
+                return;
+            }
+        }
+        if wp.psprites[which].tics != 0 {
+            return;
+        }
+        state = ctx.defs.state(wp.psprites[which].state).next_state;
+    }
+}
+
+fn call_weapon_action(ctx: &mut WeaponCtx, wp: &mut Weapons, which: usize, action: Action) {
+    match action {
+        Action::WeaponReady => a_weapon_ready(ctx, wp),
+        Action::ReFire => a_re_fire(ctx, wp),
+        Action::CheckReload => {
+            p_check_ammo(ctx, wp);
+        }
+        Action::Lower => a_lower(ctx, wp),
+        Action::Raise => a_raise(ctx, wp),
+        Action::GunFlash => a_gun_flash(ctx, wp),
+        Action::Punch => a_punch(ctx),
+        Action::Saw => a_saw(ctx),
+        Action::FirePistol => a_fire_pistol(ctx, wp),
+        Action::FireShotgun => a_fire_shotgun(ctx, wp),
+        Action::FireShotgun2 => a_fire_shotgun2(ctx, wp),
+        Action::FireCGun => a_fire_cgun(ctx, wp, which),
+        Action::FireMissile => a_fire_missile(ctx, wp),
+        Action::FirePlasma => a_fire_plasma(ctx, wp),
+        Action::FireBFG => a_fire_bfg(ctx, wp),
+        Action::OpenShotgun2 => player_sound(ctx, Sound::dbopn),
+        Action::LoadShotgun2 => player_sound(ctx, Sound::dbload),
+        Action::CloseShotgun2 => {
+            player_sound(ctx, Sound::dbcls);
+            a_re_fire(ctx, wp);
+        }
+        Action::BFGsound => player_sound(ctx, Sound::bfg),
+        _ => {}
+    }
+}
+
+/*──────────────────────────── helpers ──────────────────────────────*/
+
+fn player_sound(ctx: &mut WeaponCtx, s: Sound) {
+    sound(ctx.world, ctx.events, ctx.player, s);
+}
+
+fn player_health(world: &World, player: Entity) -> i32 {
+    world.get::<&PlayerStatus>(player).map_or(0, |st| st.health)
+}
+
+/// The player mobj's own sprite: `PLAY_ATK1` when firing, `PLAY_ATK2`
+/// for the flash, `PLAY` to stop.
+fn set_player_state(ctx: &mut WeaponCtx, state: State) {
+    if let Ok(mut anim) = ctx.world.get::<&mut Animation>(ctx.player) {
+        anim.state = state;
+        anim.tics = ctx.defs.state(state).tics;
+    }
+}
+
+fn use_ammo(world: &mut World, player: Entity, weapon: WeaponType, n: i32) {
+    if let (Some(ammo), Ok(mut st)) = (weapon.info().ammo, world.get::<&mut PlayerStatus>(player)) {
+        st.ammo[ammo as usize] -= n;
+    }
+}
+
+/// The flash psprite to the weapon's flash state, `offset` states on.
+fn set_flash(ctx: &mut WeaponCtx, wp: &mut Weapons, offset: usize) {
+    let flash = ctx.defs.states[wp.ready.info().flash as usize + offset].state;
+    p_set_psprite(ctx, wp, PS_FLASH, flash);
+}
+
+/// Vanilla `P_BringUpWeapon`: start the pending weapon (or the held one)
+/// up from the bottom of the screen.
+fn p_bring_up_weapon(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    let weapon = wp.pending.take().unwrap_or(wp.ready);
+    if weapon == WeaponType::Chainsaw {
+        player_sound(ctx, Sound::sawup);
+    }
+    wp.psprites[PS_WEAPON].sy = WEAPON_BOTTOM;
+    p_set_psprite(ctx, wp, PS_WEAPON, weapon.info().up);
+}
+
+/// Vanilla `P_CheckAmmo`: enough for a shot?  If not, pick the best
+/// weapon that has some and start lowering this one.
+fn p_check_ammo(ctx: &mut WeaponCtx, wp: &mut Weapons) -> bool {
+    let Ok(ammo) = ctx.world.get::<&PlayerStatus>(ctx.player).map(|st| st.ammo) else {
+        return false;
+    };
+    let have = |t: AmmoType| ammo[t as usize];
+    let enough = wp
+        .ready
+        .info()
+        .ammo
+        .is_none_or(|t| have(t) >= wp.ready.ammo_per_shot(ctx.defs));
+    if enough {
+        return true;
+    }
+
+    // out of ammo, pick a weapon to change to; preferences are set here
+    use WeaponType::*;
+    let owns = |w| wp.owns(w);
+    let next = if owns(Plasma) && have(AmmoType::Cell) > 0 {
+        Plasma
+    } else if owns(SuperShotgun) && have(AmmoType::Shell) > 2 {
+        SuperShotgun
+    } else if owns(Chaingun) && have(AmmoType::Clip) > 0 {
+        Chaingun
+    } else if owns(Shotgun) && have(AmmoType::Shell) > 0 {
+        Shotgun
+    } else if have(AmmoType::Clip) > 0 {
+        Pistol
+    } else if owns(Chainsaw) {
+        Chainsaw
+    } else if owns(Missile) && have(AmmoType::Misl) > 0 {
+        Missile
+    } else if owns(Bfg) && have(AmmoType::Cell) > ctx.defs.misc.bfg_cells_per_shot {
+        Bfg
+    } else {
+        // if everything fails
+        Fist
+    };
+    wp.pending = Some(next);
+    // now set appropriate weapon overlay
+    p_set_psprite(ctx, wp, PS_WEAPON, wp.ready.info().down);
+    false
+}
+
+/// Vanilla `P_FireWeapon`.
+fn p_fire_weapon(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    if !p_check_ammo(ctx, wp) {
+        return;
+    }
+    set_player_state(ctx, State::PLAY_ATK1);
+    p_set_psprite(ctx, wp, PS_WEAPON, wp.ready.info().attack);
+    if let Ok(ss) = ctx.world.get::<&Subsector>(ctx.player).map(|ss| ss.0) {
+        let sector = ctx.level.sector_of_subsector(ss);
+        noise::p_noise_alert(ctx.level, ctx.sounds, ctx.player, sector);
+    }
+}
+
+/// Vanilla `P_BulletSlope`: aim straight ahead, then a little either
+/// side; level if nothing is there, or if autoaim is off.
+fn p_bullet_slope(ctx: &WeaponCtx) -> f32 {
+    if !ctx.autoaim {
+        // there's no looking up or down to take the slope from yet
+        return 0.0;
+    }
+    let Ok(angle) = ctx.world.get::<&Angle>(ctx.player).map(|a| *a) else {
+        return 0.0;
+    };
+    [angle, angle + AUTOAIM_NUDGE, angle - AUTOAIM_NUDGE]
+        .into_iter()
+        .find_map(|an| {
+            hitscan::p_aim_line_attack(
+                ctx.world,
+                ctx.level,
+                ctx.thing_grid,
+                ctx.player,
+                an,
+                16.0 * 64.0,
+            )
+        })
+        .map_or(0.0, |(_, slope)| slope)
+}
+
+/// Vanilla `P_GunShot`: one bullet for 5‥15, spread unless `accurate`.
+fn p_gun_shot(ctx: &mut WeaponCtx, accurate: bool, slope: f32) {
+    let damage = 5 * (ctx.rng.p_random() % 3 + 1);
+    let Ok(mut angle) = ctx.world.get::<&Angle>(ctx.player).map(|a| *a) else {
+        return;
+    };
+    if !accurate {
+        angle += Angle((ctx.rng.p_subrandom() << 18) as u32);
+    }
+    hitscan::p_line_attack(
+        ctx.world,
+        ctx.thing_grid,
+        ctx.level,
+        ctx.rng,
+        ctx.rules,
+        ctx.defs,
+        ctx.events,
+        ctx.player,
+        angle,
+        MISSILE_RANGE,
+        slope,
+        damage,
+    );
+}
+
+/// The short-range swing of the fist and chainsaw: what it hit, if
+/// anything, and the angle it was swung at.
+fn melee(ctx: &mut WeaponCtx, damage: i32, range: f32) -> Option<Entity> {
+    let mut angle = *ctx.world.get::<&Angle>(ctx.player).ok()?;
+    angle += Angle((ctx.rng.p_subrandom() << 18) as u32);
+    let slope = hitscan::p_aim_line_attack(
+        ctx.world,
+        ctx.level,
+        ctx.thing_grid,
+        ctx.player,
+        angle,
+        range,
+    )
+    .map_or(0.0, |(_, slope)| slope);
+    hitscan::p_line_attack(
+        ctx.world,
+        ctx.thing_grid,
+        ctx.level,
+        ctx.rng,
+        ctx.rules,
+        ctx.defs,
+        ctx.events,
+        ctx.player,
+        angle,
+        range,
+        slope,
+        damage,
+    )
+}
+
+/// Angle from the player to `target`.
+fn angle_to(world: &World, player: Entity, target: Entity) -> Option<Angle> {
+    let from = world.get::<&Position>(player).ok()?.0;
+    let to = world.get::<&Position>(target).ok()?.0;
+    Some(world::angle_to(from, to))
+}
+
+/*──────────────────────────── codepointers ─────────────────────────*/
+
+/// Vanilla `A_WeaponReady`: idle, lower for a switch (or a death), or
+/// fire when the trigger is pulled.
+fn a_weapon_ready(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    // get out of attack state
+    if let Ok(state) = ctx.world.get::<&Animation>(ctx.player).map(|a| a.state) {
+        if matches!(state, State::PLAY_ATK1 | State::PLAY_ATK2) {
+            set_player_state(ctx, State::PLAY);
+        }
+    }
+    if wp.ready == WeaponType::Chainsaw && wp.psprites[PS_WEAPON].state == State::SAW {
+        player_sound(ctx, Sound::sawidl);
+    }
+
+    // check for change; if player is dead, put the weapon away
+    if wp.pending.is_some() || player_health(ctx.world, ctx.player) <= 0 {
+        p_set_psprite(ctx, wp, PS_WEAPON, wp.ready.info().down);
+        return;
+    }
+
+    // check for fire; the missile launcher and bfg do not auto fire
+    if ctx.fire {
+        if !wp.attack_down || !matches!(wp.ready, WeaponType::Missile | WeaponType::Bfg) {
+            wp.attack_down = true;
+            p_fire_weapon(ctx, wp);
+            return;
+        }
+    } else {
+        wp.attack_down = false;
+    }
+
+    let psp = &mut wp.psprites[PS_WEAPON];
+    (psp.sx, psp.sy) = (1.0, WEAPON_TOP);
+}
+
+/// Vanilla `A_ReFire`: the trigger is still down at the end of a shot,
+/// so fire again.
+fn a_re_fire(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    if ctx.fire && wp.pending.is_none() && player_health(ctx.world, ctx.player) > 0 {
+        wp.refire += 1;
+        p_fire_weapon(ctx, wp);
+    } else {
+        wp.refire = 0;
+        p_check_ammo(ctx, wp);
+    }
+}
+
+/// Vanilla `A_Lower`: sink out of view, then bring the pending weapon
+/// up.  A dead player's weapon stays down.
+fn a_lower(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    let psp = &mut wp.psprites[PS_WEAPON];
+    psp.sy += LOWER_SPEED;
+    // is already down
+    if psp.sy < WEAPON_BOTTOM {
+        return;
+    }
+    if player_health(ctx.world, ctx.player) <= 0 {
+        psp.sy = WEAPON_BOTTOM;
+        return;
+    }
+    if let Some(pending) = wp.pending {
+        wp.ready = pending;
+    }
+    p_bring_up_weapon(ctx, wp);
+}
+
+/// Vanilla `A_Raise`: rise into view, then get ready.
+fn a_raise(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    let psp = &mut wp.psprites[PS_WEAPON];
+    psp.sy -= RAISE_SPEED;
+    if psp.sy > WEAPON_TOP {
+        return;
+    }
+    psp.sy = WEAPON_TOP;
+    // the weapon has been raised all the way, so change to the ready
+    // state
+    p_set_psprite(ctx, wp, PS_WEAPON, wp.ready.info().ready);
+}
+
+/// Vanilla `A_GunFlash`.
+fn a_gun_flash(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    set_player_state(ctx, State::PLAY_ATK2);
+    set_flash(ctx, wp, 0);
+}
+
+/// Vanilla `A_Punch`: 2‥20, ten times that with berserk; turns to face
+/// whatever it hit.
+fn a_punch(ctx: &mut WeaponCtx) {
+    let mut damage = (ctx.rng.p_random() % 10 + 1) << 1;
+    if ctx
+        .world
+        .get::<&PlayerStatus>(ctx.player)
+        .is_ok_and(|st| st.powers[Power::Strength as usize] != 0)
+    {
+        damage *= 10;
+    }
+    let Some(target) = melee(ctx, damage, MELEE_RANGE) else {
+        return;
+    };
+    // turn to face target
+    player_sound(ctx, Sound::punch);
+    if let Some(an) = angle_to(ctx.world, ctx.player, target) {
+        if let Ok(mut angle) = ctx.world.get::<&mut Angle>(ctx.player) {
+            *angle = an;
+        }
+    }
+}
+
+/// Vanilla `A_Saw`: 2‥20 a tic in reach, pulling the player round
+/// towards whatever it's chewing.
+fn a_saw(ctx: &mut WeaponCtx) {
+    let damage = 2 * (ctx.rng.p_random() % 10 + 1);
+    // use meleerange + 1 so the puff doesn't skip the flash
+    let Some(target) = melee(ctx, damage, MELEE_RANGE + 1.0) else {
+        player_sound(ctx, Sound::sawful);
+        return;
+    };
+    player_sound(ctx, Sound::sawhit);
+
+    // turn to face target
+    let Some(an) = angle_to(ctx.world, ctx.player, target) else {
+        return;
+    };
+    let Ok((angle, flags)) = ctx
+        .world
+        .query_one_mut::<(&mut Angle, &mut ActorFlags)>(ctx.player)
+    else {
+        return;
+    };
+    let step = Angle(Angle::ANG90.0 / 20);
+    let delta = an - *angle;
+    *angle = if delta > Angle::ANG180 {
+        if (delta.0 as i32) < -(step.0 as i32) {
+            an + Angle(Angle::ANG90.0 / 21)
+        } else {
+            *angle - step
+        }
+    } else if delta > step {
+        an - Angle(Angle::ANG90.0 / 21)
+    } else {
+        *angle + step
+    };
+    flags.0.insert(MobjFlags::JUSTATTACKED);
+}
+
+/// Vanilla `A_FirePistol`: dead on for the first shot, spread after.
+fn a_fire_pistol(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    player_sound(ctx, Sound::pistol);
+    set_player_state(ctx, State::PLAY_ATK2);
+    use_ammo(ctx.world, ctx.player, wp.ready, 1);
+    set_flash(ctx, wp, 0);
+    let slope = p_bullet_slope(ctx);
+    p_gun_shot(ctx, wp.refire == 0, slope);
+}
+
+/// Vanilla `A_FireShotgun`: seven spread pellets.
+fn a_fire_shotgun(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    player_sound(ctx, Sound::shotgn);
+    set_player_state(ctx, State::PLAY_ATK2);
+    use_ammo(ctx.world, ctx.player, wp.ready, 1);
+    set_flash(ctx, wp, 0);
+    let slope = p_bullet_slope(ctx);
+    for _ in 0..7 {
+        p_gun_shot(ctx, false, slope);
+    }
+}
+
+/// Vanilla `A_FireShotgun2`: twenty pellets, spread up and down too.
+fn a_fire_shotgun2(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    player_sound(ctx, Sound::dshtgn);
+    set_player_state(ctx, State::PLAY_ATK2);
+    use_ammo(ctx.world, ctx.player, wp.ready, 2);
+    set_flash(ctx, wp, 0);
+    let slope = p_bullet_slope(ctx);
+    let Ok(angle) = ctx.world.get::<&Angle>(ctx.player).map(|a| *a) else {
+        return;
+    };
+    for _ in 0..20 {
+        let damage = 5 * (ctx.rng.p_random() % 3 + 1);
+        let an = angle + Angle((ctx.rng.p_subrandom() << 19) as u32);
+        let slope = slope + ctx.rng.p_subrandom() as f32 / 2048.0;
+        hitscan::p_line_attack(
+            ctx.world,
+            ctx.thing_grid,
+            ctx.level,
+            ctx.rng,
+            ctx.rules,
+            ctx.defs,
+            ctx.events,
+            ctx.player,
+            an,
+            MISSILE_RANGE,
+            slope,
+            damage,
+        );
+    }
+}
+
+/// Vanilla `A_FireCGun`: one bullet per barrel state, each with its own
+/// flash frame.
+fn a_fire_cgun(ctx: &mut WeaponCtx, wp: &mut Weapons, which: usize) {
+    player_sound(ctx, Sound::pistol);
+    let has_ammo = wp.ready.info().ammo.is_none_or(|t| {
+        ctx.world
+            .get::<&PlayerStatus>(ctx.player)
+            .is_ok_and(|st| st.ammo[t as usize] > 0)
+    });
+    if !has_ammo {
+        return;
+    }
+    set_player_state(ctx, State::PLAY_ATK2);
+    use_ammo(ctx.world, ctx.player, wp.ready, 1);
+    let barrel = (wp.psprites[which].state as usize).saturating_sub(State::CHAIN1 as usize);
+    set_flash(ctx, wp, barrel);
+    let slope = p_bullet_slope(ctx);
+    p_gun_shot(ctx, wp.refire == 0, slope);
+}
+
+fn fire_missile(ctx: &mut WeaponCtx, id: &str) {
+    let info = *ctx
+        .defs
+        .by_id(id)
+        .expect("player missile missing from MOBJINFO");
+    mob::p_spawn_player_missile(
+        ctx.world,
+        ctx.thing_grid,
+        ctx.level,
+        ctx.rng,
+        ctx.defs,
+        ctx.events,
+        ctx.player,
+        &info,
+        ctx.autoaim,
+    );
+}
+
+/// Vanilla `A_FireMissile`: a rocket.
+fn a_fire_missile(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    use_ammo(ctx.world, ctx.player, wp.ready, 1);
+    fire_missile(ctx, "ROCKET");
+}
+
+/// Vanilla `A_FirePlasma`: a plasma ball, flashing one of two frames.
+fn a_fire_plasma(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    use_ammo(ctx.world, ctx.player, wp.ready, 1);
+    let frame = (ctx.rng.p_random() & 1) as usize;
+    set_flash(ctx, wp, frame);
+    fire_missile(ctx, "PLASMA");
+}
+
+/// Vanilla `A_FireBFG`.
+fn a_fire_bfg(ctx: &mut WeaponCtx, wp: &mut Weapons) {
+    let cells = ctx.defs.misc.bfg_cells_per_shot;
+    use_ammo(ctx.world, ctx.player, wp.ready, cells);
+    fire_missile(ctx, "BFG");
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defs;
+    use crate::sim::{Class, Health, InputCmd, TicRunner};
+    use crate::world::testmap;
+
+    /// A player in room A facing east, with `weapon` up and ready.
+    fn armed(level: &mut Level, weapon: WeaponType) -> (TicRunner, Entity) {
+        let mut sim = TicRunner::new(level);
+        let info = defs::by_id("PLAYER").unwrap();
+        let player = sim.spawn_mobj(level, info, 32.0, 64.0, 0.0, 0);
+        sim.set_player(player);
+        {
+            let world = sim.world_mut();
+            let mut wp = *world.get::<&Weapons>(player).unwrap();
+            wp.owned = [true; NUM_WEAPONS];
+            world
+                .insert_one(
+                    player,
+                    Weapons::new(&Definitions::shared_builtin(), wp.owned, weapon),
+                )
+                .unwrap();
+            world.get::<&mut PlayerStatus>(player).unwrap().ammo = [200, 50, 300, 50];
+        }
+        for _ in 0..20 {
+            sim.run_tic(level, InputCmd::default());
+        }
+        (sim, player)
+    }
+
+    fn weapons(sim: &TicRunner, player: Entity) -> Weapons {
+        *sim.world().get::<&Weapons>(player).unwrap()
+    }
+
+    fn ammo(sim: &TicRunner, player: Entity, t: AmmoType) -> i32 {
+        sim.world().get::<&PlayerStatus>(player).unwrap().ammo[t as usize]
+    }
+
+    const FIRE: InputCmd = InputCmd {
+        forward: 0.0,
+        strafe: 0.0,
+        turn: 0.0,
+        run: false,
+        fire: true,
+        use_act: false,
+        weapon: None,
+        fly: 0.0,
+    };
+
+    #[test]
+    fn switching_lowers_then_raises() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = armed(&mut level, WeaponType::Pistol);
+        let wp = weapons(&sim, player);
+        assert_eq!(wp.psprites[PS_WEAPON].state, State::PISTOL);
+        assert_eq!(wp.psprites[PS_WEAPON].sy, WEAPON_TOP);
+
+        // the shotgun key picks the super shotgun first
+        let mut cmd = InputCmd {
+            weapon: Some(3),
+            ..InputCmd::default()
+        };
+        sim.run_tic(&mut level, cmd);
+        assert_eq!(
+            weapons(&sim, player).pending,
+            Some(WeaponType::SuperShotgun)
+        );
+        cmd.weapon = None;
+        // 96 rows down and back up at 6 a tic
+        let mut lowest = WEAPON_TOP;
+        for _ in 0..40 {
+            sim.run_tic(&mut level, cmd);
+            lowest = lowest.max(weapons(&sim, player).psprites[PS_WEAPON].sy);
+        }
+        let wp = weapons(&sim, player);
+        // the new weapon starts up the tic the old one reaches the bottom
+        assert_eq!(lowest, WEAPON_BOTTOM - RAISE_SPEED);
+        assert_eq!((wp.ready, wp.pending), (WeaponType::SuperShotgun, None));
+        assert_eq!(wp.psprites[PS_WEAPON].state, State::DSGUN);
+
+        // and the plain one when the super shotgun is already up
+        cmd.weapon = Some(3);
+        sim.run_tic(&mut level, cmd);
+        assert_eq!(weapons(&sim, player).pending, Some(WeaponType::Shotgun));
+
+        // the fist key means the chainsaw once owned
+        cmd.weapon = Some(1);
+        sim.run_tic(&mut level, cmd);
+        assert_eq!(weapons(&sim, player).pending, Some(WeaponType::Chainsaw));
+    }
+
+    #[test]
+    fn shotgun_fires_seven_pellets_per_shell() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = armed(&mut level, WeaponType::Shotgun);
+        // a cacodemon (400 hp) right in front soaks up every pellet
+        let caco = defs::by_id("HEAD").unwrap();
+        let target = sim.spawn_mobj(&level, caco, 96.0, 64.0, 0.0, 0);
+
+        // a tap: SGUN1 waits 3 tics before A_FireShotgun
+        sim.run_tic(&mut level, FIRE);
+        assert_eq!(
+            weapons(&sim, player).psprites[PS_WEAPON].state,
+            State::SGUN1
+        );
+        for _ in 0..3 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(ammo(&sim, player, AmmoType::Shell), 49);
+        assert_eq!(
+            weapons(&sim, player).psprites[PS_FLASH].state,
+            State::SGUNFLASH1
+        );
+        let hp = sim.world().get::<&Health>(target).unwrap().0;
+        // 5‥15 each
+        assert!((400 - 7 * 15..=400 - 7 * 5).contains(&hp), "{hp}");
+
+        // holding the trigger refires once the pump is done
+        for _ in 0..80 {
+            sim.run_tic(&mut level, FIRE);
+        }
+        assert!(ammo(&sim, player, AmmoType::Shell) < 48);
+        assert!(weapons(&sim, player).refire > 0);
+    }
+
+    #[test]
+    fn rockets_need_a_fresh_press() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = armed(&mut level, WeaponType::Missile);
+        // the trigger was already down when the launcher came up
+        sim.world_mut()
+            .get::<&mut Weapons>(player)
+            .unwrap()
+            .attack_down = true;
+        for _ in 0..30 {
+            sim.run_tic(&mut level, FIRE);
+        }
+        assert_eq!(ammo(&sim, player, AmmoType::Misl), 50);
+
+        // let go and press again: MISSILE1 and MISSILE2 take 20 tics
+        sim.run_tic(&mut level, InputCmd::default());
+        for _ in 0..25 {
+            sim.run_tic(&mut level, FIRE);
+        }
+        assert_eq!(ammo(&sim, player, AmmoType::Misl), 49);
+        let rockets = sim
+            .world()
+            .query::<&Class>()
+            .iter()
+            .filter(|(_, c)| c.0.id == "ROCKET")
+            .count();
+        assert_eq!(rockets, 1);
+    }
+
+    #[test]
+    fn empty_weapon_switches_by_vanilla_priority() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let (mut sim, player) = armed(&mut level, WeaponType::Plasma);
+        sim.world_mut()
+            .get::<&mut PlayerStatus>(player)
+            .unwrap()
+            .ammo = [20, 4, 0, 0];
+        sim.run_tic(&mut level, FIRE);
+        // no cells: the super shotgun (shells > 2) wins over the chaingun
+        assert_eq!(
+            weapons(&sim, player).pending,
+            Some(WeaponType::SuperShotgun)
+        );
+        for _ in 0..40 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(weapons(&sim, player).ready, WeaponType::SuperShotgun);
+
+        sim.world_mut()
+            .get::<&mut PlayerStatus>(player)
+            .unwrap()
+            .ammo = [0, 0, 0, 0];
+        sim.run_tic(&mut level, FIRE);
+        assert_eq!(weapons(&sim, player).pending, Some(WeaponType::Chainsaw));
+    }
+}
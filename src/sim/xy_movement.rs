@@ -7,25 +7,59 @@ use glam::{Vec2, Vec3};
 use hecs::{Entity, World};
 use smallvec::SmallVec;
 
-use super::spacial::{ThingGrid, ThingSpatial};
-use super::{ActorFlags, Animation, Class, Position, Subsector, Velocity};
-use crate::defs::{State, flags::MobjFlags};
-use crate::world::{Aabb, Level, Linedef, LinedefFlags, LinedefId};
-
-/* ----------------------------------------------------------------- */
-/*  Physics constants (f32 map-units)                                */
-/* ----------------------------------------------------------------- */
-const MAX_MOVE: f32 = 32.0; // vanilla 0x10000
-const MAX_STEP_HEIGHT: f32 = 24.0; // vanilla 24*FRACUNIT
-const STOP_SPEED: f32 = 0.125; // vanilla FRACUNIT/8
-const FRICTION: f32 = 0.90625; // vanilla 0xE800/FRACUNIT
+use super::ceilings::Ceiling;
+use super::doors::Door;
+use super::events::SimEvent;
+use super::fixed::{Fixed, FixedMotion};
+use super::floors::Floor;
+use super::pickups;
+use super::plats::Platform;
+use super::spacial::{
+    ThingGrid, ThingSpatial, fetch_thing, p_set_thing_position, p_unset_thing_position,
+};
+use super::specials::{self, SpecialCtx, point_on_line_side, table::Activation};
+use super::switches::{Button, SwitchList};
+use super::{
+    ActorFlags, Animation, Class, GameRules, MoveDir, PhysicsConfig, PlayerStatus, Position, Rng,
+    Skill, Subsector, Target, Velocity, damage, mob, systems,
+};
+use crate::defs::{Definitions, Sound, State, flags::MobjFlags};
+use crate::world::{Aabb, Level, Linedef, LinedefFlags, LinedefId, Sector, SubsectorId};
 
 /* ----------------------------------------------------------------- */
 /*  Action queue – avoids mutable-borrow conflicts                    */
 /* ----------------------------------------------------------------- */
 enum Action {
-    SetState { entity: Entity, new_state: State },
-    Explode { entity: Entity },
+    SetState {
+        entity: Entity,
+        new_state: State,
+    },
+    Explode {
+        entity: Entity,
+    },
+    /// A missile flew into the sky; it's removed without a bang.
+    Vanish {
+        entity: Entity,
+    },
+    /// `missile` flew into `target`; damage it before exploding.
+    MissileHit {
+        missile: Entity,
+        target: Entity,
+    },
+    /// A charging lost soul flew into `target`.
+    SkullHit {
+        skull: Entity,
+        target: Entity,
+    },
+    CrossLine {
+        entity: Entity,
+        line: LinedefId,
+        side: usize,
+    },
+    Touch {
+        special: Entity,
+        toucher: Entity,
+    },
 }
 type Actions = SmallVec<[Action; 2]>;
 
@@ -33,40 +67,238 @@ type Actions = SmallVec<[Action; 2]>;
 /*  Public system                                                    */
 /* ================================================================= */
 
-pub fn xy_movement_system(world: &mut World, thing_grid: &mut ThingGrid, level: &Level) {
+#[allow(clippy::too_many_arguments)]
+pub fn xy_movement_system(
+    world: &mut World,
+    thing_grid: &mut ThingGrid,
+    level: &mut Level,
+    cfg: &PhysicsConfig,
+    rng: &mut Rng,
+    rules: GameRules,
+    skill: Skill,
+    defs: &Definitions,
+    switches: &SwitchList,
+    buttons: &mut Vec<Button>,
+    ceilings: &mut Vec<Ceiling>,
+    plats: &mut Vec<Platform>,
+    doors: &mut Vec<Door>,
+    floors: &mut Vec<Floor>,
+    events: &mut Vec<SimEvent>,
+) {
     let mut queue = Actions::new();
 
-    {
-        let query = world.query_mut::<(
-            &mut Position,
-            &mut Velocity,
-            &mut ActorFlags,
-            &Class,
-            &mut Subsector,
-            &mut Animation,
-        )>();
-
-        for (e, (p, v, f, c, ss, an)) in query {
-            queue.extend(p_xy_movement(level, thing_grid, e, p, v, f, c, ss, an));
+    // things that go anywhere this tic; the rest are left alone
+    let movers: SmallVec<[Entity; 16]> = world
+        .query_mut::<(&Velocity, &ActorFlags)>()
+        .into_iter()
+        .filter(|(_, (v, f))| v.0.x != 0.0 || v.0.y != 0.0 || f.0.contains(MobjFlags::SKULLFLY))
+        .map(|(e, _)| e)
+        .collect();
+
+    // work on copies: checking the move reads every other thing
+    type Q<'a> = (
+        &'a mut Position,
+        &'a mut Velocity,
+        &'a mut ActorFlags,
+        &'a Class,
+        &'a mut Subsector,
+        &'a Animation,
+    );
+    for e in movers {
+        let Ok((mut p, mut v, mut f, c, mut ss, an)) = world
+            .query_one_mut::<Q>(e)
+            .map(|(p, v, f, c, ss, an)| (*p, *v, *f, *c, *ss, *an))
+        else {
+            continue;
+        };
+        let mut motion = cfg.fixed_point.then(|| {
+            let prev = world.get::<&FixedMotion>(e).ok().map(|m| *m);
+            FixedMotion::sync(prev, p.0, v.0.truncate())
+        });
+        queue.extend(p_xy_movement(
+            level,
+            cfg,
+            world,
+            thing_grid,
+            e,
+            &mut p,
+            &mut v,
+            &mut f,
+            &c,
+            &mut ss,
+            &an,
+            motion.as_mut(),
+        ));
+        let mut dz = 0.0;
+        if let Ok((wp, wv, wf, _, wss, _)) = world.query_one_mut::<Q>(e) {
+            dz = p.1 - wp.1;
+            (*wp, *wv, *wf, *wss) = (p, v, f, ss);
+        }
+        if dz != 0.0 {
+            if let Ok(mut status) = world.get::<&mut PlayerStatus>(e) {
+                if systems::p_player_z_step(&mut status, dz, cfg.gravity) {
+                    events.push(SimEvent::Sound {
+                        sound: Sound::oof,
+                        origin: p.0,
+                        full_volume: false,
+                    });
+                }
+            }
+        }
+        if let Some(m) = motion {
+            let _ = world.insert_one(e, m);
         }
     }
 
     // side-effect phase
+    let mut ctx = SpecialCtx {
+        world,
+        thing_grid,
+        level,
+        rng,
+        rules,
+        skill,
+        defs,
+        switches,
+        buttons,
+        ceilings,
+        plats,
+        doors,
+        floors,
+        events,
+    };
+    run_actions(&mut ctx, queue);
+}
+
+fn run_actions(ctx: &mut SpecialCtx, queue: Actions) {
     for act in queue {
         match act {
-            Action::SetState { entity, new_state } => p_set_mobj_state(world, entity, new_state),
-            Action::Explode { entity } => p_explode_missile(world, entity, level),
+            Action::SetState { entity, new_state } => {
+                p_set_mobj_state(ctx.world, entity, new_state)
+            }
+            Action::Explode { entity } => mob::p_explode_missile(
+                ctx.world,
+                ctx.thing_grid,
+                ctx.level,
+                ctx.rng,
+                ctx.rules,
+                ctx.defs,
+                ctx.events,
+                entity,
+            ),
+            Action::Vanish { entity } => mob::remove_mobj(ctx.world, ctx.thing_grid, entity),
+            Action::MissileHit { missile, target } => {
+                p_missile_damage(ctx.world, ctx.rng, ctx.rules, ctx.defs, missile, target)
+            }
+            Action::SkullHit { skull, target } => {
+                p_skull_damage(ctx.world, ctx.rng, ctx.rules, ctx.defs, skull, target)
+            }
+            Action::CrossLine { entity, line, side } => {
+                specials::try_activate(ctx, line, side, Activation::Cross, entity);
+            }
+            Action::Touch { special, toucher } => pickups::p_touch_special_thing(
+                ctx.world,
+                ctx.thing_grid,
+                ctx.defs,
+                ctx.skill,
+                ctx.events,
+                special,
+                toucher,
+            ),
         }
     }
 }
 
+/* ================================================================= */
+/*  P_Move – monsters walking                                        */
+/* ================================================================= */
+
+/// Vanilla `P_Move`: step monster `actor` its speed along its
+/// [`MoveDir`], without momentum.
+///
+/// A blocked floater with room to fit in the opening rises or sinks
+/// `FLOAT_SPEED` towards its floor instead, and counts as moved.  A
+/// blocked walker uses the special lines in its way; if a door opens it
+/// drops its heading (`NoDir`) and also counts as moved, so it waits for
+/// the door rather than turning away – the heading is only picked anew
+/// once `p_move` fails on `NoDir`.
+pub(super) fn p_move(ctx: &mut SpecialCtx, cfg: &PhysicsConfig, actor: Entity) -> bool {
+    let dir = ctx
+        .world
+        .get::<&MoveDir>(actor)
+        .map_or(MoveDir::NoDir, |d| *d);
+    if dir == MoveDir::NoDir {
+        return false;
+    }
+    let Ok((mut pos, mut ss, mut flags, class)) = ctx
+        .world
+        .query_one_mut::<(&Position, &Subsector, &ActorFlags, &Class)>(actor)
+        .map(|(p, ss, f, c)| (*p, *ss, *f, *c))
+    else {
+        return false;
+    };
+    let dest = pos.0 + dir.step() * class.0.speed as f32;
+
+    let mut acts = Actions::new();
+    let mut blocked = Blocked::default();
+    let moved = p_try_move(
+        ctx.level,
+        cfg,
+        ctx.world,
+        ctx.thing_grid,
+        actor,
+        &mut pos,
+        &mut ss,
+        &mut flags,
+        &class,
+        dest,
+        &mut None,
+        &mut blocked,
+        &mut acts,
+    );
+    let float_to = blocked
+        .float_ok
+        .filter(|_| flags.0.contains(MobjFlags::FLOAT));
+    if moved {
+        flags.0.remove(MobjFlags::INFLOAT);
+    } else if let Some(floor_z) = float_to {
+        pos.1 += if pos.1 < floor_z {
+            FLOAT_SPEED
+        } else {
+            -FLOAT_SPEED
+        };
+        flags.0.insert(MobjFlags::INFLOAT);
+    }
+    if let Ok((wp, wss, wf)) = ctx
+        .world
+        .query_one_mut::<(&mut Position, &mut Subsector, &mut ActorFlags)>(actor)
+    {
+        (*wp, *wss, *wf) = (pos, ss, flags);
+    }
+    run_actions(ctx, acts);
+
+    if moved || float_to.is_some() {
+        return true;
+    }
+    if blocked.special_lines.is_empty() {
+        return false;
+    }
+    ctx.world.insert_one(actor, MoveDir::NoDir).ok();
+    specials::p_monster_use_lines(ctx, actor, &blocked.special_lines)
+}
+
 /* ================================================================= */
 /*  Core P_XYMovement                                                */
 /* ================================================================= */
 
+/// `motion` is the exact fixed-point state under
+/// `PhysicsConfig::fixed_point`; the move then runs on it, vanilla
+/// rounding and all, and `pos` / `vel` only receive the results.
 #[allow(clippy::too_many_arguments)]
 fn p_xy_movement(
     level: &Level,
+    cfg: &PhysicsConfig,
+    world: &World,
     thing_grid: &mut ThingGrid,
     ent: Entity,
     pos: &mut Position,
@@ -74,7 +306,8 @@ fn p_xy_movement(
     flags: &mut ActorFlags,
     class: &Class,
     subsector: &mut Subsector,
-    anim: &mut Animation,
+    anim: &Animation,
+    mut motion: Option<&mut FixedMotion>,
 ) -> Actions {
     let mut acts = Actions::new();
 
@@ -94,51 +327,150 @@ fn p_xy_movement(
     let is_player = class.0.id == "PLAYER";
 
     /* -- 1: clamp & prepare move ----------------------------------- */
-    vel.0.x = vel.0.x.clamp(-MAX_MOVE, MAX_MOVE);
-    vel.0.y = vel.0.y.clamp(-MAX_MOVE, MAX_MOVE);
+    let max_move = cfg.max_move;
+    vel.0.x = vel.0.x.clamp(-max_move, max_move);
+    vel.0.y = vel.0.y.clamp(-max_move, max_move);
     let (mut xmove, mut ymove) = (vel.0.x, vel.0.y);
 
+    let fixed_max = Fixed::from_f32(max_move);
+    let (mut fxmove, mut fymove) = (Fixed::ZERO, Fixed::ZERO);
+    if let Some(m) = motion.as_deref_mut() {
+        m.momx = m.momx.clamp(-fixed_max, fixed_max);
+        m.momy = m.momy.clamp(-fixed_max, fixed_max);
+        (fxmove, fymove) = (m.momx, m.momy);
+    }
+
     /* -- 2: possibly split the move (vanilla does this recursively) */
-    while xmove != 0.0 || ymove != 0.0 {
-        let mut step = Vec2::new(xmove, ymove);
-        if step.x.abs() > MAX_MOVE * 0.5 || step.y.abs() > MAX_MOVE * 0.5 {
-            step *= 0.5; // halve once – good enough for map speeds
-        }
-        xmove -= step.x;
-        ymove -= step.y;
+    loop {
+        let mut fixed_dest = None;
+        let dest = if let Some(m) = motion.as_deref() {
+            // vanilla only splits large *positive* moves, halving twice
+            // over: `/ 2` truncates, `>> 1` floors
+            let step = if fxmove > fixed_max.half() || fymove > fixed_max.half() {
+                let step = (fxmove.half(), fymove.half());
+                (fxmove, fymove) = (Fixed(fxmove.0 >> 1), Fixed(fymove.0 >> 1));
+                step
+            } else {
+                let step = (fxmove, fymove);
+                (fxmove, fymove) = (Fixed::ZERO, Fixed::ZERO);
+                step
+            };
+            let (x, y) = (m.x + step.0, m.y + step.1);
+            fixed_dest = Some((x, y));
+            Vec2::new(x.to_f32(), y.to_f32())
+        } else {
+            let mut step = Vec2::new(xmove, ymove);
+            if step.x.abs() > max_move * 0.5 || step.y.abs() > max_move * 0.5 {
+                step *= 0.5; // halve once – good enough for map speeds
+            }
+            xmove -= step.x;
+            ymove -= step.y;
+            pos.0 + step
+        };
 
-        let dest = pos.0 + step;
         let mut slide_normal = None;
+        let mut blocked = Blocked::default();
 
-        if !p_try_move(
+        if p_try_move(
             level,
+            cfg,
+            world,
             thing_grid,
             ent,
             pos,
             subsector,
             flags,
             class,
-            is_player,
             dest,
             &mut slide_normal,
+            &mut blocked,
+            &mut acts,
         ) {
+            if let (Some(m), Some((x, y))) = (motion.as_deref_mut(), fixed_dest) {
+                (m.x, m.y) = (x, y);
+            }
+        } else {
             // fallbacks
             if is_player {
                 p_slide_move(level, pos, vel, class, &slide_normal);
             } else if flags.0.contains(MobjFlags::MISSILE) {
-                acts.push(Action::Explode { entity: ent });
+                // the sky hack: a missile stopped by a line whose far
+                // side is sky went into the sky, and just goes
+                let into_sky = blocked
+                    .ceiling_line
+                    .and_then(|line| back_sector(level, line))
+                    .is_some_and(|sec| sec.is_sky_ceiling);
+                acts.push(if into_sky {
+                    Action::Vanish { entity: ent }
+                } else {
+                    Action::Explode { entity: ent }
+                });
+                return acts;
             } else {
                 vel.0.x = 0.0;
                 vel.0.y = 0.0;
+                if let Some(m) = motion.as_deref_mut() {
+                    (m.momx, m.momy) = (Fixed::ZERO, Fixed::ZERO);
+                }
+                // a lost soul isn't a missile: whatever it hits, wall or
+                // thing, it just stops dead and goes back to hovering
+                if flags.0.contains(MobjFlags::SKULLFLY) {
+                    flags.0.remove(MobjFlags::SKULLFLY);
+                    vel.0 = Vec3::ZERO;
+                    acts.push(Action::SetState {
+                        entity: ent,
+                        new_state: class.0.spawnstate,
+                    });
+                }
             }
         }
+
+        let done = if motion.is_some() {
+            fxmove == Fixed::ZERO && fymove == Fixed::ZERO
+        } else {
+            xmove == 0.0 && ymove == 0.0
+        };
+        if done {
+            break;
+        }
     }
 
     /* -- 3: friction / stop ---------------------------------------- */
-    if !flags.0.intersects(MobjFlags::MISSILE | MobjFlags::SKULLFLY)
-        && pos.1 <= get_floor_z(level, subsector)
+    // the floor under the whole box (vanilla `mo->floorz`), which is only
+    // above the sector's when the box hangs over a step
+    let sector_floor = get_floor_z(level, subsector);
+    let floor_z = if pos.1 > sector_floor {
+        let thing = Mover {
+            ent,
+            pos: *pos,
+            class: *class,
+            flags: *flags,
+        };
+        let at = motion.as_deref().map_or(pos.0, |m| m.pos());
+        p_check_position(level, cfg, world, thing_grid, &thing, at).floor_z
+    } else {
+        sector_floor
+    };
+    // a corpse halfway off a step with some momentum keeps sliding
+    let sliding = flags.0.contains(MobjFlags::CORPSE)
+        && floor_z != sector_floor
+        && match motion.as_deref() {
+            Some(m) => {
+                let quarter = Fixed::from_f32(0.25);
+                m.momx > quarter || m.momx < -quarter || m.momy > quarter || m.momy < -quarter
+            }
+            None => vel.0.x.abs() > 0.25 || vel.0.y.abs() > 0.25,
+        };
+    if !flags.0.intersects(MobjFlags::MISSILE | MobjFlags::SKULLFLY) && pos.1 <= floor_z && !sliding
     {
-        if vel.0.x.abs() < STOP_SPEED && vel.0.y.abs() < STOP_SPEED && player_cmd_idle() {
+        let slow = match motion.as_deref() {
+            Some(m) => {
+                let stop = Fixed::from_f32(cfg.stop_speed);
+                m.momx > -stop && m.momx < stop && m.momy > -stop && m.momy < stop
+            }
+            None => vel.0.x.abs() < cfg.stop_speed && vel.0.y.abs() < cfg.stop_speed,
+        };
+        if slow && player_cmd_idle() {
             if is_player && (anim.state >= State::PLAY_RUN1 && anim.state <= State::PLAY_RUN4) {
                 acts.push(Action::SetState {
                     entity: ent,
@@ -147,12 +479,24 @@ fn p_xy_movement(
             }
             vel.0.x = 0.0;
             vel.0.y = 0.0;
+            if let Some(m) = motion.as_deref_mut() {
+                (m.momx, m.momy) = (Fixed::ZERO, Fixed::ZERO);
+            }
+        } else if let Some(m) = motion.as_deref_mut() {
+            let friction = Fixed::from_f32(cfg.friction);
+            m.momx = m.momx * friction;
+            m.momy = m.momy * friction;
         } else {
-            vel.0.x *= FRICTION;
-            vel.0.y *= FRICTION;
+            vel.0.x *= cfg.friction;
+            vel.0.y *= cfg.friction;
         }
     }
 
+    if let Some(m) = motion {
+        pos.0 = m.pos();
+        (vel.0.x, vel.0.y) = (m.momx.to_f32(), m.momy.to_f32());
+    }
+
     acts
 }
 
@@ -160,54 +504,114 @@ fn p_xy_movement(
 /*  Helpers – still many TODOs                                       */
 /* ================================================================= */
 
+/// How fast a floating monster rises or sinks (vanilla `FLOATSPEED`).
+pub(super) const FLOAT_SPEED: f32 = 4.0;
+
+/// What a failed [`p_try_move`] leaves behind for [`p_move`].
+#[derive(Default)]
+struct Blocked {
+    /// Floor of the opening, if it was tall enough for the mover and only
+    /// the heights were in the way (vanilla `floatok` and `tmfloorz`).
+    float_ok: Option<f32>,
+    /// Special lines the failed move touched (vanilla `spechit`).
+    special_lines: SmallVec<[LinedefId; 4]>,
+    /// The line that set the lowest ceiling (vanilla `ceilingline`).
+    ceiling_line: Option<LinedefId>,
+}
+
+/// Sector on the left side of `line`, if it has one.
+fn back_sector(level: &Level, line: LinedefId) -> Option<&Sector> {
+    let side = level.linedefs.get(line as usize)?.left_sidedef?;
+    level
+        .sectors
+        .get(level.sidedefs.get(side as usize)?.sector as usize)
+}
+
 fn player_cmd_idle() -> bool {
     false /* TODO */
 }
 
-fn get_floor_z(level: &Level, sub: &Subsector) -> f32 {
+pub(super) fn get_floor_z(level: &Level, sub: &Subsector) -> f32 {
     level.sectors[level.subsectors[sub.0 as usize].sector as usize].floor_h
 }
 
 #[allow(clippy::too_many_arguments)]
 fn p_try_move(
     level: &Level,
+    cfg: &PhysicsConfig,
+    world: &World,
     grid: &mut ThingGrid,
     ent: Entity,
     pos: &mut Position,
     sub: &mut Subsector,
     flags: &mut ActorFlags,
     class: &Class,
-    is_player: bool,
     dest: Vec2,
     slide_nrm: &mut Option<Vec2>,
+    blocked: &mut Blocked,
+    acts: &mut Actions,
 ) -> bool {
-    let mut thing = ThingSpatial {
+    let thing = Mover {
         ent,
         pos: *pos,
         class: *class,
         flags: *flags,
     };
 
-    let check = p_check_position(level, grid, &thing, is_player, dest);
+    let check = p_check_position(level, cfg, world, grid, &thing, dest);
+    acts.extend(check.touched.iter().map(|&special| Action::Touch {
+        special,
+        toucher: ent,
+    }));
+    if let Some(target) = check.missile_hit {
+        acts.push(if flags.0.contains(MobjFlags::SKULLFLY) {
+            Action::SkullHit { skull: ent, target }
+        } else {
+            Action::MissileHit {
+                missile: ent,
+                target,
+            }
+        });
+    }
+    let height = mob::mobj_height(class, flags);
+    let fits = check.ceiling_z - check.floor_z >= height;
+    // players walk off anything; monsters only off ledges they could
+    // climb back up, unless they drop off or float
+    let walks_off =
+        class.0.id == "PLAYER" || flags.0.intersects(MobjFlags::DROPOFF | MobjFlags::FLOAT);
 
     if check.blocked
-        || check.ceiling_z - check.floor_z < class.0.height as f32
-        || check.floor_z - pos.1 > MAX_STEP_HEIGHT
-        || check.floor_z - check.dropoff_z > MAX_STEP_HEIGHT
+        || (!flags.0.contains(MobjFlags::NOCLIP)
+            && (!fits
+                || check.ceiling_z - pos.1 < height
+                || check.floor_z - pos.1 > cfg.max_step
+                || (!walks_off && check.floor_z - check.dropoff_z > cfg.max_step)))
     {
         *slide_nrm = None; // TODO
+        *blocked = Blocked {
+            float_ok: (!check.blocked && fits).then_some(check.floor_z),
+            special_lines: check.special_lines,
+            ceiling_line: check.ceiling_line,
+        };
         return false;
     }
 
-    p_cross_special_lines(level, dest, pos.0, check.special_lines);
-
     // relink
-    p_unset_thing_position(grid, &thing);
+    let old = pos.0;
+    p_unset_thing_position(grid, ent);
     pos.0 = dest;
-    pos.1 = check.floor_z;
+    pos.1 = if flags.0.contains(MobjFlags::NOGRAVITY) {
+        // floaters keep their height, squeezed into the new opening
+        pos.1.min(check.ceiling_z - height).max(check.floor_z)
+    } else {
+        check.floor_z
+    };
     sub.0 = check.subsector;
-    thing.pos = *pos;
-    p_set_thing_position(grid, thing);
+    p_set_thing_position(grid, ent, pos, class, flags, check.subsector);
+
+    if !flags.0.intersects(MobjFlags::TELEPORT | MobjFlags::NOCLIP) {
+        p_cross_special_lines(level, ent, dest, old, &check.special_lines, acts);
+    }
 
     true
 }
@@ -267,6 +671,14 @@ pub struct CheckCtx {
     pub thing_is_missile: bool,
     pub thins_is_player: bool,
     pub special_lines: SmallVec<[LinedefId; 4]>,
+    /// `SPECIAL` things a `PICKUP` thing overlapped on the way.
+    pub touched: SmallVec<[Entity; 2]>,
+    /// `PhysicsConfig::infinitely_tall_actors`.
+    pub infinitely_tall: bool,
+    /// `PhysicsConfig::max_step`.
+    pub max_step: f32,
+    /// What a missile or a charging lost soul flew into.
+    pub missile_hit: Option<Entity>,
 }
 
 /// returns *false* when the line blocks the move
@@ -283,7 +695,8 @@ pub fn pit_check_line(level: &Level, line: &Linedef, ctx: &mut CheckCtx) -> bool
     /* all corners on same side ? ------------------------------------ */
     let v1 = level.vertices[line.v1 as usize].pos;
     let v2 = level.vertices[line.v2 as usize].pos;
-    if box_on_line_side(&ctx.bbox, v1, v2) != -1 {
+    // a zero-length line has no sides to cross
+    if v1 == v2 || box_on_line_side(&ctx.bbox, v1, v2) != -1 {
         return true;
     }
 
@@ -321,23 +734,40 @@ pub fn pit_check_line(level: &Level, line: &Linedef, ctx: &mut CheckCtx) -> bool
     true
 }
 
+/// The thing `P_CheckPosition` is asked about, as it stands before the
+/// move.
+#[derive(Clone, Copy)]
+pub struct Mover {
+    pub ent: Entity,
+    pub pos: Position,
+    pub class: Class,
+    pub flags: ActorFlags,
+}
+
 /// Everything P_CheckPosition discovered for the tentative spot.
 pub struct CheckResult {
     pub blocked: bool,
     pub floor_z: f32,
     pub ceiling_z: f32,
     pub dropoff_z: f32,
-    pub subsector: u16,
+    pub subsector: SubsectorId,
     pub special_lines: SmallVec<[LinedefId; 4]>,
+    /// The line that set `ceiling_z`, if one did (vanilla `ceilingline`).
+    pub ceiling_line: Option<LinedefId>,
+    /// `SPECIAL` things a `PICKUP` thing overlapped on the way.
+    pub touched: SmallVec<[Entity; 2]>,
+    /// Shootable thing a missile hit; it explodes there.
+    pub missile_hit: Option<Entity>,
 }
 
 /// Full collision test (lines + things) at <dest>.
 /// *Return `None` for a solid block; otherwise return floor/ceiling data.*
-fn p_check_position(
+pub(super) fn p_check_position(
     level: &Level,
+    cfg: &PhysicsConfig,
+    world: &World,
     grid: &ThingGrid,
-    thing: &ThingSpatial,
-    is_player: bool,
+    thing: &Mover,
     dest: Vec2,
 ) -> CheckResult {
     let radius = thing.class.0.radius as f32;
@@ -353,6 +783,21 @@ fn p_check_position(
         max: dest + Vec2::splat(radius),
     };
 
+    // MF_NOCLIP: take the sector heights and skip every blocking test
+    if thing.flags.0.contains(MobjFlags::NOCLIP) {
+        return CheckResult {
+            blocked: false,
+            floor_z: sector.floor_h,
+            ceiling_z: sector.ceil_h,
+            dropoff_z: sector.floor_h,
+            subsector: ss_idx,
+            special_lines: SmallVec::new(),
+            ceiling_line: None,
+            touched: SmallVec::new(),
+            missile_hit: None,
+        };
+    }
+
     let mut ctx = CheckCtx {
         bbox,
         floor_z: sector.floor_h,
@@ -360,12 +805,19 @@ fn p_check_position(
         dropoff_z: sector.floor_h,
         ceilingline: None,
         thing_is_missile: thing.class.0.flags.contains(MobjFlags::MISSILE),
-        thins_is_player: is_player,
+        thins_is_player: thing.class.0.id == "PLAYER",
         special_lines: SmallVec::<[LinedefId; 4]>::new(),
+        touched: SmallVec::new(),
+        infinitely_tall: cfg.infinitely_tall_actors,
+        max_step: cfg.max_step,
+        missile_hit: None,
     };
 
-    let blocked = !grid.for_each_in_bbox(bbox, |other| !pit_check_thing(thing, other, dest))
-        || !level.block_lines_iter(bbox, |ld| pit_check_line(level, ld, &mut ctx));
+    let blocked = !grid.for_each_in_bbox(bbox, |other| {
+        !pit_check_thing(world, thing, other, dest, &mut ctx)
+    }) || !level.block_lines_iter(grid.line_query(), bbox, |ld| {
+        pit_check_line(level, ld, &mut ctx)
+    });
 
     CheckResult {
         blocked,
@@ -374,12 +826,45 @@ fn p_check_position(
         dropoff_z: ctx.dropoff_z,
         subsector: ss_idx,
         special_lines: ctx.special_lines,
+        ceiling_line: ctx.ceilingline,
+        touched: ctx.touched,
+        missile_hit: ctx.missile_hit,
     }
 }
 
-pub fn pit_check_thing(self_stub: &ThingSpatial, other: &ThingSpatial, dest: Vec2) -> bool {
+/// `true` when `other` blocks `self_stub` at `dest`.  Specials a pickup
+/// thing overlaps are queued in `ctx.touched` for `P_TouchSpecialThing`;
+/// a thing passed over or under narrows `ctx`'s floor or ceiling.
+pub fn pit_check_thing(
+    world: &World,
+    self_stub: &Mover,
+    stub: &ThingSpatial,
+    dest: Vec2,
+    ctx: &mut CheckCtx,
+) -> bool {
     /* ─── early outs ─────────────────────────────────────────────── */
 
+    // never collide with ourselves
+    if stub.ent == self_stub.ent {
+        return false;
+    }
+
+    // distance check in the XY plane
+    let block_dist = stub.radius + self_stub.class.0.radius as f32;
+    if (stub.pos.x - dest.x).abs() >= block_dist || (stub.pos.y - dest.y).abs() >= block_dist {
+        return false; // no overlap
+    }
+
+    let Some((pos, class, flags)) = fetch_thing(world, stub.ent) else {
+        return false;
+    };
+    let other = Mover {
+        ent: stub.ent,
+        pos,
+        class,
+        flags,
+    };
+
     // ignore non‑solid, non‑special, non‑shootable actors
     if !other
         .flags
@@ -389,22 +874,11 @@ pub fn pit_check_thing(self_stub: &ThingSpatial, other: &ThingSpatial, dest: Vec
         return false;
     }
 
-    // never collide with ourselves
-    if other.ent == self_stub.ent {
-        return false;
-    }
-
-    // distance check in the XY plane
-    let block_dist = (other.class.0.radius + self_stub.class.0.radius) as f32;
-    if (other.pos.0.x - dest.x).abs() >= block_dist || (other.pos.0.y - dest.y).abs() >= block_dist
-    {
-        return false; // no overlap
-    }
-
     /* ─── SKULLFLY (charging lost‑soul) --------------------------- */
+    // it hits whatever is in the way, at any height; the blocked move
+    // stops it
     if self_stub.flags.0.contains(MobjFlags::SKULLFLY) {
-        // TODO: call P_DamageMobj(other, self, self, ...)
-        //       reset SKULLFLY state + momentum
+        ctx.missile_hit = Some(other.ent);
         return true;
     }
 
@@ -418,60 +892,84 @@ pub fn pit_check_thing(self_stub: &ThingSpatial, other: &ThingSpatial, dest: Vec
             return false; // underneath
         }
 
-        // same‑species optimisation
-        // if let Some(origin_target) = None {
-        //     // TODO store the missile's owner in the stub and skip
-        //     //      damage check if `origin_target.kind == other.kind`
-        // }
+        // don't hit the thing that fired it; its species is spared the
+        // damage in `p_missile_damage`
+        if world
+            .get::<&Target>(self_stub.ent)
+            .is_ok_and(|t| t.0 == other.ent)
+        {
+            return false;
+        }
 
         if !other.flags.0.contains(MobjFlags::SHOOTABLE) {
             return other.flags.0.contains(MobjFlags::SOLID);
         }
 
-        // TODO: apply missile damage, spawn explosion, etc.
+        // damage / explode
+        ctx.missile_hit = Some(other.ent);
         return true;
     }
 
     /* ─── SPECIAL pickup ------------------------------------------ */
-    if other.flags.0.contains(MobjFlags::SPECIAL) {
-        let solid = other.flags.0.contains(MobjFlags::SOLID);
-
-        if self_stub.flags.0.contains(MobjFlags::PICKUP) {
-            // TODO: P_TouchSpecialThing(other,self)
-        }
-        return solid;
+    if other.flags.0.contains(MobjFlags::SPECIAL) && self_stub.flags.0.contains(MobjFlags::PICKUP) {
+        ctx.touched.push(other.ent);
     }
 
     /* ─── ordinary solid collision -------------------------------- */
-    other.flags.0.contains(MobjFlags::SOLID)
+    other.flags.0.contains(MobjFlags::SOLID) && !p_pass_over_or_under(self_stub, &other, ctx)
 }
 
-/*================================================================ */
-/* ===  Small helper *stubs*  ==================================== */
-/*================================================================ */
-
-/// Remove the actor from the spatial data-structures (blockmap / BSP).
-fn p_unset_thing_position(grid: &mut ThingGrid, thing: &ThingSpatial) {
-    if !thing.flags.0.contains(MobjFlags::NOBLOCKMAP) {
-        grid.remove(thing);
+/// Finite-height actors only: can `mover` stand on `other` (stepping up
+/// if it is low enough) or pass beneath it?  Raises `ctx`'s floor to
+/// `other`'s top, or lowers its ceiling to `other`'s bottom, when so.
+fn p_pass_over_or_under(mover: &Mover, other: &Mover, ctx: &mut CheckCtx) -> bool {
+    if ctx.infinitely_tall {
+        return false;
     }
-}
-
-/// Re-link the actor at its new coordinates.
-fn p_set_thing_position(grid: &mut ThingGrid, thing: ThingSpatial) {
-    if !thing.flags.0.contains(MobjFlags::NOBLOCKMAP) {
-        grid.insert(thing);
+    let z = mover.pos.1;
+    let top = other.pos.1 + other.class.0.height as f32;
+    if top <= z + ctx.max_step {
+        ctx.floor_z = ctx.floor_z.max(top);
+        // standing on it isn't a ledge to fall from
+        ctx.dropoff_z = ctx.dropoff_z.max(top);
+        return true;
+    }
+    if z + mover.class.0.height as f32 <= other.pos.1 {
+        ctx.ceiling_z = ctx.ceiling_z.min(other.pos.1);
+        return true;
     }
+    false
 }
 
-/// Check special lines crossed between <old_xy> → <new_xy>.
+/*================================================================ */
+/* ===  Small helper *stubs*  ==================================== */
+/*================================================================ */
+
+/// Check special lines crossed between <old_xy> → <new_xy> and queue
+/// them for `P_CrossSpecialLine` (vanilla walks `spechit` backwards).
 fn p_cross_special_lines(
-    _level: &Level,
-    _new_xy: Vec2,
-    _old_xy: Vec2,
-    _special_lines: SmallVec<[LinedefId; 4]>,
+    level: &Level,
+    ent: Entity,
+    new_xy: Vec2,
+    old_xy: Vec2,
+    special_lines: &[LinedefId],
+    acts: &mut Actions,
 ) {
-    /* TODO (use P_PointOnLineSide + P_CrossSpecialLine later) */
+    for &line in special_lines.iter().rev() {
+        let ld = &level.linedefs[line as usize];
+        let v1 = level.vertices[ld.v1 as usize].pos;
+        let v2 = level.vertices[ld.v2 as usize].pos;
+
+        let side = point_on_line_side(new_xy, v1, v2);
+        let old_side = point_on_line_side(old_xy, v1, v2);
+        if side != old_side {
+            acts.push(Action::CrossLine {
+                entity: ent,
+                line,
+                side: old_side,
+            });
+        }
+    }
 }
 
 /*----------------- helper stubs to fill later -----------------*/
@@ -492,7 +990,518 @@ fn p_set_mobj_state(world: &mut World, entity: Entity, new_state: State) {
     /* TODO */
 }
 
-fn p_explode_missile(world: &mut World, entity: Entity, _level: &Level) {
-    world.despawn(entity).ok();
-    /* TODO missile impact FX */
+/// The missile half of vanilla `PIT_CheckThing`: `missile` hurts
+/// `target` for 1‥8 × its damage, blaming whoever fired it.  The
+/// shooter's own species only wakes up; players may shoot each other.
+fn p_missile_damage(
+    world: &mut World,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    missile: Entity,
+    target: Entity,
+) {
+    let Ok((class, owner)) = world
+        .query_one_mut::<(&Class, Option<&Target>)>(missile)
+        .map(|(c, t)| (*c, t.map(|t| t.0)))
+    else {
+        return;
+    };
+    let species = |e: Entity| world.get::<&Class>(e).ok().map(|c| c.0);
+    if let (Some(shooter), Some(victim)) = (owner.and_then(species), species(target)) {
+        if victim.id != "PLAYER" && damage::same_species(&shooter, &victim) {
+            damage::p_wake(world, rules, defs, target);
+            return;
+        }
+    }
+    let damage = (rng.p_random() % 8 + 1) * class.0.damage;
+    damage::p_damage_mobj(
+        world,
+        rng,
+        rules,
+        defs,
+        target,
+        Some(missile),
+        owner,
+        damage,
+    );
+}
+
+/// The lost soul half of vanilla `PIT_CheckThing`: a charging `skull`
+/// hurts `target` for 1‥8 × its damage.
+fn p_skull_damage(
+    world: &mut World,
+    rng: &mut Rng,
+    rules: GameRules,
+    defs: &Definitions,
+    skull: Entity,
+    target: Entity,
+) {
+    let Ok(damage) = world.get::<&Class>(skull).map(|c| c.0.damage) else {
+        return;
+    };
+    let damage = (rng.p_random() % 8 + 1) * damage;
+    damage::p_damage_mobj(
+        world,
+        rng,
+        rules,
+        defs,
+        target,
+        Some(skull),
+        Some(skull),
+        damage,
+    );
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{Angle, Health, InputCmd, TicRunner, fixed};
+    use crate::sim::{enemy, mob};
+    use crate::world::{Blockmap, Vertex, VertexId};
+    use crate::{defs, world::testmap};
+
+    #[test]
+    fn missiles_spare_their_shooters_species() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut world = World::new();
+        let mut grid = ThingGrid::new(level.blockmap.origin);
+        let mut spawn = |world: &mut World, id, x| {
+            let info = defs::by_id(id).unwrap();
+            mob::spawn_mobj_at(
+                world,
+                &mut grid,
+                &level,
+                &Definitions::shared_builtin(),
+                info,
+                Vec2::new(x, 64.0),
+                Angle::ZERO,
+            )
+        };
+        let imp = spawn(&mut world, "TROOP", 16.0);
+        let other_imp = spawn(&mut world, "TROOP", 48.0);
+        let zombie = spawn(&mut world, "POSSESSED", 80.0);
+        let player = spawn(&mut world, "PLAYER", 112.0);
+        let fireball = spawn(&mut world, "TROOPSHOT", 32.0);
+        world.insert_one(fireball, Target(imp)).unwrap();
+        let mut rng = Rng::default();
+        let health = |world: &World, e| world.get::<&Health>(e).unwrap().0;
+
+        p_missile_damage(
+            &mut world,
+            &mut rng,
+            GameRules::VANILLA,
+            &Definitions::shared_builtin(),
+            fireball,
+            other_imp,
+        );
+        assert_eq!(health(&world, other_imp), 60);
+        let info = defs::by_id("TROOP").unwrap();
+        let anim = *world.get::<&Animation>(other_imp).unwrap();
+        assert_eq!(anim.state, info.seestate, "not woken");
+        assert!(world.get::<&Target>(other_imp).is_err());
+
+        // other species and players get hurt and turn on the shooter
+        for victim in [zombie, player] {
+            let before = health(&world, victim);
+            p_missile_damage(
+                &mut world,
+                &mut rng,
+                GameRules::VANILLA,
+                &Definitions::shared_builtin(),
+                fireball,
+                victim,
+            );
+            assert!(health(&world, victim) < before);
+            assert_eq!(world.get::<&Target>(victim).unwrap().0, imp);
+        }
+    }
+
+    /// A barrel in room A and a player `z` units up, right on top of it.
+    fn stacked(z: f32) -> (Level, World, ThingGrid, Mover, Entity) {
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut world = World::new();
+        let mut grid = ThingGrid::new(level.blockmap.origin);
+        let at = Vec2::new(64.0, 64.0);
+        let barrel = mob::spawn_mobj_at(
+            &mut world,
+            &mut grid,
+            &level,
+            &Definitions::shared_builtin(),
+            defs::by_id("BARREL").unwrap(),
+            at,
+            Angle::ZERO,
+        );
+        let player = mob::spawn_mobj_at(
+            &mut world,
+            &mut grid,
+            &level,
+            &Definitions::shared_builtin(),
+            defs::by_id("PLAYER").unwrap(),
+            at,
+            Angle::ZERO,
+        );
+        world.get::<&mut Position>(player).unwrap().1 = z;
+        let (pos, class, flags) = fetch_thing(&world, player).unwrap();
+        let mover = Mover {
+            ent: player,
+            pos,
+            class,
+            flags,
+        };
+        (level, world, grid, mover, barrel)
+    }
+
+    const FINITE: PhysicsConfig = PhysicsConfig {
+        infinitely_tall_actors: false,
+        ..PhysicsConfig::VANILLA
+    };
+
+    #[test]
+    fn infinitely_tall_actors_block_at_any_height() {
+        let (level, world, grid, mover, _) = stacked(42.0);
+        let cfg = PhysicsConfig::default();
+        let check = p_check_position(&level, &cfg, &world, &grid, &mover, mover.pos.0);
+        assert!(check.blocked);
+    }
+
+    #[test]
+    fn zero_length_lines_never_block() {
+        let (mut level, world, _, mover, _) = stacked(0.0);
+        let grid = ThingGrid::new(level.blockmap.origin);
+        level.vertices.push(Vertex {
+            pos: Vec2::new(64.0, 64.0),
+        });
+        let v = level.vertices.len() as VertexId - 1;
+        let mut line = level.linedefs[0].clone();
+        (line.v1, line.v2) = (v, v);
+        line.id = level.linedefs.len() as LinedefId;
+        line.bbox = Aabb {
+            min: Vec2::splat(64.0),
+            max: Vec2::splat(64.0),
+        };
+        level.linedefs.push(line);
+        level.blockmap = Blockmap::rebuild(&level.vertices, &level.linedefs);
+
+        let check = p_check_position(&level, &FINITE, &world, &grid, &mover, mover.pos.0);
+        assert!(!check.blocked);
+    }
+
+    #[test]
+    fn things_stand_on_solid_actors() {
+        let (level, world, grid, mover, _) = stacked(42.0);
+        let check = p_check_position(&level, &FINITE, &world, &grid, &mover, mover.pos.0);
+        assert!(!check.blocked);
+        assert_eq!((check.floor_z, check.ceiling_z), (42.0, 128.0));
+
+        // a barrel top within a step of our feet is climbed onto
+        let (level, world, grid, mover, _) = stacked(20.0);
+        let check = p_check_position(&level, &FINITE, &world, &grid, &mover, mover.pos.0);
+        assert!(!check.blocked);
+        assert_eq!(check.floor_z, 42.0);
+
+        // but not one higher up
+        let (level, world, grid, mover, _) = stacked(0.0);
+        let check = p_check_position(&level, &FINITE, &world, &grid, &mover, mover.pos.0);
+        assert!(check.blocked);
+    }
+
+    #[test]
+    fn things_pass_under_floating_actors() {
+        let (level, world, grid, mover, barrel) = stacked(0.0);
+        world.get::<&mut Position>(barrel).unwrap().1 = 80.0;
+        let check = p_check_position(&level, &FINITE, &world, &grid, &mover, mover.pos.0);
+        assert!(!check.blocked);
+        assert_eq!((check.floor_z, check.ceiling_z), (0.0, 80.0));
+    }
+
+    /// Vanilla walking over open floor, on plain integers: `P_Thrust`
+    /// along `angle`, the positive-only move split, `FixedMul` friction.
+    fn vanilla_walk(mut x: i32, mut y: i32, angle: u32, forward: i32, tics: u32) -> (i32, i32) {
+        let fine = |a: u32| fixed::fine_cosine(a).0;
+        let (cos, sin) = (fine(angle), fine(angle.wrapping_sub(fixed::ANG90)));
+        let thrust = |m: i32, t: i32| m + ((i64::from(forward * 2048) * i64::from(t)) >> 16) as i32;
+        let (mut momx, mut momy) = (0, 0);
+        for _ in 0..tics {
+            momx = thrust(momx, cos).clamp(-30 << 16, 30 << 16);
+            momy = thrust(momy, sin).clamp(-30 << 16, 30 << 16);
+            let (mut xmove, mut ymove) = (momx, momy);
+            while xmove != 0 || ymove != 0 {
+                if xmove > 15 << 16 || ymove > 15 << 16 {
+                    (x, y) = (x + xmove / 2, y + ymove / 2);
+                    (xmove, ymove) = (xmove >> 1, ymove >> 1);
+                } else {
+                    (x, y) = (x + xmove, y + ymove);
+                    (xmove, ymove) = (0, 0);
+                }
+            }
+            momx = ((i64::from(momx) * 0xE800) >> 16) as i32;
+            momy = ((i64::from(momy) * 0xE800) >> 16) as i32;
+        }
+        (x, y)
+    }
+
+    /// Fixed-point mode; spawn the player at `at` facing `angle` and hold
+    /// walk forward for `tics`.  Returns its exact position.
+    fn walk_fixed(level: &mut Level, at: Vec2, angle: Angle, tics: u32) -> (Fixed, Fixed) {
+        let mut sim = TicRunner::new(level);
+        sim.set_physics(PhysicsConfig {
+            fixed_point: true,
+            ..PhysicsConfig::VANILLA
+        });
+        for thing in &level.things {
+            sim.spawn_map_thing(level, thing);
+        }
+        let ss = level.locate_subsector(at);
+        let player = sim.spawn_mobj(level, defs::by_id("PLAYER").unwrap(), at.x, at.y, angle, ss);
+        sim.set_player(player);
+        let walk = InputCmd {
+            forward: 1.0,
+            ..InputCmd::default()
+        };
+        for _ in 0..tics {
+            sim.run_tic(level, walk);
+        }
+        let m = *sim.world().get::<&FixedMotion>(player).unwrap();
+        assert_eq!(sim.world().get::<&Position>(player).unwrap().0, m.pos());
+        (m.x, m.y)
+    }
+
+    #[test]
+    fn fixed_point_walk_matches_vanilla_math() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        // east through the open middle, drifting north on finesine[0]
+        let end = walk_fixed(&mut level, Vec2::new(24.0, 40.0), Angle::ZERO, 30);
+        let (x, y) = vanilla_walk(24 << 16, 40 << 16, 0, 0x19, 30);
+        assert_eq!(end, (Fixed(x), Fixed(y)));
+        assert!(y > 40 << 16 && x > 190 << 16);
+    }
+
+    #[test]
+    fn e1m1_fixed_point_walk_is_vanilla() {
+        let wad = crate::wad::Wad::from_file(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/doom.wad"),
+        )
+        .unwrap();
+        let mut bank = crate::world::TextureBank::default_with_checker();
+        let mut level = crate::wad::load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        level.finalise_bsp();
+        let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+        let (at, angle) = (start.pos, start.angle);
+
+        // 100 tics up the start room; finecosine[ANG90] is -25, so vanilla
+        // drifts west a little on the way
+        let end = walk_fixed(&mut level, at, angle, 100);
+        let (x, y) = vanilla_walk(
+            Fixed::from_f32(at.x).0,
+            Fixed::from_f32(at.y).0,
+            angle.bam(),
+            0x19,
+            100,
+        );
+        assert_eq!(end, (Fixed(x), Fixed(y)));
+    }
+
+    /// Room B raised into a 32-unit ledge over the middle strip and A,
+    /// with `id` standing at `x`; a monster is headed `dir`.
+    fn ledge(id: &str, x: f32, dir: MoveDir) -> (Level, TicRunner, Entity) {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        level.sectors[2].floor_h = 32.0;
+        let mut sim = TicRunner::new(&level);
+        let ss = level.locate_subsector(Vec2::new(x, 64.0));
+        let info = defs::by_id(id).unwrap();
+        let thing = sim.spawn_mobj(&level, info, x, 64.0, Angle::ANG180, ss);
+        sim.world_mut().insert_one(thing, dir).unwrap();
+        (level, sim, thing)
+    }
+
+    #[test]
+    fn players_walk_off_ledges_monsters_dont() {
+        let (mut level, mut sim, player) = ledge("PLAYER", 200.0, MoveDir::NoDir);
+        sim.set_player(player);
+        let walk = InputCmd {
+            forward: 1.0,
+            ..InputCmd::default()
+        };
+        for _ in 0..20 {
+            sim.run_tic(&mut level, walk);
+        }
+        assert!(sim.world().get::<&Position>(player).unwrap().0.x < 128.0);
+
+        let (mut level, mut sim, demon) = ledge("SERGEANT", 200.0, MoveDir::West);
+        let mut steps = 0;
+        while sim.monster_try_walk(&mut level, demon) {
+            steps += 1;
+        }
+        // it stops with its edge still over the ledge
+        let pos = *sim.world().get::<&Position>(demon).unwrap();
+        assert_eq!((steps, pos.0.x, pos.1), (2, 180.0, 32.0));
+    }
+
+    /// Halfway off the ledge with the centre over the strip, sliding west:
+    /// a corpse keeps all its speed, anything else feels the floor.
+    #[test]
+    fn corpses_slide_off_ledges() {
+        for (id, dead) in [("SERGEANT", true), ("PLAYER", false)] {
+            let (mut level, mut sim, thing) = ledge(id, 143.0, MoveDir::NoDir);
+            if dead {
+                sim.damage_mobj(thing, None, None, 10_000);
+            }
+            let world = sim.world_mut();
+            world.get::<&mut Position>(thing).unwrap().1 = 32.0;
+            world.get::<&mut Velocity>(thing).unwrap().0 = Vec3::new(-4.0, 0.0, 0.0);
+            sim.run_tic(&mut level, InputCmd::default());
+
+            let pos = *sim.world().get::<&Position>(thing).unwrap();
+            let vel = sim.world().get::<&Velocity>(thing).unwrap().0;
+            assert_eq!((pos.0.x, pos.1), (139.0, 32.0), "{id}");
+            let want = if dead { -4.0 } else { -4.0 * 0.90625 };
+            assert_eq!(vel.x, want, "{id}");
+        }
+    }
+
+    #[test]
+    fn floaters_rise_over_ledges() {
+        let (mut level, mut sim, caco) = ledge("HEAD", 60.0, MoveDir::East);
+        let mut floats = 0;
+        while sim.monster_try_walk(&mut level, caco) {
+            let flags = sim.world().get::<&ActorFlags>(caco).unwrap().0;
+            floats += flags.contains(MobjFlags::INFLOAT) as i32;
+        }
+        // up 8 in two goes, just enough to step the rest onto the ledge,
+        // then on to the far wall
+        let pos = *sim.world().get::<&Position>(caco).unwrap();
+        assert_eq!((floats, pos.1), (2, 32.0));
+        assert_eq!(pos.0.x, 236.0);
+    }
+
+    #[test]
+    fn charging_skulls_bite_once_and_stop() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        let mut sim = TicRunner::new(&level);
+        let spawn = |sim: &mut TicRunner, id, x: f32| {
+            let ss = level.locate_subsector(Vec2::new(x, 64.0));
+            sim.spawn_mobj(&level, defs::by_id(id).unwrap(), x, 64.0, Angle::ZERO, ss)
+        };
+        let skull = spawn(&mut sim, "SKULL", 40.0);
+        let demon = spawn(&mut sim, "SERGEANT", 200.0);
+        sim.world_mut().insert_one(skull, Target(demon)).unwrap();
+
+        let mut events = Vec::new();
+        enemy::a_skull_attack(sim.world_mut(), &mut Rng::default(), &mut events, skull);
+        let vel = sim.world().get::<&Velocity>(skull).unwrap().0;
+        assert_eq!(vel.truncate(), Vec2::new(enemy::SKULL_SPEED, 0.0));
+        // 160 to go, 8 tics: up to the demon's middle, 28 up
+        assert_eq!(vel.z, 28.0 / 8.0);
+        assert!(matches!(
+            events[..],
+            [SimEvent::Sound {
+                sound: Sound::sklatk,
+                ..
+            }]
+        ));
+
+        // climbing towards it as it goes
+        let z = |sim: &TicRunner| sim.world().get::<&Position>(skull).unwrap().1;
+        let start = z(&sim);
+        for _ in 0..3 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        assert_eq!(z(&sim), start + 3.0 * 28.0 / 8.0);
+
+        let health = |sim: &TicRunner| sim.world().get::<&Health>(demon).unwrap().0;
+        let mut hits = 0;
+        for _ in 0..32 {
+            let before = health(&sim);
+            sim.run_tic(&mut level, InputCmd::default());
+            hits += (health(&sim) < before) as i32;
+        }
+        assert_eq!(hits, 1);
+        let world = sim.world();
+        assert!(
+            !world
+                .get::<&ActorFlags>(skull)
+                .unwrap()
+                .0
+                .contains(MobjFlags::SKULLFLY)
+        );
+        assert_eq!(world.get::<&Velocity>(skull).unwrap().0, Vec3::ZERO);
+        // stopped short of the demon
+        let gap =
+            world.get::<&Position>(demon).unwrap().0.x - world.get::<&Position>(skull).unwrap().0.x;
+        assert!(gap >= 16.0 + 30.0, "{gap}");
+    }
+
+    #[test]
+    fn missiles_vanish_into_sky_hack_walls() {
+        // fired east at the closed strip between rooms A and B
+        let flown = |sky: bool| {
+            let mut level = testmap::three_rooms(testmap::Middle::Open {
+                floor: 0.0,
+                ceil: 0.0,
+            });
+            level.sectors[0].is_sky_ceiling = sky;
+            level.sectors[1].is_sky_ceiling = sky;
+            let mut sim = TicRunner::new(&level);
+            let ss = level.locate_subsector(Vec2::new(64.0, 64.0));
+            let info = defs::by_id("TROOPSHOT").unwrap();
+            let shot = sim.spawn_mobj(&level, info, 64.0, 64.0, Angle::ZERO, ss);
+            sim.world_mut().get::<&mut Position>(shot).unwrap().1 = 32.0;
+            sim.world_mut().get::<&mut Velocity>(shot).unwrap().0 = Vec3::new(10.0, 0.0, 0.0);
+            for _ in 0..8 {
+                sim.run_tic(&mut level, InputCmd::default());
+            }
+            sim.world().contains(shot)
+        };
+        assert!(flown(false), "exploding against a plain wall");
+        assert!(!flown(true), "still there against the sky");
+    }
+
+    #[test]
+    fn charging_skulls_bounce_off_floor_and_ceiling() {
+        let mut level = testmap::three_rooms(testmap::Middle::Wall);
+        let mut sim = TicRunner::new(&level);
+        let ss = level.locate_subsector(Vec2::new(64.0, 64.0));
+        let skull = sim.spawn_mobj(
+            &level,
+            defs::by_id("SKULL").unwrap(),
+            64.0,
+            64.0,
+            Angle::ZERO,
+            ss,
+        );
+        let height = defs::by_id("SKULL").unwrap().height as f32;
+        let ceil = level.sectors[0].ceil_h;
+        sim.world_mut()
+            .get::<&mut ActorFlags>(skull)
+            .unwrap()
+            .0
+            .insert(MobjFlags::SKULLFLY);
+        for (z, vz, land, bounce) in [
+            (4.0, -10.0, 0.0, 10.0),
+            (ceil - height - 4.0, 10.0, ceil - height, -10.0),
+        ] {
+            sim.world_mut().get::<&mut Position>(skull).unwrap().1 = z;
+            sim.world_mut().get::<&mut Velocity>(skull).unwrap().0 = Vec3::new(1.0, 0.0, vz);
+            sim.run_tic(&mut level, InputCmd::default());
+            let world = sim.world();
+            assert_eq!(world.get::<&Position>(skull).unwrap().1, land);
+            assert_eq!(world.get::<&Velocity>(skull).unwrap().0.z, bounce);
+        }
+    }
 }
@@ -3,6 +3,7 @@ use bincode::Decode;
 use byteorder::{LittleEndian, ReadBytesExt};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::io::Read;
 
 /*=======================================================================*/
 /*                         Raw binary structs                            */
@@ -21,8 +22,8 @@ pub struct RawThing {
 #[repr(C)]
 #[derive(Clone, Copy, Decode, Debug)]
 pub struct RawLinedef {
-    pub v1: i16,
-    pub v2: i16,
+    pub v1: u16,
+    pub v2: u16,
     pub flags: i16,
     pub special: i16,
     pub tag: i16,
@@ -41,7 +42,7 @@ pub struct RawSidedef {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Decode, Debug)]
+#[derive(Clone, Copy, Decode, Debug, Default)]
 pub struct RawVertex {
     pub x: i16,
     pub y: i16,
@@ -50,10 +51,10 @@ pub struct RawVertex {
 #[repr(C)]
 #[derive(Clone, Copy, Decode, Debug)]
 pub struct RawSeg {
-    pub v1: i16,
-    pub v2: i16,
+    pub v1: u16,
+    pub v2: u16,
     pub angle: i16,
-    pub linedef: i16,
+    pub linedef: u16,
     pub side: i16,
     pub offset: i16,
 }
@@ -61,8 +62,8 @@ pub struct RawSeg {
 #[repr(C)]
 #[derive(Clone, Copy, Decode, Debug)]
 pub struct RawSubsector {
-    pub seg_count: i16,
-    pub first_seg: i16,
+    pub seg_count: u16,
+    pub first_seg: u16,
 }
 
 #[repr(C)]
@@ -88,6 +89,21 @@ pub struct RawSector {
     pub tag: i16,
 }
 
+// `lump_to_vec` steps through a lump by `size_of`; every field above is
+// 2-byte aligned, so `repr(C)` adds no padding and these are the on-disk
+// record sizes.
+const _: () = {
+    use std::mem::size_of;
+    assert!(size_of::<RawThing>() == 10);
+    assert!(size_of::<RawLinedef>() == 14);
+    assert!(size_of::<RawSidedef>() == 30);
+    assert!(size_of::<RawVertex>() == 4);
+    assert!(size_of::<RawSeg>() == 12);
+    assert!(size_of::<RawSubsector>() == 4);
+    assert!(size_of::<RawNode>() == 28);
+    assert!(size_of::<RawSector>() == 26);
+};
+
 #[repr(C)]
 #[derive(Debug, Clone)]
 pub struct RawBlockmap {
@@ -99,6 +115,83 @@ pub struct RawBlockmap {
     pub data: Vec<i16>,    // sequences of linedef indices, -1 terminated
 }
 
+/*=======================================================================*/
+/*                  BSP in any node format, widened to u32               */
+/*=======================================================================*/
+
+/// Child-index flag marking a subsector in [`BspNode::child`].
+pub const NF_SUBSECTOR: u32 = 0x8000_0000;
+
+/// Which node builder layout the map's SEGS / SSECTORS / NODES use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeFormat {
+    /// 16-bit indices (up to 65535 with the sign bit reclaimed).
+    Vanilla,
+    /// DeePBSP `xNd4`: the same records with 32-bit indices.
+    DeePBsp,
+    /// ZDoom uncompressed `XNOD`: everything in NODES, plus split
+    /// vertices with fractional coordinates.
+    Extended,
+    /// NODES, SSECTORS or SEGS absent or empty (saved by an editor that
+    /// leaves node building to someone else); the loader builds them.
+    Missing,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BspSeg {
+    pub v1: u32,
+    pub v2: u32,
+    pub linedef: u16,
+    pub side: u16,
+    /// Distance along the linedef to the seg's start.
+    pub offset: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BspSubsector {
+    pub seg_count: u32,
+    pub first_seg: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BspNode {
+    pub x: i16,
+    pub y: i16,
+    pub dx: i16,
+    pub dy: i16,
+    pub bbox: [[i16; 4]; 2],
+    /// Node index, or subsector index | [`NF_SUBSECTOR`].
+    pub child: [u32; 2],
+}
+
+/// The three BSP lumps decoded together.
+struct Bsp {
+    format: NodeFormat,
+    split_vertices: Vec<[f32; 2]>,
+    segs: Vec<BspSeg>,
+    subsectors: Vec<BspSubsector>,
+    nodes: Vec<BspNode>,
+}
+
+impl Bsp {
+    /// No usable node lumps.
+    fn missing() -> Self {
+        Self {
+            format: NodeFormat::Missing,
+            split_vertices: Vec::new(),
+            segs: Vec::new(),
+            subsectors: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+}
+
+/// Lumps a classic map may have after its marker.
+const MAP_LUMPS: [&str; 10] = [
+    "THINGS", "LINEDEFS", "SIDEDEFS", "VERTEXES", "SEGS", "SSECTORS", "NODES", "SECTORS", "REJECT",
+    "BLOCKMAP",
+];
+
 /*=======================================================================*/
 /*                     Aggregate returned by `parse_level`               */
 /*=======================================================================*/
@@ -109,12 +202,18 @@ pub struct RawLevel {
     pub linedefs: Vec<RawLinedef>,
     pub sidedefs: Vec<RawSidedef>,
     pub vertices: Vec<RawVertex>,
-    pub segs: Vec<RawSeg>,
-    pub subsectors: Vec<RawSubsector>,
-    pub nodes: Vec<RawNode>,
+    /// Node builder split points (`XNOD` only), numbered after
+    /// `vertices`.
+    pub split_vertices: Vec<[f32; 2]>,
+    pub node_format: NodeFormat,
+    pub segs: Vec<BspSeg>,
+    pub subsectors: Vec<BspSubsector>,
+    pub nodes: Vec<BspNode>,
     pub sectors: Vec<RawSector>,
     pub reject: Vec<u8>,
-    pub blockmap: RawBlockmap,
+    /// `None` when the lump is absent, empty or too short to decode;
+    /// the loader then rebuilds the grid from the linedefs.
+    pub blockmap: Option<RawBlockmap>,
 }
 
 /*=======================================================================*/
@@ -129,6 +228,20 @@ pub enum LevelError {
     #[error("expected lump `{0}` not found after level marker")]
     Missing(&'static str),
 
+    /// UDMF (`TEXTMAP`) or Hexen (`BEHAVIOR`) maps.
+    #[error("{0} maps are not supported")]
+    UnsupportedMapFormat(&'static str),
+
+    /// Compressed or GL nodes (`ZNOD`, `XGLN`, …).
+    #[error("{0} nodes are not supported; rebuild the map with uncompressed nodes")]
+    UnsupportedNodeFormat(&'static str),
+
+    #[error("{format} nodes are malformed: {what}")]
+    BadNodes {
+        format: &'static str,
+        what: &'static str,
+    },
+
     #[error(transparent)]
     Wad(#[from] WadError),
 
@@ -141,40 +254,69 @@ pub enum LevelError {
 /*=======================================================================*/
 impl Wad {
     /// Return directory indices of every map marker (`E#M#`, `MAP##`).
+    /// A map a PWAD replaces keeps its place in the list but points at
+    /// the PWAD's version.
     pub fn level_indices(&self) -> Vec<usize> {
         static RE: Lazy<Regex> =
             Lazy::new(|| Regex::new(r"^(E[1-4]M[1-9]|MAP[0-3][0-9])$").unwrap());
 
+        let mut out: Vec<usize> = Vec::new();
+        for (i, l) in self.lumps().iter().enumerate() {
+            let name = Self::lump_name_str(&l.name);
+            if l.size != 0 || !RE.is_match(name) {
+                continue;
+            }
+            let latest = self.find_lump(name).unwrap_or(i);
+            if !out.contains(&latest) {
+                out.push(latest);
+            }
+        }
+        out
+    }
+
+    /// Index of the lump `name` in the run of map lumps right after the
+    /// marker at `marker`, wherever in the run it is.
+    fn map_lump(&self, marker: usize, name: &'static str) -> Option<usize> {
         self.lumps()
             .iter()
             .enumerate()
-            .filter(|(_, l)| l.size == 0 && RE.is_match(Self::lump_name_str(&l.name)))
+            .skip(marker + 1)
+            .take_while(|(_, l)| MAP_LUMPS.contains(&Self::lump_name_str(&l.name)))
+            .find(|(_, l)| Self::lump_name_str(&l.name) == name)
             .map(|(i, _)| i)
-            .collect()
     }
 
-    /// Return the index of the lump `name` **immediately after** `start`.
-    fn idx_of(&self, start: usize, name: &'static str) -> Result<usize, LevelError> {
-        let l = self.lumps().get(start).ok_or(LevelError::Missing(name))?;
-        match Self::lump_name_str(&l.name) == name {
-            true => Ok(start),
-            false => Err(LevelError::Missing(name)),
-        }
+    /// [`Wad::map_lump`] for a lump the map can't do without.
+    fn required_lump(&self, marker: usize, name: &'static str) -> Result<usize, LevelError> {
+        self.map_lump(marker, name).ok_or(LevelError::Missing(name))
     }
 
-    fn parse_blockmap(&self, blockmap_idx: usize) -> Result<RawBlockmap, LevelError> {
+    fn parse_blockmap(&self, blockmap_idx: usize) -> Result<Option<RawBlockmap>, LevelError> {
         let bm_bytes = self.lump_bytes(blockmap_idx)?;
         let mut rdr = std::io::Cursor::new(bm_bytes);
 
-        let origin_x = rdr.read_i16::<LittleEndian>()?;
-        let origin_y = rdr.read_i16::<LittleEndian>()?;
-        let width = rdr.read_i16::<LittleEndian>()?;
-        let height = rdr.read_i16::<LittleEndian>()?;
+        let header = (|| -> std::io::Result<_> {
+            Ok((
+                rdr.read_i16::<LittleEndian>()?,
+                rdr.read_i16::<LittleEndian>()?,
+                rdr.read_i16::<LittleEndian>()?,
+                rdr.read_i16::<LittleEndian>()?,
+            ))
+        })();
+        let Ok((origin_x, origin_y, width, height)) = header else {
+            return Ok(None); // 0-byte or truncated lump
+        };
+        if width <= 0 || height <= 0 {
+            return Ok(None);
+        }
 
         let cell_cnt = (width as usize) * (height as usize);
         let mut offsets = Vec::with_capacity(cell_cnt);
         for _ in 0..cell_cnt {
-            offsets.push(rdr.read_i16::<LittleEndian>()?);
+            match rdr.read_i16::<LittleEndian>() {
+                Ok(off) => offsets.push(off),
+                Err(_) => return Ok(None), // offset table cut short
+            }
         }
 
         let mut data = Vec::new();
@@ -182,13 +324,150 @@ impl Wad {
             data.push(v);
         }
 
-        Ok(RawBlockmap {
+        Ok(Some(RawBlockmap {
             origin_x,
             origin_y,
             width,
             height,
             offsets,
             data,
+        }))
+    }
+
+    /// Decode SEGS / SSECTORS / NODES in whichever format the node
+    /// builder wrote, or say why we can't.  Absent lumps read as empty.
+    fn parse_bsp(
+        &self,
+        segs_idx: Option<usize>,
+        ssectors_idx: Option<usize>,
+        nodes_idx: Option<usize>,
+        linedefs: &[RawLinedef],
+        vertices: &[RawVertex],
+    ) -> Result<Bsp, LevelError> {
+        let bytes = |idx: Option<usize>| idx.map_or(Ok(&[][..]), |i| self.lump_bytes(i));
+
+        // GL nodes announce themselves in SSECTORS
+        let ssectors = bytes(ssectors_idx)?;
+        for sig in ["XGLN", "ZGLN", "XGL2", "ZGL2", "XGL3", "ZGL3"] {
+            if ssectors.starts_with(sig.as_bytes()) {
+                return Err(LevelError::UnsupportedNodeFormat(sig));
+            }
+        }
+
+        let nodes = bytes(nodes_idx)?;
+        if nodes.starts_with(b"ZNOD") {
+            return Err(LevelError::UnsupportedNodeFormat("ZNOD"));
+        }
+        if let Some(data) = nodes.strip_prefix(b"XNOD") {
+            return parse_xnod(data, linedefs, vertices);
+        }
+        if let Some(data) = nodes.strip_prefix(b"xNd4\0\0\0\0") {
+            return self.parse_deepbsp(bytes(segs_idx)?, ssectors, data);
+        }
+
+        let (Some(segs_idx), Some(ssectors_idx), Some(nodes_idx)) =
+            (segs_idx, ssectors_idx, nodes_idx)
+        else {
+            return Ok(Bsp::missing());
+        };
+        if [segs_idx, ssectors_idx, nodes_idx]
+            .iter()
+            .any(|&i| self.lumps()[i].size == 0)
+        {
+            return Ok(Bsp::missing());
+        }
+        let segs = self.lump_to_vec::<RawSeg>(segs_idx)?;
+        let subsectors = self.lump_to_vec::<RawSubsector>(ssectors_idx)?;
+        let nodes = self.lump_to_vec::<RawNode>(nodes_idx)?;
+        // the sign bit of a 16-bit child is the subsector flag
+        let child = |c: u16| match c & 0x8000 {
+            0 => c as u32,
+            _ => (c & 0x7FFF) as u32 | NF_SUBSECTOR,
+        };
+        Ok(Bsp {
+            format: NodeFormat::Vanilla,
+            split_vertices: Vec::new(),
+            segs: segs
+                .into_iter()
+                .map(|s| BspSeg {
+                    v1: s.v1 as u32,
+                    v2: s.v2 as u32,
+                    linedef: s.linedef,
+                    side: s.side as u16,
+                    offset: s.offset as f32,
+                })
+                .collect(),
+            subsectors: subsectors
+                .into_iter()
+                .map(|s| BspSubsector {
+                    seg_count: s.seg_count as u32,
+                    first_seg: s.first_seg as u32,
+                })
+                .collect(),
+            nodes: nodes
+                .into_iter()
+                .map(|n| BspNode {
+                    x: n.x,
+                    y: n.y,
+                    dx: n.dx,
+                    dy: n.dy,
+                    bbox: n.bbox,
+                    child: [child(n.child[0]), child(n.child[1])],
+                })
+                .collect(),
+        })
+    }
+
+    /// DeePBSP's `xNd4` layout: the vanilla records with 32-bit vertex,
+    /// seg and child indices; `nodes` is NODES past its 8-byte header.
+    fn parse_deepbsp(&self, segs: &[u8], ssectors: &[u8], nodes: &[u8]) -> Result<Bsp, LevelError> {
+        const FORMAT: &str = "DeePBSP";
+        let truncated = |_| LevelError::BadNodes {
+            format: FORMAT,
+            what: "lump truncated",
+        };
+
+        let mut r = std::io::Cursor::new(segs);
+        let mut segs = Vec::new();
+        while (r.position() as usize) < r.get_ref().len() {
+            let v1 = r.read_u32::<LittleEndian>().map_err(truncated)?;
+            let v2 = r.read_u32::<LittleEndian>().map_err(truncated)?;
+            let _angle = r.read_i16::<LittleEndian>().map_err(truncated)?;
+            let linedef = r.read_u16::<LittleEndian>().map_err(truncated)?;
+            let side = r.read_i16::<LittleEndian>().map_err(truncated)?;
+            let offset = r.read_i16::<LittleEndian>().map_err(truncated)?;
+            segs.push(BspSeg {
+                v1,
+                v2,
+                linedef,
+                side: side as u16,
+                offset: offset as f32,
+            });
+        }
+
+        let mut r = std::io::Cursor::new(ssectors);
+        let mut subsectors = Vec::new();
+        while (r.position() as usize) < ssectors.len() {
+            let seg_count = r.read_u16::<LittleEndian>().map_err(truncated)? as u32;
+            let first_seg = r.read_u32::<LittleEndian>().map_err(truncated)?;
+            subsectors.push(BspSubsector {
+                seg_count,
+                first_seg,
+            });
+        }
+
+        let mut r = std::io::Cursor::new(nodes);
+        let mut out = Vec::new();
+        while (r.position() as usize) < nodes.len() {
+            out.push(read_node(&mut r, |r| r.read_u32::<LittleEndian>()).map_err(truncated)?);
+        }
+
+        Ok(Bsp {
+            format: NodeFormat::DeePBsp,
+            split_vertices: Vec::new(),
+            segs,
+            subsectors,
+            nodes: out,
         })
     }
 
@@ -199,29 +478,41 @@ impl Wad {
             return Err(LevelError::MarkerOob(marker_idx));
         }
 
-        // --- fixed lump order after marker -------------------------------
-        let things_idx = self.idx_of(marker_idx + 1, "THINGS")?;
-        let linedefs_idx = self.idx_of(marker_idx + 2, "LINEDEFS")?;
-        let sidedefs_idx = self.idx_of(marker_idx + 3, "SIDEDEFS")?;
-        let vertices_idx = self.idx_of(marker_idx + 4, "VERTEXES")?;
-        let segs_idx = self.idx_of(marker_idx + 5, "SEGS")?;
-        let ssectors_idx = self.idx_of(marker_idx + 6, "SSECTORS")?;
-        let nodes_idx = self.idx_of(marker_idx + 7, "NODES")?;
-        let sectors_idx = self.idx_of(marker_idx + 8, "SECTORS")?;
-        let reject_idx = self.idx_of(marker_idx + 9, "REJECT")?;
-        let blockmap_idx = self.idx_of(marker_idx + 10, "BLOCKMAP")?;
+        // --- formats we can't read --------------------------------------
+        let name_at = |i: usize| self.lumps().get(i).map(|l| Self::lump_name_str(&l.name));
+        if name_at(marker_idx + 1) == Some("TEXTMAP") {
+            return Err(LevelError::UnsupportedMapFormat("UDMF"));
+        }
+        if name_at(marker_idx + 11) == Some("BEHAVIOR") {
+            return Err(LevelError::UnsupportedMapFormat("Hexen"));
+        }
+
+        // --- the lumps following the marker -----------------------------
+        let things_idx = self.required_lump(marker_idx, "THINGS")?;
+        let linedefs_idx = self.required_lump(marker_idx, "LINEDEFS")?;
+        let sidedefs_idx = self.required_lump(marker_idx, "SIDEDEFS")?;
+        let vertices_idx = self.required_lump(marker_idx, "VERTEXES")?;
+        // the BSP lumps may be missing – the loader builds its own
+        let segs_idx = self.map_lump(marker_idx, "SEGS");
+        let ssectors_idx = self.map_lump(marker_idx, "SSECTORS");
+        let nodes_idx = self.map_lump(marker_idx, "NODES");
+        let sectors_idx = self.required_lump(marker_idx, "SECTORS")?;
+        let reject_idx = self.required_lump(marker_idx, "REJECT")?;
+        // BLOCKMAP may be omitted – it can be rebuilt from the linedefs
+        let blockmap_idx = self.map_lump(marker_idx, "BLOCKMAP");
 
         // --- decode each lump -------------------------------------------
         let things = self.lump_to_vec::<RawThing>(things_idx)?;
         let linedefs = self.lump_to_vec::<RawLinedef>(linedefs_idx)?;
         let sidedefs = self.lump_to_vec::<RawSidedef>(sidedefs_idx)?;
         let vertices = self.lump_to_vec::<RawVertex>(vertices_idx)?;
-        let segs = self.lump_to_vec::<RawSeg>(segs_idx)?;
-        let subsectors = self.lump_to_vec::<RawSubsector>(ssectors_idx)?;
-        let nodes = self.lump_to_vec::<RawNode>(nodes_idx)?;
+        let bsp = self.parse_bsp(segs_idx, ssectors_idx, nodes_idx, &linedefs, &vertices)?;
         let sectors = self.lump_to_vec::<RawSector>(sectors_idx)?;
         let reject = self.lump_bytes(reject_idx)?.to_vec();
-        let blockmap = self.parse_blockmap(blockmap_idx)?;
+        let blockmap = match blockmap_idx {
+            Some(idx) => self.parse_blockmap(idx)?,
+            None => None,
+        };
 
         Ok(RawLevel {
             name: Self::lump_name_str(&self.lumps()[marker_idx].name).into(),
@@ -229,9 +520,11 @@ impl Wad {
             linedefs,
             sidedefs,
             vertices,
-            segs,
-            subsectors,
-            nodes,
+            split_vertices: bsp.split_vertices,
+            node_format: bsp.format,
+            segs: bsp.segs,
+            subsectors: bsp.subsectors,
+            nodes: bsp.nodes,
             sectors,
             reject,
             blockmap,
@@ -239,6 +532,123 @@ impl Wad {
     }
 }
 
+/// One node record whose children are read by `child` (16 or 32 bits).
+fn read_node<R: Read>(
+    r: &mut R,
+    mut child: impl FnMut(&mut R) -> std::io::Result<u32>,
+) -> std::io::Result<BspNode> {
+    let mut xy = [0i16; 4];
+    r.read_i16_into::<LittleEndian>(&mut xy)?;
+    let mut bbox = [[0i16; 4]; 2];
+    for b in &mut bbox {
+        r.read_i16_into::<LittleEndian>(b)?;
+    }
+    Ok(BspNode {
+        x: xy[0],
+        y: xy[1],
+        dx: xy[2],
+        dy: xy[3],
+        bbox,
+        child: [child(r)?, child(r)?],
+    })
+}
+
+/// ZDoom's uncompressed extended nodes; `data` is NODES past `XNOD`.
+///
+/// ```text
+/// orig_verts:u32  new_verts:u32  new_verts × { x y:fixed }
+/// subsectors:u32 × { seg_count:u32 }
+/// segs:u32 × { v1 v2:u32  linedef:u16  side:u8 }
+/// nodes:u32 × { x y dx dy:i16  bbox:[i16;8]  child:[u32;2] }
+/// ```
+fn parse_xnod(
+    data: &[u8],
+    linedefs: &[RawLinedef],
+    vertices: &[RawVertex],
+) -> Result<Bsp, LevelError> {
+    let bad = |what| LevelError::BadNodes {
+        format: "XNOD",
+        what,
+    };
+    let truncated = |_| bad("lump truncated");
+    let mut r = std::io::Cursor::new(data);
+
+    let orig = r.read_u32::<LittleEndian>().map_err(truncated)?;
+    let new = r.read_u32::<LittleEndian>().map_err(truncated)?;
+    let mut split_vertices = Vec::new();
+    for _ in 0..new {
+        let x = r.read_i32::<LittleEndian>().map_err(truncated)?;
+        let y = r.read_i32::<LittleEndian>().map_err(truncated)?;
+        split_vertices.push([x as f32 / 65536.0, y as f32 / 65536.0]);
+    }
+    // numbers past `orig` are split vertices, however long VERTEXES is
+    let vertex = |v: u32| match v.checked_sub(orig) {
+        None => v,
+        Some(extra) => vertices.len() as u32 + extra,
+    };
+
+    let count = r.read_u32::<LittleEndian>().map_err(truncated)?;
+    let mut subsectors = Vec::new();
+    let mut first_seg = 0u32;
+    for _ in 0..count {
+        let seg_count = r.read_u32::<LittleEndian>().map_err(truncated)?;
+        subsectors.push(BspSubsector {
+            seg_count,
+            first_seg,
+        });
+        first_seg = first_seg.saturating_add(seg_count);
+    }
+
+    let count = r.read_u32::<LittleEndian>().map_err(truncated)?;
+    if count != first_seg {
+        return Err(bad("subsector seg counts don't add up"));
+    }
+    let pos = |v: u32| -> Option<[f32; 2]> {
+        let v = v as usize;
+        match vertices.get(v) {
+            Some(rv) => Some([rv.x as f32, rv.y as f32]),
+            None => split_vertices.get(v - vertices.len()).copied(),
+        }
+    };
+    let mut segs = Vec::new();
+    for _ in 0..count {
+        let v1 = vertex(r.read_u32::<LittleEndian>().map_err(truncated)?);
+        let v2 = vertex(r.read_u32::<LittleEndian>().map_err(truncated)?);
+        let linedef = r.read_u16::<LittleEndian>().map_err(truncated)?;
+        let side = r.read_u8().map_err(truncated)? as u16;
+
+        // XNOD drops the offset: measure from the linedef's start on our side
+        let ld = linedefs
+            .get(linedef as usize)
+            .ok_or(bad("seg on a missing linedef"))?;
+        let from = if side == 0 { ld.v1 } else { ld.v2 };
+        let (Some(a), Some(b), true) = (pos(from as u32), pos(v1), pos(v2).is_some()) else {
+            return Err(bad("seg on a missing vertex"));
+        };
+        segs.push(BspSeg {
+            v1,
+            v2,
+            linedef,
+            side,
+            offset: (b[0] - a[0]).hypot(b[1] - a[1]),
+        });
+    }
+
+    let count = r.read_u32::<LittleEndian>().map_err(truncated)?;
+    let mut nodes = Vec::new();
+    for _ in 0..count {
+        nodes.push(read_node(&mut r, |r| r.read_u32::<LittleEndian>()).map_err(truncated)?);
+    }
+
+    Ok(Bsp {
+        format: NodeFormat::Extended,
+        split_vertices,
+        segs,
+        subsectors,
+        nodes,
+    })
+}
+
 /*=======================================================================*/
 /*                                Tests                                  */
 /*=======================================================================*/
@@ -276,4 +686,134 @@ mod tests {
         let err = wad.parse_level(idx).unwrap_err();
         matches!(err, LevelError::Missing("THINGS"));
     }
+
+    /*──────────────────────── synthetic maps ──────────────────────────*/
+
+    fn le16(v: &[i16]) -> Vec<u8> {
+        v.iter().flat_map(|x| x.to_le_bytes()).collect()
+    }
+
+    fn le32(v: &[u32]) -> Vec<u8> {
+        v.iter().flat_map(|x| x.to_le_bytes()).collect()
+    }
+
+    /// A 64×64 square room (plus one stray vertex) with the given BSP lumps.
+    fn square(segs: Vec<u8>, ssectors: Vec<u8>, nodes: Vec<u8>) -> Wad {
+        let vertexes = le16(&[0, 0, 64, 0, 64, 64, 0, 64, 99, 99]);
+        let mut linedefs = Vec::new();
+        for (v1, v2) in [(1, 0), (2, 1), (3, 2), (0, 3)] {
+            linedefs.extend(le16(&[v1, v2, 1, 0, 0, 0, -1]));
+        }
+        let mut sidedef = le16(&[0, 0]);
+        sidedef.extend(b"-\0\0\0\0\0\0\0-\0\0\0\0\0\0\0STARTAN3");
+        sidedef.extend(le16(&[0]));
+        let mut sector = le16(&[0, 128]);
+        sector.extend(b"FLOOR4_8CEIL3_5\0");
+        sector.extend(le16(&[160, 0, 0]));
+        Wad::from_lumps(&[
+            ("E1M1", Vec::new()),
+            ("THINGS", le16(&[32, 32, 0, 1, 7])),
+            ("LINEDEFS", linedefs),
+            ("SIDEDEFS", sidedef),
+            ("VERTEXES", vertexes),
+            ("SEGS", segs),
+            ("SSECTORS", ssectors),
+            ("NODES", nodes),
+            ("SECTORS", sector),
+            ("REJECT", Vec::new()),
+        ])
+    }
+
+    #[test]
+    fn xnod_remaps_split_vertices_and_measures_offsets() {
+        let mut nodes = b"XNOD".to_vec();
+        nodes.extend(le32(&[4, 1, 16 << 16, 0])); // one split vertex at (16,0)
+        nodes.extend(le32(&[2, 2, 2])); // two subsectors of two segs
+        nodes.extend(le32(&[4]));
+        for (v1, v2, line) in [(4u32, 0u32, 0u16), (1, 4, 0), (2, 1, 1), (3, 2, 2)] {
+            nodes.extend(le32(&[v1, v2]));
+            nodes.extend(line.to_le_bytes());
+            nodes.push(0);
+        }
+        nodes.extend(le32(&[1]));
+        nodes.extend(le16(&[16, 0, 0, 64, 64, 0, 0, 64, 64, 0, 0, 64]));
+        nodes.extend(le32(&[NF_SUBSECTOR, NF_SUBSECTOR | 1]));
+
+        let lvl = square(Vec::new(), Vec::new(), nodes)
+            .parse_level(0)
+            .unwrap();
+        assert_eq!(lvl.node_format, NodeFormat::Extended);
+        assert_eq!(lvl.split_vertices, vec![[16.0, 0.0]]);
+        // past the five VERTEXES entries, not past `orig_verts`
+        assert_eq!((lvl.segs[0].v1, lvl.segs[1].v2), (5, 5));
+        assert_eq!((lvl.segs[0].offset, lvl.segs[1].offset), (48.0, 0.0));
+        assert_eq!(lvl.subsectors[1].first_seg, 2);
+        assert_eq!(lvl.nodes[0].child, [NF_SUBSECTOR, NF_SUBSECTOR | 1]);
+    }
+
+    #[test]
+    fn truncated_xnod_is_bad_nodes() {
+        let mut nodes = b"XNOD".to_vec();
+        nodes.extend(le32(&[4, 1]));
+        let err = square(Vec::new(), Vec::new(), nodes)
+            .parse_level(0)
+            .unwrap_err();
+        assert!(matches!(err, LevelError::BadNodes { format: "XNOD", .. }));
+    }
+
+    #[test]
+    fn deepbsp_reads_wide_indices() {
+        let mut segs = Vec::new();
+        for (v1, v2, line) in [(1u32, 0u32, 0i16), (2, 1, 1), (3, 2, 2), (0, 3, 3)] {
+            segs.extend(le32(&[v1, v2]));
+            segs.extend(le16(&[0, line, 0, 0]));
+        }
+        let mut ssectors = le16(&[4]);
+        ssectors.extend(le32(&[0]));
+        let mut nodes = b"xNd4\0\0\0\0".to_vec();
+        nodes.extend(le16(&[0, 0, 64, 0, 64, 0, 0, 64, 64, 0, 0, 64]));
+        nodes.extend(le32(&[NF_SUBSECTOR, NF_SUBSECTOR]));
+
+        let lvl = square(segs, ssectors, nodes).parse_level(0).unwrap();
+        assert_eq!(lvl.node_format, NodeFormat::DeePBsp);
+        assert_eq!(lvl.segs.len(), 4);
+        assert_eq!(lvl.segs[3].linedef, 3);
+        assert_eq!(lvl.subsectors[0].seg_count, 4);
+        assert_eq!(lvl.nodes[0].child[1], NF_SUBSECTOR);
+    }
+
+    #[test]
+    fn vanilla_children_move_the_subsector_flag() {
+        let segs = le16(&[1, 0, 0, 0, 0, 0]);
+        let ssectors = le16(&[1, 0]);
+        let mut nodes = le16(&[0, 0, 64, 0, 64, 0, 0, 64, 64, 0, 0, 64]);
+        nodes.extend(0x8000u16.to_le_bytes());
+        nodes.extend(0u16.to_le_bytes());
+
+        let lvl = square(segs, ssectors, nodes).parse_level(0).unwrap();
+        assert_eq!(lvl.node_format, NodeFormat::Vanilla);
+        assert_eq!(lvl.nodes[0].child, [NF_SUBSECTOR, 0]);
+    }
+
+    #[test]
+    fn unsupported_formats_say_so() {
+        let err = square(Vec::new(), Vec::new(), b"ZNOD\0\0".to_vec())
+            .parse_level(0)
+            .unwrap_err();
+        assert!(matches!(err, LevelError::UnsupportedNodeFormat("ZNOD")));
+
+        let err = square(Vec::new(), b"XGLN".to_vec(), b"XNOD".to_vec())
+            .parse_level(0)
+            .unwrap_err();
+        assert!(matches!(err, LevelError::UnsupportedNodeFormat("XGLN")));
+
+        let udmf = Wad::from_lumps(&[
+            ("MAP01", Vec::new()),
+            ("TEXTMAP", b"namespace = \"zdoom\";".to_vec()),
+            ("ENDMAP", Vec::new()),
+        ]);
+        let err = udmf.parse_level(0).unwrap_err();
+        assert!(matches!(err, LevelError::UnsupportedMapFormat("UDMF")));
+        assert!(err.to_string().contains("UDMF"));
+    }
 }
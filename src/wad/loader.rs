@@ -8,12 +8,14 @@
 // ──────────────────────────────────────────────────────────────────────────
 
 use glam::{Vec2, vec2};
+use std::collections::HashMap;
 use thiserror::Error;
 
-use super::level as raw_level;
+use super::level::{self as raw_level, NodeFormat};
 use super::raw::{Wad, WadError};
 
-use crate::world;
+use crate::defs;
+use crate::world::{self, Name8};
 
 /*──────────────────────────── Error type ───────────────────────────*/
 
@@ -34,8 +36,23 @@ pub enum LoadError {
     #[error("COLORMAP lump missing - cannot build palette")]
     NoColormap,
 
-    #[error("S_START/END lump missing - cannot build sprites")]
+    #[error("S_START/S_END (or SS_START/SS_END) lump missing - cannot build sprites")]
     NoSprites,
+
+    /// Defects `Level::repair` can't fix, or any at all when loading
+    /// strictly.
+    #[error("{map} is malformed: {} ({} defects in all)", .defects[0], .defects.len())]
+    InvalidMap {
+        map: String,
+        defects: Vec<world::MapDefect>,
+    },
+}
+
+/// How forgiving `load_level_with` is.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoadOptions {
+    /// Refuse maps with any defect instead of repairing them.
+    pub strict: bool,
 }
 
 /*====================================================================*/
@@ -44,19 +61,61 @@ pub enum LoadError {
 
 /// Load the map at `marker` into a `world::Level` and populate `bank` with
 /// every texture that map references.  Unknown names are replaced by the
-/// bank’s checkerboard id (0).
+/// bank’s checkerboard id (0).  Broken references are repaired with a
+/// warning on stderr (see `Level::repair`).
 pub fn load_level(
     wad: &Wad,
     marker: usize,
     bank: &mut world::TextureBank,
 ) -> Result<world::Level, LoadError> {
+    load_level_with(wad, marker, bank, LoadOptions::default())
+}
+
+/// [`load_level`] with `opts`.
+pub fn load_level_with(
+    wad: &Wad,
+    marker: usize,
+    bank: &mut world::TextureBank,
+    opts: LoadOptions,
+) -> Result<world::Level, LoadError> {
+    let (level, report) = load_level_report(wad, marker, bank, opts)?;
+    for defect in &report.defects {
+        eprintln!("warning: {}: {defect}", level.name);
+    }
+    Ok(level)
+}
+
+/// What [`load_level_report`] noticed about a map that loaded anyway.
+#[derive(Clone, Debug)]
+pub struct LoadReport {
+    /// Defects `Level::repair` found and fixed (none when loading
+    /// strictly, which refuses them instead).
+    pub defects: Vec<world::MapDefect>,
+    /// Textures and flats the map names that the WAD doesn't have; they
+    /// show the checkerboard.  Sorted, each once.
+    pub missing_textures: Vec<Name8>,
+    /// Things whose type is neither a player or deathmatch start nor
+    /// known to `defs`, by index; the sim leaves them out.
+    pub unknown_things: Vec<usize>,
+    /// How the map's nodes were stored; `Missing` ones were built.
+    pub node_format: NodeFormat,
+}
+
+/// [`load_level_with`], telling what it found and repaired instead of
+/// printing warnings.
+pub fn load_level_report(
+    wad: &Wad,
+    marker: usize,
+    bank: &mut world::TextureBank,
+    opts: LoadOptions,
+) -> Result<(world::Level, LoadReport), LoadError> {
     /*----- 1. Raw lumps --------------------------------------------------*/
     let raw = wad.parse_level(marker)?;
 
     /*----- 2. world::Palette needed for patches + flats -------------------------*/
-    let palette = load_palette(wad).ok_or(LoadError::NoPalette)?;
+    let palettes = load_palettes(wad).ok_or(LoadError::NoPalette)?;
 
-    bank.set_palette(palette);
+    bank.set_palettes(palettes);
 
     let colormap = load_colormap(wad).ok_or(LoadError::NoPalette)?;
 
@@ -66,22 +125,29 @@ pub fn load_level(
 
     load_all_sprites(wad, bank)?;
 
-    /*----- 3. Patch cache (index → world::Texture) ------------------------------*/
-    let patch_vec = decode_all_patches(wad)?;
+    /*----- 3. Deferred texture source (composes on first use) -----------*/
+    let mut source = WadTextures::new(wad)?;
 
     /*----- 4. Helper: resolve name → TextureId ---------------------------*/
+    let mut missing_textures = Vec::new();
     let mut tex_id = |name_bytes: &[u8; 8]| -> Result<TextureId, LoadError> {
-        let name = Wad::lump_name_str(name_bytes).to_ascii_uppercase();
-        if let Some(id) = bank.id(&name) {
-            return Ok(id);
+        let name = Name8::from_bytes(name_bytes);
+        // "-" is no texture at all; a name that isn't found still shows
+        // up as the checkerboard
+        if name.is_empty() || name == "-" {
+            return Ok(TRANSPARENT_TEXTURE);
         }
-        if let Some(tex) = build_wall_texture(wad, &patch_vec, &name) {
-            return Ok(bank.insert(name, tex)?);
+        // switches flip to their partner at runtime, so keep both halves
+        let mut partner = *name.as_bytes();
+        if let [b'S', b'W', n @ (b'1' | b'2'), ..] = &mut partner {
+            *n = if *n == b'1' { b'2' } else { b'1' };
+            bank.resolve(&partner, &mut source);
         }
-        if let Some(tex) = decode_flat(wad, &name) {
-            return Ok(bank.insert(name, tex)?);
+        let id = bank.resolve(name, &mut source);
+        if id == NO_TEXTURE {
+            missing_textures.push(name);
         }
-        Ok(world::NO_TEXTURE)
+        Ok(id)
     };
 
     /*----- 5. Convert raw → geo lists ------------------------------------*/
@@ -94,8 +160,9 @@ pub fn load_level(
         .into_iter()
         .enumerate()
         .map(|(idx, raw_ld)| {
-            let v1 = &raw.vertices[raw_ld.v1 as usize];
-            let v2 = &raw.vertices[raw_ld.v2 as usize];
+            // bad vertex indices are reported and fixed by `repair`
+            let vertex = |v: u16| raw.vertices.get(v as usize).copied().unwrap_or_default();
+            let (v1, v2) = (vertex(raw_ld.v1), vertex(raw_ld.v2));
             let bbox = world::Aabb {
                 min: Vec2::new(v1.x.min(v2.x) as f32, v1.y.min(v2.y) as f32),
                 max: Vec2::new(v1.x.max(v2.x) as f32, v1.y.max(v2.y) as f32),
@@ -108,6 +175,11 @@ pub fn load_level(
         .vertices
         .into_iter()
         .map(raw_to_geo::vertex_from)
+        .chain(
+            raw.split_vertices
+                .into_iter()
+                .map(|[x, y]| Vertex { pos: vec2(x, y) }),
+        )
         .collect();
 
     let segs: Vec<Segment> = raw.segs.into_iter().map(raw_to_geo::seg_from).collect();
@@ -136,7 +208,7 @@ pub fn load_level(
         })
         .collect::<Result<_, LoadError>>()?;
 
-    let sectors: Vec<Sector> = raw
+    let mut sectors: Vec<Sector> = raw
         .sectors
         .into_iter()
         .map(|s| {
@@ -148,14 +220,33 @@ pub fn load_level(
                 light: f32::from(s.light >> 3) / 31.0,
                 special: s.special,
                 tag: s.tag,
+                is_sky_ceiling: false,
+                is_sky_floor: false,
             })
         })
         .collect::<Result<_, LoadError>>()?;
 
-    let blockmap = raw_to_geo::blockmap_from(raw.blockmap);
+    let blockmap = raw
+        .blockmap
+        .and_then(raw_to_geo::blockmap_from)
+        .unwrap_or_else(|| Blockmap::rebuild(&vertices, &linedefs));
+
+    let reject = (!raw.reject.is_empty()).then(|| BitMatrix::from_lump(&raw.reject, sectors.len()));
+    missing_textures.sort();
+    missing_textures.dedup();
+
+    let sky_texture = resolve_sky(bank, &mut source, &raw.name);
+
+    // flag the sky flat once, rather than comparing names every frame
+    if let Some(sky) = bank.id(SKY_FLAT) {
+        for s in &mut sectors {
+            s.is_sky_ceiling = s.ceil_tex == sky;
+            s.is_sky_floor = s.floor_tex == sky;
+        }
+    }
 
     /*----- 6. Assemble world::Level -------------------------------------*/
-    Ok(Level {
+    let mut level = Level {
         name: raw.name,
         things,
         linedefs,
@@ -166,7 +257,126 @@ pub fn load_level(
         nodes,
         sectors,
         blockmap,
-    })
+        reject,
+        sky_texture,
+        sector_graph: Default::default(),
+        sector_index: Default::default(),
+    };
+
+    if raw.node_format == raw_level::NodeFormat::Missing {
+        level.build_nodes();
+    }
+
+    /*----- 7. Check it; fix what can be fixed ----------------------------*/
+    let mut repaired = Vec::new();
+    let defects = if opts.strict {
+        level.validate()
+    } else {
+        repaired = level.repair();
+        // stray things and empty subsectors stay, harmlessly; only an
+        // empty lump can't be played
+        let mut left = level.validate();
+        left.retain(|d| d.item == world::MapItem::Map);
+        left
+    };
+    if !defects.is_empty() {
+        return Err(LoadError::InvalidMap {
+            map: level.name,
+            defects,
+        });
+    }
+
+    level.measure_segs();
+
+    // vanilla P_SpawnMapThing would stop the game at these
+    let unknown_things = (level.things.iter().enumerate())
+        .filter(|(_, t)| {
+            !matches!(t.type_id, 1..=4 | 11) && defs::by_doomednum(t.type_id).is_none()
+        })
+        .map(|(i, _)| i)
+        .collect();
+    let report = LoadReport {
+        defects: repaired,
+        missing_textures,
+        unknown_things,
+        node_format: raw.node_format,
+    };
+    Ok((level, report))
+}
+
+/// Compose every wall texture and decode every flat in `wad` up front,
+/// instead of on first reference (`--preload-all`).
+pub fn preload_all_textures(wad: &Wad, bank: &mut world::TextureBank) -> Result<(), LoadError> {
+    bank.preload_all(&mut WadTextures::new(wad)?);
+    Ok(())
+}
+
+/// Decode the standalone patch lump `name` (HUD font, status bar, menu
+/// graphics…); `None` if the WAD doesn't have it or it isn't a patch.
+pub fn load_patch(wad: &Wad, name: &str) -> Option<world::Texture> {
+    let bytes = wad.lump_bytes(wad.find_lump(name)?).ok()?;
+    decode_patch(name, bytes).ok()
+}
+
+/// Register the patches `<prefix>NNN` for characters `first..=last`
+/// (`STCFN033`‥`STCFN095` is the HUD font) as the font `prefix`.  Glyphs
+/// the WAD lacks are left out; ones already in the bank are reused.
+pub fn load_font(wad: &Wad, bank: &mut world::TextureBank, prefix: &str, first: u8, last: u8) {
+    let glyphs = (first..=last)
+        .map(|c| {
+            let name = format!("{prefix}{c:03}");
+            bank.id(name.as_str())
+                .or_else(|| bank.insert(name.as_str(), load_patch(wad, &name)?).ok())
+        })
+        .collect();
+    bank.insert_font(prefix, world::Font::new(first, glyphs));
+}
+
+/// The `(leftoffset, topoffset)` in the header of patch lump `name`: where
+/// its origin sits, from its top-left corner.  Sprites hang from it.
+pub fn patch_offsets(wad: &Wad, name: &str) -> Option<(i16, i16)> {
+    let bytes = wad.lump_bytes(wad.find_lump(name)?).ok()?;
+    let word = |i: usize| Some(i16::from_le_bytes(bytes.get(i..i + 2)?.try_into().ok()?));
+    Some((word(4)?, word(6)?))
+}
+
+/// The flat that marks a ceiling (or floor) as open sky.
+const SKY_FLAT: &str = "F_SKY1";
+
+/// Vanilla `G_DoLoadLevel` sky choice: one per episode in Doom 1, one per
+/// map range (01–11, 12–20, 21+) in Doom 2.
+pub fn sky_for_level(map: &str) -> &'static str {
+    const SKIES: [&str; 4] = ["SKY1", "SKY2", "SKY3", "SKY4"];
+    if let Some(num) = map.strip_prefix("MAP").and_then(|n| n.parse::<u8>().ok()) {
+        return match num {
+            ..12 => SKIES[0],
+            12..21 => SKIES[1],
+            _ => SKIES[2],
+        };
+    }
+    match map.strip_prefix('E').and_then(|r| r.bytes().next()) {
+        Some(ep @ b'1'..=b'4') => SKIES[(ep - b'1') as usize],
+        _ => SKIES[0],
+    }
+}
+
+/// [`sky_for_level`] for `map` out of `bank`, or `SKY1` if the WAD doesn't
+/// have that one (the shareware IWAD ships only `SKY1`).
+fn resolve_sky(
+    bank: &mut world::TextureBank,
+    source: &mut dyn world::TextureSource,
+    map: &str,
+) -> world::TextureId {
+    match bank.resolve(sky_for_level(map), source) {
+        world::NO_TEXTURE => bank.resolve("SKY1", source),
+        id => id,
+    }
+}
+
+/// Last lump named any of `names`; PWADs spell the sprite and flat markers
+/// `SS_START` / `FF_START` as well as the IWAD's `S_START` / `F_START`.
+fn find_marker(wad: &Wad, names: &[&str]) -> Option<usize> {
+    names.iter().filter_map(|n| wad.find_lump(n)).max()
 }
 
 /*====================================================================*/
@@ -175,20 +385,14 @@ pub fn load_level(
 mod raw_to_geo {
     use super::*;
     pub fn thing_from(r: raw_level::RawThing) -> world::Thing {
-        let min_skill = match r.options & 0x0007 {
-            0x0001 => 1,
-            0x0002 => 2,
-            0x0004 => 3,
-            _ => 1,
-        };
         world::Thing {
             pos: vec2(r.x as f32, r.y as f32),
-            angle: (r.angle as f32).to_radians(),
+            angle: world::Angle::from_degrees(r.angle as f32),
             type_id: r.type_ as u16,
-            min_skill,
-            is_deaf: r.options & 0x0020 != 0,
-            multiplayer: r.options & 0x0100 != 0,
-            sub_sector: u16::MAX,
+            skills: (r.options & 0x0007) as u8,
+            is_deaf: r.options & 0x0008 != 0,
+            multiplayer: r.options & 0x0010 != 0,
+            sub_sector: world::SubsectorId::MAX,
         }
     }
 
@@ -215,19 +419,22 @@ mod raw_to_geo {
             pos: vec2(r.x as f32, r.y as f32),
         }
     }
-    pub fn seg_from(r: raw_level::RawSeg) -> world::Segment {
+    pub fn seg_from(r: raw_level::BspSeg) -> world::Segment {
         world::Segment {
-            v1: r.v1 as world::VertexId,
-            v2: r.v2 as world::VertexId,
-            linedef: r.linedef as world::LinedefId,
-            dir: r.side as u16,
-            offset: r.offset as f32,
+            v1: r.v1,
+            v2: r.v2,
+            linedef: r.linedef,
+            dir: r.side,
+            offset: r.offset,
+            // measured once the vertices are in
+            length: 0.0,
+            angle: world::Angle::ZERO,
         }
     }
-    pub fn subsector_from(r: raw_level::RawSubsector) -> world::Subsector {
+    pub fn subsector_from(r: raw_level::BspSubsector) -> world::Subsector {
         world::Subsector {
-            num_lines: r.seg_count as u16,
-            first_line: r.first_seg as world::SegmentId,
+            num_lines: r.seg_count,
+            first_line: r.first_seg,
             sector: u16::MAX,
             things: Vec::new(),
         }
@@ -246,7 +453,7 @@ mod raw_to_geo {
         }
     }
 
-    pub fn node_from(r: raw_level::RawNode) -> world::Node {
+    pub fn node_from(r: raw_level::BspNode) -> world::Node {
         world::Node {
             x: r.x as f32,
             y: r.y as f32,
@@ -257,15 +464,21 @@ mod raw_to_geo {
         }
     }
 
-    pub fn blockmap_from(r: raw_level::RawBlockmap) -> world::Blockmap {
+    /// Decode the lump's offset table into per-cell line lists.
+    /// Returns `None` if any offset points outside the lump (truncated by
+    /// the 64 KiB limit, garbage from a broken node builder, …).
+    pub fn blockmap_from(r: raw_level::RawBlockmap) -> Option<world::Blockmap> {
         let cell_cnt = (r.width as usize) * (r.height as usize);
-        let data_base = 4 + cell_cnt as i16; // header + offset table
+        let data_base = 4 + cell_cnt; // header + offset table, in words
 
         let mut bm_lines: Vec<Vec<world::LinedefId>> = vec![Vec::new(); cell_cnt];
 
         for (cell, &off) in r.offsets.iter().enumerate() {
-            // convert lump-relative word offset → index into `r.data`
-            let mut i = (off - data_base) as usize; // ← FIX ❶
+            // offsets are unsigned words; convert to an index into `r.data`
+            let mut i = (off as u16 as usize).checked_sub(data_base)?;
+            if i >= r.data.len() {
+                return None;
+            }
             while i < r.data.len() {
                 let v = r.data[i];
                 if v == -1 {
@@ -276,27 +489,37 @@ mod raw_to_geo {
             }
         }
 
-        world::Blockmap {
+        Some(world::Blockmap {
             origin: vec2(r.origin_x as f32, r.origin_y as f32),
             width: r.width as i32,
             height: r.height as i32,
             lines: bm_lines,
-        }
+        })
     }
 }
 
 /*====================================================================*/
 /*                  world::Palette / patch / texture helpers                 */
 /*====================================================================*/
-fn load_palette(wad: &Wad) -> Option<world::Palette> {
+/// Every palette in PLAYPAL (up to `NUM_PALETTES`); `None` if there
+/// isn't even the normal one.
+pub fn load_palettes(wad: &Wad) -> Option<Vec<world::Palette>> {
     let idx = wad.find_lump("PLAYPAL")?;
     let bytes = wad.lump_bytes(idx).ok()?;
-    let mut pal = world::Palette::default();
-    for i in 0..256 {
-        pal[i] =
-            (bytes[i * 3] as u32) << 16 | (bytes[i * 3 + 1] as u32) << 8 | bytes[i * 3 + 2] as u32;
-    }
-    Some(pal)
+    let palettes: Vec<_> = bytes
+        .chunks_exact(256 * 3)
+        .take(world::NUM_PALETTES)
+        .map(|rgb| {
+            let mut pal = world::Palette::default();
+            for i in 0..256 {
+                pal[i] = (rgb[i * 3] as u32) << 16
+                    | (rgb[i * 3 + 1] as u32) << 8
+                    | rgb[i * 3 + 2] as u32;
+            }
+            pal
+        })
+        .collect();
+    (!palettes.is_empty()).then_some(palettes)
 }
 
 fn load_colormap(wad: &Wad) -> Option<world::Colormap> {
@@ -324,147 +547,370 @@ fn load_colormap(wad: &Wad) -> Option<world::Colormap> {
     Some(cm)
 }
 
-/*-------------------- patch cache -----------------------------------*/
+/*-------------------- deferred texture source ----------------------*/
+
+/// `TextureSource` backed by a WAD: indexes TEXTURE1/2 and PNAMES once,
+/// then decodes patches and composes textures only when asked.  Build a
+/// texture or flat by name through [`world::TextureSource::build`].
+pub struct WadTextures<'a> {
+    wad: &'a Wad,
+    /// Texture name → (TEXTUREx lump, entry offset).
+    composites: HashMap<Name8, (usize, usize)>,
+    /// PNAMES index → patch lump.
+    pnames: Vec<Option<usize>>,
+    /// Decoded patches, filled lazily and shared by every composite.
+    patches: Vec<Option<world::Texture>>,
+}
+
+impl<'a> WadTextures<'a> {
+    pub fn new(wad: &'a Wad) -> Result<Self, WadError> {
+        let mut composites = HashMap::new();
+        for table in ["TEXTURE1", "TEXTURE2"] {
+            let Some(idx) = wad.find_lump(table) else {
+                continue;
+            };
+            let bytes = wad.lump_bytes(idx)?;
+            let ntex = read_u32(table, bytes, 0)? as usize;
+            for i in 0..ntex {
+                let off = read_u32(table, bytes, 4 + i * 4)? as usize;
+                let name = read_name(table, bytes, off)?;
+                // first definition wins, like vanilla R_TextureNumForName
+                composites
+                    .entry(Name8::from_bytes(name))
+                    .or_insert((idx, off));
+            }
+        }
+
+        let mut pnames = Vec::new();
+        if let Some(idx) = wad.find_lump("PNAMES") {
+            let bytes = wad.lump_bytes(idx)?;
+            let num = read_u32("PNAMES", bytes, 0)? as usize;
+            for i in 0..num {
+                let name = read_name("PNAMES", bytes, 4 + i * 8)?;
+                pnames.push(wad.find_lump(Wad::lump_name_str(name)));
+            }
+        }
+        let patches = vec![None; pnames.len()];
 
-fn decode_all_patches(wad: &Wad) -> Result<Vec<world::Texture>, WadError> {
-    let idx = wad.find_lump("PNAMES").unwrap();
-    let bytes = wad.lump_bytes(idx)?;
-    let num = u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as usize;
+        Ok(Self {
+            wad,
+            composites,
+            pnames,
+            patches,
+        })
+    }
 
-    let mut vec = Vec::with_capacity(num);
-    for i in 0..num {
-        let name_bytes: &[u8; 8] = (&bytes[4 + i * 8..4 + i * 8 + 8]).try_into().unwrap();
-        let name = Wad::lump_name_str(name_bytes);
-        if let Some(id) = wad.find_lump(name) {
-            vec.push(decode_patch(name, wad.lump_bytes(id)?));
-        } else {
-            vec.push(world::Texture::default()); // unlikely but keeps indices aligned
+    /// PNAMES entry `idx`, decoded on first use; the caller checks the
+    /// lump exists.  Broken patches are an error every time they're asked
+    /// for.
+    fn patch(&mut self, idx: usize) -> Result<&world::Texture, WadError> {
+        if self.patches[idx].is_none() {
+            let lump = self.pnames[idx].expect("patch lump checked by the caller");
+            let name = Wad::lump_name_str(&self.wad.lumps()[lump].name);
+            self.patches[idx] = Some(decode_patch(name, self.wad.lump_bytes(lump)?)?);
         }
+        Ok(self.patches[idx].as_ref().unwrap())
+    }
+
+    /// Compose the TEXTURE1/2 entry `name` from its patches; `Ok(None)` if
+    /// there is no such composite, or none of its patches exist.  Patches
+    /// PNAMES doesn't list, or lists but the WAD lacks (registered
+    /// patches in the shareware IWAD, partial PWADs), are left out with
+    /// one warning for the texture.
+    pub fn compose_texture(
+        &mut self,
+        name: impl Into<Name8>,
+    ) -> Result<Option<world::Texture>, WadError> {
+        let name = name.into();
+        let Some(&(lump, off)) = self.composites.get(&name) else {
+            return Ok(None);
+        };
+        let table = Wad::lump_name_str(&self.wad.lumps()[lump].name);
+        let bytes = self.wad.lump_bytes(lump)?;
+        // maptexture_t: name[8] masked:u32 width height:i16 columndirectory:u32
+        //               patchcount:i16 patches[] × { originx originy patch:i16
+        //                                            stepdir colormap:i16 }
+        let w_tex = read_u16(table, bytes, off + 12)? as usize;
+        let h_tex = read_u16(table, bytes, off + 14)? as usize;
+        let np = read_u16(table, bytes, off + 20)? as usize;
+        let pinfo = take(table, bytes, off + 22, np * 10)?;
+
+        let mut placed = Vec::with_capacity(np);
+        for p in pinfo.chunks_exact(10) {
+            let ox = i16::from_le_bytes([p[0], p[1]]) as i32;
+            let oy = i16::from_le_bytes([p[2], p[3]]) as i32;
+            let idx = u16::from_le_bytes([p[4], p[5]]) as usize;
+            if self.pnames.get(idx).is_none_or(Option::is_none) {
+                continue;
+            }
+            self.patch(idx)?;
+            placed.push((idx, ox, oy));
+        }
+        let missing = np - placed.len();
+        if missing > 0 {
+            eprintln!("warning: {name}: {missing}/{np} patches missing");
+            if placed.is_empty() {
+                return Ok(None);
+            }
+        }
+
+        // one patch filling the texture exactly: its columns are the
+        // texture's, as vanilla draws them straight from the patch
+        if let [(idx, 0, _)] = placed[..] {
+            let patch = self.patches[idx].as_ref().unwrap();
+            if (patch.w, patch.h) == (w_tex, h_tex) {
+                return Ok(Some(world::Texture {
+                    name,
+                    ..patch.clone()
+                }));
+            }
+        }
+
+        let mut tex = Composite::new(w_tex, h_tex);
+        for &(idx, ox, _) in &placed {
+            tex.count_patch(self.patches[idx].as_ref().unwrap(), ox);
+        }
+        for &(idx, ox, oy) in &placed {
+            tex.draw_patch(self.patches[idx].as_ref().unwrap(), ox, oy);
+        }
+        Ok(Some(tex.finish(name)))
     }
-    Ok(vec)
 }
 
-fn decode_patch(name: &str, raw: &[u8]) -> world::Texture {
-    let w = u16::from_le_bytes(raw[0..2].try_into().unwrap()) as usize;
-    let h = u16::from_le_bytes(raw[2..4].try_into().unwrap()) as usize;
+impl world::TextureSource for WadTextures<'_> {
+    fn build(&mut self, name: Name8) -> Option<world::Texture> {
+        match self.compose_texture(name) {
+            Ok(Some(tex)) => Some(tex),
+            Ok(None) => load_flat(self.wad, name.as_str()),
+            Err(e) => {
+                eprintln!("warning: texture {name}: {e}");
+                None
+            }
+        }
+    }
+
+    fn names(&self) -> Vec<Name8> {
+        let mut names: Vec<Name8> = self.composites.keys().copied().collect();
+        names.sort();
+        if let (Some(start), Some(end)) = (
+            find_marker(self.wad, &["F_START", "FF_START"]),
+            find_marker(self.wad, &["F_END", "FF_END"]),
+        ) {
+            if start < end {
+                names.extend(
+                    self.wad.lumps()[start + 1..end]
+                        .iter()
+                        .map(|l| Name8::from_bytes(&l.name)),
+                );
+            }
+        }
+        names
+    }
+}
+
+/// Decode a patch in Doom's column format.  Every offset is checked
+/// against the lump; posts running past the bottom are clipped.
+///
+/// ```text
+/// width height:u16  leftoffset topoffset:i16  columnofs:[u32; width]
+/// column = posts × { topdelta:u8  length:u8  pad:u8  pixels:[u8; length]  pad:u8 }  0xFF
+/// ```
+fn decode_patch(name: &str, raw: &[u8]) -> Result<world::Texture, WadError> {
+    let w = read_u16(name, raw, 0)? as usize;
+    let h = read_u16(name, raw, 2)? as usize;
+    let colofs = take(name, raw, 8, w * 4)?;
     let mut pix = vec![0u8; w * h];
-    let colofs = &raw[8..8 + w * 4];
-    for x in 0..w {
-        let mut p = u32::from_le_bytes(colofs[x * 4..][..4].try_into().unwrap()) as usize;
+    let mut mask = world::TexMask::new(w * h);
+    for (x, ofs) in colofs.chunks_exact(4).enumerate() {
+        let mut p = u32::from_le_bytes([ofs[0], ofs[1], ofs[2], ofs[3]]) as usize;
         loop {
-            let row = raw[p] as usize;
+            let row = take(name, raw, p, 1)?[0] as usize;
             if row == 0xFF {
                 break;
             }
-            let len = raw[p + 1] as usize;
-            p += 3;
-            for i in 0..len {
-                pix[(row + i) * w + x] = raw[p + i];
+            let len = take(name, raw, p + 1, 1)?[0] as usize;
+            let post = take(name, raw, p + 3, len + 1)?;
+            for (i, &c) in post[..len].iter().enumerate().take(h.saturating_sub(row)) {
+                pix[(row + i) * w + x] = c;
+                mask.set((row + i) * w + x);
             }
-            p += len + 1;
+            p += len + 4;
         }
     }
-    world::Texture {
+    let full = mask.is_full(w * h);
+    Ok(world::Texture {
         name: name.into(),
         w,
         h,
         pixels: pix,
-    }
+        mask: (!full).then_some(mask),
+    })
 }
 
-/*-------------------- wall texture compose --------------------------*/
-
-fn build_wall_texture(wad: &Wad, patches: &[world::Texture], name: &str) -> Option<world::Texture> {
-    for table in ["TEXTURE1", "TEXTURE2"] {
-        let Some(idx) = wad.find_lump(table) else {
-            continue;
-        };
-        let bytes = wad.lump_bytes(idx).ok()?;
-        let ntex = u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as usize;
-        let mut offs = &bytes[4..];
-        for _ in 0..ntex {
-            let off = u32::from_le_bytes(offs[0..4].try_into().unwrap()) as usize;
-            offs = &offs[4..];
-            let entry = &bytes[off..];
-            let e_name_bytes: &[u8; 8] = (&entry[0..8]).try_into().unwrap();
-            let e_name = Wad::lump_name_str(e_name_bytes);
-            if !e_name.eq_ignore_ascii_case(name) {
+/// The other way round, for tests: a `w`×`h` patch with its origin at
+/// `(left, top)`, one post per run of `Some` texels down each column.
+#[cfg(test)]
+pub(crate) fn encode_patch(
+    w: usize,
+    h: usize,
+    (left, top): (i16, i16),
+    texel: impl Fn(usize, usize) -> Option<u8>,
+) -> Vec<u8> {
+    let mut head = Vec::new();
+    for n in [w as i16, h as i16, left, top] {
+        head.extend(n.to_le_bytes());
+    }
+    let mut columns = Vec::new();
+    for x in 0..w {
+        head.extend(((8 + 4 * w + columns.len()) as u32).to_le_bytes());
+        let mut y = 0;
+        while y < h {
+            if texel(x, y).is_none() {
+                y += 1;
                 continue;
             }
-            return Some(compose_texture(e_name, entry, patches));
+            let run: Vec<u8> = (y..h).map_while(|y| texel(x, y)).collect();
+            columns.extend([y as u8, run.len() as u8, 0]);
+            columns.extend(&run);
+            columns.push(0);
+            y += run.len();
         }
+        columns.push(0xFF);
     }
-    None
+    head.extend(columns);
+    head
 }
 
-fn compose_texture(name: &str, entry: &[u8], patches: &[world::Texture]) -> world::Texture {
-    let w_tex = i16::from_le_bytes(entry[12..14].try_into().unwrap()) as usize;
-    let h_tex = i16::from_le_bytes(entry[14..16].try_into().unwrap()) as usize;
-    let np = u16::from_le_bytes(entry[20..22].try_into().unwrap()) as usize;
-
-    let mut canvas = vec![0u8; w_tex * h_tex];
-    let mut pinfo = &entry[22..];
-    for _ in 0..np {
-        let ox = i16::from_le_bytes(pinfo[0..2].try_into().unwrap()) as i32;
-        let oy = i16::from_le_bytes(pinfo[2..4].try_into().unwrap()) as i32;
-        let idx = u16::from_le_bytes(pinfo[4..6].try_into().unwrap()) as usize;
-        blit_patch(&mut canvas, w_tex, h_tex, &patches[idx], ox, oy);
-        pinfo = &pinfo[10..];
-    }
-    world::Texture {
-        name: name.into(),
-        w: w_tex,
-        h: h_tex,
-        pixels: canvas,
-    }
+/// `len` bytes of lump `name` from `at`, or `TruncatedLump`.
+fn take<'a>(name: &str, raw: &'a [u8], at: usize, len: usize) -> Result<&'a [u8], WadError> {
+    raw.get(at..at.saturating_add(len))
+        .ok_or_else(|| WadError::TruncatedLump {
+            name: name.into(),
+            need: at.saturating_add(len),
+            size: raw.len(),
+        })
+}
+
+fn read_u16(name: &str, raw: &[u8], at: usize) -> Result<u16, WadError> {
+    let b = take(name, raw, at, 2)?;
+    Ok(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(name: &str, raw: &[u8], at: usize) -> Result<u32, WadError> {
+    let b = take(name, raw, at, 4)?;
+    Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_name<'a>(name: &str, raw: &'a [u8], at: usize) -> Result<&'a [u8; 8], WadError> {
+    Ok(take(name, raw, at, 8)?.try_into().unwrap())
 }
 
-fn blit_patch(dest: &mut [u8], dw: usize, dh: usize, p: &world::Texture, ox: i32, oy: i32) {
-    for py in 0..p.h {
-        let dy = oy + py as i32;
-        if !(0..dh as i32).contains(&dy) {
-            continue;
+/*-------------------- wall texture compose --------------------------*/
+
+/// A wall texture being put together, vanilla `R_GenerateLookup` and
+/// `R_GenerateComposite` style.  A column only one patch covers is that
+/// patch's column from its top row, `originy` and all ignored; a column
+/// several cover is composed, each patch clipped to the texture from its
+/// own origin, so one hanging above the top shows only its lower part.
+struct Composite {
+    w: usize,
+    h: usize,
+    pixels: Vec<u8>,
+    mask: world::TexMask,
+    /// Vanilla `patchcount`: patches over each column.
+    patch_count: Vec<u16>,
+}
+
+impl Composite {
+    fn new(w: usize, h: usize) -> Self {
+        Self {
+            w,
+            h,
+            pixels: vec![0; w * h],
+            mask: world::TexMask::new(w * h),
+            patch_count: vec![0; w],
         }
-        for px in 0..p.w {
+    }
+
+    /// The patch columns of `p` at `ox` and the texture columns they fall
+    /// on, those inside the texture.
+    fn columns(&self, p: &world::Texture, ox: i32) -> impl Iterator<Item = (usize, usize)> + use<> {
+        let w = self.w as i32;
+        (0..p.w).filter_map(move |px| {
             let dx = ox + px as i32;
-            if !(0..dw as i32).contains(&dx) {
-                continue;
-            }
-            let src = p.pixels[py * p.w + px];
-            if src != 0 {
-                dest[dy as usize * dw + dx as usize] = src;
+            (0..w).contains(&dx).then_some((px, dx as usize))
+        })
+    }
+
+    /// Count `p` at `ox` over the columns it covers.  Every patch is
+    /// counted before any is drawn.
+    fn count_patch(&mut self, p: &world::Texture, ox: i32) {
+        for (_, dx) in self.columns(p, ox) {
+            self.patch_count[dx] += 1;
+        }
+    }
+
+    fn draw_patch(&mut self, p: &world::Texture, ox: i32, oy: i32) {
+        for (px, dx) in self.columns(p, ox) {
+            let oy = if self.patch_count[dx] == 1 { 0 } else { oy };
+            for py in 0..p.h {
+                let dy = oy + py as i32;
+                if !(0..self.h as i32).contains(&dy) {
+                    continue;
+                }
+                let src = py * p.w + px;
+                if p.is_opaque(src) {
+                    let dst = dy as usize * self.w + dx;
+                    self.pixels[dst] = p.pixels[src];
+                    self.mask.set(dst);
+                }
             }
         }
     }
+
+    fn finish(self, name: Name8) -> world::Texture {
+        let full = self.mask.is_full(self.w * self.h);
+        world::Texture {
+            name,
+            w: self.w,
+            h: self.h,
+            pixels: self.pixels,
+            mask: (!full).then_some(self.mask),
+        }
+    }
 }
 
 /*----------------------------- flats --------------------------------*/
 
-fn decode_flat(wad: &Wad, name: &str) -> Option<world::Texture> {
+/// Flat lump `name` as a 64-wide texture; `None` if it isn't one.  Flats
+/// are 64×64, but PWADs ship some with junk rows on the end, which are
+/// kept.
+pub fn load_flat(wad: &Wad, name: &str) -> Option<world::Texture> {
     let idx = wad.find_lump(name)?;
     let bytes = wad.lump_bytes(idx).ok()?;
-    if bytes.len() != 4096 {
+    if bytes.len() < 4096 || bytes.len() % 64 != 0 {
         return None;
     }
-    let mut rgba = Vec::with_capacity(64 * 64);
-    for &b in bytes {
-        rgba.push(b);
-    }
     Some(world::Texture {
         name: name.into(),
         w: 64,
-        h: 64,
-        pixels: rgba,
+        h: bytes.len() / 64,
+        pixels: bytes.to_vec(),
+        mask: None,
     })
 }
 
 fn load_all_sprites(wad: &Wad, bank: &mut world::TextureBank) -> Result<(), LoadError> {
-    let start_index = wad.find_lump("S_START").ok_or(LoadError::NoSprites)? + 1;
-    let end_index = wad.find_lump("S_END").ok_or(LoadError::NoSprites)?;
+    let start_index = find_marker(wad, &["S_START", "SS_START"]).ok_or(LoadError::NoSprites)? + 1;
+    let end_index = find_marker(wad, &["S_END", "SS_END"]).ok_or(LoadError::NoSprites)?;
 
     for idx in start_index..end_index {
-        let name = Wad::lump_name_str(&wad.lumps()[idx].name);
-        let id = bank.insert(name, decode_patch(name, wad.lump_bytes(idx)?))?;
+        let name = Name8::from_bytes(&wad.lumps()[idx].name);
+        if bank.id(name).is_some() {
+            continue; // already loaded by a previous map
+        }
+        let patch = decode_patch(name.as_str(), wad.lump_bytes(idx)?)?;
+        let id = bank.insert(name, patch)?;
         bank.register_sprite_lump(name, id);
     }
 
@@ -503,6 +949,464 @@ mod tests {
         assert_eq!(tex.h, 128); // STARTAN textures are 128×128
     }
 
+    #[test]
+    fn textures_compose_lazily() {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = world::TextureBank::default_with_checker();
+        load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        let lazy = bank.len();
+        let lazy_bytes = bank.memory_usage();
+
+        // a second map re-uses the sprites and shared textures
+        load_level(&wad, wad.level_indices()[1], &mut bank).unwrap();
+
+        preload_all_textures(&wad, &mut bank).unwrap();
+        assert!(bank.len() > lazy);
+        assert!(bank.memory_usage() > lazy_bytes);
+    }
+
+    #[test]
+    fn sprites_keep_black_texels() {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = world::TextureBank::default_with_checker();
+        load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+
+        // some sprite somewhere draws palette index 0 as a real colour
+        let black_opaque = (1..bank.len() as world::TextureId).any(|id| {
+            let t = bank.texture(id).unwrap();
+            t.mask.is_some() && (0..t.pixels.len()).any(|i| t.pixels[i] == 0 && t.is_opaque(i))
+        });
+        assert!(black_opaque);
+    }
+
+    /// 1×1 patch of palette index `c`.
+    fn dot_patch(c: u8) -> Vec<u8> {
+        encode_patch(1, 1, (0, 0), |_, _| Some(c))
+    }
+
+    #[test]
+    fn sky_follows_episode_or_map_range() {
+        for e in 1..=4 {
+            for m in 1..=9 {
+                assert_eq!(sky_for_level(&format!("E{e}M{m}")), format!("SKY{e}"));
+            }
+        }
+        for n in 1..=32 {
+            let sky = match n {
+                ..=11 => "SKY1",
+                12..=20 => "SKY2",
+                _ => "SKY3",
+            };
+            assert_eq!(sky_for_level(&format!("MAP{n:02}")), sky, "MAP{n:02}");
+        }
+        assert_eq!(sky_for_level("START"), "SKY1");
+    }
+
+    #[test]
+    fn missing_sky_falls_back_to_sky1() {
+        /// The shareware IWAD: `SKY1` and nothing else.
+        struct Shareware;
+        impl world::TextureSource for Shareware {
+            fn build(&mut self, name: Name8) -> Option<world::Texture> {
+                (name == "SKY1").then(|| world::Texture {
+                    name,
+                    w: 1,
+                    h: 1,
+                    pixels: vec![0],
+                    mask: None,
+                })
+            }
+            fn names(&self) -> Vec<Name8> {
+                vec!["SKY1".into()]
+            }
+        }
+        let mut bank = world::TextureBank::default_with_checker();
+        let sky1 = resolve_sky(&mut bank, &mut Shareware, "E1M1");
+        assert_ne!(sky1, world::NO_TEXTURE);
+        assert_eq!(resolve_sky(&mut bank, &mut Shareware, "E3M4"), sky1);
+        assert_eq!(resolve_sky(&mut bank, &mut Shareware, "MAP25"), sky1);
+    }
+
+    #[test]
+    fn pwad_marker_spellings() {
+        let wad = Wad::from_lumps(&[
+            ("MAP01", vec![]),
+            ("THINGS", vec![]),
+            ("E1M1", vec![]),
+            ("THINGS", vec![]),
+            ("SS_START", vec![]),
+            ("TROOA1", dot_patch(7)),
+            ("SS_END", vec![]),
+            ("FF_START", vec![]),
+            ("FLOOR0_1", vec![0; 4096]),
+            ("FLOOR0_2", vec![0; 4160]),
+            ("FF_END", vec![]),
+        ]);
+        assert_eq!(wad.level_indices(), [0, 2]);
+
+        let mut bank = world::TextureBank::default_with_checker();
+        load_all_sprites(&wad, &mut bank).unwrap();
+        let id = bank.id("TROOA1").expect("sprite between SS_ markers");
+        assert_eq!(bank.texture(id).unwrap().pixels, [7]);
+
+        let mut source = WadTextures::new(&wad).unwrap();
+        assert!(world::TextureSource::names(&source).contains(&"FLOOR0_1".into()));
+        let flat = world::TextureSource::build(&mut source, "FLOOR0_1".into()).unwrap();
+        assert_eq!((flat.w, flat.h), (64, 64));
+        let junk = load_flat(&wad, "FLOOR0_2").unwrap();
+        assert_eq!((junk.w, junk.h), (64, 65));
+        assert!(load_flat(&wad, "TROOA1").is_none());
+        assert_eq!(patch_offsets(&wad, "TROOA1"), Some((0, 0)));
+        assert_eq!(patch_offsets(&wad, "THINGS"), None);
+
+        let bare = Wad::from_lumps(&[("PLAYPAL", vec![0; 768])]);
+        assert!(matches!(
+            load_all_sprites(&bare, &mut bank),
+            Err(LoadError::NoSprites)
+        ));
+    }
+
+    /// TEXTURE1 with one `w`×`h` texture made of PNAMES patch 0 at (0, 0).
+    fn texture1(w: u16, h: u16) -> Vec<u8> {
+        texture1_of(w, h, &[(0, 0)])
+    }
+
+    /// TEXTURE1 with one `w`×`h` texture WALL made of `(x, PNAMES index)`
+    /// patches along the top.
+    fn texture1_of(w: u16, h: u16, patches: &[(i16, u16)]) -> Vec<u8> {
+        let at: Vec<_> = patches.iter().map(|&(x, idx)| (x, 0, idx)).collect();
+        texture1_at(w, h, &at)
+    }
+
+    /// TEXTURE1 with one `w`×`h` texture WALL made of `(x, y, PNAMES
+    /// index)` patches.
+    fn texture1_at(w: u16, h: u16, patches: &[(i16, i16, u16)]) -> Vec<u8> {
+        let mut t = 1u32.to_le_bytes().to_vec();
+        t.extend(8u32.to_le_bytes());
+        t.extend(b"WALL\0\0\0\0");
+        t.extend([0; 4]);
+        t.extend(w.to_le_bytes());
+        t.extend(h.to_le_bytes());
+        t.extend([0; 4]);
+        t.extend((patches.len() as u16).to_le_bytes());
+        for &(x, y, idx) in patches {
+            t.extend(x.to_le_bytes());
+            t.extend(y.to_le_bytes());
+            t.extend(idx.to_le_bytes());
+            t.extend([0; 4]);
+        }
+        t
+    }
+
+    #[test]
+    fn missing_patches_are_left_out() {
+        let mut pnames = 2u32.to_le_bytes().to_vec();
+        pnames.extend(b"DOT\0\0\0\0\0");
+        pnames.extend(b"GONE\0\0\0\0");
+
+        // DOT at x=0; GONE (listed, absent) and index 7 (not listed) skipped
+        let wad = Wad::from_lumps(&[
+            ("TEXTURE1", texture1_of(3, 1, &[(0, 0), (1, 1), (2, 7)])),
+            ("PNAMES", pnames.clone()),
+            ("DOT", dot_patch(9)),
+        ]);
+        let wall = WadTextures::new(&wad)
+            .unwrap()
+            .compose_texture("WALL")
+            .unwrap()
+            .unwrap();
+        assert_eq!(wall.pixels, [9, 0, 0]);
+        assert!(wall.is_opaque(0) && !wall.is_opaque(1) && !wall.is_opaque(2));
+
+        // nothing left: no texture, so the sidedef gets the checker
+        let wad = Wad::from_lumps(&[
+            ("TEXTURE1", texture1_of(2, 1, &[(0, 1), (1, 7)])),
+            ("PNAMES", pnames),
+        ]);
+        let mut source = WadTextures::new(&wad).unwrap();
+        assert_eq!(source.compose_texture("WALL").unwrap(), None);
+        assert!(world::TextureSource::build(&mut source, "WALL".into()).is_none());
+    }
+
+    #[test]
+    fn truncated_or_garbage_patches_are_errors() {
+        let mut patch = dot_patch(3);
+        patch[2] = 2; // 1×2, a second post below the first
+        patch.truncate(patch.len() - 1);
+        patch.extend([1, 1, 0, 4, 0, 0xFF]);
+        let tex = decode_patch("DOT", &patch).unwrap();
+        assert_eq!(tex.pixels, [3, 4]);
+
+        // every cut short of the end is caught
+        for len in 0..patch.len() {
+            assert!(
+                matches!(
+                    decode_patch("DOT", &patch[..len]),
+                    Err(WadError::TruncatedLump { .. })
+                ),
+                "{len} bytes"
+            );
+        }
+
+        // random bytes: an error or a picture, never a panic
+        let mut seed = 0x2545_F491_u32;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        for _ in 0..2000 {
+            let len = next() as usize % 64;
+            let mut raw: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            if raw.len() >= 4 {
+                // keep sizes small so the canvas stays cheap
+                raw[1] = 0;
+                raw[3] = 0;
+            }
+            let _ = decode_patch("JUNK", &raw);
+        }
+
+        // a post hanging past the bottom is clipped, not written out of bounds
+        let mut tall = dot_patch(5);
+        tall[12] = 0; // topdelta 0…
+        tall[13] = 3; // …but three rows long in a 1-high patch
+        tall.truncate(15);
+        tall.extend([5, 5, 5, 0, 0xFF]);
+        assert_eq!(decode_patch("TALL", &tall).unwrap().pixels, [5]);
+    }
+
+    #[test]
+    fn truncated_texture_tables_are_errors() {
+        let mut pnames = 1u32.to_le_bytes().to_vec();
+        pnames.extend(b"DOT\0\0\0\0\0");
+        let wad = Wad::from_lumps(&[
+            ("TEXTURE1", texture1(2, 1)),
+            ("PNAMES", pnames.clone()),
+            ("DOT", dot_patch(9)),
+        ]);
+        let wall = WadTextures::new(&wad)
+            .unwrap()
+            .compose_texture("WALL")
+            .unwrap()
+            .unwrap();
+        assert_eq!((wall.w, wall.h, wall.pixels[0]), (2, 1, 9));
+        assert!(!wall.is_opaque(1));
+
+        let mut table = texture1(2, 1);
+        table.truncate(table.len() - 4); // patch list cut short
+        let wad = Wad::from_lumps(&[("TEXTURE1", table), ("PNAMES", pnames.clone())]);
+        let mut source = WadTextures::new(&wad).unwrap();
+        assert!(matches!(
+            source.compose_texture("WALL"),
+            Err(WadError::TruncatedLump { .. })
+        ));
+        assert!(world::TextureSource::build(&mut source, "WALL".into()).is_none());
+
+        let broken = Wad::from_lumps(&[
+            ("TEXTURE1", texture1(2, 1)),
+            ("PNAMES", pnames),
+            ("DOT", vec![1, 0, 1, 0, 0, 0, 0, 0, 99, 0, 0, 0]),
+        ]);
+        assert!(
+            WadTextures::new(&broken)
+                .unwrap()
+                .compose_texture("WALL")
+                .is_err()
+        );
+
+        for table in [vec![5, 0, 0, 0], texture1(2, 1)[..10].to_vec()] {
+            let wad = Wad::from_lumps(&[("TEXTURE1", table)]);
+            assert!(matches!(
+                WadTextures::new(&wad),
+                Err(WadError::TruncatedLump { .. })
+            ));
+        }
+    }
+
+    /// Two patches, the second 16 rows above the top and half past the
+    /// first.  Where they overlap the texture is composed: the first,
+    /// then the second's bottom rows over it, its hole letting the first
+    /// through.  Where the second is alone its column is used as it is,
+    /// from its top row, the way vanilla ignores `originy` there.
+    #[test]
+    fn patches_above_the_top_show_their_lower_rows() {
+        let mut pnames = 2u32.to_le_bytes().to_vec();
+        pnames.extend(b"LOW\0\0\0\0\0");
+        pnames.extend(b"TALL\0\0\0\0");
+        let low = encode_patch(4, 4, (0, 0), |x, y| Some((10 * (x + 1) + y) as u8));
+        let tall = encode_patch(4, 20, (0, 0), |x, y| {
+            (x != 0 || y != 17).then_some((100 + 20 * x + y) as u8)
+        });
+        let wad = Wad::from_lumps(&[
+            ("TEXTURE1", texture1_at(6, 4, &[(0, 0, 0), (2, -16, 1)])),
+            ("PNAMES", pnames),
+            ("LOW", low),
+            ("TALL", tall),
+        ]);
+        let wall = WadTextures::new(&wad)
+            .unwrap()
+            .compose_texture("WALL")
+            .unwrap()
+            .unwrap();
+        #[rustfmt::skip]
+        let want: [u8; 24] = [
+            10, 20, 116, 136, 140, 160,
+            11, 21,  31, 137, 141, 161,
+            12, 22, 118, 138, 142, 162,
+            13, 23, 119, 139, 143, 163,
+        ];
+        assert_eq!(wall.pixels, want);
+        assert!(wall.mask.is_none());
+
+        // a lone patch the texture's size is the texture, holes and all
+        let mut pnames = 1u32.to_le_bytes().to_vec();
+        pnames.extend(b"TALL\0\0\0\0");
+        let tall = encode_patch(2, 3, (0, 0), |x, y| (x + y != 2).then_some(7));
+        let wad = Wad::from_lumps(&[
+            ("TEXTURE1", texture1_at(2, 3, &[(0, -8, 0)])),
+            ("PNAMES", pnames),
+            ("TALL", tall.clone()),
+        ]);
+        let wall = WadTextures::new(&wad)
+            .unwrap()
+            .compose_texture("WALL")
+            .unwrap()
+            .unwrap();
+        let mut patch = decode_patch("TALL", &tall).unwrap();
+        patch.name = "WALL".into();
+        assert_eq!(wall, patch);
+        assert!(!wall.is_opaque(3) && !wall.is_opaque(4) && wall.is_opaque(5));
+    }
+
+    /// BIGDOOR2 hashed against `golden/bigdoor2.hash` beside this file;
+    /// skipped unless `assets/doom.wad` is present.  Set `YADOOM_BLESS=1`
+    /// to (re)write the hash; without it a missing one is a failure.
+    #[test]
+    fn bigdoor2_matches_its_golden_hash() {
+        if !doom_wad().exists() {
+            eprintln!("skipping: {} not found", doom_wad().display());
+            return;
+        }
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let tex = WadTextures::new(&wad)
+            .unwrap()
+            .compose_texture("BIGDOOR2")
+            .unwrap()
+            .expect("BIGDOOR2");
+        // FNV-1a over the size, then each texel and whether it shows
+        let mut hash = 0xCBF2_9CE4_8422_2325_u64;
+        let bytes = [tex.w as u32, tex.h as u32].map(u32::to_le_bytes);
+        let texels = (0..tex.pixels.len()).flat_map(|i| [tex.pixels[i], tex.is_opaque(i) as u8]);
+        for b in bytes.into_iter().flatten().chain(texels) {
+            hash = (hash ^ b as u64).wrapping_mul(0x100_0000_01B3);
+        }
+        let hash = format!("{hash:016x}");
+
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/wad/golden")
+            .join("bigdoor2.hash");
+        if std::env::var_os("YADOOM_BLESS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &hash).unwrap();
+            eprintln!("blessed {}", path.display());
+            return;
+        }
+        let Ok(want) = std::fs::read_to_string(&path) else {
+            panic!(
+                "no reference at {}; run with YADOOM_BLESS=1 to write it",
+                path.display()
+            );
+        };
+        assert_eq!(want.trim(), hash);
+    }
+
+    /// A 128-unit square room without SEGS / SSECTORS / NODES data, the
+    /// player start in the middle, its walls' middle texture `middle`;
+    /// `absent` leaves the lumps out entirely.
+    fn nodeless_square(absent: bool, middle: &[u8; 8]) -> Wad {
+        let le16 = |v: &[i16]| -> Vec<u8> { v.iter().flat_map(|x| x.to_le_bytes()).collect() };
+        let mut linedefs = Vec::new();
+        for (v1, v2) in [(1, 0), (2, 1), (3, 2), (0, 3)] {
+            linedefs.extend(le16(&[v1, v2, 1, 0, 0, 0, -1]));
+        }
+        let mut sidedef = le16(&[0, 0]);
+        sidedef.extend(b"-\0\0\0\0\0\0\0-\0\0\0\0\0\0\0");
+        sidedef.extend(middle);
+        sidedef.extend(le16(&[0]));
+        let mut sector = le16(&[0, 128]);
+        sector.extend(b"FLOOR4_8CEIL3_5\0");
+        sector.extend(le16(&[160, 0, 0]));
+
+        let mut lumps = vec![
+            ("PLAYPAL", vec![0; 768]),
+            ("COLORMAP", vec![0; 34 * 256]),
+            ("S_START", vec![]),
+            ("S_END", vec![]),
+            ("E1M1", vec![]),
+            ("THINGS", le16(&[64, 64, 0, 1, 7, 32, 32, 0, 9999, 7])),
+            ("LINEDEFS", linedefs),
+            ("SIDEDEFS", sidedef),
+            ("VERTEXES", le16(&[0, 0, 128, 0, 128, 128, 0, 128])),
+        ];
+        if !absent {
+            lumps.extend([("SEGS", vec![]), ("SSECTORS", vec![]), ("NODES", vec![])]);
+        }
+        lumps.extend([("SECTORS", sector), ("REJECT", vec![])]);
+        Wad::from_lumps(&lumps)
+    }
+
+    #[test]
+    fn maps_without_nodes_get_built_ones() {
+        for absent in [false, true] {
+            let wad = nodeless_square(absent, b"-\0\0\0\0\0\0\0");
+            let raw = wad.parse_level(4).unwrap();
+            assert_eq!(raw.node_format, raw_level::NodeFormat::Missing);
+
+            let mut bank = world::TextureBank::default_with_checker();
+            let mut level = load_level(&wad, 4, &mut bank).unwrap();
+            level.finalise_bsp();
+            assert_eq!(level.segs.len(), 4);
+            assert_eq!(level.subsectors.len(), 1);
+            assert!(!level.nodes.is_empty());
+            assert_eq!(level.things[0].sub_sector, 0);
+            assert_eq!(level.sector_of_subsector(0), 0);
+        }
+    }
+
+    #[test]
+    fn dash_is_transparent_but_typos_show_the_checker() {
+        let mut bank = world::TextureBank::default_with_checker();
+        let level = load_level(&nodeless_square(true, b"-\0\0\0\0\0\0\0"), 4, &mut bank).unwrap();
+        let sd = &level.sidedefs[0];
+        assert_eq!(
+            [sd.upper, sd.lower, sd.middle],
+            [world::TRANSPARENT_TEXTURE; 3]
+        );
+
+        let level = load_level(&nodeless_square(true, b"STARTAN9"), 4, &mut bank).unwrap();
+        let sd = &level.sidedefs[0];
+        assert_eq!(sd.upper, world::TRANSPARENT_TEXTURE);
+        assert_eq!(sd.middle, world::NO_TEXTURE);
+    }
+
+    #[test]
+    fn report_names_what_the_wad_lacks() {
+        let mut bank = world::TextureBank::default_with_checker();
+        let wad = nodeless_square(true, b"startan9");
+        let (level, report) =
+            load_level_report(&wad, 4, &mut bank, LoadOptions::default()).unwrap();
+        assert_eq!(
+            report.missing_textures,
+            ["CEIL3_5", "FLOOR4_8", "STARTAN9"].map(Name8::from)
+        );
+        // the player start is fine, type 9999 isn't
+        assert_eq!(report.unknown_things, [1]);
+        assert_eq!(level.things[1].type_id, 9999);
+        assert_eq!(report.node_format, NodeFormat::Missing);
+        assert!(report.defects.is_empty());
+    }
+
     #[test]
     fn unknown_name_gets_checker() {
         let bank = world::TextureBank::default_with_checker();
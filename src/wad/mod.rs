@@ -2,5 +2,13 @@ mod level;
 mod loader;
 mod raw;
 
-pub use loader::load_level;
-pub use raw::Wad;
+pub use level::NodeFormat;
+pub use loader::{
+    LoadError, LoadOptions, LoadReport, WadTextures, load_flat, load_font, load_level,
+    load_level_report, load_level_with, load_palettes, load_patch, patch_offsets,
+    preload_all_textures, sky_for_level,
+};
+pub use raw::{LumpInfo, Wad, WadError};
+
+#[cfg(test)]
+pub(crate) use loader::encode_patch;
@@ -3,16 +3,14 @@
 //! * Reads the entire IWAD into RAM.  
 //! * Provides zero-copy access to individual lumps.  
 //! * Decodes binary lumps into typed vectors with **bincode 2**.
-//!
-//! Only the “IWAD” magic is accepted for now (PWAD support can be added
-//! later).
+//! * PWADs are appended on top; their lumps shadow the IWAD's by name.
 
 use bincode::{Decode, config, decode_from_slice};
 use byteorder::{LittleEndian as LE, ReadBytesExt};
 use std::{
     collections::HashMap,
     fs::File,
-    io::{self, Read, Seek, SeekFrom},
+    io::{self, Read},
     mem,
     path::Path,
 };
@@ -40,7 +38,7 @@ pub enum WadError {
     #[error("I/O error: {0}")]
     Io(#[from] io::Error),
 
-    #[error("file is not an IWAD")]
+    #[error("wrong WAD magic (IWAD for the base file, IWAD or PWAD on top)")]
     BadMagic,
 
     #[error("directory extends beyond end of file")]
@@ -73,6 +71,13 @@ pub enum WadError {
         elem: usize,
         source: bincode::error::DecodeError,
     },
+
+    #[error("lump {name} truncated: needs {need} bytes, has {size}")]
+    TruncatedLump {
+        name: String,
+        need: usize,
+        size: usize,
+    },
 }
 
 impl Wad {
@@ -117,6 +122,10 @@ impl Wad {
     // Generic decode helper
     // ------------------------------------------------------------------ //
 
+    /// Decode lump `idx` as an array of `T`, little-endian field by field
+    /// whatever the host.  Records are sized with `size_of::<T>()`, so
+    /// `T` must be `repr(C)` without padding (see the asserts in
+    /// `level.rs`).
     pub fn lump_to_vec<T>(&self, idx: usize) -> Result<Vec<T>, WadError>
     where
         T: Decode<()>,
@@ -158,21 +167,58 @@ impl Wad {
     // ------------------------------------------------------------------ //
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, WadError> {
-        let mut file = File::open(path)?;
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_bytes(bytes)
+    }
+
+    /// Parse a WAD image that is already in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, WadError> {
+        let lumps = Self::read_directory(&bytes, &[b"IWAD"])?;
+        let mut wad = Self {
+            lumps,
+            bytes,
+            by_name: HashMap::new(),
+        };
+        wad.index_names();
+        Ok(wad)
+    }
+
+    /// Load a PWAD from disk on top of this WAD (see [`Wad::merge_bytes`]).
+    pub fn merge_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), WadError> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        self.merge_bytes(bytes)
+    }
+
+    /// Append a PWAD (or another IWAD) image: its lumps go after ours, so
+    /// `find_lump` returns its versions of any names both have.
+    pub fn merge_bytes(&mut self, bytes: Vec<u8>) -> Result<(), WadError> {
+        let base = self.bytes.len();
+        let mut lumps = Self::read_directory(&bytes, &[b"IWAD", b"PWAD"])?;
+        for l in &mut lumps {
+            l.offset = u32::try_from(base + l.offset as usize)
+                .map_err(|_| WadError::DirectoryOutOfBounds)?;
+        }
+        self.bytes.extend(bytes);
+        self.lumps.extend(lumps);
+        self.index_names();
+        Ok(())
+    }
+
+    /// Parse and bounds-check the directory of one WAD image whose magic
+    /// must be one of `magics`.
+    fn read_directory(bytes: &[u8], magics: &[&[u8; 4]]) -> Result<Vec<LumpInfo>, WadError> {
+        let mut header = bytes;
 
         let mut magic = [0u8; 4];
-        file.read_exact(&mut magic)?;
-        if &magic != b"IWAD" {
+        header.read_exact(&mut magic)?;
+        if !magics.contains(&&magic) {
             return Err(WadError::BadMagic);
         }
 
-        let num_lumps = file.read_u32::<LE>()?;
-        let dir_offset = file.read_u32::<LE>()?;
-
-        // read whole file
-        let mut bytes = Vec::new();
-        file.seek(SeekFrom::Start(0))?;
-        file.read_to_end(&mut bytes)?;
+        let num_lumps = header.read_u32::<LE>()?;
+        let dir_offset = header.read_u32::<LE>()?;
 
         // directory bounds check
         let dir_end = dir_offset as usize + num_lumps as usize * 16;
@@ -210,19 +256,17 @@ impl Wad {
             }
         }
 
-        // build name → idx map (later lumps shadow earlier ones)
-        let mut by_name = HashMap::with_capacity(lumps.len());
-        for (i, l) in lumps.iter().enumerate().rev() {
-            by_name
+        Ok(lumps)
+    }
+
+    /// Rebuild the name → index map (later lumps shadow earlier ones).
+    fn index_names(&mut self) {
+        self.by_name.clear();
+        for (i, l) in self.lumps.iter().enumerate().rev() {
+            self.by_name
                 .entry(Self::lump_name_str(&l.name).to_owned())
                 .or_insert(i);
         }
-
-        Ok(Self {
-            lumps,
-            bytes,
-            by_name,
-        })
     }
 }
 
@@ -230,6 +274,35 @@ impl Wad {
 // Tests
 // ==========================================================================
 
+#[cfg(test)]
+impl Wad {
+    /// In-memory IWAD holding `lumps` in order.
+    pub(crate) fn from_lumps(lumps: &[(&str, Vec<u8>)]) -> Wad {
+        Wad::from_bytes(Wad::image(b"IWAD", lumps)).unwrap()
+    }
+
+    /// A WAD file with `magic` holding `lumps` in order.
+    pub(crate) fn image(magic: &[u8; 4], lumps: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let data: usize = lumps.iter().map(|(_, b)| b.len()).sum();
+        let mut bytes = magic.to_vec();
+        bytes.extend((lumps.len() as u32).to_le_bytes());
+        bytes.extend((12 + data as u32).to_le_bytes());
+        for (_, b) in lumps {
+            bytes.extend(b);
+        }
+        let mut offset = 12u32;
+        for (name, b) in lumps {
+            let mut raw = [0u8; 8];
+            raw[..name.len()].copy_from_slice(name.as_bytes());
+            bytes.extend(offset.to_le_bytes());
+            bytes.extend((b.len() as u32).to_le_bytes());
+            bytes.extend(raw);
+            offset += b.len() as u32;
+        }
+        bytes
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,6 +314,44 @@ mod tests {
             .join("doom.wad")
     }
 
+    #[test]
+    fn empty_image_from_bytes() {
+        let mut bytes = b"IWAD".to_vec();
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(12u32.to_le_bytes());
+        let wad = Wad::from_bytes(bytes).unwrap();
+        assert!(wad.lumps().is_empty());
+
+        assert!(matches!(
+            Wad::from_bytes(b"PWAD\0\0\0\0\x0c\0\0\0".to_vec()),
+            Err(WadError::BadMagic)
+        ));
+    }
+
+    #[test]
+    fn pwad_lumps_shadow_the_iwad() {
+        let mut wad = Wad::from_lumps(&[("PLAYPAL", vec![1]), ("COLORMAP", vec![2])]);
+        let pwad = Wad::image(b"PWAD", &[("PLAYPAL", vec![3, 4])]);
+        assert!(matches!(
+            Wad::from_bytes(pwad.clone()),
+            Err(WadError::BadMagic)
+        ));
+        wad.merge_bytes(pwad).unwrap();
+
+        assert_eq!(wad.lumps().len(), 3);
+        let pal = wad.find_lump("PLAYPAL").unwrap();
+        assert_eq!(pal, 2);
+        assert_eq!(wad.lump_bytes(pal).unwrap(), [3, 4]);
+        assert_eq!(
+            wad.lump_bytes(wad.find_lump("COLORMAP").unwrap()).unwrap(),
+            [2]
+        );
+        assert!(matches!(
+            wad.merge_bytes(b"JUNK\0\0\0\0\x0c\0\0\0".to_vec()),
+            Err(WadError::BadMagic)
+        ));
+    }
+
     #[test]
     fn opens_header() {
         let wad = Wad::from_file(doom_wad()).unwrap();
@@ -0,0 +1,190 @@
+//! Directions as vanilla's `angle_t`: binary angle measurement, where a
+//! full turn is 2³², so adding and subtracting wrap round exactly and
+//! never drift.
+//!
+//! 0 is east and angles grow anticlockwise, like radians.  The trig goes
+//! through `f32` radians; [`Angle::sin_cos`] picks the representation
+//! nearest 0 so it loses as little as it can.  A turn is taken to be
+//! `f32` τ exactly, so `PI`, `FRAC_PI_2` and whatever `atan2` says for the
+//! axes land on `ANG180`, `ANG90` and friends with nothing to round.
+
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use glam::Vec2;
+
+/// BAM units in a full turn.
+const TURN: f64 = 4_294_967_296.0;
+
+/// Radians in a full turn, as `f32` has it.
+const TAU: f64 = std::f32::consts::TAU as f64;
+
+/// Ordered like vanilla's unsigned `angle_t`: `ANG270 > ANG90`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Angle(pub u32);
+
+impl Angle {
+    pub const ZERO: Self = Self(0);
+    pub const ANG45: Self = Self(0x2000_0000);
+    pub const ANG90: Self = Self(0x4000_0000);
+    pub const ANG180: Self = Self(0x8000_0000);
+    pub const ANG270: Self = Self(0xC000_0000);
+
+    /// `turns` of a full turn, rounded to the nearest BAM unit.
+    fn from_turns(turns: f64) -> Self {
+        Self((turns.rem_euclid(1.0) * TURN).round() as u64 as u32)
+    }
+
+    pub fn from_radians(radians: f32) -> Self {
+        Self::from_turns(f64::from(radians) / TAU)
+    }
+
+    pub fn from_degrees(degrees: f32) -> Self {
+        Self::from_turns(f64::from(degrees) / 360.0)
+    }
+
+    /// The raw `angle_t`.
+    #[inline]
+    pub fn bam(self) -> u32 {
+        self.0
+    }
+
+    /// In `0..=τ`: the last few units short of a full turn round up.
+    #[inline]
+    pub fn radians(self) -> f32 {
+        (f64::from(self.0) / TURN * TAU) as f32
+    }
+
+    /// In `-π..π`.
+    #[inline]
+    pub fn signed_radians(self) -> f32 {
+        (f64::from(self.0 as i32) / TURN * TAU) as f32
+    }
+
+    /// In `0..360`.
+    #[inline]
+    pub fn degrees(self) -> f32 {
+        (f64::from(self.0) / TURN * 360.0) as f32
+    }
+
+    #[inline]
+    pub fn sin_cos(self) -> (f32, f32) {
+        self.signed_radians().sin_cos()
+    }
+
+    /// Unit vector pointing this way.
+    #[inline]
+    pub fn unit(self) -> Vec2 {
+        let (s, c) = self.sin_cos();
+        Vec2::new(c, s)
+    }
+}
+
+/// Vanilla `R_PointToAngle2`: the direction from `from` to `to`.
+#[inline]
+pub fn angle_to(from: Vec2, to: Vec2) -> Angle {
+    Angle::from_radians((to - from).to_angle())
+}
+
+/// Radians.
+impl From<f32> for Angle {
+    fn from(radians: f32) -> Self {
+        Self::from_radians(radians)
+    }
+}
+
+/// Radians, as [`Angle::radians`].
+impl From<Angle> for f32 {
+    fn from(angle: Angle) -> Self {
+        angle.radians()
+    }
+}
+
+impl Add for Angle {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Angle {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Neg for Angle {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Angle {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Angle {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn wraps_exactly_at_the_seam() {
+        let just_short = Angle(u32::MAX);
+        assert_eq!(just_short + Angle(1), Angle::ZERO);
+        assert_eq!(Angle::ZERO - Angle(1), just_short);
+        assert_eq!(Angle::ANG270 + Angle::ANG180, Angle::ANG90);
+        assert_eq!(-Angle::ANG90, Angle::ANG270);
+
+        // a negative radian lands just below the seam, not at 0
+        assert_eq!(Angle::from_radians(-FRAC_PI_2), Angle::ANG270);
+        assert_eq!(Angle::from_degrees(-45.0), Angle::ANG270 + Angle::ANG45);
+        assert_eq!(Angle::from_degrees(360.0), Angle::ZERO);
+        assert_eq!(Angle::from_degrees(720.0 + 90.0), Angle::ANG90);
+        assert!(just_short.radians() <= std::f32::consts::TAU);
+        assert!(just_short.signed_radians() < 0.0);
+        assert_eq!(Angle::ANG180.signed_radians(), -PI);
+
+        // a turn's worth of small turns comes back to where it started
+        let step = Angle(1 << 22);
+        let mut a = Angle::ANG90;
+        for _ in 0..1024 {
+            a += step;
+        }
+        assert_eq!(a, Angle::ANG90);
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        for deg in [0.0, 45.0, 90.0, 135.0, 180.0, 270.0, 315.0] {
+            let a = Angle::from_degrees(deg);
+            assert_eq!(a.degrees(), deg);
+            // f32 radians hold 24 bits of the 32
+            let back = Angle::from_radians(a.radians()) - a;
+            assert!(back.0.wrapping_add(1 << 8) < 1 << 9, "{deg}: {back:?}");
+        }
+        assert_eq!(Angle::from_radians(PI), Angle::ANG180);
+        assert_eq!(Angle::ANG90.radians(), FRAC_PI_2);
+        let (s, c) = Angle::ANG90.sin_cos();
+        assert!(c.abs() < 1e-6 && (s - 1.0).abs() < 1e-6);
+        assert_eq!(angle_to(Vec2::ZERO, Vec2::new(0.0, -3.0)), Angle::ANG270);
+        assert_eq!(angle_to(Vec2::ONE, Vec2::new(-4.0, 1.0)), Angle::ANG180);
+    }
+}
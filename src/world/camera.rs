@@ -1,20 +1,38 @@
 use glam::{Vec2, Vec3, vec2};
 
+use super::Angle;
+
 /// Player view-point in world space.
 ///
 /// * Only **yaw** (heading) is simulated – Doom never tilts up/down.
 /// * `z` holds eye height above floor, not absolute altitude.
 #[derive(Clone, Copy, Debug)]
 pub struct Camera {
-    pub pos: Vec3, // x,y in map-units; z = eye height above floor
-    pub yaw: f32,  // radians (0 = east, counter-clockwise)
-    pub fov: f32,  // horizontal FoV (radians, typical 90–110°)
+    pub pos: Vec3,  // x,y in map-units; z = eye height above floor
+    pub yaw: Angle, // 0 = east, counter-clockwise
+    pub fov: f32,   // horizontal FoV (radians, typical 90–110°)
+    near: f32,
 }
 
 impl Camera {
+    /// Near-plane distance [`Camera::new`] gives a camera.
+    pub const NEAR: f32 = 1.0;
+
     /// Create a new camera at `pos`, facing `yaw`, with horizontal FoV `fov`.
-    pub fn new(pos: Vec3, yaw: f32, fov: f32) -> Self {
-        Self { pos, yaw, fov }
+    /// `yaw` is an [`Angle`] or `f32` radians.
+    pub fn new(pos: Vec3, yaw: impl Into<Angle>, fov: f32) -> Self {
+        Self::with_near(pos, yaw, fov, Self::NEAR)
+    }
+
+    /// As [`Camera::new`], clipping walls and sprites `near` units out.
+    pub fn with_near(pos: Vec3, yaw: impl Into<Angle>, fov: f32, near: f32) -> Self {
+        debug_assert!(near > 0.0, "near plane at {near}");
+        Self {
+            pos,
+            yaw: yaw.into(),
+            fov,
+            near,
+        }
     }
 
     /// Transform an X–Y point `p` into camera‐local coords:
@@ -38,8 +56,7 @@ impl Camera {
     /// Unit vector pointing where the camera looks on the X-Y plane.
     #[inline(always)]
     pub fn forward(self) -> Vec2 {
-        let (s, c) = self.yaw.sin_cos();
-        Vec2::new(c, s) // 0 rad = +X (east), CCW positive
+        self.yaw.unit() // 0 = +X (east), CCW positive
     }
 
     /// Unit vector pointing to the camera's right on the X-Y plane.
@@ -60,8 +77,8 @@ impl Camera {
     }
 
     /// Rotate around Z-axis (positive = turn left).
-    pub fn turn(&mut self, delta_yaw: f32) {
-        self.yaw = (self.yaw + delta_yaw).rem_euclid(std::f32::consts::TAU);
+    pub fn turn(&mut self, delta_yaw: impl Into<Angle>) {
+        self.yaw += delta_yaw.into();
     }
 
     /*───────────────── projection / frustum helpers ─────────────────*/
@@ -76,10 +93,52 @@ impl Camera {
         (w as f32) * 0.5 / (self.fov * 0.5).tan()
     }
 
-    /// Near-plane distance (fixed small constant in classic Doom).
+    /// Near-plane distance; walls and sprites are both cut off here.
     #[inline(always)]
     pub fn near(self) -> f32 {
-        1.0
+        self.near
+    }
+}
+
+/// Where the view is taken from.  The renderer only ever sees the
+/// resulting `Camera`; the mode decides how that gets placed each frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CameraMode {
+    /// From the player's eyes.
+    #[default]
+    FirstPerson,
+    /// `distance` units behind the player and `height` above the eyes,
+    /// pulled in short of any wall in between.
+    Chase { distance: f32, height: f32 },
+    /// Detached from the player and flown about on its own, through
+    /// walls.
+    Free,
+}
+
+impl CameraMode {
+    /// A chase view that keeps the player's whole sprite in frame.
+    pub const CHASE: Self = Self::Chase {
+        distance: 96.0,
+        height: 16.0,
+    };
+
+    /// First person → chase → free → first person.
+    pub fn next(self) -> Self {
+        match self {
+            Self::FirstPerson => Self::CHASE,
+            Self::Chase { .. } => Self::Free,
+            Self::Free => Self::FirstPerson,
+        }
+    }
+}
+
+impl std::fmt::Display for CameraMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::FirstPerson => "first person",
+            Self::Chase { .. } => "chase",
+            Self::Free => "free",
+        })
     }
 }
 
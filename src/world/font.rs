@@ -0,0 +1,76 @@
+//! Bitmap fonts: a patch per character, kept in the [`TextureBank`] with
+//! the rest of the graphics so any renderer can draw text from them.
+
+use super::{Texture, TextureBank, TextureId};
+
+/// Advance for a space, or a character the font has no patch for
+/// (vanilla `HU_FONT`'s 4).
+pub const FONT_SPACE_W: usize = 4;
+
+/// Patches for the characters from `first` on.  Doom's fonts have no
+/// lower case, so it is drawn as upper case.
+#[derive(Clone, Debug, Default)]
+pub struct Font {
+    first: u8,
+    glyphs: Vec<Option<TextureId>>,
+}
+
+impl Font {
+    /// `glyphs[i]` is the patch for character `first + i`, if there is one.
+    pub fn new(first: u8, glyphs: Vec<Option<TextureId>>) -> Self {
+        Self { first, glyphs }
+    }
+}
+
+/// A [`Font`] at a size, with the bank its glyphs are in: what
+/// `Renderer::draw_text` draws with.
+#[derive(Clone, Copy)]
+pub struct FontRef<'a> {
+    font: &'a Font,
+    bank: &'a TextureBank,
+    /// Every font texel is `scale`×`scale` pixels.
+    pub scale: usize,
+}
+
+impl<'a> FontRef<'a> {
+    pub(super) fn new(font: &'a Font, bank: &'a TextureBank) -> Self {
+        Self {
+            font,
+            bank,
+            scale: 1,
+        }
+    }
+
+    /// The same font, each texel `scale`×`scale` pixels.
+    pub fn scaled(self, scale: usize) -> Self {
+        Self {
+            scale: scale.max(1),
+            ..self
+        }
+    }
+
+    pub fn bank(&self) -> &'a TextureBank {
+        self.bank
+    }
+
+    /// The patch for `c`, if the font has one.
+    pub fn glyph(&self, c: char) -> Option<&'a Texture> {
+        let c = c.to_ascii_uppercase() as u32;
+        let i = c.checked_sub(self.font.first as u32)? as usize;
+        let id = (*self.font.glyphs.get(i)?)?;
+        self.bank.texture(id).ok()
+    }
+
+    /// Height of one text row, in pixels.
+    pub fn line_height(&self) -> usize {
+        (self.glyph('A').map_or(7, |g| g.h) + 1) * self.scale
+    }
+
+    /// Width of `text`, in pixels.
+    pub fn text_width(&self, text: &str) -> usize {
+        let w: usize = (text.chars())
+            .map(|c| self.glyph(c).map_or(FONT_SPACE_W, |g| g.w))
+            .sum();
+        w * self.scale
+    }
+}
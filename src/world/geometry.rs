@@ -1,12 +1,13 @@
 use bitflags::bitflags;
 use glam::Vec2;
 
+use crate::world::Angle;
 use crate::world::texture::TextureId;
 
-pub type SubsectorId = u16;
+pub type SubsectorId = u32;
 pub type LinedefId = u16;
-pub type SegmentId = u16;
-pub type VertexId = u16;
+pub type SegmentId = u32;
+pub type VertexId = u32;
 pub type SidedefId = u16;
 pub type SectorId = u16;
 pub type ThingId = u16;
@@ -24,6 +25,15 @@ pub struct Level {
     pub nodes: Vec<Node>,
     pub sectors: Vec<Sector>,
     pub blockmap: Blockmap,
+    /// REJECT lump; `None` when the map ships an empty one.
+    pub reject: Option<BitMatrix>,
+    /// Sky drawn for `F_SKY1` ceilings (`SKY1`‥`SKY4`, by episode or map).
+    pub sky_texture: TextureId,
+    /// Which sectors border which; built by `finalise_bsp`.
+    pub sector_graph: SectorGraph,
+    /// Each sector's lines and subsectors, and sectors by tag; built by
+    /// `finalise_bsp`.
+    pub sector_index: SectorIndex,
 }
 
 /*------------------------- game objects -----------------------------*/
@@ -31,11 +41,11 @@ pub struct Level {
 #[derive(Clone, Debug)]
 pub struct Thing {
     pub pos: Vec2,
-    pub angle: f32,        // radians
+    pub angle: Angle,
     pub type_id: u16,      // mobjtype_t index
-    pub min_skill: u8,     // 1 easy, 2 medium, 3 hard
-    pub is_deaf: bool,     // MF_AMBUSH
-    pub multiplayer: bool, // NOTSINGLE player flag
+    pub skills: u8,        // MTF_EASY 1 | MTF_NORMAL 2 | MTF_HARD 4
+    pub is_deaf: bool,     // MTF_AMBUSH (0x0008) → MF_AMBUSH
+    pub multiplayer: bool, // 0x0010: not in single player
 
     pub sub_sector: SubsectorId,
 }
@@ -96,11 +106,17 @@ pub struct Segment {
     pub linedef: LinedefId,
     pub dir: u16,
     pub offset: f32,
+    /// `v1` to `v2`, measured by [`Level::measure_segs`] so the renderer
+    /// doesn't take a square root per seg per frame.
+    pub length: f32,
+    /// Direction from `v1` to `v2` (vanilla `seg->angle`), measured with
+    /// `length`.
+    pub angle: Angle,
 }
 
 #[derive(Clone, Debug)]
 pub struct Subsector {
-    pub num_lines: u16,
+    pub num_lines: u32,
     pub first_line: SegmentId,
 
     pub sector: SectorId,
@@ -120,7 +136,8 @@ pub struct Node {
     pub dx: f32,
     pub dy: f32,
     pub bbox: [Aabb; 2],
-    pub child: [u16; 2],
+    /// Node index, or subsector index | `SUBSECTOR_BIT`.
+    pub child: [u32; 2],
 }
 
 #[derive(Clone, Debug)]
@@ -132,6 +149,107 @@ pub struct Sector {
     pub light: f32,
     pub special: i16,
     pub tag: i16,
+    /// The ceiling is `F_SKY1`: open sky, drawn with `Level::sky_texture`.
+    pub is_sky_ceiling: bool,
+    /// The floor is `F_SKY1`.
+    pub is_sky_floor: bool,
+}
+
+/// Sector adjacency through two-sided linedefs, for sound to flood
+/// along (vanilla walks `sector_t::lines`).
+#[derive(Clone, Debug, Default)]
+pub struct SectorGraph {
+    /// Per sector, the sector across each of its two-sided lines.
+    pub(super) neighbors: Vec<Vec<SectorId>>,
+    /// Per sector, those lines, in the same order.
+    pub(super) portals: Vec<Vec<LinedefId>>,
+}
+
+/// What belongs to each sector, for the lookups that would otherwise
+/// scan the whole map: `Level::linedefs_of_sector`,
+/// `Level::subsectors_of_sector` and `Level::sectors_with_tag`.
+#[derive(Clone, Debug, Default)]
+pub struct SectorIndex {
+    /// Per sector, every line with a side in it, once each (vanilla
+    /// `sector->lines`).
+    pub(super) lines: Vec<Vec<LinedefId>>,
+    /// Per sector, its subsectors.
+    pub(super) subsectors: Vec<Vec<SubsectorId>>,
+    /// `(tag, sector)` for every sector, by tag then sector.
+    pub(super) by_tag: Vec<(u16, SectorId)>,
+}
+
+/// Sector × sector bit matrix from the REJECT lump.
+/// A set bit means "sector *a* can never see sector *b*".
+#[derive(Debug, Clone)]
+pub struct BitMatrix {
+    size: usize,
+    bits: Vec<u8>,
+}
+
+impl BitMatrix {
+    /// Wrap a raw lump for `sectors` sectors.  Short lumps are padded with
+    /// zeros (= "may see"), long ones truncated.
+    pub fn from_lump(bytes: &[u8], sectors: usize) -> Self {
+        let len = (sectors * sectors).div_ceil(8);
+        let mut bits = bytes[..bytes.len().min(len)].to_vec();
+        bits.resize(len, 0);
+        Self {
+            size: sectors,
+            bits,
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, a: SectorId, b: SectorId) -> bool {
+        let i = a as usize * self.size + b as usize;
+        self.bits
+            .get(i >> 3)
+            .is_some_and(|byte| byte & (1 << (i & 7)) != 0)
+    }
+}
+
+/// One bit per linedef: the lines the player has seen, which the automap
+/// shows (vanilla `ML_MAPPED`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineSet(Vec<u64>);
+
+impl LineSet {
+    /// No lines yet, room for `lines`.
+    pub fn new(lines: usize) -> Self {
+        Self(vec![0; lines.div_ceil(64)])
+    }
+
+    /// Rebuild a set from [`LineSet::words`].
+    pub fn from_words(words: Vec<u64>) -> Self {
+        Self(words)
+    }
+
+    #[inline]
+    pub fn insert(&mut self, line: LinedefId) {
+        let word = line as usize >> 6;
+        if word >= self.0.len() {
+            self.0.resize(word + 1, 0);
+        }
+        self.0[word] |= 1 << (line & 63);
+    }
+
+    #[inline]
+    pub fn contains(&self, line: LinedefId) -> bool {
+        self.0
+            .get(line as usize >> 6)
+            .is_some_and(|word| word & (1 << (line & 63)) != 0)
+    }
+
+    /// How many lines are in the set.
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The bits, line 0 in the low bit of the first word.
+    pub fn words(&self) -> &[u64] {
+        &self.0
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,15 +1,89 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use glam::Vec2;
+use smallvec::SmallVec;
 
 use super::Camera;
-use super::{Aabb, Level, Node, SubsectorId};
-
-pub const CHILD_MASK: u16 = 0x7FFF;
-
-pub const SUBSECTOR_BIT: u16 = 0x8000;
+use super::{
+    Aabb, Blockmap, Level, Linedef, LinedefFlags, LinedefId, Node, SectorGraph, SectorId,
+    SectorIndex, Sidedef, Subsector, SubsectorId, Vertex, angle_to,
+};
+
+pub const CHILD_MASK: u32 = 0x7FFF_FFFF;
+
+pub const SUBSECTOR_BIT: u32 = 0x8000_0000;
+
+/// Pending children of a BSP walk, with their depth; deep enough for any
+/// sane node tree without touching the heap.
+pub(super) type BspStack = SmallVec<[(u32, usize); 64]>;
+
+/// Say once that a BSP walk gave up: it went deeper than there are nodes,
+/// which only a loop in the tree can make it do.  `Level::repair` breaks
+/// such loops on load, so this is for levels built some other way.
+pub(super) fn warn_bsp_loop(walk: &str) {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    if !WARNED.swap(true, Ordering::Relaxed) {
+        eprintln!("warning: {walk}: the BSP loops back on itself; gave up");
+    }
+}
 
 /// size of one grid cell in world units
 const MAPBLOCKSHIFT: i32 = 7; // 2^7 = 128
-const MAPBLOCKSIZE: f32 = (1 << MAPBLOCKSHIFT) as f32;
+pub const MAPBLOCKSIZE: f32 = (1 << MAPBLOCKSHIFT) as f32;
+
+/// Scratch for blockmap walks – vanilla `validcount`.  A line that sits
+/// in several cells is only visited once per walk: each walk takes a
+/// fresh count and stamps the lines it visits with it, so nothing needs
+/// clearing in between.  Keep one around and pass it to every walk; the
+/// stamps are allocated on the first.
+#[derive(Debug, Default)]
+pub struct BlockmapQuery {
+    /// Stamp sets not in use, each with the last count it stamped with.
+    /// A walk takes the top one and puts it back when done, so a walk
+    /// started inside another gets a set (and a count) of its own.
+    free: RefCell<Vec<(Vec<u32>, u32)>>,
+}
+
+/// One walk's view of a [`BlockmapQuery`].
+pub struct LineStamps<'a> {
+    stamps: &'a mut [u32],
+    validcount: u32,
+}
+
+impl LineStamps<'_> {
+    /// Whether this walk is seeing `line` for the first time.
+    #[inline]
+    pub fn first_visit(&mut self, line: LinedefId) -> bool {
+        let stamp = &mut self.stamps[line as usize];
+        if *stamp == self.validcount {
+            return false;
+        }
+        *stamp = self.validcount;
+        true
+    }
+}
+
+impl BlockmapQuery {
+    /// Run one walk over a level with `lines` linedefs.  A walk started
+    /// from inside another gets stamps of its own.
+    pub fn walk<R>(&self, lines: usize, f: impl FnOnce(&mut LineStamps) -> R) -> R {
+        let (mut stamps, validcount) = self.free.borrow_mut().pop().unwrap_or_default();
+        let mut validcount = validcount.wrapping_add(1);
+        if validcount == 0 || stamps.len() < lines {
+            // wrapped around, or a bigger level: start the stamps over
+            stamps.clear();
+            stamps.resize(lines, 0);
+            validcount = 1;
+        }
+        let out = f(&mut LineStamps {
+            stamps: &mut stamps,
+            validcount,
+        });
+        self.free.borrow_mut().push((stamps, validcount));
+        out
+    }
+}
 
 // ──────────────────────────────────────────────────────────────────────────
 //                       Level – public helpers
@@ -17,15 +91,16 @@ const MAPBLOCKSIZE: f32 = (1 << MAPBLOCKSHIFT) as f32;
 impl Level {
     /// Index of the BSP root (`nodes.len()-1` in Doom).
     #[inline(always)]
-    pub fn bsp_root(&self) -> u16 {
+    pub fn bsp_root(&self) -> u32 {
         assert!(!self.nodes.is_empty());
-        (self.nodes.len() - 1) as u16
+        (self.nodes.len() - 1) as u32
     }
 
-    /// Walk the BSP and return the subsector id containing `p`.
-    pub fn locate_subsector(&self, p: Vec2) -> u16 {
+    /// Walk the BSP and return the subsector id containing `p`; subsector
+    /// 0 if the walk takes more steps than there are nodes.
+    pub fn locate_subsector(&self, p: Vec2) -> SubsectorId {
         let mut idx = self.bsp_root();
-        loop {
+        for _ in 0..self.nodes.len() {
             let node = &self.nodes[idx as usize];
             let child = node.child[node.point_side(p) as usize];
             if child & SUBSECTOR_BIT != 0 {
@@ -33,11 +108,157 @@ impl Level {
             }
             idx = child;
         }
+        warn_bsp_loop("locate_subsector");
+        0
+    }
+
+    /// Sector a subsector belongs to (valid after `finalise_bsp`).
+    #[inline]
+    pub fn sector_of_subsector(&self, ss: SubsectorId) -> SectorId {
+        self.subsectors[ss as usize].sector
+    }
+
+    /// Floor height of the sector `pos` is in, found through the BSP.
+    #[inline]
+    pub fn floor_height_at(&self, pos: Vec2) -> f32 {
+        let sector = self.sector_of_subsector(self.locate_subsector(pos));
+        self.sectors[sector as usize].floor_h
+    }
+
+    /// Sectors across the two-sided lines of `sector`, once per line.
+    #[inline]
+    pub fn sector_neighbors(&self, sector: SectorId) -> &[SectorId] {
+        self.sector_graph
+            .neighbors
+            .get(sector as usize)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The two-sided lines of `sector`, in `sector_neighbors` order.
+    #[inline]
+    pub fn sector_portals(&self, sector: SectorId) -> &[LinedefId] {
+        self.sector_graph
+            .portals
+            .get(sector as usize)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Darkest light of any sector sharing a two-sided line with `sector`,
+    /// or its own light if none is darker (vanilla
+    /// `P_FindMinSurroundingLight`).
+    pub fn min_neighbor_light(&self, sector: SectorId) -> f32 {
+        let mut min = self.sectors[sector as usize].light;
+        for line in &self.linedefs {
+            let (Some(r), Some(l)) = (line.right_sidedef, line.left_sidedef) else {
+                continue;
+            };
+            let front = self.sidedefs[r as usize].sector;
+            let back = self.sidedefs[l as usize].sector;
+            let other = if front == sector {
+                back
+            } else if back == sector {
+                front
+            } else {
+                continue;
+            };
+            min = min.min(self.sectors[other as usize].light);
+        }
+        min
+    }
+
+    /// Lowest floor of any neighbouring sector, or `sector`'s own floor
+    /// if none is lower (vanilla `P_FindLowestFloorSurrounding`).
+    pub fn lowest_neighbor_floor(&self, sector: SectorId) -> f32 {
+        self.sector_neighbors(sector)
+            .iter()
+            .map(|&s| self.sectors[s as usize].floor_h)
+            .fold(self.sectors[sector as usize].floor_h, f32::min)
+    }
+
+    /// Highest floor of any neighbouring sector, -500 if there are none
+    /// (vanilla `P_FindHighestFloorSurrounding`).
+    pub fn highest_neighbor_floor(&self, sector: SectorId) -> f32 {
+        self.sector_neighbors(sector)
+            .iter()
+            .map(|&s| self.sectors[s as usize].floor_h)
+            .fold(-500.0, f32::max)
+    }
+
+    /// Lowest ceiling of any neighbouring sector, `f32::MAX` if there are
+    /// none (vanilla `P_FindLowestCeilingSurrounding`).
+    pub fn lowest_neighbor_ceiling(&self, sector: SectorId) -> f32 {
+        self.sector_neighbors(sector)
+            .iter()
+            .map(|&s| self.sectors[s as usize].ceil_h)
+            .fold(f32::MAX, f32::min)
+    }
+
+    /// Lowest neighbouring floor above `height`, or `height` itself if
+    /// none is higher (vanilla `P_FindNextHighestFloor`).
+    pub fn next_higher_floor(&self, sector: SectorId, height: f32) -> f32 {
+        self.sector_neighbors(sector)
+            .iter()
+            .map(|&s| self.sectors[s as usize].floor_h)
+            .filter(|&h| h > height)
+            .reduce(f32::min)
+            .unwrap_or(height)
+    }
+
+    /// Where `sector`'s sounds come from (vanilla `sector->soundorg`).
+    pub fn sound_origin(&self, sector: SectorId) -> Vec2 {
+        let bbox = self.sector_bbox(sector);
+        (bbox.min + bbox.max) * 0.5
+    }
+
+    /// Bounding box of every line bordering `sector` (vanilla
+    /// `sector->blockbox` before the `MAXRADIUS` padding).  Its centre is
+    /// the sector's [`sound_origin`](Self::sound_origin).
+    pub fn sector_bbox(&self, sector: SectorId) -> Aabb {
+        let mut bbox = Aabb {
+            min: Vec2::splat(f32::MAX),
+            max: Vec2::splat(f32::MIN),
+        };
+        for line in &self.linedefs {
+            let borders = [line.right_sidedef, line.left_sidedef]
+                .into_iter()
+                .flatten()
+                .any(|sd| self.sidedefs[sd as usize].sector == sector);
+            if borders {
+                bbox.min = bbox.min.min(line.bbox.min);
+                bbox.max = bbox.max.max(line.bbox.max);
+            }
+        }
+        bbox
+    }
+
+    /// Work out every seg's [`length`](super::Segment::length) and
+    /// [`angle`](super::Segment::angle) from its vertices.  Segs with a
+    /// vertex that isn't there (for `repair` to deal with) are left alone.
+    pub fn measure_segs(&mut self) {
+        for seg in &mut self.segs {
+            if let (Some(a), Some(b)) = (
+                self.vertices.get(seg.v1 as usize),
+                self.vertices.get(seg.v2 as usize),
+            ) {
+                seg.length = (b.pos - a.pos).length();
+                seg.angle = angle_to(a.pos, b.pos);
+            }
+        }
     }
 
     pub fn finalise_bsp(&mut self) {
+        self.measure_segs();
+        self.sector_graph = SectorGraph::build(&self.linedefs, &self.sidedefs, self.sectors.len());
+
         for ss in self.subsectors.iter_mut() {
-            let seg = &self.segs[ss.first_line as usize];
+            // a subsector without segs keeps what `repair` gave it
+            let Some(seg) = self
+                .segs
+                .get(ss.first_line as usize)
+                .filter(|_| ss.num_lines > 0)
+            else {
+                continue;
+            };
             let ld = &self.linedefs[seg.linedef as usize];
             let side = if seg.dir == 0 {
                 ld.right_sidedef
@@ -50,7 +271,7 @@ impl Level {
                 .unwrap_or(u16::MAX);
         }
 
-        let ss_for_thing: Vec<u16> = self
+        let ss_for_thing: Vec<SubsectorId> = self
             .things
             .iter()
             .map(|t| self.locate_subsector(t.pos))
@@ -65,6 +286,18 @@ impl Level {
                 .things
                 .push(thing_idx as u16);
         }
+        self.index_sectors();
+    }
+
+    /// (Re)build [`sector_index`](Self::sector_index) from the lines,
+    /// subsectors and sector tags as they stand.
+    pub fn index_sectors(&mut self) {
+        self.sector_index = SectorIndex::build(
+            &self.linedefs,
+            &self.sidedefs,
+            &self.subsectors,
+            &self.sectors,
+        );
     }
 
     pub fn fill_active_subsectors(&self, camera: &Camera, subsectors: &mut Vec<SubsectorId>) {
@@ -73,25 +306,33 @@ impl Level {
         self.walk_bsp(self.bsp_root(), camera, subsectors);
     }
 
-    fn walk_bsp(&self, child: u16, camera: &Camera, subsectors: &mut Vec<SubsectorId>) {
-        if child & SUBSECTOR_BIT != 0 {
-            subsectors.push(child & CHILD_MASK);
-            return;
-        }
-
-        // Internal node ──────
-        let node = &self.nodes[child as usize];
-        let front = node.point_side(camera.pos.truncate()) as usize; // 0: front, 1: back
-        let near = node.child[front];
-        let back = node.child[front ^ 1];
-        let back_visible = node.bbox[front ^ 1].bbox_in_fov(camera);
+    /// Front-to-back subsectors under `root`, on an explicit stack no
+    /// deeper than the node count.
+    fn walk_bsp(&self, root: u32, camera: &Camera, subsectors: &mut Vec<SubsectorId>) {
+        let mut stack = BspStack::new();
+        stack.push((root, 0));
+        while let Some((child, depth)) = stack.pop() {
+            if child & SUBSECTOR_BIT != 0 {
+                subsectors.push(child & CHILD_MASK);
+                continue;
+            }
+            if depth > self.nodes.len() {
+                warn_bsp_loop("walk_bsp");
+                return;
+            }
 
-        // Near side first …
-        self.walk_bsp(near, camera, subsectors);
+            // Internal node ──────
+            let node = &self.nodes[child as usize];
+            let front = node.point_side(camera.pos.truncate()) as usize; // 0: front, 1: back
+            let near = node.child[front];
+            let back = node.child[front ^ 1];
 
-        // … far side only if its bounding box might be visible.
-        if back_visible {
-            self.walk_bsp(back, camera, subsectors);
+            // Far side only if its bounding box might be visible …
+            if node.bbox[front ^ 1].bbox_in_fov(camera) {
+                stack.push((back, depth + 1));
+            }
+            // … after the near side.
+            stack.push((near, depth + 1));
         }
     }
 
@@ -101,42 +342,330 @@ impl Level {
         ((x - origin) / MAPBLOCKSIZE).floor() as i32
     }
 
+    /// Linedefs registered in blockmap cell (`bx`, `by`); none outside the
+    /// grid, like vanilla `P_BlockLinesIterator`.
+    pub fn block_lines(&self, bx: i32, by: i32) -> &[LinedefId] {
+        let bm = &self.blockmap;
+        if bx < 0 || by < 0 || bx >= bm.width || by >= bm.height {
+            return &[];
+        }
+        &bm.lines[(by * bm.width + bx) as usize]
+    }
+
     /// vanilla-style iterator over *unique* linedefs that the axis-aligned
-    /// bounding box touches.  Stops early if func returns false.
-    pub fn block_lines_iter<F>(&self, bbox: Aabb, mut func: F) -> bool
+    /// bounding box touches, marking them in `query`.  Stops early if func
+    /// returns false.
+    pub fn block_lines_iter<F>(&self, query: &BlockmapQuery, bbox: Aabb, mut func: F) -> bool
     where
         F: FnMut(&crate::world::geometry::Linedef) -> bool,
     {
         let bm = &self.blockmap;
         assert!(bm.width > 0 && bm.height > 0);
 
-        let mut visited = vec![false; self.linedefs.len()];
-
         let bx1 = Self::world_to_block(bbox.min.x, bm.origin.x).clamp(0, bm.width - 1);
         let by1 = Self::world_to_block(bbox.min.y, bm.origin.y).clamp(0, bm.height - 1);
         let bx2 = Self::world_to_block(bbox.max.x, bm.origin.x).clamp(0, bm.width - 1);
         let by2 = Self::world_to_block(bbox.max.y, bm.origin.y).clamp(0, bm.height - 1);
 
-        for by in by1..=by2 {
-            for bx in bx1..=bx2 {
-                let cell = (by * bm.width + bx) as usize;
-                for &li in &bm.lines[cell] {
-                    let idx = li as usize;
-                    if visited[idx] {
+        query.walk(self.linedefs.len(), |stamps| {
+            for by in by1..=by2 {
+                for bx in bx1..=bx2 {
+                    let cell = (by * bm.width + bx) as usize;
+                    for &li in &bm.lines[cell] {
+                        if !stamps.first_visit(li) {
+                            continue;
+                        }
+
+                        let line = &self.linedefs[li as usize];
+
+                        if !func(line) {
+                            return false;
+                        }
+                    }
+                }
+            }
+            true
+        })
+    }
+}
+
+// ──────────────────────────────────────────────────────────────────────────
+//                       Level – geometry queries
+// ──────────────────────────────────────────────────────────────────────────
+impl Level {
+    /// The sector `pos` is in, found through the BSP.  Points outside the
+    /// map land in whichever sector the BSP puts them.
+    ///
+    /// ```no_run
+    /// # use yadoom_rs::{wad::{Wad, load_level}, world::TextureBank};
+    /// let wad = Wad::from_file("doom.wad")?;
+    /// let mut bank = TextureBank::default_with_checker();
+    /// let mut level = load_level(&wad, wad.level_indices()[0], &mut bank)?;
+    /// level.finalise_bsp();
+    /// let sector = level.sector_at(glam::Vec2::new(1056.0, -3616.0));
+    /// println!("floor at {}", level.sectors[sector as usize].floor_h);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[inline]
+    pub fn sector_at(&self, pos: Vec2) -> SectorId {
+        self.sector_of_subsector(self.locate_subsector(pos))
+    }
+
+    /// The nearest point within `max_dist` of `pos` on a line that blocks
+    /// walking (one-sided or impassable), as `(line, distance, point)`.
+    /// Only the blockmap cells in reach are searched; two-sided lines
+    /// that only block by their heights don't count.
+    ///
+    /// ```no_run
+    /// # use glam::Vec2;
+    /// # fn f(level: &yadoom_rs::world::Level) {
+    /// if let Some((line, dist, at)) = level.nearest_wall(Vec2::new(1056.0, -3616.0), 64.0) {
+    ///     println!("line {line} is {dist:.1} away, at {at}");
+    /// }
+    /// # }
+    /// ```
+    pub fn nearest_wall(&self, pos: Vec2, max_dist: f32) -> Option<(LinedefId, f32, Vec2)> {
+        let bm = &self.blockmap;
+        if bm.width <= 0 || bm.height <= 0 {
+            return None;
+        }
+        let cell = |v: f32, origin: f32, n: i32| Self::world_to_block(v, origin).clamp(0, n - 1);
+        let (lo, hi) = (pos - max_dist, pos + max_dist);
+        let mut best: Option<(LinedefId, f32, Vec2)> = None;
+        for by in cell(lo.y, bm.origin.y, bm.height)..=cell(hi.y, bm.origin.y, bm.height) {
+            for bx in cell(lo.x, bm.origin.x, bm.width)..=cell(hi.x, bm.origin.x, bm.width) {
+                // a line in several cells is measured again; it can't win twice
+                for &li in self.block_lines(bx, by) {
+                    let line = &self.linedefs[li as usize];
+                    let blocks = line.left_sidedef.is_none()
+                        || line.flags.contains(LinedefFlags::IMPASSABLE);
+                    if !blocks {
                         continue;
                     }
-                    visited[idx] = true;
+                    let (a, b) = (
+                        self.vertices[line.v1 as usize].pos,
+                        self.vertices[line.v2 as usize].pos,
+                    );
+                    let d = b - a;
+                    let len2 = d.length_squared();
+                    let t = if len2 > 0.0 {
+                        ((pos - a).dot(d) / len2).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    let at = a + d * t;
+                    let dist = pos.distance(at);
+                    if dist <= max_dist && best.is_none_or(|(_, bd, _)| dist < bd) {
+                        best = Some((li, dist, at));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Sectors whose tag is `tag`, in index order; what a line special
+    /// with that tag acts on.
+    pub fn sectors_with_tag(&self, tag: u16) -> impl Iterator<Item = SectorId> + '_ {
+        let by_tag = &self.sector_index.by_tag;
+        let start = by_tag.partition_point(|&(t, _)| t < tag);
+        by_tag[start..]
+            .iter()
+            .take_while(move |&&(t, _)| t == tag)
+            .map(|&(_, sector)| sector)
+    }
+
+    /// Lines with a side in `sector`, each once.
+    pub fn linedefs_of_sector(&self, sector: SectorId) -> impl Iterator<Item = LinedefId> + '_ {
+        let lines = self.sector_index.lines.get(sector as usize);
+        lines.map_or(&[][..], Vec::as_slice).iter().copied()
+    }
 
-                    let line = &self.linedefs[idx];
+    /// The subsectors `sector` is made of.
+    pub fn subsectors_of_sector(&self, sector: SectorId) -> impl Iterator<Item = SubsectorId> + '_ {
+        let subsectors = self.sector_index.subsectors.get(sector as usize);
+        subsectors.map_or(&[][..], Vec::as_slice).iter().copied()
+    }
 
-                    if !func(line) {
-                        return false;
+    /// Bounding box of every vertex; an empty box at the origin for a
+    /// level without any.
+    pub fn bounds(&self) -> Aabb {
+        let mut points = self.vertices.iter().map(|v| v.pos);
+        let Some(first) = points.next() else {
+            return Aabb::default();
+        };
+        points.fold(
+            Aabb {
+                min: first,
+                max: first,
+            },
+            |b, p| Aabb {
+                min: b.min.min(p),
+                max: b.max.max(p),
+            },
+        )
+    }
+}
+
+// ──────────────────────────────────────────────────────────────────────────
+//                       Sector index
+// ──────────────────────────────────────────────────────────────────────────
+impl SectorIndex {
+    /// Sort every line, subsector and tag under its sector.  Sides and
+    /// subsectors pointing past the sectors are left out.
+    pub fn build(
+        linedefs: &[Linedef],
+        sidedefs: &[Sidedef],
+        subsectors: &[Subsector],
+        sectors: &[super::Sector],
+    ) -> Self {
+        let n = sectors.len();
+        let mut index = Self {
+            lines: vec![Vec::new(); n],
+            subsectors: vec![Vec::new(); n],
+            by_tag: (sectors.iter().enumerate())
+                .map(|(id, s)| (s.tag as u16, id as SectorId))
+                .collect(),
+        };
+        index.by_tag.sort_unstable();
+        for (id, line) in linedefs.iter().enumerate() {
+            let [front, back] = [line.right_sidedef, line.left_sidedef].map(|sd| {
+                sd.and_then(|sd| sidedefs.get(sd as usize))
+                    .map(|sd| sd.sector as usize)
+                    .filter(|&s| s < n)
+            });
+            // a line with both sides in one sector is listed once
+            let back = back.filter(|&b| Some(b) != front);
+            for s in [front, back].into_iter().flatten() {
+                index.lines[s].push(id as LinedefId);
+            }
+        }
+        for (id, ss) in subsectors.iter().enumerate() {
+            if let Some(list) = index.subsectors.get_mut(ss.sector as usize) {
+                list.push(id as SubsectorId);
+            }
+        }
+        index
+    }
+}
+
+// ──────────────────────────────────────────────────────────────────────────
+//                       Sector adjacency
+// ──────────────────────────────────────────────────────────────────────────
+impl SectorGraph {
+    /// Link the two sectors of every two-sided line.  Lines with the same
+    /// sector on both sides, or a side out of range, are left out.
+    pub fn build(linedefs: &[Linedef], sidedefs: &[Sidedef], sectors: usize) -> Self {
+        let mut graph = Self {
+            neighbors: vec![Vec::new(); sectors],
+            portals: vec![Vec::new(); sectors],
+        };
+        let sector = |sd: Option<u16>| {
+            sd.and_then(|sd| sidedefs.get(sd as usize))
+                .map(|sd| sd.sector)
+                .filter(|&s| (s as usize) < sectors)
+        };
+        for (id, line) in linedefs.iter().enumerate() {
+            let (Some(front), Some(back)) = (sector(line.right_sidedef), sector(line.left_sidedef))
+            else {
+                continue;
+            };
+            if front == back {
+                continue;
+            }
+            for (from, to) in [(front, back), (back, front)] {
+                graph.neighbors[from as usize].push(to);
+                graph.portals[from as usize].push(id as LinedefId);
+            }
+        }
+        graph
+    }
+}
+
+// ──────────────────────────────────────────────────────────────────────────
+//                       Blockmap construction
+// ──────────────────────────────────────────────────────────────────────────
+impl Blockmap {
+    /// Build the grid from scratch for maps whose BLOCKMAP lump is missing
+    /// or unusable.  Origin sits 8 units outside the level bounds, like the
+    /// original node builder.
+    pub fn rebuild(vertices: &[Vertex], linedefs: &[Linedef]) -> Blockmap {
+        let (mut min, mut max) = (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN));
+        for v in vertices {
+            min = min.min(v.pos);
+            max = max.max(v.pos);
+        }
+        if vertices.is_empty() {
+            (min, max) = (Vec2::ZERO, Vec2::ZERO);
+        }
+
+        let origin = (min - 8.0).floor();
+        let width = ((max.x - origin.x) / MAPBLOCKSIZE).floor() as i32 + 1;
+        let height = ((max.y - origin.y) / MAPBLOCKSIZE).floor() as i32 + 1;
+        Self::rebuild_at(origin, width, height, vertices, linedefs)
+    }
+
+    /// Rasterise every linedef into the cells of a `width × height` grid at
+    /// `origin`.  Conservative: a line lands in every cell whose square it
+    /// touches, edges and corners included.
+    pub fn rebuild_at(
+        origin: Vec2,
+        width: i32,
+        height: i32,
+        vertices: &[Vertex],
+        linedefs: &[Linedef],
+    ) -> Blockmap {
+        let mut lines = vec![Vec::new(); (width * height).max(0) as usize];
+
+        for (idx, ld) in linedefs.iter().enumerate() {
+            let a = vertices[ld.v1 as usize].pos;
+            let b = vertices[ld.v2 as usize].pos;
+
+            let bx1 = Level::world_to_block(a.x.min(b.x), origin.x).clamp(0, width - 1);
+            let bx2 = Level::world_to_block(a.x.max(b.x), origin.x).clamp(0, width - 1);
+            let by1 = Level::world_to_block(a.y.min(b.y), origin.y).clamp(0, height - 1);
+            let by2 = Level::world_to_block(a.y.max(b.y), origin.y).clamp(0, height - 1);
+
+            for by in by1..=by2 {
+                for bx in bx1..=bx2 {
+                    let cell_min = origin + Vec2::new(bx as f32, by as f32) * MAPBLOCKSIZE;
+                    let cell = Aabb {
+                        min: cell_min,
+                        max: cell_min + MAPBLOCKSIZE,
+                    };
+                    if segment_touches_box(a, b, &cell) {
+                        lines[(by * width + bx) as usize].push(idx as LinedefId);
                     }
                 }
             }
         }
-        true
+
+        Blockmap {
+            origin,
+            width,
+            height,
+            lines,
+        }
+    }
+}
+
+/// Does segment `a→b` touch the closed box?  Caller guarantees the
+/// segment's bbox overlaps; we only reject boxes wholly on one side.
+fn segment_touches_box(a: Vec2, b: Vec2, bx: &Aabb) -> bool {
+    let d = b - a;
+    let corners = [
+        bx.min,
+        Vec2::new(bx.max.x, bx.min.y),
+        Vec2::new(bx.min.x, bx.max.y),
+        bx.max,
+    ];
+    let (mut pos, mut neg) = (false, false);
+    for c in corners {
+        let cross = d.perp_dot(c - a);
+        pos |= cross >= 0.0;
+        neg |= cross <= 0.0;
     }
+    pos && neg
 }
 
 // ──────────────────────────────────────────────────────────────────────────
@@ -187,23 +716,17 @@ impl Aabb {
         }
 
         // 1. collect the four corner angles (wrapped to [-π, π])
-        let rel = [
-            Vec2::new(self.min.x - cam.pos.x, self.min.y - cam.pos.y),
-            Vec2::new(self.max.x - cam.pos.x, self.min.y - cam.pos.y),
-            Vec2::new(self.min.x - cam.pos.x, self.max.y - cam.pos.y),
-            Vec2::new(self.max.x - cam.pos.x, self.max.y - cam.pos.y),
+        let corners = [
+            self.min,
+            Vec2::new(self.max.x, self.min.y),
+            Vec2::new(self.min.x, self.max.y),
+            self.max,
         ];
 
         let mut left = PI;
         let mut right = -PI;
-        for v in &rel {
-            let mut a = v.y.atan2(v.x) - cam.yaw;
-            if a > PI {
-                a -= 2.0 * PI;
-            }
-            if a < -PI {
-                a += 2.0 * PI;
-            }
+        for &corner in &corners {
+            let a = (angle_to(cam.pos.truncate(), corner) - cam.yaw).signed_radians();
             left = left.min(a);
             right = right.max(a);
         }
@@ -225,9 +748,10 @@ impl Aabb {
 // ──────────────────────────────────────────────────────────────────────────
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::{
         wad::{Wad, load_level},
-        world::TextureBank,
+        world::{TextureBank, testmap},
     };
     use std::path::PathBuf;
 
@@ -237,6 +761,38 @@ mod tests {
             .join("doom.wad")
     }
 
+    #[test]
+    fn eye_height_holds_across_subsector_seams() {
+        use crate::game::VIEW_HEIGHT;
+        // a flat floor through all three rooms: the BSP seams at x=128
+        // and x=144 mustn't move the eye
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        for x in (1..272).map(|x| x as f32) {
+            for y in [1.0, 64.0, 127.0] {
+                let eye = level.floor_height_at(Vec2::new(x, y)) + VIEW_HEIGHT;
+                assert_eq!(eye, VIEW_HEIGHT, "at ({x}, {y})");
+            }
+        }
+
+        // a raised strip is picked up exactly over the strip
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 24.0,
+            ceil: 128.0,
+        });
+        // (off the lines themselves, which either side may claim)
+        for x in (1..271).map(|x| x as f32 + 0.5) {
+            let want = if (128.0..144.0).contains(&x) {
+                24.0
+            } else {
+                0.0
+            };
+            assert_eq!(level.floor_height_at(Vec2::new(x, 64.0)), want, "at x={x}");
+        }
+    }
+
     #[test]
     fn point_side_matches_bbox() {
         let wad = Wad::from_file(doom_wad()).unwrap();
@@ -250,4 +806,204 @@ mod tests {
             assert_eq!(root.point_side(mid), side as i32);
         }
     }
+
+    #[test]
+    fn sectors_neighbor_through_two_sided_lines() {
+        use crate::world::testmap::{Middle, three_rooms};
+        let open = three_rooms(Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        assert_eq!(open.sector_neighbors(1), [0, 2]);
+        assert_eq!(open.sector_neighbors(0), [1]);
+        let line = open.sector_portals(0)[0] as usize;
+        assert_eq!(open.sector_portals(1)[0] as usize, line);
+        assert!(three_rooms(Middle::Wall).sector_neighbors(1).is_empty());
+        assert!(open.sector_neighbors(9).is_empty());
+    }
+
+    #[test]
+    fn rebuild_rasterises_diagonal() {
+        use crate::world::{Aabb, Linedef, LinedefFlags, Vertex};
+        use glam::Vec2;
+
+        let vertices = [
+            Vertex {
+                pos: Vec2::new(8.0, 8.0),
+            },
+            Vertex {
+                pos: Vec2::new(300.0, 200.0),
+            },
+        ];
+        let line = Linedef {
+            id: 0,
+            v1: 0,
+            v2: 1,
+            flags: LinedefFlags::empty(),
+            special: 0,
+            tag: 0,
+            right_sidedef: Some(0),
+            left_sidedef: None,
+            bbox: Aabb::default(),
+        };
+        let bm = Blockmap::rebuild(&vertices, &[line]);
+        assert_eq!(bm.origin, Vec2::ZERO);
+        assert_eq!((bm.width, bm.height), (3, 2));
+
+        let cells: Vec<usize> = (0..bm.lines.len())
+            .filter(|&c| !bm.lines[c].is_empty())
+            .collect();
+        // (0,0) (1,0) (1,1) (2,1) – never the far corners (2,0) / (0,1)
+        assert_eq!(cells, vec![0, 1, 4, 5]);
+    }
+
+    /// Rebuilt grid must contain every line the lump lists, cell by cell,
+    /// and add only lines that genuinely touch the cell.
+    #[test]
+    fn rebuilt_blockmap_matches_lump() {
+        let wad = Wad::from_file(doom_wad()).unwrap();
+        let mut bank = TextureBank::default_with_checker();
+        let level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+        let lump = &level.blockmap;
+
+        let rebuilt = Blockmap::rebuild_at(
+            lump.origin,
+            lump.width,
+            lump.height,
+            &level.vertices,
+            &level.linedefs,
+        );
+
+        let mut extra = 0;
+        let mut total = 0;
+        for (cell, (want, got)) in lump.lines.iter().zip(&rebuilt.lines).enumerate() {
+            // node builders start every list with a dummy 0 entry
+            let want: Vec<_> = match want.split_first() {
+                Some((0, rest)) => rest.to_vec(),
+                _ => want.clone(),
+            };
+            for li in &want {
+                assert!(got.contains(li), "cell {cell}: line {li} missing");
+            }
+            extra += got.iter().filter(|li| !want.contains(li)).count();
+            total += want.len();
+        }
+        // conservative rasterisation may add lines grazing a cell edge
+        assert!(extra * 20 <= total, "{extra} extra entries of {total}");
+
+        // and the free-standing variant covers the whole map
+        let auto = Blockmap::rebuild(&level.vertices, &level.linedefs);
+        let in_grid = |bm: &Blockmap| {
+            bm.lines
+                .iter()
+                .flatten()
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+        };
+        assert_eq!(in_grid(&auto), level.linedefs.len());
+    }
+
+    #[test]
+    fn block_lines_iter_visits_each_line_once() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let query = BlockmapQuery::default();
+        let everything = Aabb {
+            min: Vec2::splat(-16.0),
+            max: Vec2::new(300.0, 150.0),
+        };
+        let count = |query: &BlockmapQuery| {
+            let mut n = 0;
+            level.block_lines_iter(query, everything, |_| {
+                n += 1;
+                true
+            });
+            n
+        };
+        // lines spanning two cells still come once
+        assert_eq!(count(&query), level.linedefs.len());
+
+        // a walk inside a walk doesn't upset the outer one
+        let mut outer = 0;
+        level.block_lines_iter(&query, everything, |_| {
+            outer += 1;
+            assert_eq!(count(&query), level.linedefs.len());
+            true
+        });
+        assert_eq!(outer, level.linedefs.len());
+    }
+
+    #[test]
+    fn walks_after_a_nested_walk_see_every_line() {
+        let seen = |s: &mut LineStamps| (0..4).filter(|&l| s.first_visit(l)).count();
+        for idle in 0..20 {
+            let query = BlockmapQuery::default();
+            for _ in 0..10 {
+                query.walk(4, |_| ());
+            }
+            let outer = query.walk(4, |s| {
+                query.walk(4, |_| ());
+                seen(s)
+            });
+            assert_eq!(outer, 4);
+            // the outer walk's stamps went back with its own count; a
+            // later walk counting up to it must not take them for its own
+            for _ in 0..idle {
+                query.walk(4, |_| ());
+            }
+            assert_eq!(query.walk(4, seen), 4, "{idle} walks after");
+        }
+    }
+
+    #[test]
+    fn geometry_queries_on_three_rooms() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        for (x, want) in [(64.0, 0), (136.0, 1), (200.0, 2)] {
+            assert_eq!(level.sector_at(Vec2::new(x, 64.0)), want, "at x={x}");
+        }
+        let bounds = level.bounds();
+        assert_eq!(
+            (bounds.min, bounds.max),
+            (Vec2::ZERO, Vec2::new(272.0, 128.0))
+        );
+
+        // the west wall of A (line 6) is 10 away
+        let (line, dist, at) = level.nearest_wall(Vec2::new(10.0, 64.0), 64.0).unwrap();
+        assert_eq!((line, dist, at), (6, 10.0, Vec2::new(0.0, 64.0)));
+        assert_eq!(level.nearest_wall(Vec2::new(10.0, 64.0), 9.0), None);
+        // the open separators 8 away don't count; the strip's own walls do
+        let (line, dist, _) = level.nearest_wall(Vec2::new(136.0, 64.0), 64.0).unwrap();
+        assert!(matches!(line, 2 | 3) && dist == 64.0, "{line} at {dist}");
+        assert_eq!(level.nearest_wall(Vec2::new(136.0, 64.0), 32.0), None);
+
+        let lines = |level: &Level, s| level.linedefs_of_sector(s).collect::<Vec<_>>();
+        assert_eq!(lines(&level, 0), [0, 1, 6, 8]);
+        assert_eq!(lines(&level, 1), [2, 3, 8, 9]);
+        assert_eq!(lines(&level, 2), [4, 5, 7, 9]);
+        for s in 0..3 {
+            let ss: Vec<_> = level.subsectors_of_sector(s).collect();
+            assert_eq!(ss, [s as SubsectorId]);
+        }
+        assert_eq!(level.linedefs_of_sector(3).count(), 0);
+
+        assert_eq!(level.sectors_with_tag(7).count(), 0);
+        level.sectors[2].tag = 7;
+        level.sectors[0].tag = 7;
+        level.sectors[1].tag = 3;
+        level.index_sectors();
+        assert_eq!(level.sectors_with_tag(7).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(level.sectors_with_tag(3).collect::<Vec<_>>(), [1]);
+        assert_eq!(level.sectors_with_tag(5).count(), 0);
+    }
+
+    #[test]
+    fn walls_between_rooms_block_when_solid() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        let (line, dist, at) = level.nearest_wall(Vec2::new(126.0, 64.0), 64.0).unwrap();
+        assert_eq!((line, dist, at), (8, 2.0, Vec2::new(128.0, 64.0)));
+        // a one-sided separator belongs to the room it faces
+        assert_eq!(level.linedefs_of_sector(1).collect::<Vec<_>>(), [2, 3]);
+    }
 }
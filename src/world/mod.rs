@@ -1,13 +1,35 @@
+mod angle;
 mod camera;
+mod font;
 mod geometry;
 mod helpers;
+mod name8;
+mod nodebuild;
+mod sight;
+#[cfg(test)]
+pub(crate) mod testmap;
 mod texture;
+mod validate;
 
 pub use geometry::{
-    Aabb, Blockmap, Level, Linedef, LinedefFlags, LinedefId, Node, Sector, SectorId, Segment,
-    SegmentId, Sidedef, SidedefId, Subsector, SubsectorId, Thing, ThingId, Vertex, VertexId,
+    Aabb, BitMatrix, Blockmap, Level, LineSet, Linedef, LinedefFlags, LinedefId, Node, Sector,
+    SectorGraph, SectorId, SectorIndex, Segment, SegmentId, Sidedef, SidedefId, Subsector,
+    SubsectorId, Thing, ThingId, Vertex, VertexId,
 };
 
-pub use camera::Camera;
+pub use angle::{Angle, angle_to};
 
-pub use texture::{Colormap, NO_TEXTURE, Palette, Texture, TextureBank, TextureError, TextureId};
+pub use camera::{Camera, CameraMode};
+
+pub use font::{FONT_SPACE_W, Font, FontRef};
+
+pub use validate::{MapDefect, MapItem};
+
+pub use name8::Name8;
+
+pub use helpers::{BlockmapQuery, LineStamps, MAPBLOCKSIZE};
+
+pub use texture::{
+    Colormap, INVERSE_COLORMAP, NO_TEXTURE, NUM_PALETTES, Palette, PaletteEffect, ShadeTable,
+    TRANSPARENT_TEXTURE, TexMask, Texture, TextureBank, TextureError, TextureId, TextureSource,
+};
@@ -0,0 +1,118 @@
+//! Lump, texture and flat names as the WAD stores them: eight bytes,
+//! NUL-padded.
+//!
+//! Doom looks names up case-blind (vanilla `W_CheckNumForName`
+//! upper-cases first), so a [`Name8`] is upper-cased when it is made and
+//! compares, hashes and copies as the plain eight bytes; turning a
+//! sidedef's texture name into a bank key costs no allocation.
+
+use std::fmt;
+
+/// An upper-cased, NUL-padded name of at most eight bytes.  Longer names
+/// are cut to eight, and anything after a NUL is dropped.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Name8([u8; 8]);
+
+impl Name8 {
+    /// A name straight out of a WAD record.
+    pub const fn from_bytes(raw: &[u8; 8]) -> Self {
+        let mut out = [0; 8];
+        let mut i = 0;
+        while i < 8 && raw[i] != 0 {
+            out[i] = raw[i].to_ascii_uppercase();
+            i += 1;
+        }
+        Self(out)
+    }
+
+    /// The eight bytes, NUL-padded.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        &self.0
+    }
+
+    /// Up to the first NUL.  A name cut in the middle of a multi-byte
+    /// character loses the partial character.
+    pub fn as_str(&self) -> &str {
+        let end = self.0.iter().position(|&b| b == 0).unwrap_or(8);
+        match std::str::from_utf8(&self.0[..end]) {
+            Ok(s) => s,
+            Err(e) => std::str::from_utf8(&self.0[..e.valid_up_to()]).unwrap_or_default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0[0] == 0
+    }
+}
+
+impl From<&str> for Name8 {
+    fn from(name: &str) -> Self {
+        let mut raw = [0; 8];
+        let len = name.len().min(8);
+        raw[..len].copy_from_slice(&name.as_bytes()[..len]);
+        Self::from_bytes(&raw)
+    }
+}
+
+impl From<&String> for Name8 {
+    fn from(name: &String) -> Self {
+        name.as_str().into()
+    }
+}
+
+impl From<&[u8; 8]> for Name8 {
+    fn from(raw: &[u8; 8]) -> Self {
+        Self::from_bytes(raw)
+    }
+}
+
+impl PartialEq<&str> for Name8 {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for Name8 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Name8 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Name8({:?})", self.as_str())
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_fold_case_and_pad() {
+        let a = Name8::from("startan3");
+        assert_eq!(a, Name8::from("STARTAN3"));
+        assert_eq!(a, Name8::from_bytes(b"StArTaN3"));
+        assert_eq!(a.as_str(), "STARTAN3");
+        assert_eq!(Name8::from("sky1").as_bytes(), b"SKY1\0\0\0\0");
+        assert_eq!(Name8::from("-").to_string(), "-");
+        assert!(Name8::from("").is_empty());
+        // only ASCII folds
+        assert_eq!(Name8::from("né").as_str(), "Né");
+    }
+
+    #[test]
+    fn long_names_are_cut_at_eight_bytes() {
+        assert_eq!(Name8::from("BIGDOOR10"), Name8::from("bigdoor1"));
+        assert_eq!(Name8::from("BIGDOOR10").as_str(), "BIGDOOR1");
+        // junk after a NUL in a WAD record is ignored
+        assert_eq!(Name8::from_bytes(b"FLAT\0xyz"), Name8::from("FLAT"));
+        // a character split at the cut is dropped, not garbled
+        assert_eq!(Name8::from("SKYWALLé").as_str(), "SKYWALL");
+        assert_eq!(format!("{:?}", Name8::from("a")), "Name8(\"A\")");
+    }
+}
@@ -0,0 +1,525 @@
+//! Node builder for maps saved without SEGS / SSECTORS / NODES.
+//!
+//! Every linedef side becomes a seg; a set of segs that isn't convex yet
+//! is cut along the seg line that splits the fewest others while keeping
+//! the halves balanced.  The result is nothing like what vanilla's `bsp`
+//! tools write, but its subsectors are convex and one sector each, which
+//! is all the renderer and `locate_subsector` need.
+
+use glam::{DVec2, Vec2};
+
+use super::helpers::SUBSECTOR_BIT;
+use super::{Aabb, Angle, Level, Linedef, Node, SectorId, Segment, Sidedef, Subsector, Vertex};
+
+/// Points closer to a partition line than this are on it.
+const ON_LINE: f64 = 1.0 / 256.0;
+
+/// A split counts this much more than one seg of imbalance.
+const SPLIT_COST: usize = 8;
+
+/// Largest number of partition candidates tried per set; bigger sets try
+/// an even spread of their segs.
+const MAX_CANDIDATES: usize = 128;
+
+/// Where a seg lies relative to a partition line.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Side {
+    Front,
+    Back,
+    /// Crosses at this fraction of the way from `v1` to `v2`.
+    Split(f64),
+}
+
+struct Builder<'a> {
+    vertices: &'a mut Vec<Vertex>,
+    linedefs: &'a [Linedef],
+    sidedefs: &'a [Sidedef],
+    segs: Vec<Segment>,
+    subsectors: Vec<Subsector>,
+    nodes: Vec<Node>,
+}
+
+impl Level {
+    /// Replace the BSP with one built from the linedefs; split points are
+    /// appended to `vertices`.  Leaves a map without lines untouched.
+    pub fn build_nodes(&mut self) {
+        let mut b = Builder {
+            vertices: &mut self.vertices,
+            linedefs: &self.linedefs,
+            sidedefs: &self.sidedefs,
+            segs: Vec::new(),
+            subsectors: Vec::new(),
+            nodes: Vec::new(),
+        };
+        let segs = b.initial_segs();
+        if segs.is_empty() {
+            return;
+        }
+        let bbox = b.bbox(&segs);
+        let first = b.line(&segs[0]);
+        let root = b.build(segs);
+        if root & SUBSECTOR_BIT != 0 {
+            // one convex room: the BSP still needs a root node
+            b.nodes.push(node(first, [bbox, bbox], [root, root]));
+        }
+        let (segs, subsectors, nodes) = (b.segs, b.subsectors, b.nodes);
+        self.segs = segs;
+        self.subsectors = subsectors;
+        self.nodes = nodes;
+        self.measure_segs();
+    }
+}
+
+impl Builder<'_> {
+    /// One seg per sidedef, running with its linedef on the front side
+    /// and against it on the back.
+    fn initial_segs(&self) -> Vec<Segment> {
+        let mut segs = Vec::new();
+        for (i, ld) in self.linedefs.iter().enumerate() {
+            if self.pos(ld.v1) == self.pos(ld.v2) {
+                continue;
+            }
+            let sides = [
+                (ld.right_sidedef, ld.v1, ld.v2),
+                (ld.left_sidedef, ld.v2, ld.v1),
+            ];
+            for (dir, (side, v1, v2)) in sides.into_iter().enumerate() {
+                if side.is_some() {
+                    segs.push(Segment {
+                        v1,
+                        v2,
+                        linedef: i as u16,
+                        dir: dir as u16,
+                        offset: 0.0,
+                        length: 0.0,
+                        angle: Angle::ZERO,
+                    });
+                }
+            }
+        }
+        segs
+    }
+
+    /// Child index for `segs`: a subsector if they're convex, otherwise
+    /// a node splitting them.
+    fn build(&mut self, segs: Vec<Segment>) -> u32 {
+        if self.is_convex(&segs) {
+            return self.subsector(segs);
+        }
+        let Some(splitter) = self.pick_splitter(&segs) else {
+            // nothing divides them; can only happen on broken maps
+            return self.subsector(segs);
+        };
+        let line = self.line(&segs[splitter]);
+
+        let mut halves: [Vec<Segment>; 2] = Default::default();
+        for seg in segs {
+            match self.classify(line, &seg) {
+                Side::Front => halves[0].push(seg),
+                Side::Back => halves[1].push(seg),
+                Side::Split(t) => {
+                    let (a, b) = self.split(seg, t);
+                    // the part starting on the front goes in front
+                    let start = self.pos(a.v1);
+                    let front_first = side_dist(line, start) > 0.0;
+                    halves[!front_first as usize].push(a);
+                    halves[front_first as usize].push(b);
+                }
+            }
+        }
+
+        let bbox = [self.bbox(&halves[0]), self.bbox(&halves[1])];
+        let [front, back] = halves;
+        let child = [self.build(front), self.build(back)];
+        self.nodes.push(node(line, bbox, child));
+        self.nodes.len() as u32 - 1
+    }
+
+    /// Every seg in front of (or along) every other's line, all in one
+    /// sector.
+    fn is_convex(&self, segs: &[Segment]) -> bool {
+        let sector = self.sector(&segs[0]);
+        segs.iter().all(|s| {
+            let line = self.line(s);
+            self.sector(s) == sector && segs.iter().all(|t| self.classify(line, t) == Side::Front)
+        })
+    }
+
+    /// The seg whose line divides `segs` best: fewest splits, then the
+    /// most even halves.  `None` if none leaves anything behind it.
+    fn pick_splitter(&self, segs: &[Segment]) -> Option<usize> {
+        let step = segs.len().div_ceil(MAX_CANDIDATES);
+        let mut best: Option<(usize, usize)> = None;
+        for c in (0..segs.len()).step_by(step) {
+            let line = self.line(&segs[c]);
+            let (mut front, mut back, mut splits) = (0usize, 0usize, 0);
+            for seg in segs {
+                match self.classify(line, seg) {
+                    Side::Front => front += 1,
+                    Side::Back => back += 1,
+                    Side::Split(_) => splits += 1,
+                }
+            }
+            if back + splits == 0 {
+                continue;
+            }
+            let cost = splits * SPLIT_COST + front.abs_diff(back);
+            if best.is_none_or(|(_, b)| cost < b) {
+                best = Some((c, cost));
+            }
+        }
+        best.map(|(c, _)| c)
+    }
+
+    fn classify(&self, (origin, dir): (DVec2, DVec2), seg: &Segment) -> Side {
+        let (a, b) = (self.pos(seg.v1), self.pos(seg.v2));
+        let (da, db) = (side_dist((origin, dir), a), side_dist((origin, dir), b));
+        if da.abs() <= ON_LINE && db.abs() <= ON_LINE {
+            // along the line: facing the same way is in front
+            if (b - a).dot(dir) > 0.0 {
+                Side::Front
+            } else {
+                Side::Back
+            }
+        } else if da >= -ON_LINE && db >= -ON_LINE {
+            Side::Front
+        } else if da <= ON_LINE && db <= ON_LINE {
+            Side::Back
+        } else {
+            Side::Split(da / (da - db))
+        }
+    }
+
+    /// Cut `seg` `t` of the way along, at a new vertex.
+    fn split(&mut self, seg: Segment, t: f64) -> (Segment, Segment) {
+        let (a, b) = (self.pos(seg.v1), self.pos(seg.v2));
+        let at = a + (b - a) * t;
+        self.vertices.push(Vertex { pos: at.as_vec2() });
+        let mid = self.vertices.len() as u32 - 1;
+        let second = Segment {
+            v1: mid,
+            offset: seg.offset + a.distance(at) as f32,
+            ..seg.clone()
+        };
+        (Segment { v2: mid, ..seg }, second)
+    }
+
+    fn subsector(&mut self, segs: Vec<Segment>) -> u32 {
+        self.subsectors.push(Subsector {
+            num_lines: segs.len() as u32,
+            first_line: self.segs.len() as u32,
+            sector: self.sector(&segs[0]).unwrap_or(SectorId::MAX),
+            things: Vec::new(),
+        });
+        self.segs.extend(segs);
+        (self.subsectors.len() as u32 - 1) | SUBSECTOR_BIT
+    }
+
+    /// Sector on the seg's side of its linedef.
+    fn sector(&self, seg: &Segment) -> Option<SectorId> {
+        let ld = &self.linedefs[seg.linedef as usize];
+        let side = if seg.dir == 0 {
+            ld.right_sidedef
+        } else {
+            ld.left_sidedef
+        };
+        side.and_then(|s| self.sidedefs.get(s as usize))
+            .map(|sd| sd.sector)
+    }
+
+    /// The seg's line: its start and direction.
+    fn line(&self, seg: &Segment) -> (DVec2, DVec2) {
+        let a = self.pos(seg.v1);
+        (a, self.pos(seg.v2) - a)
+    }
+
+    fn pos(&self, v: u32) -> DVec2 {
+        self.vertices[v as usize].pos.as_dvec2()
+    }
+
+    fn bbox(&self, segs: &[Segment]) -> Aabb {
+        let mut bbox = Aabb {
+            min: Vec2::splat(f32::MAX),
+            max: Vec2::splat(f32::MIN),
+        };
+        for seg in segs {
+            for v in [seg.v1, seg.v2] {
+                let p = self.vertices[v as usize].pos;
+                bbox.min = bbox.min.min(p);
+                bbox.max = bbox.max.max(p);
+            }
+        }
+        bbox
+    }
+}
+
+/// Signed distance from the line to `p`; positive on the front (right)
+/// side, like `Node::point_side`.
+fn side_dist((origin, dir): (DVec2, DVec2), p: DVec2) -> f64 {
+    let d = p - origin;
+    (d.x * dir.y - d.y * dir.x) / dir.length()
+}
+
+fn node((origin, dir): (DVec2, DVec2), bbox: [Aabb; 2], child: [u32; 2]) -> Node {
+    Node {
+        x: origin.x as f32,
+        y: origin.y as f32,
+        dx: dir.x as f32,
+        dy: dir.y as f32,
+        bbox,
+        child,
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        defs,
+        renderer::render_to_buffer,
+        sim::{Angle, InputCmd, Position, TicRunner},
+        world::{Camera, Colormap, Palette, TRANSPARENT_TEXTURE, Texture, TextureBank, testmap},
+    };
+    use glam::Vec3;
+    use std::f32::consts::PI;
+
+    /// The map `make` builds as is, and with its BSP thrown away and
+    /// built again.
+    fn hand_and_built(make: impl Fn() -> Level) -> (Level, Level) {
+        let mut hand = make();
+        hand.finalise_bsp();
+        let mut built = make();
+        built.segs.clear();
+        built.subsectors.clear();
+        built.nodes.clear();
+        built.build_nodes();
+        built.finalise_bsp();
+        (hand, built)
+    }
+
+    /// Each subsector's segs face into it: every seg is in front of
+    /// every other's line, and they all border one sector.
+    fn assert_convex(level: &Level) {
+        for (i, ss) in level.subsectors.iter().enumerate() {
+            let first = ss.first_line as usize;
+            let segs = &level.segs[first..first + ss.num_lines as usize];
+            for s in segs {
+                let (a, b) = (
+                    level.vertices[s.v1 as usize].pos,
+                    level.vertices[s.v2 as usize].pos,
+                );
+                let (origin, dir) = (a.as_dvec2(), (b - a).as_dvec2());
+                for t in segs {
+                    for v in [t.v1, t.v2] {
+                        let p = level.vertices[v as usize].pos.as_dvec2();
+                        assert!(
+                            side_dist((origin, dir), p) >= -ON_LINE,
+                            "subsector {i} isn't convex"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn assert_same_sectors(hand: &Level, built: &Level) {
+        for x in (4..hand.blockmap.width * 128).step_by(7) {
+            for y in (3..128).step_by(9) {
+                let p = Vec2::new(x as f32, y as f32);
+                let [a, b] = [hand, built].map(|l| l.sector_of_subsector(l.locate_subsector(p)));
+                assert_eq!(a, b, "at {p}");
+            }
+        }
+    }
+
+    /// Walls, flats and mid-textures all a checkerboard, so every surface
+    /// draws.
+    fn textured(mut level: Level) -> (Level, TextureBank) {
+        let mut bank = TextureBank::default_with_checker();
+        let mut grey = Palette::default();
+        let mut colormap = Colormap::default();
+        for i in 0..256 {
+            grey[i] = (i as u32) * 0x01_01_01;
+            for (row, shades) in colormap.0.iter_mut().enumerate() {
+                shades[i] = (i as u8).saturating_sub(row as u8);
+            }
+        }
+        bank.set_palettes(vec![grey]);
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+        let wall = bank.insert("WALL", Texture::default()).unwrap();
+        for sd in &mut level.sidedefs {
+            (sd.upper, sd.lower, sd.middle) = (wall, wall, TRANSPARENT_TEXTURE);
+        }
+        for s in &mut level.sectors {
+            (s.floor_tex, s.ceil_tex) = (wall, wall);
+        }
+        (level, bank)
+    }
+
+    fn assert_same_frames(
+        hand: &Level,
+        built: &Level,
+        bank: &TextureBank,
+        views: &[(f32, f32, f32)],
+    ) {
+        for &(x, y, yaw) in views {
+            let camera = Camera::new(Vec3::new(x, y, 41.0), yaw, 90_f32.to_radians());
+            let [a, b] = [hand, built]
+                .map(|l| render_to_buffer(l, &camera, &TicRunner::new(l), bank, 160, 100));
+            assert!(a == b, "frames differ from ({x}, {y}) facing {yaw}");
+        }
+    }
+
+    /// Where a player ends up after `tics` of `cmd` from `(x, y)`.
+    fn walk(
+        level: &mut Level,
+        (x, y, yaw): (f32, f32, f32),
+        cmd: InputCmd,
+        tics: u32,
+    ) -> Vec<Position> {
+        let mut sim = TicRunner::new(level);
+        let ss = level.locate_subsector(Vec2::new(x, y));
+        let player = sim.spawn_mobj(level, defs::by_id("PLAYER").unwrap(), x, y, 0.0, ss);
+        *sim.world_mut().get::<&mut Angle>(player).unwrap() = yaw.into();
+        sim.set_player(player);
+        (0..tics)
+            .map(|_| {
+                sim.run_tic(level, cmd);
+                *sim.world().get::<&Position>(player).unwrap()
+            })
+            .collect()
+    }
+
+    fn assert_same_walks(
+        hand: &mut Level,
+        built: &mut Level,
+        walks: &[((f32, f32, f32), InputCmd)],
+    ) {
+        for &(from, cmd) in walks {
+            let (a, b) = (walk(hand, from, cmd, 70), walk(built, from, cmd, 70));
+            for (tic, (a, b)) in a.iter().zip(&b).enumerate() {
+                assert_eq!((a.0, a.1), (b.0, b.1), "tic {tic} from {from:?}");
+            }
+        }
+    }
+
+    const FORWARD: InputCmd = InputCmd {
+        forward: 1.0,
+        strafe: 0.0,
+        turn: 0.0,
+        run: true,
+        fire: false,
+        use_act: false,
+        weapon: None,
+        fly: 0.0,
+    };
+
+    const SLIDE: InputCmd = InputCmd {
+        strafe: 1.0,
+        ..FORWARD
+    };
+
+    #[test]
+    fn square_room_is_one_subsector() {
+        let (hand, built) = hand_and_built(testmap::square_room);
+        assert_eq!(built.subsectors.len(), 1);
+        assert_eq!(built.nodes.len(), 1);
+        assert_eq!(built.vertices.len(), hand.vertices.len());
+        assert!(built.validate().is_empty());
+        assert_convex(&built);
+        assert_same_sectors(&hand, &built);
+
+        let (mut hand, bank) = textured(hand);
+        let (mut built, _) = textured(built);
+        assert_same_frames(
+            &hand,
+            &built,
+            &bank,
+            &[(64.0, 64.0, 0.0), (20.0, 100.0, -PI / 4.0)],
+        );
+        assert_same_walks(
+            &mut hand,
+            &mut built,
+            &[((64.0, 64.0, 0.3), FORWARD), ((30.0, 30.0, PI), SLIDE)],
+        );
+    }
+
+    #[test]
+    fn two_rooms_with_a_door_match_the_hand_built_bsp() {
+        for (floor, ceil) in [(0.0, 0.0), (0.0, 128.0), (24.0, 96.0)] {
+            let (hand, built) =
+                hand_and_built(|| testmap::three_rooms(testmap::Middle::Open { floor, ceil }));
+            assert!(built.validate().is_empty());
+            assert_convex(&built);
+            assert_same_sectors(&hand, &built);
+
+            let (mut hand, bank) = textured(hand);
+            let (mut built, _) = textured(built);
+            let views = [(40.0, 64.0, 0.0), (250.0, 20.0, PI), (100.0, 120.0, -0.5)];
+            assert_same_frames(&hand, &built, &bank, &views);
+            // through (or into) the door, and along the walls
+            assert_same_walks(
+                &mut hand,
+                &mut built,
+                &[
+                    ((40.0, 64.0, 0.0), FORWARD),
+                    ((250.0, 100.0, PI + 0.2), FORWARD),
+                    ((40.0, 20.0, 0.0), SLIDE),
+                ],
+            );
+        }
+    }
+
+    #[test]
+    fn concave_rooms_are_split() {
+        // an L: the square with its north-east quarter cut out
+        let mut level = testmap::square_room();
+        let corners = [
+            (0.0, 0.0),
+            (128.0, 0.0),
+            (128.0, 64.0),
+            (64.0, 64.0),
+            (64.0, 128.0),
+            (0.0, 128.0),
+        ];
+        level.vertices = corners
+            .iter()
+            .map(|&(x, y)| Vertex {
+                pos: Vec2::new(x, y),
+            })
+            .collect();
+        let template = level.linedefs[0].clone();
+        level.linedefs = (0..6u16)
+            .map(|i| Linedef {
+                id: i,
+                v1: u32::from((i + 1) % 6),
+                v2: u32::from(i),
+                ..template.clone()
+            })
+            .collect();
+        level.build_nodes();
+        level.finalise_bsp();
+
+        assert!(level.subsectors.len() >= 2);
+        assert!(level.validate().is_empty());
+        assert_convex(&level);
+        for p in [
+            Vec2::new(10.0, 10.0),
+            Vec2::new(100.0, 30.0),
+            Vec2::new(30.0, 100.0),
+        ] {
+            assert_eq!(level.sector_of_subsector(level.locate_subsector(p)), 0);
+        }
+        // seg offsets still measure along the linedef
+        for seg in &level.segs {
+            let ld = &level.linedefs[seg.linedef as usize];
+            let from = level.vertices[ld.v1 as usize].pos;
+            let at = level.vertices[seg.v1 as usize].pos;
+            assert!((from.distance(at) - seg.offset).abs() < 1e-3);
+        }
+    }
+}
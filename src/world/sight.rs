@@ -0,0 +1,223 @@
+//! Line-of-sight – vanilla `p_sight.c`.
+//!
+//! REJECT gives a cheap "never visible" answer; otherwise the sight line is
+//! walked through the BSP front-to-back and every two-sided line it crosses
+//! must leave an opening at the sight line's height.
+
+use glam::{Vec2, Vec3};
+
+use super::helpers::{BspStack, CHILD_MASK, SUBSECTOR_BIT, warn_bsp_loop};
+use super::{Level, LinedefFlags, SubsectorId};
+
+/// Sight line being traced (vanilla `strace` + `sightzstart`).
+struct Trace {
+    from: Vec3,
+    to: Vec3,
+    delta: Vec2,
+}
+
+/// 0 = front (right), 1 = back, 2 = on the line – vanilla `P_DivlineSide`.
+#[inline]
+fn divline_side(p: Vec2, origin: Vec2, dir: Vec2) -> u8 {
+    let cross = dir.perp_dot(p - origin);
+    if cross < 0.0 {
+        0
+    } else if cross == 0.0 {
+        2
+    } else {
+        1
+    }
+}
+
+impl Level {
+    /// Can a point at `from` see a point at `to`?
+    pub fn check_sight(&self, from: Vec3, to: Vec3) -> bool {
+        /* REJECT fast path */
+        if let Some(reject) = &self.reject {
+            let s1 = self.sector_of_subsector(self.locate_subsector(from.truncate()));
+            let s2 = self.sector_of_subsector(self.locate_subsector(to.truncate()));
+            if reject.get(s1, s2) {
+                return false;
+            }
+        }
+
+        let trace = Trace {
+            from,
+            to,
+            delta: to.truncate() - from.truncate(),
+        };
+        if self.nodes.is_empty() {
+            return self.cross_subsector(0, &trace);
+        }
+        self.cross_bsp_node(self.bsp_root(), &trace)
+    }
+
+    /// Vanilla `P_CrossBSPNode`: `true` if the trace passes the subtree
+    /// under `root`.  Near side first, on an explicit stack no deeper than
+    /// the node count; a deeper tree loops and blocks the trace.
+    fn cross_bsp_node(&self, root: u32, trace: &Trace) -> bool {
+        let mut stack = BspStack::new();
+        stack.push((root, 0));
+        while let Some((child, depth)) = stack.pop() {
+            if child & SUBSECTOR_BIT != 0 {
+                if !self.cross_subsector(child & CHILD_MASK, trace) {
+                    return false;
+                }
+                continue;
+            }
+            if depth > self.nodes.len() {
+                warn_bsp_loop("check_sight");
+                return false;
+            }
+
+            let node = &self.nodes[child as usize];
+            let origin = Vec2::new(node.x, node.y);
+            let dir = Vec2::new(node.dx, node.dy);
+
+            // an on-line start is treated as front
+            let side = match divline_side(trace.from.truncate(), origin, dir) {
+                2 => 0,
+                s => s as usize,
+            };
+
+            // end point on the same side – no need to look behind the partition
+            if divline_side(trace.to.truncate(), origin, dir) as usize != side {
+                stack.push((node.child[side ^ 1], depth + 1));
+            }
+            stack.push((node.child[side], depth + 1));
+        }
+        true
+    }
+
+    /// Vanilla `P_CrossSubsector`: check every line of `ss` the trace
+    /// crosses for a blocking wall or an opening that misses the sight line.
+    fn cross_subsector(&self, ss: SubsectorId, trace: &Trace) -> bool {
+        let ss = &self.subsectors[ss as usize];
+        let from = trace.from.truncate();
+        let to = trace.to.truncate();
+
+        for seg in &self.segs[ss.first_line as usize..][..ss.num_lines as usize] {
+            let line = &self.linedefs[seg.linedef as usize];
+            let v1 = self.vertices[line.v1 as usize].pos;
+            let v2 = self.vertices[line.v2 as usize].pos;
+
+            // line endpoints on the same side of the trace → not crossed
+            if divline_side(v1, from, trace.delta) == divline_side(v2, from, trace.delta) {
+                continue;
+            }
+            // trace endpoints on the same side of the line → not crossed
+            let ldir = v2 - v1;
+            if divline_side(from, v1, ldir) == divline_side(to, v1, ldir) {
+                continue;
+            }
+
+            // stop on a one-sided wall
+            if !line.flags.contains(LinedefFlags::TWO_SIDED) {
+                return false;
+            }
+            let (Some(fs), Some(bs)) = (line.right_sidedef, line.left_sidedef) else {
+                return false;
+            };
+            let front = &self.sectors[self.sidedefs[fs as usize].sector as usize];
+            let back = &self.sectors[self.sidedefs[bs as usize].sector as usize];
+
+            // no height change – can't block
+            if front.floor_h == back.floor_h && front.ceil_h == back.ceil_h {
+                continue;
+            }
+
+            let open_top = front.ceil_h.min(back.ceil_h);
+            let open_bottom = front.floor_h.max(back.floor_h);
+
+            // quick test for totally closed doors
+            if open_bottom >= open_top {
+                return false;
+            }
+
+            // height of the sight line where it crosses this line
+            let denom = ldir.perp_dot(trace.delta);
+            if denom == 0.0 {
+                continue;
+            }
+            let frac = ldir.perp_dot(v1 - from) / denom;
+            let z = trace.from.z + (trace.to.z - trace.from.z) * frac;
+
+            if front.floor_h != back.floor_h && z < open_bottom {
+                return false;
+            }
+            if front.ceil_h != back.ceil_h && z > open_top {
+                return false;
+            }
+        }
+
+        true // passed the subsector ok
+    }
+}
+
+/*====================================================================*/
+/*                               Tests                                */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{BitMatrix, testmap};
+
+    const EYE: f32 = 41.0;
+
+    fn a_to_b(level: &Level, za: f32, zb: f32) -> bool {
+        level.check_sight(Vec3::new(64.0, 64.0, za), Vec3::new(208.0, 64.0, zb))
+    }
+
+    #[test]
+    fn solid_wall_blocks() {
+        let level = testmap::three_rooms(testmap::Middle::Wall);
+        assert!(!a_to_b(&level, EYE, EYE));
+    }
+
+    #[test]
+    fn open_doorway_sees() {
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        assert!(a_to_b(&level, EYE, EYE));
+        // and sight is symmetric
+        assert!(level.check_sight(Vec3::new(208.0, 64.0, EYE), Vec3::new(64.0, 64.0, EYE)));
+    }
+
+    #[test]
+    fn window_sees_only_at_window_height() {
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 40.0,
+            ceil: 80.0,
+        });
+        assert!(a_to_b(&level, 60.0, 60.0));
+        assert!(!a_to_b(&level, 20.0, 20.0));
+        assert!(!a_to_b(&level, 100.0, 100.0));
+        // slanted line through the window
+        assert!(a_to_b(&level, 45.0, 75.0));
+    }
+
+    #[test]
+    fn closed_door_blocks() {
+        let level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 0.0,
+        });
+        assert!(!a_to_b(&level, EYE, EYE));
+    }
+
+    #[test]
+    fn reject_short_circuits() {
+        let mut level = testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        });
+        // sector 0 (room A) can't see sector 2 (room B); lump is short
+        let mut lump = vec![0u8; 1];
+        lump[0] |= 1 << 2;
+        level.reject = Some(BitMatrix::from_lump(&lump, 3));
+        assert!(!a_to_b(&level, EYE, EYE));
+        assert!(level.check_sight(Vec3::new(208.0, 64.0, EYE), Vec3::new(64.0, 64.0, EYE)));
+    }
+}
@@ -0,0 +1,284 @@
+//! Tiny hand-built maps for unit tests that must not depend on a WAD.
+//!
+//! ```text
+//!  y=128 +---------+--+---------+
+//!        |         |  |         |
+//!        |  A (0)  |M |  B (2)  |
+//!        |         |(1)|        |
+//!  y=0   +---------+--+---------+
+//!        0       128 144       272
+//! ```
+//!
+//! Rooms A and B have floor 0 / ceiling 128; the strip M between them is
+//! configurable.  The BSP splits at x=144, then at x=128.
+
+use glam::Vec2;
+
+use super::helpers::SUBSECTOR_BIT;
+use super::{
+    Aabb, Angle, Blockmap, Level, Linedef, LinedefFlags, Node, Sector, SectorGraph, Segment,
+    Sidedef, Subsector, TRANSPARENT_TEXTURE, Vertex,
+};
+
+/// What separates room A from room B.
+#[derive(Clone, Copy, Debug)]
+pub enum Middle {
+    /// One-sided walls on both edges of M.
+    Wall,
+    /// Two-sided lines into a sector with these heights.
+    Open { floor: f32, ceil: f32 },
+}
+
+fn sector(floor_h: f32, ceil_h: f32) -> Sector {
+    Sector {
+        floor_h,
+        ceil_h,
+        floor_tex: 0,
+        ceil_tex: 0,
+        light: 1.0,
+        special: 0,
+        tag: 0,
+        is_sky_ceiling: false,
+        is_sky_floor: false,
+    }
+}
+
+fn sidedef(sector: u16) -> Sidedef {
+    Sidedef {
+        x_off: 0.0,
+        y_off: 0.0,
+        upper: TRANSPARENT_TEXTURE,
+        lower: TRANSPARENT_TEXTURE,
+        middle: TRANSPARENT_TEXTURE,
+        sector,
+    }
+}
+
+pub fn three_rooms(middle: Middle) -> Level {
+    let xs = [0.0, 128.0, 144.0, 272.0];
+    // 0..4 along y=0, 4..8 along y=128
+    let vertices: Vec<Vertex> = [0.0, 128.0]
+        .iter()
+        .flat_map(|&y| {
+            xs.iter().map(move |&x| Vertex {
+                pos: Vec2::new(x, y),
+            })
+        })
+        .collect();
+
+    let (mid_floor, mid_ceil, open) = match middle {
+        Middle::Wall => (0.0, 128.0, false),
+        Middle::Open { floor, ceil } => (floor, ceil, true),
+    };
+    let sectors = vec![
+        sector(0.0, 128.0),
+        sector(mid_floor, mid_ceil),
+        sector(0.0, 128.0),
+    ];
+    let sidedefs = vec![sidedef(0), sidedef(1), sidedef(2)];
+
+    let mut linedefs = Vec::new();
+    let mut line = |v1: u16, v2: u16, right: u16, left: Option<u16>| {
+        let (a, b) = (vertices[v1 as usize].pos, vertices[v2 as usize].pos);
+        linedefs.push(Linedef {
+            id: linedefs.len() as u16,
+            v1: v1.into(),
+            v2: v2.into(),
+            flags: if left.is_some() {
+                LinedefFlags::TWO_SIDED
+            } else {
+                LinedefFlags::IMPASSABLE
+            },
+            special: 0,
+            tag: 0,
+            right_sidedef: Some(right),
+            left_sidedef: left,
+            bbox: Aabb {
+                min: a.min(b),
+                max: a.max(b),
+            },
+        });
+        linedefs.len() as u16 - 1
+    };
+
+    /* per-sector outer walls, right side facing in; then the separators */
+    let mut walls: [Vec<u16>; 3] = Default::default();
+    for s in 0..3u16 {
+        let (l, r) = (s, s + 1);
+        walls[s as usize].push(line(r, l, s, None)); // south
+        walls[s as usize].push(line(l + 4, r + 4, s, None)); // north
+    }
+    walls[0].push(line(0, 4, 0, None)); // west
+    walls[2].push(line(7, 3, 2, None)); // east
+
+    // separators run south → north, so their right side faces east
+    let (sep_a, sep_b) = if open {
+        (line(1, 5, 1, Some(0)), line(2, 6, 2, Some(1)))
+    } else {
+        let a = line(5, 1, 0, None);
+        let b = line(2, 6, 2, None);
+        (a, b)
+    };
+    walls[0].push(sep_a);
+    walls[1].push(sep_a);
+    walls[1].push(sep_b);
+    walls[2].push(sep_b);
+
+    /* one seg per (subsector, line) */
+    let mut segs = Vec::new();
+    let mut subsectors = Vec::new();
+    for (s, lines) in walls.iter().enumerate() {
+        subsectors.push(Subsector {
+            num_lines: lines.len() as u32,
+            first_line: segs.len() as u32,
+            sector: s as u16,
+            things: Vec::new(),
+        });
+        for &li in lines {
+            // a separator's back side runs the other way, as in a real BSP
+            let ld = &linedefs[li as usize];
+            let back = ld.right_sidedef != Some(s as u16);
+            let (v1, v2) = if back { (ld.v2, ld.v1) } else { (ld.v1, ld.v2) };
+            segs.push(Segment {
+                v1,
+                v2,
+                linedef: li,
+                dir: back as u16,
+                offset: 0.0,
+                length: 0.0,
+                angle: Angle::ZERO,
+            });
+        }
+    }
+
+    let bbox = |x0: f32, x1: f32| Aabb {
+        min: Vec2::new(x0, 0.0),
+        max: Vec2::new(x1, 128.0),
+    };
+    let nodes = vec![
+        // x=128: front (east) = M, back (west) = A
+        Node {
+            x: 128.0,
+            y: 0.0,
+            dx: 0.0,
+            dy: 128.0,
+            bbox: [bbox(128.0, 144.0), bbox(0.0, 128.0)],
+            child: [1 | SUBSECTOR_BIT, SUBSECTOR_BIT],
+        },
+        // root, x=144: front (east) = B, back = node 0
+        Node {
+            x: 144.0,
+            y: 0.0,
+            dx: 0.0,
+            dy: 128.0,
+            bbox: [bbox(144.0, 272.0), bbox(0.0, 144.0)],
+            child: [2 | SUBSECTOR_BIT, 0],
+        },
+    ];
+
+    let blockmap = Blockmap::rebuild(&vertices, &linedefs);
+    let sector_graph = SectorGraph::build(&linedefs, &sidedefs, sectors.len());
+    let mut level = Level {
+        name: "TEST".into(),
+        things: Vec::new(),
+        linedefs,
+        sidedefs,
+        vertices,
+        segs,
+        subsectors,
+        nodes,
+        sectors,
+        blockmap,
+        reject: None,
+        sky_texture: crate::world::NO_TEXTURE,
+        sector_graph,
+        sector_index: Default::default(),
+    };
+    level.measure_segs();
+    level.index_sectors();
+    level
+}
+
+/// One 128×128 room, floor 0 / ceiling 128, with the single-node BSP a
+/// node builder writes for a convex room.
+pub fn square_room() -> Level {
+    let vertices: Vec<Vertex> = [(0.0, 0.0), (128.0, 0.0), (128.0, 128.0), (0.0, 128.0)]
+        .iter()
+        .map(|&(x, y)| Vertex {
+            pos: Vec2::new(x, y),
+        })
+        .collect();
+    // counter-clockwise vertices, so each wall runs from v2 back to v1
+    let linedefs: Vec<Linedef> = (0..4u16)
+        .map(|i| {
+            let (v1, v2) = ((i + 1) % 4, i);
+            let (a, b) = (vertices[v1 as usize].pos, vertices[v2 as usize].pos);
+            Linedef {
+                id: i,
+                v1: v1.into(),
+                v2: v2.into(),
+                flags: LinedefFlags::IMPASSABLE,
+                special: 0,
+                tag: 0,
+                right_sidedef: Some(0),
+                left_sidedef: None,
+                bbox: Aabb {
+                    min: a.min(b),
+                    max: a.max(b),
+                },
+            }
+        })
+        .collect();
+    let segs = linedefs
+        .iter()
+        .map(|ld| Segment {
+            v1: ld.v1,
+            v2: ld.v2,
+            linedef: ld.id,
+            dir: 0,
+            offset: 0.0,
+            length: 0.0,
+            angle: Angle::ZERO,
+        })
+        .collect();
+    let bbox = Aabb {
+        min: Vec2::ZERO,
+        max: Vec2::splat(128.0),
+    };
+    let sectors = vec![sector(0.0, 128.0)];
+    let sidedefs = vec![sidedef(0)];
+
+    let blockmap = Blockmap::rebuild(&vertices, &linedefs);
+    let sector_graph = SectorGraph::build(&linedefs, &sidedefs, sectors.len());
+    let mut level = Level {
+        name: "TEST".into(),
+        things: Vec::new(),
+        linedefs,
+        sidedefs,
+        vertices,
+        segs,
+        subsectors: vec![Subsector {
+            num_lines: 4,
+            first_line: 0,
+            sector: 0,
+            things: Vec::new(),
+        }],
+        nodes: vec![Node {
+            x: 128.0,
+            y: 0.0,
+            dx: -128.0,
+            dy: 0.0,
+            bbox: [bbox, bbox],
+            child: [SUBSECTOR_BIT, SUBSECTOR_BIT],
+        }],
+        sectors,
+        blockmap,
+        reject: None,
+        sky_texture: crate::world::NO_TEXTURE,
+        sector_graph,
+        sector_index: Default::default(),
+    };
+    level.measure_segs();
+    level.index_sectors();
+    level
+}
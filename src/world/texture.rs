@@ -4,6 +4,8 @@
 use std::collections::HashMap;
 use std::ops::{Index, IndexMut};
 
+use super::{Font, FontRef, Name8};
+
 /// Runtime handle for a texture in this bank.
 ///
 /// *Guaranteed* to remain stable for the lifetime of the bank.
@@ -13,15 +15,77 @@ pub type TextureId = u16;
 /// Always = 0 because `TextureBank::new()` inserts it first.
 pub const NO_TEXTURE: TextureId = 0;
 
-/// CPU-side storage: 32-bit **ARGB**  (0xAARRGGBB) in row-major order.
+/// `TextureId` of a sidedef's `"-"`: nothing is drawn there (vanilla's
+/// texture 0).  Never backed by a texture in the bank.
+pub const TRANSPARENT_TEXTURE: TextureId = TextureId::MAX;
+
+/// Palettes in PLAYPAL: normal, 8 reds (pain), 4 yellows (pickups) and
+/// the radiation suit green.
+pub const NUM_PALETTES: usize = 14;
+
+/// Colormap row with the invulnerability inverse greys.
+pub const INVERSE_COLORMAP: u8 = 32;
+
+/// Colormap rows per palette: 0-31 light, 32 invul, 33 torch.
+const SHADE_ROWS: usize = 34;
+
+/// CPU-side storage: one **palette index** per texel in row-major order.
 /// The loader fills the pixel vector; the renderer may later upload it
 /// to the GPU and drop the CPU copy if desired.
+///
+/// Palette index 0 is an ordinary colour (black in DOOM's PLAYPAL), so
+/// holes in sprites / masked mid-textures are tracked separately in `mask`.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Texture {
-    pub name: String,
+    pub name: Name8,
     pub w: usize,
     pub h: usize,
     pub pixels: Vec<u8>,
+    /// Opacity bitmap; `None` means every texel is solid.
+    pub mask: Option<TexMask>,
+}
+
+impl Texture {
+    /// Is texel `idx` (row-major) drawn?
+    #[inline(always)]
+    pub fn is_opaque(&self, idx: usize) -> bool {
+        self.mask.as_ref().is_none_or(|m| m.get(idx))
+    }
+
+    /// Bytes held by pixels + mask.
+    pub fn memory_usage(&self) -> usize {
+        self.pixels.len() + self.mask.as_ref().map_or(0, TexMask::memory_usage)
+    }
+}
+
+/// One bit per texel, set = opaque.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TexMask(Vec<u64>);
+
+impl TexMask {
+    /// All-transparent mask for `len` texels.
+    pub fn new(len: usize) -> Self {
+        TexMask(vec![0; len.div_ceil(64)])
+    }
+
+    #[inline(always)]
+    pub fn get(&self, idx: usize) -> bool {
+        self.0[idx >> 6] & (1 << (idx & 63)) != 0
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, idx: usize) {
+        self.0[idx >> 6] |= 1 << (idx & 63);
+    }
+
+    /// `true` if the first `len` texels are all opaque.
+    pub fn is_full(&self, len: usize) -> bool {
+        (0..len).all(|i| self.get(i))
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.0.len() * std::mem::size_of::<u64>()
+    }
 }
 /// Convenience checkerboard 8×8 (dark/light grey).
 impl Default for Texture {
@@ -39,10 +103,11 @@ impl Default for Texture {
             }
         }
         Texture {
-            name: "CHECKER".to_string(),
+            name: "CHECKER".into(),
             w: 8,
             h: 8,
             pixels: pix,
+            mask: None,
         }
     }
 }
@@ -52,13 +117,26 @@ impl Default for Texture {
 pub enum TextureError {
     /// Attempted to insert a second texture with an existing name.
     #[error("texture name `{0}` already present in bank")]
-    Duplicate(String),
+    Duplicate(Name8),
 
     /// Requested ID is outside `0 .. bank.len()`.
     #[error("texture id {0} out of range")]
     BadId(TextureId),
 }
 
+/// Deferred texture builder handed to [`TextureBank::resolve`].
+///
+/// Lets the bank stay format-agnostic while the loader composes wall
+/// textures / decodes flats only for names a map actually references.
+pub trait TextureSource {
+    /// Build the texture called `name`, or `None` if unknown.
+    fn build(&mut self, name: Name8) -> Option<Texture>;
+
+    /// Every name this source can build (for `--preload-all`).
+    fn names(&self) -> Vec<Name8>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Palette(pub [u32; 256]);
 impl Default for Palette {
     fn default() -> Self {
@@ -95,17 +173,41 @@ impl IndexMut<usize> for Colormap {
     }
 }
 
+/// Full-screen colour effect: which PLAYPAL palette to draw with and,
+/// for invulnerability / light amp, a colormap row forced on every
+/// pixel regardless of light.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PaletteEffect {
+    pub palette: u8,
+    pub fixed_colormap: Option<u8>,
+}
+
+/// `[shade][texel]` → ARGB rows for one [`PaletteEffect`]; looking a
+/// pixel up costs the same single index as [`TextureBank::get_color`].
+#[derive(Clone, Copy)]
+pub struct ShadeTable<'a> {
+    rows: &'a [u32],
+    /// 0 when a fixed colormap pins every shade to the one row.
+    mask: u8,
+}
+
+impl ShadeTable<'_> {
+    #[inline(always)]
+    pub fn get(self, shade_idx: u8, texel: u8) -> u32 {
+        self.rows[((shade_idx & self.mask) as usize) << 8 | (texel as usize)]
+    }
+}
+
 type SpriteKey = u64; // packed (code , frame , rot)
 type SpriteVal = (TextureId, bool); // (id , flip?)
 
 #[inline]
-fn pack_sprite_code(code: &str) -> u32 {
+fn pack_sprite_code(b: &[u8]) -> u32 {
     // code is always 4 ASCII bytes (“TROO”, “POSS”, …)
-    let b = code.as_bytes();
     (b[0] as u32) << 24 | (b[1] as u32) << 16 | (b[2] as u32) << 8 | (b[3] as u32)
 }
 #[inline]
-fn sprite_key(code: &str, frame: char, rot: u8) -> SpriteKey {
+fn sprite_key(code: &[u8], frame: char, rot: u8) -> SpriteKey {
     ((pack_sprite_code(code) as u64) << 16) | ((frame as u8 as u64) << 8) | rot as u64
 }
 
@@ -118,13 +220,15 @@ fn sprite_key(code: &str, frame: char, rot: u8) -> SpriteKey {
 /// **Thread-safety:** access `TextureBank` from a single thread or wrap it
 /// in `RwLock`; the struct itself is not `Sync`.
 pub struct TextureBank {
-    by_name: HashMap<String, TextureId>,
+    by_name: HashMap<Name8, TextureId>,
     data: Vec<Texture>,
-    palette: Palette,
+    /// PLAYPAL; index 0 is the normal palette.
+    palettes: Vec<Palette>,
     colormap: Colormap,
-    /// Pre-computed [ shade<<8 | color ] → ARGB.
+    /// Pre-computed [ palette ][ shade<<8 | color ] → ARGB.
     shade_table: Vec<u32>,
     sprite_cache: HashMap<SpriteKey, SpriteVal>,
+    fonts: HashMap<Name8, Font>,
 }
 
 impl TextureBank {
@@ -137,19 +241,28 @@ impl TextureBank {
     /// and obtains the handle **0**.
     pub fn new(missing_tex: Texture) -> Self {
         let mut by_name = HashMap::new();
-        by_name.insert("MISSING".into(), NO_TEXTURE);
+        by_name.insert(Name8::from("MISSING"), NO_TEXTURE);
         Self {
             by_name,
             data: vec![missing_tex],
-            palette: Palette::default(),
+            palettes: vec![Palette::default()],
             colormap: Colormap::default(),
             shade_table: Vec::new(),
             sprite_cache: HashMap::new(),
+            fonts: HashMap::new(),
         }
     }
 
+    /// Use `palette` alone; palette effects fall back to it.
     pub fn set_palette(&mut self, palette: Palette) {
-        self.palette = palette;
+        self.palettes = vec![palette];
+    }
+
+    /// Every PLAYPAL palette, normal one first.  Ignored if empty.
+    pub fn set_palettes(&mut self, palettes: Vec<Palette>) {
+        if !palettes.is_empty() {
+            self.palettes = palettes;
+        }
     }
 
     pub fn set_colormap(&mut self, colormap: Colormap) {
@@ -172,14 +285,14 @@ impl TextureBank {
         self.data.len() == 1
     } // only checker
 
-    /// Obtain the id for a *loaded* texture by name.
+    /// Obtain the id for a *loaded* texture by name, in any case.
     /// Returns `None` if the name is unknown.
-    pub fn id(&self, name: &str) -> Option<TextureId> {
-        self.by_name.get(name).copied()
+    pub fn id(&self, name: impl Into<Name8>) -> Option<TextureId> {
+        self.by_name.get(&name.into()).copied()
     }
 
     /// Fallback-safe query: unknown names resolve to the checkerboard id.
-    pub fn id_or_missing(&self, name: &str) -> TextureId {
+    pub fn id_or_missing(&self, name: impl Into<Name8>) -> TextureId {
         self.id(name).unwrap_or(NO_TEXTURE)
     }
 
@@ -195,17 +308,47 @@ impl TextureBank {
             .ok_or(TextureError::BadId(id))
     }
 
+    /// Approximate heap bytes held by texels, masks and lookup tables.
+    pub fn memory_usage(&self) -> usize {
+        let texels: usize = self.data.iter().map(Texture::memory_usage).sum();
+        let names = self.by_name.len() * std::mem::size_of::<(Name8, TextureId)>();
+        let sprites = self.sprite_cache.len() * std::mem::size_of::<(SpriteKey, SpriteVal)>();
+        texels + names + sprites + self.shade_table.len() * std::mem::size_of::<u32>()
+    }
+
     // ---------------------------------------------------------------------
     // Mutations
     // ---------------------------------------------------------------------
 
+    /// Id for `name`, building it through `src` on first use.
+    /// Unknown names resolve to the checkerboard id.
+    pub fn resolve(&mut self, name: impl Into<Name8>, src: &mut dyn TextureSource) -> TextureId {
+        let name = name.into();
+        if let Some(id) = self.id(name) {
+            return id;
+        }
+        match src.build(name) {
+            Some(tex) => self
+                .insert(name, tex)
+                .expect("name checked absent just above"),
+            None => NO_TEXTURE,
+        }
+    }
+
+    /// Eagerly build everything `src` knows about.
+    pub fn preload_all(&mut self, src: &mut dyn TextureSource) {
+        for name in src.names() {
+            self.resolve(name, src);
+        }
+    }
+
     /// Insert a texture under `name`.
     ///
     /// * Returns the newly assigned `TextureId`.
     /// * Fails if the name already exists (`Duplicate`).
-    pub fn insert<S: Into<String>>(
+    pub fn insert(
         &mut self,
-        name: S,
+        name: impl Into<Name8>,
         tex: Texture,
     ) -> Result<TextureId, TextureError> {
         let name = name.into();
@@ -218,23 +361,71 @@ impl TextureBank {
         Ok(id)
     }
 
+    /// Register `font`, whose glyphs are already in the bank, as `name`;
+    /// a font of that name already there is replaced.
+    pub fn insert_font(&mut self, name: impl Into<Name8>, font: Font) {
+        self.fonts.insert(name.into(), font);
+    }
+
+    /// The font registered as `name`, at scale 1.
+    pub fn font(&self, name: impl Into<Name8>) -> Option<FontRef<'_>> {
+        let font = self.fonts.get(&name.into())?;
+        Some(FontRef::new(font, self))
+    }
+
+    /// Pre-compute the shade rows of every palette so a palette effect
+    /// costs nothing per pixel.
     pub fn build_shade_table(&mut self) {
-        const ROWS: usize = 34; // 0-31 light, 32 invul, 33 torch
         const COLS: usize = 256;
 
         self.shade_table.clear();
-        self.shade_table.reserve_exact(ROWS * COLS);
+        self.shade_table
+            .reserve_exact(self.palettes.len() * SHADE_ROWS * COLS);
 
-        for row in 0..ROWS {
-            for texel in 0..COLS {
-                // Step 1: remap texel through COLORMAP
-                let pal_idx = self.colormap[row][texel];
+        for palette in &self.palettes {
+            for row in 0..SHADE_ROWS {
+                for texel in 0..COLS {
+                    // Step 1: remap texel through COLORMAP
+                    let pal_idx = self.colormap[row][texel];
+
+                    // Step 2: convert palette entry to ARGB
+                    let argb = palette[pal_idx as usize];
+
+                    self.shade_table.push(argb);
+                }
+            }
+        }
+    }
 
-                // Step 2: convert palette entry to ARGB
-                let argb = self.palette[pal_idx as usize];
+    /// The normal palette's `[shade][texel]` table, 34 rows of 256
+    /// colours, for backends that do the colormap lookup themselves.
+    pub fn shade_table(&self) -> &[u32] {
+        let len = self.shade_table.len().min(SHADE_ROWS * 256);
+        &self.shade_table[..len]
+    }
 
-                self.shade_table.push(argb);
+    /// Shade rows to draw with under `effect`.  Out-of-range palettes
+    /// fall back to the normal one.
+    pub fn shades(&self, effect: PaletteEffect) -> ShadeTable<'_> {
+        const BLOCK: usize = SHADE_ROWS * 256;
+        let pal = match effect.palette as usize {
+            p if (p + 1) * BLOCK <= self.shade_table.len() => p,
+            _ => 0,
+        };
+        let block =
+            &self.shade_table[pal * BLOCK..(pal * BLOCK + BLOCK).min(self.shade_table.len())];
+        match effect.fixed_colormap {
+            Some(row) => {
+                let start = (row as usize).min(SHADE_ROWS - 1) << 8;
+                ShadeTable {
+                    rows: &block[start..start + 256],
+                    mask: 0,
+                }
             }
+            None => ShadeTable {
+                rows: block,
+                mask: 0xFF,
+            },
         }
     }
 
@@ -243,21 +434,20 @@ impl TextureBank {
         self.shade_table[(shade_idx as usize) << 8 | (texel as usize)]
     }
 
-    pub fn register_sprite_lump(&mut self, lump_name: &str, id: TextureId) {
-        let bytes = lump_name.as_bytes();
-        match bytes.len() {
+    pub fn register_sprite_lump(&mut self, lump_name: impl Into<Name8>, id: TextureId) {
+        let name = lump_name.into();
+        let bytes = name.as_bytes();
+        let code = &bytes[0..4];
+        let frame = bytes[4] as char;
+        match name.as_str().len() {
             6 => {
                 // „TROOA6”
-                let code = &lump_name[0..4];
-                let frame = bytes[4] as char;
                 let rot = bytes[5] - b'0';
                 self.sprite_cache
                     .insert(sprite_key(code, frame, rot), (id, false));
             }
             8 => {
                 // „POSSB8B2”  or  „POSSB2B8”
-                let code = &lump_name[0..4];
-                let frame = bytes[4] as char;
                 let r1 = bytes[5] - b'0';
                 let r2 = bytes[7] - b'0';
                 // first rotation is the “original”
@@ -271,15 +461,28 @@ impl TextureBank {
     }
 
     /// O(1) – returns `(NO_TEXTURE, false)` if frame is absent.
+    /// Whether `code`'s `frame` comes in eight rotations rather than one
+    /// `…0` lump for every angle (vanilla `spriteframe_t::rotate`).
+    pub fn sprite_rotates(&self, code: &str, frame: char) -> bool {
+        self.sprite_cache
+            .contains_key(&sprite_key(code.as_bytes(), frame, 1))
+    }
+
     pub fn sprite_id(&self, code: &str, frame: char, rot: u8) -> (TextureId, bool) {
         // 1. exact match ----------------------------------------------------
-        if let Some(&(id, flip)) = self.sprite_cache.get(&sprite_key(code, frame, rot)) {
+        if let Some(&(id, flip)) = self
+            .sprite_cache
+            .get(&sprite_key(code.as_bytes(), frame, rot))
+        {
             return (id, flip);
         }
 
         // 2. billboard fallback --------------------------------------------
         if rot != 0 {
-            if let Some(&(id, _)) = self.sprite_cache.get(&sprite_key(code, frame, 0)) {
+            if let Some(&(id, _)) = self
+                .sprite_cache
+                .get(&sprite_key(code.as_bytes(), frame, 0))
+            {
                 return (id, false); // never mirror A0
             }
         }
@@ -298,11 +501,63 @@ mod tests {
 
     fn dummy_tex(color: u8) -> Texture {
         Texture {
-            name: "Dummy".to_string(),
+            name: "DUMMY".into(),
             w: 2,
             h: 2,
             pixels: vec![color; 4],
+            mask: None,
+        }
+    }
+
+    struct Counting(usize);
+    impl TextureSource for Counting {
+        fn build(&mut self, name: Name8) -> Option<Texture> {
+            self.0 += 1;
+            (name != "NOPE").then(|| dummy_tex(7))
+        }
+        fn names(&self) -> Vec<Name8> {
+            vec!["A".into(), "B".into()]
+        }
+    }
+
+    #[test]
+    fn palette_effects_pick_shade_rows() {
+        let mut bank = TextureBank::default_with_checker();
+        let mut normal = Palette::default();
+        let mut red = Palette::default();
+        for i in 0..256 {
+            normal[i] = i as u32;
+            red[i] = 0x1000 | i as u32;
+        }
+        bank.set_palettes(vec![normal, red]);
+        let mut colormap = Colormap::default();
+        for (row, shades) in colormap.0.iter_mut().enumerate() {
+            shades.fill(row as u8);
         }
+        bank.set_colormap(colormap);
+        bank.build_shade_table();
+
+        assert_eq!(bank.shade_table().len(), SHADE_ROWS * 256);
+        let plain = bank.shades(PaletteEffect::default());
+        assert_eq!(plain.get(5, 9), bank.get_color(5, 9));
+        let pain = PaletteEffect {
+            palette: 1,
+            fixed_colormap: None,
+        };
+        assert_eq!(bank.shades(pain).get(5, 9), 0x1005);
+        // missing palettes fall back to the normal one
+        let rad = PaletteEffect {
+            palette: 13,
+            fixed_colormap: None,
+        };
+        assert_eq!(bank.shades(rad).get(5, 9), 5);
+        // the fixed colormap ignores light
+        let invul = PaletteEffect {
+            palette: 0,
+            fixed_colormap: Some(INVERSE_COLORMAP),
+        };
+        assert_eq!(bank.shades(invul).get(5, 9), 32);
+        assert_eq!(bank.shades(invul).get(31, 9), 32);
     }
 
     #[test]
@@ -316,6 +571,10 @@ mod tests {
         assert_eq!(bank.id("RED"), Some(red));
         assert_eq!(bank.id("BLUE"), Some(blue));
         assert_eq!(bank.id("NOPE"), None);
+        // names are looked up case-blind, cut to eight
+        assert_eq!(bank.id("red"), Some(red));
+        assert_eq!(bank.id("BLUEBLUE"), None);
+        assert_eq!(bank.id(Name8::from("Blue")), Some(blue));
 
         assert_eq!(bank.texture(red).unwrap().pixels[0], 0x00);
         assert_eq!(bank.texture(blue).unwrap().pixels[0], 0xFF);
@@ -331,6 +590,33 @@ mod tests {
         assert_eq!(bank.len(), 2);
     }
 
+    #[test]
+    fn resolve_builds_once() {
+        let mut bank = TextureBank::default_with_checker();
+        let mut src = Counting(0);
+        let a = bank.resolve("A", &mut src);
+        assert_eq!(bank.resolve("A", &mut src), a);
+        assert_eq!(src.0, 1);
+        assert_eq!(bank.resolve("NOPE", &mut src), NO_TEXTURE);
+
+        bank.preload_all(&mut src);
+        assert!(bank.id("B").is_some());
+        assert_eq!(src.0, 3);
+    }
+
+    #[test]
+    fn mask_keeps_black_texels() {
+        let mut tex = dummy_tex(0);
+        let mut mask = TexMask::new(4);
+        mask.set(0);
+        mask.set(3);
+        tex.mask = Some(mask);
+        assert!(tex.is_opaque(0));
+        assert!(!tex.is_opaque(1));
+        assert!(tex.is_opaque(3));
+        assert!(dummy_tex(0).is_opaque(1));
+    }
+
     #[test]
     fn bad_id_guard() {
         let bank = TextureBank::default_with_checker();
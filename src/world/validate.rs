@@ -0,0 +1,562 @@
+//! Map sanity checks – catch the broken indices a malformed PWAD carries
+//! before the renderer or sim trips over them.
+//!
+//! [`Level::validate`] only reports; [`Level::repair`] also rewrites every
+//! bad reference to something harmless (vertex 0, a dummy sidedef or
+//! sector appended for the purpose, …) so the map can be played anyway.
+
+use std::fmt;
+
+use super::helpers::{CHILD_MASK, SUBSECTOR_BIT};
+use super::{Aabb, Level, LinedefFlags, NO_TEXTURE, Sector, SectorId, Sidedef, SidedefId};
+
+/// Which part of the map a [`MapDefect`] is in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapItem {
+    /// The map as a whole (an empty lump).
+    Map,
+    Thing,
+    Linedef,
+    Sidedef,
+    Seg,
+    Subsector,
+    Node,
+    /// A blockmap cell.
+    Blockmap,
+}
+
+impl fmt::Display for MapItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Map => "map",
+            Self::Thing => "thing",
+            Self::Linedef => "linedef",
+            Self::Sidedef => "sidedef",
+            Self::Seg => "seg",
+            Self::Subsector => "subsector",
+            Self::Node => "node",
+            Self::Blockmap => "blockmap cell",
+        })
+    }
+}
+
+/// One problem found by [`Level::validate`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapDefect {
+    pub item: MapItem,
+    /// Index of the offending entry in its lump.
+    pub index: usize,
+    pub message: String,
+}
+
+impl MapDefect {
+    fn new(item: MapItem, index: usize, message: impl Into<String>) -> Self {
+        Self {
+            item,
+            index,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for MapDefect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.item == MapItem::Map {
+            return f.write_str(&self.message);
+        }
+        write!(f, "{} {}: {}", self.item, self.index, self.message)
+    }
+}
+
+impl Level {
+    /// Check every cross-reference of the map; empty if it's sound.
+    pub fn validate(&self) -> Vec<MapDefect> {
+        use MapItem::*;
+        let mut out = Vec::new();
+        let (nv, nl, nsd, nsec) = (
+            self.vertices.len(),
+            self.linedefs.len(),
+            self.sidedefs.len(),
+            self.sectors.len(),
+        );
+        let (nseg, nss) = (self.segs.len(), self.subsectors.len());
+
+        for (what, len) in [
+            ("vertices", nv),
+            ("linedefs", nl),
+            ("sectors", nsec),
+            ("subsectors", nss),
+        ] {
+            if len == 0 {
+                out.push(MapDefect::new(Map, 0, format!("the map has no {what}")));
+            }
+        }
+
+        for (i, line) in self.linedefs.iter().enumerate() {
+            for v in [line.v1, line.v2] {
+                if v as usize >= nv {
+                    out.push(MapDefect::new(
+                        Linedef,
+                        i,
+                        format!("vertex {v} out of range ({nv} vertices)"),
+                    ));
+                }
+            }
+            match line.right_sidedef {
+                None => out.push(MapDefect::new(Linedef, i, "no front sidedef")),
+                Some(sd) if sd as usize >= nsd => out.push(MapDefect::new(
+                    Linedef,
+                    i,
+                    format!("front sidedef {sd} out of range ({nsd} sidedefs)"),
+                )),
+                Some(_) => {}
+            }
+            if let Some(sd) = line.left_sidedef {
+                if sd as usize >= nsd {
+                    out.push(MapDefect::new(
+                        Linedef,
+                        i,
+                        format!("back sidedef {sd} out of range ({nsd} sidedefs)"),
+                    ));
+                }
+            }
+        }
+
+        for (i, side) in self.sidedefs.iter().enumerate() {
+            if side.sector as usize >= nsec {
+                out.push(MapDefect::new(
+                    Sidedef,
+                    i,
+                    format!("sector {} out of range ({nsec} sectors)", side.sector),
+                ));
+            }
+        }
+
+        for (i, seg) in self.segs.iter().enumerate() {
+            for v in [seg.v1, seg.v2] {
+                if v as usize >= nv {
+                    out.push(MapDefect::new(
+                        Seg,
+                        i,
+                        format!("vertex {v} out of range ({nv} vertices)"),
+                    ));
+                }
+            }
+            let Some(line) = self.linedefs.get(seg.linedef as usize) else {
+                out.push(MapDefect::new(
+                    Seg,
+                    i,
+                    format!("linedef {} out of range ({nl} linedefs)", seg.linedef),
+                ));
+                continue;
+            };
+            if seg.dir != 0 && line.left_sidedef.is_none() {
+                out.push(MapDefect::new(
+                    Seg,
+                    i,
+                    format!(
+                        "on the back of linedef {}, which has no back sidedef",
+                        seg.linedef
+                    ),
+                ));
+            }
+        }
+
+        for (i, ss) in self.subsectors.iter().enumerate() {
+            let end = ss.first_line as usize + ss.num_lines as usize;
+            if ss.num_lines == 0 {
+                out.push(MapDefect::new(Subsector, i, "no segs"));
+            } else if end > nseg {
+                out.push(MapDefect::new(
+                    Subsector,
+                    i,
+                    format!("segs {}‥{end} out of range ({nseg} segs)", ss.first_line),
+                ));
+            }
+        }
+
+        for (i, _, message) in self.bad_node_children() {
+            out.push(MapDefect::new(Node, i, message));
+        }
+
+        for (cell, lines) in self.blockmap.lines.iter().enumerate() {
+            if let Some(&bad) = lines.iter().find(|&&l| l as usize >= nl) {
+                out.push(MapDefect::new(
+                    Blockmap,
+                    cell,
+                    format!("linedef {bad} out of range ({nl} linedefs)"),
+                ));
+            }
+        }
+
+        if let Some(bounds) = self.vertex_bounds() {
+            for (i, thing) in self.things.iter().enumerate() {
+                let p = thing.pos;
+                if p.cmplt(bounds.min).any() || p.cmpgt(bounds.max).any() {
+                    out.push(MapDefect::new(
+                        Thing,
+                        i,
+                        format!("at ({}, {}), outside the map", p.x, p.y),
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// [`validate`](Self::validate), then point every bad reference at
+    /// something harmless: vertex 0, subsector 0, a dummy sidedef or
+    /// sector appended to the map.  Segs on a missing back side are
+    /// turned to the front; things outside the map stay where they are.
+    /// Returns what was found.  `validate` still reports those things, a
+    /// subsector without segs (now in the dummy sector) and any empty
+    /// lump, which can't be repaired.
+    pub fn repair(&mut self) -> Vec<MapDefect> {
+        let defects = self.validate();
+        if defects.is_empty() {
+            return defects;
+        }
+        let nv = self.vertices.len() as u32;
+        let nl = self.linedefs.len();
+        let nss = self.subsectors.len() as u32;
+        let mut dummy_sector = None;
+        let mut dummy_side = None;
+
+        for i in 0..self.sidedefs.len() {
+            if self.sidedefs[i].sector as usize >= self.sectors.len() {
+                self.sidedefs[i].sector = self.dummy_sector(&mut dummy_sector);
+            }
+        }
+
+        for i in 0..nl {
+            let nsd = self.sidedefs.len();
+            let line = &mut self.linedefs[i];
+            let mut moved = false;
+            for v in [&mut line.v1, &mut line.v2] {
+                if *v >= nv && nv > 0 {
+                    *v = 0;
+                    moved = true;
+                }
+            }
+            if line.left_sidedef.is_some_and(|sd| sd as usize >= nsd) {
+                line.left_sidedef = None;
+                line.flags.remove(LinedefFlags::TWO_SIDED);
+            }
+            if line.right_sidedef.is_none_or(|sd| sd as usize >= nsd) {
+                let sd = self.dummy_sidedef(&mut dummy_side, &mut dummy_sector);
+                self.linedefs[i].right_sidedef = Some(sd);
+            }
+            if moved {
+                let line = &mut self.linedefs[i];
+                let (a, b) = (
+                    self.vertices[line.v1 as usize].pos,
+                    self.vertices[line.v2 as usize].pos,
+                );
+                line.bbox = Aabb {
+                    min: a.min(b),
+                    max: a.max(b),
+                };
+            }
+        }
+
+        for seg in &mut self.segs {
+            for v in [&mut seg.v1, &mut seg.v2] {
+                if *v >= nv && nv > 0 {
+                    *v = 0;
+                }
+            }
+            if seg.linedef as usize >= nl && nl > 0 {
+                seg.linedef = 0;
+            }
+            if seg.dir != 0
+                && self
+                    .linedefs
+                    .get(seg.linedef as usize)
+                    .is_some_and(|l| l.left_sidedef.is_none())
+            {
+                seg.dir = 0;
+            }
+        }
+
+        let nseg = self.segs.len() as u32;
+        for i in 0..self.subsectors.len() {
+            let ss = &mut self.subsectors[i];
+            ss.first_line = ss.first_line.min(nseg);
+            ss.num_lines = ss.num_lines.min(nseg - ss.first_line);
+            if ss.num_lines == 0 {
+                // no seg to take a sector from
+                self.subsectors[i].sector = self.dummy_sector(&mut dummy_sector);
+            }
+        }
+
+        if nss > 0 {
+            for (i, side, _) in self.bad_node_children() {
+                self.nodes[i].child[side] = SUBSECTOR_BIT;
+            }
+        }
+
+        let nl = self.linedefs.len();
+        for lines in &mut self.blockmap.lines {
+            lines.retain(|&l| (l as usize) < nl);
+        }
+        defects
+    }
+
+    /// Node children the BSP walks can't follow, as `(node, side,
+    /// message)`: subsectors and nodes out of range, and nodes a
+    /// depth-first walk from the root reaches a second time – a loop, or
+    /// a subtree hung under two parents.  Nodes the root doesn't lead to
+    /// are never walked and aren't checked.
+    fn bad_node_children(&self) -> Vec<(usize, usize, String)> {
+        let (nn, nss) = (self.nodes.len(), self.subsectors.len());
+        let mut out = Vec::new();
+        let Some(root) = nn.checked_sub(1) else {
+            return out;
+        };
+        let mut seen = vec![false; nn];
+        seen[root] = true;
+        let mut stack = vec![root];
+        while let Some(i) = stack.pop() {
+            for (side, &child) in self.nodes[i].child.iter().enumerate() {
+                let index = (child & CHILD_MASK) as usize;
+                if child & SUBSECTOR_BIT != 0 {
+                    if index >= nss {
+                        out.push((
+                            i,
+                            side,
+                            format!("subsector {index} out of range ({nss} subsectors)"),
+                        ));
+                    }
+                } else if index >= nn {
+                    out.push((
+                        i,
+                        side,
+                        format!("child node {index} out of range ({nn} nodes)"),
+                    ));
+                } else if seen[index] {
+                    out.push((
+                        i,
+                        side,
+                        format!("child node {index} is already in the tree"),
+                    ));
+                } else {
+                    seen[index] = true;
+                    stack.push(index);
+                }
+            }
+        }
+        out.sort_by_key(|&(i, side, _)| (i, side));
+        out
+    }
+
+    /// Box around every vertex; `None` for a map without any.
+    fn vertex_bounds(&self) -> Option<Aabb> {
+        let first = self.vertices.first()?.pos;
+        Some(self.vertices.iter().fold(
+            Aabb {
+                min: first,
+                max: first,
+            },
+            |b, v| Aabb {
+                min: b.min.min(v.pos),
+                max: b.max.max(v.pos),
+            },
+        ))
+    }
+
+    /// The closed, lightless sector bad references are pointed at,
+    /// appended on first use.
+    fn dummy_sector(&mut self, cached: &mut Option<SectorId>) -> SectorId {
+        *cached.get_or_insert_with(|| {
+            self.sectors.push(Sector {
+                floor_h: 0.0,
+                ceil_h: 0.0,
+                floor_tex: NO_TEXTURE,
+                ceil_tex: NO_TEXTURE,
+                light: 0.0,
+                special: 0,
+                tag: 0,
+                is_sky_ceiling: false,
+                is_sky_floor: false,
+            });
+            (self.sectors.len() - 1) as SectorId
+        })
+    }
+
+    /// A sidedef facing the dummy sector, showing the checkerboard so the
+    /// broken wall is easy to spot.
+    fn dummy_sidedef(
+        &mut self,
+        cached: &mut Option<SidedefId>,
+        sector: &mut Option<SectorId>,
+    ) -> SidedefId {
+        if let Some(sd) = *cached {
+            return sd;
+        }
+        let sector = self.dummy_sector(sector);
+        self.sidedefs.push(Sidedef {
+            x_off: 0.0,
+            y_off: 0.0,
+            upper: NO_TEXTURE,
+            lower: NO_TEXTURE,
+            middle: NO_TEXTURE,
+            sector,
+        });
+        let sd = (self.sidedefs.len() - 1) as SidedefId;
+        *cached = Some(sd);
+        sd
+    }
+}
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::testmap;
+    use glam::Vec2;
+
+    /// The map with a bit of everything broken, and the index of the seg
+    /// put on a missing back side.
+    fn broken() -> (Level, usize) {
+        let mut level = level();
+        level.sidedefs[1].sector = 99;
+        level.linedefs[0].right_sidedef = None;
+        level.linedefs[1].v2 = 5000;
+        let one_sided = level
+            .segs
+            .iter()
+            .position(|s| level.linedefs[s.linedef as usize].left_sidedef.is_none())
+            .unwrap();
+        level.segs[one_sided].dir = 1;
+        level.segs[0].linedef = 900;
+        level.subsectors[0].num_lines = 0;
+        level.nodes[0].child[1] = 77;
+        level.blockmap.lines[0].push(600);
+        level.things.push(crate::world::Thing {
+            pos: Vec2::new(-500.0, 64.0),
+            angle: crate::world::Angle::ZERO,
+            type_id: 1,
+            skills: 7,
+            is_deaf: false,
+            multiplayer: false,
+            sub_sector: 0,
+        });
+        (level, one_sided)
+    }
+
+    fn level() -> Level {
+        testmap::three_rooms(testmap::Middle::Open {
+            floor: 0.0,
+            ceil: 128.0,
+        })
+    }
+
+    #[test]
+    fn sound_map_has_no_defects() {
+        let mut level = level();
+        assert_eq!(level.validate(), []);
+        assert_eq!(level.repair(), []);
+    }
+
+    #[test]
+    fn broken_references_are_reported_and_repaired() {
+        let (mut level, one_sided) = broken();
+        let (nsec, nsd) = (level.sectors.len(), level.sidedefs.len());
+
+        let defects = level.validate();
+        let found = |item, index| defects.iter().any(|d| d.item == item && d.index == index);
+        assert!(found(MapItem::Sidedef, 1), "{defects:#?}");
+        assert!(found(MapItem::Linedef, 0) && found(MapItem::Linedef, 1));
+        assert!(found(MapItem::Seg, one_sided) && found(MapItem::Seg, 0));
+        assert!(found(MapItem::Subsector, 0) && found(MapItem::Node, 0));
+        assert!(found(MapItem::Blockmap, 0));
+        assert!(found(MapItem::Thing, level.things.len() - 1));
+        let message = defects.iter().find(|d| d.item == MapItem::Sidedef).unwrap();
+        assert_eq!(
+            message.to_string(),
+            "sidedef 1: sector 99 out of range (3 sectors)"
+        );
+
+        assert_eq!(level.repair(), defects);
+        // what's left is harmless: the stray thing and the empty subsector
+        let left: Vec<_> = level.validate().iter().map(|d| d.item).collect();
+        assert_eq!(left, [MapItem::Subsector, MapItem::Thing]);
+
+        // one dummy sector and sidedef serve every repair
+        assert_eq!(
+            (level.sectors.len(), level.sidedefs.len()),
+            (nsec + 1, nsd + 1)
+        );
+        assert_eq!(level.sidedefs[1].sector as usize, nsec);
+        assert_eq!(level.linedefs[0].right_sidedef, Some(nsd as SidedefId));
+        assert_eq!(level.subsectors[0].sector as usize, nsec);
+        assert_eq!(level.segs[one_sided].dir, 0);
+        assert_eq!(level.nodes[0].child[1], SUBSECTOR_BIT);
+    }
+
+    #[test]
+    fn bsp_loops_are_caught_on_load_and_survived_at_run_time() {
+        use crate::world::Camera;
+        use glam::Vec3;
+
+        // west of x=128 leads back up to the root
+        let mut level = level();
+        level.nodes[0].child[1] = 1;
+        let defects = level.validate();
+        assert_eq!(
+            defects,
+            [MapDefect::new(
+                MapItem::Node,
+                0,
+                "child node 1 is already in the tree"
+            )]
+        );
+
+        // walked as it is, nothing hangs
+        let west = Vec2::new(64.0, 64.0);
+        assert_eq!(level.locate_subsector(west), 0);
+        let camera = Camera::new(west.extend(41.0), 0.0, 90_f32.to_radians());
+        let mut active = Vec::new();
+        level.fill_active_subsectors(&camera, &mut active);
+        assert!(!level.check_sight(west.extend(41.0), Vec3::new(200.0, 64.0, 41.0)));
+
+        assert_eq!(level.repair(), defects);
+        assert_eq!(level.validate(), []);
+        assert_eq!(level.locate_subsector(west), 0);
+        assert!(level.check_sight(west.extend(41.0), Vec3::new(200.0, 64.0, 41.0)));
+    }
+
+    #[test]
+    fn repaired_map_renders_and_runs() {
+        use crate::renderer::{Renderer, Software};
+        use crate::sim::{InputCmd, TicRunner};
+        use crate::world::{Camera, TextureBank};
+        use glam::Vec3;
+
+        let (mut level, _) = broken();
+        level.repair();
+        level.finalise_bsp();
+
+        let bank = TextureBank::default_with_checker();
+        let mut sim = TicRunner::new(&level);
+        for thing in level.things.clone() {
+            sim.spawn_map_thing(&level, &thing);
+        }
+        for _ in 0..35 {
+            sim.run_tic(&mut level, InputCmd::default());
+        }
+        for x in [20.0, 100.0, 200.0] {
+            let camera = Camera::new(Vec3::new(x, 64.0, 41.0), 0.0, 90_f32.to_radians());
+            let mut active = Vec::new();
+            level.fill_active_subsectors(&camera, &mut active);
+            let mut sw = Software {
+                record: Some(Vec::new()),
+                ..Default::default()
+            };
+            sw.begin_frame(160, 100);
+            sw.draw_level(&active, &level, &sim, &camera, &bank);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! A tiny synthetic IWAD for the integration tests, so they run without
+//! `assets/doom.wad`.
+
+// each test binary uses its own share of these
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+pub fn le16(v: &[i16]) -> Vec<u8> {
+    v.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+/// A WAD file with `magic` holding `lumps` in order.
+pub fn image(magic: &[u8; 4], lumps: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let data: usize = lumps.iter().map(|(_, b)| b.len()).sum();
+    let mut bytes = magic.to_vec();
+    bytes.extend((lumps.len() as u32).to_le_bytes());
+    bytes.extend((12 + data as u32).to_le_bytes());
+    for (_, b) in lumps {
+        bytes.extend(b);
+    }
+    let mut offset = 12u32;
+    for (name, b) in lumps {
+        let mut raw = [0u8; 8];
+        raw[..name.len()].copy_from_slice(name.as_bytes());
+        bytes.extend(offset.to_le_bytes());
+        bytes.extend((b.len() as u32).to_le_bytes());
+        bytes.extend(raw);
+        offset += b.len() as u32;
+    }
+    bytes
+}
+
+/// The lumps of a 256×256 room called `name` with the player 1 start
+/// at (64, 128) and the player 2 start at (64, 64), both facing east.
+pub fn square_map(name: &'static str) -> Vec<(&'static str, Vec<u8>)> {
+    let vertexes = le16(&[0, 0, 256, 0, 256, 256, 0, 256]);
+    let mut linedefs = Vec::new();
+    for (v1, v2) in [(1, 0), (2, 1), (3, 2), (0, 3)] {
+        linedefs.extend(le16(&[v1, v2, 1, 0, 0, 0, -1]));
+    }
+    let mut sidedef = le16(&[0, 0]);
+    sidedef.extend(b"-\0\0\0\0\0\0\0-\0\0\0\0\0\0\0STARTAN3");
+    sidedef.extend(le16(&[0]));
+    let mut segs = Vec::new();
+    for (line, (v1, v2, angle)) in [(1, 0, 0x8000u16), (2, 1, 0xC000), (3, 2, 0), (0, 3, 0x4000)]
+        .into_iter()
+        .enumerate()
+    {
+        segs.extend(le16(&[v1, v2, angle as i16, line as i16, 0, 0]));
+    }
+    let mut nodes = le16(&[0, 0, 256, 0, 256, 0, 0, 256, 256, 0, 0, 256]);
+    nodes.extend(le16(&[0x8000u16 as i16, 0x8000u16 as i16]));
+    let mut sector = le16(&[0, 128]);
+    sector.extend(b"FLOOR4_8CEIL3_5\0");
+    sector.extend(le16(&[160, 0, 0]));
+    vec![
+        (name, Vec::new()),
+        ("THINGS", le16(&[64, 128, 0, 1, 7, 64, 64, 0, 2, 7])),
+        ("LINEDEFS", linedefs),
+        ("SIDEDEFS", sidedef),
+        ("VERTEXES", vertexes),
+        ("SEGS", segs),
+        ("SSECTORS", le16(&[4, 0])),
+        ("NODES", nodes),
+        ("SECTORS", sector),
+        ("REJECT", Vec::new()),
+    ]
+}
+
+/// A 64×64 patch of vertical stripes.
+pub fn stripes() -> Vec<u8> {
+    let mut patch = le16(&[64, 64, 0, 0]);
+    for x in 0..64u32 {
+        patch.extend((8 + 64 * 4 + x * 69).to_le_bytes());
+    }
+    for x in 0..64u8 {
+        patch.extend([0, 64, 0]);
+        patch.extend([x * 4; 64]);
+        patch.extend([0, 0xFF]);
+    }
+    patch
+}
+
+/// An IWAD with a grey ramp palette, identity colormaps, the textures
+/// `square_map` uses and E1M1.
+pub fn iwad() -> Vec<u8> {
+    let playpal: Vec<u8> = (0..=255u8).flat_map(|i| [i, i, i]).collect();
+    let colormap: Vec<u8> = (0..34).flat_map(|_| 0..=255u8).collect();
+    let mut pnames = 1u32.to_le_bytes().to_vec();
+    pnames.extend(b"WALL\0\0\0\0");
+    // one texture: STARTAN3, 64×64, patch 0 at (0, 0)
+    let mut texture1 = le16(&[1, 0, 8, 0]);
+    texture1.extend(b"STARTAN3");
+    texture1.extend(le16(&[0, 0, 64, 64, 0, 0, 1, 0, 0, 0, 1, 0]));
+    let mut lumps = vec![
+        ("PLAYPAL", playpal),
+        ("COLORMAP", colormap),
+        ("TEXTURE1", texture1),
+        ("PNAMES", pnames),
+        ("S_START", Vec::new()),
+        ("S_END", Vec::new()),
+        ("P_START", Vec::new()),
+        ("WALL", stripes()),
+        ("P_END", Vec::new()),
+        ("F_START", Vec::new()),
+        ("FLOOR4_8", vec![96; 4096]),
+        ("CEIL3_5", vec![160; 4096]),
+        ("F_END", Vec::new()),
+    ];
+    lumps.extend(square_map("E1M1"));
+    image(b"IWAD", &lumps)
+}
+
+/// Write `bytes` to a file in the temp dir unique to this test run.
+pub fn temp_wad(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("yadoom-{}-{name}.wad", std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
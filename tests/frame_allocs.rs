@@ -0,0 +1,89 @@
+//! The software renderer draws a steady view without touching the heap.
+//!
+//! A counting global allocator watches this thread while E1M1 is drawn
+//! from the player start 100 times; after the first few frames have grown
+//! every buffer, a frame must not allocate at all.  Skipped without
+//! `assets/doom.wad`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::path::PathBuf;
+
+use yadoom_rs::{
+    defs,
+    renderer::{Renderer, Software},
+    sim::TicRunner,
+    wad::{Wad, load_level},
+    world::{Angle, Camera, TextureBank},
+};
+
+/// Frames allowed to allocate while the buffers grow.
+const WARM_UP: usize = 3;
+
+/// Counts allocations per thread, so the harness's own threads don't show
+/// up in the count.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // not while the thread is being torn down
+        let _ = ALLOCS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static COUNTING: CountingAlloc = CountingAlloc;
+
+#[test]
+fn e1m1_frames_stop_allocating() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join("doom.wad");
+    let Ok(wad) = Wad::from_file(&path) else {
+        eprintln!("skipping: {} not found", path.display());
+        return;
+    };
+    let mut bank = TextureBank::default_with_checker();
+    let mut level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+    level.finalise_bsp();
+    let mut sim = TicRunner::new(&level);
+    for thing in &level.things {
+        if let Some(info) = defs::by_doomednum(thing.type_id) {
+            sim.spawn_mobj(
+                &level,
+                info,
+                thing.pos.x,
+                thing.pos.y,
+                thing.angle,
+                thing.sub_sector,
+            );
+        }
+    }
+
+    let start = level.things.iter().find(|t| t.type_id == 1).unwrap();
+    let eye = start.pos.extend(level.floor_height_at(start.pos) + 41.0);
+    let camera = Camera::new(eye, start.angle + Angle::ANG45, 90_f32.to_radians());
+
+    let mut sw = Software::default();
+    let mut active = Vec::new();
+    for frame in 0..100 {
+        let before = ALLOCS.with(Cell::get);
+        sw.begin_frame(640, 400);
+        level.fill_active_subsectors(&camera, &mut active);
+        sw.draw_level(&active, &level, &sim, &camera, &bank);
+        sw.end_frame(|fb, _, _| assert!(!fb.is_empty()));
+        let made = ALLOCS.with(Cell::get) - before;
+        if frame >= WARM_UP {
+            assert_eq!(made, 0, "frame {frame} allocated {made} times");
+        }
+    }
+}
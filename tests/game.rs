@@ -0,0 +1,246 @@
+//! Drive the public `Game` façade the way an embedder would: load a WAD
+//! from disk, run tics, render a frame, change maps.
+//!
+//! The WAD is a tiny synthetic one written to the temp dir, so this runs
+//! without `assets/doom.wad`.
+
+mod common;
+
+use common::{image, iwad, square_map, temp_wad};
+use yadoom_rs::{
+    Game, GameConfig,
+    console::Console,
+    game::{GameError, Phase},
+    renderer::Software,
+    sim::{Health, InputCmd, PlayerStatus, Position},
+    world::CameraMode,
+};
+
+fn player_pos(game: &Game) -> Position {
+    let player = game.sim().player().expect("no player");
+    *game.sim().world().get::<&Position>(player).unwrap()
+}
+
+#[test]
+fn runs_renders_and_changes_maps() {
+    let wad_path = temp_wad("iwad", &iwad());
+    let mut game = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        ..GameConfig::default()
+    })
+    .unwrap();
+    assert_eq!(game.level().name, "E1M1");
+    assert!(matches!(game.phase(), Phase::Level));
+
+    let start = player_pos(&game);
+    let forward = InputCmd {
+        forward: 1.0,
+        ..InputCmd::default()
+    };
+    for _ in 0..100 {
+        game.tick(forward);
+    }
+    game.drain_events().for_each(drop);
+    assert_eq!(game.sim().gametic(), 100);
+    let end = player_pos(&game);
+    assert!(end.0.x > start.0.x + 64.0, "{start:?} → {end:?}");
+    assert!(end.0.x <= 240.0, "walked through the east wall: {end:?}");
+
+    let mut renderer = Software::default();
+    let mut frame = Vec::new();
+    game.render(&mut renderer, 320, 200, |fb, w, h| {
+        assert_eq!((w, h), (320, 200));
+        frame.extend_from_slice(fb);
+    });
+    assert_eq!(frame.len(), 320 * 200);
+    assert!(frame.iter().any(|&p| p != frame[0]), "blank frame");
+    assert_eq!(game.camera().pos.x, end.0.x);
+
+    assert!(matches!(
+        game.change_map("E1M2"),
+        Err(GameError::NoSuchMap(_))
+    ));
+
+    // a PWAD adds the map, so it can be started straight away
+    let pwad_path = temp_wad("pwad", &image(b"PWAD", &square_map("E1M2")));
+    let mut game = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        pwads: vec![pwad_path.clone()],
+        map: Some("e1m2".into()),
+        ..GameConfig::default()
+    })
+    .unwrap();
+    assert_eq!(game.level().name, "E1M2");
+    game.change_map("E1M1").unwrap();
+    assert_eq!(game.level().name, "E1M1");
+    assert_eq!(game.sim().gametic(), 0);
+
+    std::fs::remove_file(wad_path).ok();
+    std::fs::remove_file(pwad_path).ok();
+}
+
+#[test]
+fn two_players_walk_and_share_the_screen() {
+    let wad_path = temp_wad("split", &iwad());
+    let mut game = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        players: 2,
+        ..GameConfig::default()
+    })
+    .unwrap();
+    let [one, two] = game.sim().players() else {
+        panic!("{:?}", game.sim().players());
+    };
+    let (one, two) = (*one, *two);
+    let pos = |game: &Game, p| game.sim().world().get::<&Position>(p).unwrap().0;
+
+    let mut walk = InputCmd {
+        forward: 1.0,
+        ..InputCmd::default()
+    };
+    let mut stand = InputCmd::default();
+    assert_eq!(game.run_player_tics(&mut [&mut stand, &mut walk], 35), 35);
+    assert_eq!(pos(&game, one), glam::Vec2::new(64.0, 128.0));
+    assert!(pos(&game, two).x > 64.0 + 64.0);
+
+    let mut renderer = Software::default();
+    let mut frame = Vec::new();
+    game.render_split(&mut renderer, 320, 200, |fb, _, _| {
+        frame.extend_from_slice(fb)
+    });
+    // player 2 stands closer to the east wall, so the halves differ
+    let (top, bottom) = frame.split_at(320 * 100);
+    assert_ne!(top, bottom);
+    assert!(bottom.iter().any(|&p| p != bottom[0]), "blank bottom half");
+    assert_eq!(game.camera().pos.x, 64.0);
+
+    std::fs::remove_file(wad_path).ok();
+}
+
+#[test]
+fn free_camera_flies_off_without_the_player() {
+    let wad_path = temp_wad("free-cam", &iwad());
+    let mut game = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        ..GameConfig::default()
+    })
+    .unwrap();
+    let mut renderer = Software::default();
+    game.render(&mut renderer, 320, 200, |_, _, _| {});
+    let eye = game.camera().pos;
+
+    game.set_camera_mode(CameraMode::Free);
+    let mut forward = InputCmd {
+        forward: 1.0,
+        ..InputCmd::default()
+    };
+    game.fly_camera(&mut forward, 30);
+    game.tick(InputCmd::default());
+    game.render(&mut renderer, 320, 200, |_, _, _| {});
+    // through the east wall and beyond; the player hasn't moved
+    assert!(game.camera().pos.x > 256.0, "{:?}", game.camera().pos);
+    assert_eq!(game.camera().pos.y, eye.y);
+    assert_eq!(player_pos(&game).0.x, eye.x);
+
+    // the chase view sits behind the player, inside the room
+    game.set_camera_mode(CameraMode::CHASE);
+    game.render(&mut renderer, 320, 200, |_, _, _| {});
+    let cam = game.camera().pos;
+    assert!(cam.x < eye.x && cam.x > 0.0, "{cam:?}");
+
+    std::fs::remove_file(wad_path).ok();
+}
+
+#[test]
+fn dehacked_lump_and_patch_files_apply_in_order() {
+    let wad_path = temp_wad("deh-iwad", &iwad());
+    let lump = b"Misc 0\nInitial Health = 150\nInitial Bullets = 20\n".to_vec();
+    let pwad_path = temp_wad("deh-pwad", &image(b"PWAD", &[("DEHACKED", lump)]));
+    let deh_path = std::env::temp_dir().join(format!("yadoom-{}.deh", std::process::id()));
+    std::fs::write(&deh_path, "Misc 0\nInitial Bullets = 75\n").unwrap();
+
+    let game = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        pwads: vec![pwad_path.clone()],
+        deh: vec![deh_path.clone()],
+        ..GameConfig::default()
+    })
+    .unwrap();
+    let player = game.sim().player().unwrap();
+    let world = game.sim().world();
+    assert_eq!(world.get::<&Health>(player).unwrap().0, 150);
+    // the file comes after the lump
+    assert_eq!(world.get::<&PlayerStatus>(player).unwrap().ammo[0], 75);
+
+    std::fs::write(&deh_path, "Thing 9999\n").unwrap();
+    let err = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        deh: vec![deh_path.clone()],
+        ..GameConfig::default()
+    });
+    assert!(matches!(err, Err(GameError::Deh { .. })));
+
+    for path in [wad_path, pwad_path, deh_path] {
+        std::fs::remove_file(path).ok();
+    }
+}
+
+#[test]
+fn dying_to_a_summoned_barrel_and_starting_over() {
+    let wad_path = temp_wad("barrel", &iwad());
+    let mut game = Game::new(GameConfig {
+        wad_path: wad_path.clone(),
+        ..GameConfig::default()
+    })
+    .unwrap();
+    let start = player_pos(&game);
+    let things = game.sim().world().len();
+    Console::new().execute(game.state_mut(), "summon barrel");
+
+    // walk up to it and shoot it from point blank
+    let shoot = InputCmd {
+        forward: 1.0,
+        fire: true,
+        ..InputCmd::default()
+    };
+    let status = |game: &Game| {
+        let player = game.sim().player().unwrap();
+        *game.sim().world().get::<&PlayerStatus>(player).unwrap()
+    };
+    let mut tics = 0;
+    while status(&game).health > 0 {
+        game.tick(shoot);
+        tics += 1;
+        assert!(tics < 350, "the barrel never went off");
+    }
+    let player = game.sim().player().unwrap();
+    assert!(game.sim().world().get::<&Health>(player).unwrap().0 <= 0);
+    assert!(status(&game).damage_count > 0, "no red flash");
+
+    // the view sinks to the floor and nothing but use does anything; the
+    // blast has thrown the body back, and it slides to a stop
+    for _ in 0..100 {
+        game.tick(shoot);
+    }
+    let dead_at = player_pos(&game);
+    assert!(dead_at.0.x < 100.0, "{dead_at:?}");
+    for _ in 0..10 {
+        game.tick(shoot);
+    }
+    // walking would have gone a long way in that time
+    let crept = player_pos(&game).0.distance(dead_at.0);
+    assert!(crept < 0.1, "moved {crept}");
+    assert_eq!(status(&game).view_height, 6.0);
+    assert!(matches!(game.phase(), Phase::Level));
+
+    game.tick(InputCmd {
+        use_act: true,
+        ..InputCmd::default()
+    });
+    assert_eq!(game.sim().gametic(), 0, "the map didn't start over");
+    let st = status(&game);
+    assert_eq!((st.health, st.ammo[0], st.damage_count), (100, 50, 0));
+    assert_eq!(player_pos(&game).0, start.0);
+    assert_eq!(game.sim().world().len(), things, "the barrel came back");
+    std::fs::remove_file(wad_path).ok();
+}
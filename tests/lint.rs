@@ -0,0 +1,39 @@
+//! Run `yadoom-lint` the way a mapper would.
+
+use std::{path::PathBuf, process::Command};
+
+fn lint(wad: &PathBuf) -> (bool, String) {
+    let out = Command::new(env!("CARGO_BIN_EXE_yadoom-lint"))
+        .arg(wad)
+        .output()
+        .unwrap();
+    (
+        out.status.success(),
+        String::from_utf8_lossy(&out.stdout).into_owned(),
+    )
+}
+
+#[test]
+fn doom_wad_has_no_hard_errors() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join("doom.wad");
+    if !path.exists() {
+        eprintln!("skipping: {} not found", path.display());
+        return;
+    }
+    let (ok, report) = lint(&path);
+    assert!(ok, "{report}");
+    assert!(report.starts_with("E1M1: "), "{report}");
+    assert!(!report.contains("FAILED"), "{report}");
+    assert!(report.trim_end().ends_with(" 0 failed"), "{report}");
+}
+
+#[test]
+fn unreadable_wads_fail() {
+    let path = std::env::temp_dir().join(format!("yadoom-lint-{}.wad", std::process::id()));
+    std::fs::write(&path, b"not a wad").unwrap();
+    let (ok, _) = lint(&path);
+    std::fs::remove_file(&path).ok();
+    assert!(!ok);
+}
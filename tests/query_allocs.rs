@@ -0,0 +1,96 @@
+//! The blockmap walks and the sector, wall and tag queries on `Level`
+//! don't touch the heap.
+//!
+//! A counting global allocator watches this thread while the queries run
+//! over the synthetic square room a thousand times.  It lives in its own
+//! test binary so the library's unit tests keep the system allocator.
+
+mod common;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use common::{iwad, temp_wad};
+use glam::Vec2;
+use yadoom_rs::{
+    wad::{Wad, load_level},
+    world::{Aabb, BlockmapQuery, Level, TextureBank},
+};
+
+/// Counts allocations per thread, so tests running alongside don't show
+/// up in each other's counts.
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // not while the thread is being torn down
+        let _ = ALLOCS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static COUNTING: CountingAlloc = CountingAlloc;
+
+fn square_room() -> Level {
+    let wad = Wad::from_file(temp_wad("query-allocs", &iwad())).unwrap();
+    let mut bank = TextureBank::default_with_checker();
+    let mut level = load_level(&wad, wad.level_indices()[0], &mut bank).unwrap();
+    level.finalise_bsp();
+    level
+}
+
+/// Heap allocations `f` makes on this thread.
+fn allocs(f: impl FnOnce()) -> usize {
+    let before = ALLOCS.with(Cell::get);
+    f();
+    ALLOCS.with(Cell::get) - before
+}
+
+#[test]
+fn block_lines_iter_does_not_allocate() {
+    let level = square_room();
+    let query = BlockmapQuery::default();
+    let everything = Aabb {
+        min: Vec2::splat(-16.0),
+        max: Vec2::splat(272.0),
+    };
+    let count = |query: &BlockmapQuery| {
+        let mut n = 0;
+        level.block_lines_iter(query, everything, |_| {
+            n += 1;
+            true
+        });
+        n
+    };
+    assert_eq!(count(&query), level.linedefs.len());
+
+    let made = allocs(|| {
+        for _ in 0..1000 {
+            assert_eq!(count(&query), level.linedefs.len());
+        }
+    });
+    assert_eq!(made, 0);
+}
+
+#[test]
+fn sector_queries_do_not_allocate() {
+    let level = square_room();
+    let mut n = 0;
+    let made = allocs(|| {
+        for _ in 0..1000 {
+            n += level.nearest_wall(Vec2::new(10.0, 128.0), 64.0).is_some() as usize;
+            n += level.sectors_with_tag(0).count() + level.linedefs_of_sector(0).count();
+        }
+    });
+    assert_eq!(made, 0);
+    assert_eq!(n, 1000 * 6);
+}
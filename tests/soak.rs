@@ -0,0 +1,188 @@
+//! Thrash the player around every map of `assets/doom.wad` with random
+//! input and check the sim stays sane: no panics, no NaNs, the player's
+//! subsector where the BSP says it is, and every thing between its floor
+//! and ceiling.
+//!
+//! ```text
+//! cargo test --release --test soak -- --ignored
+//! YADOOM_SOAK_SEED=1234 YADOOM_SOAK_TICS=20000 cargo test --release --test soak -- --ignored
+//! ```
+//!
+//! `YADOOM_SOAK_WAD` points it at another IWAD.
+//!
+//! A failure names the map, the seed and the tic, so it can be replayed.
+
+use std::{
+    env,
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::PathBuf,
+};
+
+use yadoom_rs::{
+    Game, GameConfig,
+    game::Phase,
+    sim::{InputCmd, InputSource, Position, Skill, Subsector, Velocity},
+    wad::Wad,
+};
+
+/// How far below its floor a thing may be, for float slop.
+const FLOOR_EPSILON: f32 = 0.01;
+
+/// Random commands in bursts: hold a direction for a while (which runs
+/// into walls and wedges into corners), with turns that are sometimes
+/// gentle and sometimes far past a full key press.
+struct Chaos {
+    state: u64,
+    held: InputCmd,
+    left: u32,
+}
+
+impl Chaos {
+    fn new(seed: u64) -> Self {
+        Self {
+            state: seed.max(1),
+            held: InputCmd::default(),
+            left: 0,
+        }
+    }
+
+    /// xorshift64.
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// In −1‥1.
+    fn unit(&mut self) -> f32 {
+        (self.next() % 2001) as f32 / 1000.0 - 1.0
+    }
+
+    fn chance(&mut self, one_in: u64) -> bool {
+        self.next().is_multiple_of(one_in)
+    }
+}
+
+impl InputSource for Chaos {
+    fn next_cmd(&mut self) -> Option<InputCmd> {
+        if self.left == 0 {
+            self.left = 1 + (self.next() % 70) as u32;
+            self.held = InputCmd {
+                forward: [1.0, 1.0, -1.0, 0.0][(self.next() % 4) as usize],
+                strafe: [0.0, 0.0, 1.0, -1.0][(self.next() % 4) as usize],
+                turn: self.unit() * 0.3,
+                run: self.chance(2),
+                ..InputCmd::default()
+            };
+        }
+        self.left -= 1;
+        let mut cmd = self.held;
+        if self.chance(8) {
+            cmd.turn = self.unit() * 8.0;
+        }
+        cmd.fire = self.chance(6);
+        cmd.use_act = self.chance(10);
+        if self.chance(50) {
+            cmd.weapon = Some(1 + (self.next() % 7) as u8);
+        }
+        Some(cmd)
+    }
+}
+
+/// What's wrong with the sim after a tic, if anything.
+fn check(game: &Game) -> Result<(), String> {
+    let (level, world) = (game.level(), game.sim().world());
+    for (e, (pos, vel)) in world.query::<(&Position, Option<&Velocity>)>().iter() {
+        let Position(xy, z) = *pos;
+        if !xy.is_finite() || !z.is_finite() {
+            return Err(format!("{e:?} is at {xy}, {z}"));
+        }
+        if let Some(Velocity(v)) = vel {
+            if !v.is_finite() {
+                return Err(format!("{e:?} at {xy} is moving at {v}"));
+            }
+        }
+        let sector = &level.sectors[level.sector_of_subsector(level.locate_subsector(xy)) as usize];
+        if z < sector.floor_h - FLOOR_EPSILON || z > sector.ceil_h {
+            return Err(format!(
+                "{e:?} at {xy} has z {z}, outside {}‥{}",
+                sector.floor_h, sector.ceil_h
+            ));
+        }
+    }
+    for &player in game.sim().players() {
+        let (Ok(pos), Ok(ss)) = (
+            world.get::<&Position>(player),
+            world.get::<&Subsector>(player),
+        ) else {
+            continue;
+        };
+        let found = level.locate_subsector(pos.0);
+        if ss.0 != found {
+            return Err(format!(
+                "player at {} thinks it's in subsector {} but is in {found}",
+                pos.0, ss.0
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[test]
+#[ignore = "minutes long; run with --ignored"]
+fn every_map_survives_random_input() {
+    let path = env::var_os("YADOOM_SOAK_WAD").map_or_else(
+        || {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("assets")
+                .join("doom.wad")
+        },
+        PathBuf::from,
+    );
+    if !path.exists() {
+        eprintln!("skipping: {} not found", path.display());
+        return;
+    }
+    let seed: u64 = env_or("YADOOM_SOAK_SEED", 0x5eed);
+    let tics: u32 = env_or("YADOOM_SOAK_TICS", 5000);
+
+    let mut game = Game::new(GameConfig {
+        wad_path: path,
+        skill: Skill::Hard,
+        ..GameConfig::default()
+    })
+    .unwrap();
+    let maps: Vec<String> = (game.wad().level_indices().iter())
+        .map(|&i| Wad::lump_name_str(&game.wad().lumps()[i].name).to_owned())
+        .collect();
+
+    for map in &maps {
+        game.change_map(map).unwrap();
+        let mut input = Chaos::new(seed);
+        for tic in 0..tics {
+            let fail = |why: &str| panic!("{map}, seed {seed}, tic {tic}: {why}");
+            if !matches!(game.phase(), Phase::Level) {
+                // exited through a switch it stumbled on
+                break;
+            }
+            if let Err(e) = catch_unwind(AssertUnwindSafe(|| game.run_tics(&mut input, 1))) {
+                let why = (e.downcast_ref::<String>().map(String::as_str))
+                    .or_else(|| e.downcast_ref::<&str>().copied())
+                    .unwrap_or("panicked");
+                fail(why);
+            }
+            if let Err(why) = check(&game) {
+                fail(&why);
+            }
+        }
+        eprintln!("{map}: ok");
+    }
+}
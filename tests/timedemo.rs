@@ -0,0 +1,52 @@
+//! `view_sw --timedemo` on the bundled demo: `tests/data/timedemo.ydm`,
+//! recorded with `--record` on the synthetic E1M1 of `common::iwad`.
+
+mod common;
+
+use std::{path::PathBuf, process::Command};
+
+use common::{iwad, temp_wad};
+
+fn timedemo(extra: &[&str]) -> String {
+    let wad = temp_wad("timedemo", &iwad());
+    let demo = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("data")
+        .join("timedemo.ydm");
+    let out = Command::new(env!("CARGO_BIN_EXE_view_sw"))
+        .arg(&wad)
+        .arg("--timedemo")
+        .arg(demo)
+        // vanilla's 320×200 keeps a debug build quick
+        .args(["--video-mode", "crispy"])
+        .args(extra)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+    assert!(
+        out.status.success(),
+        "{stdout}{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    stdout
+}
+
+/// The number after `"key":` in a line of flat-ish JSON.
+fn field(json: &str, key: &str) -> f64 {
+    let at = json.find(&format!("\"{key}\":")).unwrap() + key.len() + 3;
+    let end = json[at..].find([',', '}']).unwrap();
+    json[at..at + end].parse().unwrap()
+}
+
+#[test]
+fn timedemo_plays_every_tic_and_reports_fps() {
+    let report = timedemo(&[]);
+    assert!(report.starts_with("timed 350 gametics in "), "{report}");
+    assert!(report.contains("per frame:"), "{report}");
+
+    let json = timedemo(&["--json"]);
+    assert_eq!(json.lines().count(), 1, "{json}");
+    assert_eq!(field(&json, "tics"), 350.0);
+    assert!(field(&json, "fps") > 0.0, "{json}");
+    assert!(field(&json, "min") <= field(&json, "max"), "{json}");
+}
@@ -1,13 +1,16 @@
 //! gen_mobjinfo.rs - one-shot CLI to convert Doom’s original `info.c`
-//! into Rust source files (`states.rs` + `mobjinfo.rs`).
+//! and `sounds.c` into Rust source files (`states.rs`, `mobjinfo.rs`,
+//! `sound.rs`, …).
 //!
 //! USAGE:
 //! ```bash
 //! cargo run --bin gen_mobjinfo -- \
 //!     --info-c path/to/info.c \
+//!     [--sounds-c path/to/sounds.c] \
 //!     --out-dir ./src/defs
 //! ```
 
+use anyhow::{Context, bail};
 use clap::Parser;
 use regex::Regex;
 use std::collections::BTreeSet;
@@ -21,6 +24,10 @@ struct Opts {
     #[arg(long, value_name = "FILE")]
     info_c: PathBuf,
 
+    /// Path to `sounds.c`; defaults to the one next to `info.c`
+    #[arg(long, value_name = "FILE")]
+    sounds_c: Option<PathBuf>,
+
     /// Directory receiving the generated files
     #[arg(long, value_name = "DIR", default_value = "./src/defs")]
     out_dir: PathBuf,
@@ -31,6 +38,8 @@ struct Opts {
 struct StateRow {
     sprite: String,
     frame: u8,
+    /// `FF_FULLBRIGHT` was set on the frame number.
+    bright: bool,
     tics: i32,
     action: String,
     next_state: String,
@@ -39,6 +48,18 @@ struct StateRow {
     name: String,
 }
 
+/// One sfxinfo_t row of `S_sfx[]`.
+#[derive(Debug, Clone, PartialEq)]
+struct SfxRow {
+    name: String,
+    singularity: bool,
+    priority: i32,
+    /// `&S_sfx[sfx_pistol]` → `pistol`.
+    link: Option<String>,
+    pitch: i32,
+    volume: i32,
+}
+
 /// Minimal representation of an mobjinfo_t row.
 #[derive(Debug, Clone)]
 struct MobjRow {
@@ -98,23 +119,36 @@ fn main() -> anyhow::Result<()> {
 
     let mobj_rows = extract_mobj_rows(&mobj_body);
 
-    let sound_names: Vec<String> = {
-        let mut set = BTreeSet::new();
-        for m in &mobj_rows {
-            set.insert(m.activesound.clone());
-            set.insert(m.attacksound.clone());
-            set.insert(m.deathsound.clone());
-            set.insert(m.painsound.clone());
-            set.insert(m.seesound.clone());
+    let sounds_c = opts
+        .sounds_c
+        .clone()
+        .unwrap_or_else(|| opts.info_c.with_file_name("sounds.c"));
+    let sounds_c_src =
+        fs::read_to_string(&sounds_c).with_context(|| format!("reading {}", sounds_c.display()))?;
+    let sfx_body = extract_array_body("sfxinfo_t", "S_sfx", &sounds_c_src)
+        .context("S_sfx[] not found in sounds.c")?;
+    let sfx_rows = parse_sfx_rows(&sfx_body);
+
+    // every sound mobjinfo names must exist, or mobjinfo.rs won't build
+    for m in &mobj_rows {
+        for s in [
+            &m.activesound,
+            &m.attacksound,
+            &m.deathsound,
+            &m.painsound,
+            &m.seesound,
+        ] {
+            if s != "0" && !sfx_rows.iter().any(|r| &r.name == s) {
+                bail!("{}: sound sfx_{s} missing from S_sfx[]", m.id);
+            }
         }
-        set.into_iter().collect()
-    };
+    }
 
     // 4. Emit generated Rust.
     fs::create_dir_all(&opts.out_dir)?;
     fs::write(opts.out_dir.join("state.rs"), render_state(&state_rows))?;
     fs::write(opts.out_dir.join("action.rs"), render_action(&action_names))?;
-    fs::write(opts.out_dir.join("sound.rs"), render_sound(&sound_names))?;
+    fs::write(opts.out_dir.join("sound.rs"), render_sound(&sfx_rows))?;
     fs::write(opts.out_dir.join("states.rs"), render_states(&state_rows))?;
     fs::write(
         opts.out_dir.join("mobjinfo.rs"),
@@ -122,9 +156,10 @@ fn main() -> anyhow::Result<()> {
     )?;
 
     println!(
-        "Generated {} states and {} mobjs",
+        "Generated {} states, {} mobjs and {} sounds",
         state_rows.len(),
-        mobj_rows.len()
+        mobj_rows.len(),
+        sfx_rows.len()
     );
     Ok(())
 }
@@ -183,9 +218,12 @@ fn parse_state_line(line: &str) -> Option<StateRow> {
         .trim_start_matches("S_")
         .to_string();
 
+    // frame numbers carry `FF_FULLBRIGHT` (0x8000) above the frame
+    let frame: i32 = fields[1].parse().unwrap_or(0);
     Some(StateRow {
         sprite: fields[0].trim_start_matches("SPR_").to_string(),
-        frame: fields[1].parse().unwrap_or(0),
+        frame: (frame & 0x7fff) as u8,
+        bright: frame & 0x8000 != 0,
         tics: fields[2].parse().unwrap_or(0),
         action: action_raw.to_string(),
         next_state: fields[4].trim_start_matches("S_").to_string(),
@@ -195,6 +233,36 @@ fn parse_state_line(line: &str) -> Option<StateRow> {
     })
 }
 
+/// Every `{ "name", singular, priority, link, pitch, volume, data }` row
+/// of the `S_sfx[]` body, in `sfxenum_t` order.
+fn parse_sfx_rows(body: &str) -> Vec<SfxRow> {
+    let row_re = Regex::new(
+        r#"\{\s*"(\w+)"\s*,\s*(\w+)\s*,\s*(-?\d+)\s*,\s*([^,]+?)\s*,\s*(-?\d+)\s*,\s*(-?\d+)\s*,[^}]*\}"#,
+    )
+    .unwrap();
+    let cleaned = body
+        .lines()
+        .map(|l| l.split("//").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    row_re
+        .captures_iter(&cleaned)
+        .map(|c| SfxRow {
+            name: c[1].to_string(),
+            singularity: &c[2] == "true",
+            priority: c[3].parse().unwrap_or(0),
+            link: c[4]
+                .trim_start_matches("&S_sfx[")
+                .trim_end_matches(']')
+                .strip_prefix("sfx_")
+                .map(str::to_string),
+            pitch: c[5].parse().unwrap_or(-1),
+            volume: c[6].parse().unwrap_or(-1),
+        })
+        .collect()
+}
+
 /// Convert a single mobjinfo initializer (comments already inside) into a row.
 fn parse_mobj_chunk(text: &str) -> Option<MobjRow> {
     // 0) capture the leading “// MT_FOO” tag (if any)
@@ -240,7 +308,7 @@ fn parse_mobj_chunk(text: &str) -> Option<MobjRow> {
         deathstate: f[12].trim_start_matches("S_").to_string(),
         xdeathstate: f[13].trim_start_matches("S_").to_string(),
         deathsound: f[14].trim_start_matches("sfx_").to_string(),
-        speed: f[15].parse().unwrap_or(0),
+        speed: f[15].trim_end_matches("*FRACUNIT").parse().unwrap_or(0),
         radius: f[16].trim_end_matches("*FRACUNIT").parse().unwrap_or(0),
         height: f[17].trim_end_matches("*FRACUNIT").parse().unwrap_or(0),
         mass: f[18].parse().unwrap_or(0),
@@ -265,6 +333,8 @@ pub struct StateInfo {\n\
     pub state: State,\n\
     pub sprite: &'static str,\n\
     pub frame: u8,\n\
+    /// Drawn at full brightness (vanilla `FF_FULLBRIGHT`).\n\
+    pub bright: bool,\n\
     pub tics: i32,\n\
     pub action: Action,\n\
     pub next_state: State,\n\
@@ -282,9 +352,17 @@ pub const STATES: &[StateInfo] = &[\n",
         };
 
         out.push_str(&format!(
-            "   StateInfo {{ state: State::{}, sprite: \"{}\", frame: {}, tics: {}, \
+            "   StateInfo {{ state: State::{}, sprite: \"{}\", frame: {}, bright: {}, tics: {}, \
              action: {}, next_state: State::{}, misc1: {}, misc2: {} }},\n",
-            s.name, s.sprite, s.frame, s.tics, action_token, s.next_state, s.misc1, s.misc2,
+            s.name,
+            s.sprite,
+            s.frame,
+            s.bright,
+            s.tics,
+            action_token,
+            s.next_state,
+            s.misc1,
+            s.misc2,
         ));
     }
     out.push_str("];");
@@ -296,7 +374,7 @@ fn render_mobjinfo(rows: &[MobjRow]) -> String {
         "// AUTO-GENERATED - see tools/gen_mobjinfo\n\n\
 use crate::defs::flags::MobjFlags as MF;\n\
 use crate::defs::{state::State, sound::Sound};\n\n\
-#[derive(Debug, Clone)]\n\
+#[derive(Debug, Clone, Copy)]\n\
 pub struct MobjInfo {\n\
     pub id: &'static str,\n\
     pub doomednum:    i32,\n\
@@ -446,7 +524,10 @@ pub enum State {\n",
     out.push_str("}\n\n");
 
     out.push_str(
-        "impl State {\n\
+        "/// Lookups in the compiled-in `STATES`; the sim goes through its own\n\
+/// [`Definitions::state`](super::Definitions::state), which a DeHackEd\n\
+/// patch may have changed.\n\
+impl State {\n\
 #[inline(always)]\n\
 pub fn info(self) -> &'static super::states::StateInfo {\n\
     &super::states::STATES[self as usize]\n\
@@ -487,18 +568,128 @@ pub enum Action {\n    None,\n",
     out
 }
 
-fn render_sound(names: &[String]) -> String {
+/// `sfx_none` is `Sound::None`; the rest keep their sounds.c names.
+fn sound_variant(name: &str) -> String {
+    if name == "none" {
+        "None".into()
+    } else {
+        name.to_string()
+    }
+}
+
+fn render_sound(rows: &[SfxRow]) -> String {
     let mut out = String::from(
         "// AUTO-GENERATED - see tools/gen_mobjinfo\n\n\
-#[derive(Debug, Copy, Clone)]\n\
+#[repr(usize)]\n\
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]\n\
 #[allow(non_camel_case_types)]\n\
 pub enum Sound {\n",
     );
-    for n in names {
-        if n != "0" {
-            out.push_str(&format!("    {},\n", n));
-        }
+    for (i, r) in rows.iter().enumerate() {
+        out.push_str(&format!("    {} = {},\n", sound_variant(&r.name), i));
     }
-    out.push_str("}\n");
+    out.push_str("}\n\n");
+
+    out.push_str(
+        "/// One `sfxinfo_t`.\n\
+#[derive(Debug, Copy, Clone)]\n\
+pub struct SoundInfo {\n\
+    pub sound: Sound,\n\
+    /// `DS*` lump with the samples.\n\
+    pub lump: &'static str,\n\
+    /// Never plays twice at once.\n\
+    pub singularity: bool,\n\
+    /// Lower wins when channels run out.\n\
+    pub priority: i32,\n\
+    /// Plays this sound's samples instead (`chgun` uses `pistol`).\n\
+    pub link: Option<Sound>,\n\
+    pub pitch: i32,\n\
+    pub volume: i32,\n\
+}\n\n\
+pub const SOUNDS: &[SoundInfo] = &[\n",
+    );
+    for r in rows {
+        let link = match &r.link {
+            Some(l) => format!("Some(Sound::{})", sound_variant(l)),
+            None => "None".into(),
+        };
+        out.push_str(&format!(
+            "    SoundInfo {{ sound: Sound::{}, lump: \"DS{}\", singularity: {}, priority: {}, \
+             link: {}, pitch: {}, volume: {} }},\n",
+            sound_variant(&r.name),
+            r.name.to_ascii_uppercase(),
+            r.singularity,
+            r.priority,
+            link,
+            r.pitch,
+            r.volume,
+        ));
+    }
+    out.push_str("];\n\n");
+
+    out.push_str(
+        "impl Sound {\n\
+#[inline(always)]\n\
+pub fn info(self) -> &'static SoundInfo {\n\
+    &SOUNDS[self as usize]\n\
+}\n\
+/// The `DS*` lump to play, following `link`.\n\
+pub fn lump_name(self) -> &'static str {\n\
+    self.info().link.unwrap_or(self).info().lump\n\
+}\n\
+}\n",
+    );
     out
 }
+
+/*====================================================================*/
+/*                              Tests                                 */
+/*====================================================================*/
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOUNDS_C: &str = r#"
+sfxinfo_t S_sfx[] =
+{
+  // S_sfx[0] needs to be a dummy for odd reasons.
+  { "none", false,  0, 0, -1, -1, 0 },
+
+  { "pistol", false, 64, 0, -1, -1, 0 },
+  { "itemup", true, 78, 0, -1, -1, 0 },
+  { "chgun", false, 64, &S_sfx[sfx_pistol], 150, 0, 0 },
+  { "radio", false, 60, 0, -1, -1, 0 }
+};
+"#;
+
+    #[test]
+    fn parses_sfx_rows_in_order() {
+        let body = extract_array_body("sfxinfo_t", "S_sfx", SOUNDS_C).unwrap();
+        let rows = parse_sfx_rows(&body);
+        let names: Vec<_> = rows.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["none", "pistol", "itemup", "chgun", "radio"]);
+        assert_eq!(
+            rows[2],
+            SfxRow {
+                name: "itemup".into(),
+                singularity: true,
+                priority: 78,
+                link: None,
+                pitch: -1,
+                volume: -1,
+            }
+        );
+        assert_eq!(rows[3].link.as_deref(), Some("pistol"));
+        assert_eq!((rows[3].pitch, rows[3].volume), (150, 0));
+    }
+
+    #[test]
+    fn renders_enum_table_and_links() {
+        let body = extract_array_body("sfxinfo_t", "S_sfx", SOUNDS_C).unwrap();
+        let src = render_sound(&parse_sfx_rows(&body));
+        assert!(src.contains("    None = 0,\n    pistol = 1,"));
+        assert!(src.contains("lump: \"DSITEMUP\", singularity: true, priority: 78"));
+        assert!(src.contains("sound: Sound::chgun, lump: \"DSCHGUN\""));
+        assert!(src.contains("link: Some(Sound::pistol), pitch: 150"));
+    }
+}